@@ -192,6 +192,49 @@ class AbstractDomain {
   }
 };
 
+namespace ad_impl {
+
+template <typename Domain, typename = void>
+struct has_canonicalize : std::false_type {};
+
+template <typename Domain>
+struct has_canonicalize<
+    Domain,
+    std::void_t<decltype(std::declval<Domain&>().canonicalize())>>
+    : std::true_type {};
+
+} // namespace ad_impl
+
+/*
+ * Some abstract domains have several representations of the same element,
+ * e.g., a set domain over a finite universe in which the full set and Top
+ * denote the same element. Such representations are equal with respect to
+ * `equals()`, but they may defeat the optimizations that rely on the physical
+ * representation of the values, such as digests or the sharing of subtrees in
+ * Patricia trees. A domain can rewrite its values into a canonical form by
+ * defining a method with the following signature:
+ *
+ *   void canonicalize();
+ *
+ * The abstract environments and partitions canonicalize the values before
+ * storing them. Canonicalizing the value of any other domain is a no-op.
+ */
+template <typename Domain>
+typename std::enable_if<ad_impl::has_canonicalize<Domain>::value>::type
+canonicalize(Domain* x) {
+  x->canonicalize();
+}
+
+template <typename Domain>
+typename std::enable_if<!ad_impl::has_canonicalize<Domain>::value>::type
+canonicalize(Domain*) {}
+
+template <typename Domain>
+Domain canonical(Domain x) {
+  canonicalize(&x);
+  return x;
+}
+
 /*
  * When implementing an abstract domain, one often has to encode the Top and
  * Bottom values in a special way. This leads to a nontrivial case analysis when
@@ -0,0 +1,284 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstdint>
+#include <cstdio>
+#include <memory>
+#include <ostream>
+#include <sstream>
+#include <string>
+#include <type_traits>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * A machine-readable description of the configuration of an analysis, meant
+ * to be stored with its results, so that runs can be reproduced and their
+ * precision compared knowing exactly what produced each artifact. A manifest
+ * is a JSON object whose keys are kept in insertion order:
+ *
+ *   AnalysisManifest manifest = make_manifest("constant-propagation");
+ *   fixpoint.describe(&manifest.section("iterator"));
+ *   describe_domain<Environment>(&manifest.section("domain"));
+ *   manifest.section("parameters").set("max_field_depth", 3);
+ *   manifest.write_json(out);
+ *
+ * The fixpoint iterators describe their strategy and budgets (see
+ * MonotonicFixpointIteratorBase::describe). An abstract domain can describe
+ * its name and parameters by defining the static method
+ *
+ *   static void describe(AnalysisManifest* manifest);
+ *
+ * which is called by describe_domain().
+ */
+class AnalysisManifest final {
+ public:
+  // The version of the layout of the manifests produced by this library.
+  static constexpr uint32_t FORMAT_VERSION = 1;
+
+  AnalysisManifest() = default;
+
+  AnalysisManifest(const AnalysisManifest& other) { *this = other; }
+
+  AnalysisManifest& operator=(const AnalysisManifest& other) {
+    m_entries.clear();
+    for (const auto& entry : other.m_entries) {
+      m_entries.emplace_back(
+          entry.key,
+          entry.value,
+          entry.section ? std::make_unique<AnalysisManifest>(*entry.section)
+                        : nullptr);
+    }
+    return *this;
+  }
+
+  AnalysisManifest(AnalysisManifest&&) = default;
+
+  AnalysisManifest& operator=(AnalysisManifest&&) = default;
+
+  /*
+   * Sets a key to a scalar value. Setting a key again replaces its value,
+   * which keeps its position.
+   */
+  AnalysisManifest& set(const std::string& key, const std::string& value) {
+    return set_encoded(key, quote(value));
+  }
+
+  AnalysisManifest& set(const std::string& key, const char* value) {
+    return set(key, std::string(value));
+  }
+
+  AnalysisManifest& set(const std::string& key, bool value) {
+    return set_encoded(key, value ? "true" : "false");
+  }
+
+  template <typename Integer,
+            typename = std::enable_if_t<std::is_integral<Integer>::value &&
+                                        !std::is_same<Integer, bool>::value>>
+  AnalysisManifest& set(const std::string& key, Integer value) {
+    return set_encoded(key, std::to_string(value));
+  }
+
+  AnalysisManifest& set(const std::string& key, double value) {
+    std::ostringstream encoded;
+    encoded.precision(17);
+    encoded << value;
+    return set_encoded(key, encoded.str());
+  }
+
+  AnalysisManifest& set_null(const std::string& key) {
+    return set_encoded(key, "null");
+  }
+
+  /*
+   * Returns the nested object under a key, which is created if needed. A
+   * scalar value under the same key is replaced.
+   */
+  AnalysisManifest& section(const std::string& key) {
+    Entry* entry = find_entry(key);
+    if (entry == nullptr) {
+      m_entries.emplace_back(key, "", nullptr);
+      entry = &m_entries.back();
+    }
+    if (!entry->section) {
+      entry->value.clear();
+      entry->section = std::make_unique<AnalysisManifest>();
+    }
+    return *entry->section;
+  }
+
+  bool contains(const std::string& key) const {
+    return find_entry(key) != nullptr;
+  }
+
+  /*
+   * Returns the JSON encoding of the value under a key, or the empty string
+   * if there is none.
+   */
+  std::string get(const std::string& key) const {
+    const Entry* entry = find_entry(key);
+    if (entry == nullptr) {
+      return "";
+    }
+    return entry->section ? entry->section->to_json() : entry->value;
+  }
+
+  void write_json(std::ostream& o, size_t indent = 0) const {
+    if (m_entries.empty()) {
+      o << "{}";
+      return;
+    }
+    o << "{\n";
+    for (size_t i = 0; i < m_entries.size(); ++i) {
+      const auto& entry = m_entries[i];
+      o << std::string(indent + 2, ' ') << quote(entry.key) << ": ";
+      if (entry.section) {
+        entry.section->write_json(o, indent + 2);
+      } else {
+        o << entry.value;
+      }
+      o << (i + 1 < m_entries.size() ? ",\n" : "\n");
+    }
+    o << std::string(indent, ' ') << "}";
+  }
+
+  std::string to_json() const {
+    std::ostringstream o;
+    write_json(o);
+    return o.str();
+  }
+
+  friend bool operator==(const AnalysisManifest& x,
+                         const AnalysisManifest& y) {
+    return x.to_json() == y.to_json();
+  }
+
+  friend bool operator!=(const AnalysisManifest& x,
+                         const AnalysisManifest& y) {
+    return !(x == y);
+  }
+
+ private:
+  struct Entry {
+    Entry(std::string key,
+          std::string value,
+          std::unique_ptr<AnalysisManifest> section)
+        : key(std::move(key)),
+          value(std::move(value)),
+          section(std::move(section)) {}
+
+    std::string key;
+    // The JSON encoding of a scalar value.
+    std::string value;
+    std::unique_ptr<AnalysisManifest> section;
+  };
+
+  static std::string quote(const std::string& s) {
+    std::string result = "\"";
+    for (char c : s) {
+      switch (c) {
+      case '"':
+        result += "\\\"";
+        break;
+      case '\\':
+        result += "\\\\";
+        break;
+      case '\n':
+        result += "\\n";
+        break;
+      case '\t':
+        result += "\\t";
+        break;
+      default:
+        if (static_cast<unsigned char>(c) < 0x20) {
+          char escaped[8];
+          std::snprintf(escaped, sizeof(escaped), "\\u%04x", c);
+          result += escaped;
+        } else {
+          result += c;
+        }
+      }
+    }
+    return result + "\"";
+  }
+
+  AnalysisManifest& set_encoded(const std::string& key, std::string value) {
+    Entry* entry = find_entry(key);
+    if (entry == nullptr) {
+      m_entries.emplace_back(key, std::move(value), nullptr);
+    } else {
+      entry->value = std::move(value);
+      entry->section.reset();
+    }
+    return *this;
+  }
+
+  const Entry* find_entry(const std::string& key) const {
+    for (const auto& entry : m_entries) {
+      if (entry.key == key) {
+        return &entry;
+      }
+    }
+    return nullptr;
+  }
+
+  Entry* find_entry(const std::string& key) {
+    return const_cast<Entry*>(
+        static_cast<const AnalysisManifest*>(this)->find_entry(key));
+  }
+
+  std::vector<Entry> m_entries;
+};
+
+/*
+ * The manifest of an analysis, with the versions of the manifest format and
+ * of the toolchain that built it.
+ */
+inline AnalysisManifest make_manifest(const std::string& analysis) {
+  AnalysisManifest manifest;
+  manifest.set("format_version", AnalysisManifest::FORMAT_VERSION);
+  manifest.set("analysis", analysis);
+#ifdef __VERSION__
+  manifest.set("compiler", __VERSION__);
+#endif
+  manifest.set("cplusplus", static_cast<int64_t>(__cplusplus));
+  return manifest;
+}
+
+namespace am_impl {
+
+template <typename Domain, typename = void>
+struct has_describe : std::false_type {};
+
+template <typename Domain>
+struct has_describe<Domain,
+                    std::void_t<decltype(Domain::describe(
+                        std::declval<AnalysisManifest*>()))>>
+    : std::true_type {};
+
+} // namespace am_impl
+
+/*
+ * Lets the domain describe its name and parameters, if it knows how to.
+ */
+template <typename Domain>
+std::enable_if_t<am_impl::has_describe<Domain>::value> describe_domain(
+    AnalysisManifest* manifest) {
+  Domain::describe(manifest);
+}
+
+template <typename Domain>
+std::enable_if_t<!am_impl::has_describe<Domain>::value> describe_domain(
+    AnalysisManifest*) {}
+
+} // namespace sparta
@@ -0,0 +1,309 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <functional>
+#include <map>
+#include <memory>
+#include <mutex>
+#include <string>
+#include <typeinfo>
+#include <utility>
+#include <vector>
+
+#include "AnalysisManifest.h"
+#include "Exceptions.h"
+#include "FixpointIterator.h"
+
+namespace sparta {
+
+namespace ar_impl {
+
+// Retrieves the graph interface and the abstract domain of a fixpoint iterator
+// from its FixpointIterator base class.
+template <typename GraphInterface, typename Domain>
+std::pair<GraphInterface, Domain> iterator_parameters(
+    const FixpointIterator<GraphInterface, Domain>*);
+
+template <typename Iterator>
+using IteratorParameters = decltype(
+    iterator_parameters(std::declval<const Iterator*>()));
+
+template <typename Iterator>
+using GraphInterfaceOf = typename IteratorParameters<Iterator>::first_type;
+
+template <typename Iterator>
+using DomainOf = typename IteratorParameters<Iterator>::second_type;
+
+template <typename Iterator, typename = void>
+struct has_describe : std::false_type {};
+
+template <typename Iterator>
+struct has_describe<
+    Iterator,
+    std::void_t<decltype(std::declval<const Iterator&>().describe(
+        std::declval<AnalysisManifest*>()))>> : std::true_type {};
+
+template <typename Iterator>
+std::enable_if_t<has_describe<Iterator>::value> describe_iterator(
+    const Iterator& iterator, AnalysisManifest* manifest) {
+  iterator.describe(manifest);
+}
+
+template <typename Iterator>
+std::enable_if_t<!has_describe<Iterator>::value> describe_iterator(
+    const Iterator&, AnalysisManifest*) {}
+
+} // namespace ar_impl
+
+/*
+ * A type-erased interface to a fixpoint engine, i.e., a fixpoint iterator
+ * instantiated with the transformers of an analysis. This lets a host
+ * application run an analysis selected at runtime, without knowing the
+ * concrete type of its fixpoint iterator.
+ */
+template <typename GraphInterface, typename Domain>
+class AnalysisEngine {
+ public:
+  using Graph = typename GraphInterface::Graph;
+  using NodeId = typename GraphInterface::NodeId;
+
+  virtual ~AnalysisEngine() = default;
+
+  virtual void run(const Domain& init) = 0;
+
+  virtual Domain get_entry_state_at(const NodeId& node) const = 0;
+
+  virtual Domain get_exit_state_at(const NodeId& node) const = 0;
+
+  /*
+   * Describes the configuration of the engine, i.e., the strategy and budgets
+   * of its fixpoint iterator and the parameters of its abstract domain, in a
+   * manifest (see AnalysisManifest.h).
+   */
+  virtual void describe(AnalysisManifest* manifest) const = 0;
+
+  /*
+   * Returns the fixpoint iterator of the engine if it has the given type, or
+   * nullptr otherwise. Since the iterator implements the transformers of the
+   * analysis, this gives access to the state they accumulate during a run
+   * (e.g., caches or diagnostics).
+   */
+  template <typename Iterator>
+  Iterator* iterator_as() {
+    return static_cast<Iterator*>(get_iterator_of_type(typeid(Iterator)));
+  }
+
+  template <typename Iterator>
+  const Iterator* iterator_as() const {
+    auto* engine = const_cast<AnalysisEngine*>(this);
+    return static_cast<const Iterator*>(
+        engine->get_iterator_of_type(typeid(Iterator)));
+  }
+
+ private:
+  virtual void* get_iterator_of_type(const std::type_info& type) = 0;
+
+  // Transfers the ownership of the iterator to the caller. The engine must be
+  // destroyed afterwards.
+  virtual void* release_iterator_of_type(const std::type_info& type) = 0;
+
+  template <typename Iterator, typename GI, typename D>
+  friend std::unique_ptr<Iterator> release_iterator(
+      std::unique_ptr<AnalysisEngine<GI, D>> engine);
+};
+
+/*
+ * Destroys the engine and returns its fixpoint iterator, which must have the
+ * given type. Throws invalid_argument otherwise.
+ */
+template <typename Iterator, typename GraphInterface, typename Domain>
+std::unique_ptr<Iterator> release_iterator(
+    std::unique_ptr<AnalysisEngine<GraphInterface, Domain>> engine) {
+  RUNTIME_CHECK(engine->get_iterator_of_type(typeid(Iterator)) != nullptr,
+                invalid_argument()
+                    << argument_name("engine")
+                    << error_msg("The fixpoint iterator has a different type"));
+  void* iterator = engine->release_iterator_of_type(typeid(Iterator));
+  return std::unique_ptr<Iterator>(static_cast<Iterator*>(iterator));
+}
+
+/*
+ * Wraps a fixpoint iterator (e.g., a subclass of MonotonicFixpointIterator that
+ * implements the transformers of an analysis) into an AnalysisEngine. The
+ * arguments of the constructor are forwarded to the iterator.
+ */
+template <typename Iterator>
+class AnalysisEngineAdapter final
+    : public AnalysisEngine<ar_impl::GraphInterfaceOf<Iterator>,
+                            ar_impl::DomainOf<Iterator>> {
+ public:
+  using Domain = ar_impl::DomainOf<Iterator>;
+  using NodeId = typename ar_impl::GraphInterfaceOf<Iterator>::NodeId;
+
+  template <typename... Args>
+  explicit AnalysisEngineAdapter(Args&&... args)
+      : m_iterator(new Iterator(std::forward<Args>(args)...)) {}
+
+  void run(const Domain& init) override { m_iterator->run(init); }
+
+  Domain get_entry_state_at(const NodeId& node) const override {
+    return m_iterator->get_entry_state_at(node);
+  }
+
+  Domain get_exit_state_at(const NodeId& node) const override {
+    return m_iterator->get_exit_state_at(node);
+  }
+
+  void describe(AnalysisManifest* manifest) const override {
+    ar_impl::describe_iterator(*m_iterator, manifest);
+    if (am_impl::has_describe<Domain>::value) {
+      describe_domain<Domain>(&manifest->section("domain"));
+    }
+  }
+
+  Iterator& iterator() { return *m_iterator; }
+
+  const Iterator& iterator() const { return *m_iterator; }
+
+ private:
+  void* get_iterator_of_type(const std::type_info& type) override {
+    return type == typeid(Iterator) ? m_iterator.get() : nullptr;
+  }
+
+  void* release_iterator_of_type(const std::type_info& type) override {
+    return type == typeid(Iterator) ? m_iterator.release() : nullptr;
+  }
+
+  std::unique_ptr<Iterator> m_iterator;
+};
+
+/*
+ * A registry of the analyses that operate on a given type of graph and
+ * abstract domain. Each analysis is registered under a unique name with a
+ * factory that creates a fixpoint engine for a graph, so that a host
+ * application can select the analyses to run from its configuration:
+ *
+ *   using Registry = AnalysisRegistry<ControlFlowGraphInterface, Environment>;
+ *
+ *   // In the translation unit of the analysis.
+ *   static AnalysisRegistration<ConstantPropagation> s_registration(
+ *       "constant-propagation");
+ *
+ *   // In the host application.
+ *   for (const auto& name : config.analyses) {
+ *     auto engine = Registry::global().create(name, cfg);
+ *     engine->run(Environment::top());
+ *     ...
+ *   }
+ *
+ * The engine keeps a reference to the graph, which must outlive it. All the
+ * operations on a registry are thread-safe.
+ */
+template <typename GraphInterface, typename Domain>
+class AnalysisRegistry final {
+ public:
+  using Graph = typename GraphInterface::Graph;
+  using Engine = AnalysisEngine<GraphInterface, Domain>;
+  using Factory = std::function<std::unique_ptr<Engine>(const Graph&)>;
+
+  /*
+   * The registry shared by all the analyses of this type, which is populated
+   * by AnalysisRegistration.
+   */
+  static AnalysisRegistry& global() {
+    static AnalysisRegistry registry;
+    return registry;
+  }
+
+  /*
+   * Throws invalid_argument if an analysis has already been registered under
+   * the same name.
+   */
+  void register_analysis(const std::string& name, Factory factory) {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    bool inserted = m_factories.emplace(name, std::move(factory)).second;
+    RUNTIME_CHECK(inserted,
+                  invalid_argument()
+                      << argument_name("name")
+                      << error_msg("Analysis '" + name +
+                                   "' is already registered"));
+  }
+
+  /*
+   * Registers a fixpoint iterator, which is constructed from the graph
+   * followed by the given arguments, e.g., the number of threads of a
+   * ParallelMonotonicFixpointIterator.
+   */
+  template <typename Iterator, typename... Args>
+  void register_iterator(const std::string& name, Args... args) {
+    register_analysis(name, [args...](const Graph& graph) {
+      return std::unique_ptr<Engine>(
+          new AnalysisEngineAdapter<Iterator>(graph, args...));
+    });
+  }
+
+  bool contains(const std::string& name) const {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    return m_factories.count(name) > 0;
+  }
+
+  /*
+   * Throws invalid_argument if no analysis has been registered under that
+   * name.
+   */
+  std::unique_ptr<Engine> create(const std::string& name,
+                                 const Graph& graph) const {
+    Factory factory;
+    {
+      std::lock_guard<std::mutex> guard(m_mutex);
+      auto it = m_factories.find(name);
+      RUNTIME_CHECK(it != m_factories.end(),
+                    invalid_argument()
+                        << argument_name("name")
+                        << error_msg("Unknown analysis '" + name + "'"));
+      factory = it->second;
+    }
+    return factory(graph);
+  }
+
+  /*
+   * The names of the registered analyses, in lexicographic order.
+   */
+  std::vector<std::string> names() const {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    std::vector<std::string> result;
+    for (const auto& entry : m_factories) {
+      result.push_back(entry.first);
+    }
+    return result;
+  }
+
+ private:
+  std::map<std::string, Factory> m_factories;
+  mutable std::mutex m_mutex;
+};
+
+/*
+ * Registers a fixpoint iterator in the global registry upon construction.
+ * This is meant to be used as a static variable in the translation unit that
+ * defines the analysis.
+ */
+template <typename Iterator>
+class AnalysisRegistration final {
+ public:
+  using Registry = AnalysisRegistry<ar_impl::GraphInterfaceOf<Iterator>,
+                                    ar_impl::DomainOf<Iterator>>;
+
+  template <typename... Args>
+  explicit AnalysisRegistration(const std::string& name, Args... args) {
+    Registry::global().template register_iterator<Iterator>(name, args...);
+  }
+};
+
+} // namespace sparta
@@ -0,0 +1,340 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <array>
+#include <cstddef>
+#include <functional>
+#include <initializer_list>
+#include <memory>
+#include <ostream>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+#include "DefaultBinding.h"
+
+namespace sparta {
+
+/*
+ * An abstract environment whose variables are dense indices 0, 1, 2, ...,
+ * e.g., the registers of a register-based bytecode. This is an alternative to
+ * PatriciaTreeMapAbstractEnvironment that is faster and smaller when the
+ * indices are small and contiguous.
+ *
+ * The values are stored in an array of chunks of ChunkSize consecutive slots.
+ * The chunks are immutable and shared between the copies of an environment,
+ * hence copying an environment only copies the array of pointers, and a chunk
+ * is copied on the first write to one of its slots. The join, meet and order
+ * operations skip the chunks that are shared by both operands.
+ *
+ * As in the other abstract environments, the indices that are not explicitly
+ * bound are bound to Top, and an environment in which some index is bound to
+ * Bottom is Bottom.
+ */
+template <typename Domain, size_t ChunkSize = 16>
+class ArrayAbstractEnvironment final
+    : public AbstractDomain<ArrayAbstractEnvironment<Domain, ChunkSize>> {
+  static_assert(ChunkSize > 0, "the chunks must not be empty.");
+
+ public:
+  using DefaultBinding = DefaultIsTop;
+
+  /*
+   * The default constructor produces the Top value.
+   */
+  ArrayAbstractEnvironment() = default;
+
+  ArrayAbstractEnvironment(std::initializer_list<Domain> values) {
+    size_t index = 0;
+    for (const auto& value : values) {
+      set(index++, value);
+    }
+  }
+
+  static ArrayAbstractEnvironment bottom() {
+    ArrayAbstractEnvironment env;
+    env.set_to_bottom();
+    return env;
+  }
+
+  static ArrayAbstractEnvironment top() { return ArrayAbstractEnvironment(); }
+
+  /*
+   * The number of slots that are allocated, i.e., all the indices above are
+   * bound to Top.
+   */
+  size_t capacity() const { return m_chunks.size() * ChunkSize; }
+
+  const Domain& get(size_t index) const {
+    if (m_is_bottom) {
+      static const Domain bottom = Domain::bottom();
+      return bottom;
+    }
+    if (index >= capacity()) {
+      static const Domain top = Domain::top();
+      return top;
+    }
+    return (*m_chunks[index / ChunkSize])[index % ChunkSize];
+  }
+
+  ArrayAbstractEnvironment& set(size_t index, const Domain& value) {
+    return update(index, [&value](Domain* slot) { *slot = value; });
+  }
+
+  ArrayAbstractEnvironment& update(
+      size_t index, const std::function<void(Domain*)>& operation) {
+    if (m_is_bottom) {
+      return *this;
+    }
+    if (index >= capacity()) {
+      Domain value = Domain::top();
+      operation(&value);
+      if (value.is_top()) {
+        return *this;
+      }
+      if (value.is_bottom()) {
+        set_to_bottom();
+        return *this;
+      }
+      m_chunks.resize(index / ChunkSize + 1, top_chunk());
+      *mutable_slot(index) = std::move(value);
+      return *this;
+    }
+    Domain* slot = mutable_slot(index);
+    operation(slot);
+    if (slot->is_bottom()) {
+      set_to_bottom();
+    }
+    return *this;
+  }
+
+  bool is_bottom() const override { return m_is_bottom; }
+
+  bool is_top() const override {
+    if (m_is_bottom) {
+      return false;
+    }
+    for (const auto& chunk : m_chunks) {
+      if (chunk != top_chunk() && !is_top_chunk(*chunk)) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  void set_to_bottom() override {
+    m_is_bottom = true;
+    m_chunks.clear();
+  }
+
+  void set_to_top() override {
+    m_is_bottom = false;
+    m_chunks.clear();
+  }
+
+  bool leq(const ArrayAbstractEnvironment& other) const override {
+    if (m_is_bottom) {
+      return true;
+    }
+    if (other.m_is_bottom) {
+      return false;
+    }
+    for (size_t i = 0; i < other.m_chunks.size(); ++i) {
+      const auto& chunk = i < m_chunks.size() ? m_chunks[i] : top_chunk();
+      if (chunk == other.m_chunks[i]) {
+        continue;
+      }
+      for (size_t j = 0; j < ChunkSize; ++j) {
+        if (!(*chunk)[j].leq((*other.m_chunks[i])[j])) {
+          return false;
+        }
+      }
+    }
+    // The indices above are bound to Top in `other`.
+    return true;
+  }
+
+  bool equals(const ArrayAbstractEnvironment& other) const override {
+    if (m_is_bottom || other.m_is_bottom) {
+      return m_is_bottom == other.m_is_bottom;
+    }
+    size_t size = std::max(m_chunks.size(), other.m_chunks.size());
+    for (size_t i = 0; i < size; ++i) {
+      const auto& chunk = i < m_chunks.size() ? m_chunks[i] : top_chunk();
+      const auto& other_chunk =
+          i < other.m_chunks.size() ? other.m_chunks[i] : top_chunk();
+      if (chunk == other_chunk) {
+        continue;
+      }
+      for (size_t j = 0; j < ChunkSize; ++j) {
+        if (!(*chunk)[j].equals((*other_chunk)[j])) {
+          return false;
+        }
+      }
+    }
+    return true;
+  }
+
+  void join_with(const ArrayAbstractEnvironment& other) override {
+    join_like_operation(
+        other, [](Domain* x, const Domain& y) { x->join_with(y); });
+  }
+
+  void widen_with(const ArrayAbstractEnvironment& other) override {
+    join_like_operation(
+        other, [](Domain* x, const Domain& y) { x->widen_with(y); });
+  }
+
+  void meet_with(const ArrayAbstractEnvironment& other) override {
+    meet_like_operation(
+        other, [](Domain* x, const Domain& y) { x->meet_with(y); });
+  }
+
+  void narrow_with(const ArrayAbstractEnvironment& other) override {
+    meet_like_operation(
+        other, [](Domain* x, const Domain& y) { x->narrow_with(y); });
+  }
+
+  friend std::ostream& operator<<(std::ostream& o,
+                                  const ArrayAbstractEnvironment& env) {
+    if (env.is_bottom()) {
+      return o << "_|_";
+    }
+    if (env.is_top()) {
+      return o << "T";
+    }
+    o << "{";
+    bool first = true;
+    for (size_t index = 0; index < env.capacity(); ++index) {
+      const Domain& value = env.get(index);
+      if (value.is_top()) {
+        continue;
+      }
+      o << (first ? "" : ", ") << index << " -> " << value;
+      first = false;
+    }
+    return o << "}";
+  }
+
+ private:
+  using Chunk = std::array<Domain, ChunkSize>;
+
+  /*
+   * The chunk in which all the slots are Top, which is shared by all the
+   * environments.
+   */
+  static const std::shared_ptr<const Chunk>& top_chunk() {
+    static const std::shared_ptr<const Chunk> chunk = [] {
+      auto chunk = std::make_shared<Chunk>();
+      chunk->fill(Domain::top());
+      return chunk;
+    }();
+    return chunk;
+  }
+
+  static bool is_top_chunk(const Chunk& chunk) {
+    for (const auto& value : chunk) {
+      if (!value.is_top()) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  /*
+   * Copies the chunk of the index if it is shared with another environment.
+   */
+  Domain* mutable_slot(size_t index) {
+    auto& chunk = m_chunks[index / ChunkSize];
+    if (chunk.use_count() > 1) {
+      chunk = std::make_shared<Chunk>(*chunk);
+    }
+    // The chunk is only referenced by this environment.
+    return &const_cast<Chunk&>(*chunk)[index % ChunkSize];
+  }
+
+  /*
+   * Combines the chunks of both environments slot by slot. The chunks of the
+   * first operand are only copied if they change.
+   */
+  template <typename Operation>
+  void combine_chunks(const ArrayAbstractEnvironment& other,
+                      size_t size,
+                      Operation operation) {
+    for (size_t i = 0; i < size; ++i) {
+      const auto& other_chunk =
+          i < other.m_chunks.size() ? other.m_chunks[i] : top_chunk();
+      if (m_chunks[i] == other_chunk) {
+        continue;
+      }
+      std::shared_ptr<Chunk> result;
+      for (size_t j = 0; j < ChunkSize; ++j) {
+        Domain value = (*m_chunks[i])[j];
+        operation(&value, (*other_chunk)[j]);
+        if (value.equals((*m_chunks[i])[j])) {
+          continue;
+        }
+        if (value.is_bottom()) {
+          set_to_bottom();
+          return;
+        }
+        if (result == nullptr) {
+          result = std::make_shared<Chunk>(*m_chunks[i]);
+        }
+        (*result)[j] = std::move(value);
+      }
+      if (result != nullptr) {
+        m_chunks[i] = std::move(result);
+      }
+    }
+  }
+
+  template <typename Operation>
+  void join_like_operation(const ArrayAbstractEnvironment& other,
+                           Operation operation) {
+    if (other.m_is_bottom) {
+      return;
+    }
+    if (m_is_bottom) {
+      *this = other;
+      return;
+    }
+    // The indices that are not allocated in `other` are bound to Top.
+    if (m_chunks.size() > other.m_chunks.size()) {
+      m_chunks.resize(other.m_chunks.size());
+    }
+    combine_chunks(other, m_chunks.size(), operation);
+  }
+
+  template <typename Operation>
+  void meet_like_operation(const ArrayAbstractEnvironment& other,
+                           Operation operation) {
+    if (m_is_bottom) {
+      return;
+    }
+    if (other.m_is_bottom) {
+      set_to_bottom();
+      return;
+    }
+    size_t size = m_chunks.size();
+    if (size < other.m_chunks.size()) {
+      // The chunks of `other` above are shared, since this environment binds
+      // the corresponding indices to Top.
+      m_chunks.insert(
+          m_chunks.end(), other.m_chunks.begin() + size, other.m_chunks.end());
+    }
+    combine_chunks(other, size, operation);
+  }
+
+  bool m_is_bottom{false};
+  std::vector<std::shared_ptr<const Chunk>> m_chunks;
+};
+
+} // namespace sparta
@@ -0,0 +1,233 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <cstddef>
+#include <functional>
+#include <memory>
+#include <utility>
+#include <vector>
+
+#include <boost/functional/hash.hpp>
+
+#include "MonotonicFixpointIterator.h"
+#include "ReachabilityAnalysis.h"
+
+namespace sparta {
+
+/*
+ * The product of a control-flow graph with a deterministic finite automaton
+ * that monitors a property of the executions, e.g., a typestate property such
+ * as "a file is never read after it has been closed". The automaton should
+ * have the following layout:
+ *
+ * class Automaton {
+ *   // State must be copyable, equality comparable and hashable.
+ *   using State = ...;
+ *
+ *   State initial() const { ... }
+ *
+ *   // All the states of the automaton.
+ *   std::vector<State> states() const { ... }
+ *
+ *   // The state of the automaton after the execution of the node.
+ *   State transition(const State& state, const NodeId& node) const { ... }
+ *
+ *   // Accepting states typically denote a violation of the property.
+ *   bool is_accepting(const State& state) const { ... }
+ * };
+ *
+ * The nodes of the product are the pairs (node, state), where state is the
+ * state of the automaton upon entering the node. The product is never
+ * materialized: its nodes and edges are computed on demand by
+ * AutomatonProductInterface, and a fixpoint iterator only visits the pairs
+ * that are reachable from the entry.
+ *
+ * Both the graph and the automaton must outlive the product.
+ */
+template <typename GraphInterface, typename Automaton>
+class AutomatonProduct final {
+ public:
+  using State = typename Automaton::State;
+
+  AutomatonProduct(const typename GraphInterface::Graph& graph,
+                   const Automaton& automaton)
+      : m_graph(graph), m_automaton(automaton), m_states(automaton.states()) {}
+
+  const typename GraphInterface::Graph& graph() const { return m_graph; }
+
+  const Automaton& automaton() const { return m_automaton; }
+
+  const std::vector<State>& states() const { return m_states; }
+
+ private:
+  const typename GraphInterface::Graph& m_graph;
+  const Automaton& m_automaton;
+  std::vector<State> m_states;
+};
+
+/*
+ * The graph interface of an AutomatonProduct. An edge of the product is an
+ * edge of the graph together with the state of the automaton at its source.
+ */
+template <typename GraphInterface,
+          typename Automaton,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class AutomatonProductInterface {
+ public:
+  using Graph = AutomatonProduct<GraphInterface, Automaton>;
+  using State = typename Automaton::State;
+  using NodeId = std::pair<typename GraphInterface::NodeId, State>;
+  using EdgeId = std::pair<typename GraphInterface::EdgeId, State>;
+
+  struct NodeIdHash {
+    size_t operator()(const NodeId& node) const {
+      size_t seed = NodeHash()(node.first);
+      boost::hash_combine(seed, std::hash<State>()(node.second));
+      return seed;
+    }
+  };
+
+  static NodeId entry(const Graph& product) {
+    return NodeId(GraphInterface::entry(product.graph()),
+                  product.automaton().initial());
+  }
+
+  static std::vector<EdgeId> predecessors(const Graph& product,
+                                          const NodeId& node) {
+    std::vector<EdgeId> edges;
+    for (const auto& edge :
+         GraphInterface::predecessors(product.graph(), node.first)) {
+      auto source = GraphInterface::source(product.graph(), edge);
+      for (const auto& state : product.states()) {
+        if (product.automaton().transition(state, source) == node.second) {
+          edges.emplace_back(edge, state);
+        }
+      }
+    }
+    return edges;
+  }
+
+  static std::vector<EdgeId> successors(const Graph& product,
+                                        const NodeId& node) {
+    std::vector<EdgeId> edges;
+    for (const auto& edge :
+         GraphInterface::successors(product.graph(), node.first)) {
+      edges.emplace_back(edge, node.second);
+    }
+    return edges;
+  }
+
+  static NodeId source(const Graph& product, const EdgeId& edge) {
+    return NodeId(GraphInterface::source(product.graph(), edge.first),
+                  edge.second);
+  }
+
+  static NodeId target(const Graph& product, const EdgeId& edge) {
+    auto source = GraphInterface::source(product.graph(), edge.first);
+    return NodeId(GraphInterface::target(product.graph(), edge.first),
+                  product.automaton().transition(edge.second, source));
+  }
+
+  static size_t size_hint(const Graph& product) {
+    return graph_size_hint<GraphInterface>(product.graph()) *
+           product.states().size();
+  }
+};
+
+namespace ap_impl {
+
+template <typename GraphInterface, typename Automaton, typename NodeHash>
+using ProductIterator = MonotonicFixpointIterator<
+    AutomatonProductInterface<GraphInterface, Automaton, NodeHash>,
+    ReachabilityDomain,
+    typename AutomatonProductInterface<GraphInterface, Automaton, NodeHash>::
+        NodeIdHash>;
+
+} // namespace ap_impl
+
+/*
+ * Computes the states of a property automaton that may be reached at each
+ * node of a control-flow graph, by running a fixpoint iteration over the
+ * product of the graph and the automaton:
+ *
+ *   AutomatonProductAnalysis<CFGInterface, FileAutomaton> analysis(
+ *       cfg, automaton);
+ *   analysis.run();
+ *   for (auto node : nodes) {
+ *     if (analysis.may_reach_accepting_state(node)) {
+ *       report(node);
+ *     }
+ *   }
+ *
+ * The states are the ones upon entering the node. Only the pairs (node, state)
+ * that are reachable from the entry of the product are ever visited.
+ */
+template <typename GraphInterface,
+          typename Automaton,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class AutomatonProductAnalysis final
+    : public ap_impl::ProductIterator<GraphInterface, Automaton, NodeHash> {
+ public:
+  using Product = AutomatonProduct<GraphInterface, Automaton>;
+  using ProductInterface =
+      AutomatonProductInterface<GraphInterface, Automaton, NodeHash>;
+  using State = typename Automaton::State;
+  using Base = ap_impl::ProductIterator<GraphInterface, Automaton, NodeHash>;
+
+  AutomatonProductAnalysis(const typename GraphInterface::Graph& graph,
+                           const Automaton& automaton)
+      : AutomatonProductAnalysis(
+            std::unique_ptr<Product>(new Product(graph, automaton))) {}
+
+  void run() { Base::run(ReachabilityDomain::top()); }
+
+  void analyze_node(const typename ProductInterface::NodeId&,
+                    ReachabilityDomain*) const override {}
+
+  ReachabilityDomain analyze_edge(
+      const typename ProductInterface::EdgeId&,
+      const ReachabilityDomain& state) const override {
+    return state;
+  }
+
+  /*
+   * The states of the automaton that may hold upon entering the node, in the
+   * order of Automaton::states().
+   */
+  std::vector<State> reachable_states(
+      const typename GraphInterface::NodeId& node) const {
+    std::vector<State> states;
+    for (const auto& state : m_product->states()) {
+      if (!this->get_entry_state_at({node, state}).is_bottom()) {
+        states.push_back(state);
+      }
+    }
+    return states;
+  }
+
+  bool may_reach_accepting_state(
+      const typename GraphInterface::NodeId& node) const {
+    auto states = reachable_states(node);
+    return std::any_of(
+        states.begin(), states.end(), [this](const State& state) {
+          return m_product->automaton().is_accepting(state);
+        });
+  }
+
+ private:
+  // The product must be allocated before the base class is constructed, since
+  // the latter computes the weak partial ordering of the product.
+  explicit AutomatonProductAnalysis(std::unique_ptr<Product> product)
+      : Base(*product), m_product(std::move(product)) {}
+
+  std::unique_ptr<Product> m_product;
+};
+
+} // namespace sparta
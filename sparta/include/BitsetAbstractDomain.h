@@ -0,0 +1,223 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <bitset>
+#include <cstddef>
+#include <initializer_list>
+#include <ostream>
+#include <vector>
+
+#include "AbstractDomain.h"
+#include "Exceptions.h"
+
+namespace sparta {
+
+/*
+ * A powerset abstract domain over the dense universe {0, ..., N - 1}, where a
+ * set is represented by a bitset of N bits. Unions, intersections and subset
+ * tests are performed on whole machine words, which makes this domain much
+ * faster than the general powerset domains for analyses like liveness or
+ * reaching definitions, whose facts (variables, definitions) can be numbered
+ * beforehand.
+ *
+ * Since the universe is finite, Top is the set of all the elements. As in the
+ * other powerset domains, Bottom is distinct from the empty set.
+ */
+template <size_t N>
+class BitsetAbstractDomain final
+    : public AbstractDomain<BitsetAbstractDomain<N>> {
+ public:
+  using Bits = std::bitset<N>;
+
+  /*
+   * The default constructor produces the empty set.
+   */
+  BitsetAbstractDomain() = default;
+
+  explicit BitsetAbstractDomain(size_t element) { add(element); }
+
+  explicit BitsetAbstractDomain(std::initializer_list<size_t> elements) {
+    for (size_t element : elements) {
+      add(element);
+    }
+  }
+
+  explicit BitsetAbstractDomain(const Bits& bits) : m_bits(bits) {}
+
+  static BitsetAbstractDomain bottom() {
+    BitsetAbstractDomain result;
+    result.m_is_bottom = true;
+    return result;
+  }
+
+  static BitsetAbstractDomain top() {
+    BitsetAbstractDomain result;
+    result.m_bits.set();
+    return result;
+  }
+
+  static constexpr size_t universe_size() { return N; }
+
+  bool is_bottom() const override { return m_is_bottom; }
+
+  bool is_top() const override { return !m_is_bottom && m_bits.all(); }
+
+  void set_to_bottom() override {
+    m_is_bottom = true;
+    m_bits.reset();
+  }
+
+  void set_to_top() override {
+    m_is_bottom = false;
+    m_bits.set();
+  }
+
+  /*
+   * The underlying bitset. This operation is not defined on Bottom.
+   */
+  const Bits& bits() const {
+    RUNTIME_CHECK(!m_is_bottom, undefined_operation());
+    return m_bits;
+  }
+
+  /*
+   * The elements of the set in increasing order. This operation is not
+   * defined on Bottom.
+   */
+  std::vector<size_t> elements() const {
+    RUNTIME_CHECK(!m_is_bottom, undefined_operation());
+    std::vector<size_t> result;
+    result.reserve(m_bits.count());
+    for (size_t i = 0; i < N; ++i) {
+      if (m_bits.test(i)) {
+        result.push_back(i);
+      }
+    }
+    return result;
+  }
+
+  size_t size() const {
+    RUNTIME_CHECK(!m_is_bottom, undefined_operation());
+    return m_bits.count();
+  }
+
+  bool empty() const { return !m_is_bottom && m_bits.none(); }
+
+  bool contains(size_t element) const {
+    check_element(element);
+    return m_bits.test(element);
+  }
+
+  /*
+   * Adding or removing an element is a no-op on Bottom.
+   */
+  BitsetAbstractDomain& add(size_t element) {
+    check_element(element);
+    if (!m_is_bottom) {
+      m_bits.set(element);
+    }
+    return *this;
+  }
+
+  BitsetAbstractDomain& remove(size_t element) {
+    check_element(element);
+    m_bits.reset(element);
+    return *this;
+  }
+
+  /*
+   * Applies the transformer of a gen/kill analysis, i.e., (x \ kill) U gen,
+   * with two word-parallel operations. This is a no-op on Bottom.
+   */
+  BitsetAbstractDomain& apply_gen_kill(const Bits& gen, const Bits& kill) {
+    if (!m_is_bottom) {
+      m_bits &= ~kill;
+      m_bits |= gen;
+    }
+    return *this;
+  }
+
+  void difference_with(const BitsetAbstractDomain& other) {
+    if (other.m_is_bottom) {
+      return;
+    }
+    m_bits &= ~other.m_bits;
+  }
+
+  bool leq(const BitsetAbstractDomain& other) const override {
+    if (m_is_bottom) {
+      return true;
+    }
+    if (other.m_is_bottom) {
+      return false;
+    }
+    return (m_bits & ~other.m_bits).none();
+  }
+
+  bool equals(const BitsetAbstractDomain& other) const override {
+    return m_is_bottom == other.m_is_bottom && m_bits == other.m_bits;
+  }
+
+  void join_with(const BitsetAbstractDomain& other) override {
+    if (other.m_is_bottom) {
+      return;
+    }
+    m_is_bottom = false;
+    m_bits |= other.m_bits;
+  }
+
+  // The lattice has finite height, hence the widening is the join.
+  void widen_with(const BitsetAbstractDomain& other) override {
+    join_with(other);
+  }
+
+  void meet_with(const BitsetAbstractDomain& other) override {
+    if (other.m_is_bottom) {
+      set_to_bottom();
+      return;
+    }
+    m_bits &= other.m_bits;
+  }
+
+  void narrow_with(const BitsetAbstractDomain& other) override {
+    meet_with(other);
+  }
+
+  friend std::ostream& operator<<(std::ostream& o,
+                                  const BitsetAbstractDomain& s) {
+    if (s.is_bottom()) {
+      return o << "_|_";
+    }
+    if (s.is_top()) {
+      return o << "T";
+    }
+    o << "{";
+    bool first = true;
+    for (size_t element : s.elements()) {
+      o << (first ? "" : ", ") << element;
+      first = false;
+    }
+    return o << "}";
+  }
+
+ private:
+  static void check_element(size_t element) {
+    RUNTIME_CHECK(element < N,
+                  invalid_argument()
+                      << argument_name("element")
+                      << operation_name("BitsetAbstractDomain"));
+  }
+
+  // Bottom is represented with an empty bitset, so that the bitwise
+  // operations above don't need to special-case it.
+  bool m_is_bottom{false};
+  Bits m_bits;
+};
+
+} // namespace sparta
@@ -0,0 +1,324 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <bitset>
+#include <boost/optional.hpp>
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <initializer_list>
+#include <ostream>
+#include <unordered_map>
+#include <utility>
+#include <vector>
+
+#include "Exceptions.h"
+#include "PowersetAbstractDomain.h"
+
+namespace sparta {
+
+// Forward declaration.
+class BitsetDomain;
+
+namespace bd_impl {
+
+/*
+ * An abstract value from a powerset is implemented as a bitset that grows
+ * with the largest element of the set. The trailing words of the bitset are
+ * never zero, so that two equal sets have the same representation.
+ */
+class SetValue final : public PowersetImplementation<size_t,
+                                                     std::vector<size_t>,
+                                                     SetValue> {
+ public:
+  using Word = uint64_t;
+
+  static constexpr size_t kWordBits = 64;
+
+  SetValue() = default;
+
+  SetValue(size_t e) { add(e); }
+
+  SetValue(std::initializer_list<size_t> l) {
+    for (size_t e : l) {
+      add(e);
+    }
+  }
+
+  std::vector<size_t> elements() const override {
+    std::vector<size_t> result;
+    for (size_t i = 0; i < m_words.size(); ++i) {
+      size_t e = i * kWordBits;
+      for (Word word = m_words[i]; word != 0; word >>= 1, ++e) {
+        if ((word & 1) != 0) {
+          result.push_back(e);
+        }
+      }
+    }
+    return result;
+  }
+
+  size_t size() const override {
+    size_t result = 0;
+    for (Word word : m_words) {
+      result += std::bitset<kWordBits>(word).count();
+    }
+    return result;
+  }
+
+  bool contains(const size_t& e) const override {
+    size_t i = e / kWordBits;
+    return i < m_words.size() && (m_words[i] & bit(e)) != 0;
+  }
+
+  void add(const size_t& e) override {
+    size_t i = e / kWordBits;
+    if (i >= m_words.size()) {
+      m_words.resize(i + 1, 0);
+    }
+    m_words[i] |= bit(e);
+  }
+
+  void remove(const size_t& e) override {
+    size_t i = e / kWordBits;
+    if (i < m_words.size()) {
+      m_words[i] &= ~bit(e);
+      trim();
+    }
+  }
+
+  void clear() override { m_words.clear(); }
+
+  AbstractValueKind kind() const override { return AbstractValueKind::Value; }
+
+  bool leq(const SetValue& other) const override {
+    if (m_words.size() > other.m_words.size()) {
+      return false;
+    }
+    for (size_t i = 0; i < m_words.size(); ++i) {
+      if ((m_words[i] & ~other.m_words[i]) != 0) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  bool equals(const SetValue& other) const override {
+    return m_words == other.m_words;
+  }
+
+  AbstractValueKind join_with(const SetValue& other) override {
+    if (other.m_words.size() > m_words.size()) {
+      m_words.resize(other.m_words.size(), 0);
+    }
+    for (size_t i = 0; i < other.m_words.size(); ++i) {
+      m_words[i] |= other.m_words[i];
+    }
+    return AbstractValueKind::Value;
+  }
+
+  AbstractValueKind meet_with(const SetValue& other) override {
+    m_words.resize(std::min(m_words.size(), other.m_words.size()));
+    for (size_t i = 0; i < m_words.size(); ++i) {
+      m_words[i] &= other.m_words[i];
+    }
+    trim();
+    return AbstractValueKind::Value;
+  }
+
+  AbstractValueKind difference_with(const SetValue& other) override {
+    size_t n = std::min(m_words.size(), other.m_words.size());
+    for (size_t i = 0; i < n; ++i) {
+      m_words[i] &= ~other.m_words[i];
+    }
+    trim();
+    return AbstractValueKind::Value;
+  }
+
+  friend std::ostream& operator<<(std::ostream& o, const SetValue& value) {
+    o << "[#" << value.size() << "]{";
+    bool first = true;
+    for (size_t e : value.elements()) {
+      o << (first ? "" : ", ") << e;
+      first = false;
+    }
+    return o << "}";
+  }
+
+ private:
+  static Word bit(size_t e) { return Word(1) << (e % kWordBits); }
+
+  void trim() {
+    while (!m_words.empty() && m_words.back() == 0) {
+      m_words.pop_back();
+    }
+  }
+
+  std::vector<Word> m_words;
+
+  friend class sparta::BitsetDomain;
+};
+
+} // namespace bd_impl
+
+/*
+ * A powerset abstract domain over facts that have been numbered beforehand,
+ * e.g., the definitions of a method for a reaching-definitions analysis. A set
+ * of facts is represented as a bitset indexed by the numbers of the facts,
+ * hence unions, intersections and inclusion tests operate on whole machine
+ * words. Unlike BitsetAbstractDomain, the size of the universe doesn't need to
+ * be known at compile time: the bitsets grow with the largest number in the
+ * set. As in the other powerset domains, Top is the set of all facts and
+ * cannot be enumerated.
+ *
+ * The numbering of the facts is best built with a FactNumberer (see below).
+ */
+class BitsetDomain final
+    : public PowersetAbstractDomain<size_t,
+                                    bd_impl::SetValue,
+                                    std::vector<size_t>,
+                                    BitsetDomain> {
+ public:
+  using Value = bd_impl::SetValue;
+
+  BitsetDomain()
+      : PowersetAbstractDomain<size_t,
+                               Value,
+                               std::vector<size_t>,
+                               BitsetDomain>() {}
+
+  BitsetDomain(AbstractValueKind kind)
+      : PowersetAbstractDomain<size_t,
+                               Value,
+                               std::vector<size_t>,
+                               BitsetDomain>(kind) {}
+
+  explicit BitsetDomain(size_t e) { this->set_to_value(Value(e)); }
+
+  explicit BitsetDomain(std::initializer_list<size_t> l) {
+    this->set_to_value(Value(l));
+  }
+
+  static BitsetDomain bottom() {
+    return BitsetDomain(AbstractValueKind::Bottom);
+  }
+
+  static BitsetDomain top() { return BitsetDomain(AbstractValueKind::Top); }
+
+  /*
+   * Applies the transformer of a gen/kill analysis, i.e., (x \ kill) U gen,
+   * with word-parallel operations. As for difference_with(), removing facts
+   * from Top leaves Top unchanged. This is a no-op on Bottom.
+   */
+  BitsetDomain& apply_gen_kill(const BitsetDomain& gen,
+                               const BitsetDomain& kill) {
+    RUNTIME_CHECK(gen.kind() == AbstractValueKind::Value,
+                  invalid_abstract_value()
+                      << expected_kind(AbstractValueKind::Value)
+                      << actual_kind(gen.kind()));
+    RUNTIME_CHECK(kill.kind() == AbstractValueKind::Value,
+                  invalid_abstract_value()
+                      << expected_kind(AbstractValueKind::Value)
+                      << actual_kind(kill.kind()));
+    if (this->kind() == AbstractValueKind::Value) {
+      Value* value = this->get_value();
+      value->difference_with(*kill.get_value());
+      value->join_with(*gen.get_value());
+    }
+    return *this;
+  }
+};
+
+/*
+ * Assigns consecutive numbers to facts in the order in which they are first
+ * encountered, so that sets of facts can be represented by a BitsetDomain:
+ *
+ *   FactNumberer<const IRInstruction*> definitions;
+ *   for (auto* insn : ...) {
+ *     if (insn->has_dest()) {
+ *       definitions.number(insn);
+ *     }
+ *   }
+ *   BitsetDomain reaching = definitions.encode({insn1, insn2});
+ *   for (auto* insn : definitions.decode(reaching)) { ... }
+ */
+template <typename Fact, typename FactHash = std::hash<Fact>>
+class FactNumberer final {
+ public:
+  /*
+   * Returns the number of a fact, which is assigned if needed.
+   */
+  size_t number(const Fact& fact) {
+    auto result = m_numbers.emplace(fact, m_facts.size());
+    if (result.second) {
+      m_facts.push_back(fact);
+    }
+    return result.first->second;
+  }
+
+  boost::optional<size_t> find(const Fact& fact) const {
+    auto it = m_numbers.find(fact);
+    if (it == m_numbers.end()) {
+      return boost::none;
+    }
+    return it->second;
+  }
+
+  const Fact& fact(size_t number) const {
+    RUNTIME_CHECK(number < m_facts.size(),
+                  invalid_argument() << argument_name("number")
+                                     << operation_name("FactNumberer::fact"));
+    return m_facts[number];
+  }
+
+  /*
+   * The size of the universe, i.e., the number of facts numbered so far.
+   */
+  size_t size() const { return m_facts.size(); }
+
+  /*
+   * The facts in the order of their numbers.
+   */
+  const std::vector<Fact>& facts() const { return m_facts; }
+
+  /*
+   * Numbers the facts if needed, and returns the set of their numbers.
+   */
+  template <typename InputIterator>
+  BitsetDomain encode(InputIterator first, InputIterator last) {
+    BitsetDomain result;
+    for (auto it = first; it != last; ++it) {
+      result.add(number(*it));
+    }
+    return result;
+  }
+
+  BitsetDomain encode(std::initializer_list<Fact> l) {
+    return encode(l.begin(), l.end());
+  }
+
+  /*
+   * The facts of a set, in the order of their numbers. This operation is only
+   * defined on a set of numbered facts.
+   */
+  std::vector<Fact> decode(const BitsetDomain& set) const {
+    std::vector<Fact> result;
+    for (size_t number : set.elements()) {
+      result.push_back(fact(number));
+    }
+    return result;
+  }
+
+ private:
+  std::unordered_map<Fact, size_t, FactHash> m_numbers;
+  std::vector<Fact> m_facts;
+};
+
+} // namespace sparta
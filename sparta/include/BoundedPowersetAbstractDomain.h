@@ -0,0 +1,168 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <initializer_list>
+#include <ostream>
+#include <type_traits>
+#include <utility>
+
+#include "AbstractDomain.h"
+#include "PowersetAbstractDomain.h"
+
+namespace sparta {
+
+namespace bpad_impl {
+
+// Retrieves the type of the elements of a powerset abstract domain.
+template <typename Element,
+          typename Powerset,
+          typename Snapshot,
+          typename Derived>
+Element element_of(
+    const PowersetAbstractDomain<Element, Powerset, Snapshot, Derived>*);
+
+} // namespace bpad_impl
+
+/*
+ * A powerset abstract domain whose sets have at most MaxSize elements. A set
+ * that grows beyond MaxSize, e.g., as the result of a join or an insertion, is
+ * collapsed to Top. This prevents the sets of points-to or type-set analyses
+ * from exploding, at the cost of losing all the information about the value.
+ *
+ * The underlying set can be any powerset abstract domain, e.g.:
+ *
+ *   using Types = BoundedPowersetAbstractDomain<
+ *       PatriciaTreeSetAbstractDomain<const DexType*>, 16>;
+ *
+ * Since the sets are bounded, the lattice has finite height for any base set
+ * of elements, and the widening is simply the join.
+ */
+template <typename Set, size_t MaxSize>
+class BoundedPowersetAbstractDomain final
+    : public AbstractDomain<BoundedPowersetAbstractDomain<Set, MaxSize>> {
+ public:
+  using Element =
+      decltype(bpad_impl::element_of(std::declval<const Set*>()));
+
+  static_assert(MaxSize > 0, "MaxSize must be positive");
+
+  /*
+   * The default constructor produces the empty set, which is distinct from
+   * Bottom.
+   */
+  BoundedPowersetAbstractDomain() = default;
+
+  explicit BoundedPowersetAbstractDomain(const Element& e) : m_set(e) {}
+
+  explicit BoundedPowersetAbstractDomain(std::initializer_list<Element> l)
+      : m_set(l) {
+    normalize();
+  }
+
+  explicit BoundedPowersetAbstractDomain(Set set) : m_set(std::move(set)) {
+    normalize();
+  }
+
+  static BoundedPowersetAbstractDomain bottom() {
+    return BoundedPowersetAbstractDomain(Set::bottom());
+  }
+
+  static BoundedPowersetAbstractDomain top() {
+    return BoundedPowersetAbstractDomain(Set::top());
+  }
+
+  static constexpr size_t max_size() { return MaxSize; }
+
+  /*
+   * The underlying set, which never has more than MaxSize elements.
+   */
+  const Set& set() const { return m_set; }
+
+  decltype(auto) elements() const { return m_set.elements(); }
+
+  size_t size() const { return m_set.size(); }
+
+  bool empty() const { return m_set.is_value() && m_set.size() == 0; }
+
+  bool contains(const Element& e) const { return m_set.contains(e); }
+
+  BoundedPowersetAbstractDomain& add(const Element& e) {
+    m_set.add(e);
+    normalize();
+    return *this;
+  }
+
+  template <typename InputIterator>
+  BoundedPowersetAbstractDomain& add(InputIterator first,
+                                     InputIterator last) {
+    for (auto it = first; it != last && m_set.is_value(); ++it) {
+      add(*it);
+    }
+    return *this;
+  }
+
+  BoundedPowersetAbstractDomain& remove(const Element& e) {
+    m_set.remove(e);
+    return *this;
+  }
+
+  void difference_with(const BoundedPowersetAbstractDomain& other) {
+    m_set.difference_with(other.m_set);
+  }
+
+  bool is_bottom() const override { return m_set.is_bottom(); }
+
+  bool is_top() const override { return m_set.is_top(); }
+
+  void set_to_bottom() override { m_set.set_to_bottom(); }
+
+  void set_to_top() override { m_set.set_to_top(); }
+
+  bool leq(const BoundedPowersetAbstractDomain& other) const override {
+    return m_set.leq(other.m_set);
+  }
+
+  bool equals(const BoundedPowersetAbstractDomain& other) const override {
+    return m_set.equals(other.m_set);
+  }
+
+  void join_with(const BoundedPowersetAbstractDomain& other) override {
+    m_set.join_with(other.m_set);
+    normalize();
+  }
+
+  void widen_with(const BoundedPowersetAbstractDomain& other) override {
+    join_with(other);
+  }
+
+  void meet_with(const BoundedPowersetAbstractDomain& other) override {
+    m_set.meet_with(other.m_set);
+  }
+
+  void narrow_with(const BoundedPowersetAbstractDomain& other) override {
+    meet_with(other);
+  }
+
+  friend std::ostream& operator<<(std::ostream& o,
+                                  const BoundedPowersetAbstractDomain& x) {
+    return o << x.m_set;
+  }
+
+ private:
+  void normalize() {
+    if (m_set.is_value() && m_set.size() > MaxSize) {
+      m_set.set_to_top();
+    }
+  }
+
+  Set m_set;
+};
+
+} // namespace sparta
@@ -0,0 +1,313 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <boost/optional.hpp>
+#include <cstddef>
+#include <ostream>
+#include <vector>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * An abstraction of stacks of values, e.g., the operand stack of a stack-based
+ * bytecode. An abstract stack keeps track of the top MaxDepth elements
+ * individually, and smashes all the elements below into a single abstract
+ * value, which stands for zero or more elements of unknown number:
+ *
+ *   rest* . e_1 . ... . e_n     (e_n is the top of the stack, n <= MaxDepth)
+ *
+ * When no element has been smashed, the depth of the stack is known exactly.
+ * Top denotes any stack, and Bottom denotes an unreachable state or a stack
+ * underflow.
+ *
+ * Stacks of different depths are joined by aligning their tops, and by
+ * smashing the elements in excess. Since the number of elements that are
+ * tracked individually is bounded, the widening of the stacks terminates as
+ * long as the widening of Domain does.
+ */
+template <typename Domain, size_t MaxDepth>
+class BoundedStackDomain final
+    : public AbstractDomain<BoundedStackDomain<Domain, MaxDepth>> {
+  static_assert(MaxDepth > 0, "the depth cap must be positive.");
+
+ public:
+  // By default, an abstract stack is initialized to Top.
+  BoundedStackDomain() : m_rest(Domain::top()) {}
+
+  static BoundedStackDomain bottom() {
+    BoundedStackDomain stack;
+    stack.set_to_bottom();
+    return stack;
+  }
+
+  static BoundedStackDomain top() { return BoundedStackDomain(); }
+
+  /*
+   * The stack that is known to be empty.
+   */
+  static BoundedStackDomain empty() {
+    BoundedStackDomain stack;
+    stack.m_rest.set_to_bottom();
+    return stack;
+  }
+
+  bool is_bottom() const override { return m_is_bottom; }
+
+  bool is_top() const override {
+    return !m_is_bottom && m_elements.empty() && m_rest.is_top();
+  }
+
+  void set_to_bottom() override {
+    m_is_bottom = true;
+    m_elements.clear();
+    m_rest.set_to_bottom();
+  }
+
+  void set_to_top() override {
+    m_is_bottom = false;
+    m_elements.clear();
+    m_rest.set_to_top();
+  }
+
+  /*
+   * The exact depth of the stack, or none if some elements have been smashed.
+   */
+  boost::optional<size_t> depth() const {
+    if (m_is_bottom || !m_rest.is_bottom()) {
+      return boost::none;
+    }
+    return m_elements.size();
+  }
+
+  /*
+   * The elements that are tracked individually, from the bottom of the stack
+   * to the top.
+   */
+  const std::vector<Domain>& elements() const { return m_elements; }
+
+  /*
+   * The join of the elements that have been smashed, or Bottom if there are
+   * none.
+   */
+  const Domain& smashed() const { return m_rest; }
+
+  /*
+   * Pushing Bottom makes the whole stack Bottom.
+   */
+  void push(const Domain& value) {
+    if (m_is_bottom) {
+      return;
+    }
+    if (value.is_bottom()) {
+      set_to_bottom();
+      return;
+    }
+    m_elements.push_back(value);
+    if (m_elements.size() > MaxDepth) {
+      m_rest.join_with(m_elements.front());
+      m_elements.erase(m_elements.begin());
+    }
+  }
+
+  /*
+   * Removes the top of the stack and returns it. Popping a stack that is
+   * known to be empty is an underflow, which makes the stack Bottom.
+   */
+  Domain pop() {
+    if (m_is_bottom) {
+      return Domain::bottom();
+    }
+    if (m_elements.empty()) {
+      if (m_rest.is_bottom()) {
+        set_to_bottom();
+      }
+      // Removing one of the smashed elements leaves zero or more of them.
+      return m_rest;
+    }
+    Domain value = m_elements.back();
+    m_elements.pop_back();
+    return value;
+  }
+
+  /*
+   * The element at the given depth, where depth 0 is the top of the stack.
+   * Returns Bottom if the stack is known to be shallower.
+   */
+  Domain peek(size_t depth = 0) const {
+    if (m_is_bottom) {
+      return Domain::bottom();
+    }
+    if (depth < m_elements.size()) {
+      return m_elements[m_elements.size() - 1 - depth];
+    }
+    return m_rest;
+  }
+
+  bool leq(const BoundedStackDomain& other) const override {
+    if (m_is_bottom) {
+      return true;
+    }
+    if (other.m_is_bottom) {
+      return false;
+    }
+    size_t n = m_elements.size();
+    size_t m = other.m_elements.size();
+    if (other.m_rest.is_bottom()) {
+      if (!m_rest.is_bottom() || n != m) {
+        return false;
+      }
+    } else if (n < m || !m_rest.leq(other.m_rest)) {
+      // All the stacks of `other` have at least m elements.
+      return false;
+    }
+    for (size_t i = 0; i < n; ++i) {
+      const Domain& bound =
+          i < m ? other.m_elements[m - 1 - i] : other.m_rest;
+      if (!m_elements[n - 1 - i].leq(bound)) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  bool equals(const BoundedStackDomain& other) const override {
+    if (m_is_bottom || other.m_is_bottom) {
+      return m_is_bottom == other.m_is_bottom;
+    }
+    if (m_elements.size() != other.m_elements.size() ||
+        !m_rest.equals(other.m_rest)) {
+      return false;
+    }
+    for (size_t i = 0; i < m_elements.size(); ++i) {
+      if (!m_elements[i].equals(other.m_elements[i])) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  void join_with(const BoundedStackDomain& other) override {
+    join_like_operation(other, [](Domain* x, const Domain& y) {
+      x->join_with(y);
+    });
+  }
+
+  void widen_with(const BoundedStackDomain& other) override {
+    join_like_operation(other, [](Domain* x, const Domain& y) {
+      x->widen_with(y);
+    });
+  }
+
+  void meet_with(const BoundedStackDomain& other) override {
+    meet_like_operation(other, [](Domain* x, const Domain& y) {
+      x->meet_with(y);
+    });
+  }
+
+  void narrow_with(const BoundedStackDomain& other) override {
+    meet_like_operation(other, [](Domain* x, const Domain& y) {
+      x->narrow_with(y);
+    });
+  }
+
+  friend std::ostream& operator<<(std::ostream& o,
+                                  const BoundedStackDomain& stack) {
+    if (stack.is_bottom()) {
+      return o << "_|_";
+    }
+    if (stack.is_top()) {
+      return o << "T";
+    }
+    o << "[";
+    bool first = true;
+    if (!stack.m_rest.is_bottom()) {
+      o << stack.m_rest << "*";
+      first = false;
+    }
+    for (const auto& element : stack.m_elements) {
+      o << (first ? "" : ", ") << element;
+      first = false;
+    }
+    return o << "]";
+  }
+
+ private:
+  template <typename Operation>
+  void join_like_operation(const BoundedStackDomain& other,
+                           Operation operation) {
+    if (other.m_is_bottom) {
+      return;
+    }
+    if (m_is_bottom) {
+      *this = other;
+      return;
+    }
+    size_t n = m_elements.size();
+    size_t m = other.m_elements.size();
+    size_t common = std::min(n, m);
+    // The elements in excess are smashed on both sides.
+    Domain rest = m_rest;
+    for (size_t i = 0; i < n - common; ++i) {
+      rest.join_with(m_elements[i]);
+    }
+    Domain other_rest = other.m_rest;
+    for (size_t i = 0; i < m - common; ++i) {
+      other_rest.join_with(other.m_elements[i]);
+    }
+    operation(&rest, other_rest);
+    m_elements.erase(m_elements.begin(), m_elements.begin() + (n - common));
+    for (size_t i = 0; i < common; ++i) {
+      operation(&m_elements[i], other.m_elements[m - common + i]);
+    }
+    m_rest = std::move(rest);
+  }
+
+  template <typename Operation>
+  void meet_like_operation(const BoundedStackDomain& other,
+                           Operation operation) {
+    if (m_is_bottom) {
+      return;
+    }
+    if (other.m_is_bottom) {
+      set_to_bottom();
+      return;
+    }
+    size_t n = m_elements.size();
+    size_t m = other.m_elements.size();
+    // A stack whose depth is known exactly cannot be shallower than the
+    // other one.
+    if ((m_rest.is_bottom() && n < m) || (other.m_rest.is_bottom() && m < n)) {
+      set_to_bottom();
+      return;
+    }
+    std::vector<Domain> elements;
+    elements.reserve(std::max(n, m));
+    for (size_t i = std::max(n, m); i-- > 0;) {
+      // The i-th element from the top of the stack.
+      Domain element = i < n ? m_elements[n - 1 - i] : m_rest;
+      operation(&element, i < m ? other.m_elements[m - 1 - i] : other.m_rest);
+      if (element.is_bottom()) {
+        set_to_bottom();
+        return;
+      }
+      elements.push_back(std::move(element));
+    }
+    operation(&m_rest, other.m_rest);
+    m_elements = std::move(elements);
+  }
+
+  bool m_is_bottom{false};
+  std::vector<Domain> m_elements;
+  Domain m_rest;
+};
+
+} // namespace sparta
@@ -0,0 +1,261 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <boost/optional.hpp>
+#include <cstdint>
+#include <functional>
+#include <queue>
+#include <type_traits>
+#include <unordered_map>
+#include <unordered_set>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+#include "Exceptions.h"
+
+namespace sparta {
+
+/*
+ * A call graph whose nodes are the functions reachable from an entry function.
+ * There is an edge from a caller to a callee for every call site of the
+ * caller that may invoke the callee. Edges are identified by indices.
+ */
+template <typename Function,
+          typename CallSite,
+          typename FunctionHash = std::hash<Function>,
+          typename CallSiteHash = std::hash<CallSite>>
+class CallGraph final {
+ public:
+  using NodeId = Function;
+  using EdgeId = uint32_t;
+
+  struct Edge {
+    Function caller;
+    Function callee;
+    CallSite call_site;
+  };
+
+  explicit CallGraph(Function entry) : m_entry(std::move(entry)) {
+    add_node(m_entry);
+  }
+
+  const Function& entry() const { return m_entry; }
+
+  /*
+   * The functions of the graph, in the order in which they were discovered.
+   */
+  const std::vector<Function>& functions() const { return m_functions; }
+
+  bool contains(const Function& function) const {
+    return m_successors.count(function) > 0;
+  }
+
+  const std::vector<EdgeId>& successors(const Function& function) const {
+    return edges_of(m_successors, function);
+  }
+
+  const std::vector<EdgeId>& predecessors(const Function& function) const {
+    return edges_of(m_predecessors, function);
+  }
+
+  const Edge& edge(EdgeId edge) const { return m_edges.at(edge); }
+
+  size_t num_edges() const { return m_edges.size(); }
+
+  /*
+   * The functions that may be invoked by a call site.
+   */
+  std::vector<Function> callees(const CallSite& call_site) const {
+    std::vector<Function> result;
+    auto it = m_call_sites.find(call_site);
+    if (it != m_call_sites.end()) {
+      for (EdgeId edge : it->second) {
+        result.push_back(m_edges[edge].callee);
+      }
+    }
+    return result;
+  }
+
+  /*
+   * Adds the callee to the graph if needed. Returns false if the edge already
+   * exists.
+   */
+  bool add_edge(const Function& caller,
+                const Function& callee,
+                const CallSite& call_site) {
+    RUNTIME_CHECK(contains(caller), undefined_operation());
+    auto& call_site_edges = m_call_sites[call_site];
+    for (EdgeId edge : call_site_edges) {
+      if (m_edges[edge].callee == callee) {
+        return false;
+      }
+    }
+    add_node(callee);
+    EdgeId edge = m_edges.size();
+    m_edges.push_back(Edge{caller, callee, call_site});
+    call_site_edges.push_back(edge);
+    m_successors.at(caller).push_back(edge);
+    m_predecessors.at(callee).push_back(edge);
+    return true;
+  }
+
+ private:
+  using EdgeMap =
+      std::unordered_map<Function, std::vector<EdgeId>, FunctionHash>;
+
+  static const std::vector<EdgeId>& edges_of(const EdgeMap& map,
+                                             const Function& function) {
+    auto it = map.find(function);
+    if (it == map.end()) {
+      static const std::vector<EdgeId> empty;
+      return empty;
+    }
+    return it->second;
+  }
+
+  void add_node(const Function& function) {
+    if (m_successors.emplace(function, std::vector<EdgeId>()).second) {
+      m_predecessors.emplace(function, std::vector<EdgeId>());
+      m_functions.push_back(function);
+    }
+  }
+
+  Function m_entry;
+  std::vector<Function> m_functions;
+  std::vector<Edge> m_edges;
+  EdgeMap m_successors;
+  EdgeMap m_predecessors;
+  std::unordered_map<CallSite, std::vector<EdgeId>, CallSiteHash>
+      m_call_sites;
+};
+
+/*
+ * The interface to a call graph, as required by the fixpoint iterators and by
+ * the InterproceduralAnalyzer (see Analyzer.h).
+ */
+template <typename CallGraph>
+class CallGraphInterface {
+ public:
+  using Graph = CallGraph;
+  using NodeId = typename Graph::NodeId;
+  using EdgeId = typename Graph::EdgeId;
+
+  static NodeId entry(const Graph& graph) { return graph.entry(); }
+  static std::vector<EdgeId> predecessors(const Graph& graph,
+                                          const NodeId& node) {
+    return graph.predecessors(node);
+  }
+  static std::vector<EdgeId> successors(const Graph& graph,
+                                        const NodeId& node) {
+    return graph.successors(node);
+  }
+  static NodeId source(const Graph& graph, const EdgeId& edge) {
+    return graph.edge(edge).caller;
+  }
+  static NodeId target(const Graph& graph, const EdgeId& edge) {
+    return graph.edge(edge).callee;
+  }
+};
+
+/*
+ * Builds the call graph of the functions reachable from an entry function.
+ * The analysis is independent of the IR: the calls of a function are
+ * described by the Analysis parameter, together with an abstract value of a
+ * powerset domain approximating the dynamic types of the receiver of each
+ * virtual call, e.g., the result of a type or points-to analysis of the
+ * function. A virtual call is resolved for each receiver type, and to all the
+ * implementations of the method if the receiver types are unknown (Top). A
+ * call whose receiver types are Bottom is unreachable and has no callees.
+ *
+ *   struct Analysis {
+ *     using Function = ...; // Must be hashable.
+ *     using CallSite = ...; // Identifies a call instruction, must be hashable.
+ *     using Method = ...;   // The method referenced by a call instruction.
+ *     using Type = ...;
+ *     using TypeSet = ...;  // A powerset abstract domain over Type.
+ *
+ *     // Visits the calls of a function. The receiver types are none for a
+ *     // static call.
+ *     static void for_each_call(
+ *         const Function& function,
+ *         const std::function<void(const CallSite&,
+ *                                  const Method&,
+ *                                  const boost::optional<TypeSet>&)>& visit);
+ *
+ *     // The implementation of a method invoked on a receiver of the given
+ *     // dynamic type, if any.
+ *     static boost::optional<Function> resolve(const Method& method,
+ *                                              const Type& receiver_type);
+ *
+ *     // All the implementations of a method, e.g., the single target of a
+ *     // static method, or the overriding methods of a virtual method.
+ *     static std::vector<Function> implementations(const Method& method);
+ *   };
+ *
+ *   auto graph = CallGraphAnalysis<Analysis>::run(main);
+ *   InterproceduralAnalyzer can then use CallGraphInterface<decltype(graph)>.
+ */
+template <typename Analysis,
+          typename FunctionHash = std::hash<typename Analysis::Function>,
+          typename CallSiteHash = std::hash<typename Analysis::CallSite>>
+class CallGraphAnalysis final {
+ public:
+  using Function = typename Analysis::Function;
+  using CallSite = typename Analysis::CallSite;
+  using Method = typename Analysis::Method;
+  using TypeSet = typename Analysis::TypeSet;
+  using Graph = CallGraph<Function, CallSite, FunctionHash, CallSiteHash>;
+
+  static_assert(std::is_base_of<AbstractDomain<TypeSet>, TypeSet>::value,
+                "Analysis::TypeSet must inherit from sparta::AbstractDomain");
+
+  static Graph run(const Function& entry) {
+    Graph graph(entry);
+    std::queue<Function> worklist;
+    worklist.push(entry);
+    auto add_edge = [&](const Function& caller,
+                        const CallSite& call_site,
+                        const Function& callee) {
+      bool is_new = !graph.contains(callee);
+      graph.add_edge(caller, callee, call_site);
+      if (is_new) {
+        worklist.push(callee);
+      }
+    };
+    while (!worklist.empty()) {
+      Function caller = worklist.front();
+      worklist.pop();
+      Analysis::for_each_call(
+          caller,
+          [&](const CallSite& call_site,
+              const Method& method,
+              const boost::optional<TypeSet>& receiver_types) {
+            if (receiver_types && receiver_types->is_bottom()) {
+              return;
+            }
+            if (!receiver_types || receiver_types->is_top()) {
+              for (const auto& callee : Analysis::implementations(method)) {
+                add_edge(caller, call_site, callee);
+              }
+              return;
+            }
+            for (const auto& type : receiver_types->elements()) {
+              auto callee = Analysis::resolve(method, type);
+              if (callee) {
+                add_edge(caller, call_site, *callee);
+              }
+            }
+          });
+    }
+    return graph;
+  }
+};
+
+} // namespace sparta
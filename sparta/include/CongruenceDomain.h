@@ -0,0 +1,475 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <boost/optional.hpp>
+#include <limits>
+#include <numeric>
+#include <ostream>
+#include <type_traits>
+
+#include "AbstractDomain.h"
+#include "Exceptions.h"
+#include "NumericalDomainOps.h"
+
+namespace sparta {
+
+/*
+ * Arithmetic congruences (Granger, 1989): the sets of integers of the form
+ * a*Z + b, where the modulus a is non-negative. A congruence with a modulus
+ * of 0 denotes the constant b, and Top is 1*Z + 0. When a > 0, the residue b
+ * is normalized to [0, a). This domain complements intervals with alignment
+ * and stride information, e.g., an index that is incremented by 4 in a loop
+ * starting at 2 is in 4*Z + 2.
+ *
+ * The values are mathematical integers: an operation whose result cannot be
+ * represented in Integer is approximated by Top.
+ *
+ * The ascending chains of congruences are finite, hence the widening is the
+ * join. The descending chains are not, hence the narrowing only refines Top.
+ */
+template <typename Integer>
+class CongruenceDomain final
+    : public AbstractDomain<CongruenceDomain<Integer>> {
+  static_assert(std::is_integral<Integer>::value &&
+                    std::is_signed<Integer>::value,
+                "expecting signed integers.");
+
+ public:
+  static constexpr Integer MIN = std::numeric_limits<Integer>::min();
+  static constexpr Integer MAX = std::numeric_limits<Integer>::max();
+
+  // By default, a congruence is initialized to Top.
+  CongruenceDomain() = default;
+
+  static CongruenceDomain bottom() {
+    CongruenceDomain x;
+    x.set_to_bottom();
+    return x;
+  }
+
+  static CongruenceDomain top() { return CongruenceDomain(); }
+
+  static CongruenceDomain constant(Integer value) {
+    return CongruenceDomain(0, value);
+  }
+
+  /* modulus*Z + residue */
+  static CongruenceDomain congruence(Integer modulus, Integer residue) {
+    RUNTIME_CHECK(modulus >= 0, invalid_argument()
+                                    << argument_name("modulus")
+                                    << operation_name("congruence"));
+    return CongruenceDomain(modulus, residue);
+  }
+
+  /*
+   * The modulus and residue are only meaningful when the congruence is not
+   * Bottom.
+   */
+  Integer modulus() const { return m_modulus; }
+
+  Integer residue() const { return m_residue; }
+
+  boost::optional<Integer> get_constant() const {
+    if (m_is_bottom || m_modulus != 0) {
+      return boost::none;
+    }
+    return m_residue;
+  }
+
+  bool contains(Integer value) const {
+    if (m_is_bottom) {
+      return false;
+    }
+    if (m_modulus == 0) {
+      return value == m_residue;
+    }
+    return normalize(value, m_modulus) == m_residue;
+  }
+
+  bool is_bottom() const override { return m_is_bottom; }
+
+  bool is_top() const override { return !m_is_bottom && m_modulus == 1; }
+
+  void set_to_bottom() override {
+    m_is_bottom = true;
+    m_modulus = 0;
+    m_residue = 0;
+  }
+
+  void set_to_top() override {
+    m_is_bottom = false;
+    m_modulus = 1;
+    m_residue = 0;
+  }
+
+  bool leq(const CongruenceDomain& other) const override {
+    if (m_is_bottom) {
+      return true;
+    }
+    if (other.m_is_bottom) {
+      return false;
+    }
+    if (other.m_modulus == 0) {
+      return m_modulus == 0 && m_residue == other.m_residue;
+    }
+    return m_modulus % other.m_modulus == 0 && other.contains(m_residue);
+  }
+
+  bool equals(const CongruenceDomain& other) const override {
+    return m_is_bottom == other.m_is_bottom && m_modulus == other.m_modulus &&
+           m_residue == other.m_residue;
+  }
+
+  /*
+   * The smallest congruence that contains both operands has the gcd of both
+   * moduli and of the difference between the residues as its modulus.
+   */
+  void join_with(const CongruenceDomain& other) override {
+    if (other.m_is_bottom) {
+      return;
+    }
+    if (m_is_bottom) {
+      *this = other;
+      return;
+    }
+    auto difference = checked_sub(m_residue, other.m_residue);
+    if (!difference || *difference == MIN) {
+      set_to_top();
+      return;
+    }
+    auto modulus = std::gcd(std::gcd(m_modulus, other.m_modulus), *difference);
+    *this = CongruenceDomain(modulus, m_residue);
+  }
+
+  void widen_with(const CongruenceDomain& other) override { join_with(other); }
+
+  /*
+   * The intersection of two congruences is computed by the Chinese remainder
+   * theorem. When the resulting modulus cannot be represented, the first
+   * operand is left unchanged, which is a sound approximation.
+   */
+  void meet_with(const CongruenceDomain& other) override {
+    if (m_is_bottom) {
+      return;
+    }
+    if (other.m_is_bottom) {
+      set_to_bottom();
+      return;
+    }
+    if (other.m_modulus == 0) {
+      if (contains(other.m_residue)) {
+        *this = other;
+      } else {
+        set_to_bottom();
+      }
+      return;
+    }
+    if (m_modulus == 0) {
+      if (!other.contains(m_residue)) {
+        set_to_bottom();
+      }
+      return;
+    }
+    Integer g = std::gcd(m_modulus, other.m_modulus);
+    // Both residues are normalized, hence the difference cannot overflow.
+    Integer difference = other.m_residue - m_residue;
+    if (difference % g != 0) {
+      set_to_bottom();
+      return;
+    }
+    auto lcm = checked_mul(m_modulus / g, other.m_modulus);
+    if (!lcm) {
+      return;
+    }
+    // Solve m_modulus * k = difference (mod other.m_modulus).
+    Integer m = other.m_modulus / g;
+    auto k = checked_mul(normalize(difference / g, m),
+                         modular_inverse(normalize(m_modulus / g, m), m));
+    if (!k) {
+      return;
+    }
+    // The residue is smaller than the lcm.
+    *this = CongruenceDomain(*lcm, m_residue + m_modulus * (*k % m));
+  }
+
+  void narrow_with(const CongruenceDomain& other) override {
+    if (is_top()) {
+      *this = other;
+    } else if (other.is_bottom()) {
+      set_to_bottom();
+    }
+  }
+
+  CongruenceDomain operator-() const {
+    if (m_is_bottom) {
+      return *this;
+    }
+    if (m_residue == MIN) {
+      return top();
+    }
+    return CongruenceDomain(m_modulus, -m_residue);
+  }
+
+  /*
+   * (a*Z + b) + (a'*Z + b') = gcd(a, a')*Z + (b + b')
+   */
+  CongruenceDomain& operator+=(const CongruenceDomain& that) {
+    if (m_is_bottom || that.m_is_bottom) {
+      set_to_bottom();
+      return *this;
+    }
+    auto residue = checked_add(m_residue, that.m_residue);
+    if (!residue) {
+      set_to_top();
+      return *this;
+    }
+    *this = CongruenceDomain(std::gcd(m_modulus, that.m_modulus), *residue);
+    return *this;
+  }
+
+  CongruenceDomain& operator-=(const CongruenceDomain& that) {
+    return *this += -that;
+  }
+
+  /*
+   * (a*Z + b) * (a'*Z + b') = gcd(a*a', a*b', a'*b)*Z + b*b'
+   */
+  CongruenceDomain& operator*=(const CongruenceDomain& that) {
+    if (m_is_bottom || that.m_is_bottom) {
+      set_to_bottom();
+      return *this;
+    }
+    auto aa = checked_mul(m_modulus, that.m_modulus);
+    auto ab = checked_mul(m_modulus, that.m_residue);
+    auto ba = checked_mul(that.m_modulus, m_residue);
+    auto bb = checked_mul(m_residue, that.m_residue);
+    if (!aa || !ab || !ba || !bb || *ab == MIN || *ba == MIN) {
+      set_to_top();
+      return *this;
+    }
+    *this = CongruenceDomain(std::gcd(std::gcd(*aa, *ab), *ba), *bb);
+    return *this;
+  }
+
+  friend CongruenceDomain operator+(CongruenceDomain x,
+                                    const CongruenceDomain& y) {
+    return x += y;
+  }
+
+  friend CongruenceDomain operator-(CongruenceDomain x,
+                                    const CongruenceDomain& y) {
+    return x -= y;
+  }
+
+  friend CongruenceDomain operator*(CongruenceDomain x,
+                                    const CongruenceDomain& y) {
+    return x *= y;
+  }
+
+  friend std::ostream& operator<<(std::ostream& o, const CongruenceDomain& x) {
+    if (x.is_bottom()) {
+      return o << "_|_";
+    }
+    if (x.is_top()) {
+      return o << "T";
+    }
+    // Unary plus avoids printing narrow integers as characters.
+    if (x.m_modulus == 0) {
+      return o << +x.m_residue;
+    }
+    return o << +x.m_modulus << "Z+" << +x.m_residue;
+  }
+
+ private:
+  CongruenceDomain(Integer modulus, Integer residue)
+      : m_modulus(modulus),
+        m_residue(modulus == 0 ? residue : normalize(residue, modulus)) {}
+
+  /*
+   * The representative of the value modulo m in [0, m).
+   */
+  static Integer normalize(Integer value, Integer m) {
+    Integer r = value % m;
+    return r < 0 ? r + m : r;
+  }
+
+  /*
+   * The inverse of a modulo m, where a and m are coprime and 0 <= a < m.
+   */
+  static Integer modular_inverse(Integer a, Integer m) {
+    Integer old_r = a, r = m;
+    Integer old_s = 1, s = 0;
+    while (r != 0) {
+      Integer q = old_r / r;
+      Integer next_r = old_r - q * r;
+      old_r = r;
+      r = next_r;
+      Integer next_s = old_s - q * s;
+      old_s = s;
+      s = next_s;
+    }
+    return m == 1 ? 0 : normalize(old_s, m);
+  }
+
+  static boost::optional<Integer> checked_add(Integer a, Integer b) {
+    if ((b > 0 && a > MAX - b) || (b < 0 && a < MIN - b)) {
+      return boost::none;
+    }
+    return Integer(a + b);
+  }
+
+  static boost::optional<Integer> checked_sub(Integer a, Integer b) {
+    if ((b < 0 && a > MAX + b) || (b > 0 && a < MIN + b)) {
+      return boost::none;
+    }
+    return Integer(a - b);
+  }
+
+  static boost::optional<Integer> checked_mul(Integer a, Integer b) {
+    if (a == 0 || b == 0) {
+      return Integer(0);
+    }
+    bool overflow = a > 0 ? (b > 0 ? a > MAX / b : b < MIN / a)
+                          : (b > 0 ? a < MIN / b : a != 0 && b < MAX / a);
+    if (overflow) {
+      return boost::none;
+    }
+    return Integer(a * b);
+  }
+
+  bool m_is_bottom{false};
+  Integer m_modulus{1};
+  Integer m_residue{0};
+};
+
+template <typename Integer>
+struct ArithmeticDomainOps<CongruenceDomain<Integer>> {
+  using Domain = CongruenceDomain<Integer>;
+
+  template <typename Constant>
+  static Domain literal(const Constant& value) {
+    return Domain::constant(static_cast<Integer>(value));
+  }
+
+  static Domain neg(const Domain& x) { return -x; }
+
+  static Domain add(const Domain& x, const Domain& y) { return x + y; }
+
+  static Domain sub(const Domain& x, const Domain& y) { return x - y; }
+
+  static Domain mul(const Domain& x, const Domain& y) { return x * y; }
+
+  static Domain div(const Domain& x, const Domain& y) {
+    auto divisor = y.get_constant();
+    if (x.is_bottom() || y.is_bottom() || (divisor && *divisor == 0)) {
+      return Domain::bottom();
+    }
+    auto dividend = x.get_constant();
+    if (!dividend || !divisor ||
+        (*dividend == Domain::MIN && *divisor == Integer(-1))) {
+      return Domain::top();
+    }
+    return Domain::constant(Integer(*dividend / *divisor));
+  }
+
+  static Domain bit_and(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) { return Integer(a & b); });
+  }
+
+  static Domain bit_or(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) { return Integer(a | b); });
+  }
+
+  static Domain bit_xor(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) { return Integer(a ^ b); });
+  }
+
+  /*
+   * A left shift by a constant amount is a multiplication.
+   */
+  static Domain shl(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    auto amount = y.get_constant();
+    if (!amount || !ops_impl::is_valid_shift(*amount) ||
+        *amount >= std::numeric_limits<Integer>::digits) {
+      return Domain::top();
+    }
+    return x * Domain::constant(Integer(Integer(1) << *amount));
+  }
+
+  static Domain shr(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) {
+      return ops_impl::is_valid_shift(b) ? boost::optional<Integer>(a >> b)
+                                         : boost::none;
+    });
+  }
+
+ private:
+  template <typename Operation>
+  static Domain lift(const Domain& x, const Domain& y, Operation operation) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    if (!x.get_constant() || !y.get_constant()) {
+      return Domain::top();
+    }
+    boost::optional<Integer> result =
+        operation(*x.get_constant(), *y.get_constant());
+    return result ? Domain::constant(*result) : Domain::top();
+  }
+};
+
+template <typename Integer>
+struct CompareDomainOps<CongruenceDomain<Integer>>
+    : DerivedCompareDomainOps<CompareDomainOps<CongruenceDomain<Integer>>,
+                              CongruenceDomain<Integer>> {
+  using Domain = CongruenceDomain<Integer>;
+
+  /*
+   * Two values from disjoint congruences are never equal.
+   */
+  static BooleanDomain eq(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return BooleanDomain::bottom();
+    }
+    if (x.meet(y).is_bottom()) {
+      return BooleanDomain(false);
+    }
+    if (x.get_constant() && y.get_constant()) {
+      return BooleanDomain(true);
+    }
+    return BooleanDomain::top();
+  }
+
+  static BooleanDomain lt(const Domain& x, const Domain& y) {
+    return compare(x, y, [](Integer a, Integer b) { return a < b; });
+  }
+
+  static BooleanDomain le(const Domain& x, const Domain& y) {
+    return compare(x, y, [](Integer a, Integer b) { return a <= b; });
+  }
+
+ private:
+  template <typename Comparison>
+  static BooleanDomain compare(const Domain& x,
+                               const Domain& y,
+                               Comparison comparison) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return BooleanDomain::bottom();
+    }
+    if (!x.get_constant() || !y.get_constant()) {
+      return BooleanDomain::top();
+    }
+    return BooleanDomain(comparison(*x.get_constant(), *y.get_constant()));
+  }
+};
+
+} // namespace sparta
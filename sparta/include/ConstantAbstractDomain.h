@@ -126,6 +126,40 @@ class ConstantAbstractDomain final
                : boost::none;
   }
 
+  /*
+   * Applies a function to the constant, if any. Top and Bottom are mapped to
+   * Top and Bottom respectively:
+   *
+   *   ConstantAbstractDomain<int>(3).map([](int x) { return x + 1; })
+   *     == ConstantAbstractDomain<int>(4)
+   */
+  template <typename Function,
+            typename Result =
+                std::decay_t<std::invoke_result_t<Function, const Constant&>>>
+  ConstantAbstractDomain<Result> map(Function&& f) const {
+    if (this->kind() == AbstractValueKind::Value) {
+      return ConstantAbstractDomain<Result>(
+          f(this->get_value()->get_constant()));
+    }
+    return ConstantAbstractDomain<Result>(this->kind());
+  }
+
+  /*
+   * Applies a function that returns an abstract value to the constant, if
+   * any, e.g., a partial operation that returns Bottom when it is undefined
+   * and Top when its result is unknown. Top and Bottom are mapped to Top and
+   * Bottom respectively.
+   */
+  template <typename Function,
+            typename Result =
+                std::decay_t<std::invoke_result_t<Function, const Constant&>>>
+  Result bind(Function&& f) const {
+    if (this->kind() == AbstractValueKind::Value) {
+      return f(this->get_value()->get_constant());
+    }
+    return Result(this->kind());
+  }
+
   static ConstantAbstractDomain bottom() {
     return ConstantAbstractDomain(AbstractValueKind::Bottom);
   }
@@ -0,0 +1,495 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <boost/optional.hpp>
+#include <cstddef>
+#include <cstdint>
+#include <initializer_list>
+#include <ostream>
+#include <utility>
+
+#include "AbstractDomain.h"
+#include "Exceptions.h"
+#include "PatriciaTreeMap.h"
+
+namespace sparta {
+
+/*
+ * An abstract domain for the number of occurrences of an entity, e.g., the
+ * number of references to an object or the number of times a resource has
+ * been acquired. It is an interval domain over the bounds {0, 1, +oo}:
+ *
+ *                      [0, +oo] (Top)
+ *                     /        \
+ *                [0, 1]       [1, +oo]
+ *               /      \      /
+ *             0          1
+ *               \       /
+ *                  _|_
+ *
+ * where +oo stands for "two or more". This is enough to distinguish an entity
+ * that is certainly absent, certainly unique or possibly shared, while
+ * guaranteeing that analyses converge without widening.
+ */
+class CountingDomain final : public AbstractDomain<CountingDomain> {
+ public:
+  /*
+   * The default constructor produces the Top value.
+   */
+  CountingDomain() : m_lower(0), m_upper(MANY) {}
+
+  static CountingDomain bottom() { return CountingDomain(1, 0); }
+
+  static CountingDomain top() { return CountingDomain(0, MANY); }
+
+  static CountingDomain zero() { return CountingDomain(0, 0); }
+
+  static CountingDomain one() { return CountingDomain(1, 1); }
+
+  static CountingDomain at_most_one() { return CountingDomain(0, 1); }
+
+  static CountingDomain at_least_one() { return CountingDomain(1, MANY); }
+
+  /*
+   * The lower bound of the count, which is either 0 or 1. Throws
+   * invalid_abstract_value if the value is Bottom.
+   */
+  uint32_t lower_bound() const {
+    check_not_bottom();
+    return m_lower;
+  }
+
+  /*
+   * The upper bound of the count, or none if the count is unbounded. Throws
+   * invalid_abstract_value if the value is Bottom.
+   */
+  boost::optional<uint32_t> upper_bound() const {
+    check_not_bottom();
+    if (m_upper == MANY) {
+      return boost::none;
+    }
+    return m_upper;
+  }
+
+  bool is_zero() const { return m_lower == 0 && m_upper == 0; }
+
+  bool is_one() const { return m_lower == 1 && m_upper == 1; }
+
+  /*
+   * Returns true if the count may be 0, i.e., if the entity may be absent.
+   */
+  bool may_be_zero() const { return m_lower == 0; }
+
+  /*
+   * Returns true if the count may be greater than one.
+   */
+  bool may_be_many() const { return m_upper == MANY; }
+
+  /*
+   * Adds one occurrence.
+   */
+  CountingDomain& increment() {
+    if (!is_bottom()) {
+      m_lower = 1;
+      m_upper = std::min<uint8_t>(m_upper + 1, MANY);
+    }
+    return *this;
+  }
+
+  /*
+   * Removes one occurrence. Since a count cannot be negative, removing an
+   * occurrence from a count that is certainly 0 yields Bottom.
+   */
+  CountingDomain& decrement() {
+    if (is_bottom()) {
+      return *this;
+    }
+    if (m_upper == 0) {
+      set_to_bottom();
+      return *this;
+    }
+    m_lower = 0;
+    if (m_upper != MANY) {
+      --m_upper;
+    }
+    return *this;
+  }
+
+  /*
+   * Adds the occurrences counted by another value, e.g., when merging the
+   * resources held by a callee into the ones held by its caller. The sum is
+   * Bottom if either count is Bottom.
+   */
+  CountingDomain& add(const CountingDomain& other) {
+    if (is_bottom() || other.is_bottom()) {
+      set_to_bottom();
+      return *this;
+    }
+    m_lower = std::min<uint8_t>(m_lower + other.m_lower, 1);
+    m_upper = std::min<uint8_t>(m_upper + other.m_upper, MANY);
+    return *this;
+  }
+
+  bool is_bottom() const override { return m_lower > m_upper; }
+
+  bool is_top() const override { return m_lower == 0 && m_upper == MANY; }
+
+  void set_to_bottom() override {
+    m_lower = 1;
+    m_upper = 0;
+  }
+
+  void set_to_top() override {
+    m_lower = 0;
+    m_upper = MANY;
+  }
+
+  bool leq(const CountingDomain& other) const override {
+    if (is_bottom()) {
+      return true;
+    }
+    if (other.is_bottom()) {
+      return false;
+    }
+    return m_lower >= other.m_lower && m_upper <= other.m_upper;
+  }
+
+  bool equals(const CountingDomain& other) const override {
+    return m_lower == other.m_lower && m_upper == other.m_upper;
+  }
+
+  void join_with(const CountingDomain& other) override {
+    if (is_bottom()) {
+      *this = other;
+    } else if (!other.is_bottom()) {
+      m_lower = std::min(m_lower, other.m_lower);
+      m_upper = std::max(m_upper, other.m_upper);
+    }
+  }
+
+  void widen_with(const CountingDomain& other) override { join_with(other); }
+
+  void meet_with(const CountingDomain& other) override {
+    m_lower = std::max(m_lower, other.m_lower);
+    m_upper = std::min(m_upper, other.m_upper);
+    if (m_lower > m_upper) {
+      set_to_bottom();
+    }
+  }
+
+  void narrow_with(const CountingDomain& other) override { meet_with(other); }
+
+  friend std::ostream& operator<<(std::ostream& o, const CountingDomain& x) {
+    if (x.is_bottom()) {
+      return o << "_|_";
+    }
+    if (x.is_top()) {
+      return o << "T";
+    }
+    if (x.m_lower == x.m_upper) {
+      return o << +x.m_lower;
+    }
+    o << "[" << +x.m_lower << ", ";
+    if (x.m_upper == MANY) {
+      o << "+oo";
+    } else {
+      o << +x.m_upper;
+    }
+    return o << "]";
+  }
+
+ private:
+  static constexpr uint8_t MANY = 2;
+
+  CountingDomain(uint8_t lower, uint8_t upper)
+      : m_lower(lower), m_upper(upper) {}
+
+  void check_not_bottom() const {
+    RUNTIME_CHECK(!is_bottom(),
+                  invalid_abstract_value()
+                      << actual_kind(AbstractValueKind::Bottom));
+  }
+
+  // A value is Bottom iff the lower bound exceeds the upper bound, and the
+  // canonical representation of Bottom is [1, 0].
+  uint8_t m_lower;
+  uint8_t m_upper;
+};
+
+/*
+ * An abstract domain for multisets, which maps each element to a
+ * CountingDomain that approximates its number of occurrences. This is the
+ * composition of a set domain with the counting domain: an element is in the
+ * set if its count is not 0. Elements that are not explicitly bound have a
+ * count of 0, so that the empty multiset is cheap to represent.
+ *
+ * The elements are stored in a Patricia tree, hence they must be unsigned
+ * integers or pointers. Since the counts of all the elements are independent,
+ * a multiset in which the count of some element is Bottom is Bottom.
+ */
+template <typename Element>
+class MultisetAbstractDomain final
+    : public AbstractDomain<MultisetAbstractDomain<Element>> {
+ public:
+  struct ValueInterface {
+    using type = CountingDomain;
+
+    static type default_value() { return type::zero(); }
+
+    static bool is_default_value(const type& x) { return x.is_zero(); }
+
+    static bool equals(const type& x, const type& y) { return x.equals(y); }
+  };
+
+  using MapType = PatriciaTreeMap<Element, CountingDomain, ValueInterface>;
+
+  /*
+   * The default constructor produces the empty multiset.
+   */
+  MultisetAbstractDomain() = default;
+
+  explicit MultisetAbstractDomain(AbstractValueKind kind) : m_kind(kind) {
+    RUNTIME_CHECK(kind != AbstractValueKind::Value,
+                  invalid_argument() << argument_name("kind"));
+  }
+
+  /*
+   * A multiset in which each element of the list occurs exactly once.
+   */
+  MultisetAbstractDomain(std::initializer_list<Element> elements) {
+    for (const auto& element : elements) {
+      add(element);
+    }
+  }
+
+  static MultisetAbstractDomain bottom() {
+    return MultisetAbstractDomain(AbstractValueKind::Bottom);
+  }
+
+  static MultisetAbstractDomain top() {
+    return MultisetAbstractDomain(AbstractValueKind::Top);
+  }
+
+  bool is_value() const { return m_kind == AbstractValueKind::Value; }
+
+  /*
+   * The elements whose count is not 0. This operation is only defined if the
+   * multiset is a regular value.
+   */
+  const MapType& bindings() const {
+    RUNTIME_CHECK(is_value(),
+                  invalid_abstract_value()
+                      << expected_kind(AbstractValueKind::Value)
+                      << actual_kind(m_kind));
+    return m_counts;
+  }
+
+  CountingDomain count(const Element& element) const {
+    if (is_bottom()) {
+      return CountingDomain::bottom();
+    }
+    if (is_top()) {
+      return CountingDomain::top();
+    }
+    return m_counts.at(element);
+  }
+
+  /*
+   * Returns true if the element certainly occurs at least once.
+   */
+  bool contains(const Element& element) const {
+    auto x = count(element);
+    return !x.is_bottom() && !x.may_be_zero();
+  }
+
+  /*
+   * This is a no-op if the multiset is Top.
+   */
+  MultisetAbstractDomain& set(const Element& element,
+                              const CountingDomain& value) {
+    if (!is_value()) {
+      return *this;
+    }
+    if (value.is_bottom()) {
+      set_to_bottom();
+      return *this;
+    }
+    m_counts.insert_or_assign(element, value);
+    return *this;
+  }
+
+  /*
+   * Adds one occurrence of the element.
+   */
+  MultisetAbstractDomain& add(const Element& element) {
+    return update(element, [](CountingDomain* x) { x->increment(); });
+  }
+
+  /*
+   * Removes one occurrence of the element. This yields Bottom if the element
+   * certainly does not occur in the multiset.
+   */
+  MultisetAbstractDomain& remove(const Element& element) {
+    return update(element, [](CountingDomain* x) { x->decrement(); });
+  }
+
+  bool is_bottom() const override {
+    return m_kind == AbstractValueKind::Bottom;
+  }
+
+  bool is_top() const override { return m_kind == AbstractValueKind::Top; }
+
+  void set_to_bottom() override {
+    m_kind = AbstractValueKind::Bottom;
+    m_counts.clear();
+  }
+
+  void set_to_top() override {
+    m_kind = AbstractValueKind::Top;
+    m_counts.clear();
+  }
+
+  bool leq(const MultisetAbstractDomain& other) const override {
+    if (is_bottom() || other.is_top()) {
+      return true;
+    }
+    if (other.is_bottom() || is_top()) {
+      return false;
+    }
+    for (const auto& binding : m_counts) {
+      if (!binding.second.leq(other.m_counts.at(binding.first))) {
+        return false;
+      }
+    }
+    // The elements that only occur in the other multiset have a count of 0
+    // in this one.
+    for (const auto& binding : other.m_counts) {
+      if (!binding.second.may_be_zero() &&
+          ValueInterface::is_default_value(m_counts.at(binding.first))) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  bool equals(const MultisetAbstractDomain& other) const override {
+    return m_kind == other.m_kind && m_counts.equals(other.m_counts);
+  }
+
+  void join_with(const MultisetAbstractDomain& other) override {
+    if (is_top() || other.is_bottom()) {
+      return;
+    }
+    if (other.is_top() || is_bottom()) {
+      *this = other;
+      return;
+    }
+    auto only_here = only_in(m_counts, other.m_counts);
+    auto only_there = only_in(other.m_counts, m_counts);
+    m_counts.union_with(
+        [](const CountingDomain& x, const CountingDomain& y) {
+          return x.join(y);
+        },
+        other.m_counts);
+    // The element may be absent from the other multiset.
+    for (const auto* one_sided : {&only_here, &only_there}) {
+      for (const auto& binding : *one_sided) {
+        m_counts.insert_or_assign(
+            binding.first, binding.second.join(CountingDomain::zero()));
+      }
+    }
+  }
+
+  void widen_with(const MultisetAbstractDomain& other) override {
+    join_with(other);
+  }
+
+  void meet_with(const MultisetAbstractDomain& other) override {
+    if (is_bottom() || other.is_top()) {
+      return;
+    }
+    if (other.is_bottom() || is_top()) {
+      *this = other;
+      return;
+    }
+    // The meet of the count of an element that is absent from one multiset is
+    // either 0 or Bottom.
+    auto only_here = only_in(m_counts, other.m_counts);
+    auto only_there = only_in(other.m_counts, m_counts);
+    for (const auto* one_sided : {&only_here, &only_there}) {
+      for (const auto& binding : *one_sided) {
+        if (!binding.second.may_be_zero()) {
+          set_to_bottom();
+          return;
+        }
+      }
+    }
+    m_counts.intersection_with(
+        [](const CountingDomain& x, const CountingDomain& y) {
+          return x.meet(y);
+        },
+        other.m_counts);
+    for (const auto& binding : m_counts) {
+      if (binding.second.is_bottom()) {
+        set_to_bottom();
+        return;
+      }
+    }
+  }
+
+  void narrow_with(const MultisetAbstractDomain& other) override {
+    meet_with(other);
+  }
+
+  friend std::ostream& operator<<(std::ostream& o,
+                                  const MultisetAbstractDomain& x) {
+    if (x.is_bottom()) {
+      return o << "_|_";
+    }
+    if (x.is_top()) {
+      return o << "T";
+    }
+    o << "{";
+    for (auto it = x.m_counts.begin(); it != x.m_counts.end();) {
+      o << it->first << " -> " << it->second;
+      ++it;
+      if (it != x.m_counts.end()) {
+        o << ", ";
+      }
+    }
+    return o << "}";
+  }
+
+ private:
+  template <typename Operation>
+  MultisetAbstractDomain& update(const Element& element,
+                                 Operation&& operation) {
+    if (!is_value()) {
+      return *this;
+    }
+    auto value = m_counts.at(element);
+    operation(&value);
+    return set(element, value);
+  }
+
+  // The bindings of the elements of x that are not bound in y.
+  static MapType only_in(const MapType& x, const MapType& y) {
+    return x.get_difference_with(
+        [](const CountingDomain&, const CountingDomain&) {
+          return CountingDomain::zero();
+        },
+        y);
+  }
+
+  AbstractValueKind m_kind{AbstractValueKind::Value};
+  MapType m_counts;
+};
+
+} // namespace sparta
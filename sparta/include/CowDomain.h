@@ -0,0 +1,182 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <functional>
+#include <memory>
+#include <ostream>
+#include <utility>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+namespace cow_impl {
+
+template <typename Domain>
+const std::shared_ptr<Domain>& top_value() {
+  static const std::shared_ptr<Domain> top =
+      std::make_shared<Domain>(Domain::top());
+  return top;
+}
+
+template <typename Domain>
+const std::shared_ptr<Domain>& bottom_value() {
+  static const std::shared_ptr<Domain> bottom =
+      std::make_shared<Domain>(Domain::bottom());
+  return bottom;
+}
+
+} // namespace cow_impl
+
+/*
+ * A copy-on-write wrapper around an abstract domain whose values are expensive
+ * to copy. Copying an element only copies a reference-counted pointer, and the
+ * underlying value is cloned the first time a shared element is modified. This
+ * pays off in a fixpoint iteration, which copies the states at every node and
+ * edge while most transformers only modify a few of them:
+ *
+ *   using State = CowDomain<Environment>;
+ *
+ *   State analyze_edge(const EdgeId&, const State& state) const override {
+ *     return state; // No copy of the environment.
+ *   }
+ *
+ *   void analyze_node(const NodeId& node, State* state) const override {
+ *     if (assigns_register(node)) {
+ *       state->mutate()->set(dest(node), value(node)); // Clones if shared.
+ *     }
+ *   }
+ *
+ * The lattice operations are forwarded to the underlying domain, and they
+ * share the operands instead of copying them whenever the result is one of
+ * them, e.g., when joining with Bottom. Two elements that share the same value
+ * are compared in constant time.
+ *
+ * Distinct elements can be used concurrently, even if they share the same
+ * value, but a given element must not be read while it is being modified.
+ */
+template <typename Domain>
+class CowDomain final : public AbstractDomain<CowDomain<Domain>> {
+ public:
+  /*
+   * The default constructor wraps the default value of the underlying domain.
+   */
+  CowDomain() : m_value(std::make_shared<Domain>()) {}
+
+  CowDomain(Domain value)
+      : m_value(std::make_shared<Domain>(std::move(value))) {}
+
+  const Domain& get() const { return *m_value; }
+
+  const Domain* operator->() const { return m_value.get(); }
+
+  /*
+   * Returns the underlying value for modification, which is cloned first if
+   * it is shared with another element.
+   */
+  Domain* mutate() {
+    if (m_value.use_count() > 1) {
+      m_value = std::make_shared<Domain>(*m_value);
+    }
+    return m_value.get();
+  }
+
+  CowDomain& update(const std::function<void(Domain*)>& operation) {
+    operation(mutate());
+    return *this;
+  }
+
+  /*
+   * Whether both elements share the same underlying value.
+   */
+  bool reference_equals(const CowDomain& other) const {
+    return m_value == other.m_value;
+  }
+
+  bool is_bottom() const override { return m_value->is_bottom(); }
+
+  bool is_top() const override { return m_value->is_top(); }
+
+  bool leq(const CowDomain& other) const override {
+    return reference_equals(other) || m_value->leq(*other.m_value);
+  }
+
+  bool equals(const CowDomain& other) const override {
+    return reference_equals(other) || m_value->equals(*other.m_value);
+  }
+
+  void set_to_bottom() override { m_value = cow_impl::bottom_value<Domain>(); }
+
+  void set_to_top() override { m_value = cow_impl::top_value<Domain>(); }
+
+  void join_with(const CowDomain& other) override {
+    if (reference_equals(other) || is_top() || other.is_bottom()) {
+      return;
+    }
+    if (is_bottom() || other.is_top()) {
+      m_value = other.m_value;
+      return;
+    }
+    mutate()->join_with(*other.m_value);
+  }
+
+  void widen_with(const CowDomain& other) override {
+    if (is_top() || other.is_bottom()) {
+      return;
+    }
+    if (is_bottom()) {
+      m_value = other.m_value;
+      return;
+    }
+    mutate()->widen_with(*other.m_value);
+  }
+
+  void meet_with(const CowDomain& other) override {
+    if (reference_equals(other) || is_bottom() || other.is_top()) {
+      return;
+    }
+    if (is_top() || other.is_bottom()) {
+      m_value = other.m_value;
+      return;
+    }
+    mutate()->meet_with(*other.m_value);
+  }
+
+  void narrow_with(const CowDomain& other) override {
+    if (is_bottom() || other.is_top()) {
+      return;
+    }
+    if (is_top()) {
+      m_value = other.m_value;
+      return;
+    }
+    mutate()->narrow_with(*other.m_value);
+  }
+
+  static CowDomain bottom() {
+    return CowDomain(cow_impl::bottom_value<Domain>());
+  }
+
+  static CowDomain top() { return CowDomain(cow_impl::top_value<Domain>()); }
+
+ private:
+  explicit CowDomain(std::shared_ptr<Domain> value)
+      : m_value(std::move(value)) {}
+
+  std::shared_ptr<Domain> m_value;
+};
+
+} // namespace sparta
+
+template <typename Domain>
+inline std::ostream& operator<<(std::ostream& o,
+                                const sparta::CowDomain<Domain>& cow) {
+  o << cow.get();
+  return o;
+}
@@ -0,0 +1,113 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <type_traits>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * The abstract domains that map keys to abstract values only store some of
+ * the bindings, and the keys that are not stored are implicitly bound to a
+ * default value. There are two opposite conventions:
+ *
+ *   Domain                             Missing key   Non-extremal example
+ *   ---------------------------------  -----------   ---------------------
+ *   HashedAbstractEnvironment          Top           {x -> [0, 1]}
+ *   PatriciaTreeMapAbstractEnvironment Top           {x -> [0, 1]}
+ *   ArrayAbstractEnvironment           Top           {0 -> [0, 1]}
+ *   WideRegisterEnvironment            Top           {v0 -> [0, 1]}
+ *   HashedAbstractPartition            Bottom        {l -> [0, 1]}
+ *   PatriciaTreeMapAbstractPartition   Bottom        {l -> [0, 1]}
+ *   SsaAbstractEnvironment             Bottom        {x -> [0, 1]}
+ *
+ * An environment is a conjunction of properties: a missing variable is
+ * unconstrained, and binding any variable to Bottom makes the whole
+ * environment Bottom (except in SsaAbstractEnvironment, where a missing
+ * variable is undefined). A partition is a disjunction of properties: a
+ * missing label is unreachable, and binding any label to Top makes the whole
+ * partition Top. In both cases, binding a key to the default value is the
+ * same as removing the binding. The extremal values don't follow the
+ * convention: all the keys of the Bottom environment are bound to Bottom and
+ * all the labels of the Top partition are bound to Top.
+ *
+ * Each of these domains declares its convention as a member type:
+ *
+ *   using DefaultBinding = DefaultIsTop; // or DefaultIsBottom
+ *
+ * MapAbstractDomain takes the convention as a parameter instead, and the
+ * Patricia tree environment and partition are its two instances.
+ *
+ * so that generic code over maps can check at compile time that it doesn't
+ * mix the two conventions, e.g., a summary store that relies on a missing key
+ * meaning "no summary":
+ *
+ *   template <typename Summaries>
+ *   class SummaryStore {
+ *     static_assert(default_is_bottom<Summaries>::value,
+ *                   "an unknown summary must be Bottom");
+ *     ...
+ *   };
+ */
+struct DefaultIsTop {};
+
+struct DefaultIsBottom {};
+
+/*
+ * The convention of a map domain. The member `type` is only defined for the
+ * domains that declare their convention.
+ */
+template <typename Map, typename = void>
+struct default_binding_of {};
+
+template <typename Map>
+struct default_binding_of<Map, std::void_t<typename Map::DefaultBinding>> {
+  using type = typename Map::DefaultBinding;
+};
+
+template <typename Map, typename = void>
+struct default_is_top : std::false_type {};
+
+template <typename Map>
+struct default_is_top<Map, std::void_t<typename Map::DefaultBinding>>
+    : std::is_same<typename Map::DefaultBinding, DefaultIsTop> {};
+
+template <typename Map, typename = void>
+struct default_is_bottom : std::false_type {};
+
+template <typename Map>
+struct default_is_bottom<Map, std::void_t<typename Map::DefaultBinding>>
+    : std::is_same<typename Map::DefaultBinding, DefaultIsBottom> {};
+
+/*
+ * Holds iff both map domains declare the same convention.
+ */
+template <typename Map1, typename Map2>
+struct same_default_binding
+    : std::integral_constant<bool,
+                             (default_is_top<Map1>::value &&
+                              default_is_top<Map2>::value) ||
+                                 (default_is_bottom<Map1>::value &&
+                                  default_is_bottom<Map2>::value)> {};
+
+/*
+ * The value of the keys that are missing from a map domain of the given
+ * convention.
+ */
+template <typename DefaultBinding, typename Domain>
+Domain default_binding_value() {
+  static_assert(std::is_same<DefaultBinding, DefaultIsTop>::value ||
+                    std::is_same<DefaultBinding, DefaultIsBottom>::value,
+                "DefaultBinding must be DefaultIsTop or DefaultIsBottom");
+  return std::is_same<DefaultBinding, DefaultIsTop>::value ? Domain::top()
+                                                           : Domain::bottom();
+}
+
+} // namespace sparta
@@ -118,6 +118,19 @@ class DisjointUnionAbstractDomain final
     this->meet_with(other);
   }
 
+  /*
+   * The extremal elements of all the component domains are equivalent. This
+   * represents them by the ones of the first domain (see canonicalize() in
+   * AbstractDomain.h).
+   */
+  void canonicalize() {
+    if (is_top()) {
+      m_variant = FirstDomain::top();
+    } else if (is_bottom()) {
+      m_variant = FirstDomain::bottom();
+    }
+  }
+
   /*
    * This will throw if the domain contained in the union differs from the
    * requested Domain.
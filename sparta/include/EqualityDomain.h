@@ -0,0 +1,304 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <cstddef>
+#include <ostream>
+#include <unordered_map>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+#include "PatriciaTreeMap.h"
+#include "PatriciaTreeSet.h"
+#include "PatriciaTreeUtil.h"
+
+namespace sparta {
+
+/*
+ * The domain of conjunctions of equalities between variables, i.e., of
+ * partitions of the variables into equivalence classes, e.g., for copy
+ * propagation (x = y, hence x can be replaced by y) or for alias classes.
+ * This is a lightweight relational domain that can be used alongside any
+ * non-relational environment, e.g., in a reduced product.
+ *
+ * An element is a persistent union-find: every variable of a class with at
+ * least two variables is bound to the representative of its class, which is
+ * the variable with the largest encoding (see PatriciaTreeKeyTraits), and
+ * every representative is bound to the set of variables of its class. The
+ * union-find is kept flat, so that finding the representative of a variable
+ * is a single lookup. Since the representation is canonical and based on
+ * Patricia trees, copying an element is constant-time and comparing two
+ * elements is fast when they share most of their bindings.
+ *
+ * Top is the absence of any equality, i.e., all the classes are singletons.
+ * The join only keeps the equalities that hold on both sides, and the meet
+ * keeps the equalities of either side. Equalities can never be
+ * contradictory, hence Bottom is only reached by an explicit set_to_bottom().
+ * Since the join of an element with an ascending chain removes equalities,
+ * the domain has no infinite ascending chain and the widening is the join.
+ *
+ * The variables must be unsigned integers, pointers or have a specialization
+ * of PatriciaTreeKeyTraits.
+ */
+template <typename Variable>
+class EqualityDomain final : public AbstractDomain<EqualityDomain<Variable>> {
+ public:
+  using IntegerType = typename PatriciaTreeKeyTraits<Variable>::IntegerType;
+  using Class = PatriciaTreeSet<Variable>;
+
+  /*
+   * The default constructor produces the Top value.
+   */
+  EqualityDomain() = default;
+
+  static EqualityDomain bottom() {
+    EqualityDomain equalities;
+    equalities.set_to_bottom();
+    return equalities;
+  }
+
+  static EqualityDomain top() { return EqualityDomain(); }
+
+  bool is_bottom() const override { return m_is_bottom; }
+
+  bool is_top() const override {
+    return !m_is_bottom && m_representatives.empty();
+  }
+
+  void set_to_bottom() override {
+    m_is_bottom = true;
+    m_representatives.clear();
+    m_classes.clear();
+  }
+
+  void set_to_top() override {
+    m_is_bottom = false;
+    m_representatives.clear();
+    m_classes.clear();
+  }
+
+  /*
+   * The representative of the class of a variable. Two variables are known to
+   * be equal iff they have the same representative.
+   */
+  Variable representative(const Variable& x) const {
+    IntegerType r = m_representatives.at(x);
+    return r == 0 ? x : decode(r);
+  }
+
+  bool are_equal(const Variable& x, const Variable& y) const {
+    return m_is_bottom || representative(x) == representative(y);
+  }
+
+  /*
+   * The variables that are known to be equal to a variable, including itself.
+   */
+  Class class_of(const Variable& x) const {
+    Class members = m_classes.at(representative(x));
+    return members.empty() ? Class{x} : members;
+  }
+
+  /*
+   * The classes with at least two variables, each in increasing order of
+   * encoding, ordered by their smallest variable.
+   */
+  std::vector<std::vector<Variable>> classes() const {
+    std::vector<std::vector<Variable>> result;
+    for (const auto& binding : m_classes) {
+      std::vector<Variable> members(binding.second.begin(),
+                                    binding.second.end());
+      std::sort(members.begin(), members.end(), less);
+      result.push_back(std::move(members));
+    }
+    std::sort(result.begin(),
+              result.end(),
+              [](const auto& c1, const auto& c2) {
+                return less(c1.front(), c2.front());
+              });
+    return result;
+  }
+
+  /*
+   * Adds the equality x = y, which merges the classes of x and y.
+   */
+  EqualityDomain& add_equality(const Variable& x, const Variable& y) {
+    if (m_is_bottom) {
+      return *this;
+    }
+    Variable rx = representative(x);
+    Variable ry = representative(y);
+    if (rx == ry) {
+      return *this;
+    }
+    if (less(rx, ry)) {
+      std::swap(rx, ry);
+    }
+    // The class of ry is merged into the class of rx.
+    Class merged = class_of(rx);
+    Class absorbed = class_of(ry);
+    for (const auto& z : absorbed) {
+      m_representatives.insert_or_assign(z, encode(rx));
+    }
+    m_classes.insert_or_assign(ry, Class());
+    m_classes.insert_or_assign(rx, merged.union_with(absorbed));
+    return *this;
+  }
+
+  /*
+   * Removes all the equalities involving a variable, e.g., when it is
+   * overwritten.
+   */
+  EqualityDomain& forget(const Variable& x) {
+    if (m_is_bottom) {
+      return *this;
+    }
+    Variable rx = representative(x);
+    Class members = m_classes.at(rx);
+    if (members.empty()) {
+      return *this;
+    }
+    members.remove(x);
+    m_representatives.insert_or_assign(x, 0);
+    m_classes.insert_or_assign(rx, Class());
+    if (members.size() == 1) {
+      // The remaining variable is now alone in its class.
+      m_representatives.insert_or_assign(*members.begin(), 0);
+      return *this;
+    }
+    Variable r = rx;
+    if (r == x) {
+      r = *std::max_element(members.begin(), members.end(), less);
+      m_representatives.insert_or_assign(r, 0);
+      for (const auto& z : members) {
+        if (z != r) {
+          m_representatives.insert_or_assign(z, encode(r));
+        }
+      }
+    }
+    m_classes.insert_or_assign(r, members);
+    return *this;
+  }
+
+  /*
+   * The transformer of the copy x = y.
+   */
+  EqualityDomain& assign(const Variable& x, const Variable& y) {
+    if (x == y) {
+      return *this;
+    }
+    forget(x);
+    return add_equality(x, y);
+  }
+
+  bool leq(const EqualityDomain& other) const override {
+    if (m_is_bottom) {
+      return true;
+    }
+    if (other.m_is_bottom) {
+      return false;
+    }
+    // Every equality of the other element must hold in this one.
+    for (const auto& binding : other.m_representatives) {
+      if (representative(binding.first) !=
+          representative(decode(binding.second))) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  bool equals(const EqualityDomain& other) const override {
+    return m_is_bottom == other.m_is_bottom &&
+           m_representatives.equals(other.m_representatives);
+  }
+
+  void join_with(const EqualityDomain& other) override {
+    if (other.m_is_bottom) {
+      return;
+    }
+    if (m_is_bottom) {
+      *this = other;
+      return;
+    }
+    // Two variables remain equal iff they are equal on both sides, i.e., the
+    // classes of the join are the intersections of the classes.
+    EqualityDomain result;
+    for (const auto& binding : m_classes) {
+      std::unordered_map<IntegerType, Variable> first_of;
+      for (const auto& x : binding.second) {
+        auto it = first_of.emplace(encode(other.representative(x)), x).first;
+        result.add_equality(it->second, x);
+      }
+    }
+    *this = std::move(result);
+  }
+
+  void widen_with(const EqualityDomain& other) override { join_with(other); }
+
+  void meet_with(const EqualityDomain& other) override {
+    if (m_is_bottom) {
+      return;
+    }
+    if (other.m_is_bottom) {
+      set_to_bottom();
+      return;
+    }
+    for (const auto& binding : other.m_representatives) {
+      add_equality(binding.first, decode(binding.second));
+    }
+  }
+
+  void narrow_with(const EqualityDomain& other) override { meet_with(other); }
+
+  friend std::ostream& operator<<(std::ostream& o,
+                                  const EqualityDomain& equalities) {
+    if (equalities.is_bottom()) {
+      return o << "_|_";
+    }
+    if (equalities.is_top()) {
+      return o << "T";
+    }
+    o << "{";
+    bool first = true;
+    for (const auto& members : equalities.classes()) {
+      o << (first ? "" : ", ");
+      first = false;
+      for (size_t i = 0; i < members.size(); ++i) {
+        o << (i == 0 ? "" : " = ") << members[i];
+      }
+    }
+    return o << "}";
+  }
+
+ private:
+  static IntegerType encode(const Variable& x) {
+    return PatriciaTreeKeyTraits<Variable>::encode(x);
+  }
+
+  static Variable decode(IntegerType x) {
+    return PatriciaTreeKeyTraits<Variable>::decode(x);
+  }
+
+  static bool less(const Variable& x, const Variable& y) {
+    return encode(x) < encode(y);
+  }
+
+  bool m_is_bottom{false};
+  // Binds every variable of a class with at least two variables, other than
+  // the representative, to the encoding of the representative. The encoding
+  // of a representative is never 0, which is the default value of the map,
+  // since it is larger than the encoding of another variable.
+  PatriciaTreeMap<Variable, IntegerType> m_representatives;
+  // Binds the representative of every class with at least two variables to
+  // the variables of the class.
+  PatriciaTreeMap<Variable, Class> m_classes;
+};
+
+} // namespace sparta
@@ -0,0 +1,159 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <functional>
+#include <type_traits>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * The control-flow graphs of languages with exceptions (e.g., Dex or JVM
+ * bytecode) have two kinds of edges: normal edges, which are followed when a
+ * node executes to completion, and exceptional edges, which are followed when
+ * an instruction in the node throws. Folding both kinds of edges into a single
+ * edge set loses precision, since the state of the program at the point where
+ * the exception is raised is generally not the exit state of the node.
+ *
+ * In addition to the methods described in FixpointIterator.h, the interface to
+ * the CFG must provide the following method in order to be used with the
+ * adaptors defined here:
+ *
+ *  static bool is_exceptional(const Graph& graph, const EdgeId& e) { ... }
+ *
+ * This class exposes the normal and exceptional successors and predecessors of
+ * a node separately.
+ */
+template <typename GraphInterface>
+class ExceptionalEdgesAdaptor {
+ public:
+  using Graph = typename GraphInterface::Graph;
+  using NodeId = typename GraphInterface::NodeId;
+  using EdgeId = typename GraphInterface::EdgeId;
+
+  static std::vector<EdgeId> normal_successors(const Graph& graph,
+                                               const NodeId& node) {
+    return filter(graph, GraphInterface::successors(graph, node),
+                  /* exceptional */ false);
+  }
+
+  static std::vector<EdgeId> exceptional_successors(const Graph& graph,
+                                                    const NodeId& node) {
+    return filter(graph, GraphInterface::successors(graph, node),
+                  /* exceptional */ true);
+  }
+
+  static std::vector<EdgeId> normal_predecessors(const Graph& graph,
+                                                 const NodeId& node) {
+    return filter(graph, GraphInterface::predecessors(graph, node),
+                  /* exceptional */ false);
+  }
+
+  static std::vector<EdgeId> exceptional_predecessors(const Graph& graph,
+                                                      const NodeId& node) {
+    return filter(graph, GraphInterface::predecessors(graph, node),
+                  /* exceptional */ true);
+  }
+
+ private:
+  template <typename Edges>
+  static std::vector<EdgeId> filter(const Graph& graph,
+                                    const Edges& edges,
+                                    bool exceptional) {
+    static_assert(
+        std::is_same<decltype(GraphInterface::is_exceptional(
+                         std::declval<Graph>(), std::declval<EdgeId>())),
+                     bool>::value,
+        "No implementation of is_exceptional()");
+    std::vector<EdgeId> result;
+    for (const EdgeId& edge : edges) {
+      if (GraphInterface::is_exceptional(graph, edge) == exceptional) {
+        result.push_back(edge);
+      }
+    }
+    return result;
+  }
+};
+
+/*
+ * This fixpoint iterator analyzes normal and exceptional edges with distinct
+ * semantic transformers. It is parameterized by one of the monotonic fixpoint
+ * iterators defined in MonotonicFixpointIterator.h, e.g.:
+ *
+ *   class MyAnalyzer final
+ *       : public ExceptionalEdgesFixpointIterator<MonotonicFixpointIterator,
+ *                                                 MyCFGInterface,
+ *                                                 MyDomain> {
+ *     ...
+ *   };
+ *
+ * The state propagated along an exceptional edge is obtained by applying
+ * `analyze_node_until_throw` to the entry state of the source node, i.e., the
+ * throwing edge only observes the effects of the instructions executed before
+ * the exception is raised.
+ */
+template <template <typename GraphInterface, typename Domain, typename NodeHash>
+          class FixpointIteratorBase,
+          typename GraphInterface,
+          typename Domain,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class ExceptionalEdgesFixpointIterator
+    : public FixpointIteratorBase<GraphInterface, Domain, NodeHash> {
+ public:
+  using Base = FixpointIteratorBase<GraphInterface, Domain, NodeHash>;
+  using Graph = typename GraphInterface::Graph;
+  using NodeId = typename GraphInterface::NodeId;
+  using EdgeId = typename GraphInterface::EdgeId;
+
+  using Base::Base;
+
+  /*
+   * This method computes the state of the program at the point where an
+   * exception is raised within the node. It is invoked with the entry state of
+   * the node. If a node may throw at several points, the transformer should
+   * return the join of the states at all these points.
+   *
+   * Node transformers are required to be monotonic.
+   */
+  virtual void analyze_node_until_throw(const NodeId& node,
+                                        Domain* current_state) const = 0;
+
+  /*
+   * The semantic transformer of a normal edge, which is given the exit state
+   * of the source node.
+   */
+  virtual Domain analyze_normal_edge(
+      const EdgeId& edge, const Domain& exit_state_at_source) const = 0;
+
+  /*
+   * The semantic transformer of an exceptional edge, which is given the state
+   * computed by `analyze_node_until_throw` for the source node.
+   */
+  virtual Domain analyze_exceptional_edge(
+      const EdgeId& edge, const Domain& throw_state_at_source) const = 0;
+
+  Domain analyze_edge(const EdgeId& edge,
+                      const Domain& exit_state_at_source) const final {
+    if (!GraphInterface::is_exceptional(this->m_graph, edge)) {
+      return analyze_normal_edge(edge, exit_state_at_source);
+    }
+    Domain throw_state = this->get_entry_state_at(
+        GraphInterface::source(this->m_graph, edge));
+    if (!throw_state.is_bottom()) {
+      analyze_node_until_throw(GraphInterface::source(this->m_graph, edge),
+                               &throw_state);
+    }
+    return analyze_exceptional_edge(edge, throw_state);
+  }
+};
+
+} // namespace sparta
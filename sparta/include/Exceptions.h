@@ -7,7 +7,9 @@
 
 #pragma once
 
+#include <cstdint>
 #include <exception>
+#include <string>
 
 #include <boost/exception/all.hpp>
 
@@ -46,6 +48,16 @@ using argument_name = boost::error_info<struct tag_argument_name, std::string>;
 using operation_name =
     boost::error_info<struct tag_operation_name, std::string>;
 
+/*
+ * The node of the graph being analyzed when an error was raised by a fixpoint
+ * iterator, either in the iterator itself or in the abstract domain, as
+ * described by the NodeInfo of the iterator (see NodeInfo.h), and its index in
+ * the weak partial ordering of the graph, if the iterator uses one.
+ */
+using node_name = boost::error_info<struct tag_node_name, std::string>;
+
+using wpo_index = boost::error_info<struct tag_wpo_index, uint32_t>;
+
 } // namespace sparta
 
 /*
@@ -55,3 +67,23 @@ using operation_name =
   if (!(C)) {                   \
     BOOST_THROW_EXCEPTION((E)); \
   }
+
+/*
+ * Checks an internal invariant of a data structure, e.g., that the
+ * representation of an abstract value is normalized. These checks can be
+ * expensive, hence they are disabled by default and can be enabled by defining
+ * the macro SPARTA_CHECK_INVARIANTS before including any header of the
+ * library. A violation throws an internal_error.
+ */
+#ifdef SPARTA_CHECK_INVARIANTS
+#define SPARTA_INVARIANT(C)                                            \
+  do {                                                                 \
+    RUNTIME_CHECK(C,                                                   \
+                  sparta::internal_error()                             \
+                      << sparta::error_msg("invariant violated: " #C)) \
+  } while (false)
+#else
+#define SPARTA_INVARIANT(C) \
+  do {                      \
+  } while (false)
+#endif
@@ -0,0 +1,183 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include "NumericalDomainOps.h"
+
+namespace sparta {
+
+enum class ExpressionKind { Literal, Variable, Unary, Binary };
+
+enum class UnaryOperator { Neg };
+
+enum class BinaryOperator {
+  Add,
+  Sub,
+  Mul,
+  Div,
+  And,
+  Or,
+  Xor,
+  Shl,
+  Shr,
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge
+};
+
+namespace ev_impl {
+
+template <typename CompareOps, typename Domain>
+BooleanDomain compare(BinaryOperator op, const Domain& x, const Domain& y) {
+  switch (op) {
+  case BinaryOperator::Eq: {
+    return CompareOps::eq(x, y);
+  }
+  case BinaryOperator::Ne: {
+    return CompareOps::ne(x, y);
+  }
+  case BinaryOperator::Lt: {
+    return CompareOps::lt(x, y);
+  }
+  case BinaryOperator::Le: {
+    return CompareOps::le(x, y);
+  }
+  case BinaryOperator::Gt: {
+    return CompareOps::gt(x, y);
+  }
+  case BinaryOperator::Ge: {
+    return CompareOps::ge(x, y);
+  }
+  default: {
+    return BooleanDomain::top();
+  }
+  }
+}
+
+/*
+ * As in C, true is 1 and false is 0.
+ */
+template <typename Domain, typename Ops>
+Domain to_number(const BooleanDomain& condition) {
+  if (condition.is_bottom()) {
+    return Domain::bottom();
+  }
+  auto value = condition.get_constant();
+  if (value) {
+    return Ops::literal(*value ? 1 : 0);
+  }
+  return Ops::literal(0).join(Ops::literal(1));
+}
+
+} // namespace ev_impl
+
+/*
+ * Evaluates an expression in an abstract environment, i.e., an abstract
+ * domain that maps variables to abstract values via a get() method, such as
+ * HashedAbstractEnvironment. The expression language is described by an
+ * interface with the following layout:
+ *
+ * class ExpressionInterface {
+ *   using Expression = ...;
+ *
+ *   static ExpressionKind kind(const Expression& e);
+ *
+ *   // The following are only called on expressions of the matching kind.
+ *   static Literal literal(const Expression& e);
+ *   static Variable variable(const Expression& e);
+ *   static UnaryOperator unary_operator(const Expression& e);
+ *   static const Expression& operand(const Expression& e);
+ *   static BinaryOperator binary_operator(const Expression& e);
+ *   static const Expression& left(const Expression& e);
+ *   static const Expression& right(const Expression& e);
+ * };
+ *
+ * The operators are interpreted by the ArithmeticDomainOps and
+ * CompareDomainOps of the domain (see NumericalDomainOps.h). As in C, a
+ * comparison evaluates to 1 when it holds and to 0 otherwise.
+ *
+ * The evaluation is compositional: the value of an expression only depends on
+ * the values of its subexpressions. In particular, it does not take into
+ * account the correlations between the occurrences of a variable, e.g., x - x
+ * is not necessarily evaluated to 0.
+ */
+template <typename ExpressionInterface,
+          typename Domain,
+          typename Environment,
+          typename Ops = ArithmeticDomainOps<Domain>,
+          typename CompareOps = CompareDomainOps<Domain>>
+Domain evaluate(const typename ExpressionInterface::Expression& e,
+                const Environment& env) {
+  using EI = ExpressionInterface;
+  if (env.is_bottom()) {
+    return Domain::bottom();
+  }
+  switch (EI::kind(e)) {
+  case ExpressionKind::Literal: {
+    return Ops::literal(EI::literal(e));
+  }
+  case ExpressionKind::Variable: {
+    return env.get(EI::variable(e));
+  }
+  case ExpressionKind::Unary: {
+    Domain operand =
+        evaluate<EI, Domain, Environment, Ops, CompareOps>(EI::operand(e), env);
+    switch (EI::unary_operator(e)) {
+    case UnaryOperator::Neg: {
+      return Ops::neg(operand);
+    }
+    }
+    break;
+  }
+  case ExpressionKind::Binary: {
+    Domain x =
+        evaluate<EI, Domain, Environment, Ops, CompareOps>(EI::left(e), env);
+    Domain y =
+        evaluate<EI, Domain, Environment, Ops, CompareOps>(EI::right(e), env);
+    switch (EI::binary_operator(e)) {
+    case BinaryOperator::Add: {
+      return Ops::add(x, y);
+    }
+    case BinaryOperator::Sub: {
+      return Ops::sub(x, y);
+    }
+    case BinaryOperator::Mul: {
+      return Ops::mul(x, y);
+    }
+    case BinaryOperator::Div: {
+      return Ops::div(x, y);
+    }
+    case BinaryOperator::And: {
+      return Ops::bit_and(x, y);
+    }
+    case BinaryOperator::Or: {
+      return Ops::bit_or(x, y);
+    }
+    case BinaryOperator::Xor: {
+      return Ops::bit_xor(x, y);
+    }
+    case BinaryOperator::Shl: {
+      return Ops::shl(x, y);
+    }
+    case BinaryOperator::Shr: {
+      return Ops::shr(x, y);
+    }
+    default: {
+      return ev_impl::to_number<Domain, Ops>(
+          ev_impl::compare<CompareOps>(EI::binary_operator(e), x, y));
+    }
+    }
+  }
+  }
+  return Domain::top();
+}
+
+} // namespace sparta
@@ -0,0 +1,279 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <atomic>
+#include <chrono>
+#include <cstdint>
+#include <ostream>
+#include <type_traits>
+#include <utility>
+
+#include <boost/variant.hpp>
+
+#include "AbstractDomain.h"
+#include "Exceptions.h"
+
+namespace sparta {
+
+/*
+ * An abstract domain that computes with a precise but expensive domain until
+ * a budget is exhausted, and then soundly falls back to a coarser domain for
+ * the remainder of the analysis. This provides a middle ground between running
+ * the whole analysis with full precision and aborting it when it takes too
+ * much time or memory.
+ *
+ * The budget and the conversion between the domains are specified by a policy
+ * that should have the following layout:
+ *
+ * struct Policy {
+ *   // Returns an abstract value of the coarse domain that over-approximates
+ *   // the given precise value, i.e., whose concretization includes the one of
+ *   // the precise value. This must map Bottom to Bottom and Top to Top.
+ *   static Coarse coarsen(const Precise& x);
+ *
+ *   // Returns true if the analysis should stop using the precise domain. This
+ *   // is checked whenever an operation produces a precise value, e.g., by
+ *   // comparing the size of the value against a fixed bound, or by checking
+ *   // a FallbackDeadline.
+ *   static bool exceeds_budget(const Precise& x);
+ * };
+ *
+ * An abstract value holds a value of either domain. An operation on a precise
+ * value and a coarse value first coarsens the precise value, then performs the
+ * operation in the coarse domain. Since a value can only be coarsened once,
+ * the widening enforces termination as long as the widenings of both domains
+ * do.
+ *
+ * The order between values of different domains is conservative: a precise
+ * value is below a coarse value if its coarsening is, whereas a coarse value
+ * is only below a precise value if the former is Bottom or the latter is Top.
+ */
+template <typename Precise, typename Coarse, typename Policy>
+class FallbackDomain final
+    : public AbstractDomain<FallbackDomain<Precise, Coarse, Policy>> {
+ public:
+  ~FallbackDomain() {
+    static_assert(std::is_base_of<AbstractDomain<Precise>, Precise>::value,
+                  "Precise does not inherit from AbstractDomain");
+    static_assert(std::is_base_of<AbstractDomain<Coarse>, Coarse>::value,
+                  "Coarse does not inherit from AbstractDomain");
+    static_assert(
+        std::is_same<decltype(Policy::coarsen(std::declval<Precise>())),
+                     Coarse>::value,
+        "Policy::coarsen() does not exist");
+    static_assert(
+        std::is_same<decltype(Policy::exceeds_budget(std::declval<Precise>())),
+                     bool>::value,
+        "Policy::exceeds_budget() does not exist");
+  }
+
+  /*
+   * The default constructor produces the default value of the precise domain.
+   */
+  FallbackDomain() : FallbackDomain(Precise()) {}
+
+  /*
+   * The value is coarsened if it exceeds the budget.
+   */
+  explicit FallbackDomain(Precise precise) : m_value(std::move(precise)) {
+    enforce_budget();
+  }
+
+  explicit FallbackDomain(Coarse coarse) : m_value(std::move(coarse)) {}
+
+  static FallbackDomain bottom() { return FallbackDomain(Precise::bottom()); }
+
+  static FallbackDomain top() { return FallbackDomain(Precise::top()); }
+
+  bool is_precise() const { return m_value.which() == 0; }
+
+  bool is_coarse() const { return m_value.which() == 1; }
+
+  /*
+   * Throws undefined_operation if the value has been coarsened.
+   */
+  const Precise& precise() const {
+    RUNTIME_CHECK(is_precise(), undefined_operation());
+    return boost::get<Precise>(m_value);
+  }
+
+  /*
+   * Returns the value in the coarse domain, coarsening it if needed.
+   */
+  Coarse coarse() const {
+    return is_precise() ? Policy::coarsen(boost::get<Precise>(m_value))
+                        : boost::get<Coarse>(m_value);
+  }
+
+  /*
+   * Applies a transformer to the value, using the operation that corresponds
+   * to the domain it currently belongs to. The result is coarsened if it
+   * exceeds the budget.
+   */
+  template <typename PreciseOperation, typename CoarseOperation>
+  FallbackDomain& apply(PreciseOperation&& precise_operation,
+                        CoarseOperation&& coarse_operation) {
+    if (is_precise()) {
+      precise_operation(&boost::get<Precise>(m_value));
+      enforce_budget();
+    } else {
+      coarse_operation(&boost::get<Coarse>(m_value));
+    }
+    return *this;
+  }
+
+  /*
+   * Moves the value to the coarse domain, regardless of the budget.
+   */
+  void coarsen() {
+    if (is_precise()) {
+      m_value = Policy::coarsen(boost::get<Precise>(m_value));
+    }
+  }
+
+  bool is_bottom() const override {
+    return is_precise() ? boost::get<Precise>(m_value).is_bottom()
+                        : boost::get<Coarse>(m_value).is_bottom();
+  }
+
+  bool is_top() const override {
+    return is_precise() ? boost::get<Precise>(m_value).is_top()
+                        : boost::get<Coarse>(m_value).is_top();
+  }
+
+  void set_to_bottom() override { m_value = Precise::bottom(); }
+
+  void set_to_top() override { m_value = Precise::top(); }
+
+  bool leq(const FallbackDomain& other) const override {
+    if (is_precise() && other.is_precise()) {
+      return boost::get<Precise>(m_value).leq(
+          boost::get<Precise>(other.m_value));
+    }
+    if (is_coarse() && other.is_precise()) {
+      return is_bottom() || other.is_top();
+    }
+    return coarse().leq(boost::get<Coarse>(other.m_value));
+  }
+
+  bool equals(const FallbackDomain& other) const override {
+    if (is_precise() && other.is_precise()) {
+      return boost::get<Precise>(m_value).equals(
+          boost::get<Precise>(other.m_value));
+    }
+    if (is_coarse() && other.is_coarse()) {
+      return boost::get<Coarse>(m_value).equals(
+          boost::get<Coarse>(other.m_value));
+    }
+    return (is_bottom() && other.is_bottom()) || (is_top() && other.is_top());
+  }
+
+  void join_with(const FallbackDomain& other) override {
+    binary_operation(
+        other,
+        [](Precise* x, const Precise& y) { x->join_with(y); },
+        [](Coarse* x, const Coarse& y) { x->join_with(y); });
+  }
+
+  void widen_with(const FallbackDomain& other) override {
+    binary_operation(
+        other,
+        [](Precise* x, const Precise& y) { x->widen_with(y); },
+        [](Coarse* x, const Coarse& y) { x->widen_with(y); });
+  }
+
+  void meet_with(const FallbackDomain& other) override {
+    binary_operation(
+        other,
+        [](Precise* x, const Precise& y) { x->meet_with(y); },
+        [](Coarse* x, const Coarse& y) { x->meet_with(y); });
+  }
+
+  void narrow_with(const FallbackDomain& other) override {
+    binary_operation(
+        other,
+        [](Precise* x, const Precise& y) { x->narrow_with(y); },
+        [](Coarse* x, const Coarse& y) { x->narrow_with(y); });
+  }
+
+  friend std::ostream& operator<<(std::ostream& o, const FallbackDomain& x) {
+    if (x.is_precise()) {
+      o << boost::get<Precise>(x.m_value);
+    } else {
+      o << "~" << boost::get<Coarse>(x.m_value);
+    }
+    return o;
+  }
+
+ private:
+  template <typename PreciseOperation, typename CoarseOperation>
+  void binary_operation(const FallbackDomain& other,
+                        PreciseOperation&& precise_operation,
+                        CoarseOperation&& coarse_operation) {
+    if (is_precise() && other.is_precise()) {
+      precise_operation(&boost::get<Precise>(m_value),
+                        boost::get<Precise>(other.m_value));
+      enforce_budget();
+      return;
+    }
+    coarsen();
+    coarse_operation(&boost::get<Coarse>(m_value), other.coarse());
+  }
+
+  void enforce_budget() {
+    if (Policy::exceeds_budget(boost::get<Precise>(m_value))) {
+      coarsen();
+    }
+  }
+
+  boost::variant<Precise, Coarse> m_value;
+};
+
+/*
+ * A wall-clock budget that can be shared by all the abstract values of an
+ * analysis, in order to implement FallbackDomain policies such as:
+ *
+ *   struct Policy {
+ *     static FallbackDeadline deadline;
+ *
+ *     static bool exceeds_budget(const Precise&) {
+ *       return deadline.expired();
+ *     }
+ *     ...
+ *   };
+ *
+ *   Policy::deadline.start(std::chrono::seconds(10));
+ *   fixpoint_iterator.run(...);
+ *
+ * The deadline never expires until it is started. It is safe to check the
+ * deadline concurrently.
+ */
+class FallbackDeadline final {
+ public:
+  using Clock = std::chrono::steady_clock;
+
+  void start(Clock::duration budget) {
+    m_deadline = (Clock::now() + budget).time_since_epoch().count();
+  }
+
+  void reset() { m_deadline = NEVER; }
+
+  bool expired() const {
+    auto deadline = m_deadline.load();
+    return deadline != NEVER &&
+           Clock::now().time_since_epoch().count() >= deadline;
+  }
+
+ private:
+  static constexpr Clock::rep NEVER = 0;
+
+  std::atomic<Clock::rep> m_deadline{NEVER};
+};
+
+} // namespace sparta
@@ -0,0 +1,113 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <unordered_set>
+#include <vector>
+
+namespace sparta {
+
+/*
+ * A 64-bit content hash used to validate cached artifacts cheaply, e.g., a
+ * weak partial ordering, a checkpoint of a fixpoint iteration or a memoized
+ * summary: the artifact is stored along with the fingerprint of its inputs,
+ * and it is reused only if the fingerprint of the current inputs is the same.
+ *
+ * Values are added either in sequence, for the parts whose order matters, or
+ * as a multiset, for the parts whose order is an artifact of the
+ * representation, e.g., the iteration order of a hashtable:
+ *
+ *   Fingerprint fp;
+ *   fp.add(kind);
+ *   for (const auto& binding : table) {
+ *     fp.add_unordered(Fingerprint().add(hash(binding.first))
+ *                          .add(hash(binding.second))
+ *                          .value());
+ *   }
+ *   uint64_t key = fp.value();
+ *
+ * The fingerprint is stable across runs and processes as long as the hashes
+ * of the values are, which is the case of std::hash on integers but not on
+ * pointers. Fingerprints are not cryptographic: they detect accidental
+ * changes, not forgeries.
+ */
+class Fingerprint final {
+ public:
+  Fingerprint() = default;
+
+  /*
+   * A bijective mixing function with good avalanche, the finalizer of
+   * SplitMix64.
+   */
+  static uint64_t mix(uint64_t x) {
+    x += 0x9e3779b97f4a7c15ULL;
+    x = (x ^ (x >> 30)) * 0xbf58476d1ce4e5b9ULL;
+    x = (x ^ (x >> 27)) * 0x94d049bb133111ebULL;
+    return x ^ (x >> 31);
+  }
+
+  /*
+   * Adds a value after all the values added so far.
+   */
+  Fingerprint& add(uint64_t x) {
+    m_sequence = mix(m_sequence ^ mix(x));
+    return *this;
+  }
+
+  /*
+   * Adds a value to the multiset of unordered values, whose contribution
+   * doesn't depend on the order in which they are added.
+   */
+  Fingerprint& add_unordered(uint64_t x) {
+    m_multiset += mix(x);
+    return *this;
+  }
+
+  uint64_t value() const { return mix(m_sequence ^ mix(m_multiset)); }
+
+ private:
+  uint64_t m_sequence{0};
+  uint64_t m_multiset{0};
+};
+
+/*
+ * The fingerprint of the part of a graph that is reachable from its entry,
+ * i.e., the part that a fixpoint iterator explores (see FixpointIterator.h
+ * for the layout of a graph interface). The nodes are identified by their
+ * hashes and the graph by its entry and its multiset of edges, hence the
+ * result doesn't depend on the order in which the successors of a node are
+ * enumerated, nor on the identifiers of the edges.
+ */
+template <typename GraphInterface,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+uint64_t graph_fingerprint(const typename GraphInterface::Graph& graph) {
+  using NodeId = typename GraphInterface::NodeId;
+  NodeHash hash;
+  Fingerprint fp;
+  NodeId entry = GraphInterface::entry(graph);
+  fp.add(hash(entry));
+  std::unordered_set<NodeId, NodeHash> visited{entry};
+  std::vector<NodeId> stack{entry};
+  while (!stack.empty()) {
+    NodeId node = stack.back();
+    stack.pop_back();
+    for (const auto& edge : GraphInterface::successors(graph, node)) {
+      NodeId target = GraphInterface::target(graph, edge);
+      fp.add_unordered(Fingerprint().add(hash(node)).add(hash(target)).value());
+      if (visited.insert(target).second) {
+        stack.push_back(target);
+      }
+    }
+  }
+  return fp.value();
+}
+
+} // namespace sparta
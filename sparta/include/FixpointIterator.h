@@ -39,8 +39,26 @@ namespace sparta {
  *  // requirement is that it must define a standard iterator interface.
  *  static Edges predecessors(const Graph& graph, const NodeId& m) { ... }
  *  static Edges successors(const Graph& graph, const NodeId& m) { ... }
+ *
+ *  // Optional. An estimate of the number of nodes in the graph, which doesn't
+ *  // need to be exact (see graph_size_hint() in MonotonicFixpointIterator.h).
+ *  static size_t size_hint(const Graph& graph) { ... }
  * }
  *
+ * The fixpoint iterators only query the nodes that are reachable from the
+ * entry, hence the graph may be materialized on demand. An intermediate
+ * representation that only records the successors of its basic blocks can be
+ * adapted with SuccessorGraph (see SuccessorGraph.h).
+ *
+ * NodeId must be copyable, equality comparable and hashable (the hash function
+ * is a template parameter of the fixpoint iterators, which defaults to
+ * std::hash<NodeId>). The hash function is used by all the tables indexed by
+ * nodes, e.g., the abstract states and the iteration counters, hence a cheaper
+ * hash function than the default one can speed up the iteration, and NodeId
+ * doesn't need a specialization of std::hash if one is provided.
+ * Strongly-typed identifiers defined with StrongId (see StrongId.h) satisfy
+ * all these requirements, and can also be used as the variables of a
+ * PatriciaTreeMapAbstractEnvironment.
  */
 template <typename GraphInterface, typename Domain>
 class FixpointIterator {
@@ -0,0 +1,300 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <cstddef>
+#include <cstdint>
+#include <istream>
+#include <mutex>
+#include <ostream>
+#include <string>
+#include <utility>
+#include <vector>
+
+#include "Exceptions.h"
+#include "NodeInfo.h"
+#include "WeakPartialOrdering.h"
+
+namespace sparta {
+
+/*
+ * A step of a fixpoint iteration over a weak partial ordering (WPO). Each
+ * step processes one node of the WPO, which is identified by its index.
+ */
+struct FixpointTraceEvent {
+  enum class Kind : uint8_t {
+    // A plain node or the head of a component has been analyzed. The digest
+    // is the one of the exit state of the node.
+    Analyze = 0,
+    // The exit of a component has been processed and the component has
+    // stabilized. The digest is the one of the entry state of the head.
+    Stabilize = 1,
+    // The exit of a component has been processed, the component has not
+    // stabilized yet and the entry state of the head has been extrapolated.
+    // The digest is the one of the extrapolated entry state of the head.
+    Extrapolate = 2,
+  };
+
+  Kind kind;
+  uint32_t wpo_idx;
+  uint64_t digest;
+};
+
+inline bool operator==(const FixpointTraceEvent& x,
+                       const FixpointTraceEvent& y) {
+  return x.kind == y.kind && x.wpo_idx == y.wpo_idx && x.digest == y.digest;
+}
+
+inline bool operator!=(const FixpointTraceEvent& x,
+                       const FixpointTraceEvent& y) {
+  return !(x == y);
+}
+
+/*
+ * A trace of a fixpoint iteration over a WPO, which records the WPO itself
+ * and the sequence of nodes processed by the iteration, along with digests of
+ * the abstract states computed at each step. The order of the events is the
+ * order in which the processing of the nodes started. In a concurrent
+ * iteration, this order is nondeterministic, but it is always a valid
+ * sequential schedule of the WPO. Hence, the trace of a failing concurrent run
+ * can be replayed deterministically by MonotonicFixpointIterator::replay().
+ *
+ * Traces are saved in a compact binary format, in which all integers are
+ * stored in little-endian order:
+ *
+ *   magic       8 bytes "SPARTATR"
+ *   version     u32
+ *   num_nodes   u32
+ *   nodes       num_nodes times:
+ *                 type          u8 (0: plain, 1: head, 2: exit)
+ *                 label         u32 length, followed by the characters
+ *                 successors    u32 count, followed by u32 indices
+ *                 outer_preds   u32 count, followed by (u32 index, u32 count)
+ *   num_events  u64
+ *   events      num_events times: u8 kind, u32 index, u64 digest
+ *
+ * The labels are the descriptions of the nodes provided by a NodeInfo, if any.
+ * They are only meant for human consumption.
+ */
+class FixpointTrace final {
+ public:
+  static constexpr uint32_t VERSION = 1;
+
+  struct Node {
+    enum class Type : uint8_t { Plain = 0, Head = 1, Exit = 2 };
+
+    Type type;
+    std::string label;
+    std::vector<uint32_t> successors;
+    // The number of outer predecessors of the nodes in the component of an
+    // exit node, sorted by index. See WeakPartialOrdering.h.
+    std::vector<std::pair<uint32_t, uint32_t>> num_outer_preds;
+  };
+
+  FixpointTrace() = default;
+
+  FixpointTrace(const FixpointTrace& other)
+      : m_nodes(other.m_nodes), m_events(other.m_events) {}
+
+  FixpointTrace& operator=(const FixpointTrace& other) {
+    m_nodes = other.m_nodes;
+    m_events = other.m_events;
+    return *this;
+  }
+
+  /*
+   * Records the structure of the WPO and discards all the events. The labels
+   * of the nodes are left empty if no node information is provided.
+   */
+  template <typename NodeId, typename NodeHash>
+  void set_wpo(const WeakPartialOrdering<NodeId, NodeHash>& wpo,
+               const NodeInfo<NodeId>* node_info = nullptr) {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    m_nodes.clear();
+    m_events.clear();
+    for (uint32_t idx = 0; idx < wpo.size(); ++idx) {
+      Node node;
+      if (wpo.is_head(idx)) {
+        node.type = Node::Type::Head;
+      } else if (wpo.is_exit(idx)) {
+        node.type = Node::Type::Exit;
+        node.num_outer_preds.assign(wpo.get_num_outer_preds(idx).begin(),
+                                    wpo.get_num_outer_preds(idx).end());
+        std::sort(node.num_outer_preds.begin(), node.num_outer_preds.end());
+      } else {
+        node.type = Node::Type::Plain;
+      }
+      if (node_info != nullptr) {
+        node.label = node_info->describe(wpo.get_node(idx));
+      }
+      node.successors.assign(wpo.get_successors(idx).begin(),
+                             wpo.get_successors(idx).end());
+      m_nodes.push_back(std::move(node));
+    }
+  }
+
+  /*
+   * Returns true if the trace has been recorded on the given WPO, ignoring
+   * the labels of the nodes.
+   */
+  template <typename NodeId, typename NodeHash>
+  bool matches(const WeakPartialOrdering<NodeId, NodeHash>& wpo) const {
+    FixpointTrace other;
+    other.set_wpo(wpo);
+    if (m_nodes.size() != other.m_nodes.size()) {
+      return false;
+    }
+    for (size_t idx = 0; idx < m_nodes.size(); ++idx) {
+      const auto& x = m_nodes[idx];
+      const auto& y = other.m_nodes[idx];
+      if (x.type != y.type || x.successors != y.successors ||
+          x.num_outer_preds != y.num_outer_preds) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  /*
+   * Reserves a slot for an event at the beginning of a step. This is
+   * thread-safe.
+   */
+  size_t begin_event(uint32_t wpo_idx) {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    m_events.push_back(
+        FixpointTraceEvent{FixpointTraceEvent::Kind::Analyze, wpo_idx, 0});
+    return m_events.size() - 1;
+  }
+
+  /*
+   * Completes the event recorded in the given slot. This is thread-safe.
+   */
+  void end_event(size_t slot, FixpointTraceEvent::Kind kind, uint64_t digest) {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    m_events[slot].kind = kind;
+    m_events[slot].digest = digest;
+  }
+
+  const std::vector<Node>& nodes() const { return m_nodes; }
+
+  const std::vector<FixpointTraceEvent>& events() const { return m_events; }
+
+  void write(std::ostream& o) const {
+    o.write(MAGIC, sizeof(MAGIC));
+    write_integer<uint32_t>(o, VERSION);
+    write_integer<uint32_t>(o, m_nodes.size());
+    for (const auto& node : m_nodes) {
+      write_integer<uint8_t>(o, static_cast<uint8_t>(node.type));
+      write_integer<uint32_t>(o, node.label.size());
+      o.write(node.label.data(), node.label.size());
+      write_integer<uint32_t>(o, node.successors.size());
+      for (auto succ : node.successors) {
+        write_integer<uint32_t>(o, succ);
+      }
+      write_integer<uint32_t>(o, node.num_outer_preds.size());
+      for (const auto& pred : node.num_outer_preds) {
+        write_integer<uint32_t>(o, pred.first);
+        write_integer<uint32_t>(o, pred.second);
+      }
+    }
+    write_integer<uint64_t>(o, m_events.size());
+    for (const auto& event : m_events) {
+      write_integer<uint8_t>(o, static_cast<uint8_t>(event.kind));
+      write_integer<uint32_t>(o, event.wpo_idx);
+      write_integer<uint64_t>(o, event.digest);
+    }
+  }
+
+  /*
+   * Throws invalid_argument if the input is not a well-formed trace.
+   */
+  static FixpointTrace read(std::istream& i) {
+    char magic[sizeof(MAGIC)];
+    i.read(magic, sizeof(magic));
+    RUNTIME_CHECK(i && std::equal(magic, magic + sizeof(magic), MAGIC),
+                  invalid_argument() << error_msg("Not a fixpoint trace"));
+    uint32_t version = read_integer<uint32_t>(i);
+    RUNTIME_CHECK(version == VERSION,
+                  invalid_argument()
+                      << error_msg("Unsupported fixpoint trace version"));
+    FixpointTrace trace;
+    uint32_t num_nodes = read_integer<uint32_t>(i);
+    for (uint32_t idx = 0; idx < num_nodes; ++idx) {
+      Node node;
+      uint8_t type = read_integer<uint8_t>(i);
+      RUNTIME_CHECK(type <= static_cast<uint8_t>(Node::Type::Exit),
+                    invalid_argument() << error_msg("Invalid node type"));
+      node.type = static_cast<Node::Type>(type);
+      node.label.resize(read_integer<uint32_t>(i));
+      i.read(&node.label[0], node.label.size());
+      RUNTIME_CHECK(i,
+                    invalid_argument()
+                        << error_msg("Unexpected end of fixpoint trace"));
+      uint32_t num_successors = read_integer<uint32_t>(i);
+      for (uint32_t k = 0; k < num_successors; ++k) {
+        node.successors.push_back(read_index(i, num_nodes));
+      }
+      uint32_t num_outer_preds = read_integer<uint32_t>(i);
+      for (uint32_t k = 0; k < num_outer_preds; ++k) {
+        uint32_t pred = read_index(i, num_nodes);
+        node.num_outer_preds.emplace_back(pred, read_integer<uint32_t>(i));
+      }
+      trace.m_nodes.push_back(std::move(node));
+    }
+    uint64_t num_events = read_integer<uint64_t>(i);
+    for (uint64_t k = 0; k < num_events; ++k) {
+      uint8_t kind = read_integer<uint8_t>(i);
+      RUNTIME_CHECK(
+          kind <= static_cast<uint8_t>(FixpointTraceEvent::Kind::Extrapolate),
+          invalid_argument() << error_msg("Invalid event kind"));
+      uint32_t wpo_idx = read_index(i, num_nodes);
+      uint64_t digest = read_integer<uint64_t>(i);
+      trace.m_events.push_back(FixpointTraceEvent{
+          static_cast<FixpointTraceEvent::Kind>(kind), wpo_idx, digest});
+    }
+    return trace;
+  }
+
+ private:
+  static constexpr char MAGIC[8] = {'S', 'P', 'A', 'R', 'T', 'A', 'T', 'R'};
+
+  template <typename Integer>
+  static void write_integer(std::ostream& o, Integer value) {
+    for (size_t k = 0; k < sizeof(Integer); ++k) {
+      uint64_t byte = (static_cast<uint64_t>(value) >> (8 * k)) & 0xff;
+      o.put(static_cast<char>(byte));
+    }
+  }
+
+  template <typename Integer>
+  static Integer read_integer(std::istream& i) {
+    uint64_t value = 0;
+    for (size_t k = 0; k < sizeof(Integer); ++k) {
+      int byte = i.get();
+      RUNTIME_CHECK(byte != std::char_traits<char>::eof(),
+                    invalid_argument()
+                        << error_msg("Unexpected end of fixpoint trace"));
+      value |= static_cast<uint64_t>(byte & 0xff) << (8 * k);
+    }
+    return static_cast<Integer>(value);
+  }
+
+  static uint32_t read_index(std::istream& i, uint32_t num_nodes) {
+    uint32_t idx = read_integer<uint32_t>(i);
+    RUNTIME_CHECK(idx < num_nodes,
+                  invalid_argument() << error_msg("Invalid WPO index"));
+    return idx;
+  }
+
+  std::vector<Node> m_nodes;
+  std::vector<FixpointTraceEvent> m_events;
+  std::mutex m_mutex;
+};
+
+} // namespace sparta
@@ -8,12 +8,14 @@
 #pragma once
 
 #include <algorithm>
+#include <boost/optional.hpp>
 #include <functional>
 #include <initializer_list>
 #include <limits>
 #include <ostream>
 #include <vector>
 
+#include "Exceptions.h"
 #include "PatriciaTreeUtil.h"
 
 namespace sparta {
@@ -24,14 +26,24 @@ namespace sparta {
  * It is similar to `boost::container::flat_set` but provides set operations
  * such as union, intersection and difference, using the same interface as
  * `PatriciaTreeSet`.
+ *
+ * The elements of the vector are sorted without duplicates. When
+ * SPARTA_CHECK_INVARIANTS is defined (see Exceptions.h), this is verified after
+ * every set operation.
+ *
+ * The vector type can be replaced by any sequence container with random access
+ * iterators, e.g., a vector that stores a few elements inline such as
+ * boost::container::small_vector, which avoids the heap allocation for the
+ * small sets that are common in practice.
  */
 template <typename Element,
           typename Compare = std::less<Element>,
-          typename Equal = std::equal_to<Element>>
+          typename Equal = std::equal_to<Element>,
+          typename Vector = std::vector<Element>>
 class FlatSet final {
  public:
   // C++ container concept member types
-  using iterator = typename std::vector<Element>::const_iterator;
+  using iterator = typename Vector::const_iterator;
   using const_iterator = iterator;
   using value_type = Element;
   using difference_type = std::ptrdiff_t;
@@ -54,6 +66,19 @@ class FlatSet final {
     }
   }
 
+  /*
+   * Builds a set from a vector in linear time. Returns none if the elements of
+   * the vector are not sorted or contain duplicates.
+   */
+  static boost::optional<FlatSet> try_from_sorted(Vector v) {
+    if (!is_normalized(v)) {
+      return boost::none;
+    }
+    FlatSet result;
+    result.m_vector = std::move(v);
+    return result;
+  }
+
   bool empty() const { return m_vector.empty(); }
 
   std::size_t size() const { return m_vector.size(); }
@@ -124,6 +149,7 @@ class FlatSet final {
         std::remove_if(m_vector.begin(), m_vector.end(),
                        [&](const Element& e) { return !predicate(e); }),
         m_vector.end());
+    check_invariants();
     return *this;
   }
 
@@ -139,6 +165,7 @@ class FlatSet final {
       ++it;
       ++other_it;
     }
+    check_invariants();
     return *this;
   }
 
@@ -159,6 +186,7 @@ class FlatSet final {
       ++it;
     }
     m_vector.erase(first, end);
+    check_invariants();
     return *this;
   }
 
@@ -167,13 +195,13 @@ class FlatSet final {
     auto it = m_vector.begin();
     auto other_it = other.m_vector.begin(), other_end = other.m_vector.end();
     while (other_it != other_end) {
-      it = std::lower_bound(it, m_vector.end(), *other_it);
+      it = std::lower_bound(it, m_vector.end(), *other_it, Compare());
       if (it != m_vector.end() && Equal()(*it, *other_it)) {
         it = m_vector.erase(it);
-        ++it;
       }
       ++other_it;
     }
+    check_invariants();
     return *this;
   }
 
@@ -209,7 +237,7 @@ class FlatSet final {
 
   void clear() { m_vector.clear(); }
 
-  friend std::ostream& operator<<(std::ostream& o, const FlatSet<Element>& s) {
+  friend std::ostream& operator<<(std::ostream& o, const FlatSet& s) {
     o << "{";
     for (auto it = s.begin(), end = s.end(); it != end;) {
       o << pt_util::Dereference<Element>()(*it);
@@ -223,7 +251,16 @@ class FlatSet final {
   }
 
  private:
-  std::vector<Element> m_vector;
+  static bool is_normalized(const Vector& v) {
+    return std::adjacent_find(v.begin(), v.end(),
+                              [](const Element& x, const Element& y) {
+                                return !Compare()(x, y);
+                              }) == v.end();
+  }
+
+  void check_invariants() const { SPARTA_INVARIANT(is_normalized(m_vector)); }
+
+  Vector m_vector;
 };
 
 } // namespace sparta
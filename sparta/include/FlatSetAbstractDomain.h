@@ -0,0 +1,161 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <functional>
+#include <initializer_list>
+#include <ostream>
+#include <vector>
+
+#include "FlatSet.h"
+#include "PowersetAbstractDomain.h"
+
+namespace sparta {
+
+// Forward declaration.
+template <typename Element, typename Compare, typename Equal, typename Vector>
+class FlatSetAbstractDomain;
+
+namespace fsad_impl {
+
+/*
+ * An abstract value from a powerset is implemented as a sorted vector.
+ */
+template <typename Element, typename Compare, typename Equal, typename Vector>
+class SetValue final
+    : public PowersetImplementation<
+          Element,
+          const FlatSet<Element, Compare, Equal, Vector>&,
+          SetValue<Element, Compare, Equal, Vector>> {
+ public:
+  using Set = FlatSet<Element, Compare, Equal, Vector>;
+
+  SetValue() = default;
+
+  SetValue(const Element& e) { m_set.insert(e); }
+
+  SetValue(std::initializer_list<Element> l) : m_set(l.begin(), l.end()) {}
+
+  SetValue(Set set) : m_set(std::move(set)) {}
+
+  const Set& elements() const override { return m_set; }
+
+  size_t size() const override { return m_set.size(); }
+
+  bool contains(const Element& e) const override { return m_set.contains(e); }
+
+  void add(const Element& e) override { m_set.insert(e); }
+
+  void remove(const Element& e) override { m_set.remove(e); }
+
+  void clear() override { m_set.clear(); }
+
+  AbstractValueKind kind() const override { return AbstractValueKind::Value; }
+
+  bool leq(const SetValue& other) const override {
+    return m_set.is_subset_of(other.m_set);
+  }
+
+  bool equals(const SetValue& other) const override {
+    return m_set.equals(other.m_set);
+  }
+
+  AbstractValueKind join_with(const SetValue& other) override {
+    m_set.union_with(other.m_set);
+    return AbstractValueKind::Value;
+  }
+
+  AbstractValueKind meet_with(const SetValue& other) override {
+    m_set.intersection_with(other.m_set);
+    return AbstractValueKind::Value;
+  }
+
+  AbstractValueKind difference_with(const SetValue& other) override {
+    m_set.difference_with(other.m_set);
+    return AbstractValueKind::Value;
+  }
+
+  friend std::ostream& operator<<(std::ostream& o, const SetValue& value) {
+    return o << "[#" << value.size() << "]" << value.m_set;
+  }
+
+ private:
+  Set m_set;
+
+  template <typename T1, typename T2, typename T3, typename T4>
+  friend class sparta::FlatSetAbstractDomain;
+};
+
+} // namespace fsad_impl
+
+/*
+ * An implementation of powerset abstract domains using sorted vectors (see
+ * FlatSet.h). Unlike HashedSetAbstractDomain, the elements don't need to be
+ * hashable, and the sets of a few elements, e.g., the sets of types of a
+ * register, are much cheaper to copy, compare and join than hash tables.
+ * The operations are linear in the size of the sets, hence
+ * PatriciaTreeSetAbstractDomain is preferable for large sets.
+ *
+ * The underlying vector type can be replaced by a vector that stores a few
+ * elements inline, e.g.:
+ *
+ *   using TypeSet = FlatSetAbstractDomain<
+ *       const DexType*,
+ *       std::less<const DexType*>,
+ *       std::equal_to<const DexType*>,
+ *       boost::container::small_vector<const DexType*, 4>>;
+ */
+template <typename Element,
+          typename Compare = std::less<Element>,
+          typename Equal = std::equal_to<Element>,
+          typename Vector = std::vector<Element>>
+class FlatSetAbstractDomain final
+    : public PowersetAbstractDomain<
+          Element,
+          fsad_impl::SetValue<Element, Compare, Equal, Vector>,
+          const FlatSet<Element, Compare, Equal, Vector>&,
+          FlatSetAbstractDomain<Element, Compare, Equal, Vector>> {
+ public:
+  using Value = fsad_impl::SetValue<Element, Compare, Equal, Vector>;
+  using Set = FlatSet<Element, Compare, Equal, Vector>;
+
+  FlatSetAbstractDomain()
+      : PowersetAbstractDomain<Element,
+                               Value,
+                               const Set&,
+                               FlatSetAbstractDomain>() {}
+
+  FlatSetAbstractDomain(AbstractValueKind kind)
+      : PowersetAbstractDomain<Element,
+                               Value,
+                               const Set&,
+                               FlatSetAbstractDomain>(kind) {}
+
+  explicit FlatSetAbstractDomain(const Element& e) {
+    this->set_to_value(Value(e));
+  }
+
+  explicit FlatSetAbstractDomain(std::initializer_list<Element> l) {
+    this->set_to_value(Value(l));
+  }
+
+  explicit FlatSetAbstractDomain(Set set) {
+    this->set_to_value(Value(std::move(set)));
+  }
+
+  static FlatSetAbstractDomain bottom() {
+    return FlatSetAbstractDomain(AbstractValueKind::Bottom);
+  }
+
+  static FlatSetAbstractDomain top() {
+    return FlatSetAbstractDomain(AbstractValueKind::Top);
+  }
+};
+
+} // namespace sparta
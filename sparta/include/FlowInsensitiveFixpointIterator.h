@@ -0,0 +1,135 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <unordered_set>
+#include <vector>
+
+#include "FixpointIterator.h"
+#include "MonotonicFixpointIterator.h"
+
+namespace sparta {
+
+/*
+ * A fixpoint iterator that computes a single abstract state for the whole
+ * graph, instead of one state per node. The global state is the least
+ * fixpoint of the equation:
+ *
+ *   G = init U (U_{node} analyze_node(node, G))
+ *            U (U_{edge} analyze_edge(edge, analyze_node(source(edge), G)))
+ *
+ * where the nodes and edges range over the part of the graph that is reachable
+ * from the entry. This is much cheaper than a flow-sensitive analysis, both in
+ * time and memory, and is typically used for whole-program pre-analyses, such
+ * as the computation of the seeds of a points-to analysis on a call graph.
+ *
+ * The transformers have the same interface as for the flow-sensitive fixpoint
+ * iterators (see FixpointIterator), hence the semantics can be shared by
+ * parameterizing the analyzer with the fixpoint iterator:
+ *
+ *   template <template <typename, typename, typename> class Iterator>
+ *   class Analyzer final : public Iterator<CFG, Domain, std::hash<NodeId>> {
+ *     ...
+ *   };
+ *
+ *   Analyzer<MonotonicFixpointIterator> flow_sensitive(cfg);
+ *   Analyzer<FlowInsensitiveFixpointIterator> flow_insensitive(cfg);
+ *
+ * Note that the transformers are always applied to the global state, hence
+ * every update performed by a transformer is effectively a weak update.
+ */
+template <typename GraphInterface,
+          typename Domain,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class FlowInsensitiveFixpointIterator
+    : public FixpointIterator<GraphInterface, Domain> {
+ public:
+  using Graph = typename GraphInterface::Graph;
+  using NodeId = typename GraphInterface::NodeId;
+  using EdgeId = typename GraphInterface::EdgeId;
+
+  explicit FlowInsensitiveFixpointIterator(const Graph& graph)
+      : m_graph(graph) {}
+
+  /*
+   * This method is invoked after each pass over the graph, whenever the newly
+   * computed global state is not subsumed by the current one. The default
+   * strategy applies the join after the first pass and the widening after all
+   * subsequent passes.
+   */
+  virtual void extrapolate(uint32_t pass,
+                           Domain* current_state,
+                           const Domain& new_state) const {
+    if (pass == 0) {
+      current_state->join_with(new_state);
+    } else {
+      current_state->widen_with(new_state);
+    }
+  }
+
+  void run(const Domain& init) {
+    std::vector<NodeId> nodes = reachable_nodes();
+    m_state = init;
+    m_passes = 0;
+    while (true) {
+      Domain new_state = m_state;
+      for (const auto& node : nodes) {
+        Domain exit_state = m_state;
+        this->analyze_node(node, &exit_state);
+        for (const auto& edge : GraphInterface::successors(m_graph, node)) {
+          new_state.join_with(this->analyze_edge(edge, exit_state));
+        }
+        new_state.join_with(exit_state);
+      }
+      ++m_passes;
+      if (new_state.leq(m_state)) {
+        return;
+      }
+      extrapolate(m_passes - 1, &m_state, new_state);
+    }
+  }
+
+  /*
+   * Returns the invariant computed for the whole graph.
+   */
+  const Domain& get_state() const { return m_state; }
+
+  /*
+   * The number of passes over the graph performed by the last run, including
+   * the last one that checks for stabilization.
+   */
+  uint32_t get_number_of_passes() const { return m_passes; }
+
+ private:
+  std::vector<NodeId> reachable_nodes() const {
+    std::vector<NodeId> nodes;
+    std::unordered_set<NodeId, NodeHash> visited(
+        graph_size_hint<GraphInterface>(m_graph));
+    auto entry = GraphInterface::entry(m_graph);
+    visited.insert(entry);
+    nodes.push_back(entry);
+    // The vector doubles as the work list of a breadth-first traversal.
+    for (size_t i = 0; i < nodes.size(); ++i) {
+      for (const auto& edge : GraphInterface::successors(m_graph, nodes[i])) {
+        auto target = GraphInterface::target(m_graph, edge);
+        if (visited.insert(target).second) {
+          nodes.push_back(target);
+        }
+      }
+    }
+    return nodes;
+  }
+
+  const Graph& m_graph;
+  Domain m_state{Domain::bottom()};
+  uint32_t m_passes{0};
+};
+
+} // namespace sparta
@@ -0,0 +1,259 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <functional>
+#include <type_traits>
+#include <unordered_map>
+#include <utility>
+
+#include "BitsetAbstractDomain.h"
+#include "BitsetDomain.h"
+#include "MonotonicFixpointIterator.h"
+
+namespace sparta {
+
+/*
+ * The direction in which a dataflow analysis propagates information along the
+ * edges of a control-flow graph.
+ */
+enum class AnalysisDirection { Forward, Backward };
+
+namespace gk_impl {
+
+template <typename GraphInterface, AnalysisDirection Direction>
+struct OrientedGraph {
+  using type = GraphInterface;
+};
+
+template <typename GraphInterface>
+struct OrientedGraph<GraphInterface, AnalysisDirection::Backward> {
+  using type = BackwardsFixpointIterationAdaptor<GraphInterface>;
+};
+
+/*
+ * Applies the gen and kill facts of a node to an abstract state, one fact at a
+ * time.
+ */
+template <typename Domain, typename NodeId, typename NodeHash>
+class Transformer {
+ public:
+  template <typename Relations>
+  void apply(const Relations& relations,
+             const NodeId& node,
+             Domain* current_state) {
+    for (const auto& fact : relations.kill(node)) {
+      current_state->remove(fact);
+    }
+    for (const auto& fact : relations.gen(node)) {
+      current_state->add(fact);
+    }
+  }
+};
+
+/*
+ * For a domain of bitsets, the gen and kill facts of each node are converted
+ * into bitsets the first time the node is analyzed, so that the transformer
+ * is a couple of word-parallel operations in the subsequent iterations.
+ */
+template <size_t N, typename NodeId, typename NodeHash>
+class Transformer<BitsetAbstractDomain<N>, NodeId, NodeHash> {
+ public:
+  using Domain = BitsetAbstractDomain<N>;
+  using Bits = typename Domain::Bits;
+
+  template <typename Relations>
+  void apply(const Relations& relations,
+             const NodeId& node,
+             Domain* current_state) {
+    auto it = m_gen_kill.find(node);
+    if (it == m_gen_kill.end()) {
+      Bits gen, kill;
+      for (const auto& fact : relations.kill(node)) {
+        kill.set(fact);
+      }
+      for (const auto& fact : relations.gen(node)) {
+        gen.set(fact);
+      }
+      it = m_gen_kill.emplace(node, std::make_pair(gen, kill)).first;
+    }
+    current_state->apply_gen_kill(it->second.first, it->second.second);
+  }
+
+ private:
+  std::unordered_map<NodeId, std::pair<Bits, Bits>, NodeHash> m_gen_kill;
+};
+
+/*
+ * The same precomputation for bitsets of an arbitrary size.
+ */
+template <typename NodeId, typename NodeHash>
+class Transformer<BitsetDomain, NodeId, NodeHash> {
+ public:
+  template <typename Relations>
+  void apply(const Relations& relations,
+             const NodeId& node,
+             BitsetDomain* current_state) {
+    auto it = m_gen_kill.find(node);
+    if (it == m_gen_kill.end()) {
+      const auto& gen = relations.gen(node);
+      const auto& kill = relations.kill(node);
+      BitsetDomain gen_set, kill_set;
+      gen_set.add(gen.begin(), gen.end());
+      kill_set.add(kill.begin(), kill.end());
+      it = m_gen_kill.emplace(node, std::make_pair(gen_set, kill_set)).first;
+    }
+    current_state->apply_gen_kill(it->second.first, it->second.second);
+  }
+
+ private:
+  std::unordered_map<NodeId, std::pair<BitsetDomain, BitsetDomain>, NodeHash>
+      m_gen_kill;
+};
+
+} // namespace gk_impl
+
+/*
+ * The transformer of a gen/kill analysis (see GenKillAnalysis below), for
+ * analyses that need to pick their own fixpoint iterator or to combine the
+ * gen/kill facts with other transformers:
+ *
+ *   class ReachingDefinitions final
+ *       : public WTOMonotonicFixpointIterator<CFG, Definitions> {
+ *    public:
+ *     void analyze_node(const NodeId& node,
+ *                       Definitions* current_state) const override {
+ *       m_transformer.apply(node, current_state);
+ *     }
+ *     ...
+ *    private:
+ *     mutable GenKillTransformer<Definitions, Relations, NodeId>
+ *         m_transformer;
+ *   };
+ *
+ * The transformer caches the gen and kill bitsets of the nodes, hence it must
+ * not be applied to several nodes concurrently. The relations must outlive
+ * the transformer.
+ */
+template <typename Domain,
+          typename Relations,
+          typename NodeId,
+          typename NodeHash = std::hash<NodeId>>
+class GenKillTransformer final {
+ public:
+  explicit GenKillTransformer(const Relations& relations)
+      : m_relations(relations) {}
+
+  void apply(const NodeId& node, Domain* current_state) {
+    m_transformer.apply(m_relations, node, current_state);
+  }
+
+ private:
+  const Relations& m_relations;
+  gk_impl::Transformer<Domain, NodeId, NodeHash> m_transformer;
+};
+
+/*
+ * A classic gen/kill dataflow analysis, in which the transformer of a node is
+ * derived from a declarative description of the facts the node generates and
+ * kills:
+ *
+ *   out = (in \ kill(node)) U gen(node)
+ *
+ * The description is independent from the direction of the analysis, which is
+ * a template parameter. For example, given the variables that each statement
+ * defines and uses:
+ *
+ *   - Liveness is a backward analysis, in which a statement generates the
+ *     variables it uses and kills the variables it defines.
+ *   - Reaching definitions is a forward analysis, in which a statement
+ *     generates its own definitions and kills all the other definitions of the
+ *     variables it defines.
+ *
+ * The relations should have the following layout:
+ *
+ * class Relations {
+ *   // Facts is an arbitrary iterable collection of elements of the domain.
+ *   Facts gen(const NodeId& node) const { ... }
+ *   Facts kill(const NodeId& node) const { ... }
+ * };
+ *
+ * The abstract domain is a powerset domain, such as HashedSetAbstractDomain
+ * or PatriciaTreeSetAbstractDomain. When the facts can be numbered densely
+ * (see FactNumberer), BitsetDomain or BitsetAbstractDomain are much faster:
+ * the gen and kill sets of each node are then precomputed as bitsets, and the
+ * transformers as well as the joins and the inclusion tests operate on whole
+ * machine words. The facts must then be the numbers of the bits.
+ *
+ * Edges have no effect on the facts. For a backward analysis, the graph
+ * interface must provide an exit() method (see
+ * BackwardsFixpointIterationAdaptor). The transformer is also available on its
+ * own as GenKillTransformer.
+ */
+template <typename GraphInterface,
+          typename Domain,
+          typename Relations,
+          AnalysisDirection Direction,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class GenKillAnalysis final
+    : public MonotonicFixpointIterator<
+          typename gk_impl::OrientedGraph<GraphInterface, Direction>::type,
+          Domain,
+          NodeHash> {
+ public:
+  using Base = MonotonicFixpointIterator<
+      typename gk_impl::OrientedGraph<GraphInterface, Direction>::type,
+      Domain,
+      NodeHash>;
+  using Graph = typename GraphInterface::Graph;
+  using NodeId = typename GraphInterface::NodeId;
+  using EdgeId = typename GraphInterface::EdgeId;
+
+  /*
+   * The relations must outlive the analysis.
+   */
+  GenKillAnalysis(const Graph& graph,
+                  const Relations& relations,
+                  size_t cfg_size_hint = 4)
+      : Base(graph, cfg_size_hint), m_transformer(relations) {}
+
+  void analyze_node(const NodeId& node, Domain* current_state) const override {
+    m_transformer.apply(node, current_state);
+  }
+
+  Domain analyze_edge(const EdgeId&,
+                      const Domain& exit_state_at_source) const override {
+    return exit_state_at_source;
+  }
+
+  /*
+   * The facts that hold before the node executes, in the order of the program
+   * regardless of the direction of the analysis. For liveness, these are the
+   * variables that are live on entry to the node.
+   */
+  Domain get_state_before(const NodeId& node) const {
+    return Direction == AnalysisDirection::Forward
+               ? this->get_entry_state_at(node)
+               : this->get_exit_state_at(node);
+  }
+
+  /*
+   * The facts that hold after the node executes, in the order of the program.
+   */
+  Domain get_state_after(const NodeId& node) const {
+    return Direction == AnalysisDirection::Forward
+               ? this->get_exit_state_at(node)
+               : this->get_entry_state_at(node);
+  }
+
+ private:
+  mutable GenKillTransformer<Domain, Relations, NodeId, NodeHash>
+      m_transformer;
+};
+
+} // namespace sparta
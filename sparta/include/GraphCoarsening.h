@@ -0,0 +1,633 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <boost/optional.hpp>
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <limits>
+#include <memory>
+#include <queue>
+#include <unordered_map>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+#include "MonotonicFixpointIterator.h"
+
+namespace sparta {
+
+/*
+ * The fixpoint iterators pay a fixed cost for every node of the graph (hash
+ * table lookups, joins of the incoming states, bookkeeping of the WPO), which
+ * dominates the running time on graphs whose nodes are single statements.
+ *
+ * This class computes a coarser view of the subgraph reachable from the entry
+ * of a graph, in which every maximal chain of nodes linked by single-exit and
+ * single-entry edges is collapsed into a block, i.e., the usual basic blocks.
+ * Optionally, every strongly connected component that can only be entered
+ * through one node (its head) is collapsed into a block as well. The original
+ * nodes of a block are called its members, and the edges between the members
+ * of a block are its internal edges. The first member of a block is the only
+ * one that has predecessors outside of the block.
+ *
+ * Blocks and edges of the coarsened graph are identified by indices. The entry
+ * of the coarsened graph is always 0.
+ */
+template <typename GraphInterface,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class CoarsenedGraph final {
+ public:
+  using OriginalGraph = typename GraphInterface::Graph;
+  using OriginalNodeId = typename GraphInterface::NodeId;
+  using OriginalEdgeId = typename GraphInterface::EdgeId;
+  using NodeId = uint32_t;
+  using EdgeId = uint32_t;
+
+  struct InternalEdge {
+    uint32_t source;
+    uint32_t target;
+    OriginalEdgeId original;
+  };
+
+  struct Block {
+    std::vector<OriginalNodeId> members;
+    // For a chain, the internal edge i links the member i to the member i + 1.
+    std::vector<InternalEdge> internal_edges;
+    // The internal edges leaving and entering each member, for components.
+    std::vector<std::vector<uint32_t>> successors;
+    std::vector<std::vector<uint32_t>> predecessors;
+    bool is_component = false;
+  };
+
+  CoarsenedGraph(const OriginalGraph& graph, bool collapse_components) {
+    build(graph, collapse_components);
+  }
+
+  NodeId entry() const { return 0; }
+
+  size_t size() const { return m_blocks.size(); }
+
+  const std::vector<EdgeId>& successors(const NodeId& block) const {
+    return m_successors[block];
+  }
+
+  const std::vector<EdgeId>& predecessors(const NodeId& block) const {
+    return m_predecessors[block];
+  }
+
+  NodeId source(const EdgeId& edge) const { return m_edges[edge].source; }
+
+  NodeId target(const EdgeId& edge) const { return m_edges[edge].target; }
+
+  const OriginalEdgeId& original_edge(const EdgeId& edge) const {
+    return m_edges[edge].original;
+  }
+
+  /*
+   * The position, among the members of the source block, of the original
+   * source of an edge. For a chain, this is always its last member.
+   */
+  uint32_t source_member(const EdgeId& edge) const {
+    return m_edges[edge].source_member;
+  }
+
+  const Block& block(const NodeId& block) const { return m_blocks[block]; }
+
+  /*
+   * Returns the block containing a node of the original graph, together with
+   * the position of the node among the members of the block. Returns none if
+   * the node is not reachable from the entry.
+   */
+  boost::optional<std::pair<NodeId, uint32_t>> block_of(
+      const OriginalNodeId& node) const {
+    auto it = m_positions.find(node);
+    if (it == m_positions.end()) {
+      return boost::none;
+    }
+    return it->second;
+  }
+
+ private:
+  struct Edge {
+    NodeId source;
+    NodeId target;
+    uint32_t source_member;
+    OriginalEdgeId original;
+  };
+
+  // The subgraph of the original graph reachable from the entry, with nodes
+  // numbered in breadth-first order.
+  struct Reachable {
+    std::vector<OriginalNodeId> nodes;
+    std::vector<std::vector<std::pair<OriginalEdgeId, uint32_t>>> successors;
+    std::vector<std::vector<uint32_t>> predecessors;
+  };
+
+  static Reachable explore(const OriginalGraph& graph) {
+    Reachable reachable;
+    std::unordered_map<OriginalNodeId, uint32_t, NodeHash> index;
+    auto add_node = [&](const OriginalNodeId& node) {
+      uint32_t idx = reachable.nodes.size();
+      index.emplace(node, idx);
+      reachable.nodes.push_back(node);
+      reachable.successors.emplace_back();
+      reachable.predecessors.emplace_back();
+      return idx;
+    };
+    add_node(GraphInterface::entry(graph));
+    for (uint32_t idx = 0; idx < reachable.nodes.size(); ++idx) {
+      auto node = reachable.nodes[idx];
+      for (const auto& edge : GraphInterface::successors(graph, node)) {
+        auto target = GraphInterface::target(graph, edge);
+        auto it = index.find(target);
+        uint32_t target_idx =
+            it == index.end() ? add_node(target) : it->second;
+        reachable.successors[idx].emplace_back(edge, target_idx);
+        reachable.predecessors[target_idx].push_back(idx);
+      }
+    }
+    return reachable;
+  }
+
+  /*
+   * Computes the strongly connected components of the reachable subgraph with
+   * an iterative version of Tarjan's algorithm. Returns the component of each
+   * node, or none if the node is not part of a cycle.
+   */
+  static std::vector<boost::optional<uint32_t>> components(
+      const Reachable& reachable) {
+    constexpr uint32_t undefined = std::numeric_limits<uint32_t>::max();
+    size_t size = reachable.nodes.size();
+    std::vector<boost::optional<uint32_t>> result(size);
+    std::vector<uint32_t> dfn(size, undefined);
+    std::vector<uint32_t> low(size, undefined);
+    std::vector<bool> on_stack(size, false);
+    std::vector<uint32_t> stack;
+    std::vector<std::pair<uint32_t, size_t>> frames{{0, 0}};
+    uint32_t next_dfn = 0;
+    uint32_t next_component = 0;
+    while (!frames.empty()) {
+      uint32_t node = frames.back().first;
+      size_t position = frames.back().second;
+      if (position == 0) {
+        dfn[node] = low[node] = next_dfn++;
+        stack.push_back(node);
+        on_stack[node] = true;
+      }
+      const auto& succs = reachable.successors[node];
+      bool descended = false;
+      for (; position < succs.size(); ++position) {
+        uint32_t succ = succs[position].second;
+        if (dfn[succ] == undefined) {
+          frames.back().second = position + 1;
+          frames.emplace_back(succ, 0);
+          descended = true;
+          break;
+        }
+        if (on_stack[succ]) {
+          low[node] = std::min(low[node], dfn[succ]);
+        }
+      }
+      if (descended) {
+        continue;
+      }
+      frames.pop_back();
+      if (!frames.empty()) {
+        uint32_t parent = frames.back().first;
+        low[parent] = std::min(low[parent], low[node]);
+      }
+      if (low[node] != dfn[node]) {
+        continue;
+      }
+      std::vector<uint32_t> component;
+      uint32_t member;
+      do {
+        member = stack.back();
+        stack.pop_back();
+        on_stack[member] = false;
+        component.push_back(member);
+      } while (member != node);
+      bool has_self_loop = std::any_of(
+          succs.begin(), succs.end(), [node](const auto& succ) {
+            return succ.second == node;
+          });
+      if (component.size() > 1 || has_self_loop) {
+        for (uint32_t member : component) {
+          result[member] = next_component;
+        }
+        ++next_component;
+      }
+    }
+    return result;
+  }
+
+  void build(const OriginalGraph& graph, bool collapse_components) {
+    Reachable reachable = explore(graph);
+    size_t size = reachable.nodes.size();
+
+    // The components with a single entry, identified by their head.
+    std::vector<boost::optional<uint32_t>> component_head(size);
+    if (collapse_components) {
+      auto component_of = components(reachable);
+      std::unordered_map<uint32_t, std::vector<uint32_t>> entries;
+      for (uint32_t node = 0; node < size; ++node) {
+        if (!component_of[node]) {
+          continue;
+        }
+        const auto& preds = reachable.predecessors[node];
+        bool is_entry =
+            node == 0 ||
+            std::any_of(preds.begin(), preds.end(), [&](uint32_t pred) {
+              return component_of[pred] != component_of[node];
+            });
+        auto& component_entries = entries[*component_of[node]];
+        if (is_entry) {
+          component_entries.push_back(node);
+        }
+      }
+      for (uint32_t node = 0; node < size; ++node) {
+        if (component_of[node]) {
+          const auto& component_entries = entries.at(*component_of[node]);
+          if (component_entries.size() == 1) {
+            component_head[node] = component_entries.front();
+          }
+        }
+      }
+    }
+
+    // A node continues the chain of its predecessor if they are linked by the
+    // only edge leaving the predecessor and the only edge entering the node.
+    auto continues_chain = [&](uint32_t node) {
+      if (node == 0 || component_head[node] ||
+          reachable.predecessors[node].size() != 1) {
+        return false;
+      }
+      uint32_t pred = reachable.predecessors[node].front();
+      return pred != node && !component_head[pred] &&
+             reachable.successors[pred].size() == 1;
+    };
+
+    // Blocks are created in breadth-first order. The predecessor of a node
+    // that continues a chain is visited first, hence the node has already been
+    // assigned to the block of the chain.
+    std::vector<std::pair<NodeId, uint32_t>> position(size);
+    std::vector<bool> assigned(size, false);
+    auto assign = [&](uint32_t node, NodeId block) {
+      auto& members = m_blocks[block].members;
+      position[node] = std::make_pair(block, uint32_t(members.size()));
+      assigned[node] = true;
+      members.push_back(reachable.nodes[node]);
+      m_positions.emplace(reachable.nodes[node], position[node]);
+    };
+    for (uint32_t node = 0; node < size; ++node) {
+      if (assigned[node]) {
+        continue;
+      }
+      NodeId block = m_blocks.size();
+      m_blocks.emplace_back();
+      m_successors.emplace_back();
+      m_predecessors.emplace_back();
+      if (component_head[node]) {
+        m_blocks[block].is_component = true;
+        uint32_t head = *component_head[node];
+        for (uint32_t member = head; member < size; ++member) {
+          if (member == head || component_head[member] == head) {
+            assign(member, block);
+          }
+        }
+        continue;
+      }
+      for (uint32_t member = node;;) {
+        assign(member, block);
+        const auto& succs = reachable.successors[member];
+        if (succs.size() != 1 || !continues_chain(succs.front().second)) {
+          break;
+        }
+        member = succs.front().second;
+      }
+    }
+
+    for (uint32_t node = 0; node < size; ++node) {
+      for (const auto& succ : reachable.successors[node]) {
+        auto source = position[node];
+        auto target = position[succ.second];
+        auto& block = m_blocks[source.first];
+        bool is_internal =
+            source.first == target.first &&
+            (block.is_component || target.second == source.second + 1);
+        if (is_internal) {
+          block.internal_edges.push_back(
+              InternalEdge{source.second, target.second, succ.first});
+          continue;
+        }
+        EdgeId edge = m_edges.size();
+        m_edges.push_back(
+            Edge{source.first, target.first, source.second, succ.first});
+        m_successors[source.first].push_back(edge);
+        m_predecessors[target.first].push_back(edge);
+      }
+    }
+
+    for (auto& block : m_blocks) {
+      if (!block.is_component) {
+        continue;
+      }
+      block.successors.resize(block.members.size());
+      block.predecessors.resize(block.members.size());
+      for (uint32_t edge = 0; edge < block.internal_edges.size(); ++edge) {
+        const auto& internal_edge = block.internal_edges[edge];
+        block.successors[internal_edge.source].push_back(edge);
+        block.predecessors[internal_edge.target].push_back(edge);
+      }
+    }
+  }
+
+  std::vector<Block> m_blocks;
+  std::vector<Edge> m_edges;
+  std::vector<std::vector<EdgeId>> m_successors;
+  std::vector<std::vector<EdgeId>> m_predecessors;
+  std::unordered_map<OriginalNodeId, std::pair<NodeId, uint32_t>, NodeHash>
+      m_positions;
+};
+
+/*
+ * The interface to a coarsened graph, as required by the fixpoint iterators.
+ */
+template <typename GraphInterface,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class CoarsenedGraphInterface {
+ public:
+  using Graph = CoarsenedGraph<GraphInterface, NodeHash>;
+  using NodeId = typename Graph::NodeId;
+  using EdgeId = typename Graph::EdgeId;
+
+  static NodeId entry(const Graph& graph) { return graph.entry(); }
+  static std::vector<EdgeId> predecessors(const Graph& graph,
+                                          const NodeId& node) {
+    return graph.predecessors(node);
+  }
+  static std::vector<EdgeId> successors(const Graph& graph,
+                                        const NodeId& node) {
+    return graph.successors(node);
+  }
+  static NodeId source(const Graph& graph, const EdgeId& edge) {
+    return graph.source(edge);
+  }
+  static NodeId target(const Graph& graph, const EdgeId& edge) {
+    return graph.target(edge);
+  }
+};
+
+/*
+ * A fixpoint iterator that analyzes the coarsened graph instead of the
+ * original graph. The semantic transformers are expressed on the nodes and
+ * edges of the original graph, and are fused into the transformer of each
+ * block: the transformer of a chain applies the transformers of its members
+ * and internal edges in sequence, and the transformer of a component computes
+ * a local fixpoint on the component with a MonotonicFixpointIterator. The
+ * states at the members of the chains are computed again from the entry state
+ * of their block once the global fixpoint is reached, and the states at the
+ * members of the components are those of the local fixpoint, since a
+ * component is analyzed only once. It is parameterized by one of the monotonic
+ * fixpoint iterators defined in MonotonicFixpointIterator.h, e.g.:
+ *
+ *   class MyAnalyzer final
+ *       : public CoarseningFixpointIterator<MonotonicFixpointIterator,
+ *                                           MyCFGInterface,
+ *                                           MyDomain> {
+ *    public:
+ *     MyAnalyzer(const MyCFG& cfg) : CoarseningFixpointIterator(cfg) {}
+ *     ...
+ *   };
+ */
+template <template <typename GraphInterface, typename Domain, typename NodeHash>
+          class FixpointIteratorBase,
+          typename GraphInterface,
+          typename Domain,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class CoarseningFixpointIterator {
+ public:
+  using Graph = typename GraphInterface::Graph;
+  using NodeId = typename GraphInterface::NodeId;
+  using EdgeId = typename GraphInterface::EdgeId;
+  using CoarseGraph = CoarsenedGraph<GraphInterface, NodeHash>;
+
+  /*
+   * Collapsing the components saves the cost of the WPO construction and of
+   * the iteration on the whole graph, but the widening can then only be
+   * applied at the heads of the outermost components.
+   */
+  explicit CoarseningFixpointIterator(const Graph& graph,
+                                      bool collapse_components = false)
+      : m_coarse_graph(graph, collapse_components),
+        m_engine(m_coarse_graph, *this),
+        m_components(m_coarse_graph.size()) {}
+
+  virtual ~CoarseningFixpointIterator() {}
+
+  virtual void analyze_node(const NodeId& node,
+                            Domain* current_state) const = 0;
+
+  virtual Domain analyze_edge(const EdgeId& edge,
+                              const Domain& exit_state_at_source) const = 0;
+
+  void run(const Domain& init) {
+    m_engine.run(init);
+    expand_chains();
+  }
+
+  Domain get_entry_state_at(const NodeId& node) const {
+    auto position = m_coarse_graph.block_of(node);
+    if (!position) {
+      return Domain::bottom();
+    }
+    if (!m_coarse_graph.block(position->first).is_component) {
+      return m_chain_states[position->first][position->second].first;
+    }
+    const auto& component = m_components[position->first];
+    return component ? component->get_entry_state_at(position->second)
+                     : Domain::bottom();
+  }
+
+  Domain get_exit_state_at(const NodeId& node) const {
+    auto position = m_coarse_graph.block_of(node);
+    if (!position) {
+      return Domain::bottom();
+    }
+    if (!m_coarse_graph.block(position->first).is_component) {
+      return m_chain_states[position->first][position->second].second;
+    }
+    const auto& component = m_components[position->first];
+    return component ? component->get_exit_state_at(position->second)
+                     : Domain::bottom();
+  }
+
+  const CoarseGraph& coarsened_graph() const { return m_coarse_graph; }
+
+ private:
+  using Block = typename CoarseGraph::Block;
+  using CoarseGraphInterface =
+      CoarsenedGraphInterface<GraphInterface, NodeHash>;
+
+  /*
+   * The interface to the members and internal edges of a component.
+   */
+  class ComponentInterface {
+   public:
+    using Graph = Block;
+    using NodeId = uint32_t;
+    using EdgeId = uint32_t;
+
+    static NodeId entry(const Graph&) { return 0; }
+    static std::vector<EdgeId> predecessors(const Graph& block,
+                                            const NodeId& member) {
+      return block.predecessors[member];
+    }
+    static std::vector<EdgeId> successors(const Graph& block,
+                                          const NodeId& member) {
+      return block.successors[member];
+    }
+    static NodeId source(const Graph& block, const EdgeId& edge) {
+      return block.internal_edges[edge].source;
+    }
+    static NodeId target(const Graph& block, const EdgeId& edge) {
+      return block.internal_edges[edge].target;
+    }
+  };
+
+  class ComponentIterator final
+      : public MonotonicFixpointIterator<ComponentInterface, Domain> {
+   public:
+    ComponentIterator(const Block& block,
+                      const CoarseningFixpointIterator& outer)
+        : MonotonicFixpointIterator<ComponentInterface, Domain>(
+              block, block.members.size()),
+          m_block(block),
+          m_outer(outer) {}
+
+    void analyze_node(const uint32_t& member,
+                      Domain* current_state) const override {
+      m_outer.analyze_node(m_block.members[member], current_state);
+    }
+
+    Domain analyze_edge(const uint32_t& edge,
+                        const Domain& exit_state_at_source) const override {
+      return m_outer.analyze_edge(m_block.internal_edges[edge].original,
+                                  exit_state_at_source);
+    }
+
+   private:
+    const Block& m_block;
+    const CoarseningFixpointIterator& m_outer;
+  };
+
+  class Engine final
+      : public FixpointIteratorBase<CoarseGraphInterface,
+                                    Domain,
+                                    std::hash<typename CoarseGraph::NodeId>> {
+   public:
+    Engine(const CoarseGraph& graph, const CoarseningFixpointIterator& outer)
+        : FixpointIteratorBase<CoarseGraphInterface,
+                               Domain,
+                               std::hash<typename CoarseGraph::NodeId>>(graph),
+          m_graph(graph),
+          m_outer(outer) {}
+
+    void analyze_node(const typename CoarseGraph::NodeId& block,
+                      Domain* current_state) const override {
+      m_outer.analyze_block(block, current_state);
+    }
+
+    Domain analyze_edge(const typename CoarseGraph::EdgeId& edge,
+                        const Domain& exit_state_at_source) const override {
+      const auto& component = m_outer.m_components[m_graph.source(edge)];
+      if (component) {
+        // The edge leaves the component from one of its members.
+        return m_outer.analyze_edge(
+            m_graph.original_edge(edge),
+            component->get_exit_state_at(m_graph.source_member(edge)));
+      }
+      return m_outer.analyze_edge(m_graph.original_edge(edge),
+                                  exit_state_at_source);
+    }
+
+   private:
+    const CoarseGraph& m_graph;
+    const CoarseningFixpointIterator& m_outer;
+  };
+
+  /*
+   * Applies the transformers of the members of a chain in sequence, and
+   * records the states at the members if requested.
+   */
+  void analyze_chain(const Block& block,
+                     Domain* current_state,
+                     std::vector<std::pair<Domain, Domain>>* states) const {
+    for (size_t member = 0; member < block.members.size(); ++member) {
+      if (member > 0) {
+        *current_state = analyze_edge(
+            block.internal_edges[member - 1].original, *current_state);
+      }
+      if (states != nullptr) {
+        states->emplace_back(*current_state, Domain::bottom());
+      }
+      analyze_node(block.members[member], current_state);
+      if (states != nullptr) {
+        states->back().second = *current_state;
+      }
+    }
+  }
+
+  /*
+   * A component is never part of a cycle of the coarsened graph, hence it is
+   * analyzed at most once per run and the iterator can be kept to answer the
+   * queries about its members. The exit state of the block is the join of the
+   * exit states of its members.
+   */
+  void analyze_block(uint32_t block_id, Domain* current_state) const {
+    const auto& block = m_coarse_graph.block(block_id);
+    if (!block.is_component) {
+      analyze_chain(block, current_state, nullptr);
+      return;
+    }
+    auto& component = m_components[block_id];
+    component = std::make_unique<ComponentIterator>(block, *this);
+    component->run(*current_state);
+    current_state->set_to_bottom();
+    for (uint32_t member = 0; member < block.members.size(); ++member) {
+      current_state->join_with(component->get_exit_state_at(member));
+    }
+  }
+
+  void expand_chains() {
+    m_chain_states.clear();
+    m_chain_states.resize(m_coarse_graph.size());
+    for (uint32_t block_id = 0; block_id < m_coarse_graph.size();
+         ++block_id) {
+      const auto& block = m_coarse_graph.block(block_id);
+      if (block.is_component) {
+        continue;
+      }
+      Domain state = m_engine.get_entry_state_at(block_id);
+      analyze_chain(block, &state, &m_chain_states[block_id]);
+    }
+  }
+
+  CoarseGraph m_coarse_graph;
+  Engine m_engine;
+  // The iterators of the components, indexed by block. They are only written
+  // by the transformer of their own block, which makes them safe to use with
+  // the parallel fixpoint iterator.
+  mutable std::vector<std::unique_ptr<ComponentIterator>> m_components;
+  std::vector<std::vector<std::pair<Domain, Domain>>> m_chain_states;
+};
+
+} // namespace sparta
@@ -0,0 +1,199 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <mutex>
+#include <ostream>
+#include <unordered_map>
+#include <utility>
+
+#include "Exceptions.h"
+#include "MemoryCeiling.h"
+
+namespace sparta {
+
+/*
+ * The successive levels of the growth-rate widening strategy (see below).
+ */
+enum class WideningLevel {
+  // The entry state of the head is joined with the new state.
+  Join,
+  // The widening is applied once every few extrapolations, and the join
+  // otherwise.
+  DelayedWidening,
+  // The widening is applied at every extrapolation, and the entry state is
+  // set to Top if it still grows.
+  AggressiveWidening,
+};
+
+inline std::ostream& operator<<(std::ostream& o, WideningLevel level) {
+  switch (level) {
+  case WideningLevel::Join:
+    return o << "Join";
+  case WideningLevel::DelayedWidening:
+    return o << "DelayedWidening";
+  case WideningLevel::AggressiveWidening:
+    return o << "AggressiveWidening";
+  }
+  return o;
+}
+
+struct GrowthRateWideningOptions {
+  // The number of extrapolations of a head that apply the join before
+  // escalating to the delayed widening, provided that the state doesn't grow
+  // too fast in the meantime.
+  uint32_t max_joins{2};
+  // At the delayed widening level, the widening is applied at every
+  // `widening_delay` extrapolations.
+  uint32_t widening_delay{2};
+  // The number of consecutive extrapolations that increase the size of the
+  // state at the delayed widening level before escalating to the aggressive
+  // widening.
+  uint32_t patience{3};
+  // A single extrapolation that multiplies the size of the state by more than
+  // this ratio escalates to the next level immediately.
+  double max_growth_ratio{2.0};
+};
+
+/*
+ * An extrapolation strategy that doesn't need to be tuned for each analysis.
+ * It monitors the size of the entry state of each head between iterations
+ * (as given by size_hint_of() unless a size function is provided) and
+ * escalates from the join to the delayed widening and then to the aggressive
+ * widening (see WideningLevel) when the state keeps growing:
+ *
+ *   - the join is applied at the first `max_joins` extrapolations, unless
+ *     the state grows by more than `max_growth_ratio` in one of them;
+ *   - the delayed widening is applied until the state has grown in `patience`
+ *     consecutive extrapolations, or by more than `max_growth_ratio` in one of
+ *     them;
+ *   - the aggressive widening is applied afterwards.
+ *
+ * The levels never decrease during a run, so that the widening is applied
+ * infinitely often and the iteration terminates. The aggressive level also
+ * guarantees termination with a domain whose widening is the join over an
+ * infinite universe, e.g., a set of strings, since a state that keeps growing
+ * is set to Top.
+ *
+ * This is the strategy used by a fixpoint iterator on which
+ * set_growth_rate_widening() has been called. The bookkeeping is
+ * thread-safe, so that it can be used by a parallel fixpoint iterator.
+ */
+template <typename NodeId,
+          typename Domain,
+          typename NodeHash = std::hash<NodeId>>
+class GrowthRateWidening final {
+ public:
+  explicit GrowthRateWidening(
+      GrowthRateWideningOptions options = GrowthRateWideningOptions(),
+      std::function<size_t(const Domain&)> size_of = nullptr)
+      : m_options(options),
+        m_size_of(size_of ? std::move(size_of) : [](const Domain& x) {
+          return size_hint_of(x);
+        }) {
+    RUNTIME_CHECK(options.widening_delay > 0,
+                  invalid_argument() << argument_name("widening_delay"));
+  }
+
+  const GrowthRateWideningOptions& options() const { return m_options; }
+
+  void extrapolate(const NodeId& head,
+                   Domain* current_state,
+                   const Domain& new_state) {
+    size_t previous_size = m_size_of(*current_state);
+    HeadState* head_state;
+    {
+      std::lock_guard<std::mutex> lock(m_mutex);
+      head_state = &m_heads[head];
+    }
+    // The extrapolations of the same head are never performed concurrently.
+    switch (head_state->level) {
+    case WideningLevel::Join:
+      current_state->join_with(new_state);
+      break;
+    case WideningLevel::DelayedWidening:
+      if ((head_state->extrapolations + 1) % m_options.widening_delay == 0) {
+        current_state->widen_with(new_state);
+      } else {
+        current_state->join_with(new_state);
+      }
+      break;
+    case WideningLevel::AggressiveWidening:
+      current_state->widen_with(new_state);
+      if (m_size_of(*current_state) > previous_size) {
+        current_state->set_to_top();
+      }
+      return;
+    }
+    update(head_state, previous_size, m_size_of(*current_state));
+  }
+
+  /*
+   * The level at which the next extrapolation of a head is performed.
+   */
+  WideningLevel level(const NodeId& head) const {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    auto it = m_heads.find(head);
+    return it == m_heads.end() ? WideningLevel::Join : it->second.level;
+  }
+
+  void clear() {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    m_heads.clear();
+  }
+
+  /*
+   * Same as clear(), but also releases the memory held by the bookkeeping.
+   */
+  void clear_and_shrink() {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    decltype(m_heads)().swap(m_heads);
+  }
+
+ private:
+  struct HeadState {
+    WideningLevel level{WideningLevel::Join};
+    // The number of extrapolations at the current level.
+    uint32_t extrapolations{0};
+    // The number of consecutive extrapolations that increased the size.
+    uint32_t growths{0};
+  };
+
+  void update(HeadState* head_state, size_t previous_size, size_t size) {
+    ++head_state->extrapolations;
+    head_state->growths = size > previous_size ? head_state->growths + 1 : 0;
+    bool too_fast = previous_size > 0 &&
+                    static_cast<double>(size) >
+                        m_options.max_growth_ratio * previous_size;
+    bool escalate = false;
+    if (head_state->level == WideningLevel::Join) {
+      escalate = too_fast || head_state->extrapolations >= m_options.max_joins;
+    } else {
+      escalate = too_fast || head_state->growths >= m_options.patience;
+    }
+    if (escalate) {
+      head_state->level = head_state->level == WideningLevel::Join
+                              ? WideningLevel::DelayedWidening
+                              : WideningLevel::AggressiveWidening;
+      head_state->extrapolations = 0;
+      head_state->growths = 0;
+    }
+  }
+
+  GrowthRateWideningOptions m_options;
+  std::function<size_t(const Domain&)> m_size_of;
+  // The references to the elements of an unordered map remain valid when
+  // other elements are inserted.
+  std::unordered_map<NodeId, HeadState, NodeHash> m_heads;
+  mutable std::mutex m_mutex;
+};
+
+} // namespace sparta
@@ -10,12 +10,18 @@
 #include <cstddef>
 #include <functional>
 #include <initializer_list>
+#include <map>
+#include <memory>
 #include <ostream>
 #include <sstream>
+#include <type_traits>
 #include <unordered_map>
+#include <unordered_set>
 #include <utility>
+#include <vector>
 
 #include "AbstractDomain.h"
+#include "DefaultBinding.h"
 
 namespace sparta {
 
@@ -24,9 +30,19 @@ namespace hae_impl {
 template <typename Variable,
           typename Domain,
           typename VariableHash,
-          typename VariableEqual>
+          typename VariableEqual,
+          typename Allocator>
 class MapValue;
 
+template <typename Variable, typename = void>
+struct is_ordered : std::false_type {};
+
+template <typename Variable>
+struct is_ordered<Variable,
+                  std::void_t<decltype(std::declval<const Variable&>() <
+                                       std::declval<const Variable&>())>>
+    : std::true_type {};
+
 } // namespace hae_impl
 
 /*
@@ -60,21 +76,33 @@ class MapValue;
  * environment has a default value of Top. This representation is quite
  * convenient in practice. It also allows us to manipulate large (or possibly
  * infinite) variable sets with sparse assignments of non-Top values.
+ *
+ * The allocator of the hashtable can be customized, e.g., in order to allocate
+ * the environments of an analysis run from a pool or an arena.
  */
 template <typename Variable,
           typename Domain,
           typename VariableHash = std::hash<Variable>,
-          typename VariableEqual = std::equal_to<Variable>>
+          typename VariableEqual = std::equal_to<Variable>,
+          typename Allocator =
+              std::allocator<std::pair<const Variable, Domain>>>
 class HashedAbstractEnvironment final
     : public AbstractDomainScaffolding<
-          hae_impl::MapValue<Variable, Domain, VariableHash, VariableEqual>,
+          hae_impl::MapValue<Variable,
+                             Domain,
+                             VariableHash,
+                             VariableEqual,
+                             Allocator>,
           HashedAbstractEnvironment<Variable,
                                     Domain,
                                     VariableHash,
-                                    VariableEqual>> {
+                                    VariableEqual,
+                                    Allocator>> {
  public:
-  using Value =
-      hae_impl::MapValue<Variable, Domain, VariableHash, VariableEqual>;
+  using DefaultBinding = DefaultIsTop;
+
+  using Value = hae_impl::
+      MapValue<Variable, Domain, VariableHash, VariableEqual, Allocator>;
 
   /*
    * The default constructor produces the Top value.
@@ -88,11 +116,12 @@ class HashedAbstractEnvironment final
   HashedAbstractEnvironment(
       std::initializer_list<std::pair<Variable, Domain>> l) {
     for (const auto& p : l) {
-      if (p.second.is_bottom()) {
+      Domain value = canonical(p.second);
+      if (value.is_bottom()) {
         this->set_to_bottom();
         return;
       }
-      this->get_value()->insert_binding(p.first, p.second);
+      this->get_value()->insert_binding(p.first, value);
     }
     this->normalize();
   }
@@ -107,7 +136,11 @@ class HashedAbstractEnvironment final
     return this->get_value()->m_map.size();
   }
 
-  const std::unordered_map<Variable, Domain, VariableHash, VariableEqual>&
+  const std::unordered_map<Variable,
+                           Domain,
+                           VariableHash,
+                           VariableEqual,
+                           Allocator>&
   bindings() const {
     RUNTIME_CHECK(this->kind() == AbstractValueKind::Value,
                   invalid_abstract_value()
@@ -116,6 +149,17 @@ class HashedAbstractEnvironment final
     return this->get_value()->m_map;
   }
 
+  /*
+   * The bindings sorted by variable, which is useful to produce output that
+   * doesn't depend on the hash function, e.g., for golden tests.
+   */
+  template <typename Compare = std::less<Variable>>
+  std::map<Variable, Domain, Compare> sorted_bindings(
+      const Compare& compare = Compare()) const {
+    const auto& map = bindings();
+    return std::map<Variable, Domain, Compare>(map.begin(), map.end(), compare);
+  }
+
   const Domain& get(const Variable& variable) const {
     if (this->is_bottom()) {
       static const Domain bottom = Domain::bottom();
@@ -129,11 +173,11 @@ class HashedAbstractEnvironment final
     return binding->second;
   }
 
-  HashedAbstractEnvironment& set(const Variable& variable,
-                                 const Domain& value) {
+  HashedAbstractEnvironment& set(const Variable& variable, Domain value) {
     if (this->is_bottom()) {
       return *this;
     }
+    canonicalize(&value);
     if (value.is_bottom()) {
       this->set_to_bottom();
       return *this;
@@ -160,6 +204,7 @@ class HashedAbstractEnvironment final
       value = &binding->second;
     }
     operation(value);
+    canonicalize(value);
     // We normalize the abstract environment after the operation has been
     // completed.
     if (value->is_bottom()) {
@@ -173,6 +218,51 @@ class HashedAbstractEnvironment final
     return *this;
   }
 
+  /*
+   * Abstract garbage collection: removes the bindings of all the variables
+   * that are not transitively reachable from the given roots. See the
+   * documentation of PatriciaTreeMapAbstractEnvironment::collect_garbage() for
+   * the requirements on `references`.
+   */
+  bool collect_garbage(
+      const std::vector<Variable>& roots,
+      std::function<std::vector<Variable>(const Domain&)> references) {
+    if (!this->is_value()) {
+      return false;
+    }
+    auto& map = this->get_value()->m_map;
+    std::unordered_set<Variable, VariableHash, VariableEqual> reachable;
+    std::vector<Variable> worklist(roots);
+    while (!worklist.empty()) {
+      Variable variable = worklist.back();
+      worklist.pop_back();
+      if (!reachable.insert(variable).second) {
+        continue;
+      }
+      auto binding = map.find(variable);
+      if (binding == map.end()) {
+        // The variable is implicitly bound to Top.
+        continue;
+      }
+      for (const Variable& referenced : references(binding->second)) {
+        if (reachable.count(referenced) == 0) {
+          worklist.push_back(referenced);
+        }
+      }
+    }
+    size_t size_before = map.size();
+    for (auto it = map.begin(); it != map.end();) {
+      if (reachable.count(it->first) == 0) {
+        it = map.erase(it);
+      } else {
+        ++it;
+      }
+    }
+    bool collected = map.size() != size_before;
+    this->normalize();
+    return collected;
+  }
+
   static HashedAbstractEnvironment bottom() {
     return HashedAbstractEnvironment(AbstractValueKind::Bottom);
   }
@@ -182,18 +272,49 @@ class HashedAbstractEnvironment final
   }
 };
 
+namespace hae_impl {
+
+template <typename Bindings>
+inline void print_bindings_in_order(std::ostream& o, const Bindings& bindings) {
+  for (auto it = bindings.begin(); it != bindings.end();) {
+    o << it->first << " -> " << it->second;
+    ++it;
+    if (it != bindings.end()) {
+      o << ", ";
+    }
+  }
+}
+
+template <typename Environment>
+inline void print_bindings(std::ostream& o,
+                           const Environment& e,
+                           std::true_type /* is_ordered */) {
+  print_bindings_in_order(o, e.sorted_bindings());
+}
+
+template <typename Environment>
+inline void print_bindings(std::ostream& o,
+                           const Environment& e,
+                           std::false_type /* is_ordered */) {
+  print_bindings_in_order(o, e.bindings());
+}
+
+} // namespace hae_impl
+
 } // namespace sparta
 
 template <typename Variable,
           typename Domain,
           typename VariableHash,
-          typename VariableEqual>
+          typename VariableEqual,
+          typename Allocator>
 inline std::ostream& operator<<(
     std::ostream& o,
     const typename sparta::HashedAbstractEnvironment<Variable,
                                                      Domain,
                                                      VariableHash,
-                                                     VariableEqual>& e) {
+                                                     VariableEqual,
+                                                     Allocator>& e) {
   using namespace sparta;
   switch (e.kind()) {
   case AbstractValueKind::Bottom: {
@@ -207,14 +328,12 @@ inline std::ostream& operator<<(
   case AbstractValueKind::Value: {
     o << "[#" << e.size() << "]";
     o << "{";
-    auto& bindings = e.bindings();
-    for (auto it = bindings.begin(); it != bindings.end();) {
-      o << it->first << " -> " << it->second;
-      ++it;
-      if (it != bindings.end()) {
-        o << ", ";
-      }
-    }
+    // The bindings are printed in a deterministic order whenever the
+    // variables can be compared.
+    hae_impl::print_bindings(
+        o,
+        e,
+        std::integral_constant<bool, hae_impl::is_ordered<Variable>::value>());
     o << "}";
     break;
   }
@@ -239,10 +358,11 @@ namespace hae_impl {
 template <typename Variable,
           typename Domain,
           typename VariableHash,
-          typename VariableEqual>
+          typename VariableEqual,
+          typename Allocator>
 class MapValue final
     : public AbstractValue<
-          MapValue<Variable, Domain, VariableHash, VariableEqual>> {
+          MapValue<Variable, Domain, VariableHash, VariableEqual, Allocator>> {
  public:
   MapValue() = default;
 
@@ -329,7 +449,9 @@ class MapValue final
   void insert_binding(const Variable& variable, const Domain& value) {
     // The Bottom value is handled in HashedAbstractEnvironment and should
     // never occur here.
-    RUNTIME_CHECK(!value.is_bottom(), internal_error());
+    RUNTIME_CHECK(!value.is_bottom(),
+                  internal_error() << error_msg(
+                      "Binding a variable to Bottom in an environment"));
     if (value.is_top()) {
       // Bindings with the Top value are not explicitly represented.
       m_map.erase(variable);
@@ -352,6 +474,7 @@ class MapValue final
       } else {
         // We compute the join-like combination of the values.
         operation(&it->second, other_binding->second);
+        canonicalize(&it->second);
         if (it->second.is_top()) {
           // If the result is Top, we erase the binding.
           auto to_erase = it++;
@@ -376,6 +499,7 @@ class MapValue final
       } else {
         // We compute the meet-like combination of the values.
         operation(&binding->second, other_binding.second);
+        canonicalize(&binding->second);
         if (binding->second.is_bottom()) {
           // If the result is Bottom, the entire environment becomes Bottom.
           clear();
@@ -386,9 +510,10 @@ class MapValue final
     return kind();
   }
 
-  std::unordered_map<Variable, Domain, VariableHash, VariableEqual> m_map;
+  std::unordered_map<Variable, Domain, VariableHash, VariableEqual, Allocator>
+      m_map;
 
-  template <typename T1, typename T2, typename T3, typename T4>
+  template <typename T1, typename T2, typename T3, typename T4, typename T5>
   friend class sparta::HashedAbstractEnvironment;
 };
 
@@ -10,12 +10,14 @@
 #include <cstddef>
 #include <functional>
 #include <initializer_list>
+#include <memory>
 #include <ostream>
 #include <sstream>
 #include <unordered_map>
 #include <utility>
 
 #include "AbstractDomain.h"
+#include "DefaultBinding.h"
 
 namespace sparta {
 
@@ -37,15 +39,33 @@ namespace sparta {
  *   HashedAbstractPartition::top().set(L, D) == HashedAbstractPartition::top()
  *
  * This makes for a much simpler implementation.
+ *
+ * When the labels are drawn from a large set, e.g., call sites in code that
+ * makes heavy use of reflection, the number of labels can be capped: if the
+ * result of a join or a widening has more than MaxLabels labels, it is set to
+ * Top. This trades precision for a bounded size. The result is still an upper
+ * bound of the operands, and the widening terminates even if there are
+ * infinitely many labels. A cap of 0, the default, means no limit.
+ *
+ * The allocator of the hashtable can be customized, e.g., in order to allocate
+ * the partitions of an analysis run from a pool or an arena.
  */
 template <typename Label,
           typename Domain,
           typename LabelHash = std::hash<Label>,
-          typename LabelEqual = std::equal_to<Label>>
+          typename LabelEqual = std::equal_to<Label>,
+          size_t MaxLabels = 0,
+          typename Allocator = std::allocator<std::pair<const Label, Domain>>>
 class HashedAbstractPartition final
-    : public AbstractDomain<
-          HashedAbstractPartition<Label, Domain, LabelHash, LabelEqual>> {
+    : public AbstractDomain<HashedAbstractPartition<Label,
+                                                    Domain,
+                                                    LabelHash,
+                                                    LabelEqual,
+                                                    MaxLabels,
+                                                    Allocator>> {
  public:
+  using DefaultBinding = DefaultIsBottom;
+
   /*
    * The default constructor produces the Bottom value.
    */
@@ -70,12 +90,47 @@ class HashedAbstractPartition final
    * Get the bindings that are not set to Bottom. This operation is not defined
    * if the HashedAbstractPartition is set to Top.
    */
-  const std::unordered_map<Label, Domain, LabelHash, LabelEqual>& bindings()
-      const {
+  const std::unordered_map<Label, Domain, LabelHash, LabelEqual, Allocator>&
+  bindings() const {
     RUNTIME_CHECK(!is_top(), undefined_operation());
     return m_map;
   }
 
+  /*
+   * Folds a function over the bindings that are not set to Bottom, in an
+   * unspecified order, e.g., to summarize a partition:
+   *
+   *   size_t escaping = partition.fold(
+   *       size_t(0), [](size_t n, const Label&, const Domain& value) {
+   *         return n + (value.escapes() ? 1 : 0);
+   *       });
+   *
+   * This operation is not defined if the HashedAbstractPartition is set to Top.
+   */
+  template <typename Accumulator, typename Function>
+  Accumulator fold(Accumulator init, Function&& f) const {
+    RUNTIME_CHECK(!is_top(), undefined_operation());
+    for (const auto& binding : m_map) {
+      init = f(std::move(init), binding.first, binding.second);
+    }
+    return init;
+  }
+
+  /*
+   * The join of the values of all the labels, which is Bottom if all the
+   * labels are set to Bottom and Top if the partition is set to Top.
+   */
+  Domain join_of_all_bindings() const {
+    if (is_top()) {
+      return Domain::top();
+    }
+    return fold(Domain::bottom(),
+                [](Domain acc, const Label&, const Domain& value) {
+                  acc.join_with(value);
+                  return acc;
+                });
+  }
+
   const Domain& get(const Label& label) const {
     if (is_top()) {
       static const Domain top = Domain::top();
@@ -92,10 +147,11 @@ class HashedAbstractPartition final
   /*
    * This is a no-op if the partition is set to Top.
    */
-  HashedAbstractPartition& set(const Label& label, const Domain& value) {
+  HashedAbstractPartition& set(const Label& label, Domain value) {
     if (is_top()) {
       return *this;
     }
+    canonicalize(&value);
     if (value.is_bottom()) {
       m_map.erase(label);
     } else {
@@ -123,6 +179,7 @@ class HashedAbstractPartition final
       value = &binding->second;
     }
     operation(value);
+    canonicalize(value);
     if (value->is_bottom()) {
       m_map.erase(label);
     }
@@ -224,11 +281,17 @@ class HashedAbstractPartition final
       } else {
         // We compute the join-like combination of the values.
         operation(&binding->second, other_binding.second);
+        canonicalize(&binding->second);
         // By construction, it's impossible to have Bottom in both operands,
         // hence the result can never be Bottom.
-        RUNTIME_CHECK(!binding->second.is_bottom(), internal_error());
+        RUNTIME_CHECK(!binding->second.is_bottom(),
+                      internal_error() << error_msg(
+                          "The join of two labels is Bottom in a partition"));
       }
     }
+    if (MaxLabels > 0 && m_map.size() > MaxLabels) {
+      set_to_top();
+    }
   }
 
   void meet_like_operation(
@@ -252,6 +315,7 @@ class HashedAbstractPartition final
       } else {
         // We compute the meet-like combination of the values.
         operation(&it->second, other_binding->second);
+        canonicalize(&it->second);
         if (it->second.is_bottom()) {
           // If the result is Bottom, we erase the binding.
           auto to_erase = it++;
@@ -272,7 +336,7 @@ class HashedAbstractPartition final
   }
 
  private:
-  std::unordered_map<Label, Domain, LabelHash, LabelEqual> m_map;
+  std::unordered_map<Label, Domain, LabelHash, LabelEqual, Allocator> m_map;
   bool m_is_top{false};
 };
 
@@ -281,12 +345,17 @@ class HashedAbstractPartition final
 template <typename Label,
           typename Domain,
           typename LabelHash,
-          typename LabelEqual>
+          typename LabelEqual,
+          size_t MaxLabels,
+          typename Allocator>
 inline std::ostream& operator<<(
     std::ostream& o,
-    const typename sparta::
-        HashedAbstractPartition<Label, Domain, LabelHash, LabelEqual>&
-            partition) {
+    const typename sparta::HashedAbstractPartition<Label,
+                                                   Domain,
+                                                   LabelHash,
+                                                   LabelEqual,
+                                                   MaxLabels,
+                                                   Allocator>& partition) {
   if (partition.is_bottom()) {
     o << "_|_";
   } else if (partition.is_top()) {
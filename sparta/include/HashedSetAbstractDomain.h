@@ -9,6 +9,7 @@
 
 #include <cstddef>
 #include <initializer_list>
+#include <memory>
 #include <unordered_set>
 
 #include "PowersetAbstractDomain.h"
@@ -16,7 +17,7 @@
 namespace sparta {
 
 // Forward declaration.
-template <typename Element, typename Hash, typename Equal>
+template <typename Element, typename Hash, typename Equal, typename Allocator>
 class HashedSetAbstractDomain;
 
 namespace hsad_impl {
@@ -24,11 +25,12 @@ namespace hsad_impl {
 /*
  * An abstract value from a powerset is implemented as a hash table.
  */
-template <typename Element, typename Hash, typename Equal>
-class SetValue final : public PowersetImplementation<
-                           Element,
-                           const std::unordered_set<Element, Hash, Equal>&,
-                           SetValue<Element, Hash, Equal>> {
+template <typename Element, typename Hash, typename Equal, typename Allocator>
+class SetValue final
+    : public PowersetImplementation<
+          Element,
+          const std::unordered_set<Element, Hash, Equal, Allocator>&,
+          SetValue<Element, Hash, Equal, Allocator>> {
  public:
   SetValue() = default;
 
@@ -36,7 +38,8 @@ class SetValue final : public PowersetImplementation<
 
   SetValue(std::initializer_list<Element> l) : m_set(l.begin(), l.end()) {}
 
-  const std::unordered_set<Element, Hash, Equal>& elements() const override {
+  const std::unordered_set<Element, Hash, Equal, Allocator>& elements()
+      const override {
     return m_set;
   }
 
@@ -112,40 +115,45 @@ class SetValue final : public PowersetImplementation<
   }
 
  private:
-  std::unordered_set<Element, Hash, Equal> m_set;
+  std::unordered_set<Element, Hash, Equal, Allocator> m_set;
 
-  template <typename T1, typename T2, typename T3>
+  template <typename T1, typename T2, typename T3, typename T4>
   friend class sparta::HashedSetAbstractDomain;
 };
 
 } // namespace hsad_impl
 
 /*
- * An implementation of powerset abstract domains using hash tables.
+ * An implementation of powerset abstract domains using hash tables. The
+ * allocator of the hash tables can be customized, e.g., in order to allocate
+ * the sets of an analysis run from a pool or an arena.
  */
 template <typename Element,
           typename Hash = std::hash<Element>,
-          typename Equal = std::equal_to<Element>>
+          typename Equal = std::equal_to<Element>,
+          typename Allocator = std::allocator<Element>>
 class HashedSetAbstractDomain final
     : public PowersetAbstractDomain<
           Element,
-          hsad_impl::SetValue<Element, Hash, Equal>,
-          const std::unordered_set<Element, Hash, Equal>&,
-          HashedSetAbstractDomain<Element, Hash, Equal>> {
+          hsad_impl::SetValue<Element, Hash, Equal, Allocator>,
+          const std::unordered_set<Element, Hash, Equal, Allocator>&,
+          HashedSetAbstractDomain<Element, Hash, Equal, Allocator>> {
  public:
-  using Value = hsad_impl::SetValue<Element, Hash, Equal>;
+  using Value = hsad_impl::SetValue<Element, Hash, Equal, Allocator>;
 
   HashedSetAbstractDomain()
-      : PowersetAbstractDomain<Element,
-                               Value,
-                               const std::unordered_set<Element, Hash, Equal>&,
-                               HashedSetAbstractDomain>() {}
+      : PowersetAbstractDomain<
+            Element,
+            Value,
+            const std::unordered_set<Element, Hash, Equal, Allocator>&,
+            HashedSetAbstractDomain>() {}
 
   HashedSetAbstractDomain(AbstractValueKind kind)
-      : PowersetAbstractDomain<Element,
-                               Value,
-                               const std::unordered_set<Element, Hash, Equal>&,
-                               HashedSetAbstractDomain>(kind) {}
+      : PowersetAbstractDomain<
+            Element,
+            Value,
+            const std::unordered_set<Element, Hash, Equal, Allocator>&,
+            HashedSetAbstractDomain>(kind) {}
 
   explicit HashedSetAbstractDomain(const Element& e) {
     this->set_to_value(Value(e));
@@ -0,0 +1,153 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <functional>
+#include <ostream>
+#include <sstream>
+#include <string>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * The Hasse diagram of a set of abstract values ordered by leq(), i.e., the
+ * graph of the covering relation: there is an edge from x to y iff x < y and
+ * there is no value z in the set such that x < z < y. Equivalent values are
+ * only kept once.
+ *
+ * For a small finite domain, e.g., a FiniteAbstractDomain over an enum, the
+ * diagram of all the elements is the diagram of the whole lattice. For any
+ * other domain, the diagram of a sample of interesting values shows how they
+ * are ordered, e.g., when reviewing the design of a custom domain:
+ *
+ *   HasseDiagram<Domain> diagram({x, y, x.join(y), x.meet(y)},
+ *                                 true); // Add Bottom and Top.
+ *   diagram.write_dot(std::cout);
+ *
+ * The construction performs a quadratic number of comparisons and a cubic
+ * number of steps in the number of values, hence it is only intended for a
+ * few dozen values.
+ */
+template <typename Domain>
+class HasseDiagram final {
+ public:
+  // An edge from the index of a value to the index of a value that covers it.
+  using Edge = std::pair<size_t, size_t>;
+
+  explicit HasseDiagram(const std::vector<Domain>& values,
+                        bool add_extremal_values = false) {
+    if (add_extremal_values) {
+      add(Domain::bottom());
+    }
+    for (const auto& value : values) {
+      add(value);
+    }
+    if (add_extremal_values) {
+      add(Domain::top());
+    }
+    compute_covers();
+  }
+
+  /*
+   * Builds the diagram of the values of a domain that can be constructed from
+   * the given elements, e.g., all the elements of a FiniteAbstractDomain.
+   */
+  template <typename Element>
+  static HasseDiagram of_elements(const std::vector<Element>& elements) {
+    std::vector<Domain> values;
+    values.reserve(elements.size());
+    for (const auto& element : elements) {
+      values.emplace_back(element);
+    }
+    return HasseDiagram(values);
+  }
+
+  /*
+   * The distinct values, in the order in which they were given.
+   */
+  const std::vector<Domain>& values() const { return m_values; }
+
+  const std::vector<Edge>& edges() const { return m_edges; }
+
+  /*
+   * Renders the diagram in the DOT format of Graphviz, with the greater
+   * values above. The values are labeled by their printed form.
+   */
+  void write_dot(std::ostream& o) const {
+    write_dot(o, [](const Domain& value) {
+      std::ostringstream label;
+      label << value;
+      return label.str();
+    });
+  }
+
+  void write_dot(std::ostream& o,
+                 const std::function<std::string(const Domain&)>& label) const {
+    o << "digraph {\n";
+    o << "  rankdir=BT;\n";
+    for (size_t i = 0; i < m_values.size(); ++i) {
+      o << "  n" << i << " [label=\"";
+      for (char c : label(m_values[i])) {
+        if (c == '"' || c == '\\') {
+          o << '\\';
+        }
+        o << c;
+      }
+      o << "\"];\n";
+    }
+    for (const auto& edge : m_edges) {
+      o << "  n" << edge.first << " -> n" << edge.second << ";\n";
+    }
+    o << "}\n";
+  }
+
+ private:
+  void add(const Domain& value) {
+    for (const auto& other : m_values) {
+      if (value.equals(other)) {
+        return;
+      }
+    }
+    m_values.push_back(value);
+  }
+
+  void compute_covers() {
+    size_t n = m_values.size();
+    std::vector<std::vector<bool>> less(n, std::vector<bool>(n, false));
+    for (size_t i = 0; i < n; ++i) {
+      for (size_t j = 0; j < n; ++j) {
+        // The values are pairwise distinct.
+        less[i][j] = i != j && m_values[i].leq(m_values[j]);
+      }
+    }
+    for (size_t i = 0; i < n; ++i) {
+      for (size_t j = 0; j < n; ++j) {
+        if (!less[i][j]) {
+          continue;
+        }
+        bool is_cover = true;
+        for (size_t k = 0; k < n && is_cover; ++k) {
+          is_cover = !(less[i][k] && less[k][j]);
+        }
+        if (is_cover) {
+          m_edges.emplace_back(i, j);
+        }
+      }
+    }
+  }
+
+  std::vector<Domain> m_values;
+  std::vector<Edge> m_edges;
+};
+
+} // namespace sparta
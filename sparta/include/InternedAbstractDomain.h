@@ -0,0 +1,243 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <functional>
+#include <memory>
+#include <mutex>
+#include <ostream>
+#include <unordered_set>
+#include <utility>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+namespace interned_impl {
+
+template <typename Domain>
+const std::shared_ptr<const Domain>& top_value() {
+  static const std::shared_ptr<const Domain> top =
+      std::make_shared<const Domain>(Domain::top());
+  return top;
+}
+
+template <typename Domain>
+const std::shared_ptr<const Domain>& bottom_value() {
+  static const std::shared_ptr<const Domain> bottom =
+      std::make_shared<const Domain>(Domain::bottom());
+  return bottom;
+}
+
+} // namespace interned_impl
+
+/*
+ * A table of hash-consed abstract values. Interning two equal values returns
+ * the same pointer, which makes it possible to share the storage of values
+ * that are repeated many times across the states of an analysis (e.g., the
+ * small sets of live registers in a liveness analysis).
+ *
+ * The Hash parameter must be consistent with Domain::equals(). Interned values
+ * are kept alive until the interner is cleared or destroyed. An interner can
+ * be shared across threads.
+ */
+template <typename Domain, typename Hash = std::hash<Domain>>
+class DomainInterner final {
+ public:
+  std::shared_ptr<const Domain> intern(const Domain& value) {
+    // Top and Bottom are shared among all interners.
+    if (value.is_top()) {
+      return interned_impl::top_value<Domain>();
+    }
+    if (value.is_bottom()) {
+      return interned_impl::bottom_value<Domain>();
+    }
+    std::lock_guard<std::mutex> guard(m_mutex);
+    // We look up the value using a non-owning pointer in order to avoid a
+    // copy when the value has already been interned.
+    auto it = m_table.find(Pointer(Pointer(), &value));
+    if (it != m_table.end()) {
+      return *it;
+    }
+    auto interned = std::make_shared<const Domain>(value);
+    m_table.insert(interned);
+    return interned;
+  }
+
+  size_t size() const {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    return m_table.size();
+  }
+
+  /*
+   * Values that have already been handed out remain valid, but they are no
+   * longer shared with the values interned afterwards.
+   */
+  void clear() {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    m_table.clear();
+  }
+
+ private:
+  using Pointer = std::shared_ptr<const Domain>;
+
+  struct PointerHash {
+    size_t operator()(const Pointer& p) const { return Hash()(*p); }
+  };
+
+  struct PointerEqual {
+    bool operator()(const Pointer& p, const Pointer& q) const {
+      return p->equals(*q);
+    }
+  };
+
+  mutable std::mutex m_mutex;
+  std::unordered_set<Pointer, PointerHash, PointerEqual> m_table;
+};
+
+/*
+ * An abstract domain whose elements are hash-consed values of the underlying
+ * domain. Copying an element only copies a pointer, and the comparison of two
+ * elements interned by the same interner reduces to a pointer comparison. The
+ * other operations are performed on the underlying values and their results
+ * are interned again.
+ *
+ * Sample usage:
+ *
+ *   using Interned = InternedAbstractDomain<LiveRegisters, LiveRegistersHash>;
+ *   auto interner = std::make_shared<Interned::Interner>();
+ *   Interned live(interner, LiveRegisters({v0, v1}));
+ *
+ * The Top and Bottom elements, as well as default-constructed elements (which
+ * are Top), are not attached to any interner. An operation between elements
+ * uses the interner of either operand.
+ */
+template <typename Domain, typename Hash = std::hash<Domain>>
+class InternedAbstractDomain final
+    : public AbstractDomain<InternedAbstractDomain<Domain, Hash>> {
+ public:
+  using Interner = DomainInterner<Domain, Hash>;
+
+  InternedAbstractDomain() : m_value(interned_impl::top_value<Domain>()) {}
+
+  InternedAbstractDomain(std::shared_ptr<Interner> interner,
+                         const Domain& value)
+      : m_interner(std::move(interner)),
+        m_value(m_interner->intern(value)) {}
+
+  const Domain& get() const { return *m_value; }
+
+  const std::shared_ptr<Interner>& interner() const { return m_interner; }
+
+  /*
+   * Applies the operation to a copy of the underlying value and interns the
+   * result.
+   */
+  InternedAbstractDomain& update(std::function<void(Domain*)> operation) {
+    Domain value = *m_value;
+    operation(&value);
+    set_value(m_interner, value);
+    return *this;
+  }
+
+  bool is_bottom() const override { return m_value->is_bottom(); }
+
+  bool is_top() const override { return m_value->is_top(); }
+
+  bool leq(const InternedAbstractDomain& other) const override {
+    if (m_value == other.m_value) {
+      return true;
+    }
+    return m_value->leq(*other.m_value);
+  }
+
+  bool equals(const InternedAbstractDomain& other) const override {
+    if (m_value == other.m_value) {
+      return true;
+    }
+    if (m_interner != nullptr && m_interner == other.m_interner) {
+      // Both values have been hash-consed by the same interner.
+      return false;
+    }
+    return m_value->equals(*other.m_value);
+  }
+
+  void set_to_bottom() override {
+    m_value = interned_impl::bottom_value<Domain>();
+  }
+
+  void set_to_top() override { m_value = interned_impl::top_value<Domain>(); }
+
+  void join_with(const InternedAbstractDomain& other) override {
+    binary_operation(other,
+                     [](Domain* x, const Domain& y) { x->join_with(y); });
+  }
+
+  void widen_with(const InternedAbstractDomain& other) override {
+    binary_operation(other,
+                     [](Domain* x, const Domain& y) { x->widen_with(y); });
+  }
+
+  void meet_with(const InternedAbstractDomain& other) override {
+    binary_operation(other,
+                     [](Domain* x, const Domain& y) { x->meet_with(y); });
+  }
+
+  void narrow_with(const InternedAbstractDomain& other) override {
+    binary_operation(other,
+                     [](Domain* x, const Domain& y) { x->narrow_with(y); });
+  }
+
+  static InternedAbstractDomain bottom() {
+    InternedAbstractDomain result;
+    result.set_to_bottom();
+    return result;
+  }
+
+  static InternedAbstractDomain top() { return InternedAbstractDomain(); }
+
+ private:
+  void set_value(const std::shared_ptr<Interner>& interner,
+                 const Domain& value) {
+    m_interner = interner;
+    if (m_interner != nullptr) {
+      m_value = m_interner->intern(value);
+    } else if (value.is_top()) {
+      m_value = interned_impl::top_value<Domain>();
+    } else if (value.is_bottom()) {
+      m_value = interned_impl::bottom_value<Domain>();
+    } else {
+      m_value = std::make_shared<const Domain>(value);
+    }
+  }
+
+  void binary_operation(const InternedAbstractDomain& other,
+                        std::function<void(Domain*, const Domain&)> operation) {
+    if (m_value == other.m_value) {
+      // All the binary operations are idempotent.
+      return;
+    }
+    Domain value = *m_value;
+    operation(&value, *other.m_value);
+    set_value(m_interner != nullptr ? m_interner : other.m_interner, value);
+  }
+
+  std::shared_ptr<Interner> m_interner;
+  std::shared_ptr<const Domain> m_value;
+};
+
+} // namespace sparta
+
+template <typename Domain, typename Hash>
+inline std::ostream& operator<<(
+    std::ostream& o,
+    const typename sparta::InternedAbstractDomain<Domain, Hash>& interned) {
+  o << interned.get();
+  return o;
+}
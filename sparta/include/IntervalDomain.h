@@ -7,11 +7,17 @@
 
 #pragma once
 
+#include <algorithm>
+#include <boost/optional.hpp>
 #include <cassert>
+#include <iterator>
 #include <limits>
 #include <ostream>
+#include <type_traits>
 
 #include "AbstractDomain.h"
+#include "Exceptions.h"
+#include "NumericalDomainOps.h"
 
 namespace sparta {
 
@@ -39,6 +45,13 @@ namespace sparta {
  * This property is exploited for the implementation of is_bottom() and means
  * that code that assumes a sensible ordering of bounds must be guarded by a
  * check for `!is_bottom()`.
+ *
+ * The checked constructors try_finite(), try_bounded_below() and
+ * try_bounded_above() return none instead of asserting when the bounds do not
+ * describe a valid interval, which is convenient when the bounds are computed
+ * from untrusted input. When SPARTA_CHECK_INVARIANTS is defined (see
+ * Exceptions.h), every lattice operation verifies that its result is
+ * normalized.
  */
 template <typename Num>
 class IntervalDomain final : public AbstractDomain<IntervalDomain<Num>> {
@@ -71,6 +84,30 @@ class IntervalDomain final : public AbstractDomain<IntervalDomain<Num>> {
     return {MIN, ub};
   }
 
+  /* Returns none if [lb, ub] is not a finite interval. */
+  static boost::optional<IntervalDomain> try_finite(Num lb, Num ub) {
+    if (!(MIN < lb && lb <= ub && ub < MAX)) {
+      return boost::none;
+    }
+    return IntervalDomain(lb, ub);
+  }
+
+  /* Returns none if lb is -inf. */
+  static boost::optional<IntervalDomain> try_bounded_below(Num lb) {
+    if (lb == MIN) {
+      return boost::none;
+    }
+    return IntervalDomain(lb, MAX);
+  }
+
+  /* Returns none if ub is +inf. */
+  static boost::optional<IntervalDomain> try_bounded_above(Num ub) {
+    if (ub == MAX) {
+      return boost::none;
+    }
+    return IntervalDomain(MIN, ub);
+  }
+
   /* [max, +inf] */
   static IntervalDomain high() { return {MAX, MAX}; }
 
@@ -102,9 +139,12 @@ class IntervalDomain final : public AbstractDomain<IntervalDomain<Num>> {
     if (that.is_bottom()) {
       set_to_bottom();
     } else if (!is_bottom()) {
-      m_lb = m_lb == MIN ? m_lb : clamped_add(m_lb, that.m_lb);
-      m_ub = m_ub == MAX ? m_ub : clamped_add(m_ub, that.m_ub);
+      m_lb = m_lb == MIN || that.m_lb == MIN ? MIN
+                                             : clamped_add(m_lb, that.m_lb);
+      m_ub = m_ub == MAX || that.m_ub == MAX ? MAX
+                                             : clamped_add(m_ub, that.m_ub);
     }
+    check_invariants();
     return *this;
   }
 
@@ -116,6 +156,112 @@ class IntervalDomain final : public AbstractDomain<IntervalDomain<Num>> {
     return cpy;
   }
 
+  /*
+   *   [a,b] - [c,d] = [a - d, b - c]
+   */
+  IntervalDomain& operator-=(const IntervalDomain& that) {
+    if (that.is_bottom()) {
+      set_to_bottom();
+    } else if (!is_bottom()) {
+      Num lb = m_lb == MIN || that.m_ub == MAX ? MIN
+                                               : clamped_sub(m_lb, that.m_ub);
+      Num ub = m_ub == MAX || that.m_lb == MIN ? MAX
+                                               : clamped_sub(m_ub, that.m_lb);
+      m_lb = lb;
+      m_ub = ub;
+    }
+    check_invariants();
+    return *this;
+  }
+
+  IntervalDomain& operator-=(Num b) { return *this -= {b, b}; }
+
+  IntervalDomain operator-(const IntervalDomain& that) const {
+    auto cpy = *this;
+    cpy -= that;
+    return cpy;
+  }
+
+  /*
+   *   -[a,b] = [-b, -a]
+   */
+  IntervalDomain operator-() const {
+    static_assert(std::is_signed<Num>::value, "expecting signed bounds.");
+    return IntervalDomain(0, 0) - *this;
+  }
+
+  /*
+   *   [a,b] * [c,d] = [min(ac, ad, bc, bd), max(ac, ad, bc, bd)]
+   *
+   * where the products of the infinite bounds follow the rule of signs, and
+   * the product of 0 and an infinite bound is 0.
+   */
+  IntervalDomain& operator*=(const IntervalDomain& that) {
+    if (is_bottom() || that.is_bottom()) {
+      set_to_bottom();
+      return *this;
+    }
+    const Bound products[] = {
+        multiply(lower(), that.lower()), multiply(lower(), that.upper()),
+        multiply(upper(), that.lower()), multiply(upper(), that.upper())};
+    m_lb = std::min_element(std::begin(products), std::end(products))->to_num();
+    m_ub = std::max_element(std::begin(products), std::end(products))->to_num();
+    check_invariants();
+    return *this;
+  }
+
+  IntervalDomain& operator*=(Num b) { return *this *= {b, b}; }
+
+  IntervalDomain operator*(const IntervalDomain& that) const {
+    auto cpy = *this;
+    cpy *= that;
+    return cpy;
+  }
+
+  /*
+   *   [a,b] / [c,d] = [min(a/c, a/d, b/c, b/d), max(a/c, a/d, b/c, b/d)]
+   *
+   * when 0 is not in [c,d], where the division truncates toward zero. A
+   * finite bound divided by an infinite one is 0, and an infinite bound divided
+   * by any bound is the infinity of their sign. Otherwise, the result is the
+   * join of the divisions by the negative and the positive parts of [c,d],
+   * and the division by [0,0] has no result.
+   */
+  IntervalDomain& operator/=(const IntervalDomain& that) {
+    if (is_bottom() || that.is_bottom()) {
+      set_to_bottom();
+      return *this;
+    }
+    if (that.m_lb <= 0 && 0 <= that.m_ub) {
+      auto result = bottom();
+      if (that.m_lb < 0) {
+        result.join_with(*this / IntervalDomain(that.m_lb, Num(-1)));
+      }
+      if (that.m_ub > 0) {
+        result.join_with(*this / IntervalDomain(1, that.m_ub));
+      }
+      *this = result;
+      return *this;
+    }
+    const Bound quotients[] = {
+        divide(lower(), that.lower()), divide(lower(), that.upper()),
+        divide(upper(), that.lower()), divide(upper(), that.upper())};
+    m_lb = std::min_element(std::begin(quotients), std::end(quotients))
+               ->to_num();
+    m_ub = std::max_element(std::begin(quotients), std::end(quotients))
+               ->to_num();
+    check_invariants();
+    return *this;
+  }
+
+  IntervalDomain& operator/=(Num b) { return *this /= {b, b}; }
+
+  IntervalDomain operator/(const IntervalDomain& that) const {
+    auto cpy = *this;
+    cpy /= that;
+    return cpy;
+  }
+
   bool is_bottom() const override { return m_lb > m_ub; }
   bool is_top() const override { return m_lb == MIN && m_ub == MAX; }
 
@@ -145,6 +291,7 @@ class IntervalDomain final : public AbstractDomain<IntervalDomain<Num>> {
   void join_with(const IntervalDomain& that) override {
     m_lb = std::min(m_lb, that.m_lb);
     m_ub = std::max(m_ub, that.m_ub);
+    check_invariants();
   }
 
   /*
@@ -166,6 +313,48 @@ class IntervalDomain final : public AbstractDomain<IntervalDomain<Num>> {
     if (m_ub < that.m_ub) {
       m_ub = MAX;
     }
+    check_invariants();
+  }
+
+  /*
+   * Widening with thresholds, which extrapolates an unstable bound to the
+   * nearest threshold beyond it instead of infinity:
+   *
+   *   [a,b] W [c,d] = [ c < a ? max{t in T | t <= c} : a
+   *                   , b < d ? min{t in T | t >= d} : b]
+   *
+   * where the maximum and minimum of the empty set are -inf and +inf
+   * respectively. The thresholds T must be sorted in increasing order, e.g.,
+   * a std::set<Num> or a sorted std::vector<Num>. Since there are finitely
+   * many thresholds, this is still a widening operator. The thresholds are
+   * typically the constants that appear in the comparisons of the program,
+   * which a fixpoint iterator can use in its extrapolate() method:
+   *
+   *   current_state->widen_with_thresholds(new_state, m_thresholds);
+   */
+  template <typename Thresholds>
+  void widen_with_thresholds(const IntervalDomain& that,
+                             const Thresholds& thresholds) {
+    if (is_bottom()) {
+      *this = that;
+      return;
+    }
+    if (that.is_bottom()) {
+      return;
+    }
+
+    if (that.m_lb < m_lb) {
+      auto it = std::upper_bound(thresholds.begin(), thresholds.end(),
+                                 that.m_lb);
+      m_lb = it == thresholds.begin() ? MIN : *std::prev(it);
+    }
+
+    if (m_ub < that.m_ub) {
+      auto it = std::lower_bound(thresholds.begin(), thresholds.end(),
+                                 that.m_ub);
+      m_ub = it == thresholds.end() ? MAX : *it;
+    }
+    check_invariants();
   }
 
   /*
@@ -181,6 +370,7 @@ class IntervalDomain final : public AbstractDomain<IntervalDomain<Num>> {
       // Normalize the representation of bottom to simplify equality.
       set_to_bottom();
     }
+    check_invariants();
   }
 
   /*
@@ -207,6 +397,7 @@ class IntervalDomain final : public AbstractDomain<IntervalDomain<Num>> {
       // Normalize the representation of bottom to simplify equality.
       set_to_bottom();
     }
+    check_invariants();
   }
 
  private:
@@ -215,6 +406,14 @@ class IntervalDomain final : public AbstractDomain<IntervalDomain<Num>> {
 
   IntervalDomain(Num lb, Num ub) : m_lb(lb), m_ub(ub) {}
 
+  /*
+   * Bottom has a unique representation, so that equals() can compare the
+   * bounds directly.
+   */
+  void check_invariants() const {
+    SPARTA_INVARIANT(!is_bottom() || (m_lb == MAX && m_ub == MIN));
+  }
+
   /*
    * Addition with overflow and underflow protection.
    */
@@ -231,9 +430,83 @@ class IntervalDomain final : public AbstractDomain<IntervalDomain<Num>> {
 
     return a + b;
   }
-};
 
-} // namespace sparta
+  /*
+   * Subtraction with overflow and underflow protection.
+   */
+  static Num clamped_sub(Num a, Num b) {
+    // a - b < MIN
+    if (b > 0 && a < MIN + b) {
+      return MIN;
+    }
+
+    // a - b > MAX
+    if (b < 0 && a > MAX + b) {
+      return MAX;
+    }
+
+    return a - b;
+  }
+
+  /*
+   * A bound extended with infinities, which are only used to compute the
+   * bounds of a product.
+   */
+  struct Bound {
+    Num value;
+    // -1 for -inf, 1 for +inf and 0 for a finite value.
+    int infinity;
+
+    bool operator<(const Bound& that) const {
+      return infinity != that.infinity ? infinity < that.infinity
+                                       : infinity == 0 && value < that.value;
+    }
+
+    int sign() const {
+      return infinity != 0 ? infinity : value > 0 ? 1 : value < 0 ? -1 : 0;
+    }
+
+    Num to_num() const {
+      return infinity < 0 ? MIN : infinity > 0 ? MAX : value;
+    }
+  };
+
+  Bound lower() const { return {m_lb, m_lb == MIN ? -1 : 0}; }
+
+  Bound upper() const { return {m_ub, m_ub == MAX ? 1 : 0}; }
+
+  /*
+   * A product that overflows is approximated by the infinity of its sign.
+   */
+  static Bound multiply(const Bound& x, const Bound& y) {
+    int sign = x.sign() * y.sign();
+    if (sign == 0) {
+      return {0, 0};
+    }
+    if (x.infinity != 0 || y.infinity != 0) {
+      return {0, sign};
+    }
+    Num a = x.value;
+    Num b = y.value;
+    bool overflow = a > 0 ? (b > 0 ? a > MAX / b : b < MIN / a)
+                          : (b > 0 ? a < MIN / b : b < MAX / a);
+    return overflow ? Bound{0, sign} : Bound{Num(a * b), 0};
+  }
+
+  /*
+   * The divisor must not be 0. An infinite bound divided by an infinite one is
+   * the infinity of their sign.
+   */
+  static Bound divide(const Bound& x, const Bound& y) {
+    if (x.infinity != 0) {
+      return {0, x.sign() * y.sign()};
+    }
+    if (y.infinity != 0) {
+      return {0, 0};
+    }
+    return {Num(x.value / y.value), 0};
+  }
+};
 
 template <typename Num>
 inline std::ostream& operator<<(std::ostream& o,
@@ -264,3 +537,419 @@ inline std::ostream& operator<<(std::ostream& o,
 
   return o << "]";
 }
+
+/*
+ * Refinement of intervals on a branch condition. Each function tightens both
+ * operands of the comparison, e.g., on the true branch of `if (x < y)`:
+ *
+ *   refine_lt(&x, &y);
+ *
+ * The operands are set to Bottom if the condition cannot hold, i.e., if the
+ * branch is not feasible. The infinite bounds are never refined, since
+ * [-inf, min] and [max, +inf] approximate intervals that extend beyond the
+ * range of Num.
+ */
+namespace interval_impl {
+
+template <typename Num>
+Num successor(Num n) {
+  using Interval = IntervalDomain<Num>;
+  return n == Interval::MIN || n == Interval::MAX ? n : n + 1;
+}
+
+template <typename Num>
+Num predecessor(Num n) {
+  using Interval = IntervalDomain<Num>;
+  return n == Interval::MIN || n == Interval::MAX ? n : n - 1;
+}
+
+template <typename Num>
+IntervalDomain<Num> at_most(Num ub) {
+  return ub == IntervalDomain<Num>::MAX ? IntervalDomain<Num>::top()
+         : ub == IntervalDomain<Num>::MIN
+             ? IntervalDomain<Num>::low()
+             : IntervalDomain<Num>::bounded_above(ub);
+}
+
+template <typename Num>
+IntervalDomain<Num> at_least(Num lb) {
+  return lb == IntervalDomain<Num>::MIN ? IntervalDomain<Num>::top()
+         : lb == IntervalDomain<Num>::MAX
+             ? IntervalDomain<Num>::high()
+             : IntervalDomain<Num>::bounded_below(lb);
+}
+
+template <typename Num>
+bool is_constant(const IntervalDomain<Num>& i) {
+  using Interval = IntervalDomain<Num>;
+  return !i.is_bottom() && i.lower_bound() == i.upper_bound() &&
+         i.lower_bound() != Interval::MIN && i.lower_bound() != Interval::MAX;
+}
+
+// Excludes the constant c from the interval, which is only possible when c is
+// one of its bounds.
+template <typename Num>
+void exclude(IntervalDomain<Num>* i, Num c) {
+  if (i->is_bottom()) {
+    return;
+  }
+  if (i->lower_bound() == c) {
+    i->meet_with(at_least(successor(c)));
+  } else if (i->upper_bound() == c) {
+    i->meet_with(at_most(predecessor(c)));
+  }
+}
+
+} // namespace interval_impl
+
+/* x <= y */
+template <typename Num>
+void refine_le(IntervalDomain<Num>* x, IntervalDomain<Num>* y) {
+  if (x->is_bottom() || y->is_bottom()) {
+    x->set_to_bottom();
+    y->set_to_bottom();
+    return;
+  }
+  auto x_lb = x->lower_bound();
+  x->meet_with(interval_impl::at_most(y->upper_bound()));
+  y->meet_with(interval_impl::at_least(x_lb));
+  if (x->is_bottom() || y->is_bottom()) {
+    x->set_to_bottom();
+    y->set_to_bottom();
+  }
+}
+
+/* x < y */
+template <typename Num>
+void refine_lt(IntervalDomain<Num>* x, IntervalDomain<Num>* y) {
+  if (x->is_bottom() || y->is_bottom()) {
+    x->set_to_bottom();
+    y->set_to_bottom();
+    return;
+  }
+  auto x_lb = x->lower_bound();
+  x->meet_with(
+      interval_impl::at_most(interval_impl::predecessor(y->upper_bound())));
+  y->meet_with(interval_impl::at_least(interval_impl::successor(x_lb)));
+  if (x->is_bottom() || y->is_bottom()) {
+    x->set_to_bottom();
+    y->set_to_bottom();
+  }
+}
+
+/* x == y */
+template <typename Num>
+void refine_eq(IntervalDomain<Num>* x, IntervalDomain<Num>* y) {
+  x->meet_with(*y);
+  *y = *x;
+}
+
+/* x != y */
+template <typename Num>
+void refine_ne(IntervalDomain<Num>* x, IntervalDomain<Num>* y) {
+  if (interval_impl::is_constant(*y)) {
+    interval_impl::exclude(x, y->lower_bound());
+  } else if (interval_impl::is_constant(*x)) {
+    interval_impl::exclude(y, x->lower_bound());
+  }
+  if (x->is_bottom() || y->is_bottom()) {
+    x->set_to_bottom();
+    y->set_to_bottom();
+  }
+}
+
+/*
+ * The same refinements on the variables of an abstract environment that maps
+ * variables to intervals, e.g., in the analyze_edge() method of a fixpoint
+ * iterator:
+ *
+ *   Environment analyze_edge(const EdgeId& e, const Environment& env) {
+ *     Environment result = env;
+ *     if (is_true_branch_of_lt(e)) {
+ *       refine_lt(result, x, y);
+ *     }
+ *     return result;
+ *   }
+ *
+ * The environment is set to Bottom if the branch is not feasible.
+ */
+namespace interval_impl {
+
+template <typename Environment, typename Variable, typename Refine>
+Environment& refine(Environment& env,
+                    const Variable& x,
+                    const Variable& y,
+                    bool reflexive,
+                    Refine&& refine_intervals) {
+  if (env.is_bottom()) {
+    return env;
+  }
+  if (x == y) {
+    if (!reflexive) {
+      env.set_to_bottom();
+    }
+    return env;
+  }
+  auto x_value = env.get(x);
+  auto y_value = env.get(y);
+  refine_intervals(&x_value, &y_value);
+  env.set(x, x_value);
+  env.set(y, y_value);
+  return env;
+}
+
+} // namespace interval_impl
+
+/* x <= y */
+template <typename Environment, typename Variable>
+Environment& refine_le(Environment& env, const Variable& x, const Variable& y) {
+  return interval_impl::refine(
+      env, x, y, /* reflexive */ true, [](auto* a, auto* b) {
+        refine_le(a, b);
+      });
+}
+
+/* x < y */
+template <typename Environment, typename Variable>
+Environment& refine_lt(Environment& env, const Variable& x, const Variable& y) {
+  return interval_impl::refine(
+      env, x, y, /* reflexive */ false, [](auto* a, auto* b) {
+        refine_lt(a, b);
+      });
+}
+
+/* x >= y */
+template <typename Environment, typename Variable>
+Environment& refine_ge(Environment& env, const Variable& x, const Variable& y) {
+  return refine_le(env, y, x);
+}
+
+/* x > y */
+template <typename Environment, typename Variable>
+Environment& refine_gt(Environment& env, const Variable& x, const Variable& y) {
+  return refine_lt(env, y, x);
+}
+
+/* x == y */
+template <typename Environment, typename Variable>
+Environment& refine_eq(Environment& env, const Variable& x, const Variable& y) {
+  return interval_impl::refine(
+      env, x, y, /* reflexive */ true, [](auto* a, auto* b) {
+        refine_eq(a, b);
+      });
+}
+
+/* x != y */
+template <typename Environment, typename Variable>
+Environment& refine_ne(Environment& env, const Variable& x, const Variable& y) {
+  return interval_impl::refine(
+      env, x, y, /* reflexive */ false, [](auto* a, auto* b) {
+        refine_ne(a, b);
+      });
+}
+
+namespace interval_impl {
+
+/*
+ * [lb, ub], where lb and ub may be infinite.
+ */
+template <typename Num>
+IntervalDomain<Num> range(Num lb, Num ub) {
+  return at_least(lb).meet(at_most(ub));
+}
+
+/*
+ * The smallest number of the form 2^k - 1 that is greater than or equal to a
+ * non-negative number.
+ */
+template <typename Num>
+Num all_ones_above(Num n) {
+  for (int shift = 1; shift < std::numeric_limits<Num>::digits; shift *= 2) {
+    n |= n >> shift;
+  }
+  return n;
+}
+
+} // namespace interval_impl
+
+template <typename Num>
+struct ArithmeticDomainOps<IntervalDomain<Num>> {
+  using Domain = IntervalDomain<Num>;
+
+  template <typename Constant>
+  static Domain literal(const Constant& value) {
+    return interval_impl::range(static_cast<Num>(value),
+                                static_cast<Num>(value));
+  }
+
+  static Domain neg(const Domain& x) { return -x; }
+
+  static Domain add(const Domain& x, const Domain& y) { return x + y; }
+
+  static Domain sub(const Domain& x, const Domain& y) { return x - y; }
+
+  static Domain mul(const Domain& x, const Domain& y) { return x * y; }
+
+  static Domain div(const Domain& x, const Domain& y) { return x / y; }
+
+  /*
+   * The result of a bitwise and is non-negative and bounded by any
+   * non-negative operand.
+   */
+  static Domain bit_and(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    if (is_non_negative(x) && is_non_negative(y)) {
+      return interval_impl::range(Num(0),
+                                  std::min(x.upper_bound(), y.upper_bound()));
+    }
+    if (is_non_negative(x)) {
+      return interval_impl::range(Num(0), x.upper_bound());
+    }
+    if (is_non_negative(y)) {
+      return interval_impl::range(Num(0), y.upper_bound());
+    }
+    return Domain::top();
+  }
+
+  /*
+   * The result of a bitwise or of non-negative operands is bounded below by
+   * both operands, and above by the smallest 2^k - 1 that bounds both.
+   */
+  static Domain bit_or(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    if (!is_non_negative(x) || !is_non_negative(y)) {
+      return Domain::top();
+    }
+    return interval_impl::range(std::max(x.lower_bound(), y.lower_bound()),
+                                all_ones_above(x, y));
+  }
+
+  static Domain bit_xor(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    if (!is_non_negative(x) || !is_non_negative(y)) {
+      return Domain::top();
+    }
+    return interval_impl::range(Num(0), all_ones_above(x, y));
+  }
+
+  /*
+   * Shifting left by a constant amount is a multiplication by a power of 2.
+   */
+  static Domain shl(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    if (!interval_impl::is_constant(y) ||
+        !ops_impl::is_valid_shift(y.lower_bound()) ||
+        y.lower_bound() >= std::numeric_limits<Num>::digits) {
+      return Domain::top();
+    }
+    Num factor = Num(Num(1) << y.lower_bound());
+    return x * interval_impl::range(factor, factor);
+  }
+
+  /*
+   * The arithmetic right shift by a constant amount is monotonic. Shifting a
+   * non-negative number right by any amount yields a smaller non-negative
+   * number.
+   */
+  static Domain shr(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    if (interval_impl::is_constant(y) &&
+        ops_impl::is_valid_shift(y.lower_bound())) {
+      Num lb = x.lower_bound();
+      Num ub = x.upper_bound();
+      Num amount = y.lower_bound();
+      return interval_impl::range(lb == Domain::MIN ? lb : Num(lb >> amount),
+                                  ub == Domain::MAX ? ub : Num(ub >> amount));
+    }
+    if (is_non_negative(x) && is_non_negative(y)) {
+      return interval_impl::range(Num(0), x.upper_bound());
+    }
+    return Domain::top();
+  }
+
+ private:
+  static bool is_non_negative(const Domain& x) {
+    return x.lower_bound() != Domain::MIN && x.lower_bound() >= 0;
+  }
+
+  static Num all_ones_above(const Domain& x, const Domain& y) {
+    Num ub = std::max(x.upper_bound(), y.upper_bound());
+    return ub == Domain::MAX ? ub : interval_impl::all_ones_above(ub);
+  }
+};
+
+/*
+ * A comparison holds (resp. does not hold) if it holds (resp. does not hold)
+ * for all the values of the intervals. The infinite bounds never prove a
+ * comparison.
+ */
+template <typename Num>
+struct CompareDomainOps<IntervalDomain<Num>>
+    : DerivedCompareDomainOps<CompareDomainOps<IntervalDomain<Num>>,
+                              IntervalDomain<Num>> {
+  using Domain = IntervalDomain<Num>;
+
+  static BooleanDomain eq(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return BooleanDomain::bottom();
+    }
+    if (interval_impl::is_constant(x) && x.equals(y)) {
+      return BooleanDomain(true);
+    }
+    if (x.meet(y).is_bottom()) {
+      return BooleanDomain(false);
+    }
+    return BooleanDomain::top();
+  }
+
+  static BooleanDomain lt(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return BooleanDomain::bottom();
+    }
+    if (is_finite_ub(x) && is_finite_lb(y) &&
+        x.upper_bound() < y.lower_bound()) {
+      return BooleanDomain(true);
+    }
+    if (is_finite_lb(x) && is_finite_ub(y) &&
+        x.lower_bound() >= y.upper_bound()) {
+      return BooleanDomain(false);
+    }
+    return BooleanDomain::top();
+  }
+
+  static BooleanDomain le(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return BooleanDomain::bottom();
+    }
+    if (is_finite_ub(x) && is_finite_lb(y) &&
+        x.upper_bound() <= y.lower_bound()) {
+      return BooleanDomain(true);
+    }
+    if (is_finite_lb(x) && is_finite_ub(y) &&
+        x.lower_bound() > y.upper_bound()) {
+      return BooleanDomain(false);
+    }
+    return BooleanDomain::top();
+  }
+
+ private:
+  static bool is_finite_lb(const Domain& x) {
+    return x.lower_bound() != Domain::MIN;
+  }
+
+  static bool is_finite_ub(const Domain& x) {
+    return x.upper_bound() != Domain::MAX;
+  }
+};
+
+} // namespace sparta
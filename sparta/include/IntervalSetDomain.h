@@ -0,0 +1,524 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <boost/optional.hpp>
+#include <cstddef>
+#include <initializer_list>
+#include <iterator>
+#include <ostream>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+#include "IntervalDomain.h"
+#include "NumericalDomainOps.h"
+
+namespace sparta {
+
+/*
+ * Unions of at most MaxIntervals disjoint intervals, e.g., {[0, 3], [7, 7],
+ * [10, +inf]}. This is more precise than a single interval for the values that
+ * are tested against several constants, which is typical of switch
+ * statements: on the default branch of a switch over cases 1, 2 and 5,
+ * excluding the cases from [0, 10] yields {[0, 0], [3, 4], [6, 10]} instead of
+ * [0, 10].
+ *
+ * The intervals are kept sorted, and overlapping or adjacent intervals are
+ * merged. A set that would need more than MaxIntervals intervals, e.g., as
+ * the result of a join or an arithmetic operation, is collapsed to its hull,
+ * i.e., the smallest interval that contains all of them. The meet is exact
+ * unless the intersection needs more than MaxIntervals intervals.
+ *
+ * As in IntervalDomain, MIN and MAX are the infinite bounds. Bottom is the
+ * empty union and Top is the single interval [-inf, +inf].
+ *
+ * The widening extrapolates the bounds of the hull as the interval widening
+ * does, and collapses the set to its hull when an interval that is strictly
+ * inside the hull grows, since otherwise the intervals could grow towards each
+ * other indefinitely. The narrowing only refines the infinite bounds of the
+ * hull.
+ */
+template <typename Num, size_t MaxIntervals>
+class IntervalSetDomain final
+    : public AbstractDomain<IntervalSetDomain<Num, MaxIntervals>> {
+  static_assert(MaxIntervals > 0, "MaxIntervals must be positive");
+
+ public:
+  using Interval = IntervalDomain<Num>;
+
+  static constexpr Num MIN = Interval::MIN;
+  static constexpr Num MAX = Interval::MAX;
+
+  /*
+   * The default constructor produces the Top value.
+   */
+  IntervalSetDomain() : m_intervals({Interval::top()}) {}
+
+  /* implicit */ IntervalSetDomain(const Interval& interval) {
+    if (!interval.is_bottom()) {
+      m_intervals.push_back(interval);
+    }
+  }
+
+  IntervalSetDomain(std::initializer_list<Interval> intervals) {
+    for (const auto& interval : intervals) {
+      if (!interval.is_bottom()) {
+        m_intervals.push_back(interval);
+      }
+    }
+    normalize();
+  }
+
+  static IntervalSetDomain bottom() { return IntervalSetDomain(Intervals()); }
+
+  static IntervalSetDomain top() { return IntervalSetDomain(); }
+
+  static IntervalSetDomain constant(Num value) {
+    return IntervalSetDomain(interval_impl::range(value, value));
+  }
+
+  static IntervalSetDomain finite(Num lb, Num ub) {
+    return IntervalSetDomain(Interval::finite(lb, ub));
+  }
+
+  /*
+   * The disjoint intervals in increasing order, which is empty for Bottom.
+   */
+  const std::vector<Interval>& intervals() const { return m_intervals; }
+
+  /*
+   * The smallest interval that contains the set.
+   */
+  Interval hull() const {
+    if (m_intervals.empty()) {
+      return Interval::bottom();
+    }
+    return interval_impl::range(m_intervals.front().lower_bound(),
+                                m_intervals.back().upper_bound());
+  }
+
+  boost::optional<Num> get_constant() const {
+    if (m_intervals.size() == 1 &&
+        interval_impl::is_constant(m_intervals.front())) {
+      return m_intervals.front().lower_bound();
+    }
+    return boost::none;
+  }
+
+  bool contains(Num value) const {
+    auto it = first_not_below(value);
+    return it != m_intervals.end() && it->lower_bound() <= value;
+  }
+
+  /*
+   * Removes a value from the set, e.g., on the branch where a variable is
+   * known to differ from a constant. The infinite bounds are never excluded,
+   * since they approximate the values beyond them.
+   */
+  IntervalSetDomain& exclude(Num value) {
+    if (value == MIN || value == MAX) {
+      return *this;
+    }
+    auto it = first_not_below(value);
+    if (it == m_intervals.end() || it->lower_bound() > value) {
+      return *this;
+    }
+    Num lb = it->lower_bound();
+    Num ub = it->upper_bound();
+    it = m_intervals.erase(it);
+    if (value < ub) {
+      it = m_intervals.insert(it, interval_impl::range(Num(value + 1), ub));
+    }
+    if (lb < value) {
+      m_intervals.insert(it, interval_impl::range(lb, Num(value - 1)));
+    }
+    normalize();
+    return *this;
+  }
+
+  IntervalSetDomain operator-() const {
+    return lift(*this, [](const Interval& x) { return -x; });
+  }
+
+  IntervalSetDomain operator+(const IntervalSetDomain& that) const {
+    return lift(*this, that, [](const Interval& x, const Interval& y) {
+      return x + y;
+    });
+  }
+
+  IntervalSetDomain operator-(const IntervalSetDomain& that) const {
+    return lift(*this, that, [](const Interval& x, const Interval& y) {
+      return x - y;
+    });
+  }
+
+  IntervalSetDomain operator*(const IntervalSetDomain& that) const {
+    return lift(*this, that, [](const Interval& x, const Interval& y) {
+      return x * y;
+    });
+  }
+
+  /*
+   * Applies an operation over intervals to each interval of the set and
+   * returns the union of the results.
+   */
+  template <typename Operation>
+  static IntervalSetDomain lift(const IntervalSetDomain& x,
+                                Operation&& operation) {
+    Intervals result;
+    for (const auto& i : x.m_intervals) {
+      Interval r = operation(i);
+      if (!r.is_bottom()) {
+        result.push_back(r);
+      }
+    }
+    return IntervalSetDomain(std::move(result));
+  }
+
+  /*
+   * Applies a binary operation over intervals to each pair of intervals of
+   * the operands and returns the union of the results.
+   */
+  template <typename Operation>
+  static IntervalSetDomain lift(const IntervalSetDomain& x,
+                                const IntervalSetDomain& y,
+                                Operation&& operation) {
+    Intervals result;
+    for (const auto& i : x.m_intervals) {
+      for (const auto& j : y.m_intervals) {
+        Interval r = operation(i, j);
+        if (!r.is_bottom()) {
+          result.push_back(r);
+        }
+      }
+    }
+    return IntervalSetDomain(std::move(result));
+  }
+
+  bool is_bottom() const override { return m_intervals.empty(); }
+
+  bool is_top() const override {
+    return m_intervals.size() == 1 && m_intervals.front().is_top();
+  }
+
+  bool leq(const IntervalSetDomain& other) const override {
+    // Since the intervals of `other` are disjoint and not adjacent, each
+    // interval of this set must be included in one of them.
+    auto it = other.m_intervals.begin();
+    for (const auto& interval : m_intervals) {
+      while (it != other.m_intervals.end() &&
+             it->upper_bound() < interval.lower_bound()) {
+        ++it;
+      }
+      if (it == other.m_intervals.end() || !interval.leq(*it)) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  bool equals(const IntervalSetDomain& other) const override {
+    return std::equal(m_intervals.begin(), m_intervals.end(),
+                      other.m_intervals.begin(), other.m_intervals.end(),
+                      [](const Interval& x, const Interval& y) {
+                        return x.equals(y);
+                      });
+  }
+
+  void set_to_bottom() override { m_intervals.clear(); }
+
+  void set_to_top() override { m_intervals = {Interval::top()}; }
+
+  void join_with(const IntervalSetDomain& other) override {
+    Intervals result;
+    result.reserve(m_intervals.size() + other.m_intervals.size());
+    std::merge(m_intervals.begin(), m_intervals.end(),
+               other.m_intervals.begin(), other.m_intervals.end(),
+               std::back_inserter(result), by_lower_bound);
+    m_intervals = std::move(result);
+    normalize();
+  }
+
+  void widen_with(const IntervalSetDomain& other) override {
+    if (other.leq(*this)) {
+      return;
+    }
+    if (is_bottom()) {
+      *this = other;
+      return;
+    }
+    Interval old_hull = hull();
+    join_with(other);
+    Interval new_hull = hull();
+    if (new_hull.equals(old_hull)) {
+      // Only the intervals inside the hull have grown.
+      m_intervals = {new_hull};
+      return;
+    }
+    Num lb = new_hull.lower_bound() < old_hull.lower_bound()
+                 ? MIN
+                 : new_hull.lower_bound();
+    Num ub = old_hull.upper_bound() < new_hull.upper_bound()
+                 ? MAX
+                 : new_hull.upper_bound();
+    m_intervals.front() = interval_impl::range(
+        lb, m_intervals.front().upper_bound());
+    m_intervals.back() = interval_impl::range(
+        m_intervals.back().lower_bound(), ub);
+  }
+
+  void meet_with(const IntervalSetDomain& other) override {
+    Intervals result;
+    auto it = m_intervals.begin();
+    auto other_it = other.m_intervals.begin();
+    while (it != m_intervals.end() && other_it != other.m_intervals.end()) {
+      Interval intersection = it->meet(*other_it);
+      if (!intersection.is_bottom()) {
+        result.push_back(intersection);
+      }
+      if (it->upper_bound() < other_it->upper_bound()) {
+        ++it;
+      } else {
+        ++other_it;
+      }
+    }
+    m_intervals = std::move(result);
+    normalize();
+  }
+
+  void narrow_with(const IntervalSetDomain& other) override {
+    if (is_bottom()) {
+      return;
+    }
+    IntervalSetDomain refined = this->meet(other);
+    if (refined.is_bottom()) {
+      set_to_bottom();
+      return;
+    }
+    Num lb = m_intervals.front().lower_bound() == MIN
+                 ? refined.m_intervals.front().lower_bound()
+                 : MIN;
+    Num ub = m_intervals.back().upper_bound() == MAX
+                 ? refined.m_intervals.back().upper_bound()
+                 : MAX;
+    meet_with(IntervalSetDomain(interval_impl::range(lb, ub)));
+  }
+
+  friend std::ostream& operator<<(std::ostream& o,
+                                  const IntervalSetDomain& x) {
+    if (x.is_bottom()) {
+      return o << "_|_";
+    }
+    if (x.is_top()) {
+      return o << "T";
+    }
+    o << "{";
+    for (auto it = x.m_intervals.begin(); it != x.m_intervals.end(); ++it) {
+      o << (it == x.m_intervals.begin() ? "" : ", ") << *it;
+    }
+    return o << "}";
+  }
+
+ private:
+  using Intervals = std::vector<Interval>;
+
+  explicit IntervalSetDomain(Intervals intervals)
+      : m_intervals(std::move(intervals)) {
+    normalize();
+  }
+
+  static bool by_lower_bound(const Interval& x, const Interval& y) {
+    return x.lower_bound() < y.lower_bound();
+  }
+
+  /*
+   * The first interval whose upper bound is not below the value.
+   */
+  typename Intervals::const_iterator first_not_below(Num value) const {
+    return std::lower_bound(
+        m_intervals.begin(), m_intervals.end(), value,
+        [](const Interval& x, Num v) { return x.upper_bound() < v; });
+  }
+
+  /*
+   * Sorts the intervals, merges the ones that overlap or are adjacent, and
+   * collapses the set to its hull if there are too many intervals.
+   */
+  void normalize() {
+    if (m_intervals.empty()) {
+      return;
+    }
+    std::sort(m_intervals.begin(), m_intervals.end(), by_lower_bound);
+    Intervals result;
+    result.reserve(m_intervals.size());
+    Num lb = m_intervals.front().lower_bound();
+    Num ub = m_intervals.front().upper_bound();
+    for (const auto& interval : m_intervals) {
+      if (ub == MAX || interval.lower_bound() <= Num(ub + 1)) {
+        ub = std::max(ub, interval.upper_bound());
+        continue;
+      }
+      result.push_back(interval_impl::range(lb, ub));
+      lb = interval.lower_bound();
+      ub = interval.upper_bound();
+    }
+    result.push_back(interval_impl::range(lb, ub));
+    if (result.size() > MaxIntervals) {
+      result = {interval_impl::range(result.front().lower_bound(),
+                                     result.back().upper_bound())};
+    }
+    m_intervals = std::move(result);
+  }
+
+  Intervals m_intervals;
+};
+
+template <typename Num, size_t MaxIntervals>
+struct ArithmeticDomainOps<IntervalSetDomain<Num, MaxIntervals>> {
+  using Domain = IntervalSetDomain<Num, MaxIntervals>;
+  using IntervalOps = ArithmeticDomainOps<typename Domain::Interval>;
+
+  template <typename Constant>
+  static Domain literal(const Constant& value) {
+    return Domain(IntervalOps::literal(value));
+  }
+
+  static Domain neg(const Domain& x) { return -x; }
+
+  static Domain add(const Domain& x, const Domain& y) { return x + y; }
+
+  static Domain sub(const Domain& x, const Domain& y) { return x - y; }
+
+  static Domain mul(const Domain& x, const Domain& y) { return x * y; }
+
+  static Domain div(const Domain& x, const Domain& y) {
+    return Domain::lift(x, y, IntervalOps::div);
+  }
+
+  static Domain bit_and(const Domain& x, const Domain& y) {
+    return Domain::lift(x, y, IntervalOps::bit_and);
+  }
+
+  static Domain bit_or(const Domain& x, const Domain& y) {
+    return Domain::lift(x, y, IntervalOps::bit_or);
+  }
+
+  static Domain bit_xor(const Domain& x, const Domain& y) {
+    return Domain::lift(x, y, IntervalOps::bit_xor);
+  }
+
+  static Domain shl(const Domain& x, const Domain& y) {
+    return Domain::lift(x, y, IntervalOps::shl);
+  }
+
+  static Domain shr(const Domain& x, const Domain& y) {
+    return Domain::lift(x, y, IntervalOps::shr);
+  }
+};
+
+/*
+ * The equality is decided on the sets, and the order comparisons on their
+ * hulls.
+ */
+template <typename Num, size_t MaxIntervals>
+struct CompareDomainOps<IntervalSetDomain<Num, MaxIntervals>>
+    : DerivedCompareDomainOps<
+          CompareDomainOps<IntervalSetDomain<Num, MaxIntervals>>,
+          IntervalSetDomain<Num, MaxIntervals>> {
+  using Domain = IntervalSetDomain<Num, MaxIntervals>;
+  using IntervalOps = CompareDomainOps<typename Domain::Interval>;
+
+  static BooleanDomain eq(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return BooleanDomain::bottom();
+    }
+    if (x.meet(y).is_bottom()) {
+      return BooleanDomain(false);
+    }
+    return IntervalOps::eq(x.hull(), y.hull());
+  }
+
+  static BooleanDomain lt(const Domain& x, const Domain& y) {
+    return IntervalOps::lt(x.hull(), y.hull());
+  }
+
+  static BooleanDomain le(const Domain& x, const Domain& y) {
+    return IntervalOps::le(x.hull(), y.hull());
+  }
+};
+
+/*
+ * Refinement of interval sets on a branch condition, as for intervals in
+ * IntervalDomain.h. The refinements of abstract environments in
+ * IntervalDomain.h also apply to environments that map variables to interval
+ * sets. A disequality with a constant removes it from the other operand.
+ */
+
+/* x <= y */
+template <typename Num, size_t MaxIntervals>
+void refine_le(IntervalSetDomain<Num, MaxIntervals>* x,
+               IntervalSetDomain<Num, MaxIntervals>* y) {
+  if (x->is_bottom() || y->is_bottom()) {
+    x->set_to_bottom();
+    y->set_to_bottom();
+    return;
+  }
+  auto x_lb = x->hull().lower_bound();
+  x->meet_with(interval_impl::at_most(y->hull().upper_bound()));
+  y->meet_with(interval_impl::at_least(x_lb));
+  if (x->is_bottom() || y->is_bottom()) {
+    x->set_to_bottom();
+    y->set_to_bottom();
+  }
+}
+
+/* x < y */
+template <typename Num, size_t MaxIntervals>
+void refine_lt(IntervalSetDomain<Num, MaxIntervals>* x,
+               IntervalSetDomain<Num, MaxIntervals>* y) {
+  if (x->is_bottom() || y->is_bottom()) {
+    x->set_to_bottom();
+    y->set_to_bottom();
+    return;
+  }
+  auto x_lb = x->hull().lower_bound();
+  x->meet_with(interval_impl::at_most(
+      interval_impl::predecessor(y->hull().upper_bound())));
+  y->meet_with(interval_impl::at_least(interval_impl::successor(x_lb)));
+  if (x->is_bottom() || y->is_bottom()) {
+    x->set_to_bottom();
+    y->set_to_bottom();
+  }
+}
+
+/* x == y */
+template <typename Num, size_t MaxIntervals>
+void refine_eq(IntervalSetDomain<Num, MaxIntervals>* x,
+               IntervalSetDomain<Num, MaxIntervals>* y) {
+  x->meet_with(*y);
+  *y = *x;
+}
+
+/* x != y */
+template <typename Num, size_t MaxIntervals>
+void refine_ne(IntervalSetDomain<Num, MaxIntervals>* x,
+               IntervalSetDomain<Num, MaxIntervals>* y) {
+  if (auto y_value = y->get_constant()) {
+    x->exclude(*y_value);
+  } else if (auto x_value = x->get_constant()) {
+    y->exclude(*x_value);
+  }
+  if (x->is_bottom() || y->is_bottom()) {
+    x->set_to_bottom();
+    y->set_to_bottom();
+  }
+}
+
+} // namespace sparta
@@ -0,0 +1,448 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <boost/optional.hpp>
+#include <limits>
+#include <ostream>
+#include <type_traits>
+
+#include "AbstractDomain.h"
+#include "NumericalDomainOps.h"
+
+namespace sparta {
+
+/*
+ * The known bits of a machine integer: each bit is either known to be 0,
+ * known to be 1, or unknown. An abstract value is represented by two masks,
+ * the bits that are known to be 0 and the bits that are known to be 1, in the
+ * style of LLVM's KnownBits. This domain is precise for bit-manipulating code,
+ * e.g., masking, flag tests or alignment, which intervals model poorly.
+ *
+ * The arithmetic wraps around, like the machine arithmetic on Integer. Shifts
+ * to the right are arithmetic if Integer is signed. Since the lattice has a
+ * finite height, the widening is the join and the narrowing is the meet.
+ */
+template <typename Integer>
+class KnownBitsDomain final : public AbstractDomain<KnownBitsDomain<Integer>> {
+  static_assert(std::is_integral<Integer>::value, "expecting integers.");
+
+ public:
+  using Unsigned = typename std::make_unsigned<Integer>::type;
+
+  static constexpr unsigned WIDTH = std::numeric_limits<Unsigned>::digits;
+
+  // By default, all the bits are unknown.
+  KnownBitsDomain() = default;
+
+  static KnownBitsDomain bottom() {
+    return KnownBitsDomain(Unsigned(~Unsigned(0)), Unsigned(~Unsigned(0)));
+  }
+
+  static KnownBitsDomain top() { return KnownBitsDomain(); }
+
+  static KnownBitsDomain constant(Integer value) {
+    return KnownBitsDomain(Unsigned(~Unsigned(value)), Unsigned(value));
+  }
+
+  /*
+   * The bits of `zeros` are known to be 0 and the bits of `ones` are known to
+   * be 1. A bit that is in both masks makes the value Bottom.
+   */
+  static KnownBitsDomain from_masks(Unsigned zeros, Unsigned ones) {
+    return (zeros & ones) == 0 ? KnownBitsDomain(zeros, ones) : bottom();
+  }
+
+  /*
+   * The masks are only meaningful when the value is not Bottom.
+   */
+  Unsigned known_zeros() const { return m_zeros; }
+
+  Unsigned known_ones() const { return m_ones; }
+
+  Unsigned unknown_bits() const { return Unsigned(~(m_zeros | m_ones)); }
+
+  boost::optional<Integer> get_constant() const {
+    if (is_bottom() || unknown_bits() != 0) {
+      return boost::none;
+    }
+    return Integer(m_ones);
+  }
+
+  bool contains(Integer value) const {
+    return !is_bottom() && (Unsigned(value) & m_zeros) == 0 &&
+           (Unsigned(value) & m_ones) == m_ones;
+  }
+
+  /*
+   * The smallest and largest integers in the concretization, which are only
+   * meaningful when the value is not Bottom.
+   */
+  Integer min_value() const {
+    Unsigned value = m_ones;
+    if (std::is_signed<Integer>::value && (unknown_bits() & SIGN_BIT)) {
+      value |= SIGN_BIT;
+    }
+    return Integer(value);
+  }
+
+  Integer max_value() const {
+    Unsigned value = Unsigned(~m_zeros);
+    if (std::is_signed<Integer>::value && (unknown_bits() & SIGN_BIT)) {
+      value &= Unsigned(~SIGN_BIT);
+    }
+    return Integer(value);
+  }
+
+  bool is_bottom() const override { return (m_zeros & m_ones) != 0; }
+
+  bool is_top() const override { return m_zeros == 0 && m_ones == 0; }
+
+  void set_to_bottom() override { *this = bottom(); }
+
+  void set_to_top() override { *this = top(); }
+
+  bool leq(const KnownBitsDomain& other) const override {
+    if (is_bottom()) {
+      return true;
+    }
+    if (other.is_bottom()) {
+      return false;
+    }
+    return (other.m_zeros & ~m_zeros) == 0 && (other.m_ones & ~m_ones) == 0;
+  }
+
+  bool equals(const KnownBitsDomain& other) const override {
+    return m_zeros == other.m_zeros && m_ones == other.m_ones;
+  }
+
+  void join_with(const KnownBitsDomain& other) override {
+    if (other.is_bottom()) {
+      return;
+    }
+    if (is_bottom()) {
+      *this = other;
+      return;
+    }
+    m_zeros &= other.m_zeros;
+    m_ones &= other.m_ones;
+  }
+
+  void widen_with(const KnownBitsDomain& other) override { join_with(other); }
+
+  void meet_with(const KnownBitsDomain& other) override {
+    *this = from_masks(m_zeros | other.m_zeros, m_ones | other.m_ones);
+  }
+
+  void narrow_with(const KnownBitsDomain& other) override { meet_with(other); }
+
+  KnownBitsDomain operator~() const {
+    return is_bottom() ? *this : KnownBitsDomain(m_ones, m_zeros);
+  }
+
+  KnownBitsDomain& operator&=(const KnownBitsDomain& that) {
+    return lift(that, [this, &that]() {
+      return KnownBitsDomain(m_zeros | that.m_zeros, m_ones & that.m_ones);
+    });
+  }
+
+  KnownBitsDomain& operator|=(const KnownBitsDomain& that) {
+    return lift(that, [this, &that]() {
+      return KnownBitsDomain(m_zeros & that.m_zeros, m_ones | that.m_ones);
+    });
+  }
+
+  KnownBitsDomain& operator^=(const KnownBitsDomain& that) {
+    return lift(that, [this, &that]() {
+      Unsigned known = (m_zeros | m_ones) & (that.m_zeros | that.m_ones);
+      Unsigned value = m_ones ^ that.m_ones;
+      return KnownBitsDomain(Unsigned(~value & known), Unsigned(value & known));
+    });
+  }
+
+  /*
+   * The sum is computed as in LLVM's KnownBits::computeForAddSub: a bit of the
+   * result is known if the corresponding bits of both operands and the carry
+   * into it are known.
+   */
+  KnownBitsDomain& operator+=(const KnownBitsDomain& that) {
+    return lift(that, [this, &that]() {
+      return add_with_carry(*this, that, /* carry */ false);
+    });
+  }
+
+  /*
+   * x - y = x + ~y + 1
+   */
+  KnownBitsDomain& operator-=(const KnownBitsDomain& that) {
+    return lift(that, [this, &that]() {
+      return add_with_carry(*this, ~that, /* carry */ true);
+    });
+  }
+
+  KnownBitsDomain operator-() const { return constant(0) - *this; }
+
+  /*
+   * Shifting by an amount that is negative or not smaller than the width of
+   * the type has an unknown result.
+   */
+  KnownBitsDomain shift_left(Integer amount) const {
+    if (is_bottom()) {
+      return *this;
+    }
+    if (!is_valid_shift(amount)) {
+      return top();
+    }
+    Unsigned low_bits = Unsigned((Unsigned(1) << amount) - 1);
+    return KnownBitsDomain(Unsigned(Unsigned(m_zeros << amount) | low_bits),
+                           Unsigned(m_ones << amount));
+  }
+
+  KnownBitsDomain shift_right(Integer amount) const {
+    if (is_bottom()) {
+      return *this;
+    }
+    if (!is_valid_shift(amount)) {
+      return top();
+    }
+    if (amount == 0) {
+      return *this;
+    }
+    // The high bits are copies of the sign bit on signed integers, and zeros
+    // otherwise.
+    Unsigned high_bits = Unsigned(~(Unsigned(~Unsigned(0)) >> amount));
+    Unsigned zeros = Unsigned(m_zeros >> amount);
+    Unsigned ones = Unsigned(m_ones >> amount);
+    if (!std::is_signed<Integer>::value || (m_zeros & SIGN_BIT)) {
+      zeros |= high_bits;
+    } else if (m_ones & SIGN_BIT) {
+      ones |= high_bits;
+    }
+    return KnownBitsDomain(zeros, ones);
+  }
+
+  friend KnownBitsDomain operator&(KnownBitsDomain x,
+                                   const KnownBitsDomain& y) {
+    return x &= y;
+  }
+
+  friend KnownBitsDomain operator|(KnownBitsDomain x,
+                                   const KnownBitsDomain& y) {
+    return x |= y;
+  }
+
+  friend KnownBitsDomain operator^(KnownBitsDomain x,
+                                   const KnownBitsDomain& y) {
+    return x ^= y;
+  }
+
+  friend KnownBitsDomain operator+(KnownBitsDomain x,
+                                   const KnownBitsDomain& y) {
+    return x += y;
+  }
+
+  friend KnownBitsDomain operator-(KnownBitsDomain x,
+                                   const KnownBitsDomain& y) {
+    return x -= y;
+  }
+
+  /*
+   * The bits from the most significant to the least significant, where an
+   * unknown bit is printed as '?', e.g., 0000?1?0 on 8 bits.
+   */
+  friend std::ostream& operator<<(std::ostream& o, const KnownBitsDomain& x) {
+    if (x.is_bottom()) {
+      return o << "_|_";
+    }
+    for (unsigned i = WIDTH; i-- > 0;) {
+      Unsigned bit = Unsigned(Unsigned(1) << i);
+      o << ((x.m_ones & bit) ? '1' : (x.m_zeros & bit) ? '0' : '?');
+    }
+    return o;
+  }
+
+ private:
+  static constexpr Unsigned SIGN_BIT = Unsigned(Unsigned(1) << (WIDTH - 1));
+
+  KnownBitsDomain(Unsigned zeros, Unsigned ones)
+      : m_zeros(zeros), m_ones(ones) {}
+
+  static bool is_valid_shift(Integer amount) {
+    return amount >= 0 && static_cast<Unsigned>(amount) < WIDTH;
+  }
+
+  template <typename Operation>
+  KnownBitsDomain& lift(const KnownBitsDomain& that, Operation operation) {
+    if (is_bottom() || that.is_bottom()) {
+      set_to_bottom();
+    } else {
+      *this = operation();
+    }
+    return *this;
+  }
+
+  static KnownBitsDomain add_with_carry(const KnownBitsDomain& x,
+                                        const KnownBitsDomain& y,
+                                        bool carry) {
+    // The largest and smallest possible sums, as unsigned integers.
+    Unsigned sum_max =
+        Unsigned(Unsigned(~x.m_zeros) + Unsigned(~y.m_zeros) + carry);
+    Unsigned sum_min = Unsigned(x.m_ones + y.m_ones + carry);
+    // The carries into each bit that are known in both extremal sums.
+    Unsigned carry_zeros = Unsigned(~(sum_max ^ x.m_zeros ^ y.m_zeros));
+    Unsigned carry_ones = Unsigned(sum_min ^ x.m_ones ^ y.m_ones);
+    Unsigned known = (x.m_zeros | x.m_ones) & (y.m_zeros | y.m_ones) &
+                     (carry_zeros | carry_ones);
+    return KnownBitsDomain(Unsigned(~sum_max & known),
+                           Unsigned(sum_min & known));
+  }
+
+  Unsigned m_zeros{0};
+  Unsigned m_ones{0};
+};
+
+template <typename Integer>
+struct ArithmeticDomainOps<KnownBitsDomain<Integer>> {
+  using Domain = KnownBitsDomain<Integer>;
+  using Unsigned = typename Domain::Unsigned;
+
+  template <typename Constant>
+  static Domain literal(const Constant& value) {
+    return Domain::constant(static_cast<Integer>(value));
+  }
+
+  static Domain neg(const Domain& x) { return -x; }
+
+  static Domain add(const Domain& x, const Domain& y) { return x + y; }
+
+  static Domain sub(const Domain& x, const Domain& y) { return x - y; }
+
+  /*
+   * The product of constants is exact. Otherwise, the number of trailing
+   * zeros of the product is at least the sum of those of the operands.
+   */
+  static Domain mul(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    if (x.get_constant() && y.get_constant()) {
+      return Domain::constant(
+          Integer(Unsigned(*x.get_constant()) * Unsigned(*y.get_constant())));
+    }
+    unsigned zeros = trailing_zeros(x) + trailing_zeros(y);
+    if (zeros >= Domain::WIDTH) {
+      return Domain::constant(0);
+    }
+    return Domain::from_masks(Unsigned((Unsigned(1) << zeros) - 1), 0);
+  }
+
+  static Domain div(const Domain& x, const Domain& y) {
+    auto divisor = y.get_constant();
+    if (x.is_bottom() || y.is_bottom() || (divisor && *divisor == 0)) {
+      return Domain::bottom();
+    }
+    auto dividend = x.get_constant();
+    if (!dividend || !divisor) {
+      return Domain::top();
+    }
+    if (std::is_signed<Integer>::value &&
+        *dividend == std::numeric_limits<Integer>::min() &&
+        *divisor == Integer(-1)) {
+      return x;
+    }
+    return Domain::constant(Integer(*dividend / *divisor));
+  }
+
+  static Domain bit_and(const Domain& x, const Domain& y) { return x & y; }
+
+  static Domain bit_or(const Domain& x, const Domain& y) { return x | y; }
+
+  static Domain bit_xor(const Domain& x, const Domain& y) { return x ^ y; }
+
+  static Domain shl(const Domain& x, const Domain& y) {
+    return shift(x, y, [](const Domain& value, Integer amount) {
+      return value.shift_left(amount);
+    });
+  }
+
+  static Domain shr(const Domain& x, const Domain& y) {
+    return shift(x, y, [](const Domain& value, Integer amount) {
+      return value.shift_right(amount);
+    });
+  }
+
+ private:
+  static unsigned trailing_zeros(const Domain& x) {
+    unsigned n = 0;
+    while (n < Domain::WIDTH &&
+           (x.known_zeros() & Unsigned(Unsigned(1) << n))) {
+      ++n;
+    }
+    return n;
+  }
+
+  template <typename Shift>
+  static Domain shift(const Domain& x, const Domain& y, Shift shift) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    auto amount = y.get_constant();
+    return amount ? shift(x, *amount) : Domain::top();
+  }
+};
+
+template <typename Integer>
+struct CompareDomainOps<KnownBitsDomain<Integer>>
+    : DerivedCompareDomainOps<CompareDomainOps<KnownBitsDomain<Integer>>,
+                              KnownBitsDomain<Integer>> {
+  using Domain = KnownBitsDomain<Integer>;
+
+  /*
+   * Two values that differ on a known bit are never equal.
+   */
+  static BooleanDomain eq(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return BooleanDomain::bottom();
+    }
+    if (x.meet(y).is_bottom()) {
+      return BooleanDomain(false);
+    }
+    if (x.get_constant() && y.get_constant()) {
+      return BooleanDomain(true);
+    }
+    return BooleanDomain::top();
+  }
+
+  static BooleanDomain lt(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return BooleanDomain::bottom();
+    }
+    if (x.max_value() < y.min_value()) {
+      return BooleanDomain(true);
+    }
+    if (x.min_value() >= y.max_value()) {
+      return BooleanDomain(false);
+    }
+    return BooleanDomain::top();
+  }
+
+  static BooleanDomain le(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return BooleanDomain::bottom();
+    }
+    if (x.max_value() <= y.min_value()) {
+      return BooleanDomain(true);
+    }
+    if (x.min_value() > y.max_value()) {
+      return BooleanDomain(false);
+    }
+    return BooleanDomain::top();
+  }
+};
+
+} // namespace sparta
@@ -0,0 +1,124 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <functional>
+#include <ostream>
+#include <type_traits>
+#include <utility>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * The result of comparing two elements of a partially ordered set.
+ */
+enum class PartialOrdering { Less, Equivalent, Greater, Unordered };
+
+inline std::ostream& operator<<(std::ostream& o, PartialOrdering ordering) {
+  switch (ordering) {
+  case PartialOrdering::Less: {
+    o << "Less";
+    break;
+  }
+  case PartialOrdering::Equivalent: {
+    o << "Equivalent";
+    break;
+  }
+  case PartialOrdering::Greater: {
+    o << "Greater";
+    break;
+  }
+  case PartialOrdering::Unordered: {
+    o << "Unordered";
+    break;
+  }
+  }
+  return o;
+}
+
+/*
+ * Compares two abstract values with respect to the partial order of their
+ * domain, i.e., using leq().
+ */
+template <typename Domain>
+PartialOrdering partial_compare(const Domain& x, const Domain& y) {
+  bool x_leq_y = x.leq(y);
+  bool y_leq_x = y.leq(x);
+  if (x_leq_y) {
+    return y_leq_x ? PartialOrdering::Equivalent : PartialOrdering::Less;
+  }
+  return y_leq_x ? PartialOrdering::Greater : PartialOrdering::Unordered;
+}
+
+/*
+ * A wrapper around an abstract value that defines the relational operators
+ * in terms of the partial order of the domain, so that abstract values can be
+ * used by generic code that expects comparison operators, e.g., in order to
+ * select the most precise of several results:
+ *
+ *   if (LatticeOrder<Domain>(x) < LatticeOrder<Domain>(y)) { ... }
+ *
+ * Note that `!(x < y)` does not imply `y <= x`, since the two values may be
+ * incomparable. The relational operators do not define a strict weak ordering
+ * in general, hence a LatticeOrder must not be used as the key of an ordered
+ * container, nor be sorted with std::sort(). Using std::less on a LatticeOrder
+ * is rejected at compile time for that reason.
+ */
+template <typename Domain>
+class LatticeOrder final {
+ public:
+  explicit LatticeOrder(Domain value) : m_value(std::move(value)) {}
+
+  const Domain& get() const { return m_value; }
+
+  PartialOrdering compare(const LatticeOrder& other) const {
+    return partial_compare(m_value, other.m_value);
+  }
+
+  friend bool operator==(const LatticeOrder& x, const LatticeOrder& y) {
+    return x.m_value.equals(y.m_value);
+  }
+
+  friend bool operator!=(const LatticeOrder& x, const LatticeOrder& y) {
+    return !x.m_value.equals(y.m_value);
+  }
+
+  friend bool operator<=(const LatticeOrder& x, const LatticeOrder& y) {
+    return x.m_value.leq(y.m_value);
+  }
+
+  friend bool operator>=(const LatticeOrder& x, const LatticeOrder& y) {
+    return y.m_value.leq(x.m_value);
+  }
+
+  friend bool operator<(const LatticeOrder& x, const LatticeOrder& y) {
+    return x.compare(y) == PartialOrdering::Less;
+  }
+
+  friend bool operator>(const LatticeOrder& x, const LatticeOrder& y) {
+    return x.compare(y) == PartialOrdering::Greater;
+  }
+
+ private:
+  Domain m_value;
+};
+
+} // namespace sparta
+
+namespace std {
+
+template <typename Domain>
+struct less<sparta::LatticeOrder<Domain>> {
+  static_assert(!std::is_same<Domain, Domain>::value,
+                "The partial order of an abstract domain is not a strict weak "
+                "ordering");
+};
+
+} // namespace std
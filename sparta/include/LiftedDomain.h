@@ -171,6 +171,159 @@ class LiftedDomain final : public AbstractDomain<LiftedDomain<D>> {
       : m_underlying(std::move(underlying)) {}
 };
 
+/*
+ * Augments an underlying domain -- D -- with a new greatest element. This is
+ * the dual of LiftedDomain: in documentation and output formats, the
+ * underlying domain's existing greatest element will be referred to by the
+ * symbol * and the new greatest element as T.
+ */
+template <typename D>
+class TopLiftedDomain final : public AbstractDomain<TopLiftedDomain<D>> {
+  static_assert(std::is_convertible<D*, AbstractDomain<D>*>::value,
+                "TopLiftedDomain must wrap another domain.");
+
+ public:
+  static TopLiftedDomain top() { return TopLiftedDomain{nullptr}; }
+
+  static TopLiftedDomain lifted(D underlying) {
+    return TopLiftedDomain{std::make_unique<D>(std::move(underlying))};
+  }
+
+  static TopLiftedDomain bottom() {
+    return TopLiftedDomain{std::make_unique<D>(D::bottom())};
+  }
+
+  /* Default constructor produces the default value in underlying domain. */
+  TopLiftedDomain() : TopLiftedDomain(std::make_unique<D>()) {}
+
+  TopLiftedDomain(TopLiftedDomain&&) = default;
+  TopLiftedDomain& operator=(TopLiftedDomain&&) = default;
+
+  TopLiftedDomain(const TopLiftedDomain& that) {
+    if (that.m_underlying) {
+      m_underlying = std::make_unique<D>(*that.m_underlying);
+    }
+  }
+
+  TopLiftedDomain& operator=(const TopLiftedDomain& that) {
+    m_underlying = that.m_underlying ? std::make_unique<D>(*that.m_underlying)
+                                     : std::unique_ptr<D>();
+    return *this;
+  }
+
+  bool is_bottom() const override {
+    return m_underlying && m_underlying->is_bottom();
+  }
+
+  bool is_top() const override { return !m_underlying; }
+
+  bool is_lifted() const { return !is_top(); }
+
+  D& lowered() {
+    RUNTIME_CHECK(is_lifted(), undefined_operation());
+    return *m_underlying;
+  }
+
+  const D& lowered() const {
+    RUNTIME_CHECK(is_lifted(), undefined_operation());
+    return *m_underlying;
+  }
+
+  bool leq(const TopLiftedDomain& that) const override {
+    if (that.is_top()) {
+      return true;
+    } else if (is_top()) {
+      return false;
+    } else {
+      return m_underlying->leq(*that.m_underlying);
+    }
+  }
+
+  bool equals(const TopLiftedDomain& that) const override {
+    if (is_top() || that.is_top()) {
+      return is_top() && that.is_top();
+    }
+    return m_underlying->equals(*that.m_underlying);
+  }
+
+  void set_to_bottom() override {
+    m_underlying = std::make_unique<D>(D::bottom());
+  }
+
+  void set_to_top() override { m_underlying = nullptr; }
+
+  /*
+   *  T \/ _ = T
+   *  _ \/ T = T
+   *  x \/ y = x \/' y
+   *
+   * Where \/' is the join on the underlying domain.
+   */
+  void join_with(const TopLiftedDomain& that) override {
+    if (is_top()) {
+      return;
+    } else if (that.is_top()) {
+      set_to_top();
+    } else {
+      m_underlying->join_with(*that.m_underlying);
+    }
+  }
+
+  /*
+   *  T W _ = T
+   *  _ W T = T
+   *  x W y = x W' y
+   *
+   * Where W' is the widening on the underlying domain.
+   */
+  void widen_with(const TopLiftedDomain& that) override {
+    if (is_top()) {
+      return;
+    } else if (that.is_top()) {
+      set_to_top();
+    } else {
+      m_underlying->widen_with(*that.m_underlying);
+    }
+  }
+
+  /*
+   *  T /\ x = x
+   *  x /\ T = x
+   *  x /\ y = x /\' y
+   *
+   * Where /\' is the meet on the underlying domain.
+   */
+  void meet_with(const TopLiftedDomain& that) override {
+    if (is_top()) {
+      *this = that;
+    } else if (!that.is_top()) {
+      m_underlying->meet_with(*that.m_underlying);
+    }
+  }
+
+  /*
+   *  T N x = x
+   *  x N T = x
+   *  x N y = x N' y
+   *
+   * Where N' is the narrowing on the underlying domain.
+   */
+  void narrow_with(const TopLiftedDomain& that) override {
+    if (is_top()) {
+      *this = that;
+    } else if (!that.is_top()) {
+      m_underlying->narrow_with(*that.m_underlying);
+    }
+  }
+
+ private:
+  // Top for the lifted domain is represented by a null underlying pointer.
+  std::unique_ptr<D> m_underlying = nullptr;
+
+  explicit TopLiftedDomain(std::unique_ptr<D> underlying)
+      : m_underlying(std::move(underlying)) {}
+};
+
 } // namespace sparta
 
 template <typename D>
@@ -191,3 +344,22 @@ inline std::ostream& operator<<(std::ostream& o,
 
   return o << under;
 }
+
+template <typename D>
+inline std::ostream& operator<<(std::ostream& o,
+                                const sparta::TopLiftedDomain<D>& i) {
+  if (i.is_top()) {
+    return o << "T";
+  }
+
+  if (i.is_bottom()) {
+    return o << "_|_";
+  }
+
+  const auto& under = i.lowered();
+  if (under.is_top()) {
+    return o << "*";
+  }
+
+  return o << under;
+}
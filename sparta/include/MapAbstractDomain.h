@@ -0,0 +1,565 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <functional>
+#include <initializer_list>
+#include <ostream>
+#include <type_traits>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+#include "DefaultBinding.h"
+#include "PatriciaTreeMap.h"
+#include "PatriciaTreeSet.h"
+
+namespace sparta {
+
+namespace mad_impl {
+
+class value_is_bottom {};
+
+} // namespace mad_impl
+
+/*
+ * An abstract domain of maps from keys to abstract values based on Patricia
+ * trees, hence cheap to copy, that is parameterized by the value of the keys
+ * that are not explicitly bound (see DefaultBinding.h):
+ *
+ *   - DefaultIsTop gives an abstract environment, in which binding a key to
+ *     Bottom makes the whole environment Bottom. The join only keeps the keys
+ *     bound on both sides and the meet keeps the keys bound on either side.
+ *
+ *   - DefaultIsBottom gives an abstract partition, in which the Top partition
+ *     binds every label to Top and cannot be re-bound. Binding a label to Top
+ *     is allowed and doesn't make the whole partition Top. The join keeps the
+ *     labels bound on either side and the meet only keeps the labels bound on
+ *     both sides. The number of labels can be capped by MaxKeys (see
+ *     HashedAbstractPartition.h).
+ *
+ * In order to minimize the size of the underlying tree, the bindings of the
+ * keys to the default value are never stored. Most code should use one of the
+ * aliases PatriciaTreeMapAbstractEnvironment or
+ * PatriciaTreeMapAbstractPartition.
+ */
+template <typename Key,
+          typename Domain,
+          typename Default,
+          size_t MaxKeys = 0>
+class MapAbstractDomain final
+    : public AbstractDomain<MapAbstractDomain<Key, Domain, Default, MaxKeys>> {
+  static_assert(std::is_same<Default, DefaultIsTop>::value ||
+                    std::is_same<Default, DefaultIsBottom>::value,
+                "Default must be DefaultIsTop or DefaultIsBottom");
+
+  static constexpr bool DEFAULT_IS_TOP =
+      std::is_same<Default, DefaultIsTop>::value;
+
+  static_assert(MaxKeys == 0 || !DEFAULT_IS_TOP,
+                "Only the number of labels of a partition can be capped");
+
+ public:
+  using DefaultBinding = Default;
+
+  struct ValueInterface {
+    using type = Domain;
+
+    static type default_value() {
+      return default_binding_value<Default, Domain>();
+    }
+
+    static bool is_default_value(const type& x) {
+      return DEFAULT_IS_TOP ? x.is_top() : x.is_bottom();
+    }
+
+    static bool equals(const type& x, const type& y) { return x.equals(y); }
+
+    static bool leq(const type& x, const type& y) { return x.leq(y); }
+  };
+
+  using MapType = PatriciaTreeMap<Key, Domain, ValueInterface>;
+
+  /*
+   * The default constructor produces the map in which every key is bound to
+   * the default value, i.e., Top for an environment and Bottom for a
+   * partition.
+   */
+  MapAbstractDomain() = default;
+
+  MapAbstractDomain(AbstractValueKind kind) {
+    switch (kind) {
+    case AbstractValueKind::Bottom:
+      set_to_bottom();
+      break;
+    case AbstractValueKind::Top:
+      set_to_top();
+      break;
+    default:
+      RUNTIME_CHECK(false, invalid_abstract_value() << actual_kind(kind));
+    }
+  }
+
+  MapAbstractDomain(std::initializer_list<std::pair<Key, Domain>> l) {
+    for (const auto& p : l) {
+      set(p.first, p.second);
+    }
+  }
+
+  AbstractValueKind kind() const {
+    if (is_bottom()) {
+      return AbstractValueKind::Bottom;
+    }
+    return is_top() ? AbstractValueKind::Top : AbstractValueKind::Value;
+  }
+
+  bool is_value() const { return kind() == AbstractValueKind::Value; }
+
+  bool is_bottom() const override {
+    return DEFAULT_IS_TOP ? m_is_collapsed : !m_is_collapsed && m_map.empty();
+  }
+
+  bool is_top() const override {
+    return DEFAULT_IS_TOP ? !m_is_collapsed && m_map.empty() : m_is_collapsed;
+  }
+
+  void set_to_bottom() override {
+    m_map.clear();
+    m_is_collapsed = DEFAULT_IS_TOP;
+  }
+
+  void set_to_top() override {
+    m_map.clear();
+    m_is_collapsed = !DEFAULT_IS_TOP;
+  }
+
+  /*
+   * Number of bindings not set to the default value. This operation is not
+   * defined on the extremal value that binds every key to the opposite of the
+   * default value, i.e., the Bottom environment and the Top partition.
+   */
+  size_t size() const {
+    check_not_collapsed();
+    return m_map.size();
+  }
+
+  /*
+   * Get the bindings that are not set to the default value. This operation is
+   * not defined on the Bottom environment and the Top partition.
+   */
+  const MapType& bindings() const {
+    check_not_collapsed();
+    return m_map;
+  }
+
+  /*
+   * Folds a function over the bindings that are not set to the default value,
+   * in an unspecified order, e.g., to summarize a partition:
+   *
+   *   size_t escaping = partition.fold(
+   *       size_t(0), [](size_t n, const Label&, const Domain& value) {
+   *         return n + (value.escapes() ? 1 : 0);
+   *       });
+   *
+   * This operation is not defined on the Bottom environment and the Top
+   * partition.
+   */
+  template <typename Accumulator, typename Function>
+  Accumulator fold(Accumulator init, Function&& f) const {
+    check_not_collapsed();
+    for (const auto& binding : m_map) {
+      init = f(std::move(init), binding.first, binding.second);
+    }
+    return init;
+  }
+
+  /*
+   * The join of the values of all the labels of a partition, which is Bottom
+   * if all the labels are set to Bottom and Top if the partition is set to
+   * Top.
+   */
+  Domain join_of_all_bindings() const {
+    static_assert(!DEFAULT_IS_TOP,
+                  "The keys of an environment are not all explicitly bound");
+    if (is_top()) {
+      return Domain::top();
+    }
+    return fold(Domain::bottom(),
+                [](Domain acc, const Key&, const Domain& value) {
+                  acc.join_with(value);
+                  return acc;
+                });
+  }
+
+  const Domain& get(const Key& key) const {
+    if (m_is_collapsed) {
+      static const Domain collapsed = collapsed_value();
+      return collapsed;
+    }
+    return m_map.at(key);
+  }
+
+  /*
+   * This is a no-op on the Bottom environment and the Top partition.
+   */
+  MapAbstractDomain& set(const Key& key, Domain value) {
+    if (m_is_collapsed) {
+      return *this;
+    }
+    canonicalize(&value);
+    if (collapses(value)) {
+      set_to_collapsed();
+      return *this;
+    }
+    m_map.insert_or_assign(key, value);
+    return *this;
+  }
+
+  /*
+   * Binds several keys at once, e.g., when stitching the state of the caller
+   * with the summary of a callee. This is equivalent to a sequence of calls to
+   * set(), but it performs two merge operations on the underlying tree instead
+   * of rewriting the path from the root for each key. If a key occurs several
+   * times, the last binding wins.
+   */
+  MapAbstractDomain& set_all(
+      std::initializer_list<std::pair<Key, Domain>> bindings) {
+    return set_all<std::initializer_list<std::pair<Key, Domain>>>(bindings);
+  }
+
+  template <typename Bindings>
+  MapAbstractDomain& set_all(const Bindings& bindings) {
+    if (m_is_collapsed) {
+      return *this;
+    }
+    MapType keys;
+    MapType values;
+    for (const auto& binding : bindings) {
+      Domain value = canonical(binding.second);
+      if (collapses(value)) {
+        set_to_collapsed();
+        return *this;
+      }
+      keys.insert_or_assign(binding.first, marker());
+      // Binding a key to the default value removes it from the map.
+      values.insert_or_assign(binding.first, value);
+    }
+    m_map.difference_with(erase, keys);
+    // The keys of the two maps are now disjoint, hence the combining function
+    // is only applied to a value and the default value, in any order.
+    m_map.union_with(
+        [](const Domain& x, const Domain& y) {
+          return ValueInterface::is_default_value(x) ? y : x;
+        },
+        values);
+    return *this;
+  }
+
+  /*
+   * Binds several keys to the default value at once. See set_all().
+   */
+  MapAbstractDomain& unset_all(std::initializer_list<Key> keys) {
+    return unset_all<std::initializer_list<Key>>(keys);
+  }
+
+  template <typename Keys>
+  MapAbstractDomain& unset_all(const Keys& keys) {
+    if (m_is_collapsed) {
+      return *this;
+    }
+    MapType erased;
+    for (const auto& key : keys) {
+      erased.insert_or_assign(key, marker());
+    }
+    m_map.difference_with(erase, erased);
+    return *this;
+  }
+
+  /*
+   * This is a no-op on the Bottom environment and the Top partition.
+   */
+  MapAbstractDomain& update(const Key& key,
+                            std::function<Domain(const Domain&)> operation) {
+    if (m_is_collapsed) {
+      return *this;
+    }
+    try {
+      m_map.update(
+          [&operation](const Domain& x) { return checked(operation(x)); },
+          key);
+    } catch (const mad_impl::value_is_bottom&) {
+      set_to_collapsed();
+    }
+    return *this;
+  }
+
+  bool map(std::function<Domain(const Domain&)> f) {
+    if (m_is_collapsed) {
+      return false;
+    }
+    try {
+      return m_map.map([&f](const Domain& x) { return checked(f(x)); });
+    } catch (const mad_impl::value_is_bottom&) {
+      set_to_collapsed();
+      return true;
+    }
+  }
+
+  bool erase_all_matching(const Key& key_mask) {
+    if (m_is_collapsed) {
+      return false;
+    }
+    return m_map.erase_all_matching(key_mask);
+  }
+
+  /*
+   * Binds every key to the default value. This is a no-op on the Bottom
+   * environment and the Top partition.
+   */
+  MapAbstractDomain& clear() {
+    if (!m_is_collapsed) {
+      m_map.clear();
+    }
+    return *this;
+  }
+
+  /*
+   * Abstract garbage collection: removes the bindings of all the variables
+   * that are not transitively reachable from the given roots. The function
+   * `references` returns the variables referenced by an abstract value, e.g.,
+   * the abstract heap locations a reference may point to. It is only invoked
+   * on explicit (i.e., non-Top) bindings, hence the caller should refrain from
+   * collecting garbage if a reachable value may reference arbitrary variables.
+   *
+   * Since unbound variables are implicitly mapped to Top, this operation is
+   * always sound: it only forgets information about variables that can no
+   * longer be accessed. Returns true if some binding has been removed. This
+   * operation is only available on environments.
+   */
+  bool collect_garbage(
+      const std::vector<Key>& roots,
+      std::function<std::vector<Key>(const Domain&)> references) {
+    static_assert(DEFAULT_IS_TOP,
+                  "Forgetting the label of a partition is unsound");
+    if (!is_value()) {
+      return false;
+    }
+    PatriciaTreeSet<Key> reachable;
+    std::vector<Key> worklist(roots);
+    while (!worklist.empty()) {
+      Key variable = worklist.back();
+      worklist.pop_back();
+      if (reachable.contains(variable)) {
+        continue;
+      }
+      reachable.insert(variable);
+      const Domain& value = m_map.at(variable);
+      if (value.is_top()) {
+        continue;
+      }
+      for (const Key& referenced : references(value)) {
+        if (!reachable.contains(referenced)) {
+          worklist.push_back(referenced);
+        }
+      }
+    }
+    std::vector<Key> garbage;
+    for (const auto& binding : m_map) {
+      if (!reachable.contains(binding.first)) {
+        garbage.push_back(binding.first);
+      }
+    }
+    unset_all(garbage);
+    return !garbage.empty();
+  }
+
+  bool leq(const MapAbstractDomain& other) const override {
+    if (is_bottom() || other.is_top()) {
+      return true;
+    }
+    if (is_top() || other.is_bottom()) {
+      return false;
+    }
+    return m_map.leq(other.m_map);
+  }
+
+  bool equals(const MapAbstractDomain& other) const override {
+    return m_is_collapsed == other.m_is_collapsed &&
+           m_map.equals(other.m_map);
+  }
+
+  void join_with(const MapAbstractDomain& other) override {
+    join_like_operation(
+        other, [](const Domain& x, const Domain& y) { return x.join(y); });
+  }
+
+  void widen_with(const MapAbstractDomain& other) override {
+    join_like_operation(
+        other, [](const Domain& x, const Domain& y) { return x.widening(y); });
+  }
+
+  void meet_with(const MapAbstractDomain& other) override {
+    meet_like_operation(
+        other, [](const Domain& x, const Domain& y) { return x.meet(y); });
+  }
+
+  void narrow_with(const MapAbstractDomain& other) override {
+    meet_like_operation(
+        other, [](const Domain& x, const Domain& y) { return x.narrowing(y); });
+  }
+
+  void join_like_operation(
+      const MapAbstractDomain& other,
+      std::function<Domain(const Domain&, const Domain&)> operation) {
+    if (is_top() || other.is_bottom()) {
+      return;
+    }
+    if (is_bottom() || other.is_top()) {
+      *this = other;
+      return;
+    }
+    auto combine = [&operation](const Domain& x, const Domain& y) {
+      return canonical(operation(x, y));
+    };
+    if (DEFAULT_IS_TOP) {
+      // A key that is only bound on one side is bound to Top on the other.
+      m_map.intersection_with(combine, other.m_map);
+      return;
+    }
+    m_map.union_with(combine, other.m_map);
+    if (MaxKeys > 0 && m_map.size() > MaxKeys) {
+      set_to_top();
+    }
+  }
+
+  void meet_like_operation(
+      const MapAbstractDomain& other,
+      std::function<Domain(const Domain&, const Domain&)> operation) {
+    if (is_bottom() || other.is_top()) {
+      return;
+    }
+    if (is_top() || other.is_bottom()) {
+      *this = other;
+      return;
+    }
+    if (!DEFAULT_IS_TOP) {
+      // A label that is only bound on one side is bound to Bottom on the
+      // other.
+      m_map.intersection_with(
+          [&operation](const Domain& x, const Domain& y) {
+            return canonical(operation(x, y));
+          },
+          other.m_map);
+      return;
+    }
+    try {
+      m_map.union_with(
+          [&operation](const Domain& x, const Domain& y) {
+            return checked(operation(x, y));
+          },
+          other.m_map);
+    } catch (const mad_impl::value_is_bottom&) {
+      set_to_bottom();
+    }
+  }
+
+  /*
+   * Combines the bindings of the labels that are bound in both partitions,
+   * and removes all the bindings if the other partition is Top.
+   */
+  void difference_like_operation(
+      const MapAbstractDomain& other,
+      std::function<Domain(const Domain&, const Domain&)> operation) {
+    static_assert(!DEFAULT_IS_TOP,
+                  "The difference is only defined on partitions");
+    if (other.is_top()) {
+      set_to_bottom();
+    } else if (is_top()) {
+      return;
+    } else {
+      m_map.difference_with(operation, other.m_map);
+    }
+  }
+
+  static MapAbstractDomain bottom() {
+    return MapAbstractDomain(AbstractValueKind::Bottom);
+  }
+
+  static MapAbstractDomain top() {
+    return MapAbstractDomain(AbstractValueKind::Top);
+  }
+
+ private:
+  // The extremal value of the domain that is opposite to the default value,
+  // i.e., Bottom for an environment and Top for a partition.
+  static Domain collapsed_value() {
+    return DEFAULT_IS_TOP ? Domain::bottom() : Domain::top();
+  }
+
+  void set_to_collapsed() {
+    m_map.clear();
+    m_is_collapsed = true;
+  }
+
+  void check_not_collapsed() const {
+    RUNTIME_CHECK(!m_is_collapsed, undefined_operation());
+  }
+
+  // Binding a key of an environment to Bottom makes the environment Bottom.
+  static bool collapses(const Domain& value) {
+    return DEFAULT_IS_TOP && value.is_bottom();
+  }
+
+  static Domain checked(Domain value) {
+    canonicalize(&value);
+    if (collapses(value)) {
+      throw mad_impl::value_is_bottom();
+    }
+    return value;
+  }
+
+  // Denotes the presence of a key in a temporary map. Any value other than the
+  // default value would do.
+  static const Domain& marker() {
+    static const Domain opposite = collapsed_value();
+    return opposite;
+  }
+
+  // Removes the bindings of the keys that occur in the other map, when used as
+  // the combining function of difference_with().
+  static Domain erase(const Domain&, const Domain&) {
+    return ValueInterface::default_value();
+  }
+
+  MapType m_map;
+  // Whether the map is the extremal value that binds every key to the
+  // opposite of the default value, i.e., Bottom for an environment and Top for
+  // a partition.
+  bool m_is_collapsed{false};
+};
+
+// Defined in the namespace of the domain, so that it can be found by
+// argument-dependent lookup from the printers of generic wrappers.
+template <typename Key, typename Domain, typename Default, size_t MaxKeys>
+inline std::ostream& operator<<(
+    std::ostream& o,
+    const MapAbstractDomain<Key, Domain, Default, MaxKeys>& m) {
+  if (m.is_bottom()) {
+    o << "_|_";
+  } else if (m.is_top()) {
+    o << "T";
+  } else {
+    o << "[#" << m.size() << "]";
+    // The printer of Patricia trees is in the global namespace.
+    ::operator<<(o, m.bindings());
+  }
+  return o;
+}
+
+} // namespace sparta
@@ -0,0 +1,116 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <atomic>
+#include <cstddef>
+#include <type_traits>
+#include <utility>
+
+namespace sparta {
+
+namespace mc_impl {
+
+template <typename Domain, typename = void>
+struct has_size_hint : std::false_type {};
+
+template <typename Domain>
+struct has_size_hint<
+    Domain,
+    std::void_t<decltype(std::declval<const Domain&>().size_hint())>>
+    : std::true_type {};
+
+} // namespace mc_impl
+
+/*
+ * Returns an approximation of the memory retained by an abstract value, in
+ * arbitrary units. Domains that own a variable amount of memory should expose
+ * it via a method with the following signature:
+ *
+ *   size_t size_hint() const;
+ *
+ * Otherwise, the footprint of the object itself is used.
+ */
+template <typename Domain>
+typename std::enable_if<mc_impl::has_size_hint<Domain>::value, size_t>::type
+size_hint_of(const Domain& x) {
+  return x.size_hint();
+}
+
+template <typename Domain>
+typename std::enable_if<!mc_impl::has_size_hint<Domain>::value, size_t>::type
+size_hint_of(const Domain&) {
+  return sizeof(Domain);
+}
+
+/*
+ * An approximate account of the abstract states retained by one or several
+ * fixpoint iterators, e.g., all the analyses of a run. When the total size of
+ * the states exceeds the limit, the ceiling is tripped and the fixpoint
+ * iterators fall back to a cheap but sound iteration: the entry state of
+ * every loop head that has not stabilized yet is set to Top, which forces the
+ * iteration to converge in one more pass (see
+ * MonotonicFixpointIteratorBase::set_memory_ceiling). The ceiling remains
+ * tripped until it is reset, even if the retained states shrink in the
+ * meantime.
+ *
+ * All the operations are thread-safe. The ceiling must outlive the fixpoint
+ * iterators that use it, since they release their states from it when they
+ * are destroyed.
+ */
+class MemoryCeiling final {
+ public:
+  explicit MemoryCeiling(size_t limit) : m_limit(limit) {}
+
+  MemoryCeiling(const MemoryCeiling&) = delete;
+
+  MemoryCeiling& operator=(const MemoryCeiling&) = delete;
+
+  size_t limit() const { return m_limit; }
+
+  /*
+   * The total size of the states currently accounted for.
+   */
+  size_t usage() const { return m_usage.load(); }
+
+  /*
+   * The largest usage observed since the ceiling was created or reset.
+   */
+  size_t peak_usage() const { return m_peak_usage.load(); }
+
+  bool exceeded() const { return m_exceeded.load(); }
+
+  void charge(size_t size) {
+    size_t usage = (m_usage += size);
+    size_t peak = m_peak_usage.load();
+    while (usage > peak && !m_peak_usage.compare_exchange_weak(peak, usage)) {
+    }
+    if (usage > m_limit) {
+      m_exceeded = true;
+    }
+  }
+
+  void release(size_t size) { m_usage -= size; }
+
+  /*
+   * Clears the tripped state and the peak usage. The states that are still
+   * retained by the fixpoint iterators remain accounted for.
+   */
+  void reset() {
+    m_exceeded = false;
+    m_peak_usage = m_usage.load();
+  }
+
+ private:
+  const size_t m_limit;
+  std::atomic<size_t> m_usage{0};
+  std::atomic<size_t> m_peak_usage{0};
+  std::atomic<bool> m_exceeded{false};
+};
+
+} // namespace sparta
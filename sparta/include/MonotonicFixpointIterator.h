@@ -8,20 +8,115 @@
 #pragma once
 
 #include <algorithm>
+#include <atomic>
+#include <boost/optional.hpp>
+#include <boost/thread/thread.hpp>
+#include <condition_variable>
 #include <cstddef>
+#include <cstdint>
+#include <deque>
+#include <exception>
 #include <functional>
+#include <iterator>
+#include <memory>
+#include <mutex>
+#include <queue>
+#include <string>
 #include <type_traits>
 #include <unordered_map>
+#include <unordered_set>
 #include <vector>
 
 #include "AbstractDomain.h"
+#include "AnalysisManifest.h"
+#include "Exceptions.h"
 #include "FixpointIterator.h"
+#include "FixpointTrace.h"
+#include "GrowthRateWidening.h"
+#include "MemoryCeiling.h"
+#include "NodeInfo.h"
+#include "NodeMetadata.h"
 #include "SpartaWorkQueue.h"
 #include "WeakPartialOrdering.h"
 #include "WeakTopologicalOrdering.h"
+#include "WideningProvenance.h"
 
 namespace sparta {
 
+/*
+ * Returns a function that computes the successors of a node in a graph, with
+ * no duplicates. This is the successor function from which the fixpoint
+ * iterators build the weak topological or partial ordering of the graph. It
+ * can also be used to build the ordering of a graph directly:
+ *
+ *   WeakPartialOrdering<NodeId> wpo(
+ *       GraphInterface::entry(graph),
+ *       successor_nodes<GraphInterface>(graph),
+ *       false);
+ *
+ * The graph must outlive the function.
+ */
+template <typename GraphInterface,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+std::function<std::vector<typename GraphInterface::NodeId>(
+    const typename GraphInterface::NodeId&)>
+successor_nodes(const typename GraphInterface::Graph& graph) {
+  using NodeId = typename GraphInterface::NodeId;
+  return [&graph](const NodeId& node) {
+    std::vector<NodeId> succ_nodes;
+    std::unordered_set<NodeId, NodeHash> succ_nodes_set;
+    for (const auto& edge : GraphInterface::successors(graph, node)) {
+      NodeId succ = GraphInterface::target(graph, edge);
+      // Filter out duplicate succ nodes.
+      if (succ_nodes_set.insert(succ).second) {
+        succ_nodes.push_back(succ);
+      }
+    }
+    return succ_nodes;
+  };
+}
+
+namespace fp_impl {
+
+template <typename GraphInterface, typename = void>
+struct has_size_hint : std::false_type {};
+
+template <typename GraphInterface>
+struct has_size_hint<GraphInterface,
+                     std::void_t<decltype(GraphInterface::size_hint(
+                         std::declval<typename GraphInterface::Graph>()))>>
+    : std::true_type {};
+
+} // namespace fp_impl
+
+/*
+ * Returns the estimate of the number of nodes provided by the graph interface,
+ * or 0 if it doesn't provide any. The estimate is given by an optional method
+ * of the graph interface:
+ *
+ *   static size_t size_hint(const Graph& graph);
+ *
+ * The fixpoint iterators never enumerate the nodes of a graph upfront. They
+ * only discover the nodes that are reachable from the entry by following the
+ * successors, so that the graph can be materialized lazily, e.g., the product
+ * of a control-flow graph with an automaton. Hence, the number of nodes may
+ * not be known in advance. The estimate is only used to preallocate the
+ * tables of states, and it doesn't need to be exact.
+ */
+template <typename GraphInterface>
+typename std::enable_if<fp_impl::has_size_hint<GraphInterface>::value,
+                        size_t>::type
+graph_size_hint(const typename GraphInterface::Graph& graph) {
+  return GraphInterface::size_hint(graph);
+}
+
+template <typename GraphInterface>
+typename std::enable_if<!fp_impl::has_size_hint<GraphInterface>::value,
+                        size_t>::type
+graph_size_hint(const typename GraphInterface::Graph&) {
+  return 0;
+}
+
 namespace fp_impl {
 
 /*
@@ -60,7 +155,7 @@ class MonotonicFixpointIteratorContext final {
       : m_init(init) {}
 
   explicit MonotonicFixpointIteratorContext(
-      const Domain& init, const std::unordered_set<NodeId>& nodes)
+      const Domain& init, const std::unordered_set<NodeId, NodeHash>& nodes)
       : m_init(init) {
     // Pre-populate hash table for all the nodes.
     for (auto& node : nodes) {
@@ -95,6 +190,212 @@ class MonotonicFixpointIteratorContext final {
   std::unordered_map<NodeId, uint32_t, NodeHash> m_local_iterations;
 };
 
+/*
+ * The threads that compute the partial joins of the incoming states of the
+ * nodes with many predecessors (see set_parallel_join). They are started once
+ * per run of a fixpoint iterator rather than once per join, and they are
+ * shared by the joins requested concurrently by the workers of a parallel
+ * fixpoint iterator. The thread that requests a join takes part in it.
+ */
+class JoinThreadPool final {
+ public:
+  explicit JoinThreadPool(size_t num_thread) {
+    for (size_t i = 0; i < num_thread; ++i) {
+      m_threads.emplace_back([this]() { work(); });
+    }
+  }
+
+  JoinThreadPool(const JoinThreadPool&) = delete;
+  JoinThreadPool& operator=(const JoinThreadPool&) = delete;
+
+  ~JoinThreadPool() {
+    {
+      std::lock_guard<std::mutex> lock(m_mutex);
+      m_stopped = true;
+    }
+    m_ready.notify_all();
+    for (auto& thread : m_threads) {
+      thread.join();
+    }
+  }
+
+  /*
+   * Invokes the task on every index in [0, num_tasks) and returns once all
+   * the invocations have completed. The task must not throw.
+   */
+  void run(size_t num_tasks, const std::function<void(size_t)>& task) {
+    Batch batch(task, num_tasks);
+    size_t num_requests =
+        std::min(m_threads.size(), num_tasks > 0 ? num_tasks - 1 : 0);
+    {
+      std::lock_guard<std::mutex> lock(m_mutex);
+      m_pending.insert(m_pending.end(), num_requests, &batch);
+    }
+    m_ready.notify_all();
+    batch.process();
+    std::unique_lock<std::mutex> lock(m_mutex);
+    // All the tasks have started at this point. The requests that no thread
+    // has picked up yet are withdrawn, and we wait for the other ones.
+    m_pending.erase(std::remove(m_pending.begin(), m_pending.end(), &batch),
+                    m_pending.end());
+    m_done.wait(lock, [&batch]() { return batch.num_helpers == 0; });
+  }
+
+ private:
+  struct Batch {
+    Batch(const std::function<void(size_t)>& task, size_t size)
+        : task(task), size(size) {}
+
+    void process() {
+      for (size_t i = next++; i < size; i = next++) {
+        task(i);
+      }
+    }
+
+    const std::function<void(size_t)>& task;
+    const size_t size;
+    std::atomic<size_t> next{0};
+    // The number of threads of the pool that are processing the batch, which
+    // is guarded by the mutex of the pool.
+    size_t num_helpers{0};
+  };
+
+  void work() {
+    std::unique_lock<std::mutex> lock(m_mutex);
+    while (true) {
+      m_ready.wait(lock, [this]() { return m_stopped || !m_pending.empty(); });
+      if (m_stopped) {
+        return;
+      }
+      Batch* batch = m_pending.front();
+      m_pending.pop_front();
+      ++batch->num_helpers;
+      lock.unlock();
+      batch->process();
+      lock.lock();
+      if (--batch->num_helpers == 0) {
+        m_done.notify_all();
+      }
+    }
+  }
+
+  std::mutex m_mutex;
+  std::condition_variable m_ready;
+  std::condition_variable m_done;
+  std::deque<Batch*> m_pending;
+  bool m_stopped{false};
+  std::vector<boost::thread> m_threads;
+};
+
+/*
+ * The recent entry states of the heads of the components, which are retained
+ * as a debugging aid (see set_state_history). For every head, the states are
+ * kept in a ring buffer indexed by iteration.
+ */
+template <typename NodeId, typename Domain, typename NodeHash>
+class StateHistory final {
+ public:
+  explicit StateHistory(size_t depth) : m_depth(depth) {}
+
+  void record(const NodeId& head, uint32_t iteration, const Domain& state) {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    auto& history = m_heads[head];
+    if (history.size() == m_depth) {
+      history.pop_front();
+    }
+    history.emplace_back(iteration, state);
+  }
+
+  boost::optional<Domain> get(const NodeId& head, uint32_t iteration) const {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    auto it = m_heads.find(head);
+    if (it == m_heads.end()) {
+      return boost::none;
+    }
+    for (const auto& entry : it->second) {
+      if (entry.first == iteration) {
+        return entry.second;
+      }
+    }
+    return boost::none;
+  }
+
+  uint32_t last_iteration(const NodeId& head) const {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    auto it = m_heads.find(head);
+    return it == m_heads.end() || it->second.empty() ? 0
+                                                     : it->second.back().first;
+  }
+
+  size_t depth() const { return m_depth; }
+
+  void clear() {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    m_heads.clear();
+  }
+
+  void clear_and_shrink() {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    decltype(m_heads)().swap(m_heads);
+  }
+
+ private:
+  size_t m_depth;
+  std::unordered_map<NodeId, std::deque<std::pair<uint32_t, Domain>>, NodeHash>
+      m_heads;
+  mutable std::mutex m_mutex;
+};
+
+/*
+ * The information about the nodes that a fixpoint iterator maintains during a
+ * run besides the entry and exit states. It only pertains to the last run,
+ * hence it is discarded altogether at the beginning of every run, as well as
+ * by reset() and clear_and_shrink().
+ */
+template <typename NodeId, typename Domain, typename NodeHash>
+struct RunTables {
+  // The compressed entry and exit states.
+  using CompressedStates = std::pair<std::string, std::string>;
+
+  // The sizes charged to the memory ceiling.
+  std::unordered_map<NodeId, std::atomic<size_t>, NodeHash> charged_sizes;
+  // The digests of the entry states of the heads at their last stabilization.
+  std::unordered_map<NodeId, boost::optional<uint64_t>, NodeHash>
+      stabilized_digests;
+  std::unordered_map<NodeId, boost::optional<CompressedStates>, NodeHash>
+      compressed_states;
+  // The following are only allocated while the state history is recorded and
+  // the growth-rate widening is enabled, respectively, so that the iterator
+  // remains movable.
+  std::unique_ptr<StateHistory<NodeId, Domain, NodeHash>> history;
+  std::unique_ptr<GrowthRateWidening<NodeId, Domain, NodeHash>>
+      growth_rate_widening;
+
+  void clear() {
+    charged_sizes.clear();
+    stabilized_digests.clear();
+    compressed_states.clear();
+    if (history) {
+      history->clear();
+    }
+    if (growth_rate_widening) {
+      growth_rate_widening->clear();
+    }
+  }
+
+  void clear_and_shrink() {
+    decltype(charged_sizes)().swap(charged_sizes);
+    decltype(stabilized_digests)().swap(stabilized_digests);
+    decltype(compressed_states)().swap(compressed_states);
+    if (history) {
+      history->clear_and_shrink();
+    }
+    if (growth_rate_widening) {
+      growth_rate_widening->clear_and_shrink();
+    }
+  }
+};
+
 /*
  * Shared by MonotonicFixpointIterator and ParallelMonotonicFixpointIterator,
  * do not use directly.
@@ -113,12 +414,20 @@ class MonotonicFixpointIteratorBase
   /*
    * When the number of nodes in the CFG is known, it's better to provide it to
    * the constructor, so as to prevent unnecessary resizing of the underlying
-   * hashtables during the iteration.
+   * hashtables during the iteration. The estimate provided by the graph
+   * interface is used if it is larger (see graph_size_hint).
    */
   MonotonicFixpointIteratorBase(const Graph& graph, size_t cfg_size_hint = 4)
       : m_graph(graph),
-        m_entry_states(cfg_size_hint),
-        m_exit_states(cfg_size_hint) {}
+        m_entry_states(
+            std::max(cfg_size_hint, graph_size_hint<GraphInterface>(graph))),
+        m_exit_states(
+            std::max(cfg_size_hint, graph_size_hint<GraphInterface>(graph))) {}
+
+  MonotonicFixpointIteratorBase(MonotonicFixpointIteratorBase&&) = default;
+
+  // Releases the retained states from the memory ceiling, if any.
+  ~MonotonicFixpointIteratorBase() { release_memory(); }
 
   /*
    * This method is invoked on the head of an SCC at each iteration, whenever
@@ -129,12 +438,28 @@ class MonotonicFixpointIteratorBase
    * gives the user a way to parameterize the application of the widening
    * operator. A default widening strategy is provided, which applies the join
    * at the first iteration and then the widening at all subsequent iterations
-   * until the limit is reached.
+   * until the limit is reached. If widening points have been set, the default
+   * strategy only applies the widening at those instead. If the growth-rate
+   * widening has been enabled, it replaces the default strategy.
    */
   virtual void extrapolate(const Context& context,
                            const NodeId& node,
                            Domain* current_state,
                            const Domain& new_state) const {
+    if (m_run_tables.growth_rate_widening) {
+      m_run_tables.growth_rate_widening->extrapolate(
+          node, current_state, new_state);
+      return;
+    }
+    if (m_widening_points) {
+      if (is_widening_point(node) &&
+          context.get_global_iterations_for(node) > 0) {
+        current_state->widen_with(new_state);
+      } else {
+        current_state->join_with(new_state);
+      }
+      return;
+    }
     if (context.get_local_iterations_for(node) == 0) {
       current_state->join_with(new_state);
     } else {
@@ -146,6 +471,9 @@ class MonotonicFixpointIteratorBase
    * Returns the invariant computed by the fixpoint iterator at a node entry.
    */
   Domain get_entry_state_at(const NodeId& node) const {
+    if (const auto* compressed = get_compressed_states(node)) {
+      return m_decompress(compressed->first);
+    }
     auto it = m_entry_states.find(node);
     return (it == m_entry_states.end()) ? Domain::bottom() : it->second;
   }
@@ -154,6 +482,9 @@ class MonotonicFixpointIteratorBase
    * Returns the invariant computed by the fixpoint iterator at a node exit.
    */
   Domain get_exit_state_at(const NodeId& node) const {
+    if (const auto* compressed = get_compressed_states(node)) {
+      return m_decompress(compressed->second);
+    }
     auto it = m_exit_states.find(node);
     // It's impossible to get rid of this condition by initializing all exit
     // states to _|_ prior to starting the fixpoint iteration. The reason is
@@ -172,35 +503,445 @@ class MonotonicFixpointIteratorBase
     return (it == m_exit_states.end()) ? Domain::bottom() : it->second;
   }
 
-  void clear() {
+  /*
+   * Discards all the invariants computed so far. The underlying hashtables
+   * retain their capacity, so that subsequent runs over the same graph don't
+   * incur the cost of growing them again.
+   */
+  void reset() {
+    release_memory();
+    clear_metadata();
     m_entry_states.clear();
     m_exit_states.clear();
+    m_run_tables.clear();
   }
 
-  void set_all_to_bottom(std::unordered_set<NodeId>& all_nodes) {
-    // Pre-populate entry and exit states for all nodes.
+  /*
+   * Same as reset().
+   */
+  void clear() { reset(); }
+
+  /*
+   * Discards all the invariants computed so far and releases the memory held
+   * by the underlying hashtables. This should be used when the fixpoint
+   * iterator is kept alive but is not expected to run again anytime soon.
+   */
+  void clear_and_shrink() {
+    release_memory();
+    clear_metadata();
+    std::unordered_map<NodeId, Domain, NodeHash>().swap(m_entry_states);
+    std::unordered_map<NodeId, Domain, NodeHash>().swap(m_exit_states);
+    m_run_tables.clear_and_shrink();
+  }
+
+  /*
+   * Reserves enough space in the tables of entry and exit states to hold the
+   * invariants of the given number of nodes without rehashing.
+   */
+  void reserve(size_t num_nodes) {
+    m_entry_states.reserve(num_nodes);
+    m_exit_states.reserve(num_nodes);
+  }
+
+  /*
+   * Pre-populates the tables of states for all the nodes. Afterwards, the
+   * tables are never modified structurally during an iteration: the accesses
+   * to the state of a node are lookups, which can run concurrently without
+   * any locking, and the state of a node is only written by the worker that
+   * processes the node. This is what lets the concurrent iteration scale with
+   * the number of threads.
+   */
+  void set_all_to_bottom(
+      const std::unordered_set<NodeId, NodeHash>& all_nodes) {
+    release_memory();
+    m_run_tables.clear();
     for (auto& node : all_nodes) {
       m_entry_states[node] = Domain::bottom();
       m_exit_states[node] = Domain::bottom();
+      if (m_memory_ceiling != nullptr) {
+        m_run_tables.charged_sizes[node] = 0;
+      }
+      if (m_head_digest) {
+        m_run_tables.stabilized_digests[node] = boost::none;
+      }
+      if (m_compress) {
+        m_run_tables.compressed_states[node] = boost::none;
+      }
+    }
+  }
+
+  /*
+   * Returns the slot of the node in a table, which is initialized with the
+   * given value if the node is missing. This only performs a lookup if the
+   * tables have been pre-populated (see set_all_to_bottom).
+   */
+  template <typename Table, typename Value>
+  static typename Table::mapped_type& get_slot(Table* table,
+                                               const NodeId& node,
+                                               const Value& init) {
+    auto it = table->find(node);
+    if (it != table->end()) {
+      return it->second;
+    }
+    return table->emplace(node, init).first->second;
+  }
+
+  /*
+   * When a node has at least `threshold` predecessors (e.g., the node
+   * following a large switch statement), the states along its incoming edges
+   * are computed and joined in parallel over `num_thread` threads. This
+   * requires `analyze_edge` to be thread-safe. A threshold of 0 disables the
+   * parallel join, which is the default.
+   *
+   * The additional threads are started at the beginning of each run and are
+   * shared by all the joins of the run, including the ones requested
+   * concurrently by the workers of a parallel fixpoint iterator. Hence the
+   * parallel join never uses more than `num_thread - 1` additional threads.
+   */
+  void set_parallel_join(size_t threshold, size_t num_thread) {
+    m_parallel_join_threshold = threshold;
+    m_parallel_join_num_thread = num_thread;
+  }
+
+  /*
+   * Records the steps of the subsequent runs of a fixpoint iterator based on a
+   * weak partial ordering into the given trace, along with the digests of the
+   * abstract states computed at each step (see FixpointTrace.h). The digest
+   * function is optional, and the node information is only used to label the
+   * nodes of the trace. Passing a null trace disables the tracing.
+   */
+  void set_trace(FixpointTrace* trace,
+                 std::function<uint64_t(const Domain&)> digest = nullptr,
+                 const NodeInfo<NodeId>* node_info = nullptr) {
+    m_trace = trace;
+    m_trace_digest = std::move(digest);
+    m_trace_node_info = node_info;
+  }
+
+  /*
+   * Describes the nodes in the errors raised during the subsequent runs, i.e.,
+   * the errors of the iterator itself and the ones thrown by the transformers
+   * and the abstract domain, which carry the description of the node being
+   * analyzed as a `node_name` (see Exceptions.h). The node information must
+   * outlive the fixpoint iterator. Passing null restores the default
+   * description, which is the node identifier if it can be printed.
+   */
+  void set_node_info(const NodeInfo<NodeId>* node_info) {
+    m_node_info = node_info;
+  }
+
+  /*
+   * Speeds up the stabilization check of the components whose head state is
+   * unchanged since the component last stabilized, which is typical of the
+   * inner loops in the later iterations of a stable outer loop. The digest of
+   * the entry state of each head is recorded whenever its component
+   * stabilizes, and the next check of the component succeeds without
+   * computing `leq` if the digest of the new entry state is the same.
+   *
+   * This is only sound if the digests of two states are equal exactly when
+   * the states are equal, i.e., the domain has canonical forms and the digest
+   * is collision-free on the states computed by the analysis, and if the
+   * extrapolation never decreases the entry state of a head, as is the case of
+   * the default strategy. Passing a null digest disables the optimization.
+   */
+  void set_head_digest(std::function<uint64_t(const Domain&)> digest) {
+    m_head_digest = std::move(digest);
+  }
+
+  /*
+   * Compresses the entry and exit states of the nodes of an outermost
+   * component of the graph as soon as the component has stabilized, e.g., by
+   * serializing them into a compact binary format. These states are never
+   * updated afterwards, and they are only read again when the results of the
+   * analysis are queried. The compressed states are decompressed on each
+   * access, which trades some time for a lower peak memory usage when most of
+   * the states belong to loops. The states of the nodes outside of any loop
+   * are left untouched, and so are the states of the nodes with a successor
+   * outside of their component, since the iteration reads them in order to
+   * compute the states of the successors of the component. The compression
+   * should not be changed between a run and the queries of its results.
+   * Passing null functions disables the compression.
+   */
+  void set_state_compression(
+      std::function<std::string(const Domain&)> compress,
+      std::function<Domain(const std::string&)> decompress) {
+    RUNTIME_CHECK(static_cast<bool>(compress) == static_cast<bool>(decompress),
+                  invalid_argument()
+                      << error_msg("Both functions must be provided"));
+    m_compress = std::move(compress);
+    m_decompress = std::move(decompress);
+  }
+
+  /*
+   * Accounts for the states computed by the subsequent runs in the given
+   * memory ceiling, which may be shared with other fixpoint iterators. The
+   * size of a state is given by size_hint_of() unless a size function is
+   * provided. Once the ceiling is exceeded, the entry state of every head of a
+   * component that has not stabilized is set to Top instead of being
+   * extrapolated. The iteration then converges quickly to a sound but coarse
+   * result, instead of exhausting the memory of the process. Passing a null
+   * ceiling disables the accounting.
+   *
+   * The states retained by the iterator are released from the ceiling when
+   * it is detached, when the iterator is cleared and when it is destroyed.
+   * Hence the ceiling must outlive the iterator, unless it is detached first
+   * by passing a null ceiling.
+   */
+  void set_memory_ceiling(
+      MemoryCeiling* ceiling,
+      std::function<size_t(const Domain&)> size_of = nullptr) {
+    release_memory();
+    m_memory_ceiling = ceiling;
+    m_size_of = size_of ? std::move(size_of) : [](const Domain& x) {
+      return size_hint_of(x);
+    };
+  }
+
+  /*
+   * Records the extrapolations of the subsequent runs that lose precision
+   * into the given provenance (see WideningProvenance.h). The provenance must
+   * outlive the fixpoint iterator. Passing a null provenance disables the
+   * recording.
+   */
+  void set_widening_provenance(
+      WideningProvenance<NodeId, Domain, NodeHash>* provenance) {
+    m_widening_provenance = provenance;
+  }
+
+  /*
+   * Restricts the widening performed by the default extrapolation strategy to
+   * the given nodes, e.g., the ones returned by compute_widening_points() (see
+   * WideningPoints.h). The heads of the components that are not widening
+   * points apply the join instead. A widening point applies the join the
+   * first time its component is extrapolated, and the widening at all the
+   * subsequent extrapolations, including those in later iterations of an
+   * enclosing component.
+   *
+   * The iteration terminates as long as every cycle of the graph contains a
+   * widening point. Indeed, the successive entry states of a widening point
+   * form a widening sequence, which is finite. Since every cycle goes through
+   * a widening point, the state of any other node only depends on finitely
+   * many states of the widening points along the acyclic paths that lead to
+   * the node, and hence it eventually stabilizes as well. Passing none
+   * restores the default strategy.
+   */
+  void set_widening_points(
+      boost::optional<std::unordered_set<NodeId, NodeHash>> points) {
+    m_widening_points = std::move(points);
+  }
+
+  /*
+   * Replaces the default extrapolation strategy by one that escalates from
+   * the join to more and more aggressive widenings as the entry state of a
+   * head keeps growing (see GrowthRateWidening.h). The size of a state is
+   * given by size_hint_of() unless a size function is provided. Passing none
+   * restores the default strategy.
+   */
+  void set_growth_rate_widening(
+      boost::optional<GrowthRateWideningOptions> options,
+      std::function<size_t(const Domain&)> size_of = nullptr) {
+    if (options) {
+      m_run_tables.growth_rate_widening = std::make_unique<
+          GrowthRateWidening<NodeId, Domain, NodeHash>>(*options,
+                                                        std::move(size_of));
+    } else {
+      m_run_tables.growth_rate_widening.reset();
+    }
+  }
+
+  /*
+   * Returns the level of the growth-rate widening that the next
+   * extrapolation of a head would use in the last run. This is always Join if
+   * the growth-rate widening is not enabled.
+   */
+  WideningLevel get_widening_level(const NodeId& head) const {
+    return m_run_tables.growth_rate_widening
+               ? m_run_tables.growth_rate_widening->level(head)
+               : WideningLevel::Join;
+  }
+
+  /*
+   * Describes the configuration of the subsequent runs, i.e., the
+   * extrapolation strategy and the budgets, in a manifest (see
+   * AnalysisManifest.h). A custom extrapolate() method is not reflected.
+   */
+  void describe(AnalysisManifest* manifest) const {
+    auto& strategy = manifest->section("strategy");
+    if (m_run_tables.growth_rate_widening) {
+      const auto& options = m_run_tables.growth_rate_widening->options();
+      strategy.set("extrapolation", "growth_rate");
+      strategy.section("growth_rate")
+          .set("max_joins", options.max_joins)
+          .set("widening_delay", options.widening_delay)
+          .set("patience", options.patience)
+          .set("max_growth_ratio", options.max_growth_ratio);
+    } else if (m_widening_points) {
+      strategy.set("extrapolation", "widening_points");
+      strategy.set("num_widening_points", m_widening_points->size());
+    } else {
+      strategy.set("extrapolation", "default");
+    }
+    strategy.set("initial_nodes", static_cast<bool>(m_is_initial))
+        .set("head_digest", static_cast<bool>(m_head_digest))
+        .set("dead_bindings", static_cast<bool>(m_remove_dead_bindings))
+        .set("state_compression", static_cast<bool>(m_compress));
+    auto& budgets = manifest->section("budgets");
+    if (m_memory_ceiling != nullptr) {
+      budgets.set("memory_ceiling", m_memory_ceiling->limit());
+    } else {
+      budgets.set_null("memory_ceiling");
+    }
+    budgets.set("parallel_join_threshold", m_parallel_join_threshold)
+        .set("parallel_join_threads", m_parallel_join_num_thread)
+        .set("state_history_depth",
+             m_run_tables.history ? m_run_tables.history->depth() : 0);
+  }
+
+  bool is_widening_point(const NodeId& node) const {
+    return m_widening_points && m_widening_points->count(node) > 0;
+  }
+
+  /*
+   * By default, the initial value passed to run() is only joined into the
+   * entry state of the entry node of the graph. This sets the nodes into which
+   * it is joined as well, e.g., all the nodes, as required by the boundary
+   * conditions of some must-analyses. Since the iteration only visits the
+   * nodes that are reachable from the entry of the graph, a backward analysis
+   * of a graph with several exits should still use a graph interface with a
+   * single exit and join the initial value at the others. Passing a null
+   * predicate restores the default.
+   */
+  void set_initial_nodes(std::function<bool(const NodeId&)> is_initial) {
+    m_is_initial = std::move(is_initial);
+  }
+
+  /*
+   * A debugging aid that retains, for every head of a component, the entry
+   * states computed by its last `depth` extrapolations in the subsequent
+   * runs, so that the convergence of a component (e.g., an oscillation before
+   * the widening kicks in) can be inspected once the run is over. Passing 0
+   * disables the recording.
+   */
+  void set_state_history(size_t depth) {
+    if (depth == 0) {
+      m_run_tables.history.reset();
+    } else {
+      m_run_tables.history =
+          std::make_unique<StateHistory<NodeId, Domain, NodeHash>>(depth);
     }
   }
 
+  /*
+   * Returns the entry state of a head after its k-th extrapolation in the last
+   * run, where the count includes the extrapolations performed in previous
+   * iterations of the enclosing components. The state with which the head was
+   * analyzed before its first extrapolation is returned for k = 0. Returns
+   * none if the state is not retained (see set_state_history).
+   */
+  boost::optional<Domain> get_entry_state_at_iteration(const NodeId& head,
+                                                       uint32_t k) const {
+    if (!m_run_tables.history) {
+      return boost::none;
+    }
+    return m_run_tables.history->get(head, k);
+  }
+
+  /*
+   * Returns the number of extrapolations of a head in the last run, provided
+   * that the state history is recorded.
+   */
+  uint32_t get_num_extrapolations(const NodeId& head) const {
+    return m_run_tables.history ? m_run_tables.history->last_iteration(head)
+                                : 0;
+  }
+
+  /*
+   * Removes the bindings of the variables that are dead after a node, e.g.,
+   * according to a prior liveness analysis, from the exit state of the node
+   * before it is propagated to the successors. Keeping dead bindings around
+   * only makes the joins downstream more expensive. The function returns a
+   * container of the variables that are dead after a given node, and the
+   * domain must provide an unset_all() method taking such a container, like
+   * PatriciaTreeMapAbstractEnvironment. Passing a null function disables the
+   * removal.
+   */
+  template <typename DeadAfter>
+  void set_dead_bindings(DeadAfter dead_after) {
+    m_remove_dead_bindings = [dead_after = std::move(dead_after)](
+                                 const NodeId& node, Domain* state) {
+      state->unset_all(dead_after(node));
+    };
+  }
+
+  void set_dead_bindings(std::nullptr_t) { m_remove_dead_bindings = nullptr; }
+
+  /*
+   * Registers a table of per-node metadata filled by the node transformer
+   * (see NodeMetadata.h). The table must outlive the fixpoint iterator.
+   */
+  void attach_metadata(nm_impl::NodeMetadataStore<NodeId>* metadata) {
+    m_metadata.push_back(metadata);
+  }
+
+  /*
+   * Runs the threads of the parallel join, if it is enabled, for as long as
+   * it is alive, i.e., for the duration of a run.
+   */
+  class ParallelJoinScope final {
+   public:
+    explicit ParallelJoinScope(MonotonicFixpointIteratorBase* iterator)
+        : m_iterator(iterator) {
+      if (iterator->m_parallel_join_threshold > 0 &&
+          iterator->m_parallel_join_num_thread > 1) {
+        iterator->m_join_pool = std::make_unique<JoinThreadPool>(
+            iterator->m_parallel_join_num_thread - 1);
+      }
+    }
+
+    ParallelJoinScope(const ParallelJoinScope&) = delete;
+
+    ~ParallelJoinScope() { m_iterator->m_join_pool.reset(); }
+
+   private:
+    MonotonicFixpointIteratorBase* m_iterator;
+  };
+
+  ParallelJoinScope start_parallel_join() { return ParallelJoinScope(this); }
+
   void compute_entry_state(Context* context,
                            const NodeId& node,
                            Domain* entry_state) {
-    if (node == GraphInterface::entry(m_graph)) {
+    if (node == GraphInterface::entry(m_graph) ||
+        (m_is_initial && m_is_initial(node))) {
       entry_state->join_with(context->get_initial_value());
     }
-    for (EdgeId edge : GraphInterface::predecessors(m_graph, node)) {
+    const auto& predecessors = GraphInterface::predecessors(m_graph, node);
+    if (m_join_pool != nullptr &&
+        static_cast<size_t>(std::distance(predecessors.begin(),
+                                          predecessors.end())) >=
+            m_parallel_join_threshold) {
+      entry_state->join_with(join_incoming_states(
+          std::vector<EdgeId>(predecessors.begin(), predecessors.end())));
+      return;
+    }
+    for (EdgeId edge : predecessors) {
       entry_state->join_with(this->analyze_edge(
           edge, get_exit_state_at(GraphInterface::source(m_graph, edge))));
     }
   }
 
   void analyze_vertex(Context* context, const NodeId& node) {
+    try {
+      analyze_vertex_unchecked(context, node);
+    } catch (boost::exception& e) {
+      add_node_context(&e, node);
+      throw;
+    }
+  }
+
+  void analyze_vertex_unchecked(Context* context, const NodeId& node) {
     // Retrieve the entry state. If it does not exist, set it to bottom.
-    Domain& entry_state =
-        m_entry_states.emplace(node, Domain::bottom()).first->second;
+    Domain& entry_state = get_slot(&m_entry_states, node, Domain::bottom());
     // We should be careful not to access m_exit_states[node] before computing
     // the entry state, as this may silently initialize it with an unwanted
     // value (i.e., the default-constructed value of Domain). This can in turn
@@ -210,14 +951,356 @@ class MonotonicFixpointIteratorBase
     // contain unreachable nodes pointing to reachable ones (see the
     // documentation of `get_exit_state_at`).
     compute_entry_state(context, node, &entry_state);
-    Domain& exit_state = m_exit_states[node];
+    Domain& exit_state = get_slot(&m_exit_states, node, Domain::bottom());
     exit_state = entry_state;
+    for (auto* metadata : m_metadata) {
+      metadata->discard(node);
+    }
     this->analyze_node(node, &exit_state);
+    if (m_remove_dead_bindings) {
+      m_remove_dead_bindings(node, &exit_state);
+    }
+    account_for(node, entry_state, exit_state);
+  }
+
+  /*
+   * Whether the component of the head has stabilized, i.e., the newly computed
+   * entry state of the head is subsumed by the current one (see
+   * set_head_digest). If so, the new state is expected to replace the current
+   * one.
+   */
+  bool is_stable(const NodeId& head,
+                 const Domain& current_state,
+                 const Domain& new_state) {
+    if (!m_head_digest) {
+      return new_state.leq(current_state);
+    }
+    uint64_t digest = m_head_digest(new_state);
+    auto& stabilized = get_slot(
+        &m_run_tables.stabilized_digests, head, boost::optional<uint64_t>());
+    // The current state is an upper bound of the state with which the
+    // component last stabilized.
+    if ((stabilized && *stabilized == digest) ||
+        new_state.leq(current_state)) {
+      stabilized = digest;
+      return true;
+    }
+    return false;
+  }
+
+  /*
+   * Updates the entry state of the head of a component that has not
+   * stabilized, either by extrapolation or, once the memory ceiling has been
+   * exceeded, by setting it to Top.
+   */
+  void extrapolate_head(const Context& context,
+                        const NodeId& head,
+                        Domain* current_state,
+                        const Domain& new_state) {
+    try {
+      extrapolate_head_unchecked(context, head, current_state, new_state);
+    } catch (boost::exception& e) {
+      add_node_context(&e, head);
+      throw;
+    }
+  }
+
+  /*
+   * Attaches the description of a node to an error, unless it already
+   * describes a node, e.g., a node of a nested analysis.
+   */
+  void add_node_context(boost::exception* e, const NodeId& node) const {
+    if (boost::get_error_info<node_name>(*e) == nullptr) {
+      *e << node_name(describe_node(node, m_node_info));
+    }
+  }
+
+  /*
+   * Checks that the scheduling counter of a node of the weak partial ordering
+   * has the expected value, which can only fail if the ordering doesn't match
+   * the graph, e.g., if the graph has been modified after the ordering has
+   * been computed.
+   */
+  void check_counter(const WeakPartialOrdering<NodeId, NodeHash>& wpo,
+                     uint32_t wpo_idx,
+                     uint32_t counter,
+                     uint32_t expected) const {
+    RUNTIME_CHECK(
+        counter == expected,
+        internal_error()
+            << error_msg("Scheduling counter mismatch: expected " +
+                         std::to_string(expected) + ", found " +
+                         std::to_string(counter) +
+                         (wpo.is_exit(wpo_idx) ? " at the exit of the head"
+                                               : ""))
+            << wpo_index(wpo_idx)
+            << node_name(describe_node(wpo.get_node(wpo_idx), m_node_info)));
+  }
+
+  void extrapolate_head_unchecked(const Context& context,
+                                  const NodeId& head,
+                                  Domain* current_state,
+                                  const Domain& new_state) {
+    boost::optional<Domain> previous_state;
+    if (m_widening_provenance != nullptr) {
+      previous_state = *current_state;
+    }
+    uint32_t iteration = context.get_global_iterations_for(head);
+    if (iteration == 0) {
+      record_history(head, iteration, *current_state);
+    }
+    if (m_memory_ceiling != nullptr && m_memory_ceiling->exceeded()) {
+      current_state->set_to_top();
+    } else {
+      this->extrapolate(context, head, current_state, new_state);
+    }
+    if (m_widening_provenance != nullptr) {
+      m_widening_provenance->record(head,
+                                    context.get_global_iterations_for(head),
+                                    *previous_state,
+                                    new_state,
+                                    *current_state);
+    }
+    record_history(head, iteration + 1, *current_state);
+    account_for(head,
+                *current_state,
+                get_slot(&m_exit_states, head, Domain::bottom()));
+  }
+
+  void record_history(const NodeId& head,
+                      uint32_t iteration,
+                      const Domain& state) {
+    if (m_run_tables.history) {
+      m_run_tables.history->record(head, iteration, state);
+    }
+  }
+
+  /*
+   * Charges the memory ceiling for the difference between the current size of
+   * the states of a node and the size they had when last accounted for.
+   */
+  void account_for(const NodeId& node,
+                   const Domain& entry_state,
+                   const Domain& exit_state) {
+    if (m_memory_ceiling == nullptr) {
+      return;
+    }
+    charge_for(node, m_size_of(entry_state) + m_size_of(exit_state));
+  }
+
+  void charge_for(const NodeId& node, size_t size) {
+    size_t previous_size =
+        get_slot(&m_run_tables.charged_sizes, node, size_t(0)).exchange(size);
+    if (size > previous_size) {
+      m_memory_ceiling->charge(size - previous_size);
+    } else {
+      m_memory_ceiling->release(previous_size - size);
+    }
+  }
+
+  bool is_compressing_states() const { return static_cast<bool>(m_compress); }
+
+  /*
+   * Replaces the states of the given nodes by their compressed form (see
+   * set_state_compression). The compressed states are charged to the memory
+   * ceiling by their size in bytes.
+   */
+  void compress_states(const std::vector<NodeId>& nodes) {
+    for (const auto& node : nodes) {
+      Domain& entry_state = get_slot(&m_entry_states, node, Domain::bottom());
+      Domain& exit_state = get_slot(&m_exit_states, node, Domain::bottom());
+      auto& compressed = get_slot(&m_run_tables.compressed_states,
+                                  node,
+                                  boost::optional<CompressedStates>());
+      compressed =
+          CompressedStates(m_compress(entry_state), m_compress(exit_state));
+      entry_state = Domain::bottom();
+      exit_state = Domain::bottom();
+      if (m_memory_ceiling != nullptr) {
+        charge_for(node, compressed->first.size() + compressed->second.size());
+      }
+    }
+  }
+
+  /*
+   * Collects the nodes of the outermost components of a WPO, whose states are
+   * compressed once the component has stabilized. This is only done once,
+   * since the graph of a fixpoint iterator never changes.
+   */
+  void prepare_state_compression(
+      const WeakPartialOrdering<NodeId, NodeHash>& wpo) {
+    if (!m_compress || !m_outermost_components.empty()) {
+      return;
+    }
+    for (uint32_t idx = 0; idx < wpo.size(); ++idx) {
+      if (wpo.is_exit(idx)) {
+        continue;
+      }
+      uint32_t head_idx = idx;
+      while (wpo.get_parent(head_idx) != head_idx) {
+        head_idx = wpo.get_parent(head_idx);
+      }
+      if (wpo.is_head(head_idx)) {
+        m_outermost_components[head_idx].push_back(wpo.get_node(idx));
+      }
+    }
+    for (auto& component : m_outermost_components) {
+      remove_boundary_nodes(&component.second);
+    }
+  }
+
+  /*
+   * Removes the nodes with a successor outside of the component from the
+   * nodes of a component, so that their states remain uncompressed.
+   */
+  void remove_boundary_nodes(std::vector<NodeId>* nodes) const {
+    std::unordered_set<NodeId, NodeHash> component(nodes->begin(),
+                                                   nodes->end());
+    auto is_boundary = [&](const NodeId& node) {
+      for (const auto& edge : GraphInterface::successors(m_graph, node)) {
+        if (component.count(GraphInterface::target(m_graph, edge)) == 0) {
+          return true;
+        }
+      }
+      return false;
+    };
+    nodes->erase(std::remove_if(nodes->begin(), nodes->end(), is_boundary),
+                 nodes->end());
+  }
+
+  /*
+   * Compresses the states of a component of a WPO that has just stabilized,
+   * if it is an outermost component.
+   */
+  void compress_component(const WeakPartialOrdering<NodeId, NodeHash>& wpo,
+                          uint32_t head_idx) {
+    if (m_compress && wpo.get_parent(head_idx) == head_idx) {
+      compress_states(m_outermost_components.at(head_idx));
+    }
+  }
+
+  void clear_metadata() {
+    for (auto* metadata : m_metadata) {
+      metadata->clear();
+    }
+  }
+
+  void release_memory() {
+    if (m_memory_ceiling != nullptr) {
+      size_t total = 0;
+      for (const auto& entry : m_run_tables.charged_sizes) {
+        total += entry.second;
+      }
+      m_memory_ceiling->release(total);
+    }
+    m_run_tables.charged_sizes.clear();
+  }
+
+  /*
+   * Joins the states along the edges by splitting them into one chunk per
+   * thread. The partial joins of the chunks are computed by the threads of
+   * the parallel join and then joined sequentially.
+   */
+  Domain join_incoming_states(const std::vector<EdgeId>& edges) const {
+    size_t num_chunks = std::min(m_parallel_join_num_thread, edges.size());
+    size_t chunk_size = (edges.size() + num_chunks - 1) / num_chunks;
+    std::vector<Domain> partial_joins(num_chunks, Domain::bottom());
+    // The exceptions are rethrown once all the chunks have been processed.
+    std::vector<std::exception_ptr> exceptions(num_chunks);
+    m_join_pool->run(num_chunks, [&](size_t chunk) {
+      size_t end = std::min(edges.size(), (chunk + 1) * chunk_size);
+      try {
+        for (size_t i = chunk * chunk_size; i < end; ++i) {
+          partial_joins[chunk].join_with(this->analyze_edge(
+              edges[i],
+              get_exit_state_at(GraphInterface::source(m_graph, edges[i]))));
+        }
+      } catch (...) {
+        exceptions[chunk] = std::current_exception();
+      }
+    });
+    Domain result = Domain::bottom();
+    for (size_t chunk = 0; chunk < num_chunks; ++chunk) {
+      if (exceptions[chunk]) {
+        std::rethrow_exception(exceptions[chunk]);
+      }
+      result.join_with(partial_joins[chunk]);
+    }
+    return result;
+  }
+
+  /*
+   * Returns the state that is summarized by the digest of a traced step, i.e.,
+   * the exit state of the node for a plain node or the head of a component,
+   * and the entry state of the head for the exit of a component.
+   */
+  Domain get_traced_state(const WeakPartialOrdering<NodeId, NodeHash>& wpo,
+                          uint32_t wpo_idx) const {
+    if (wpo.is_exit(wpo_idx)) {
+      return get_entry_state_at(wpo.get_node(wpo.get_head_of_exit(wpo_idx)));
+    }
+    return get_exit_state_at(wpo.get_node(wpo_idx));
+  }
+
+  size_t begin_trace_event(uint32_t wpo_idx) {
+    return m_trace != nullptr ? m_trace->begin_event(wpo_idx) : 0;
+  }
+
+  /*
+   * This must be called before scheduling the successors of the step, which
+   * may modify the traced state in a concurrent iteration.
+   */
+  void end_trace_event(size_t slot,
+                       FixpointTraceEvent::Kind kind,
+                       const WeakPartialOrdering<NodeId, NodeHash>& wpo,
+                       uint32_t wpo_idx) {
+    if (m_trace != nullptr) {
+      m_trace->end_event(slot, kind, digest_of(get_traced_state(wpo, wpo_idx)));
+    }
+  }
+
+  uint64_t digest_of(const Domain& state) const {
+    return m_trace_digest ? m_trace_digest(state) : 0;
   }
 
   const Graph& m_graph;
   std::unordered_map<NodeId, Domain, NodeHash> m_entry_states;
   std::unordered_map<NodeId, Domain, NodeHash> m_exit_states;
+  std::function<bool(const NodeId&)> m_is_initial;
+  boost::optional<std::unordered_set<NodeId, NodeHash>> m_widening_points;
+  size_t m_parallel_join_threshold{0};
+  size_t m_parallel_join_num_thread{1};
+  FixpointTrace* m_trace{nullptr};
+  std::function<uint64_t(const Domain&)> m_trace_digest;
+  const NodeInfo<NodeId>* m_trace_node_info{nullptr};
+  const NodeInfo<NodeId>* m_node_info{nullptr};
+  MemoryCeiling* m_memory_ceiling{nullptr};
+  WideningProvenance<NodeId, Domain, NodeHash>* m_widening_provenance{
+      nullptr};
+  std::function<size_t(const Domain&)> m_size_of;
+  std::vector<nm_impl::NodeMetadataStore<NodeId>*> m_metadata;
+  std::function<void(const NodeId&, Domain*)> m_remove_dead_bindings;
+  std::function<uint64_t(const Domain&)> m_head_digest;
+  std::function<std::string(const Domain&)> m_compress;
+  std::function<Domain(const std::string&)> m_decompress;
+  std::unordered_map<uint32_t, std::vector<NodeId>> m_outermost_components;
+  RunTables<NodeId, Domain, NodeHash> m_run_tables;
+  std::unique_ptr<JoinThreadPool> m_join_pool;
+
+ private:
+  using CompressedStates =
+      typename RunTables<NodeId, Domain, NodeHash>::CompressedStates;
+
+  const CompressedStates* get_compressed_states(const NodeId& node) const {
+    if (!m_decompress) {
+      return nullptr;
+    }
+    auto it = m_run_tables.compressed_states.find(node);
+    if (it == m_run_tables.compressed_states.end() || !it->second) {
+      return nullptr;
+    }
+    return &*it->second;
+  }
 };
 
 } // namespace fp_impl
@@ -249,17 +1332,8 @@ class WTOMonotonicFixpointIterator
       : fp_impl::MonotonicFixpointIteratorBase<GraphInterface,
                                                Domain,
                                                NodeHash>(graph, cfg_size_hint),
-        m_wto(GraphInterface::entry(graph), [=, &graph](const NodeId& x) {
-          const auto& succ_edges = GraphInterface::successors(graph, x);
-          std::vector<NodeId> succ_nodes;
-          std::transform(succ_edges.begin(),
-                         succ_edges.end(),
-                         std::back_inserter(succ_nodes),
-                         std::bind(&GraphInterface::target,
-                                   std::ref(graph),
-                                   std::placeholders::_1));
-          return succ_nodes;
-        }) {}
+        m_wto(GraphInterface::entry(graph),
+              successor_nodes<GraphInterface, NodeHash>(graph)) {}
 
   /*
    * Executes the fixpoint iterator given an abstract value describing the
@@ -268,13 +1342,29 @@ class WTOMonotonicFixpointIterator
    * initial conditions.
    */
   void run(const Domain& init) {
-    this->clear();
+    this->reset();
+    auto parallel_join = this->start_parallel_join();
     Context context(init);
     for (const WtoComponent<NodeId>& component : m_wto) {
       analyze_component(&context, component);
+      if (component.is_scc() && this->is_compressing_states()) {
+        std::vector<NodeId> nodes;
+        collect_nodes(component, &nodes);
+        this->remove_boundary_nodes(&nodes);
+        this->compress_states(nodes);
+      }
     }
   }
 
+  /*
+   * Describes the iterator and the configuration of its runs in a manifest.
+   */
+  void describe(AnalysisManifest* manifest) const {
+    manifest->set("iterator", "wto");
+    fp_impl::MonotonicFixpointIteratorBase<GraphInterface, Domain, NodeHash>::
+        describe(manifest);
+  }
+
  private:
   void analyze_component(Context* context,
                          const WtoComponent<NodeId>& component) {
@@ -285,6 +1375,16 @@ class WTOMonotonicFixpointIterator
     }
   }
 
+  static void collect_nodes(const WtoComponent<NodeId>& component,
+                            std::vector<NodeId>* nodes) {
+    nodes->push_back(component.head_node());
+    if (component.is_scc()) {
+      for (const auto& nested : component) {
+        collect_nodes(nested, nodes);
+      }
+    }
+  }
+
   void analyze_scc(Context* context, const WtoComponent<NodeId>& scc) {
     NodeId head = scc.head_node();
     bool iterate = true;
@@ -301,7 +1401,7 @@ class WTOMonotonicFixpointIterator
       Domain* current_state = &this->m_entry_states[head];
       Domain new_state = Domain::bottom();
       this->compute_entry_state(context, head, &new_state);
-      if (new_state.leq(*current_state)) {
+      if (this->is_stable(head, *current_state, new_state)) {
         // At this point we know that the monotonic iteration sequence has
         // converged and current_state is a post-fixpoint. However, since all
         // the node and edge transformers are monotonic, new_state is also a
@@ -313,7 +1413,7 @@ class WTOMonotonicFixpointIterator
         *current_state = std::move(new_state);
         iterate = false;
       } else {
-        this->extrapolate(*context, head, current_state, new_state);
+        this->extrapolate_head(*context, head, current_state, new_state);
       }
     }
   }
@@ -349,29 +1449,9 @@ class ParallelMonotonicFixpointIterator
       : fp_impl::
             MonotonicFixpointIteratorBase<GraphInterface, Domain, NodeHash>(
                 graph, /*cfg_size_hint*/ 4),
-        m_wpo(
-            GraphInterface::entry(graph),
-            [=, &graph](const NodeId& x) {
-              const auto& succ_edges = GraphInterface::successors(graph, x);
-              std::vector<NodeId> succ_nodes_tmp;
-              std::transform(succ_edges.begin(),
-                             succ_edges.end(),
-                             std::back_inserter(succ_nodes_tmp),
-                             std::bind(&GraphInterface::target,
-                                       std::ref(graph),
-                                       std::placeholders::_1));
-              // Filter out duplicate succ nodes.
-              std::vector<NodeId> succ_nodes;
-              std::unordered_set<NodeId> succ_nodes_set;
-              for (auto node : succ_nodes_tmp) {
-                if (!succ_nodes_set.count(node)) {
-                  succ_nodes_set.emplace(node);
-                  succ_nodes.emplace_back(node);
-                }
-              }
-              return succ_nodes;
-            },
-            false),
+        m_wpo(GraphInterface::entry(graph),
+              successor_nodes<GraphInterface, NodeHash>(graph),
+              false),
         m_num_thread(num_thread) {
     // Gathering all reachable nodes in graph.
     std::stack<NodeId> node_queue;
@@ -388,6 +1468,41 @@ class ParallelMonotonicFixpointIterator
         }
       }
     }
+    this->reserve(m_all_nodes.size());
+  }
+
+  /*
+   * Joins the incoming states of the nodes that have at least `threshold`
+   * predecessors in parallel, using the same number of threads as the
+   * fixpoint iteration. See MonotonicFixpointIteratorBase::set_parallel_join.
+   */
+  void set_parallel_join_threshold(size_t threshold) {
+    this->set_parallel_join(threshold, m_num_thread);
+  }
+
+  /*
+   * Limits the number of tasks of a component that can be analyzed
+   * concurrently. The nodes of a loop usually depend on each other, so there
+   * is little to gain from running many of them at the same time, whereas
+   * independent components can still be analyzed in parallel. The limit
+   * applies to the innermost component of a node, the nodes outside of any
+   * component are never throttled. A limit of 0, the default, means no limit.
+   */
+  void set_component_concurrency(size_t max_tasks) {
+    m_component_concurrency = max_tasks;
+  }
+
+  /*
+   * Pins the components that have at least `min_size` nodes in the WPO
+   * (including the nested components and the exit) to a single worker: when
+   * a task of a pinned component is picked up, that worker keeps analyzing
+   * the tasks of the component as long as there are some, instead of sending
+   * them back to the work queue. This avoids bouncing the states of a very
+   * large component between threads. Only the outermost such component is
+   * pinned. A size of 0, the default, disables pinning.
+   */
+  void set_component_pinning(size_t min_size) {
+    m_pinning_threshold = min_size;
   }
 
   /*
@@ -398,102 +1513,237 @@ class ParallelMonotonicFixpointIterator
    */
   void run(const Domain& init) {
     this->set_all_to_bottom(m_all_nodes);
+    if (this->m_trace != nullptr) {
+      this->m_trace->set_wpo(m_wpo, this->m_trace_node_info);
+    }
+    auto parallel_join = this->start_parallel_join();
     Context context(init, m_all_nodes);
     std::unique_ptr<std::atomic<uint32_t>[]> wpo_counter(
         new std::atomic<uint32_t>[m_wpo.size()]);
     std::fill_n(wpo_counter.get(), m_wpo.size(), 0);
     auto entry_idx = m_wpo.get_entry();
     assert(m_wpo.get_num_preds(entry_idx) == 0);
+    this->prepare_state_compression(m_wpo);
+    std::vector<std::unique_ptr<Lane>> lanes;
+    std::vector<Lane*> lane_of = assign_lanes(&lanes);
+    // Pushes a task whose counter matches its NumSchedPreds, unless its
+    // component has no free slot, in which case the task is deferred until a
+    // task of the component completes.
+    auto schedule = [&lane_of](WPOWorkerState* worker_state, uint32_t wpo_idx) {
+      Lane* lane = lane_of[wpo_idx];
+      if (lane != nullptr) {
+        std::lock_guard<std::mutex> guard(lane->mutex);
+        if (lane->active == lane->capacity) {
+          lane->pending.push(wpo_idx);
+          return;
+        }
+        ++lane->active;
+      }
+      worker_state->push_task(wpo_idx);
+    };
+    auto analyze = [&context, &entry_idx, &wpo_counter, &schedule, this](
+                       WPOWorkerState* worker_state, uint32_t wpo_idx) {
+      size_t trace_slot = this->begin_trace_event(wpo_idx);
+      std::atomic<uint32_t>& current_counter = wpo_counter[wpo_idx];
+      this->check_counter(
+          m_wpo, wpo_idx, current_counter, m_wpo.get_num_preds(wpo_idx));
+      current_counter = 0;
+      // NonExit node
+      if (!m_wpo.is_exit(wpo_idx)) {
+        this->analyze_vertex(&context, m_wpo.get_node(wpo_idx));
+        this->end_trace_event(
+            trace_slot, FixpointTraceEvent::Kind::Analyze, m_wpo, wpo_idx);
+        for (auto succ_idx : m_wpo.get_successors(wpo_idx)) {
+          std::atomic<uint32_t>& succ_counter = wpo_counter[succ_idx];
+          // Increase succ node's counter, push succ nodes in work queue if
+          // their counter number matches their NumSchedPreds.
+          if (++succ_counter == m_wpo.get_num_preds(succ_idx)) {
+            schedule(worker_state, succ_idx);
+          }
+        }
+        return;
+      }
+      // Exit node
+      // Check if component of the exit node has stabilized.
+      auto head_idx = m_wpo.get_head_of_exit(wpo_idx);
+      NodeId head = m_wpo.get_node(head_idx);
+      Domain* current_state =
+          &this->get_slot(&this->m_entry_states, head, Domain::bottom());
+      Domain new_state = Domain::bottom();
+      this->compute_entry_state(&context, head, &new_state);
+      if (this->is_stable(head, *current_state, new_state)) {
+        // Component stabilized.
+        context.reset_local_iteration_count_for(head);
+        *current_state = std::move(new_state);
+        this->end_trace_event(
+            trace_slot, FixpointTraceEvent::Kind::Stabilize, m_wpo, wpo_idx);
+        this->compress_component(m_wpo, head_idx);
+        for (auto succ_idx : m_wpo.get_successors(wpo_idx)) {
+          std::atomic<uint32_t>& succ_counter = wpo_counter[succ_idx];
+          // Increase succ node's counter, push succ nodes in work queue if
+          // their counter number matches their NumSchedPreds.
+          if (++succ_counter == m_wpo.get_num_preds(succ_idx)) {
+            schedule(worker_state, succ_idx);
+          }
+        }
+      } else {
+        // Component didn't stabilize.
+        this->extrapolate_head(context, head, current_state, new_state);
+        context.increase_iteration_count_for(head);
+        this->end_trace_event(
+            trace_slot, FixpointTraceEvent::Kind::Extrapolate, m_wpo, wpo_idx);
+        // Set component nodes v's counter to their
+        // NumOuterSchedPreds(v, wpo_idx)
+        for (auto pred_pair : m_wpo.get_num_outer_preds(wpo_idx)) {
+          auto component_idx = pred_pair.first;
+          assert(component_idx != entry_idx);
+          std::atomic<uint32_t>& component_counter = wpo_counter[component_idx];
+          // Push component nodes in work queue if their counter number
+          // matches their NumSchedPreds.
+
+          // Note: On page 10, https://dl.acm.org/ft_gateway.cfm?id=3371082
+          // suggests to set the counter to be *equal* to the number of
+          // predecessors not in our component. However, that is only
+          // correct when all counter updates of a scheduling step are done
+          // together as a single atomic update. Instead, we choose to
+          // update point-wise, in which case we have to *add* the number of
+          // predecessors, and update our own counter to 0 before updating
+          // any other dependent counters.
+          if ((component_counter += pred_pair.second) ==
+              m_wpo.get_num_preds(component_idx)) {
+            schedule(worker_state, component_idx);
+          }
+        }
+        if (head_idx == entry_idx) {
+          // Handle special case when there is a loop on entry node.
+          // Because entry node have num_preds = 0, and for
+          // get_num_outer_preds the nodes with num_outer_preds are ignored.
+          // So we need to manually add entry node back to work queue if
+          // the component didn't stabilize.
+          schedule(worker_state, head_idx);
+        }
+      }
+    };
     // Prepare work queue.
     auto wq = sparta::work_queue<uint32_t>(
-        [&context, &entry_idx, &wpo_counter, this](WPOWorkerState* worker_state,
-                                                   uint32_t wpo_idx) {
-          std::atomic<uint32_t>& current_counter = wpo_counter[wpo_idx];
-          assert(current_counter == m_wpo.get_num_preds(wpo_idx));
-          current_counter = 0;
-          // NonExit node
-          if (!m_wpo.is_exit(wpo_idx)) {
-            this->analyze_vertex(&context, m_wpo.get_node(wpo_idx));
-            for (auto succ_idx : m_wpo.get_successors(wpo_idx)) {
-              std::atomic<uint32_t>& succ_counter = wpo_counter[succ_idx];
-              // Increase succ node's counter, push succ nodes in work queue if
-              // their counter number matches their NumSchedPreds.
-              if (++succ_counter == m_wpo.get_num_preds(succ_idx)) {
-                worker_state->push_task(succ_idx);
-              }
-            }
-            return nullptr;
+        [&lane_of, &analyze](WPOWorkerState* worker_state, uint32_t wpo_idx) {
+          analyze(worker_state, wpo_idx);
+          Lane* lane = lane_of[wpo_idx];
+          if (lane == nullptr) {
+            return;
           }
-          // Exit node
-          // Check if component of the exit node has stabilized.
-          auto head_idx = m_wpo.get_head_of_exit(wpo_idx);
-          NodeId head = m_wpo.get_node(head_idx);
-          Domain* current_state = &this->m_entry_states[head];
-          Domain new_state = Domain::bottom();
-          this->compute_entry_state(&context, head, &new_state);
-          if (new_state.leq(*current_state)) {
-            // Component stabilized.
-            context.reset_local_iteration_count_for(head);
-            *current_state = std::move(new_state);
-            for (auto succ_idx : m_wpo.get_successors(wpo_idx)) {
-              std::atomic<uint32_t>& succ_counter = wpo_counter[succ_idx];
-              // Increase succ node's counter, push succ nodes in work queue if
-              // their counter number matches their NumSchedPreds.
-              if (++succ_counter == m_wpo.get_num_preds(succ_idx)) {
-                worker_state->push_task(succ_idx);
-              }
-            }
-          } else {
-            // Component didn't stabilize.
-            this->extrapolate(context, head, current_state, new_state);
-            context.increase_iteration_count_for(head);
-            // Set component nodes v's counter to their
-            // NumOuterSchedPreds(v, wpo_idx)
-            for (auto pred_pair : m_wpo.get_num_outer_preds(wpo_idx)) {
-              auto component_idx = pred_pair.first;
-              assert(component_idx != entry_idx);
-              std::atomic<uint32_t>& component_counter =
-                  wpo_counter[component_idx];
-              // Push component nodes in work queue if their counter number
-              // matches their NumSchedPreds.
-
-              // Note: On page 10, https://dl.acm.org/ft_gateway.cfm?id=3371082
-              // suggests to set the counter to be *equal* to the number of
-              // predecessors not in our component. However, that is only
-              // correct when all counter updates of a scheduling step are done
-              // together as a single atomic update. Instead, we choose to
-              // update point-wise, in which case we have to *add* the number of
-              // predecessors, and update our own counter to 0 before updating
-              // any other dependent counters.
-              if ((component_counter += pred_pair.second) ==
-                  m_wpo.get_num_preds(component_idx)) {
-                worker_state->push_task(component_idx);
+          // The slot of the completed task is handed over to a deferred task
+          // of the same component, if any.
+          while (true) {
+            uint32_t next_idx;
+            {
+              std::lock_guard<std::mutex> guard(lane->mutex);
+              if (lane->pending.empty()) {
+                --lane->active;
+                return;
               }
+              next_idx = lane->pending.front();
+              lane->pending.pop();
             }
-            if (head_idx == entry_idx) {
-              // Handle special case when there is a loop on entry node.
-              // Because entry node have num_preds = 0, and for
-              // get_num_outer_preds the nodes with num_outer_preds are ignored.
-              // So we need to manually add entry node back to work queue if
-              // the component didn't stabilize.
-              worker_state->push_task(head_idx);
+            if (!lane->pinned) {
+              worker_state->push_task(next_idx);
+              return;
             }
+            analyze(worker_state, next_idx);
           }
-          return nullptr;
         },
         m_num_thread,
         /*push_tasks_while_running=*/true);
-    wq.add_item(m_wpo.get_entry());
+    if (lane_of[entry_idx] != nullptr) {
+      ++lane_of[entry_idx]->active;
+    }
+    wq.add_item(entry_idx);
     wq.run_all();
     for (uint32_t idx = 0; idx < m_wpo.size(); ++idx) {
-      assert(wpo_counter[idx] == 0);
+      this->check_counter(m_wpo, idx, wpo_counter[idx], 0);
     }
   }
 
+  /*
+   * Describes the iterator, including its concurrency, in a manifest.
+   */
+  void describe(AnalysisManifest* manifest) const {
+    manifest->set("iterator", "parallel_wpo");
+    manifest->set("num_threads", m_num_thread)
+        .set("component_concurrency", m_component_concurrency)
+        .set("component_pinning", m_pinning_threshold);
+    fp_impl::MonotonicFixpointIteratorBase<GraphInterface, Domain, NodeHash>::
+        describe(manifest);
+  }
+
  private:
+  /*
+   * The tasks of a throttled or pinned component, which run under a limited
+   * number of slots.
+   */
+  struct Lane {
+    std::mutex mutex;
+    // Number of tasks of the component that are either in the work queue or
+    // being analyzed.
+    size_t active{0};
+    size_t capacity{0};
+    bool pinned{false};
+    // Tasks that are ready but wait for a free slot.
+    std::queue<uint32_t> pending;
+  };
+
+  /*
+   * Maps every WPO index to the lane of its component, or to nullptr if its
+   * tasks can be scheduled freely.
+   */
+  std::vector<Lane*> assign_lanes(std::vector<std::unique_ptr<Lane>>* lanes) {
+    std::vector<Lane*> lane_of(m_wpo.size(), nullptr);
+    if (m_component_concurrency == 0 && m_pinning_threshold == 0) {
+      return lane_of;
+    }
+    lanes->resize(m_wpo.size());
+    for (uint32_t idx = 0; idx < m_wpo.size(); ++idx) {
+      uint32_t head_idx;
+      if (m_wpo.is_head(idx)) {
+        head_idx = idx;
+      } else if (m_wpo.is_exit(idx)) {
+        head_idx = m_wpo.get_head_of_exit(idx);
+      } else if (m_wpo.get_parent(idx) != idx) {
+        head_idx = m_wpo.get_parent(idx);
+      } else {
+        // The node is not part of any component.
+        continue;
+      }
+      boost::optional<uint32_t> pinned_idx;
+      if (m_pinning_threshold > 0) {
+        for (auto h = head_idx;; h = m_wpo.get_parent(h)) {
+          if (m_wpo.get_size(h) >= m_pinning_threshold) {
+            pinned_idx = h;
+          }
+          if (m_wpo.get_parent(h) == h) {
+            break;
+          }
+        }
+      }
+      if (!pinned_idx && m_component_concurrency == 0) {
+        continue;
+      }
+      auto& lane = (*lanes)[pinned_idx ? *pinned_idx : head_idx];
+      if (lane == nullptr) {
+        lane = std::make_unique<Lane>();
+        lane->pinned = static_cast<bool>(pinned_idx);
+        lane->capacity = pinned_idx ? 1 : m_component_concurrency;
+      }
+      lane_of[idx] = lane.get();
+    }
+    return lane_of;
+  }
+
   WeakPartialOrdering<NodeId, NodeHash> m_wpo;
   size_t m_num_thread;
-  std::unordered_set<NodeId> m_all_nodes;
+  size_t m_component_concurrency{0};
+  size_t m_pinning_threshold{0};
+  std::unordered_set<NodeId, NodeHash> m_all_nodes;
 };
 
 /*
@@ -518,29 +1768,14 @@ class MonotonicFixpointIterator
       : fp_impl::MonotonicFixpointIteratorBase<GraphInterface,
                                                Domain,
                                                NodeHash>(graph, cfg_size_hint),
-        m_wpo(
-            GraphInterface::entry(graph),
-            [=, &graph](const NodeId& x) {
-              const auto& succ_edges = GraphInterface::successors(graph, x);
-              std::vector<NodeId> succ_nodes_tmp;
-              std::transform(succ_edges.begin(),
-                             succ_edges.end(),
-                             std::back_inserter(succ_nodes_tmp),
-                             std::bind(&GraphInterface::target,
-                                       std::ref(graph),
-                                       std::placeholders::_1));
-              // Filter out duplicate succ nodes.
-              std::vector<NodeId> succ_nodes;
-              std::unordered_set<NodeId, NodeHash> succ_nodes_set;
-              for (auto node : succ_nodes_tmp) {
-                if (!succ_nodes_set.count(node)) {
-                  succ_nodes_set.emplace(node);
-                  succ_nodes.emplace_back(node);
-                }
-              }
-              return succ_nodes;
-            },
-            false) {}
+        m_wpo(GraphInterface::entry(graph),
+              successor_nodes<GraphInterface, NodeHash>(graph),
+              false) {
+    // The WPO contains one node for every node reachable from the entry, plus
+    // one exit node per component. Its size is therefore a tight upper bound
+    // on the number of states computed during the iteration.
+    this->reserve(std::max(cfg_size_hint, static_cast<size_t>(m_wpo.size())));
+  }
 
   /*
    * Executes the fixpoint iterator given an abstract value describing the
@@ -549,7 +1784,12 @@ class MonotonicFixpointIterator
    * initial conditions.
    */
   void run(const Domain& init) {
-    this->clear();
+    this->reset();
+    if (this->m_trace != nullptr) {
+      this->m_trace->set_wpo(m_wpo, this->m_trace_node_info);
+    }
+    auto parallel_join = this->start_parallel_join();
+    this->prepare_state_compression(m_wpo);
     Context context(init);
     std::unique_ptr<std::atomic<uint32_t>[]> wpo_counter(
         new std::atomic<uint32_t>[m_wpo.size()]);
@@ -557,76 +1797,165 @@ class MonotonicFixpointIterator
     std::queue<uint32_t> work_queue;
     auto entry_idx = m_wpo.get_entry();
     assert(m_wpo.get_num_preds(entry_idx) == 0);
-    // Prepare work queue.
-    auto process_node = [&](uint32_t wpo_idx) {
-      assert(wpo_counter[wpo_idx] == m_wpo.get_num_preds(wpo_idx));
-      wpo_counter[wpo_idx] = 0;
-      // NonExit node
-      if (!m_wpo.is_exit(wpo_idx)) {
-        this->analyze_vertex(&context, m_wpo.get_node(wpo_idx));
-        for (auto succ_idx : m_wpo.get_successors(wpo_idx)) {
-          // Increase succ node's counter, push succ nodes in work queue if
-          // their counter number matches their NumSchedPreds.
-          if (++wpo_counter[succ_idx] == m_wpo.get_num_preds(succ_idx)) {
-            work_queue.emplace(succ_idx);
-          }
-        }
-        return nullptr;
-      }
-      // Exit node
-      // Check if component of the exit node has stabilized.
-      uint32_t head_idx = m_wpo.get_head_of_exit(wpo_idx);
-      NodeId head = m_wpo.get_node(head_idx);
-      Domain* current_state = &this->m_entry_states[head];
-      Domain new_state = Domain::bottom();
-      this->compute_entry_state(&context, head, &new_state);
-      if (new_state.leq(*current_state)) {
-        // Component stabilized.
-        context.reset_local_iteration_count_for(head);
-        *current_state = std::move(new_state);
-        for (auto succ_idx : m_wpo.get_successors(wpo_idx)) {
-          // Increase succ node's counter, push succ nodes in work queue if
-          // their counter number matches their NumSchedPreds.
-          if (++wpo_counter[succ_idx] == m_wpo.get_num_preds(succ_idx)) {
-            work_queue.emplace(succ_idx);
-          }
-        }
-      } else {
-        // Component didn't stabilize.
-        this->extrapolate(context, head, current_state, new_state);
-        context.increase_iteration_count_for(head);
-        // Set component nodes v's counter to their
-        // NumOuterSchedPreds(v, wpo_idx)
-        for (auto pred_pair : m_wpo.get_num_outer_preds(wpo_idx)) {
-          auto component_idx = pred_pair.first;
-          assert(component_idx != entry_idx);
-          // Push component nodes in work queue if their counter number
-          // matches their NumSchedPreds.
-          if ((wpo_counter[component_idx] += pred_pair.second) ==
-              m_wpo.get_num_preds(component_idx)) {
-            work_queue.emplace(component_idx);
-          }
-        }
-        if (head_idx == entry_idx) {
-          // Handle special case when there is a loop on entry node.
-          // Because entry node have num_preds = 0, and for
-          // get_num_outer_preds the nodes with num_outer_preds are ignored.
-          // So we need to manually add entry node back to work queue if
-          // the component didn't stabilize.
-          work_queue.emplace(head_idx);
-        }
-      }
-      return nullptr;
-    };
     // Start from wpo entry node.
     work_queue.emplace(entry_idx);
     while (!work_queue.empty()) {
       auto item = work_queue.front();
       work_queue.pop();
-      process_node(item);
+      size_t trace_slot = this->begin_trace_event(item);
+      process_node(&context,
+                   wpo_counter.get(),
+                   item,
+                   [&](FixpointTraceEvent::Kind kind) {
+                     this->end_trace_event(trace_slot, kind, m_wpo, item);
+                   },
+                   [&](uint32_t idx) { work_queue.emplace(idx); });
     }
     for (uint32_t idx = 0; idx < m_wpo.size(); ++idx) {
-      assert(wpo_counter[idx] == 0);
+      this->check_counter(m_wpo, idx, wpo_counter[idx], 0);
+    }
+  }
+
+  /*
+   * Describes the iterator and the configuration of its runs in a manifest.
+   */
+  void describe(AnalysisManifest* manifest) const {
+    manifest->set("iterator", "wpo");
+    fp_impl::MonotonicFixpointIteratorBase<GraphInterface, Domain, NodeHash>::
+        describe(manifest);
+  }
+
+  /*
+   * Executes the fixpoint iterator by following the schedule recorded in a
+   * trace (see FixpointTrace.h), e.g., in order to reproduce a run of the
+   * ParallelMonotonicFixpointIterator deterministically. If a digest function
+   * is provided, the digests of the states are also compared with the ones in
+   * the trace. Returns the index of the first event at which the
+   * iteration departs from the trace, or none if the whole trace has been
+   * replayed faithfully. The index is equal to the number of events if the
+   * trace ends before the iteration has converged.
+   */
+  boost::optional<size_t> replay(
+      const Domain& init,
+      const FixpointTrace& trace,
+      std::function<uint64_t(const Domain&)> digest = nullptr) {
+    RUNTIME_CHECK(trace.matches(m_wpo),
+                  invalid_argument() << error_msg(
+                      "The trace was recorded on a different graph"));
+    this->reset();
+    auto parallel_join = this->start_parallel_join();
+    this->prepare_state_compression(m_wpo);
+    Context context(init);
+    std::unique_ptr<std::atomic<uint32_t>[]> wpo_counter(
+        new std::atomic<uint32_t>[m_wpo.size()]);
+    std::fill_n(wpo_counter.get(), m_wpo.size(), 0);
+    std::vector<bool> scheduled(m_wpo.size(), false);
+    scheduled[m_wpo.get_entry()] = true;
+    size_t num_scheduled = 1;
+    const auto& events = trace.events();
+    for (size_t k = 0; k < events.size(); ++k) {
+      uint32_t wpo_idx = events[k].wpo_idx;
+      if (!scheduled[wpo_idx]) {
+        return k;
+      }
+      scheduled[wpo_idx] = false;
+      --num_scheduled;
+      bool diverged = false;
+      process_node(&context,
+                   wpo_counter.get(),
+                   wpo_idx,
+                   [&](FixpointTraceEvent::Kind kind) {
+                     diverged = events[k].kind != kind ||
+                                (digest && events[k].digest !=
+                                               digest(this->get_traced_state(
+                                                   m_wpo, wpo_idx)));
+                   },
+                   [&](uint32_t idx) {
+                     scheduled[idx] = true;
+                     ++num_scheduled;
+                   });
+      if (diverged) {
+        return k;
+      }
+    }
+    if (num_scheduled > 0) {
+      return events.size();
+    }
+    return boost::none;
+  }
+
+ private:
+  /*
+   * Processes a node of the WPO. The first callback is invoked once the
+   * abstract states have been updated, and the second one schedules the
+   * nodes that are ready to be processed.
+   */
+  void process_node(Context* context,
+                    std::atomic<uint32_t>* wpo_counter,
+                    uint32_t wpo_idx,
+                    const std::function<void(FixpointTraceEvent::Kind)>& done,
+                    const std::function<void(uint32_t)>& schedule) {
+    this->check_counter(
+        m_wpo, wpo_idx, wpo_counter[wpo_idx], m_wpo.get_num_preds(wpo_idx));
+    wpo_counter[wpo_idx] = 0;
+    // NonExit node
+    if (!m_wpo.is_exit(wpo_idx)) {
+      this->analyze_vertex(context, m_wpo.get_node(wpo_idx));
+      done(FixpointTraceEvent::Kind::Analyze);
+      for (auto succ_idx : m_wpo.get_successors(wpo_idx)) {
+        // Increase succ node's counter, push succ nodes in work queue if
+        // their counter number matches their NumSchedPreds.
+        if (++wpo_counter[succ_idx] == m_wpo.get_num_preds(succ_idx)) {
+          schedule(succ_idx);
+        }
+      }
+      return;
+    }
+    // Exit node
+    // Check if component of the exit node has stabilized.
+    uint32_t head_idx = m_wpo.get_head_of_exit(wpo_idx);
+    NodeId head = m_wpo.get_node(head_idx);
+    Domain* current_state = &this->m_entry_states[head];
+    Domain new_state = Domain::bottom();
+    this->compute_entry_state(context, head, &new_state);
+    if (this->is_stable(head, *current_state, new_state)) {
+      // Component stabilized.
+      context->reset_local_iteration_count_for(head);
+      *current_state = std::move(new_state);
+      done(FixpointTraceEvent::Kind::Stabilize);
+      this->compress_component(m_wpo, head_idx);
+      for (auto succ_idx : m_wpo.get_successors(wpo_idx)) {
+        // Increase succ node's counter, push succ nodes in work queue if
+        // their counter number matches their NumSchedPreds.
+        if (++wpo_counter[succ_idx] == m_wpo.get_num_preds(succ_idx)) {
+          schedule(succ_idx);
+        }
+      }
+    } else {
+      // Component didn't stabilize.
+      this->extrapolate_head(*context, head, current_state, new_state);
+      context->increase_iteration_count_for(head);
+      done(FixpointTraceEvent::Kind::Extrapolate);
+      // Set component nodes v's counter to their
+      // NumOuterSchedPreds(v, wpo_idx)
+      for (auto pred_pair : m_wpo.get_num_outer_preds(wpo_idx)) {
+        auto component_idx = pred_pair.first;
+        assert(component_idx != m_wpo.get_entry());
+        // Push component nodes in work queue if their counter number
+        // matches their NumSchedPreds.
+        if ((wpo_counter[component_idx] += pred_pair.second) ==
+            m_wpo.get_num_preds(component_idx)) {
+          schedule(component_idx);
+        }
+      }
+      if (head_idx == m_wpo.get_entry()) {
+        // Handle special case when there is a loop on entry node.
+        // Because entry node have num_preds = 0, and for
+        // get_num_outer_preds the nodes with num_outer_preds are ignored.
+        // So we need to manually add entry node back to work queue if
+        // the component didn't stabilize.
+        schedule(head_idx);
+      }
     }
   }
 
@@ -660,6 +1989,9 @@ class BackwardsFixpointIterationAdaptor {
   static NodeId exit(const Graph& graph) {
     return GraphInterface::entry(graph);
   }
+  static size_t size_hint(const Graph& graph) {
+    return graph_size_hint<GraphInterface>(graph);
+  }
   static std::vector<EdgeId> predecessors(const Graph& graph,
                                           const NodeId& node) {
     return GraphInterface::successors(graph, node);
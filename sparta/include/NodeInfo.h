@@ -0,0 +1,133 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <boost/optional.hpp>
+#include <cstdint>
+#include <functional>
+#include <ostream>
+#include <sstream>
+#include <string>
+#include <unordered_map>
+#include <utility>
+
+namespace sparta {
+
+/*
+ * The location in the source program that a node of a graph corresponds to.
+ * All the components are optional, since the available debug information
+ * varies across programs.
+ */
+struct SourceLocation {
+  std::string method;
+  boost::optional<uint32_t> offset;
+  boost::optional<uint32_t> line;
+};
+
+inline std::ostream& operator<<(std::ostream& o,
+                                const SourceLocation& location) {
+  o << (location.method.empty() ? "<unknown>" : location.method);
+  if (location.offset) {
+    o << "+0x" << std::hex << *location.offset << std::dec;
+  }
+  if (location.line) {
+    o << " (line " << *location.line << ")";
+  }
+  return o;
+}
+
+namespace ni_impl {
+
+// The last parameter gives precedence to the first overload when the node
+// identifier can be printed.
+template <typename T>
+auto print_id(std::ostream& o, const T& node, int)
+    -> decltype(o << node, void()) {
+  o << node;
+}
+
+template <typename T>
+void print_id(std::ostream& o, const T&, long) {
+  o << "<node>";
+}
+
+} // namespace ni_impl
+
+/*
+ * Maps the nodes of a graph back to the program. This is used when reporting
+ * analysis results (e.g., when dumping, tracing or visualizing the invariants
+ * computed by a fixpoint iterator), so that the nodes are described in terms
+ * that a human can relate to, rather than opaque identifiers.
+ */
+template <typename NodeId>
+class NodeInfo {
+ public:
+  virtual ~NodeInfo() {}
+
+  /*
+   * Returns none if the location of the node is unknown.
+   */
+  virtual boost::optional<SourceLocation> location_of(
+      const NodeId& node) const = 0;
+
+  /*
+   * Returns a human-readable description of the node. By default, this is
+   * the source location of the node if it is known, and the node identifier
+   * itself otherwise, provided it can be printed.
+   */
+  virtual std::string describe(const NodeId& node) const {
+    std::ostringstream o;
+    if (auto location = location_of(node)) {
+      o << *location;
+    } else {
+      ni_impl::print_id(o, node, 0);
+    }
+    return o.str();
+  }
+};
+
+/*
+ * A node information provider backed by a hashtable, for clients that
+ * compute the locations of all nodes upfront.
+ */
+template <typename NodeId, typename NodeHash = std::hash<NodeId>>
+class HashedNodeInfo final : public NodeInfo<NodeId> {
+ public:
+  void set_location(const NodeId& node, SourceLocation location) {
+    m_locations[node] = std::move(location);
+  }
+
+  boost::optional<SourceLocation> location_of(
+      const NodeId& node) const override {
+    auto it = m_locations.find(node);
+    if (it == m_locations.end()) {
+      return boost::none;
+    }
+    return it->second;
+  }
+
+ private:
+  std::unordered_map<NodeId, SourceLocation, NodeHash> m_locations;
+};
+
+/*
+ * Describes a node with the given provider if there is one, and with its
+ * identifier otherwise, provided it can be printed.
+ */
+template <typename NodeId>
+std::string describe_node(const NodeId& node,
+                          const NodeInfo<NodeId>* node_info) {
+  if (node_info != nullptr) {
+    return node_info->describe(node);
+  }
+  std::ostringstream o;
+  ni_impl::print_id(o, node, 0);
+  return o.str();
+}
+
+} // namespace sparta
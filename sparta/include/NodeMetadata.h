@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <functional>
+#include <mutex>
+#include <unordered_map>
+
+namespace sparta {
+
+namespace nm_impl {
+
+/*
+ * The part of a metadata table that is managed by the fixpoint iterator.
+ */
+template <typename NodeId>
+class NodeMetadataStore {
+ public:
+  virtual ~NodeMetadataStore() = default;
+
+  virtual void discard(const NodeId& node) = 0;
+
+  virtual void clear() = 0;
+};
+
+} // namespace nm_impl
+
+/*
+ * A table of auxiliary results attached to the nodes of a graph by the node
+ * transformer of a fixpoint iterator, e.g., diagnostics or facts derived from
+ * the abstract state. The table is a member of the fixpoint iterator, which
+ * must be told about it:
+ *
+ *   class Analyzer final : public MonotonicFixpointIterator<...> {
+ *    public:
+ *     explicit Analyzer(const Graph& graph)
+ *         : MonotonicFixpointIterator(graph) {
+ *       this->attach_metadata(&m_warnings);
+ *     }
+ *
+ *     void analyze_node(const NodeId& node, Domain* state) const override {
+ *       ...
+ *       if (...) {
+ *         m_warnings.at(node).push_back("...");
+ *       }
+ *     }
+ *
+ *     const NodeMetadata<NodeId, std::vector<std::string>>& warnings() const {
+ *       return m_warnings;
+ *     }
+ *
+ *    private:
+ *     NodeMetadata<NodeId, std::vector<std::string>> m_warnings;
+ *   };
+ *
+ * Since a node may be analyzed several times before the iteration converges,
+ * the fixpoint iterator discards the metadata of a node right before each
+ * analysis of the node. After a run, the metadata of a node is therefore the
+ * one attached during the last analysis of the node. The whole table is
+ * discarded whenever the states of the fixpoint iterator are.
+ *
+ * The metadata of distinct nodes can be accessed concurrently, as in the
+ * transformers of a ParallelMonotonicFixpointIterator.
+ */
+template <typename NodeId,
+          typename Metadata,
+          typename NodeHash = std::hash<NodeId>>
+class NodeMetadata final : public nm_impl::NodeMetadataStore<NodeId> {
+ public:
+  using Map = std::unordered_map<NodeId, Metadata, NodeHash>;
+
+  /*
+   * Returns the metadata of the node, which is default-constructed if none
+   * has been attached since the node was last discarded. This is meant to be
+   * called from the transformers, which are const methods.
+   */
+  Metadata& at(const NodeId& node) const {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    return m_metadata[node];
+  }
+
+  /*
+   * Returns the metadata of the node, or nullptr if none has been attached.
+   */
+  const Metadata* get(const NodeId& node) const {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    auto it = m_metadata.find(node);
+    return it == m_metadata.end() ? nullptr : &it->second;
+  }
+
+  bool contains(const NodeId& node) const { return get(node) != nullptr; }
+
+  size_t size() const {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    return m_metadata.size();
+  }
+
+  /*
+   * The metadata of all the nodes. This must not be called during a run.
+   */
+  const Map& bindings() const { return m_metadata; }
+
+  void discard(const NodeId& node) override {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    m_metadata.erase(node);
+  }
+
+  void clear() override {
+    std::lock_guard<std::mutex> lock(m_mutex);
+    m_metadata.clear();
+  }
+
+ private:
+  // References to the elements of an unordered map remain valid when the map
+  // is rehashed, hence the lock only needs to protect the structure of the
+  // map.
+  mutable std::mutex m_mutex;
+  mutable Map m_metadata;
+};
+
+} // namespace sparta
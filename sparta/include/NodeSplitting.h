@@ -0,0 +1,492 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <limits>
+#include <queue>
+#include <unordered_map>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+#include "Exceptions.h"
+
+namespace sparta {
+
+/*
+ * Weak partial orderings and weak topological orderings can handle irreducible
+ * control-flow graphs, but the precision of the analysis suffers when the heads
+ * of the irreducible components are poorly chosen, since widening is then
+ * applied at nodes that are entered from several unrelated paths.
+ *
+ * This class performs controlled node splitting on the subgraph reachable from
+ * the entry of a control-flow graph, so as to restore reducibility: whenever a
+ * strongly connected component has several entry nodes, one of them is kept as
+ * the head of the component and the rest of the component is duplicated for
+ * every other entry. The process is repeated on the nested components until the
+ * graph is reducible, or until the number of nodes would exceed the given
+ * budget, in which case the graph is left partially split. Every path of the
+ * original graph is represented by exactly one path of the split graph, hence
+ * joining the invariants computed for all the copies of a node yields a sound
+ * invariant for the node.
+ *
+ * Nodes and edges of the split graph are identified by indices. The entry of
+ * the split graph is always 0.
+ */
+template <typename GraphInterface,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class NodeSplittingGraph final {
+ public:
+  using OriginalGraph = typename GraphInterface::Graph;
+  using OriginalNodeId = typename GraphInterface::NodeId;
+  using OriginalEdgeId = typename GraphInterface::EdgeId;
+  using NodeId = uint32_t;
+  using EdgeId = uint32_t;
+
+  NodeSplittingGraph(const OriginalGraph& graph, size_t max_size) {
+    build(graph);
+    for (;;) {
+      auto status = split_one_component(max_size);
+      if (status == SplitStatus::Reducible) {
+        m_is_reducible = true;
+        break;
+      }
+      if (status == SplitStatus::BudgetExceeded) {
+        break;
+      }
+    }
+  }
+
+  NodeId entry() const { return 0; }
+
+  size_t size() const { return m_nodes.size(); }
+
+  const std::vector<EdgeId>& successors(const NodeId& node) const {
+    return m_successors[node];
+  }
+
+  const std::vector<EdgeId>& predecessors(const NodeId& node) const {
+    return m_predecessors[node];
+  }
+
+  NodeId source(const EdgeId& edge) const { return m_edges[edge].source; }
+
+  NodeId target(const EdgeId& edge) const { return m_edges[edge].target; }
+
+  const OriginalNodeId& original_node(const NodeId& node) const {
+    return m_nodes[node];
+  }
+
+  const OriginalEdgeId& original_edge(const EdgeId& edge) const {
+    return m_edges[edge].original;
+  }
+
+  /*
+   * Returns all the nodes of the split graph that are copies of the given node
+   * of the original graph. The result is empty if the node is not reachable
+   * from the entry.
+   */
+  const std::vector<NodeId>& copies_of(const OriginalNodeId& node) const {
+    auto it = m_copies.find(node);
+    if (it == m_copies.end()) {
+      static const std::vector<NodeId> empty;
+      return empty;
+    }
+    return it->second;
+  }
+
+  /*
+   * Returns false if node splitting was stopped because the budget was
+   * exceeded, in which case the split graph may still be irreducible.
+   */
+  bool is_reducible() const { return m_is_reducible; }
+
+ private:
+  struct Edge {
+    NodeId source;
+    NodeId target;
+    OriginalEdgeId original;
+  };
+
+  enum class SplitStatus { Reducible, Split, BudgetExceeded };
+
+  // The original node is taken by value, since it may refer to an element of
+  // m_nodes.
+  NodeId add_node(OriginalNodeId original) {
+    NodeId node = m_nodes.size();
+    m_nodes.push_back(original);
+    m_successors.emplace_back();
+    m_predecessors.emplace_back();
+    m_copies[original].push_back(node);
+    return node;
+  }
+
+  void add_edge(NodeId source, NodeId target, OriginalEdgeId original) {
+    EdgeId edge = m_edges.size();
+    m_edges.push_back(Edge{source, target, original});
+    m_successors[source].push_back(edge);
+    m_predecessors[target].push_back(edge);
+  }
+
+  void redirect_edge(EdgeId edge, NodeId new_target) {
+    auto& preds = m_predecessors[m_edges[edge].target];
+    preds.erase(std::find(preds.begin(), preds.end(), edge));
+    m_edges[edge].target = new_target;
+    m_predecessors[new_target].push_back(edge);
+  }
+
+  void build(const OriginalGraph& graph) {
+    std::unordered_map<OriginalNodeId, NodeId, NodeHash> index;
+    std::queue<OriginalNodeId> queue;
+    auto entry = GraphInterface::entry(graph);
+    index.emplace(entry, add_node(entry));
+    queue.push(entry);
+    while (!queue.empty()) {
+      auto node = queue.front();
+      queue.pop();
+      for (const auto& edge : GraphInterface::successors(graph, node)) {
+        auto target = GraphInterface::target(graph, edge);
+        auto it = index.find(target);
+        if (it == index.end()) {
+          it = index.emplace(target, add_node(target)).first;
+          queue.push(target);
+        }
+        add_edge(index.at(node), it->second, edge);
+      }
+    }
+  }
+
+  /*
+   * Computes the strongly connected components of the subgraph induced by the
+   * given set of nodes, using an iterative version of Tarjan's algorithm so as
+   * to not overflow the stack on large graphs. Only components with at least
+   * two nodes are returned.
+   */
+  std::vector<std::vector<NodeId>> components(
+      const std::vector<NodeId>& members,
+      const std::vector<bool>& in_subgraph) const {
+    constexpr uint32_t undefined = std::numeric_limits<uint32_t>::max();
+    std::vector<std::vector<NodeId>> result;
+    std::unordered_map<NodeId, uint32_t> dfn;
+    std::unordered_map<NodeId, uint32_t> low;
+    std::vector<NodeId> stack;
+    std::vector<bool> on_stack(m_nodes.size(), false);
+    // Each frame holds a node and the position of the next successor to visit.
+    std::vector<std::pair<NodeId, size_t>> frames;
+    uint32_t next_dfn = 0;
+    auto get = [undefined](const std::unordered_map<NodeId, uint32_t>& map,
+                           NodeId node) {
+      auto it = map.find(node);
+      return it == map.end() ? undefined : it->second;
+    };
+    for (NodeId root : members) {
+      if (get(dfn, root) != undefined) {
+        continue;
+      }
+      frames.emplace_back(root, 0);
+      while (!frames.empty()) {
+        NodeId node = frames.back().first;
+        size_t position = frames.back().second;
+        if (position == 0) {
+          dfn[node] = low[node] = next_dfn++;
+          stack.push_back(node);
+          on_stack[node] = true;
+        }
+        const auto& succs = m_successors[node];
+        bool descended = false;
+        for (; position < succs.size(); ++position) {
+          NodeId succ = m_edges[succs[position]].target;
+          if (!in_subgraph[succ]) {
+            continue;
+          }
+          if (get(dfn, succ) == undefined) {
+            frames.back().second = position + 1;
+            frames.emplace_back(succ, 0);
+            descended = true;
+            break;
+          }
+          if (on_stack[succ]) {
+            low[node] = std::min(low[node], dfn[succ]);
+          }
+        }
+        if (descended) {
+          continue;
+        }
+        frames.pop_back();
+        if (!frames.empty()) {
+          NodeId parent = frames.back().first;
+          low[parent] = std::min(low[parent], low[node]);
+        }
+        if (low[node] == dfn[node]) {
+          std::vector<NodeId> component;
+          NodeId member;
+          do {
+            member = stack.back();
+            stack.pop_back();
+            on_stack[member] = false;
+            component.push_back(member);
+          } while (member != node);
+          if (component.size() > 1) {
+            result.push_back(std::move(component));
+          }
+        }
+      }
+    }
+    return result;
+  }
+
+  /*
+   * Looks for a component with several entries, starting from the outermost
+   * components, and splits it. Nested components are only inspected once their
+   * enclosing component has a single entry (its head).
+   */
+  SplitStatus split_one_component(size_t max_size) {
+    std::vector<NodeId> all_nodes(m_nodes.size());
+    for (NodeId node = 0; node < m_nodes.size(); ++node) {
+      all_nodes[node] = node;
+    }
+    std::vector<std::vector<NodeId>> worklist{std::move(all_nodes)};
+    while (!worklist.empty()) {
+      auto members = std::move(worklist.back());
+      worklist.pop_back();
+      std::vector<bool> in_subgraph(m_nodes.size(), false);
+      for (NodeId node : members) {
+        in_subgraph[node] = true;
+      }
+      for (auto& component : components(members, in_subgraph)) {
+        std::vector<bool> in_component(m_nodes.size(), false);
+        for (NodeId node : component) {
+          in_component[node] = true;
+        }
+        // Collect the entries of the component, together with the number of
+        // edges entering the component through each of them.
+        std::vector<std::pair<NodeId, size_t>> entries;
+        for (NodeId node : component) {
+          size_t incoming = node == entry() ? 1 : 0;
+          for (EdgeId edge : m_predecessors[node]) {
+            if (!in_component[m_edges[edge].source]) {
+              ++incoming;
+            }
+          }
+          if (incoming > 0) {
+            entries.emplace_back(node, incoming);
+          }
+        }
+        // The entry of the graph is necessarily the head of its component.
+        // Otherwise, we keep the entry with the largest number of incoming
+        // edges as the head.
+        auto head_it = std::max_element(
+            entries.begin(), entries.end(), [this](auto& e1, auto& e2) {
+              if ((e1.first == entry()) != (e2.first == entry())) {
+                return e2.first == entry();
+              }
+              return e1.second < e2.second;
+            });
+        NodeId head = head_it->first;
+        if (entries.size() == 1) {
+          std::vector<NodeId> body;
+          for (NodeId node : component) {
+            if (node != head) {
+              body.push_back(node);
+            }
+          }
+          worklist.push_back(std::move(body));
+          continue;
+        }
+        size_t cost = (entries.size() - 1) * (component.size() - 1);
+        if (m_nodes.size() + cost > max_size) {
+          return SplitStatus::BudgetExceeded;
+        }
+        for (const auto& component_entry : entries) {
+          if (component_entry.first != head) {
+            split(component, in_component, head, component_entry.first);
+          }
+        }
+        return SplitStatus::Split;
+      }
+    }
+    return SplitStatus::Reducible;
+  }
+
+  /*
+   * Duplicates all the nodes of the component except its head, and redirects
+   * the edges entering the component through the given entry to the copy.
+   */
+  void split(const std::vector<NodeId>& component,
+             const std::vector<bool>& in_component,
+             NodeId head,
+             NodeId component_entry) {
+    std::unordered_map<NodeId, NodeId> copy;
+    for (NodeId node : component) {
+      if (node != head) {
+        copy[node] = add_node(m_nodes[node]);
+      }
+    }
+    for (NodeId node : component) {
+      if (node == head) {
+        continue;
+      }
+      auto succs = m_successors[node];
+      for (EdgeId edge : succs) {
+        NodeId target = m_edges[edge].target;
+        if (in_component[target] && target != head) {
+          target = copy.at(target);
+        }
+        add_edge(copy.at(node), target, m_edges[edge].original);
+      }
+    }
+    auto preds = m_predecessors[component_entry];
+    for (EdgeId edge : preds) {
+      if (!in_component[m_edges[edge].source]) {
+        redirect_edge(edge, copy.at(component_entry));
+      }
+    }
+  }
+
+  std::vector<OriginalNodeId> m_nodes;
+  std::vector<Edge> m_edges;
+  std::vector<std::vector<EdgeId>> m_successors;
+  std::vector<std::vector<EdgeId>> m_predecessors;
+  std::unordered_map<OriginalNodeId, std::vector<NodeId>, NodeHash> m_copies;
+  bool m_is_reducible = false;
+};
+
+/*
+ * The interface to a split graph, as required by the fixpoint iterators.
+ */
+template <typename GraphInterface,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class NodeSplittingGraphInterface {
+ public:
+  using Graph = NodeSplittingGraph<GraphInterface, NodeHash>;
+  using NodeId = typename Graph::NodeId;
+  using EdgeId = typename Graph::EdgeId;
+
+  static NodeId entry(const Graph& graph) { return graph.entry(); }
+  static std::vector<EdgeId> predecessors(const Graph& graph,
+                                          const NodeId& node) {
+    return graph.predecessors(node);
+  }
+  static std::vector<EdgeId> successors(const Graph& graph,
+                                        const NodeId& node) {
+    return graph.successors(node);
+  }
+  static NodeId source(const Graph& graph, const EdgeId& edge) {
+    return graph.source(edge);
+  }
+  static NodeId target(const Graph& graph, const EdgeId& edge) {
+    return graph.target(edge);
+  }
+};
+
+/*
+ * A fixpoint iterator that analyzes the graph obtained by node splitting
+ * instead of the original graph. The semantic transformers are expressed on
+ * the nodes and edges of the original graph, and the invariant at a node is
+ * the join of the invariants computed for all its copies. It is parameterized
+ * by one of the monotonic fixpoint iterators defined in
+ * MonotonicFixpointIterator.h, e.g.:
+ *
+ *   class MyAnalyzer final
+ *       : public NodeSplittingFixpointIterator<MonotonicFixpointIterator,
+ *                                              MyCFGInterface,
+ *                                              MyDomain> {
+ *    public:
+ *     MyAnalyzer(const MyCFG& cfg)
+ *         : NodeSplittingFixpointIterator(cfg, 2 * cfg.size()) {}
+ *     ...
+ *   };
+ */
+template <template <typename GraphInterface, typename Domain, typename NodeHash>
+          class FixpointIteratorBase,
+          typename GraphInterface,
+          typename Domain,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class NodeSplittingFixpointIterator {
+ public:
+  using Graph = typename GraphInterface::Graph;
+  using NodeId = typename GraphInterface::NodeId;
+  using EdgeId = typename GraphInterface::EdgeId;
+  using SplitGraph = NodeSplittingGraph<GraphInterface, NodeHash>;
+
+  /*
+   * The maximum number of nodes in the split graph bounds the amount of
+   * duplication performed.
+   */
+  NodeSplittingFixpointIterator(const Graph& graph, size_t max_size)
+      : m_split_graph(graph, max_size), m_engine(m_split_graph, *this) {}
+
+  virtual ~NodeSplittingFixpointIterator() {}
+
+  virtual void analyze_node(const NodeId& node,
+                            Domain* current_state) const = 0;
+
+  virtual Domain analyze_edge(const EdgeId& edge,
+                              const Domain& exit_state_at_source) const = 0;
+
+  void run(const Domain& init) { m_engine.run(init); }
+
+  Domain get_entry_state_at(const NodeId& node) const {
+    Domain result = Domain::bottom();
+    for (auto copy : m_split_graph.copies_of(node)) {
+      result.join_with(m_engine.get_entry_state_at(copy));
+    }
+    return result;
+  }
+
+  Domain get_exit_state_at(const NodeId& node) const {
+    Domain result = Domain::bottom();
+    for (auto copy : m_split_graph.copies_of(node)) {
+      result.join_with(m_engine.get_exit_state_at(copy));
+    }
+    return result;
+  }
+
+  const SplitGraph& split_graph() const { return m_split_graph; }
+
+ private:
+  using SplitGraphInterface =
+      NodeSplittingGraphInterface<GraphInterface, NodeHash>;
+
+  class Engine final
+      : public FixpointIteratorBase<SplitGraphInterface,
+                                    Domain,
+                                    std::hash<typename SplitGraph::NodeId>> {
+   public:
+    Engine(const SplitGraph& graph, const NodeSplittingFixpointIterator& outer)
+        : FixpointIteratorBase<SplitGraphInterface,
+                               Domain,
+                               std::hash<typename SplitGraph::NodeId>>(graph),
+          m_graph(graph),
+          m_outer(outer) {}
+
+    void analyze_node(const typename SplitGraph::NodeId& node,
+                      Domain* current_state) const override {
+      m_outer.analyze_node(m_graph.original_node(node), current_state);
+    }
+
+    Domain analyze_edge(const typename SplitGraph::EdgeId& edge,
+                        const Domain& exit_state_at_source) const override {
+      return m_outer.analyze_edge(m_graph.original_edge(edge),
+                                  exit_state_at_source);
+    }
+
+   private:
+    const SplitGraph& m_graph;
+    const NodeSplittingFixpointIterator& m_outer;
+  };
+
+  SplitGraph m_split_graph;
+  Engine m_engine;
+};
+
+} // namespace sparta
@@ -0,0 +1,242 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <limits>
+#include <type_traits>
+
+#include "ConstantAbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * The abstraction of the truth value of a condition: true, false, Top when
+ * the condition may or may not hold, and Bottom when it is not evaluated.
+ */
+using BooleanDomain = ConstantAbstractDomain<bool>;
+
+/*
+ * The abstract semantics of the arithmetic and bitwise operators over a
+ * numerical abstract domain. This lets the transformers of an analysis, as
+ * well as the expression evaluator (see ExpressionEvaluator.h), be written
+ * once for all numerical domains. A domain implements the operators by
+ * specializing this template with the following layout:
+ *
+ * template <>
+ * struct ArithmeticDomainOps<Domain> {
+ *   // The abstraction of a constant.
+ *   template <typename Constant>
+ *   static Domain literal(const Constant& value);
+ *
+ *   static Domain neg(const Domain& x);
+ *   static Domain add(const Domain& x, const Domain& y);
+ *   static Domain sub(const Domain& x, const Domain& y);
+ *   static Domain mul(const Domain& x, const Domain& y);
+ *   // Integer division truncates toward zero. Division by zero has no
+ *   // result.
+ *   static Domain div(const Domain& x, const Domain& y);
+ *
+ *   static Domain bit_and(const Domain& x, const Domain& y);
+ *   static Domain bit_or(const Domain& x, const Domain& y);
+ *   static Domain bit_xor(const Domain& x, const Domain& y);
+ *   // Shifting by a negative amount or by at least the width of the type has
+ *   // an unknown result. The right shift is arithmetic.
+ *   static Domain shl(const Domain& x, const Domain& y);
+ *   static Domain shr(const Domain& x, const Domain& y);
+ * };
+ *
+ * All the operations are required to be sound, and to return Bottom when one
+ * of their operands is Bottom.
+ */
+template <typename Domain>
+struct ArithmeticDomainOps;
+
+/*
+ * The abstract semantics of the comparison operators over a numerical
+ * abstract domain, which evaluate to a BooleanDomain. A domain implements the
+ * operators by specializing this template with the following layout:
+ *
+ * template <>
+ * struct CompareDomainOps<Domain> {
+ *   static BooleanDomain eq(const Domain& x, const Domain& y);
+ *   static BooleanDomain lt(const Domain& x, const Domain& y);
+ *   static BooleanDomain le(const Domain& x, const Domain& y);
+ * };
+ *
+ * The other comparisons are derived from these by inheriting from
+ * DerivedCompareDomainOps. The comparisons return Bottom when one of their
+ * operands is Bottom.
+ */
+template <typename Domain>
+struct CompareDomainOps;
+
+/*
+ * Logical negation: !true = false, !false = true, !T = T and !_|_ = _|_.
+ */
+inline BooleanDomain negate(const BooleanDomain& b) {
+  auto value = b.get_constant();
+  return value ? BooleanDomain(!*value) : b;
+}
+
+template <typename Ops, typename Domain>
+struct DerivedCompareDomainOps {
+  static BooleanDomain ne(const Domain& x, const Domain& y) {
+    return negate(Ops::eq(x, y));
+  }
+
+  static BooleanDomain gt(const Domain& x, const Domain& y) {
+    return Ops::lt(y, x);
+  }
+
+  static BooleanDomain ge(const Domain& x, const Domain& y) {
+    return Ops::le(y, x);
+  }
+};
+
+namespace ops_impl {
+
+template <typename Integer>
+bool is_valid_shift(Integer amount) {
+  return amount >= 0 &&
+         amount < static_cast<Integer>(std::numeric_limits<Integer>::digits +
+                                       std::is_signed<Integer>::value);
+}
+
+} // namespace ops_impl
+
+/*
+ * The constant propagation domain over machine integers, whose arithmetic
+ * wraps around on overflow.
+ */
+template <typename Integer>
+struct ArithmeticDomainOps<ConstantAbstractDomain<Integer>> {
+  static_assert(std::is_integral<Integer>::value, "expecting integers.");
+
+  using Domain = ConstantAbstractDomain<Integer>;
+  // The wraparound is only well-defined on unsigned integers.
+  using Unsigned = typename std::make_unsigned<Integer>::type;
+
+  template <typename Constant>
+  static Domain literal(const Constant& value) {
+    return Domain(static_cast<Integer>(value));
+  }
+
+  static Domain neg(const Domain& x) { return sub(Domain(0), x); }
+
+  static Domain add(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) {
+      return Domain(Integer(Unsigned(a) + Unsigned(b)));
+    });
+  }
+
+  static Domain sub(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) {
+      return Domain(Integer(Unsigned(a) - Unsigned(b)));
+    });
+  }
+
+  static Domain mul(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) {
+      return Domain(Integer(Unsigned(a) * Unsigned(b)));
+    });
+  }
+
+  static Domain div(const Domain& x, const Domain& y) {
+    auto divisor = y.get_constant();
+    if (x.is_bottom() || (divisor && *divisor == 0)) {
+      return Domain::bottom();
+    }
+    return lift(x, y, [](Integer a, Integer b) {
+      if (std::is_signed<Integer>::value &&
+          a == std::numeric_limits<Integer>::min() && b == Integer(-1)) {
+        return Domain(a);
+      }
+      return Domain(Integer(a / b));
+    });
+  }
+
+  static Domain bit_and(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) { return Domain(a & b); });
+  }
+
+  static Domain bit_or(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) { return Domain(a | b); });
+  }
+
+  static Domain bit_xor(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) { return Domain(a ^ b); });
+  }
+
+  static Domain shl(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) {
+      return ops_impl::is_valid_shift(b) ? Domain(Integer(Unsigned(a) << b))
+                                         : Domain::top();
+    });
+  }
+
+  static Domain shr(const Domain& x, const Domain& y) {
+    return lift(x, y, [](Integer a, Integer b) {
+      return ops_impl::is_valid_shift(b) ? Domain(Integer(a >> b))
+                                         : Domain::top();
+    });
+  }
+
+ private:
+  template <typename Operation>
+  static Domain lift(const Domain& x, const Domain& y, Operation operation) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    if (!x.get_constant() || !y.get_constant()) {
+      return Domain::top();
+    }
+    return operation(*x.get_constant(), *y.get_constant());
+  }
+};
+
+template <typename Constant>
+struct CompareDomainOps<ConstantAbstractDomain<Constant>>
+    : DerivedCompareDomainOps<
+          CompareDomainOps<ConstantAbstractDomain<Constant>>,
+          ConstantAbstractDomain<Constant>> {
+  using Domain = ConstantAbstractDomain<Constant>;
+
+  static BooleanDomain eq(const Domain& x, const Domain& y) {
+    return lift(x, y, [](const Constant& a, const Constant& b) {
+      return a == b;
+    });
+  }
+
+  static BooleanDomain lt(const Domain& x, const Domain& y) {
+    return lift(x, y, [](const Constant& a, const Constant& b) {
+      return a < b;
+    });
+  }
+
+  static BooleanDomain le(const Domain& x, const Domain& y) {
+    return lift(x, y, [](const Constant& a, const Constant& b) {
+      return a <= b;
+    });
+  }
+
+ private:
+  template <typename Comparison>
+  static BooleanDomain lift(const Domain& x,
+                            const Domain& y,
+                            Comparison comparison) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return BooleanDomain::bottom();
+    }
+    if (!x.get_constant() || !y.get_constant()) {
+      return BooleanDomain::top();
+    }
+    return BooleanDomain(comparison(*x.get_constant(), *y.get_constant()));
+  }
+};
+
+} // namespace sparta
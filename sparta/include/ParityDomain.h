@@ -0,0 +1,259 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <boost/optional.hpp>
+#include <ostream>
+#include <type_traits>
+
+#include "AbstractDomain.h"
+#include "NumericalDomainOps.h"
+
+namespace sparta {
+
+enum class Parity { Even, Odd };
+
+/*
+ * The parity of an integer:
+ *
+ *          T
+ *        /   \
+ *     Even   Odd
+ *        \   /
+ *         _|_
+ *
+ * Since the modulus of machine arithmetic is a power of two, the parity of
+ * additions, subtractions and multiplications is preserved by the wraparound,
+ * hence the arithmetic operators are sound for any integral type. This domain
+ * is typically combined with intervals in a reduced product.
+ */
+class ParityDomain final : public AbstractDomain<ParityDomain> {
+ public:
+  // By default, a parity domain is initialized to Top.
+  ParityDomain() = default;
+
+  explicit ParityDomain(Parity parity)
+      : m_kind(parity == Parity::Even ? Kind::Even : Kind::Odd) {}
+
+  static ParityDomain bottom() { return ParityDomain(Kind::Bottom); }
+
+  static ParityDomain top() { return ParityDomain(Kind::Top); }
+
+  template <typename Integer>
+  static ParityDomain of(Integer value) {
+    static_assert(std::is_integral<Integer>::value, "expecting integers.");
+    return ParityDomain(value % 2 == 0 ? Parity::Even : Parity::Odd);
+  }
+
+  /*
+   * Returns none if the element is Top or Bottom.
+   */
+  boost::optional<Parity> get_parity() const {
+    switch (m_kind) {
+    case Kind::Even:
+      return Parity::Even;
+    case Kind::Odd:
+      return Parity::Odd;
+    default:
+      return boost::none;
+    }
+  }
+
+  bool is_even() const { return m_kind == Kind::Even; }
+
+  bool is_odd() const { return m_kind == Kind::Odd; }
+
+  bool is_bottom() const override { return m_kind == Kind::Bottom; }
+
+  bool is_top() const override { return m_kind == Kind::Top; }
+
+  void set_to_bottom() override { m_kind = Kind::Bottom; }
+
+  void set_to_top() override { m_kind = Kind::Top; }
+
+  bool leq(const ParityDomain& other) const override {
+    return m_kind == Kind::Bottom || other.m_kind == Kind::Top ||
+           m_kind == other.m_kind;
+  }
+
+  bool equals(const ParityDomain& other) const override {
+    return m_kind == other.m_kind;
+  }
+
+  void join_with(const ParityDomain& other) override {
+    if (other.leq(*this)) {
+      return;
+    }
+    m_kind = leq(other) ? other.m_kind : Kind::Top;
+  }
+
+  void widen_with(const ParityDomain& other) override { join_with(other); }
+
+  void meet_with(const ParityDomain& other) override {
+    if (leq(other)) {
+      return;
+    }
+    m_kind = other.leq(*this) ? other.m_kind : Kind::Bottom;
+  }
+
+  void narrow_with(const ParityDomain& other) override { meet_with(other); }
+
+  ParityDomain operator-() const { return *this; }
+
+  /*
+   * The sum is even iff both operands have the same parity.
+   */
+  ParityDomain& operator+=(const ParityDomain& that) {
+    if (is_bottom() || that.is_bottom()) {
+      set_to_bottom();
+    } else if (is_top() || that.is_top()) {
+      set_to_top();
+    } else {
+      *this = ParityDomain(m_kind == that.m_kind ? Parity::Even : Parity::Odd);
+    }
+    return *this;
+  }
+
+  ParityDomain& operator-=(const ParityDomain& that) { return *this += that; }
+
+  /*
+   * The product is even as soon as one of the operands is even.
+   */
+  ParityDomain& operator*=(const ParityDomain& that) {
+    if (is_bottom() || that.is_bottom()) {
+      set_to_bottom();
+    } else if (is_even() || that.is_even()) {
+      *this = ParityDomain(Parity::Even);
+    } else if (is_top() || that.is_top()) {
+      set_to_top();
+    }
+    return *this;
+  }
+
+  friend ParityDomain operator+(ParityDomain x, const ParityDomain& y) {
+    return x += y;
+  }
+
+  friend ParityDomain operator-(ParityDomain x, const ParityDomain& y) {
+    return x -= y;
+  }
+
+  friend ParityDomain operator*(ParityDomain x, const ParityDomain& y) {
+    return x *= y;
+  }
+
+  friend std::ostream& operator<<(std::ostream& o, const ParityDomain& x) {
+    switch (x.m_kind) {
+    case Kind::Bottom:
+      return o << "_|_";
+    case Kind::Even:
+      return o << "even";
+    case Kind::Odd:
+      return o << "odd";
+    case Kind::Top:
+      return o << "T";
+    }
+    return o;
+  }
+
+ private:
+  enum class Kind { Bottom, Even, Odd, Top };
+
+  explicit ParityDomain(Kind kind) : m_kind(kind) {}
+
+  Kind m_kind{Kind::Top};
+};
+
+template <>
+struct ArithmeticDomainOps<ParityDomain> {
+  using Domain = ParityDomain;
+
+  template <typename Constant>
+  static Domain literal(const Constant& value) {
+    return Domain::of(value);
+  }
+
+  static Domain neg(const Domain& x) { return -x; }
+
+  static Domain add(const Domain& x, const Domain& y) { return x + y; }
+
+  static Domain sub(const Domain& x, const Domain& y) { return x - y; }
+
+  static Domain mul(const Domain& x, const Domain& y) { return x * y; }
+
+  /*
+   * The parity of the operands says nothing about the parity of the quotient,
+   * nor whether the divisor is zero.
+   */
+  static Domain div(const Domain& x, const Domain& y) {
+    return x.is_bottom() || y.is_bottom() ? Domain::bottom() : Domain::top();
+  }
+
+  /*
+   * The lowest bit of the result only depends on the lowest bits of the
+   * operands, hence on their parities.
+   */
+  static Domain bit_and(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    if (x.is_even() || y.is_even()) {
+      return Domain(Parity::Even);
+    }
+    return x.is_odd() && y.is_odd() ? Domain(Parity::Odd) : Domain::top();
+  }
+
+  static Domain bit_or(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    if (x.is_odd() || y.is_odd()) {
+      return Domain(Parity::Odd);
+    }
+    return x.is_even() && y.is_even() ? Domain(Parity::Even) : Domain::top();
+  }
+
+  static Domain bit_xor(const Domain& x, const Domain& y) { return x + y; }
+
+  /*
+   * The parity of the shift amount does not tell whether it is zero or valid.
+   */
+  static Domain shl(const Domain& x, const Domain& y) { return div(x, y); }
+
+  static Domain shr(const Domain& x, const Domain& y) { return div(x, y); }
+};
+
+template <>
+struct CompareDomainOps<ParityDomain>
+    : DerivedCompareDomainOps<CompareDomainOps<ParityDomain>, ParityDomain> {
+  using Domain = ParityDomain;
+
+  /*
+   * Two integers of different parities are never equal.
+   */
+  static BooleanDomain eq(const Domain& x, const Domain& y) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return BooleanDomain::bottom();
+    }
+    if ((x.is_even() && y.is_odd()) || (x.is_odd() && y.is_even())) {
+      return BooleanDomain(false);
+    }
+    return BooleanDomain::top();
+  }
+
+  static BooleanDomain lt(const Domain& x, const Domain& y) {
+    return x.is_bottom() || y.is_bottom() ? BooleanDomain::bottom()
+                                          : BooleanDomain::top();
+  }
+
+  static BooleanDomain le(const Domain& x, const Domain& y) {
+    return lt(x, y);
+  }
+};
+
+} // namespace sparta
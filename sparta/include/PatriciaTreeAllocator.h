@@ -0,0 +1,84 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <atomic>
+#include <cstddef>
+#include <memory_resource>
+
+namespace sparta {
+
+/*
+ * The memory resource from which the nodes of all Patricia trees are
+ * allocated, i.e., the nodes of PatriciaTreeSet and PatriciaTreeMap and hence
+ * of all the abstract domains built on top of them. Analyses allocate and
+ * free millions of small nodes, which can be made much cheaper by a pool or
+ * an arena, e.g., a std::pmr::synchronized_pool_resource that is released at
+ * the end of each analysis run. The default resource uses the global operator
+ * new and delete.
+ *
+ * The nodes don't record the resource they were allocated from, in order to
+ * keep them small. Hence, the resource can only be replaced when no node is
+ * alive, e.g., before an analysis run starts and after all the abstract values
+ * it computed have been destroyed. The resource must be thread-safe if the
+ * trees are manipulated by several threads.
+ */
+class PatriciaTreeAllocator final {
+ public:
+  static std::pmr::memory_resource* resource() { return current().load(); }
+
+  /*
+   * Passing a null resource restores the default one.
+   */
+  static void set_resource(std::pmr::memory_resource* resource) {
+    current().store(resource != nullptr ? resource
+                                        : std::pmr::new_delete_resource());
+  }
+
+  static void* allocate(size_t size) {
+    return resource()->allocate(size, alignof(std::max_align_t));
+  }
+
+  static void deallocate(void* p, size_t size) {
+    resource()->deallocate(p, size, alignof(std::max_align_t));
+  }
+
+ private:
+  static std::atomic<std::pmr::memory_resource*>& current() {
+    static std::atomic<std::pmr::memory_resource*> resource{
+        std::pmr::new_delete_resource()};
+    return resource;
+  }
+};
+
+/*
+ * Installs a memory resource for the nodes of the Patricia trees for the
+ * duration of a scope, e.g., an analysis run. All the trees allocated in the
+ * scope must be destroyed before the scope ends.
+ */
+class ScopedPatriciaTreeAllocator final {
+ public:
+  explicit ScopedPatriciaTreeAllocator(std::pmr::memory_resource* resource)
+      : m_previous(PatriciaTreeAllocator::resource()) {
+    PatriciaTreeAllocator::set_resource(resource);
+  }
+
+  ScopedPatriciaTreeAllocator(const ScopedPatriciaTreeAllocator&) = delete;
+
+  ScopedPatriciaTreeAllocator& operator=(const ScopedPatriciaTreeAllocator&) =
+      delete;
+
+  ~ScopedPatriciaTreeAllocator() {
+    PatriciaTreeAllocator::set_resource(m_previous);
+  }
+
+ private:
+  std::pmr::memory_resource* m_previous;
+};
+
+} // namespace sparta
@@ -18,9 +18,12 @@
 #include <type_traits>
 #include <utility>
 
+#include <boost/functional/hash.hpp>
 #include <boost/intrusive_ptr.hpp>
 
 #include "AbstractDomain.h"
+#include "PatriciaTreeAllocator.h"
+#include "PatriciaTreeStats.h"
 #include "PatriciaTreeUtil.h"
 
 // Forward declarations
@@ -72,14 +75,19 @@ inline bool equals(
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& tree2);
 
 template <typename IntegerType, typename Value>
+inline bool equal_contents(
+    const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& s,
+    const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& t);
+
+template <typename IntegerType, typename Value, typename Combine>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> combine_new_leaf(
-    const CombiningFunction<typename Value::type>& combine,
+    const Combine& combine,
     IntegerType key,
     const typename Value::type& value);
 
-template <typename IntegerType, typename Value>
+template <typename IntegerType, typename Value, typename Combine>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> update(
-    const CombiningFunction<typename Value::type>& combine,
+    const Combine& combine,
     IntegerType key,
     const typename Value::type& value,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& tree);
@@ -95,29 +103,40 @@ erase_all_matching(
     IntegerType key_mask,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& tree);
 
-template <typename IntegerType, typename Value>
+template <typename IntegerType, typename Value, typename Combine>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> merge(
-    const CombiningFunction<typename Value::type>& combine,
+    const Combine& combine,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& s,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& t);
 
-template <typename IntegerType, typename Value>
+template <typename IntegerType, typename Value, typename Combine>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> intersect(
-    const ptmap_impl::CombiningFunction<typename Value::type>& combine,
+    const Combine& combine,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& s,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& t);
 
-template <typename IntegerType, typename Value>
+template <typename IntegerType, typename Value, typename Combine>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> diff(
-    const ptmap_impl::CombiningFunction<typename Value::type>& combine,
+    const Combine& combine,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& s,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& t);
 
-template <typename T>
-T snd(const T&, const T& second) {
+template <typename IntegerType, typename T>
+T snd(IntegerType, const T&, const T& second) {
   return second;
 }
 
+/*
+ * Turns a combining function of the values into one that also receives the
+ * key, as expected by the operations on the trees.
+ */
+template <typename IntegerType, typename Function>
+auto ignore_key(const Function& combine) {
+  return [&combine](IntegerType, const auto& x, const auto& y) {
+    return combine(x, y);
+  };
+}
+
 /*
  * Convenience interface that makes it easy to define maps for value types that
  * are default-constructible and equality-comparable.
@@ -170,6 +189,15 @@ struct SimpleValue {
  *     // must be implemented. Additionally, value::type must be an
  *     // implementation of an AbstractDomain.
  *     static bool leq(const type& x, const type& y);
+ *
+ *     // Optional. A hash function for values that is consistent with
+ *     // equals(). If it is provided, each node of the tree stores a hash code
+ *     // of its contents, so that the binary operations on maps can skip the
+ *     // subtrees that hold the same bindings even if they are not physically
+ *     // shared, e.g., after restoring a map from a serialized form. As with
+ *     // physically equal subtrees, the combining function is not applied to
+ *     // the bindings of the subtrees that are skipped.
+ *     static size_t hash(const type& x);
  *   }
  *
  * Patricia trees can only handle unsigned integers. Arbitrary objects can be
@@ -193,9 +221,10 @@ class PatriciaTreeMap final {
   using const_reference = const mapped_type&;
   using const_pointer = const mapped_type*;
 
-  using IntegerType =
-      typename std::conditional_t<std::is_pointer<Key>::value, uintptr_t, Key>;
+  using IntegerType = typename PatriciaTreeKeyTraits<Key>::IntegerType;
   using combining_function = ptmap_impl::CombiningFunction<mapped_type>;
+  using keyed_combining_function =
+      std::function<mapped_type(Key, const mapped_type&, const mapped_type&)>;
   using mapping_function = ptmap_impl::MappingFunction<mapped_type>;
 
   ~PatriciaTreeMap() {
@@ -216,6 +245,8 @@ class PatriciaTreeMap final {
                                             std::declval<mapped_type>())),
                      bool>::value,
         "Value::equals() does not exist");
+    static_assert(sizeof(Key) == sizeof(IntegerType),
+                  "Key must have the same representation as its encoding");
   }
 
   bool empty() const { return m_tree == nullptr; }
@@ -290,8 +321,9 @@ class PatriciaTreeMap final {
   PatriciaTreeMap& update(
       const std::function<mapped_type(const mapped_type&)>& operation,
       Key key) {
+    PatriciaTreeStats::record(PatriciaTreeStats::Updates);
     m_tree = ptmap_impl::update<IntegerType, Value>(
-        [&operation](const mapped_type& x, const mapped_type&) {
+        [&operation](IntegerType, const mapped_type& x, const mapped_type&) {
           return operation(x);
         },
         encode(key),
@@ -316,30 +348,53 @@ class PatriciaTreeMap final {
   }
 
   PatriciaTreeMap& insert_or_assign(Key key, const mapped_type& value) {
+    PatriciaTreeStats::record(PatriciaTreeStats::Updates);
     m_tree = ptmap_impl::update<IntegerType, Value>(
-        ptmap_impl::snd<mapped_type>, encode(key), value, m_tree);
+        ptmap_impl::snd<IntegerType, mapped_type>, encode(key), value, m_tree);
     return *this;
   }
 
   PatriciaTreeMap& union_with(const combining_function& combine,
                               const PatriciaTreeMap& other) {
-    m_tree =
-        ptmap_impl::merge<IntegerType, Value>(combine, m_tree, other.m_tree);
+    m_tree = ptmap_impl::merge<IntegerType, Value>(
+        ptmap_impl::ignore_key<IntegerType>(combine), m_tree, other.m_tree);
     return *this;
   }
 
   PatriciaTreeMap& intersection_with(const combining_function& combine,
                                      const PatriciaTreeMap& other) {
     m_tree = ptmap_impl::intersect<IntegerType, Value>(
-        combine, m_tree, other.m_tree);
+        ptmap_impl::ignore_key<IntegerType>(combine), m_tree, other.m_tree);
+    return *this;
+  }
+
+  /*
+   * Same as union_with() and intersection_with(), except that the combining
+   * function also receives the key of the bindings, e.g., to apply a different
+   * policy to some distinguished keys. The combining function is only called
+   * on the keys that are bound in one of the maps, and it should be symmetric
+   * in its value arguments, since the order in which they are passed is
+   * unspecified.
+   */
+  PatriciaTreeMap& union_with_key(const keyed_combining_function& combine,
+                                  const PatriciaTreeMap& other) {
+    m_tree = ptmap_impl::merge<IntegerType, Value>(
+        decode_key(combine), m_tree, other.m_tree);
+    return *this;
+  }
+
+  PatriciaTreeMap& intersection_with_key(
+      const keyed_combining_function& combine, const PatriciaTreeMap& other) {
+    m_tree = ptmap_impl::intersect<IntegerType, Value>(
+        decode_key(combine), m_tree, other.m_tree);
     return *this;
   }
 
   // Requires that `combine(bottom, ...) = bottom`.
   PatriciaTreeMap& difference_with(const combining_function& combine,
                                    const PatriciaTreeMap& other) {
-    m_tree =
-        ptmap_impl::diff<IntegerType, Value>(combine, m_tree, other.m_tree);
+    m_tree = ptmap_impl::diff<IntegerType, Value>(
+        ptmap_impl::ignore_key<IntegerType>(combine), m_tree, other.m_tree);
     return *this;
   }
 
@@ -368,32 +423,24 @@ class PatriciaTreeMap final {
 
  private:
   // These functions are used to handle the type conversions required when
-  // manipulating maps with pointer keys. The first parameter is necessary to
-  // make template deduction work.
-  template <typename T = Key,
-            typename std::enable_if_t<std::is_pointer<T>::value, int> = 0>
-  static uintptr_t encode(Key x) {
-    return reinterpret_cast<uintptr_t>(x);
-  }
-
-  template <typename T = Key,
-            typename std::enable_if_t<!std::is_pointer<T>::value, int> = 0>
-  static Key encode(Key x) {
-    return x;
+  // manipulating maps with pointer keys or strongly-typed keys.
+  static IntegerType encode(Key x) {
+    return PatriciaTreeKeyTraits<Key>::encode(x);
   }
 
-  template <typename T = Key,
-            typename std::enable_if_t<std::is_pointer<T>::value, int> = 0>
-  static Key decode(uintptr_t x) {
-    return reinterpret_cast<Key>(x);
+  static Key decode(IntegerType x) {
+    return PatriciaTreeKeyTraits<Key>::decode(x);
   }
 
-  template <typename T = Key,
-            typename std::enable_if_t<!std::is_pointer<T>::value, int> = 0>
-  static Key decode(Key x) {
-    return x;
+  static auto decode_key(const keyed_combining_function& combine) {
+    return [&combine](IntegerType key,
+                      const mapped_type& x,
+                      const mapped_type& y) {
+      return combine(decode(key), x, y);
+    };
   }
 
+  // The first parameter is necessary to make template deduction work.
   template <typename T = Key,
             typename std::enable_if_t<std::is_pointer<T>::value, int> = 0>
   static const typename std::remove_pointer<T>::type& deref(Key x) {
@@ -441,8 +488,59 @@ namespace ptmap_impl {
 
 using namespace pt_util;
 
+// Content hashing is enabled if the value interface provides a hash function.
+template <typename Value, typename = void>
+struct has_content_hash : std::false_type {};
+
+template <typename Value>
+struct has_content_hash<
+    Value,
+    std::void_t<decltype(
+        Value::hash(std::declval<const typename Value::type&>()))>>
+    : std::true_type {};
+
+// The hash of the bindings held by a tree, which is only stored when content
+// hashing is enabled.
+template <bool Enabled>
+class ContentHash {
+ public:
+  size_t content_hash() const { return 0; }
+
+ protected:
+  void set_content_hash(size_t) {}
+};
+
+template <>
+class ContentHash<true> {
+ public:
+  size_t content_hash() const { return m_content_hash; }
+
+ protected:
+  void set_content_hash(size_t h) { m_content_hash = h; }
+
+ private:
+  size_t m_content_hash{0};
+};
+
+template <typename IntegerType, typename Value>
+size_t leaf_content_hash(IntegerType key,
+                         const typename Value::type& value,
+                         std::true_type) {
+  size_t seed = boost::hash<IntegerType>()(key);
+  boost::hash_combine(seed, Value::hash(value));
+  return seed;
+}
+
+template <typename IntegerType, typename Value>
+size_t leaf_content_hash(IntegerType,
+                         const typename Value::type&,
+                         std::false_type) {
+  return 0;
+}
+
 template <typename IntegerType, typename Value>
-class PatriciaTree {
+class PatriciaTree
+    : public ContentHash<has_content_hash<Value>::value> {
  public:
   // A Patricia tree is an immutable structure.
   PatriciaTree& operator=(const PatriciaTree& other) = delete;
@@ -459,6 +557,15 @@ class PatriciaTree {
 
   bool is_branch() const { return !is_leaf(); }
 
+  // The nodes are allocated from the resource set in PatriciaTreeAllocator.
+  static void* operator new(size_t size) {
+    return PatriciaTreeAllocator::allocate(size);
+  }
+
+  static void operator delete(void* p, size_t size) {
+    PatriciaTreeAllocator::deallocate(p, size);
+  }
+
   friend void intrusive_ptr_add_ref(const PatriciaTree<IntegerType, Value>* p) {
     p->m_reference_count.fetch_add(1, std::memory_order_relaxed);
   }
@@ -485,7 +592,14 @@ class PatriciaTreeBranch final : public PatriciaTree<IntegerType, Value> {
       : m_prefix(prefix),
         m_stacking_bit(branching_bit),
         m_left_tree(std::move(left_tree)),
-        m_right_tree(std::move(right_tree)) {}
+        m_right_tree(std::move(right_tree)) {
+    if (has_content_hash<Value>::value) {
+      // The tree is canonical, hence the hash of the subtrees is enough.
+      size_t seed = m_left_tree->content_hash();
+      boost::hash_combine(seed, m_right_tree->content_hash());
+      this->set_content_hash(seed);
+    }
+  }
 
   bool is_leaf() const override { return false; }
 
@@ -508,6 +622,7 @@ class PatriciaTreeBranch final : public PatriciaTree<IntegerType, Value> {
       IntegerType branching_bit,
       boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> left_tree,
       boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> right_tree) {
+    PatriciaTreeStats::record(PatriciaTreeStats::BranchAllocations);
     return new PatriciaTreeBranch<IntegerType, Value>(
         prefix, branching_bit, std::move(left_tree), std::move(right_tree));
   }
@@ -525,7 +640,10 @@ class PatriciaTreeLeaf final : public PatriciaTree<IntegerType, Value> {
   using mapped_type = typename Value::type;
 
   explicit PatriciaTreeLeaf(IntegerType key, const mapped_type& value)
-      : m_pair(key, value) {}
+      : m_pair(key, value) {
+    this->set_content_hash(leaf_content_hash<IntegerType, Value>(
+        key, value, has_content_hash<Value>()));
+  }
 
   bool is_leaf() const override { return true; }
 
@@ -535,6 +653,7 @@ class PatriciaTreeLeaf final : public PatriciaTree<IntegerType, Value> {
 
   static boost::intrusive_ptr<PatriciaTreeLeaf<IntegerType, Value>> make(
       IntegerType key, const mapped_type& value) {
+    PatriciaTreeStats::record(PatriciaTreeStats::LeafAllocations);
     return new PatriciaTreeLeaf<IntegerType, Value>(key, value);
   }
 
@@ -610,16 +729,21 @@ template <typename IntegerType, typename Value>
 inline bool leq(
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& s,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& t) {
+  PatriciaTreeStats::record(PatriciaTreeStats::SubsetCalls);
 
   RUNTIME_CHECK(Value::default_value().is_top() ||
                     Value::default_value().is_bottom(),
                 undefined_operation());
 
   if (s == t) {
+    PatriciaTreeStats::record(PatriciaTreeStats::SubsetReferenceHits);
     // This condition allows the leq operation to run in sublinear time when
     // comparing Patricia trees that share some structure.
     return true;
   }
+  if (equal_contents(s, t)) {
+    return true;
+  }
   if (s == nullptr) {
     return Value::default_value().is_bottom();
   }
@@ -712,7 +836,9 @@ template <typename IntegerType, typename Value>
 inline bool equals(
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& tree1,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& tree2) {
+  PatriciaTreeStats::record(PatriciaTreeStats::EqualsCalls);
   if (tree1 == tree2) {
+    PatriciaTreeStats::record(PatriciaTreeStats::EqualsReferenceHits);
     // This conditions allows the equality test to run in sublinear time when
     // comparing Patricia trees that share some structure.
     return true;
@@ -723,6 +849,11 @@ inline bool equals(
   if (tree2 == nullptr) {
     return false;
   }
+  // When content hashing is enabled, the hash codes are computed when the
+  // trees are constructed, and we can use them to cut short the equality test.
+  if (tree1->content_hash() != tree2->content_hash()) {
+    return false;
+  }
   if (tree1->is_leaf()) {
     if (tree2->is_branch()) {
       return false;
@@ -747,12 +878,29 @@ inline bool equals(
          equals(branch1->right_tree(), branch2->right_tree());
 }
 
+// Trees that hold the same bindings may not be physically equal, e.g., when
+// they have been built independently from one another. When content hashing is
+// enabled, we detect those trees in the binary operations by comparing their
+// hash codes first, so that the operations remain sublinear. Note that a
+// positive comparison requires a full equality test.
+template <typename IntegerType, typename Value>
+inline bool equal_contents(
+    const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& s,
+    const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& t) {
+  if (!has_content_hash<Value>::value || s == nullptr || t == nullptr ||
+      s->content_hash() != t->content_hash() || !equals(s, t)) {
+    return false;
+  }
+  PatriciaTreeStats::record(PatriciaTreeStats::ContentHits);
+  return true;
+}
+
 // Finds the value corresponding to :key in the tree and replaces its bound
 // value with combine(bound_value, :value). Note that the existing value is
 // always the first parameter to :combine and the new value is the second.
-template <typename IntegerType, typename Value>
+template <typename IntegerType, typename Value, typename Combine>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> update(
-    const ptmap_impl::CombiningFunction<typename Value::type>& combine,
+    const Combine& combine,
     IntegerType key,
     const typename Value::type& value,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& tree) {
@@ -780,6 +928,7 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> update(
       if (new_left_tree == branch->left_tree()) {
         return branch;
       }
+      PatriciaTreeStats::record(PatriciaTreeStats::PathCopies);
       return make_branch(branch->prefix(),
                          branch->branching_bit(),
                          new_left_tree,
@@ -789,6 +938,7 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> update(
       if (new_right_tree == branch->right_tree()) {
         return branch;
       }
+      PatriciaTreeStats::record(PatriciaTreeStats::PathCopies);
       return make_branch(branch->prefix(),
                          branch->branching_bit(),
                          branch->left_tree(),
@@ -815,7 +965,8 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> map(
     const auto& leaf =
         boost::static_pointer_cast<PatriciaTreeLeaf<IntegerType, Value>>(tree);
     auto new_value = f(leaf->value());
-    return combine_leaf(ptmap_impl::snd<typename Value::type>, new_value, leaf);
+    return combine_leaf(
+        ptmap_impl::snd<IntegerType, typename Value::type>, new_value, leaf);
   }
   const auto& branch =
       boost::static_pointer_cast<PatriciaTreeBranch<IntegerType, Value>>(tree);
@@ -866,16 +1017,21 @@ erase_all_matching(
 
 // We keep the notations of the paper so as to make the implementation easier
 // to follow.
-template <typename IntegerType, typename Value>
+template <typename IntegerType, typename Value, typename Combine>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> merge(
-    const ptmap_impl::CombiningFunction<typename Value::type>& combine,
+    const Combine& combine,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& s,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& t) {
+  PatriciaTreeStats::record(PatriciaTreeStats::MergeCalls);
   if (s == t) {
+    PatriciaTreeStats::record(PatriciaTreeStats::MergeReferenceHits);
     // This conditional is what allows the union operation to complete in
     // sublinear time when the operands share some structure.
     return s;
   }
+  if (equal_contents(s, t)) {
+    return s;
+  }
   if (s == nullptr) {
     return t;
   }
@@ -954,12 +1110,12 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> merge(
 }
 
 // Combine :value with the value in :leaf with combine(:leaf, :value).
-template <typename IntegerType, typename Value>
+template <typename IntegerType, typename Value, typename Combine>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> combine_leaf(
-    const ptmap_impl::CombiningFunction<typename Value::type>& combine,
+    const Combine& combine,
     const typename Value::type& value,
     const boost::intrusive_ptr<PatriciaTreeLeaf<IntegerType, Value>>& leaf) {
-  auto combined_value = combine(leaf->value(), value);
+  auto combined_value = combine(leaf->key(), leaf->value(), value);
   if (Value::is_default_value(combined_value)) {
     return nullptr;
   }
@@ -971,9 +1127,9 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> combine_leaf(
 }
 
 // Create a new leaf with the default value and combine :value into it.
-template <typename IntegerType, typename Value>
+template <typename IntegerType, typename Value, typename Combine>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> combine_new_leaf(
-    const ptmap_impl::CombiningFunction<typename Value::type>& combine,
+    const Combine& combine,
     IntegerType key,
     const typename Value::type& value) {
   auto new_leaf = boost::intrusive_ptr<PatriciaTreeLeaf<IntegerType, Value>>(
@@ -981,16 +1137,21 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> combine_new_leaf(
   return combine_leaf(combine, value, new_leaf);
 }
 
-template <typename IntegerType, typename Value>
+template <typename IntegerType, typename Value, typename Combine>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> intersect(
-    const ptmap_impl::CombiningFunction<typename Value::type>& combine,
+    const Combine& combine,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& s,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& t) {
+  PatriciaTreeStats::record(PatriciaTreeStats::IntersectCalls);
   if (s == t) {
+    PatriciaTreeStats::record(PatriciaTreeStats::IntersectReferenceHits);
     // This conditional is what allows the intersection operation to complete in
     // sublinear time when the operands share some structure.
     return s;
   }
+  if (equal_contents(s, t)) {
+    return s;
+  }
   if (s == nullptr || t == nullptr) {
     return nullptr;
   }
@@ -1032,8 +1193,9 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> intersect(
     // function will still be called to merge the elements in one tree with the
     // implicit default values in the other.
     return merge<IntegerType, Value>(
-        [](const typename Value::type& x, const typename Value::type& y) ->
-        typename Value::type {
+        [](IntegerType,
+           const typename Value::type& x,
+           const typename Value::type& y) -> typename Value::type {
           if (Value::is_default_value(x)) {
             return y;
           }
@@ -1058,16 +1220,21 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> intersect(
   return nullptr;
 }
 
-template <typename IntegerType, typename Value>
+template <typename IntegerType, typename Value, typename Combine>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> diff(
-    const ptmap_impl::CombiningFunction<typename Value::type>& combine,
+    const Combine& combine,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& s,
     const boost::intrusive_ptr<PatriciaTree<IntegerType, Value>>& t) {
+  PatriciaTreeStats::record(PatriciaTreeStats::DiffCalls);
   if (s == t) {
+    PatriciaTreeStats::record(PatriciaTreeStats::DiffReferenceHits);
     // This conditional is what allows the intersection operation to complete in
     // sublinear time when the operands share some structure.
     return nullptr;
   }
+  if (equal_contents(s, t)) {
+    return nullptr;
+  }
   if (s == nullptr) {
     return nullptr;
   }
@@ -1100,7 +1267,8 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType, Value>> diff(
   const auto& s1 = s_branch->right_tree();
   const auto& t0 = t_branch->left_tree();
   const auto& t1 = t_branch->right_tree();
-  auto combine_separate_trees = [](const typename Value::type& x,
+  auto combine_separate_trees = [](IntegerType,
+                                   const typename Value::type& x,
                                    const typename Value::type& y) ->
       typename Value::type {
         if (Value::is_default_value(x)) {
@@ -1164,8 +1332,7 @@ class PatriciaTreeIterator final {
   using pointer = value_type*;
   using reference = const value_type&;
 
-  using IntegerType =
-      typename std::conditional_t<std::is_pointer<Key>::value, uintptr_t, Key>;
+  using IntegerType = typename PatriciaTreeKeyTraits<Key>::IntegerType;
 
   PatriciaTreeIterator() {}
 
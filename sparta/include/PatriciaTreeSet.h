@@ -17,12 +17,16 @@
 #include <ostream>
 #include <stack>
 #include <type_traits>
+#include <unordered_map>
 #include <utility>
+#include <vector>
 
 #include <boost/functional/hash.hpp>
 #include <boost/intrusive_ptr.hpp>
 
 #include "Exceptions.h"
+#include "PatriciaTreeAllocator.h"
+#include "PatriciaTreeStats.h"
 #include "PatriciaTreeUtil.h"
 
 namespace sparta {
@@ -54,6 +58,9 @@ inline bool equals(
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& tree1,
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& tree2);
 
+template <typename IntegerType>
+class SubsetCache;
+
 template <typename IntegerType>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType>> insert(
     IntegerType key,
@@ -129,8 +136,7 @@ class PatriciaTreeSet final {
   using const_reference = const Element&;
   using const_pointer = const Element*;
 
-  using IntegerType = typename std::
-      conditional_t<std::is_pointer<Element>::value, uintptr_t, Element>;
+  using IntegerType = typename PatriciaTreeKeyTraits<Element>::IntegerType;
 
   PatriciaTreeSet() = default;
 
@@ -172,6 +178,43 @@ class PatriciaTreeSet final {
     return pt_impl::is_subset_of<IntegerType>(m_tree, other.m_tree);
   }
 
+  /*
+   * Tests this set against many candidate supersets at once, e.g., to find
+   * out which ones among hundreds of cached summaries subsume a state. The
+   * i-th element of the result is true iff this set is a subset of the i-th
+   * candidate. The inclusion tests of all pairs of subtrees are memoized, so
+   * that the subtrees shared by several candidates are only compared once.
+   */
+  std::vector<bool> is_subset_of_each(
+      const std::vector<PatriciaTreeSet>& candidates) const {
+    pt_impl::SubsetCache<IntegerType> cache;
+    std::vector<bool> result;
+    result.reserve(candidates.size());
+    for (const auto& candidate : candidates) {
+      result.push_back(cache.is_subset_of(m_tree, candidate.m_tree));
+    }
+    return result;
+  }
+
+  /*
+   * Computes the pairwise inclusion matrix of a collection of sets: the
+   * element (i, j) of the result is true iff sets[i] is a subset of sets[j].
+   * As in is_subset_of_each(), the comparisons of shared subtrees are
+   * memoized across all the pairs.
+   */
+  static std::vector<std::vector<bool>> subset_matrix(
+      const std::vector<PatriciaTreeSet>& sets) {
+    pt_impl::SubsetCache<IntegerType> cache;
+    std::vector<std::vector<bool>> result(sets.size(),
+                                          std::vector<bool>(sets.size()));
+    for (size_t i = 0; i < sets.size(); ++i) {
+      for (size_t j = 0; j < sets.size(); ++j) {
+        result[i][j] = cache.is_subset_of(sets[i].m_tree, sets[j].m_tree);
+      }
+    }
+    return result;
+  }
+
   bool equals(const PatriciaTreeSet& other) const {
     return pt_impl::equals<IntegerType>(m_tree, other.m_tree);
   }
@@ -207,11 +250,13 @@ class PatriciaTreeSet final {
   }
 
   PatriciaTreeSet& insert(Element key) {
+    PatriciaTreeStats::record(PatriciaTreeStats::Updates);
     m_tree = pt_impl::insert<IntegerType>(encode(key), m_tree);
     return *this;
   }
 
   PatriciaTreeSet& remove(Element key) {
+    PatriciaTreeStats::record(PatriciaTreeStats::Updates);
     m_tree = pt_impl::remove<IntegerType>(encode(key), m_tree);
     return *this;
   }
@@ -281,30 +326,13 @@ class PatriciaTreeSet final {
 
  private:
   // These functions are used to handle the type conversions required when
-  // manipulating sets of pointers. The first parameter is necessary to make
-  // template deduction work.
-  template <typename T = Element,
-            typename std::enable_if_t<std::is_pointer<T>::value, int> = 0>
-  static uintptr_t encode(Element x) {
-    return reinterpret_cast<uintptr_t>(x);
-  }
-
-  template <typename T = Element,
-            typename std::enable_if_t<!std::is_pointer<T>::value, int> = 0>
-  static Element encode(Element x) {
-    return x;
-  }
-
-  template <typename T = Element,
-            typename std::enable_if_t<std::is_pointer<T>::value, int> = 0>
-  static Element decode(uintptr_t x) {
-    return reinterpret_cast<Element>(x);
+  // manipulating sets of pointers or strongly-typed elements.
+  static IntegerType encode(Element x) {
+    return PatriciaTreeKeyTraits<Element>::encode(x);
   }
 
-  template <typename T = Element,
-            typename std::enable_if_t<!std::is_pointer<T>::value, int> = 0>
-  static Element decode(Element x) {
-    return x;
+  static Element decode(IntegerType x) {
+    return PatriciaTreeKeyTraits<Element>::decode(x);
   }
 
   boost::intrusive_ptr<pt_impl::PatriciaTree<IntegerType>> m_tree;
@@ -335,6 +363,15 @@ class PatriciaTree {
 
   bool is_branch() const { return !is_leaf(); }
 
+  // The nodes are allocated from the resource set in PatriciaTreeAllocator.
+  static void* operator new(size_t size) {
+    return PatriciaTreeAllocator::allocate(size);
+  }
+
+  static void operator delete(void* p, size_t size) {
+    PatriciaTreeAllocator::deallocate(p, size);
+  }
+
   size_t hash() const { return m_hash; }
 
   void set_hash(size_t h) { m_hash = h; }
@@ -401,6 +438,7 @@ class PatriciaTreeBranch final : public PatriciaTree<IntegerType> {
       IntegerType branching_bit,
       boost::intrusive_ptr<PatriciaTree<IntegerType>> left_tree,
       boost::intrusive_ptr<PatriciaTree<IntegerType>> right_tree) {
+    PatriciaTreeStats::record(PatriciaTreeStats::BranchAllocations);
     return new PatriciaTreeBranch<IntegerType>(
         prefix, branching_bit, std::move(left_tree), std::move(right_tree));
   }
@@ -426,6 +464,7 @@ class PatriciaTreeLeaf final : public PatriciaTree<IntegerType> {
 
   static boost::intrusive_ptr<PatriciaTreeLeaf<IntegerType>> make(
       IntegerType key) {
+    PatriciaTreeStats::record(PatriciaTreeStats::LeafAllocations);
     return new PatriciaTreeLeaf<IntegerType>(key);
   }
 
@@ -492,7 +531,9 @@ template <typename IntegerType>
 inline bool is_subset_of(
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& tree1,
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& tree2) {
+  PatriciaTreeStats::record(PatriciaTreeStats::SubsetCalls);
   if (tree1 == tree2) {
+    PatriciaTreeStats::record(PatriciaTreeStats::SubsetReferenceHits);
     // This conditions allows the inclusion test to run in sublinear time
     // when comparing Patricia trees that share some structure.
     return true;
@@ -534,13 +575,98 @@ inline bool is_subset_of(
   return false;
 }
 
+// A memoizing version of is_subset_of() for a batch of inclusion tests over
+// the same trees. The results of the comparisons between two branches are
+// cached, which makes the comparisons against many trees sharing subtrees
+// much cheaper. The cache refers to the trees by address, hence all the trees
+// must be kept alive for as long as the cache is used.
+template <typename IntegerType>
+class SubsetCache final {
+ public:
+  using TreePtr = boost::intrusive_ptr<PatriciaTree<IntegerType>>;
+
+  bool is_subset_of(const TreePtr& tree1, const TreePtr& tree2) {
+    PatriciaTreeStats::record(PatriciaTreeStats::SubsetCalls);
+    if (tree1 == tree2) {
+      PatriciaTreeStats::record(PatriciaTreeStats::SubsetReferenceHits);
+      return true;
+    }
+    if (tree1 == nullptr) {
+      return true;
+    }
+    if (tree2 == nullptr) {
+      return false;
+    }
+    if (tree1->is_leaf()) {
+      const auto& leaf =
+          boost::static_pointer_cast<PatriciaTreeLeaf<IntegerType>>(tree1);
+      return contains(leaf->key(), tree2);
+    }
+    if (tree2->is_leaf()) {
+      return false;
+    }
+    auto key = std::make_pair(tree1.get(), tree2.get());
+    auto it = m_results.find(key);
+    if (it != m_results.end()) {
+      return it->second;
+    }
+    bool result = is_branch_subset_of(
+        boost::static_pointer_cast<PatriciaTreeBranch<IntegerType>>(tree1),
+        boost::static_pointer_cast<PatriciaTreeBranch<IntegerType>>(tree2));
+    m_results.emplace(key, result);
+    return result;
+  }
+
+ private:
+  using BranchPtr = boost::intrusive_ptr<PatriciaTreeBranch<IntegerType>>;
+
+  bool is_branch_subset_of(const BranchPtr& branch1,
+                           const BranchPtr& branch2) {
+    if (branch1->prefix() == branch2->prefix() &&
+        branch1->branching_bit() == branch2->branching_bit()) {
+      return is_subset_of(branch1->left_tree(), branch2->left_tree()) &&
+             is_subset_of(branch1->right_tree(), branch2->right_tree());
+    }
+    if (branch1->branching_bit() > branch2->branching_bit() &&
+        match_prefix(
+            branch1->prefix(), branch2->prefix(), branch2->branching_bit())) {
+      const auto& subtree =
+          is_zero_bit(branch1->prefix(), branch2->branching_bit())
+              ? branch2->left_tree()
+              : branch2->right_tree();
+      return is_subset_of(branch1->left_tree(), subtree) &&
+             is_subset_of(branch1->right_tree(), subtree);
+    }
+    return false;
+  }
+
+  struct PairHash {
+    size_t operator()(const std::pair<const PatriciaTree<IntegerType>*,
+                                      const PatriciaTree<IntegerType>*>& p)
+        const {
+      size_t seed = 0;
+      boost::hash_combine(seed, p.first);
+      boost::hash_combine(seed, p.second);
+      return seed;
+    }
+  };
+
+  std::unordered_map<std::pair<const PatriciaTree<IntegerType>*,
+                               const PatriciaTree<IntegerType>*>,
+                     bool,
+                     PairHash>
+      m_results;
+};
+
 // A Patricia tree is a canonical representation of the set of keys it contains.
 // Hence, set equality is equivalent to structural equality of Patricia trees.
 template <typename IntegerType>
 inline bool equals(
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& tree1,
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& tree2) {
+  PatriciaTreeStats::record(PatriciaTreeStats::EqualsCalls);
   if (tree1 == tree2) {
+    PatriciaTreeStats::record(PatriciaTreeStats::EqualsReferenceHits);
     // This conditions allows the equality test to run in sublinear time
     // when comparing Patricia trees that share some structure.
     return true;
@@ -603,6 +729,7 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType>> insert(
       if (new_left_tree == branch->left_tree()) {
         return branch;
       }
+      PatriciaTreeStats::record(PatriciaTreeStats::PathCopies);
       return PatriciaTreeBranch<IntegerType>::make(branch->prefix(),
                                                    branch->branching_bit(),
                                                    new_left_tree,
@@ -612,6 +739,7 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType>> insert(
       if (new_right_tree == branch->right_tree()) {
         return branch;
       }
+      PatriciaTreeStats::record(PatriciaTreeStats::PathCopies);
       return PatriciaTreeBranch<IntegerType>::make(branch->prefix(),
                                                    branch->branching_bit(),
                                                    branch->left_tree(),
@@ -688,6 +816,7 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType>> remove(
       if (new_left_tree == branch->left_tree()) {
         return branch;
       }
+      PatriciaTreeStats::record(PatriciaTreeStats::PathCopies);
       return make_branch<IntegerType>(branch->prefix(),
                                       branch->branching_bit(),
                                       new_left_tree,
@@ -697,6 +826,7 @@ inline boost::intrusive_ptr<PatriciaTree<IntegerType>> remove(
       if (new_right_tree == branch->right_tree()) {
         return branch;
       }
+      PatriciaTreeStats::record(PatriciaTreeStats::PathCopies);
       return make_branch<IntegerType>(branch->prefix(),
                                       branch->branching_bit(),
                                       branch->left_tree(),
@@ -739,7 +869,9 @@ template <typename IntegerType>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType>> merge(
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& s,
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& t) {
+  PatriciaTreeStats::record(PatriciaTreeStats::MergeCalls);
   if (s == t) {
+    PatriciaTreeStats::record(PatriciaTreeStats::MergeReferenceHits);
     // This conditional is what allows the union operation to complete in
     // sublinear time when the operands share some structure.
     return s;
@@ -827,7 +959,9 @@ template <typename IntegerType>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType>> intersect(
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& s,
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& t) {
+  PatriciaTreeStats::record(PatriciaTreeStats::IntersectCalls);
   if (s == t) {
+    PatriciaTreeStats::record(PatriciaTreeStats::IntersectReferenceHits);
     // This conditional is what allows the intersection operation to complete in
     // sublinear time when the operands share some structure.
     return s;
@@ -878,7 +1012,9 @@ template <typename IntegerType>
 inline boost::intrusive_ptr<PatriciaTree<IntegerType>> diff(
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& s,
     const boost::intrusive_ptr<PatriciaTree<IntegerType>>& t) {
+  PatriciaTreeStats::record(PatriciaTreeStats::DiffCalls);
   if (s == t) {
+    PatriciaTreeStats::record(PatriciaTreeStats::DiffReferenceHits);
     // This conditional is what allows the intersection operation to complete in
     // sublinear time when the operands share some structure.
     return nullptr;
@@ -0,0 +1,115 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <atomic>
+#include <cstddef>
+#include <ostream>
+
+namespace sparta {
+
+/*
+ * Global counters measuring how effective the sharing of subtrees is in the
+ * Patricia tree operations, e.g., how often a merge is cut short because both
+ * operands are the same tree. This instrumentation is disabled by default and
+ * can be enabled by defining the macro SPARTA_PATRICIA_TREE_STATS before
+ * including any of the Patricia tree headers. The macro must be defined
+ * consistently across all translation units of a program. When the macro is
+ * not defined, recording an event is a no-op and all counters stay at zero.
+ *
+ * The counters cover both PatriciaTreeSet and PatriciaTreeMap (and hence all
+ * the abstract domains built on top of them). The operations on sets and maps
+ * are recursive: the number of calls of an operation includes all the
+ * recursive calls on subtrees, and a reference hit is a call that returned
+ * immediately because both subtrees were physically equal.
+ */
+class PatriciaTreeStats final {
+ public:
+  enum Counter : size_t {
+    // Number of leaf and branch nodes allocated.
+    LeafAllocations,
+    BranchAllocations,
+    // Union (merge), intersection and difference of trees.
+    MergeCalls,
+    MergeReferenceHits,
+    IntersectCalls,
+    IntersectReferenceHits,
+    DiffCalls,
+    DiffReferenceHits,
+    // Inclusion (is_subset_of for sets, leq for maps) and equality tests.
+    SubsetCalls,
+    SubsetReferenceHits,
+    EqualsCalls,
+    EqualsReferenceHits,
+    // Number of single-key updates (insertions, removals and updates of a
+    // binding), and number of branch nodes copied along the paths from the
+    // root to the updated leaves.
+    Updates,
+    PathCopies,
+    // Number of calls to a binary operation on maps that returned immediately
+    // because both subtrees held the same bindings without being physically
+    // equal. This requires content hashing (see PatriciaTreeMap.h).
+    ContentHits,
+    NumCounters
+  };
+
+  static void record(Counter counter) {
+#ifdef SPARTA_PATRICIA_TREE_STATS
+    counters()[counter].fetch_add(1, std::memory_order_relaxed);
+#else
+    (void)counter;
+#endif
+  }
+
+  static size_t get(Counter counter) {
+    return counters()[counter].load(std::memory_order_relaxed);
+  }
+
+  static void reset() {
+    for (size_t i = 0; i < NumCounters; ++i) {
+      counters()[i].store(0, std::memory_order_relaxed);
+    }
+  }
+
+  /*
+   * Prints a human-readable summary of all the counters.
+   */
+  static void report(std::ostream& o) {
+    o << "Patricia tree statistics:" << std::endl;
+    o << "  allocations: " << get(LeafAllocations) << " leaves, "
+      << get(BranchAllocations) << " branches" << std::endl;
+    report_operation(o, "merge", MergeCalls, MergeReferenceHits);
+    report_operation(o, "intersect", IntersectCalls, IntersectReferenceHits);
+    report_operation(o, "diff", DiffCalls, DiffReferenceHits);
+    report_operation(o, "subset", SubsetCalls, SubsetReferenceHits);
+    report_operation(o, "equals", EqualsCalls, EqualsReferenceHits);
+    o << "  content hits: " << get(ContentHits) << std::endl;
+    o << "  updates: " << get(Updates) << ", path copies: " << get(PathCopies);
+    if (get(Updates) > 0) {
+      o << " (" << static_cast<double>(get(PathCopies)) / get(Updates)
+        << " per update)";
+    }
+    o << std::endl;
+  }
+
+ private:
+  static std::atomic<size_t>* counters() {
+    static std::atomic<size_t> counters[NumCounters] = {};
+    return counters;
+  }
+
+  static void report_operation(std::ostream& o,
+                               const char* name,
+                               Counter calls,
+                               Counter hits) {
+    o << "  " << name << ": " << get(calls) << " calls, " << get(hits)
+      << " reference hits" << std::endl;
+  }
+};
+
+} // namespace sparta
@@ -7,10 +7,42 @@
 
 #pragma once
 
+#include <cstdint>
 #include <type_traits>
 
 namespace sparta {
 
+/*
+ * The keys of Patricia trees are encoded as unsigned integers. Unsigned
+ * integers and pointers are supported out of the box. Other key types, e.g.,
+ * strongly-typed identifiers wrapping an unsigned integer (see StrongId.h),
+ * can be used by specializing this structure:
+ *
+ *   template <>
+ *   struct sparta::PatriciaTreeKeyTraits<BlockId> {
+ *     using IntegerType = uint32_t;
+ *     static IntegerType encode(BlockId id) { return id.index; }
+ *     static BlockId decode(IntegerType x) { return BlockId{x}; }
+ *   };
+ *
+ * The iterators of PatriciaTreeMap present the encoded keys as values of the
+ * key type, hence a key must have the same representation as its encoding.
+ */
+template <typename Key, typename = void>
+struct PatriciaTreeKeyTraits {
+  using IntegerType = Key;
+  static IntegerType encode(Key x) { return x; }
+  static Key decode(IntegerType x) { return x; }
+};
+
+template <typename Key>
+struct PatriciaTreeKeyTraits<Key,
+                             std::enable_if_t<std::is_pointer<Key>::value>> {
+  using IntegerType = uintptr_t;
+  static IntegerType encode(Key x) { return reinterpret_cast<uintptr_t>(x); }
+  static Key decode(IntegerType x) { return reinterpret_cast<Key>(x); }
+};
+
 namespace pt_util {
 
 template <typename IntegerType>
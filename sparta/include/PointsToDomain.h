@@ -0,0 +1,214 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <ostream>
+#include <utility>
+
+#include "AbstractDomain.h"
+#include "PatriciaTreeMap.h"
+#include "PatriciaTreeSet.h"
+#include "PatriciaTreeSetAbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * A points-to graph, i.e., a set of edges from abstract variables to the
+ * allocation sites of the objects they may point to, in the style of
+ * Andersen's analysis. The abstract variables may be program variables, but
+ * also fields of abstract objects, provided that they are encoded as keys of
+ * a Patricia tree (unsigned integers, pointers or keys with a specialization
+ * of PatriciaTreeKeyTraits), like the allocation sites.
+ *
+ * A variable that has no edge doesn't point to any object, e.g., it is null
+ * or uninitialized. The join is the union of the edges and the meet is their
+ * intersection. Top denotes an unknown heap, in which any variable may point
+ * to any object. Since there are finitely many allocation sites in a program,
+ * the widening is the join.
+ *
+ * The graph is updated either strongly, when a variable is overwritten, or
+ * weakly, when it may or may not be, e.g., an abstract variable that
+ * represents a field of a summary object.
+ */
+template <typename Variable, typename Site>
+class PointsToDomain final
+    : public AbstractDomain<PointsToDomain<Variable, Site>> {
+ public:
+  using Sites = PatriciaTreeSet<Site>;
+  // The points-to set of a variable, which is Top if the graph is Top.
+  using PointsToSet = PatriciaTreeSetAbstractDomain<Site>;
+
+  /*
+   * The default constructor produces the graph without any edge.
+   */
+  PointsToDomain() = default;
+
+  static PointsToDomain bottom() {
+    PointsToDomain graph;
+    graph.m_kind = AbstractValueKind::Bottom;
+    return graph;
+  }
+
+  static PointsToDomain top() {
+    PointsToDomain graph;
+    graph.m_kind = AbstractValueKind::Top;
+    return graph;
+  }
+
+  bool is_bottom() const override {
+    return m_kind == AbstractValueKind::Bottom;
+  }
+
+  bool is_top() const override { return m_kind == AbstractValueKind::Top; }
+
+  void set_to_bottom() override {
+    m_kind = AbstractValueKind::Bottom;
+    m_edges.clear();
+  }
+
+  void set_to_top() override {
+    m_kind = AbstractValueKind::Top;
+    m_edges.clear();
+  }
+
+  /*
+   * The edges of the graph, which is not defined on Top.
+   */
+  const PatriciaTreeMap<Variable, Sites>& edges() const { return m_edges; }
+
+  PointsToSet points_to(const Variable& x) const {
+    switch (m_kind) {
+    case AbstractValueKind::Bottom:
+      return PointsToSet::bottom();
+    case AbstractValueKind::Top:
+      return PointsToSet::top();
+    default:
+      return PointsToSet(m_edges.at(x));
+    }
+  }
+
+  /*
+   * Whether two variables may point to the same object.
+   */
+  bool may_alias(const Variable& x, const Variable& y) const {
+    if (m_kind != AbstractValueKind::Value) {
+      return is_top();
+    }
+    return !m_edges.at(x).get_intersection_with(m_edges.at(y)).empty();
+  }
+
+  /*
+   * x = new Site, or any other assignment that overwrites x with pointers to
+   * the given sites.
+   */
+  PointsToDomain& strong_update(const Variable& x, Sites sites) {
+    if (m_kind == AbstractValueKind::Value) {
+      m_edges.insert_or_assign(x, std::move(sites));
+    }
+    return *this;
+  }
+
+  /*
+   * Adds edges from x to the given sites, keeping the existing ones.
+   */
+  PointsToDomain& weak_update(const Variable& x, const Sites& sites) {
+    if (m_kind == AbstractValueKind::Value) {
+      m_edges.update(
+          [&sites](const Sites& current) {
+            return current.get_union_with(sites);
+          },
+          x);
+    }
+    return *this;
+  }
+
+  /*
+   * The copy x = y.
+   */
+  PointsToDomain& assign(const Variable& x, const Variable& y) {
+    if (m_kind == AbstractValueKind::Value) {
+      m_edges.insert_or_assign(x, m_edges.at(y));
+    }
+    return *this;
+  }
+
+  /*
+   * Removes all the edges from a variable.
+   */
+  PointsToDomain& forget(const Variable& x) {
+    return strong_update(x, Sites());
+  }
+
+  bool leq(const PointsToDomain& other) const override {
+    if (is_bottom() || other.is_top()) {
+      return true;
+    }
+    if (other.is_bottom() || is_top()) {
+      return false;
+    }
+    for (const auto& binding : m_edges) {
+      if (!binding.second.is_subset_of(other.m_edges.at(binding.first))) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  bool equals(const PointsToDomain& other) const override {
+    return m_kind == other.m_kind && m_edges.equals(other.m_edges);
+  }
+
+  void join_with(const PointsToDomain& other) override {
+    if (is_top() || other.is_bottom()) {
+      return;
+    }
+    if (is_bottom() || other.is_top()) {
+      *this = other;
+      return;
+    }
+    m_edges.union_with(
+        [](const Sites& x, const Sites& y) { return x.get_union_with(y); },
+        other.m_edges);
+  }
+
+  void widen_with(const PointsToDomain& other) override { join_with(other); }
+
+  void meet_with(const PointsToDomain& other) override {
+    if (is_bottom() || other.is_top()) {
+      return;
+    }
+    if (is_top() || other.is_bottom()) {
+      *this = other;
+      return;
+    }
+    m_edges.intersection_with(
+        [](const Sites& x, const Sites& y) {
+          return x.get_intersection_with(y);
+        },
+        other.m_edges);
+  }
+
+  void narrow_with(const PointsToDomain& other) override { meet_with(other); }
+
+  friend std::ostream& operator<<(std::ostream& o, const PointsToDomain& x) {
+    switch (x.m_kind) {
+    case AbstractValueKind::Bottom:
+      return o << "_|_";
+    case AbstractValueKind::Top:
+      return o << "T";
+    default:
+      return o << x.m_edges;
+    }
+  }
+
+ private:
+  AbstractValueKind m_kind{AbstractValueKind::Value};
+  PatriciaTreeMap<Variable, Sites> m_edges;
+};
+
+} // namespace sparta
@@ -0,0 +1,172 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <initializer_list>
+#include <ostream>
+#include <utility>
+
+#include "AbstractDomain.h"
+#include "NumericalDomainOps.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+
+namespace sparta {
+
+/*
+ * A predicate abstraction: the truth values of a fixed set of predicates over
+ * the program state, which are chosen by the client of the analysis (e.g.,
+ * "the file is open" or "x < y"). Each predicate is either known to be true,
+ * known to be false, or unknown (Top). This supports property-directed
+ * analyses without a full symbolic engine:
+ *
+ *   using Domain = PredicateDomain<Predicate>;
+ *
+ *   Domain analyze_edge(const EdgeId& edge, const Domain& state) {
+ *     auto result = state;
+ *     if (auto p = branch_condition(edge)) {
+ *       // Bottom if the branch is infeasible.
+ *       result.assume(*p, is_true_branch(edge));
+ *     }
+ *     return result;
+ *   }
+ *
+ *   void analyze_node(const NodeId& node, Domain* state) {
+ *     for (const auto& p : predicates_invalidated_by(node)) {
+ *       state->forget(p);
+ *     }
+ *     ...
+ *   }
+ *
+ * The predicates are identified by the keys of a Patricia tree, i.e., unsigned
+ * integers or pointers. The meaning of a predicate is opaque to the domain,
+ * hence it is the responsibility of the transformers to forget or update the
+ * predicates that a statement may invalidate.
+ */
+template <typename Predicate>
+class PredicateDomain final
+    : public AbstractDomain<PredicateDomain<Predicate>> {
+ public:
+  using Environment =
+      PatriciaTreeMapAbstractEnvironment<Predicate, BooleanDomain>;
+
+  /*
+   * By default, the truth values of all the predicates are unknown.
+   */
+  PredicateDomain() = default;
+
+  PredicateDomain(
+      std::initializer_list<std::pair<Predicate, bool>> truth_values) {
+    for (const auto& binding : truth_values) {
+      m_env.set(binding.first, BooleanDomain(binding.second));
+    }
+  }
+
+  static PredicateDomain bottom() {
+    return PredicateDomain(Environment::bottom());
+  }
+
+  static PredicateDomain top() { return PredicateDomain(Environment::top()); }
+
+  /*
+   * The truth value of the predicate, which is Bottom if the state is Bottom.
+   */
+  BooleanDomain get(const Predicate& predicate) const {
+    return m_env.get(predicate);
+  }
+
+  /*
+   * Sets the truth value of a predicate, e.g., after a statement that
+   * establishes it.
+   */
+  PredicateDomain& set(const Predicate& predicate, bool value) {
+    m_env.set(predicate, BooleanDomain(value));
+    return *this;
+  }
+
+  /*
+   * Makes the truth value of the predicate unknown, e.g., after a statement
+   * that may modify the variables it depends on.
+   */
+  PredicateDomain& forget(const Predicate& predicate) {
+    m_env.set(predicate, BooleanDomain::top());
+    return *this;
+  }
+
+  /*
+   * Refines the state with the knowledge that the predicate has the given
+   * truth value, e.g., on the outgoing edges of a branch. The state becomes
+   * Bottom if it contradicts the assumption.
+   */
+  PredicateDomain& assume(const Predicate& predicate, bool value) {
+    m_env.update(predicate, [value](const BooleanDomain& truth_value) {
+      return truth_value.meet(BooleanDomain(value));
+    });
+    return *this;
+  }
+
+  /*
+   * Checks an assertion that the predicate has the given truth value. The
+   * result is true if the assertion holds in all the executions, false if it
+   * fails in all of them, Top if it may fail, and Bottom if the state is
+   * Bottom. Since the executions that fail the assertion stop, the state is
+   * then refined as if by assume().
+   */
+  BooleanDomain assert_that(const Predicate& predicate, bool value) {
+    BooleanDomain result = get(predicate);
+    if (result.is_value()) {
+      result = BooleanDomain(*result.get_constant() == value);
+    }
+    assume(predicate, value);
+    return result;
+  }
+
+  const Environment& environment() const { return m_env; }
+
+  bool is_bottom() const override { return m_env.is_bottom(); }
+
+  bool is_top() const override { return m_env.is_top(); }
+
+  bool leq(const PredicateDomain& other) const override {
+    return m_env.leq(other.m_env);
+  }
+
+  bool equals(const PredicateDomain& other) const override {
+    return m_env.equals(other.m_env);
+  }
+
+  void set_to_bottom() override { m_env.set_to_bottom(); }
+
+  void set_to_top() override { m_env.set_to_top(); }
+
+  void join_with(const PredicateDomain& other) override {
+    m_env.join_with(other.m_env);
+  }
+
+  void widen_with(const PredicateDomain& other) override {
+    m_env.widen_with(other.m_env);
+  }
+
+  void meet_with(const PredicateDomain& other) override {
+    m_env.meet_with(other.m_env);
+  }
+
+  void narrow_with(const PredicateDomain& other) override {
+    m_env.narrow_with(other.m_env);
+  }
+
+  friend std::ostream& operator<<(std::ostream& o, const PredicateDomain& d) {
+    return o << d.m_env;
+  }
+
+ private:
+  explicit PredicateDomain(Environment env) : m_env(std::move(env)) {}
+
+  Environment m_env;
+};
+
+} // namespace sparta
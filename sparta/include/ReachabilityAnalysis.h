@@ -0,0 +1,135 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <functional>
+#include <ostream>
+
+#include "AbstractDomain.h"
+#include "MonotonicFixpointIterator.h"
+
+namespace sparta {
+
+/*
+ * The two-point lattice {unreachable, reachable}, i.e., the single-point
+ * lattice lifted with a Bottom element. The single point (Top) carries no
+ * information besides the fact that a program point may be reached, which
+ * makes it the cheapest domain that the fixpoint iterators can run.
+ */
+class ReachabilityDomain final : public AbstractDomain<ReachabilityDomain> {
+ public:
+  /*
+   * The default constructor produces the Top value.
+   */
+  ReachabilityDomain() = default;
+
+  static ReachabilityDomain bottom() { return ReachabilityDomain(false); }
+
+  static ReachabilityDomain top() { return ReachabilityDomain(true); }
+
+  bool is_bottom() const override { return !m_reachable; }
+
+  bool is_top() const override { return m_reachable; }
+
+  void set_to_bottom() override { m_reachable = false; }
+
+  void set_to_top() override { m_reachable = true; }
+
+  bool leq(const ReachabilityDomain& other) const override {
+    return !m_reachable || other.m_reachable;
+  }
+
+  bool equals(const ReachabilityDomain& other) const override {
+    return m_reachable == other.m_reachable;
+  }
+
+  void join_with(const ReachabilityDomain& other) override {
+    m_reachable = m_reachable || other.m_reachable;
+  }
+
+  void widen_with(const ReachabilityDomain& other) override {
+    join_with(other);
+  }
+
+  void meet_with(const ReachabilityDomain& other) override {
+    m_reachable = m_reachable && other.m_reachable;
+  }
+
+  void narrow_with(const ReachabilityDomain& other) override {
+    meet_with(other);
+  }
+
+  friend std::ostream& operator<<(std::ostream& o,
+                                  const ReachabilityDomain& x) {
+    return o << (x.m_reachable ? "T" : "_|_");
+  }
+
+ private:
+  explicit ReachabilityDomain(bool reachable) : m_reachable(reachable) {}
+
+  bool m_reachable{true};
+};
+
+/*
+ * Computes the nodes and edges of a graph that are reachable from the entry.
+ * Derived classes can prune the edges that are known to be infeasible, e.g.,
+ * the branches of a conditional on a constant, by overriding
+ * `is_feasible()`. This is a cheap pre-pass that can be run before an
+ * expensive analysis, in order to restrict the latter to the reachable part
+ * of the graph:
+ *
+ *   ReachabilityAnalysis<CFGInterface> reachability(cfg);
+ *   reachability.run();
+ *   for (auto block : cfg.blocks()) {
+ *     if (!reachability.is_reachable(block)) {
+ *       remove(block);
+ *     }
+ *   }
+ */
+template <typename GraphInterface,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class ReachabilityAnalysis
+    : public MonotonicFixpointIterator<GraphInterface,
+                                       ReachabilityDomain,
+                                       NodeHash> {
+ public:
+  using Base =
+      MonotonicFixpointIterator<GraphInterface, ReachabilityDomain, NodeHash>;
+  using NodeId = typename GraphInterface::NodeId;
+  using EdgeId = typename GraphInterface::EdgeId;
+
+  using Base::Base;
+
+  virtual ~ReachabilityAnalysis() {}
+
+  void run() { Base::run(ReachabilityDomain::top()); }
+
+  /*
+   * Returns false if the edge can never be taken. All the edges are feasible
+   * by default.
+   */
+  virtual bool is_feasible(const EdgeId&) const { return true; }
+
+  bool is_reachable(const NodeId& node) const {
+    return !this->get_entry_state_at(node).is_bottom();
+  }
+
+  bool is_reachable_edge(const EdgeId& edge) const {
+    return is_feasible(edge) &&
+           is_reachable(GraphInterface::source(this->m_graph, edge));
+  }
+
+  void analyze_node(const NodeId&, ReachabilityDomain*) const override {}
+
+  ReachabilityDomain analyze_edge(
+      const EdgeId& edge, const ReachabilityDomain& state) const override {
+    return is_feasible(edge) ? state : ReachabilityDomain::bottom();
+  }
+};
+
+} // namespace sparta
@@ -0,0 +1,250 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <array>
+#include <cstddef>
+#include <initializer_list>
+#include <ostream>
+#include <utility>
+
+#include "AbstractDomain.h"
+#include "DefaultBinding.h"
+#include "Exceptions.h"
+
+namespace sparta {
+
+/*
+ * An abstract environment over a fixed number N of registers 0, ..., N - 1
+ * that is known at compile time, e.g., the register file of a machine or the
+ * frame of a method whose size is known in advance. The values are stored
+ * inline in an array, hence accessing a register is a mere indexing, without
+ * the hashing of HashedAbstractEnvironment, the tree traversal of
+ * PatriciaTreeMapAbstractEnvironment or the indirection through shared
+ * chunks of ArrayAbstractEnvironment. This makes it the fastest environment
+ * for the transfer functions of small, dense register spaces.
+ *
+ * In exchange, copying an environment copies the N values, and the lattice
+ * operations always visit all the registers. For large register spaces, or
+ * if the states are copied much more often than they are accessed, prefer
+ * ArrayAbstractEnvironment.
+ *
+ * The registers are initially bound to Top, and an environment in which some
+ * register is bound to Bottom is Bottom. Accessing a register outside of
+ * [0, N) throws an invalid_argument exception.
+ */
+template <typename Domain, size_t N>
+class RegisterFileDomain final
+    : public AbstractDomain<RegisterFileDomain<Domain, N>> {
+  static_assert(N > 0, "the register file must not be empty.");
+
+ public:
+  using DefaultBinding = DefaultIsTop;
+
+  /*
+   * The default constructor produces the Top value.
+   */
+  RegisterFileDomain() { m_registers.fill(Domain::top()); }
+
+  /*
+   * Binds the first registers to the given values, and the others to Top.
+   */
+  RegisterFileDomain(std::initializer_list<Domain> values)
+      : RegisterFileDomain() {
+    RUNTIME_CHECK(values.size() <= N,
+                  invalid_argument()
+                      << argument_name("values")
+                      << operation_name("RegisterFileDomain()"));
+    size_t index = 0;
+    for (const auto& value : values) {
+      set(index++, value);
+    }
+  }
+
+  static RegisterFileDomain bottom() {
+    RegisterFileDomain env;
+    env.set_to_bottom();
+    return env;
+  }
+
+  static RegisterFileDomain top() { return RegisterFileDomain(); }
+
+  static constexpr size_t size() { return N; }
+
+  const Domain& get(size_t index) const {
+    check_index(index, "RegisterFileDomain::get");
+    return m_registers[index];
+  }
+
+  RegisterFileDomain& set(size_t index, Domain value) {
+    check_index(index, "RegisterFileDomain::set");
+    if (m_is_bottom) {
+      return *this;
+    }
+    if (value.is_bottom()) {
+      set_to_bottom();
+      return *this;
+    }
+    m_registers[index] = std::move(value);
+    return *this;
+  }
+
+  /*
+   * Applies the operation to the value of a register in place.
+   */
+  template <typename Operation>
+  RegisterFileDomain& update(size_t index, Operation&& operation) {
+    check_index(index, "RegisterFileDomain::update");
+    if (m_is_bottom) {
+      return *this;
+    }
+    operation(&m_registers[index]);
+    if (m_registers[index].is_bottom()) {
+      set_to_bottom();
+    }
+    return *this;
+  }
+
+  bool is_bottom() const override { return m_is_bottom; }
+
+  bool is_top() const override {
+    if (m_is_bottom) {
+      return false;
+    }
+    for (const auto& value : m_registers) {
+      if (!value.is_top()) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  void set_to_bottom() override {
+    m_is_bottom = true;
+    m_registers.fill(Domain::bottom());
+  }
+
+  void set_to_top() override {
+    m_is_bottom = false;
+    m_registers.fill(Domain::top());
+  }
+
+  bool leq(const RegisterFileDomain& other) const override {
+    if (m_is_bottom) {
+      return true;
+    }
+    if (other.m_is_bottom) {
+      return false;
+    }
+    for (size_t i = 0; i < N; ++i) {
+      if (!m_registers[i].leq(other.m_registers[i])) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  bool equals(const RegisterFileDomain& other) const override {
+    if (m_is_bottom || other.m_is_bottom) {
+      return m_is_bottom == other.m_is_bottom;
+    }
+    for (size_t i = 0; i < N; ++i) {
+      if (!m_registers[i].equals(other.m_registers[i])) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  void join_with(const RegisterFileDomain& other) override {
+    join_like_operation(
+        other, [](Domain* x, const Domain& y) { x->join_with(y); });
+  }
+
+  void widen_with(const RegisterFileDomain& other) override {
+    join_like_operation(
+        other, [](Domain* x, const Domain& y) { x->widen_with(y); });
+  }
+
+  void meet_with(const RegisterFileDomain& other) override {
+    meet_like_operation(
+        other, [](Domain* x, const Domain& y) { x->meet_with(y); });
+  }
+
+  void narrow_with(const RegisterFileDomain& other) override {
+    meet_like_operation(
+        other, [](Domain* x, const Domain& y) { x->narrow_with(y); });
+  }
+
+  friend std::ostream& operator<<(std::ostream& o,
+                                  const RegisterFileDomain& env) {
+    if (env.is_bottom()) {
+      return o << "_|_";
+    }
+    if (env.is_top()) {
+      return o << "T";
+    }
+    o << "{";
+    bool first = true;
+    for (size_t index = 0; index < N; ++index) {
+      const Domain& value = env.m_registers[index];
+      if (value.is_top()) {
+        continue;
+      }
+      o << (first ? "" : ", ") << index << " -> " << value;
+      first = false;
+    }
+    return o << "}";
+  }
+
+ private:
+  static void check_index(size_t index, const char* operation) {
+    RUNTIME_CHECK(index < N,
+                  invalid_argument() << argument_name("index")
+                                     << operation_name(operation));
+  }
+
+  template <typename Operation>
+  void join_like_operation(const RegisterFileDomain& other,
+                           Operation operation) {
+    if (other.m_is_bottom) {
+      return;
+    }
+    if (m_is_bottom) {
+      *this = other;
+      return;
+    }
+    for (size_t i = 0; i < N; ++i) {
+      operation(&m_registers[i], other.m_registers[i]);
+    }
+  }
+
+  template <typename Operation>
+  void meet_like_operation(const RegisterFileDomain& other,
+                           Operation operation) {
+    if (m_is_bottom) {
+      return;
+    }
+    if (other.m_is_bottom) {
+      set_to_bottom();
+      return;
+    }
+    for (size_t i = 0; i < N; ++i) {
+      operation(&m_registers[i], other.m_registers[i]);
+      if (m_registers[i].is_bottom()) {
+        set_to_bottom();
+        return;
+      }
+    }
+  }
+
+  bool m_is_bottom{false};
+  std::array<Domain, N> m_registers;
+};
+
+} // namespace sparta
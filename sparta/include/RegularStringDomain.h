@@ -0,0 +1,534 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <iomanip>
+#include <limits>
+#include <map>
+#include <ostream>
+#include <queue>
+#include <set>
+#include <string>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+namespace rsd_impl {
+
+using Transitions = std::map<unsigned char, uint32_t>;
+
+/*
+ * A deterministic finite automaton over bytes, whose initial state is 0. The
+ * transitions that are not represented lead to an implicit rejecting state.
+ */
+struct Automaton {
+  std::vector<Transitions> transitions;
+  std::vector<bool> accepting;
+
+  size_t size() const { return accepting.size(); }
+
+  uint32_t add_state(bool is_accepting) {
+    transitions.emplace_back();
+    accepting.push_back(is_accepting);
+    return accepting.size() - 1;
+  }
+
+  bool operator==(const Automaton& other) const {
+    return accepting == other.accepting && transitions == other.transitions;
+  }
+
+  static Automaton universal() {
+    Automaton automaton;
+    automaton.add_state(/* is_accepting */ true);
+    for (unsigned c = 0; c <= std::numeric_limits<unsigned char>::max(); ++c) {
+      automaton.transitions[0][static_cast<unsigned char>(c)] = 0;
+    }
+    return automaton;
+  }
+
+  bool is_universal() const {
+    return size() == 1 && accepting[0] &&
+           transitions[0].size() ==
+               size_t(std::numeric_limits<unsigned char>::max()) + 1;
+  }
+};
+
+/*
+ * A nondeterministic finite automaton with epsilon transitions, which is used
+ * to build the union and the concatenation of deterministic automata.
+ */
+struct NondeterministicAutomaton {
+  std::vector<std::map<unsigned char, std::vector<uint32_t>>> transitions;
+  std::vector<std::vector<uint32_t>> epsilon;
+  std::vector<bool> accepting;
+
+  uint32_t add_state(bool is_accepting) {
+    transitions.emplace_back();
+    epsilon.emplace_back();
+    accepting.push_back(is_accepting);
+    return accepting.size() - 1;
+  }
+
+  /*
+   * Copies the states of a deterministic automaton and returns the state
+   * corresponding to its initial state.
+   */
+  uint32_t embed(const Automaton& automaton) {
+    uint32_t offset = accepting.size();
+    for (size_t s = 0; s < automaton.size(); ++s) {
+      uint32_t state = add_state(automaton.accepting[s]);
+      for (const auto& transition : automaton.transitions[s]) {
+        transitions[state][transition.first].push_back(offset +
+                                                       transition.second);
+      }
+    }
+    return offset;
+  }
+
+  std::vector<uint32_t> closure(std::vector<uint32_t> states) const {
+    std::set<uint32_t> visited(states.begin(), states.end());
+    for (size_t i = 0; i < states.size(); ++i) {
+      for (uint32_t next : epsilon[states[i]]) {
+        if (visited.insert(next).second) {
+          states.push_back(next);
+        }
+      }
+    }
+    return std::vector<uint32_t>(visited.begin(), visited.end());
+  }
+};
+
+/*
+ * The subset construction, restricted to the subsets that are reachable from
+ * the initial state.
+ */
+inline Automaton determinize(const NondeterministicAutomaton& nfa,
+                             uint32_t initial) {
+  Automaton dfa;
+  std::map<std::vector<uint32_t>, uint32_t> states;
+  std::vector<std::vector<uint32_t>> worklist;
+  auto get_state = [&](std::vector<uint32_t> subset) {
+    auto it = states.find(subset);
+    if (it != states.end()) {
+      return it->second;
+    }
+    bool is_accepting = false;
+    for (uint32_t s : subset) {
+      is_accepting = is_accepting || nfa.accepting[s];
+    }
+    uint32_t state = dfa.add_state(is_accepting);
+    states.emplace(subset, state);
+    worklist.push_back(std::move(subset));
+    return state;
+  };
+  get_state(nfa.closure({initial}));
+  while (!worklist.empty()) {
+    auto subset = std::move(worklist.back());
+    worklist.pop_back();
+    uint32_t state = states.at(subset);
+    std::map<unsigned char, std::vector<uint32_t>> successors;
+    for (uint32_t s : subset) {
+      for (const auto& transition : nfa.transitions[s]) {
+        auto& targets = successors[transition.first];
+        targets.insert(
+            targets.end(), transition.second.begin(), transition.second.end());
+      }
+    }
+    for (auto& successor : successors) {
+      uint32_t target = get_state(nfa.closure(std::move(successor.second)));
+      dfa.transitions[state][successor.first] = target;
+    }
+  }
+  return dfa;
+}
+
+/*
+ * Returns the minimal automaton recognizing the same language, in which the
+ * states are numbered in the order of a breadth-first traversal from the
+ * initial state that follows the transitions by increasing byte. Two automata
+ * recognize the same language if and only if their minimal forms are equal.
+ * The minimal form of the empty language has no state at all.
+ */
+inline Automaton minimize(const Automaton& automaton) {
+  size_t n = automaton.size();
+  // Remove the states from which no accepting state is reachable, as they are
+  // equivalent to the implicit rejecting state.
+  std::vector<std::vector<uint32_t>> predecessors(n);
+  std::vector<uint32_t> worklist;
+  std::vector<bool> is_live(n, false);
+  for (uint32_t s = 0; s < n; ++s) {
+    for (const auto& transition : automaton.transitions[s]) {
+      predecessors[transition.second].push_back(s);
+    }
+    if (automaton.accepting[s]) {
+      is_live[s] = true;
+      worklist.push_back(s);
+    }
+  }
+  while (!worklist.empty()) {
+    uint32_t s = worklist.back();
+    worklist.pop_back();
+    for (uint32_t p : predecessors[s]) {
+      if (!is_live[p]) {
+        is_live[p] = true;
+        worklist.push_back(p);
+      }
+    }
+  }
+  if (n == 0 || !is_live[0]) {
+    return Automaton();
+  }
+
+  // Moore's partition refinement.
+  constexpr uint32_t DEAD = std::numeric_limits<uint32_t>::max();
+  std::vector<uint32_t> partition(n, DEAD);
+  size_t num_classes = 0;
+  for (uint32_t s = 0; s < n; ++s) {
+    if (is_live[s]) {
+      partition[s] = automaton.accepting[s] ? 1 : 0;
+    }
+  }
+  while (true) {
+    using Signature =
+        std::pair<uint32_t, std::vector<std::pair<unsigned char, uint32_t>>>;
+    std::map<Signature, uint32_t> classes;
+    std::vector<uint32_t> refined(n, DEAD);
+    for (uint32_t s = 0; s < n; ++s) {
+      if (!is_live[s]) {
+        continue;
+      }
+      Signature signature;
+      signature.first = partition[s];
+      for (const auto& transition : automaton.transitions[s]) {
+        if (is_live[transition.second]) {
+          signature.second.emplace_back(transition.first,
+                                        partition[transition.second]);
+        }
+      }
+      refined[s] =
+          classes.emplace(std::move(signature), classes.size()).first->second;
+    }
+    partition = std::move(refined);
+    if (classes.size() == num_classes) {
+      break;
+    }
+    num_classes = classes.size();
+  }
+
+  // Build the quotient automaton with a canonical numbering of the states.
+  Automaton minimal;
+  std::vector<uint32_t> representative(num_classes, DEAD);
+  for (uint32_t s = 0; s < n; ++s) {
+    if (is_live[s] && representative[partition[s]] == DEAD) {
+      representative[partition[s]] = s;
+    }
+  }
+  std::vector<uint32_t> number(num_classes, DEAD);
+  std::queue<uint32_t> queue;
+  number[partition[0]] = minimal.add_state(automaton.accepting[0]);
+  queue.push(partition[0]);
+  while (!queue.empty()) {
+    uint32_t c = queue.front();
+    queue.pop();
+    uint32_t s = representative[c];
+    for (const auto& transition : automaton.transitions[s]) {
+      if (!is_live[transition.second]) {
+        continue;
+      }
+      uint32_t target = partition[transition.second];
+      if (number[target] == DEAD) {
+        number[target] =
+            minimal.add_state(automaton.accepting[representative[target]]);
+        queue.push(target);
+      }
+      minimal.transitions[number[c]][transition.first] = number[target];
+    }
+  }
+  return minimal;
+}
+
+} // namespace rsd_impl
+
+/*
+ * An abstract domain of strings, in which an abstract value is a regular
+ * language represented by a minimal deterministic finite automaton over
+ * bytes. This is much more precise than keeping track of a prefix and a
+ * suffix, e.g., it can represent the set of strings built by a loop that
+ * appends a separator and an element at each iteration, at a higher cost.
+ *
+ * Top is the language of all strings and Bottom is the empty language. The
+ * join is the union of the languages, which is computed by determinizing the
+ * union of the automata and minimizing the result. The meet is the
+ * intersection of the languages. Since minimal automata are canonical, the
+ * equality is structural. The join is exact, hence the ascending chains may
+ * be infinite: the widening collapses to Top as soon as the automaton of the
+ * union has more than MaxStates states, which bounds the number of values
+ * that a widening sequence can go through.
+ */
+template <size_t MaxStates = 32>
+class RegularStringDomain final
+    : public AbstractDomain<RegularStringDomain<MaxStates>> {
+ public:
+  using Automaton = rsd_impl::Automaton;
+
+  /*
+   * The default constructor produces the Top value.
+   */
+  RegularStringDomain() = default;
+
+  /*
+   * The language that only contains the given string.
+   */
+  explicit RegularStringDomain(const std::string& s) : m_is_top(false) {
+    uint32_t state = m_automaton.add_state(s.empty());
+    for (size_t i = 0; i < s.size(); ++i) {
+      uint32_t next = m_automaton.add_state(i + 1 == s.size());
+      m_automaton.transitions[state][static_cast<unsigned char>(s[i])] = next;
+      state = next;
+    }
+  }
+
+  static RegularStringDomain bottom() {
+    RegularStringDomain domain;
+    domain.set_to_bottom();
+    return domain;
+  }
+
+  static RegularStringDomain top() { return RegularStringDomain(); }
+
+  bool is_bottom() const override {
+    return !m_is_top && m_automaton.size() == 0;
+  }
+
+  bool is_top() const override { return m_is_top; }
+
+  void set_to_bottom() override {
+    m_is_top = false;
+    m_automaton = Automaton();
+  }
+
+  void set_to_top() override {
+    m_is_top = true;
+    m_automaton = Automaton();
+  }
+
+  /*
+   * The minimal automaton of the language. This is not defined for Top.
+   */
+  const Automaton& automaton() const {
+    RUNTIME_CHECK(!is_top(), undefined_operation());
+    return m_automaton;
+  }
+
+  /*
+   * The number of states of the minimal automaton, not counting the implicit
+   * rejecting state. This is not defined for Top.
+   */
+  size_t num_states() const { return automaton().size(); }
+
+  bool accepts(const std::string& s) const {
+    if (is_top()) {
+      return true;
+    }
+    if (is_bottom()) {
+      return false;
+    }
+    uint32_t state = 0;
+    for (char c : s) {
+      const auto& transitions = m_automaton.transitions[state];
+      auto it = transitions.find(static_cast<unsigned char>(c));
+      if (it == transitions.end()) {
+        return false;
+      }
+      state = it->second;
+    }
+    return m_automaton.accepting[state];
+  }
+
+  bool leq(const RegularStringDomain& other) const override {
+    if (is_bottom() || other.is_top()) {
+      return true;
+    }
+    if (is_top() || other.is_bottom()) {
+      return false;
+    }
+    // Look for a string that is accepted by this automaton and not by the
+    // other one, by exploring the product of the automata.
+    constexpr uint32_t DEAD = std::numeric_limits<uint32_t>::max();
+    std::set<std::pair<uint32_t, uint32_t>> visited{{0, 0}};
+    std::vector<std::pair<uint32_t, uint32_t>> worklist{{0, 0}};
+    while (!worklist.empty()) {
+      auto pair = worklist.back();
+      worklist.pop_back();
+      if (m_automaton.accepting[pair.first] &&
+          (pair.second == DEAD || !other.m_automaton.accepting[pair.second])) {
+        return false;
+      }
+      for (const auto& transition : m_automaton.transitions[pair.first]) {
+        uint32_t target = DEAD;
+        if (pair.second != DEAD) {
+          const auto& transitions = other.m_automaton.transitions[pair.second];
+          auto it = transitions.find(transition.first);
+          if (it != transitions.end()) {
+            target = it->second;
+          }
+        }
+        std::pair<uint32_t, uint32_t> next(transition.second, target);
+        if (visited.insert(next).second) {
+          worklist.push_back(next);
+        }
+      }
+    }
+    return true;
+  }
+
+  bool equals(const RegularStringDomain& other) const override {
+    return m_is_top == other.m_is_top && m_automaton == other.m_automaton;
+  }
+
+  void join_with(const RegularStringDomain& other) override {
+    if (is_top() || other.is_bottom()) {
+      return;
+    }
+    if (other.is_top() || is_bottom()) {
+      *this = other;
+      return;
+    }
+    rsd_impl::NondeterministicAutomaton nfa;
+    uint32_t initial = nfa.add_state(/* is_accepting */ false);
+    uint32_t left = nfa.embed(m_automaton);
+    uint32_t right = nfa.embed(other.m_automaton);
+    nfa.epsilon[initial] = {left, right};
+    set_automaton(rsd_impl::minimize(rsd_impl::determinize(nfa, initial)));
+  }
+
+  void widen_with(const RegularStringDomain& other) override {
+    join_with(other);
+    if (!is_top() && num_states() > MaxStates) {
+      set_to_top();
+    }
+  }
+
+  void meet_with(const RegularStringDomain& other) override {
+    if (is_bottom() || other.is_top()) {
+      return;
+    }
+    if (is_top() || other.is_bottom()) {
+      *this = other;
+      return;
+    }
+    // The product automaton, restricted to the reachable pairs of states.
+    Automaton product;
+    std::map<std::pair<uint32_t, uint32_t>, uint32_t> states;
+    std::vector<std::pair<uint32_t, uint32_t>> worklist;
+    auto get_state = [&](const std::pair<uint32_t, uint32_t>& pair) {
+      auto it = states.find(pair);
+      if (it != states.end()) {
+        return it->second;
+      }
+      uint32_t state =
+          product.add_state(m_automaton.accepting[pair.first] &&
+                            other.m_automaton.accepting[pair.second]);
+      states.emplace(pair, state);
+      worklist.push_back(pair);
+      return state;
+    };
+    get_state({0, 0});
+    while (!worklist.empty()) {
+      auto pair = worklist.back();
+      worklist.pop_back();
+      uint32_t state = states.at(pair);
+      const auto& transitions = other.m_automaton.transitions[pair.second];
+      for (const auto& transition : m_automaton.transitions[pair.first]) {
+        auto it = transitions.find(transition.first);
+        if (it != transitions.end()) {
+          uint32_t target = get_state({transition.second, it->second});
+          product.transitions[state][transition.first] = target;
+        }
+      }
+    }
+    set_automaton(rsd_impl::minimize(product));
+  }
+
+  void narrow_with(const RegularStringDomain& other) override {
+    meet_with(other);
+  }
+
+  /*
+   * The language of the concatenations of a string of this language and a
+   * string of the other one.
+   */
+  RegularStringDomain& concatenate(const RegularStringDomain& other) {
+    if (is_bottom() || other.is_bottom()) {
+      set_to_bottom();
+      return *this;
+    }
+    rsd_impl::NondeterministicAutomaton nfa;
+    uint32_t initial = nfa.embed(is_top() ? Automaton::universal()
+                                          : m_automaton);
+    size_t num_left = nfa.accepting.size();
+    uint32_t middle = nfa.embed(other.is_top() ? Automaton::universal()
+                                               : other.m_automaton);
+    for (uint32_t s = 0; s < num_left; ++s) {
+      if (nfa.accepting[s]) {
+        nfa.accepting[s] = false;
+        nfa.epsilon[s].push_back(middle);
+      }
+    }
+    set_automaton(rsd_impl::minimize(rsd_impl::determinize(nfa, initial)));
+    return *this;
+  }
+
+ private:
+  void set_automaton(Automaton automaton) {
+    m_is_top = automaton.is_universal();
+    m_automaton = m_is_top ? Automaton() : std::move(automaton);
+  }
+
+  bool m_is_top{true};
+  Automaton m_automaton;
+};
+
+template <size_t MaxStates>
+inline std::ostream& operator<<(std::ostream& o,
+                                const RegularStringDomain<MaxStates>& s) {
+  if (s.is_bottom()) {
+    return o << "_|_";
+  }
+  if (s.is_top()) {
+    return o << "T";
+  }
+  const auto& automaton = s.automaton();
+  o << "{";
+  for (size_t state = 0; state < automaton.size(); ++state) {
+    if (state > 0) {
+      o << ", ";
+    }
+    o << state << (automaton.accepting[state] ? "*" : "") << ":";
+    for (const auto& transition : automaton.transitions[state]) {
+      o << " ";
+      unsigned char c = transition.first;
+      if (c >= 0x20 && c < 0x7f) {
+        o << c;
+      } else {
+        o << "\\x" << std::hex << std::setw(2) << std::setfill('0')
+          << static_cast<unsigned>(c) << std::dec << std::setfill(' ');
+      }
+      o << "->" << transition.second;
+    }
+  }
+  o << "}";
+  return o;
+}
+
+} // namespace sparta
@@ -0,0 +1,214 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <iterator>
+#include <ostream>
+#include <type_traits>
+#include <utility>
+#include <vector>
+
+namespace sparta {
+
+/*
+ * How the precision of an abstract value evolves from one run of an analysis
+ * to the next. A value is more precise if it is lower in the lattice.
+ */
+enum class PrecisionChange { Unchanged, Improved, Regressed, Incomparable };
+
+inline std::ostream& operator<<(std::ostream& o, PrecisionChange change) {
+  switch (change) {
+  case PrecisionChange::Unchanged: {
+    o << "unchanged";
+    break;
+  }
+  case PrecisionChange::Improved: {
+    o << "improved";
+    break;
+  }
+  case PrecisionChange::Regressed: {
+    o << "regressed";
+    break;
+  }
+  case PrecisionChange::Incomparable: {
+    o << "incomparable";
+    break;
+  }
+  }
+  return o;
+}
+
+template <typename Domain>
+PrecisionChange compare_precision(const Domain& before, const Domain& after) {
+  bool improved = after.leq(before);
+  bool regressed = before.leq(after);
+  if (improved && regressed) {
+    return PrecisionChange::Unchanged;
+  }
+  if (improved) {
+    return PrecisionChange::Improved;
+  }
+  if (regressed) {
+    return PrecisionChange::Regressed;
+  }
+  return PrecisionChange::Incomparable;
+}
+
+/*
+ * Returns the variables whose values differ between two abstract
+ * environments, such as HashedAbstractEnvironment or
+ * PatriciaTreeMapAbstractEnvironment, along with the change of precision of
+ * their values. The variables are listed in the order of the bindings of the
+ * first environment, followed by those only bound in the second one. An
+ * environment that is Top or Bottom has no bindings, hence the result is
+ * empty if either environment is.
+ */
+template <typename Environment,
+          typename Variable = std::decay_t<
+              decltype(std::declval<const Environment&>()
+                           .bindings()
+                           .begin()
+                           ->first)>>
+std::vector<std::pair<Variable, PrecisionChange>> diff_environments(
+    const Environment& before, const Environment& after) {
+  std::vector<std::pair<Variable, PrecisionChange>> result;
+  if (before.is_top() || before.is_bottom() || after.is_top() ||
+      after.is_bottom()) {
+    return result;
+  }
+  for (const auto& binding : before.bindings()) {
+    auto change = compare_precision(binding.second, after.get(binding.first));
+    if (change != PrecisionChange::Unchanged) {
+      result.emplace_back(binding.first, change);
+    }
+  }
+  for (const auto& binding : after.bindings()) {
+    if (!before.get(binding.first).is_top()) {
+      // The binding has been compared above, since only the Top values are
+      // implicit in an environment.
+      continue;
+    }
+    auto change = compare_precision(before.get(binding.first), binding.second);
+    if (change != PrecisionChange::Unchanged) {
+      result.emplace_back(binding.first, change);
+    }
+  }
+  return result;
+}
+
+/*
+ * The differences between the invariants computed by two runs of an analysis,
+ * e.g., before and after a change to its transformers (see diff_results()).
+ */
+template <typename NodeId, typename Domain>
+class ResultDiff final {
+ public:
+  struct Change {
+    NodeId node;
+    // Whether the change is at the entry or the exit of the node.
+    bool at_entry;
+    PrecisionChange change;
+    Domain before;
+    Domain after;
+  };
+
+  void record(const NodeId& node,
+              bool at_entry,
+              const Domain& before,
+              const Domain& after) {
+    auto change = compare_precision(before, after);
+    if (change == PrecisionChange::Unchanged) {
+      ++m_num_unchanged;
+      return;
+    }
+    m_changes.push_back(Change{node, at_entry, change, before, after});
+  }
+
+  /*
+   * The invariants that differ, in the order in which they were recorded.
+   */
+  const std::vector<Change>& changes() const { return m_changes; }
+
+  size_t count(PrecisionChange change) const {
+    if (change == PrecisionChange::Unchanged) {
+      return m_num_unchanged;
+    }
+    size_t n = 0;
+    for (const auto& c : m_changes) {
+      if (c.change == change) {
+        ++n;
+      }
+    }
+    return n;
+  }
+
+  /*
+   * Whether some invariant is not at least as precise as before.
+   */
+  bool has_regressions() const {
+    return count(PrecisionChange::Regressed) > 0 ||
+           count(PrecisionChange::Incomparable) > 0;
+  }
+
+  /*
+   * One line per invariant that differs, e.g.:
+   *
+   *   regressed at exit of 3: [0, 0] -> T
+   */
+  friend std::ostream& operator<<(std::ostream& o, const ResultDiff& diff) {
+    for (const auto& c : diff.m_changes) {
+      o << c.change << " at " << (c.at_entry ? "entry" : "exit") << " of "
+        << c.node << ": " << c.before << " -> " << c.after << std::endl;
+    }
+    return o;
+  }
+
+ private:
+  std::vector<Change> m_changes;
+  size_t m_num_unchanged{0};
+};
+
+/*
+ * Compares the entry and exit states computed at the given nodes by two
+ * fixpoint iterators, e.g., in order to track the precision of an analysis
+ * across changes to its transformers:
+ *
+ *   OldAnalyzer before(cfg);
+ *   NewAnalyzer after(cfg);
+ *   before.run(Domain::top());
+ *   after.run(Domain::top());
+ *   auto diff = diff_results(before, after, cfg.nodes());
+ *   EXPECT_FALSE(diff.has_regressions()) << diff;
+ */
+template <typename Before,
+          typename After,
+          typename Nodes,
+          typename NodeId = std::decay_t<decltype(
+              *std::begin(std::declval<const Nodes&>()))>,
+          typename Domain = std::decay_t<decltype(
+              std::declval<const Before&>().get_entry_state_at(
+                  std::declval<const NodeId&>()))>>
+ResultDiff<NodeId, Domain> diff_results(const Before& before,
+                                        const After& after,
+                                        const Nodes& nodes) {
+  ResultDiff<NodeId, Domain> diff;
+  for (const auto& node : nodes) {
+    diff.record(node,
+                /* at_entry */ true,
+                before.get_entry_state_at(node),
+                after.get_entry_state_at(node));
+    diff.record(node,
+                /* at_entry */ false,
+                before.get_exit_state_at(node),
+                after.get_exit_state_at(node));
+  }
+  return diff;
+}
+
+} // namespace sparta
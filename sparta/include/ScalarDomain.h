@@ -0,0 +1,305 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <boost/optional.hpp>
+#include <tuple>
+
+#include "CongruenceDomain.h"
+#include "IntervalDomain.h"
+#include "NumericalDomainOps.h"
+#include "ReducedProductAbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * The reduced product of intervals and congruences, which is a sensible
+ * default abstraction for the integer variables of a program. The two domains
+ * refine each other:
+ *
+ *   - The bounds of the interval are moved inwards to the closest values of
+ *     the congruence class, e.g., [1, 10] and 4Z+2 reduce to [2, 10].
+ *   - An interval that contains a single value makes the congruence a
+ *     constant, and conversely.
+ *   - The product is Bottom if the interval contains no value of the
+ *     congruence class, e.g., [3, 5] and 4Z+2.
+ *
+ * The reduction is performed by the constructors, the meet, the arithmetic
+ * operations and the refinements on branch conditions. It is not performed
+ * after a join, a widening or a narrowing, since it could break the
+ * termination of the latter two.
+ *
+ * As in CongruenceDomain, the values are mathematical integers and MIN
+ * (resp. MAX) is an infinite bound of the interval.
+ */
+template <typename Integer>
+class ScalarDomain final
+    : public ReducedProductAbstractDomain<ScalarDomain<Integer>,
+                                          IntervalDomain<Integer>,
+                                          CongruenceDomain<Integer>> {
+ public:
+  using Interval = IntervalDomain<Integer>;
+  using Congruence = CongruenceDomain<Integer>;
+  using ReducedProductAbstractDomain<ScalarDomain<Integer>,
+                                     Interval,
+                                     Congruence>::ReducedProductAbstractDomain;
+
+  // By default, a scalar is initialized to Top.
+  ScalarDomain() = default;
+
+  ScalarDomain(const Interval& interval, const Congruence& congruence)
+      : ScalarDomain(std::make_tuple(interval, congruence)) {}
+
+  /* implicit */ ScalarDomain(const Interval& interval)
+      : ScalarDomain(interval, Congruence::top()) {}
+
+  /* implicit */ ScalarDomain(const Congruence& congruence)
+      : ScalarDomain(Interval::top(), congruence) {}
+
+  static ScalarDomain bottom() {
+    ScalarDomain x;
+    x.set_to_bottom();
+    return x;
+  }
+
+  static ScalarDomain top() { return ScalarDomain(); }
+
+  static ScalarDomain constant(Integer value) {
+    return ScalarDomain(Congruence::constant(value));
+  }
+
+  /*
+   * The values in [lb, ub] that are congruent to residue modulo modulus.
+   */
+  static ScalarDomain finite(Integer lb,
+                             Integer ub,
+                             Integer modulus = 1,
+                             Integer residue = 0) {
+    return ScalarDomain(Interval::finite(lb, ub),
+                        Congruence::congruence(modulus, residue));
+  }
+
+  const Interval& interval() const { return this->template get<0>(); }
+
+  const Congruence& congruence() const { return this->template get<1>(); }
+
+  boost::optional<Integer> get_constant() const {
+    return congruence().get_constant();
+  }
+
+  void meet_with(const ScalarDomain& other) override {
+    ReducedProductAbstractDomain<ScalarDomain<Integer>, Interval,
+                                 Congruence>::meet_with(other);
+    this->reduce();
+  }
+
+  ScalarDomain operator-() const {
+    return ScalarDomain(-interval(), -congruence());
+  }
+
+  ScalarDomain operator+(const ScalarDomain& that) const {
+    return ScalarDomain(interval() + that.interval(),
+                        congruence() + that.congruence());
+  }
+
+  ScalarDomain operator-(const ScalarDomain& that) const {
+    return ScalarDomain(interval() - that.interval(),
+                        congruence() - that.congruence());
+  }
+
+  ScalarDomain operator*(const ScalarDomain& that) const {
+    return ScalarDomain(interval() * that.interval(),
+                        congruence() * that.congruence());
+  }
+
+  static void reduce_product(std::tuple<Interval, Congruence>& product) {
+    Interval& interval = std::get<0>(product);
+    Congruence& congruence = std::get<1>(product);
+    if (interval.is_bottom() || congruence.is_bottom()) {
+      return;
+    }
+    Integer modulus = congruence.modulus();
+    Integer residue = congruence.residue();
+    if (modulus == 0) {
+      interval.meet_with(interval_impl::range(residue, residue));
+      return;
+    }
+    Integer lb = interval.lower_bound();
+    Integer ub = interval.upper_bound();
+    // A bound is only moved if it stays finite, which is sound since the
+    // infinite bounds approximate all the values beyond them.
+    if (is_finite(lb)) {
+      Integer delta = normalize(residue - normalize(lb, modulus), modulus);
+      if (lb < Interval::MAX - delta) {
+        lb += delta;
+      }
+    }
+    if (is_finite(ub)) {
+      Integer delta = normalize(normalize(ub, modulus) - residue, modulus);
+      if (ub > Interval::MIN + delta) {
+        ub -= delta;
+      }
+    }
+    if (lb > ub) {
+      interval.set_to_bottom();
+      return;
+    }
+    interval = interval_impl::range(lb, ub);
+    if (lb == ub && is_finite(lb)) {
+      congruence = Congruence::constant(lb);
+    }
+  }
+
+ private:
+  static bool is_finite(Integer bound) {
+    return bound != Interval::MIN && bound != Interval::MAX;
+  }
+
+  // The representative of value in [0, modulus), where modulus > 0.
+  static Integer normalize(Integer value, Integer modulus) {
+    Integer r = value % modulus;
+    return r < 0 ? Integer(r + modulus) : r;
+  }
+};
+
+template <typename Integer>
+struct ArithmeticDomainOps<ScalarDomain<Integer>> {
+  using Domain = ScalarDomain<Integer>;
+  using IntervalOps = ArithmeticDomainOps<typename Domain::Interval>;
+  using CongruenceOps = ArithmeticDomainOps<typename Domain::Congruence>;
+
+  template <typename Constant>
+  static Domain literal(const Constant& value) {
+    return Domain(IntervalOps::literal(value), CongruenceOps::literal(value));
+  }
+
+  static Domain neg(const Domain& x) { return -x; }
+
+  static Domain add(const Domain& x, const Domain& y) { return x + y; }
+
+  static Domain sub(const Domain& x, const Domain& y) { return x - y; }
+
+  static Domain mul(const Domain& x, const Domain& y) { return x * y; }
+
+  static Domain div(const Domain& x, const Domain& y) {
+    return lift(x, y, IntervalOps::div, CongruenceOps::div);
+  }
+
+  static Domain bit_and(const Domain& x, const Domain& y) {
+    return lift(x, y, IntervalOps::bit_and, CongruenceOps::bit_and);
+  }
+
+  static Domain bit_or(const Domain& x, const Domain& y) {
+    return lift(x, y, IntervalOps::bit_or, CongruenceOps::bit_or);
+  }
+
+  static Domain bit_xor(const Domain& x, const Domain& y) {
+    return lift(x, y, IntervalOps::bit_xor, CongruenceOps::bit_xor);
+  }
+
+  static Domain shl(const Domain& x, const Domain& y) {
+    return lift(x, y, IntervalOps::shl, CongruenceOps::shl);
+  }
+
+  static Domain shr(const Domain& x, const Domain& y) {
+    return lift(x, y, IntervalOps::shr, CongruenceOps::shr);
+  }
+
+ private:
+  template <typename IntervalOperation, typename CongruenceOperation>
+  static Domain lift(const Domain& x,
+                     const Domain& y,
+                     IntervalOperation interval_operation,
+                     CongruenceOperation congruence_operation) {
+    if (x.is_bottom() || y.is_bottom()) {
+      return Domain::bottom();
+    }
+    return Domain(interval_operation(x.interval(), y.interval()),
+                  congruence_operation(x.congruence(), y.congruence()));
+  }
+};
+
+/*
+ * A comparison holds (resp. does not hold) if it holds (resp. does not hold)
+ * in either component.
+ */
+template <typename Integer>
+struct CompareDomainOps<ScalarDomain<Integer>>
+    : DerivedCompareDomainOps<CompareDomainOps<ScalarDomain<Integer>>,
+                              ScalarDomain<Integer>> {
+  using Domain = ScalarDomain<Integer>;
+  using IntervalOps = CompareDomainOps<typename Domain::Interval>;
+  using CongruenceOps = CompareDomainOps<typename Domain::Congruence>;
+
+  static BooleanDomain eq(const Domain& x, const Domain& y) {
+    return IntervalOps::eq(x.interval(), y.interval())
+        .meet(CongruenceOps::eq(x.congruence(), y.congruence()));
+  }
+
+  static BooleanDomain lt(const Domain& x, const Domain& y) {
+    return IntervalOps::lt(x.interval(), y.interval())
+        .meet(CongruenceOps::lt(x.congruence(), y.congruence()));
+  }
+
+  static BooleanDomain le(const Domain& x, const Domain& y) {
+    return IntervalOps::le(x.interval(), y.interval())
+        .meet(CongruenceOps::le(x.congruence(), y.congruence()));
+  }
+};
+
+/*
+ * Refinement of scalars on a branch condition, which refines the intervals
+ * as in IntervalDomain.h and then reduces the products. The refinements of
+ * abstract environments in IntervalDomain.h also apply to environments that
+ * map variables to scalars.
+ */
+namespace scalar_impl {
+
+template <typename Integer, typename Refine>
+void refine(ScalarDomain<Integer>* x,
+            ScalarDomain<Integer>* y,
+            Refine&& refine_intervals) {
+  auto x_interval = x->interval();
+  auto y_interval = y->interval();
+  refine_intervals(&x_interval, &y_interval);
+  *x = ScalarDomain<Integer>(x_interval, x->congruence());
+  *y = ScalarDomain<Integer>(y_interval, y->congruence());
+  if (x->is_bottom() || y->is_bottom()) {
+    x->set_to_bottom();
+    y->set_to_bottom();
+  }
+}
+
+} // namespace scalar_impl
+
+/* x <= y */
+template <typename Integer>
+void refine_le(ScalarDomain<Integer>* x, ScalarDomain<Integer>* y) {
+  scalar_impl::refine(x, y, [](auto* a, auto* b) { refine_le(a, b); });
+}
+
+/* x < y */
+template <typename Integer>
+void refine_lt(ScalarDomain<Integer>* x, ScalarDomain<Integer>* y) {
+  scalar_impl::refine(x, y, [](auto* a, auto* b) { refine_lt(a, b); });
+}
+
+/* x == y */
+template <typename Integer>
+void refine_eq(ScalarDomain<Integer>* x, ScalarDomain<Integer>* y) {
+  x->meet_with(*y);
+  *y = *x;
+}
+
+/* x != y */
+template <typename Integer>
+void refine_ne(ScalarDomain<Integer>* x, ScalarDomain<Integer>* y) {
+  scalar_impl::refine(x, y, [](auto* a, auto* b) { refine_ne(a, b); });
+}
+
+} // namespace sparta
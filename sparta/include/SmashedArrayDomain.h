@@ -0,0 +1,200 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstdint>
+#include <ostream>
+#include <utility>
+
+#include "AbstractDomain.h"
+#include "IntervalDomain.h"
+
+namespace sparta {
+
+/*
+ * An abstraction of arrays (or any indexed collection) in which all the
+ * elements are smashed into a single abstract value, paired with an interval
+ * of the possible lengths of the array:
+ *
+ *   (elements, length)
+ *
+ * denotes the arrays whose length belongs to `length` and whose elements all
+ * belong to `elements`. Since the individual elements are not distinguished,
+ * a write to an unknown index can only be a weak update, which joins the
+ * written value into the smashed value. A read from an unknown index returns
+ * the smashed value.
+ *
+ * Bottom denotes no array at all, e.g., after an out-of-bounds access, and is
+ * represented by a Bottom length. The empty array is the only array whose
+ * smashed value is Bottom. Top denotes any array, of any length. The values
+ * are normalized after each operation, so that a length is never negative
+ * and the smashed value of an empty array is always Bottom.
+ */
+template <typename Domain>
+class SmashedArrayDomain final
+    : public AbstractDomain<SmashedArrayDomain<Domain>> {
+ public:
+  using Length = IntervalDomain<int64_t>;
+
+  /*
+   * The default constructor produces the Top value.
+   */
+  SmashedArrayDomain()
+      : m_elements(Domain::top()), m_length(Length::bounded_below(0)) {}
+
+  SmashedArrayDomain(Domain elements, Length length)
+      : m_elements(std::move(elements)), m_length(std::move(length)) {
+    normalize();
+  }
+
+  static SmashedArrayDomain bottom() {
+    return SmashedArrayDomain(Domain::bottom(), Length::bottom());
+  }
+
+  static SmashedArrayDomain top() { return SmashedArrayDomain(); }
+
+  /*
+   * The array that is known to be empty.
+   */
+  static SmashedArrayDomain empty() {
+    return SmashedArrayDomain(Domain::bottom(), Length::finite(0, 0));
+  }
+
+  bool is_bottom() const override { return m_length.is_bottom(); }
+
+  bool is_top() const override {
+    return m_elements.is_top() && m_length.equals(Length::bounded_below(0));
+  }
+
+  void set_to_bottom() override {
+    m_elements.set_to_bottom();
+    m_length.set_to_bottom();
+  }
+
+  void set_to_top() override {
+    m_elements.set_to_top();
+    m_length = Length::bounded_below(0);
+  }
+
+  /*
+   * The join of all the elements, which is Bottom for the empty array.
+   */
+  const Domain& elements() const { return m_elements; }
+
+  const Length& length() const { return m_length; }
+
+  /*
+   * Reads the element at an unknown index. The result is Bottom if the array
+   * is empty, since any read is then out of bounds.
+   */
+  Domain read_any() const { return m_elements; }
+
+  /*
+   * Writes a value at an unknown index. If the write doesn't fail, the array
+   * can't be empty. The update is strong when the array is known to have a
+   * single element and weak otherwise. Writing Bottom, or writing into an
+   * array that is known to be empty, makes the array Bottom.
+   */
+  void write_any(const Domain& value) {
+    if (is_bottom()) {
+      return;
+    }
+    if (value.is_bottom()) {
+      set_to_bottom();
+      return;
+    }
+    m_length.meet_with(Length::bounded_below(1));
+    if (m_length.equals(Length::finite(1, 1))) {
+      m_elements = value;
+    } else {
+      m_elements.join_with(value);
+    }
+    normalize();
+  }
+
+  /*
+   * Adds an element at the end of the array.
+   */
+  void append(const Domain& value) {
+    if (is_bottom()) {
+      return;
+    }
+    if (value.is_bottom()) {
+      set_to_bottom();
+      return;
+    }
+    m_elements.join_with(value);
+    m_length += 1;
+  }
+
+  bool leq(const SmashedArrayDomain& other) const override {
+    if (is_bottom()) {
+      return true;
+    }
+    if (other.is_bottom()) {
+      return false;
+    }
+    return m_elements.leq(other.m_elements) && m_length.leq(other.m_length);
+  }
+
+  bool equals(const SmashedArrayDomain& other) const override {
+    return m_elements.equals(other.m_elements) &&
+           m_length.equals(other.m_length);
+  }
+
+  void join_with(const SmashedArrayDomain& other) override {
+    m_elements.join_with(other.m_elements);
+    m_length.join_with(other.m_length);
+  }
+
+  void widen_with(const SmashedArrayDomain& other) override {
+    m_elements.widen_with(other.m_elements);
+    m_length.widen_with(other.m_length);
+    normalize();
+  }
+
+  void meet_with(const SmashedArrayDomain& other) override {
+    m_elements.meet_with(other.m_elements);
+    m_length.meet_with(other.m_length);
+    normalize();
+  }
+
+  void narrow_with(const SmashedArrayDomain& other) override {
+    m_elements.narrow_with(other.m_elements);
+    m_length.narrow_with(other.m_length);
+    normalize();
+  }
+
+  friend std::ostream& operator<<(std::ostream& o,
+                                  const SmashedArrayDomain& array) {
+    if (array.is_bottom()) {
+      return o << "_|_";
+    }
+    if (array.is_top()) {
+      return o << "T";
+    }
+    return o << "{" << array.m_elements << "}^" << array.m_length;
+  }
+
+ private:
+  void normalize() {
+    m_length.meet_with(Length::bounded_below(0));
+    if (m_elements.is_bottom()) {
+      // An array without any possible element is empty.
+      m_length.meet_with(Length::finite(0, 0));
+    }
+    if (m_length.is_bottom() || m_length.equals(Length::finite(0, 0))) {
+      m_elements.set_to_bottom();
+    }
+  }
+
+  Domain m_elements;
+  Length m_length;
+};
+
+} // namespace sparta
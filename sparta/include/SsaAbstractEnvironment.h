@@ -0,0 +1,286 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <functional>
+#include <initializer_list>
+#include <ostream>
+#include <utility>
+
+#include "AbstractDomain.h"
+#include "DefaultBinding.h"
+#include "Exceptions.h"
+#include "PatriciaTreeMap.h"
+#include "PatriciaTreeSet.h"
+
+namespace sparta {
+
+/*
+ * An abstract environment specialized for intermediate representations in
+ * Static Single Assignment (SSA) form.
+ *
+ * In SSA form, every variable has a unique definition, which dominates all
+ * the uses of the variable. Hence, at a control-flow merge, a variable that is
+ * defined along all incoming paths holds the same abstract value on each of
+ * them, and a variable that is defined along some of the paths only cannot be
+ * used after the merge. The only values that change are those of the phi
+ * nodes. The join of two environments is thus the union of their bindings,
+ * and only the variables bound by a phi node are actually joined. This avoids
+ * the cost of the generic per-variable join of HashedAbstractEnvironment and
+ * PatriciaTreeMapAbstractEnvironment.
+ *
+ * The phi nodes are supplied by the transformer: when analyzing the edge from
+ * a predecessor to a merge point, the transformer binds the destination of
+ * each phi node to the value of the corresponding operand using set_phi().
+ * Once the merge point has been analyzed, the transformer can call
+ * clear_phis(), so that the subsequent joins skip these variables. The phi
+ * markers are not part of the abstract value, i.e., they are ignored by leq()
+ * and equals().
+ *
+ * An unbound variable is undefined and implicitly bound to Bottom. Contrary to
+ * the other abstract environments, an environment in which no variable is
+ * defined (e.g., the default-constructed environment) is not Bottom, which
+ * denotes an unreachable program point.
+ *
+ * Note that clients must not refine the value of a variable that is not bound
+ * by a phi node along an edge (e.g., according to a branch condition), since
+ * the join would keep either of the incoming values. A refinement should
+ * instead be expressed by a new variable, as in the SSI form.
+ */
+template <typename Variable, typename Domain>
+class SsaAbstractEnvironment final
+    : public AbstractDomain<SsaAbstractEnvironment<Variable, Domain>> {
+ public:
+  using DefaultBinding = DefaultIsBottom;
+
+  struct ValueInterface {
+    using type = Domain;
+
+    static type default_value() { return type::bottom(); }
+
+    static bool is_default_value(const type& x) { return x.is_bottom(); }
+
+    static bool equals(const type& x, const type& y) { return x.equals(y); }
+
+    static bool leq(const type& x, const type& y) { return x.leq(y); }
+  };
+
+  using MapType = PatriciaTreeMap<Variable, Domain, ValueInterface>;
+  using VariableSet = PatriciaTreeSet<Variable>;
+
+  /*
+   * The default constructor produces an environment in which no variable is
+   * defined.
+   */
+  SsaAbstractEnvironment() = default;
+
+  SsaAbstractEnvironment(
+      std::initializer_list<std::pair<Variable, Domain>> l) {
+    for (const auto& p : l) {
+      set(p.first, p.second);
+    }
+  }
+
+  /*
+   * Number of defined variables. This operation is not defined if the
+   * environment is set to Top.
+   */
+  size_t size() const {
+    RUNTIME_CHECK(!is_top(), undefined_operation());
+    return m_map.size();
+  }
+
+  /*
+   * This operation is not defined if the environment is set to Top.
+   */
+  const MapType& bindings() const {
+    RUNTIME_CHECK(!is_top(), undefined_operation());
+    return m_map;
+  }
+
+  /*
+   * The variables bound by a phi node since the last call to clear_phis().
+   */
+  const VariableSet& phis() const { return m_phis; }
+
+  const Domain& get(const Variable& variable) const {
+    if (is_top()) {
+      static const Domain top = Domain::top();
+      return top;
+    }
+    return m_map.at(variable);
+  }
+
+  /*
+   * Binds the variable defined by a regular instruction. This is a no-op if the
+   * environment is Bottom or Top.
+   */
+  SsaAbstractEnvironment& set(const Variable& variable, const Domain& value) {
+    if (is_bottom() || is_top()) {
+      return *this;
+    }
+    m_map.insert_or_assign(variable, value);
+    return *this;
+  }
+
+  /*
+   * Binds the destination of a phi node to the value of the operand that
+   * corresponds to the incoming edge. This is a no-op if the environment is
+   * Bottom or Top.
+   */
+  SsaAbstractEnvironment& set_phi(const Variable& variable,
+                                  const Domain& value) {
+    if (is_bottom() || is_top()) {
+      return *this;
+    }
+    m_map.insert_or_assign(variable, value);
+    m_phis.insert(variable);
+    return *this;
+  }
+
+  SsaAbstractEnvironment& clear_phis() {
+    m_phis.clear();
+    return *this;
+  }
+
+  bool is_bottom() const override { return m_is_bottom; }
+
+  bool is_top() const override { return m_is_top; }
+
+  void set_to_bottom() override {
+    m_map.clear();
+    m_phis.clear();
+    m_is_bottom = true;
+    m_is_top = false;
+  }
+
+  void set_to_top() override {
+    m_map.clear();
+    m_phis.clear();
+    m_is_bottom = false;
+    m_is_top = true;
+  }
+
+  bool leq(const SsaAbstractEnvironment& other) const override {
+    if (is_bottom() || other.is_top()) {
+      return true;
+    }
+    if (other.is_bottom() || is_top()) {
+      return false;
+    }
+    return m_map.leq(other.m_map);
+  }
+
+  bool equals(const SsaAbstractEnvironment& other) const override {
+    return m_is_bottom == other.m_is_bottom && m_is_top == other.m_is_top &&
+           m_map.equals(other.m_map);
+  }
+
+  void join_with(const SsaAbstractEnvironment& other) override {
+    join_like_operation(
+        other, [](const Domain& x, const Domain& y) { return x.join(y); });
+  }
+
+  void widen_with(const SsaAbstractEnvironment& other) override {
+    join_like_operation(
+        other, [](const Domain& x, const Domain& y) { return x.widening(y); });
+  }
+
+  void meet_with(const SsaAbstractEnvironment& other) override {
+    meet_like_operation(
+        other, [](const Domain& x, const Domain& y) { return x.meet(y); });
+  }
+
+  void narrow_with(const SsaAbstractEnvironment& other) override {
+    meet_like_operation(
+        other, [](const Domain& x, const Domain& y) { return x.narrowing(y); });
+  }
+
+  static SsaAbstractEnvironment bottom() {
+    SsaAbstractEnvironment env;
+    env.set_to_bottom();
+    return env;
+  }
+
+  static SsaAbstractEnvironment top() {
+    SsaAbstractEnvironment env;
+    env.set_to_top();
+    return env;
+  }
+
+ private:
+  void join_like_operation(
+      const SsaAbstractEnvironment& other,
+      std::function<Domain(const Domain&, const Domain&)> operation) {
+    if (is_top() || other.is_bottom()) {
+      return;
+    }
+    if (is_bottom()) {
+      *this = other;
+      return;
+    }
+    if (other.is_top()) {
+      set_to_top();
+      return;
+    }
+    m_phis.union_with(other.m_phis);
+    // The maps share most of their structure in practice, since they derive
+    // from the environment at the immediate dominator of the merge point.
+    // Note that the combining function is also applied to the default value
+    // (Bottom) of the variables that are only defined in one of the maps.
+    MapType original = m_map;
+    m_map.union_with(
+        [](const Domain& x, const Domain& y) { return x.is_bottom() ? y : x; },
+        other.m_map);
+    for (const auto& variable : m_phis) {
+      m_map.insert_or_assign(
+          variable, operation(original.at(variable), other.m_map.at(variable)));
+    }
+  }
+
+  void meet_like_operation(
+      const SsaAbstractEnvironment& other,
+      std::function<Domain(const Domain&, const Domain&)> operation) {
+    if (is_bottom() || other.is_top()) {
+      return;
+    }
+    if (is_top()) {
+      *this = other;
+      return;
+    }
+    if (other.is_bottom()) {
+      set_to_bottom();
+      return;
+    }
+    m_phis.union_with(other.m_phis);
+    m_map.intersection_with(operation, other.m_map);
+  }
+
+  MapType m_map;
+  VariableSet m_phis;
+  bool m_is_bottom{false};
+  bool m_is_top{false};
+};
+
+} // namespace sparta
+
+template <typename Variable, typename Domain>
+inline std::ostream& operator<<(
+    std::ostream& o,
+    const typename sparta::SsaAbstractEnvironment<Variable, Domain>& env) {
+  if (env.is_bottom()) {
+    o << "_|_";
+  } else if (env.is_top()) {
+    o << "T";
+  } else {
+    o << "[#" << env.size() << "]";
+    o << env.bindings();
+  }
+  return o;
+}
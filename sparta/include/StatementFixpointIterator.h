@@ -0,0 +1,158 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <functional>
+#include <vector>
+
+#include "MonotonicFixpointIterator.h"
+#include "NodeMetadata.h"
+
+namespace sparta {
+
+/*
+ * Receives the state after each statement of a node from the transformer of a
+ * StatementFixpointIterator (see below).
+ */
+template <typename Domain>
+class StatementSink {
+ public:
+  virtual ~StatementSink() = default;
+
+  /*
+   * Called by the transformer right after the transfer function of the
+   * statement with the given index in the node has been applied.
+   */
+  virtual void emit(size_t statement, const Domain& state) = 0;
+};
+
+/*
+ * A fixpoint iterator for graphs whose nodes iterate over their own
+ * statements, e.g., the basic blocks of a control-flow graph, which gives
+ * access to the state after each statement without an instruction-level
+ * graph. The transformer to implement is analyze_statements() instead of
+ * analyze_node(), and it reports the state after each statement to a sink:
+ *
+ *   class Analyzer final
+ *       : public StatementFixpointIterator<MonotonicFixpointIterator,
+ *                                          CFG,
+ *                                          Environment> {
+ *    public:
+ *     using StatementFixpointIterator::StatementFixpointIterator;
+ *
+ *     void analyze_statements(
+ *         const NodeId& block,
+ *         Environment* env,
+ *         StatementSink<Environment>* sink) const override {
+ *       size_t i = 0;
+ *       for (const auto& insn : block->instructions()) {
+ *         analyze_instruction(insn, env);
+ *         sink->emit(i++, *env);
+ *       }
+ *     }
+ *   };
+ *
+ * The states emitted are discarded unless the capture is enabled (see
+ * set_statement_capture), in which case the iterator records the states
+ * emitted during the last analysis of each node, which are consistent with
+ * its exit state. The iterator can be any of the monotonic fixpoint
+ * iterators.
+ */
+template <template <typename, typename, typename> class Iterator,
+          typename GraphInterface,
+          typename Domain,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+class StatementFixpointIterator
+    : public Iterator<GraphInterface, Domain, NodeHash> {
+ public:
+  using Base = Iterator<GraphInterface, Domain, NodeHash>;
+  using NodeId = typename GraphInterface::NodeId;
+
+  using Base::Base;
+
+  /*
+   * The transformer of a node, which must call sink->emit() after the
+   * transfer function of each statement. The statements may be emitted in
+   * any order, e.g., backwards in a backward analysis.
+   */
+  virtual void analyze_statements(const NodeId& node,
+                                  Domain* current_state,
+                                  StatementSink<Domain>* sink) const = 0;
+
+  void analyze_node(const NodeId& node, Domain* current_state) const final {
+    if (!m_capture) {
+      DiscardingSink sink;
+      analyze_statements(node, current_state, &sink);
+      return;
+    }
+    CapturingSink sink(&m_statement_states.at(node));
+    analyze_statements(node, current_state, &sink);
+  }
+
+  /*
+   * Records the states emitted by the transformer in the subsequent runs.
+   */
+  void set_statement_capture(bool capture) {
+    if (capture && !m_attached) {
+      this->attach_metadata(&m_statement_states);
+      m_attached = true;
+    }
+    m_capture = capture;
+    m_statement_states.clear();
+  }
+
+  /*
+   * Returns the states emitted during the last analysis of a node, indexed
+   * by statement, or nullptr if the node was not analyzed or the capture is
+   * disabled. The states of the statements that have not been emitted are
+   * Bottom.
+   */
+  const std::vector<Domain>* get_statement_states(const NodeId& node) const {
+    return m_statement_states.get(node);
+  }
+
+  /*
+   * Returns the state after a statement of a node, or Bottom if it has not
+   * been captured.
+   */
+  Domain get_state_after_statement(const NodeId& node,
+                                   size_t statement) const {
+    const auto* states = get_statement_states(node);
+    return states == nullptr || statement >= states->size()
+               ? Domain::bottom()
+               : (*states)[statement];
+  }
+
+ private:
+  class DiscardingSink final : public StatementSink<Domain> {
+   public:
+    void emit(size_t, const Domain&) override {}
+  };
+
+  class CapturingSink final : public StatementSink<Domain> {
+   public:
+    explicit CapturingSink(std::vector<Domain>* states) : m_states(states) {}
+
+    void emit(size_t statement, const Domain& state) override {
+      if (statement >= m_states->size()) {
+        m_states->resize(statement + 1, Domain::bottom());
+      }
+      (*m_states)[statement] = state;
+    }
+
+   private:
+    std::vector<Domain>* m_states;
+  };
+
+  bool m_capture{false};
+  bool m_attached{false};
+  NodeMetadata<NodeId, std::vector<Domain>, NodeHash> m_statement_states;
+};
+
+} // namespace sparta
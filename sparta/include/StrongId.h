@@ -0,0 +1,90 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <ostream>
+#include <type_traits>
+
+#include "PatriciaTreeUtil.h"
+
+namespace sparta {
+
+/*
+ * A strongly-typed identifier wrapping an unsigned integer. Distinct tags
+ * yield distinct types, which prevents mixing up the identifiers of different
+ * kinds of entities (e.g., basic blocks and instructions), while retaining
+ * the efficiency of plain integers:
+ *
+ *   using BlockId = StrongId<struct BlockIdTag>;
+ *
+ * A strong identifier provides everything that is required from the NodeId
+ * type of a graph interface (see FixpointIterator.h), i.e., copy, equality
+ * and hashing. It can also be used as the key of Patricia tree-based
+ * containers and abstract environments.
+ */
+template <typename Tag, typename IntegerType = uint32_t>
+class StrongId final {
+ public:
+  static_assert(std::is_unsigned<IntegerType>::value,
+                "IntegerType is not an unsigned arithmetic type");
+
+  using integer_type = IntegerType;
+
+  constexpr StrongId() = default;
+
+  constexpr explicit StrongId(IntegerType value) : m_value(value) {}
+
+  constexpr IntegerType value() const { return m_value; }
+
+  friend constexpr bool operator==(StrongId x, StrongId y) {
+    return x.m_value == y.m_value;
+  }
+
+  friend constexpr bool operator!=(StrongId x, StrongId y) {
+    return x.m_value != y.m_value;
+  }
+
+  friend constexpr bool operator<(StrongId x, StrongId y) {
+    return x.m_value < y.m_value;
+  }
+
+  friend std::ostream& operator<<(std::ostream& o, StrongId id) {
+    o << id.m_value;
+    return o;
+  }
+
+ private:
+  IntegerType m_value{0};
+};
+
+template <typename Tag, typename Integer>
+struct PatriciaTreeKeyTraits<StrongId<Tag, Integer>> {
+  using Key = StrongId<Tag, Integer>;
+  using IntegerType = Integer;
+  static_assert(sizeof(Key) == sizeof(IntegerType),
+                "StrongId must have the same representation as IntegerType");
+
+  static IntegerType encode(Key x) { return x.value(); }
+  static Key decode(IntegerType x) { return Key(x); }
+};
+
+} // namespace sparta
+
+namespace std {
+
+template <typename Tag, typename IntegerType>
+struct hash<sparta::StrongId<Tag, IntegerType>> {
+  size_t operator()(sparta::StrongId<Tag, IntegerType> id) const {
+    return std::hash<IntegerType>()(id.value());
+  }
+};
+
+} // namespace std
@@ -0,0 +1,207 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <unordered_map>
+#include <vector>
+
+namespace sparta {
+
+/*
+ * Most intermediate representations only record the successors of a basic
+ * block, typically in its terminator instruction, whereas the fixpoint
+ * iterators also need the predecessors of each node as well as edge
+ * identifiers (see FixpointIterator.h). This class materializes these from a
+ * description of the blocks that should have the following layout:
+ *
+ * struct BlockTraits {
+ *   using Graph = ...; // E.g., a function of the IR.
+ *   using Block = ...; // E.g., a pointer to a basic block.
+ *
+ *   static Block entry(const Graph& graph) { ... }
+ *
+ *   // Blocks is an arbitrary iterable collection of blocks. A block that
+ *   // occurs several times is the target of as many distinct edges, e.g.,
+ *   // for the cases of a switch that branch to the same block.
+ *   static Blocks successors(const Graph& graph, const Block& block) { ... }
+ * };
+ *
+ * Block must be copyable, equality comparable and hashable. Only the blocks
+ * that are reachable from the entry are part of the graph. The edges are
+ * identified by indices, and the successors of a block are listed in the order
+ * given by BlockTraits::successors(). The graph is then analyzed through
+ * SuccessorGraphInterface below:
+ *
+ *   using CFG = SuccessorGraph<MyBlockTraits>;
+ *
+ *   class MyAnalyzer final
+ *       : public MonotonicFixpointIterator<SuccessorGraphInterface<CFG>,
+ *                                          MyDomain> {
+ *     ...
+ *   };
+ *
+ *   CFG cfg(function);
+ *   MyAnalyzer analyzer(cfg);
+ *
+ * The fixpoint iterators keep a reference to the graph, which must outlive
+ * them. Changes to the original graph are not reflected in this one.
+ */
+template <typename BlockTraits,
+          typename BlockHash = std::hash<typename BlockTraits::Block>>
+class SuccessorGraph final {
+ public:
+  using OriginalGraph = typename BlockTraits::Graph;
+  using NodeId = typename BlockTraits::Block;
+  using EdgeId = uint32_t;
+
+  explicit SuccessorGraph(const OriginalGraph& graph)
+      : m_entry(BlockTraits::entry(graph)) {
+    index_of(m_entry);
+    // The blocks are numbered in the order in which they are discovered by a
+    // breadth-first traversal.
+    for (uint32_t i = 0; i < m_blocks.size(); ++i) {
+      NodeId block = m_blocks[i];
+      for (const auto& successor : BlockTraits::successors(graph, block)) {
+        uint32_t target = index_of(successor);
+        EdgeId edge = m_edges.size();
+        m_edges.push_back(
+            Edge{i, target,
+                 static_cast<uint32_t>(m_successors[i].size())});
+        m_successors[i].push_back(edge);
+        m_predecessors[target].push_back(edge);
+      }
+    }
+  }
+
+  NodeId entry() const { return m_entry; }
+
+  /*
+   * The number of blocks reachable from the entry.
+   */
+  size_t size() const { return m_blocks.size(); }
+
+  /*
+   * The blocks reachable from the entry, in breadth-first order.
+   */
+  const std::vector<NodeId>& blocks() const { return m_blocks; }
+
+  bool contains(const NodeId& block) const {
+    return m_indices.count(block) > 0;
+  }
+
+  const std::vector<EdgeId>& successors(const NodeId& block) const {
+    auto it = m_indices.find(block);
+    return it == m_indices.end() ? no_edges() : m_successors[it->second];
+  }
+
+  const std::vector<EdgeId>& predecessors(const NodeId& block) const {
+    auto it = m_indices.find(block);
+    return it == m_indices.end() ? no_edges() : m_predecessors[it->second];
+  }
+
+  const NodeId& source(const EdgeId& edge) const {
+    return m_blocks[m_edges[edge].source];
+  }
+
+  const NodeId& target(const EdgeId& edge) const {
+    return m_blocks[m_edges[edge].target];
+  }
+
+  /*
+   * The position of the edge among the successors of its source, e.g., to
+   * tell the taken branch of a conditional from the fall-through one.
+   */
+  uint32_t successor_index(const EdgeId& edge) const {
+    return m_edges[edge].successor_index;
+  }
+
+ private:
+  struct Edge {
+    uint32_t source;
+    uint32_t target;
+    uint32_t successor_index;
+  };
+
+  static const std::vector<EdgeId>& no_edges() {
+    static const std::vector<EdgeId> edges;
+    return edges;
+  }
+
+  uint32_t index_of(const NodeId& block) {
+    auto it = m_indices.find(block);
+    if (it != m_indices.end()) {
+      return it->second;
+    }
+    uint32_t index = m_blocks.size();
+    m_indices.emplace(block, index);
+    m_blocks.push_back(block);
+    m_successors.emplace_back();
+    m_predecessors.emplace_back();
+    return index;
+  }
+
+  NodeId m_entry;
+  std::vector<NodeId> m_blocks;
+  std::vector<Edge> m_edges;
+  std::vector<std::vector<EdgeId>> m_successors;
+  std::vector<std::vector<EdgeId>> m_predecessors;
+  std::unordered_map<NodeId, uint32_t, BlockHash> m_indices;
+};
+
+/*
+ * The interface to a SuccessorGraph, as required by the fixpoint iterators.
+ */
+template <typename SuccessorGraph>
+class SuccessorGraphInterface {
+ public:
+  using Graph = SuccessorGraph;
+  using NodeId = typename Graph::NodeId;
+  using EdgeId = typename Graph::EdgeId;
+
+  static NodeId entry(const Graph& graph) { return graph.entry(); }
+  static const std::vector<EdgeId>& predecessors(const Graph& graph,
+                                                 const NodeId& node) {
+    return graph.predecessors(node);
+  }
+  static const std::vector<EdgeId>& successors(const Graph& graph,
+                                               const NodeId& node) {
+    return graph.successors(node);
+  }
+  static NodeId source(const Graph& graph, const EdgeId& edge) {
+    return graph.source(edge);
+  }
+  static NodeId target(const Graph& graph, const EdgeId& edge) {
+    return graph.target(edge);
+  }
+  static size_t size_hint(const Graph& graph) { return graph.size(); }
+};
+
+/*
+ * The block traits of a graph given as adjacency lists, where the successors
+ * of the node i are graph[i] and the entry is the node 0. This is the
+ * quickest way to run an analysis on a small hand-written graph:
+ *
+ *   using CFG = SuccessorGraph<AdjacencyListBlocks>;
+ *   CFG cfg({{1}, {2, 3}, {1}, {}});
+ */
+struct AdjacencyListBlocks {
+  using Graph = std::vector<std::vector<uint32_t>>;
+  using Block = uint32_t;
+
+  static Block entry(const Graph&) { return 0; }
+
+  static const std::vector<uint32_t>& successors(const Graph& graph,
+                                                 const Block& block) {
+    return graph[block];
+  }
+};
+
+} // namespace sparta
@@ -0,0 +1,344 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <functional>
+#include <list>
+#include <memory>
+#include <mutex>
+#include <unordered_map>
+#include <utility>
+#include <vector>
+
+#include "Exceptions.h"
+
+namespace sparta {
+
+/*
+ * The counters of a SummaryCache since its creation.
+ */
+struct SummaryCacheStats {
+  // The lookups that found a summary in the cache.
+  size_t hits = 0;
+  // The lookups that found an evicted summary that was still referenced
+  // elsewhere, which is then restored into the cache.
+  size_t recoveries = 0;
+  // The lookups that didn't find a summary, which then has to be recomputed.
+  size_t misses = 0;
+  size_t evictions = 0;
+};
+
+/*
+ * A bounded cache of function summaries, meant to back the registry of an
+ * interprocedural analysis (see Analyzer.h) on programs whose summaries don't
+ * all fit in memory at once. When the total weight of the summaries exceeds
+ * the capacity, the least recently used summaries are evicted, and they are
+ * recomputed the next time they are requested:
+ *
+ *   class Registry : public AbstractRegistry {
+ *    public:
+ *     Summary get(const Function& f) {
+ *       return *m_cache.get_or_compute(f, [](const Function& f) {
+ *         return analyze_again(f);
+ *       });
+ *     }
+ *     ...
+ *    private:
+ *     SummaryCache<Function, Summary> m_cache{100000};
+ *   };
+ *
+ * By default, every summary weighs 1, i.e., the capacity is a number of
+ * summaries. A custom weigher can bound the memory instead, e.g.,
+ * `size_hint_of<Summary>` defined in MemoryCeiling.h.
+ *
+ * The summaries of the functions that are being analyzed, e.g., the members
+ * of the strongly connected component of the call graph that is currently
+ * iterated upon, must be pinned: a pinned summary is never evicted, since
+ * recomputing it would restart the iteration of the component. This means
+ * that the total weight may exceed the capacity while many summaries are
+ * pinned.
+ *
+ * The cache hands out shared pointers to immutable summaries, so that a
+ * summary remains valid while it is used even if it is evicted in the
+ * meantime. The cache keeps weak references to the evicted summaries, and
+ * restores those that are still alive instead of recomputing them.
+ *
+ * All the operations are thread-safe.
+ */
+template <typename Function,
+          typename Summary,
+          typename FunctionHash = std::hash<Function>>
+class SummaryCache final {
+ public:
+  using SummaryPtr = std::shared_ptr<const Summary>;
+  using Weigher = std::function<size_t(const Summary&)>;
+
+  /*
+   * A scope in which a set of functions is pinned, e.g., the members of a
+   * strongly connected component during its analysis.
+   */
+  class PinScope final {
+   public:
+    PinScope(SummaryCache* cache, std::vector<Function> functions)
+        : m_cache(cache), m_functions(std::move(functions)) {
+      for (const auto& function : m_functions) {
+        m_cache->pin(function);
+      }
+    }
+
+    PinScope(const PinScope&) = delete;
+
+    PinScope& operator=(const PinScope&) = delete;
+
+    ~PinScope() {
+      for (const auto& function : m_functions) {
+        m_cache->unpin(function);
+      }
+    }
+
+   private:
+    SummaryCache* m_cache;
+    std::vector<Function> m_functions;
+  };
+
+  explicit SummaryCache(
+      size_t capacity, Weigher weigher = [](const Summary&) { return 1; })
+      : m_capacity(capacity), m_weigher(std::move(weigher)) {}
+
+  SummaryCache(const SummaryCache&) = delete;
+
+  SummaryCache& operator=(const SummaryCache&) = delete;
+
+  size_t capacity() const { return m_capacity; }
+
+  /*
+   * The number of summaries held by the cache.
+   */
+  size_t size() const {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    return m_entries.size();
+  }
+
+  /*
+   * The total weight of the summaries held by the cache.
+   */
+  size_t weight() const {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    return m_weight;
+  }
+
+  SummaryCacheStats stats() const {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    return m_stats;
+  }
+
+  /*
+   * Returns the summary of the function, or nullptr if it has never been
+   * stored or if it has been evicted and is no longer referenced.
+   */
+  SummaryPtr get(const Function& function) {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    return lookup(function);
+  }
+
+  /*
+   * Returns the summary of the function, which is computed and stored if it
+   * isn't available. The computation happens outside of the lock, hence
+   * concurrent requests for the same missing summary may compute it more than
+   * once, in which case the first result stored wins.
+   */
+  SummaryPtr get_or_compute(
+      const Function& function,
+      const std::function<Summary(const Function&)>& compute) {
+    {
+      std::lock_guard<std::mutex> guard(m_mutex);
+      SummaryPtr summary = lookup(function);
+      if (summary != nullptr) {
+        return summary;
+      }
+    }
+    auto summary = std::make_shared<const Summary>(compute(function));
+    std::lock_guard<std::mutex> guard(m_mutex);
+    auto it = m_entries.find(function);
+    if (it != m_entries.end()) {
+      return it->second.summary;
+    }
+    insert(function, summary);
+    return summary;
+  }
+
+  /*
+   * Stores the summary of the function, replacing the previous one if any.
+   */
+  SummaryPtr put(const Function& function, Summary summary) {
+    auto ptr = std::make_shared<const Summary>(std::move(summary));
+    std::lock_guard<std::mutex> guard(m_mutex);
+    erase_entry(function);
+    insert(function, ptr);
+    return ptr;
+  }
+
+  void erase(const Function& function) {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    erase_entry(function);
+  }
+
+  /*
+   * Pins the summary of the function, which may not have been stored yet.
+   * Pins are counted, i.e., a function must be unpinned as many times as it
+   * has been pinned before its summary can be evicted again.
+   */
+  void pin(const Function& function) {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    if (m_pins[function]++ > 0) {
+      return;
+    }
+    auto it = m_entries.find(function);
+    if (it != m_entries.end()) {
+      m_lru.erase(it->second.position);
+      it->second.position = m_lru.end();
+    }
+  }
+
+  /*
+   * Throws invalid_argument if the function isn't pinned.
+   */
+  void unpin(const Function& function) {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    auto pin = m_pins.find(function);
+    RUNTIME_CHECK(pin != m_pins.end(),
+                  invalid_argument() << argument_name("function")
+                                     << error_msg("The function isn't pinned"));
+    if (--pin->second > 0) {
+      return;
+    }
+    m_pins.erase(pin);
+    auto it = m_entries.find(function);
+    if (it != m_entries.end()) {
+      it->second.position = m_lru.insert(m_lru.begin(), function);
+      evict();
+    }
+  }
+
+  bool is_pinned(const Function& function) const {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    return m_pins.count(function) > 0;
+  }
+
+  /*
+   * Removes all the summaries from the cache, except the pinned ones.
+   */
+  void clear() {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    for (const auto& function : m_lru) {
+      auto it = m_entries.find(function);
+      m_weight -= it->second.weight;
+      m_entries.erase(it);
+    }
+    m_lru.clear();
+    m_evicted.clear();
+  }
+
+ private:
+  using LruList = std::list<Function>;
+
+  struct Entry {
+    SummaryPtr summary;
+    size_t weight;
+    // The position of the function in the LRU list, or the end of the list if
+    // the function is pinned.
+    typename LruList::iterator position;
+  };
+
+  SummaryPtr lookup(const Function& function) {
+    auto it = m_entries.find(function);
+    if (it != m_entries.end()) {
+      ++m_stats.hits;
+      touch(&it->second);
+      return it->second.summary;
+    }
+    auto evicted = m_evicted.find(function);
+    if (evicted != m_evicted.end()) {
+      SummaryPtr summary = evicted->second.lock();
+      m_evicted.erase(evicted);
+      if (summary != nullptr) {
+        ++m_stats.recoveries;
+        insert(function, summary);
+        return summary;
+      }
+    }
+    ++m_stats.misses;
+    return nullptr;
+  }
+
+  void touch(Entry* entry) {
+    if (entry->position != m_lru.end()) {
+      m_lru.splice(m_lru.begin(), m_lru, entry->position);
+    }
+  }
+
+  void insert(const Function& function, SummaryPtr summary) {
+    m_evicted.erase(function);
+    size_t weight = m_weigher(*summary);
+    auto position = m_pins.count(function) > 0
+                        ? m_lru.end()
+                        : m_lru.insert(m_lru.begin(), function);
+    m_entries.emplace(function, Entry{std::move(summary), weight, position});
+    m_weight += weight;
+    evict();
+  }
+
+  void erase_entry(const Function& function) {
+    m_evicted.erase(function);
+    auto it = m_entries.find(function);
+    if (it == m_entries.end()) {
+      return;
+    }
+    if (it->second.position != m_lru.end()) {
+      m_lru.erase(it->second.position);
+    }
+    m_weight -= it->second.weight;
+    m_entries.erase(it);
+  }
+
+  void evict() {
+    while (m_weight > m_capacity && !m_lru.empty()) {
+      auto it = m_entries.find(m_lru.back());
+      m_lru.pop_back();
+      m_weight -= it->second.weight;
+      m_evicted.emplace(it->first, it->second.summary);
+      m_entries.erase(it);
+      ++m_stats.evictions;
+    }
+    // The weak references to the summaries that are no longer alive are
+    // dropped periodically, in amortized constant time per eviction.
+    if (m_evicted.size() >= m_evicted_threshold) {
+      for (auto it = m_evicted.begin(); it != m_evicted.end();) {
+        it = it->second.expired() ? m_evicted.erase(it) : std::next(it);
+      }
+      m_evicted_threshold = 2 * m_evicted.size() + 16;
+    }
+  }
+
+  const size_t m_capacity;
+  const Weigher m_weigher;
+  std::unordered_map<Function, Entry, FunctionHash> m_entries;
+  // The most recently used functions come first. Pinned functions are not in
+  // the list.
+  LruList m_lru;
+  std::unordered_map<Function, size_t, FunctionHash> m_pins;
+  std::unordered_map<Function, std::weak_ptr<const Summary>, FunctionHash>
+      m_evicted;
+  size_t m_evicted_threshold = 16;
+  size_t m_weight = 0;
+  SummaryCacheStats m_stats;
+  mutable std::mutex m_mutex;
+};
+
+} // namespace sparta
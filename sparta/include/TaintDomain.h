@@ -0,0 +1,257 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <boost/functional/hash.hpp>
+#include <cstddef>
+#include <functional>
+#include <initializer_list>
+#include <ostream>
+#include <type_traits>
+#include <unordered_set>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+#include "HashedSetAbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * A taint label: the kind of data (e.g., user input, a password, a location)
+ * and the source that introduced it (e.g., a call site or a parameter).
+ */
+template <typename Kind, typename Source>
+struct TaintLabel {
+  Kind kind;
+  Source source;
+
+  friend bool operator==(const TaintLabel& x, const TaintLabel& y) {
+    return x.kind == y.kind && x.source == y.source;
+  }
+
+  friend bool operator!=(const TaintLabel& x, const TaintLabel& y) {
+    return !(x == y);
+  }
+
+  friend std::ostream& operator<<(std::ostream& o, const TaintLabel& t) {
+    return o << t.kind << "@" << t.source;
+  }
+};
+
+template <typename Kind,
+          typename Source,
+          typename KindHash = std::hash<Kind>,
+          typename SourceHash = std::hash<Source>>
+struct TaintLabelHash {
+  size_t operator()(const TaintLabel<Kind, Source>& t) const {
+    size_t seed = KindHash()(t.kind);
+    boost::hash_combine(seed, SourceHash()(t.source));
+    return seed;
+  }
+};
+
+/*
+ * The taint of a value, i.e., the set of labels of the data it may have been
+ * computed from. The empty set denotes an untainted value, Top denotes a value
+ * that may carry any taint and Bottom an unreachable state. The domain is
+ * meant to be the value domain of an abstract environment, in which a missing
+ * variable is conservatively bound to Top (see the functions below).
+ *
+ * The combinators model the three kinds of statements of a taint analysis:
+ *
+ *   - sources, which introduce a label (source(kind, source)),
+ *   - data flows, which propagate the labels of the operands to the result
+ *     (propagate()),
+ *   - sanitizers, which remove the labels of some kinds (sanitize()),
+ *
+ * and sinks, which report the labels of some kinds that reach them
+ * (flows_into()).
+ */
+template <typename Kind,
+          typename Source,
+          typename KindHash = std::hash<Kind>,
+          typename SourceHash = std::hash<Source>>
+class TaintDomain final
+    : public AbstractDomain<TaintDomain<Kind, Source, KindHash, SourceHash>> {
+ public:
+  using Label = TaintLabel<Kind, Source>;
+  using Labels = HashedSetAbstractDomain<
+      Label,
+      TaintLabelHash<Kind, Source, KindHash, SourceHash>>;
+  using Kinds = std::unordered_set<Kind, KindHash>;
+
+  /*
+   * The default constructor produces an untainted value.
+   */
+  TaintDomain() = default;
+
+  explicit TaintDomain(Labels labels) : m_labels(std::move(labels)) {}
+
+  explicit TaintDomain(std::initializer_list<Label> labels)
+      : m_labels(labels) {}
+
+  static TaintDomain bottom() { return TaintDomain(Labels::bottom()); }
+
+  static TaintDomain top() { return TaintDomain(Labels::top()); }
+
+  static TaintDomain untainted() { return TaintDomain(); }
+
+  /*
+   * The value introduced by a source.
+   */
+  static TaintDomain source(Kind kind, Source source) {
+    return TaintDomain({Label{std::move(kind), std::move(source)}});
+  }
+
+  bool is_bottom() const override { return m_labels.is_bottom(); }
+
+  bool is_top() const override { return m_labels.is_top(); }
+
+  void set_to_bottom() override { m_labels.set_to_bottom(); }
+
+  void set_to_top() override { m_labels.set_to_top(); }
+
+  const Labels& labels() const { return m_labels; }
+
+  bool is_tainted() const {
+    return m_labels.is_top() || (m_labels.is_value() && m_labels.size() > 0);
+  }
+
+  /*
+   * The kinds of the labels. This operation is not defined on Top.
+   */
+  Kinds kinds() const {
+    Kinds result;
+    if (!m_labels.is_bottom()) {
+      for (const auto& label : m_labels.elements()) {
+        result.insert(label.kind);
+      }
+    }
+    return result;
+  }
+
+  /*
+   * Adds the labels of a value this value is computed from.
+   */
+  TaintDomain& propagate(const TaintDomain& other) {
+    m_labels.join_with(other.m_labels);
+    return *this;
+  }
+
+  /*
+   * Removes the labels of the given kinds. Since the labels of Top are
+   * unknown, a sanitized Top remains Top.
+   */
+  TaintDomain& sanitize(const Kinds& kinds) {
+    if (!m_labels.is_value()) {
+      return *this;
+    }
+    std::vector<Label> sanitized;
+    for (const auto& label : m_labels.elements()) {
+      if (kinds.count(label.kind) != 0) {
+        sanitized.push_back(label);
+      }
+    }
+    m_labels.remove(sanitized.begin(), sanitized.end());
+    return *this;
+  }
+
+  /*
+   * The labels of the given kinds that reach a sink, which are the issues to
+   * report. The result is Top if this value is Top, and it is untainted if
+   * there is no issue.
+   */
+  TaintDomain flows_into(const Kinds& sink_kinds) const {
+    if (!m_labels.is_value()) {
+      return *this;
+    }
+    TaintDomain result;
+    for (const auto& label : m_labels.elements()) {
+      if (sink_kinds.count(label.kind) != 0) {
+        result.m_labels.add(label);
+      }
+    }
+    return result;
+  }
+
+  bool leq(const TaintDomain& other) const override {
+    return m_labels.leq(other.m_labels);
+  }
+
+  bool equals(const TaintDomain& other) const override {
+    return m_labels.equals(other.m_labels);
+  }
+
+  void join_with(const TaintDomain& other) override {
+    m_labels.join_with(other.m_labels);
+  }
+
+  void widen_with(const TaintDomain& other) override {
+    m_labels.widen_with(other.m_labels);
+  }
+
+  void meet_with(const TaintDomain& other) override {
+    m_labels.meet_with(other.m_labels);
+  }
+
+  void narrow_with(const TaintDomain& other) override {
+    m_labels.narrow_with(other.m_labels);
+  }
+
+  friend std::ostream& operator<<(std::ostream& o, const TaintDomain& x) {
+    return o << x.m_labels;
+  }
+
+ private:
+  Labels m_labels;
+};
+
+/*
+ * The combinators below apply the statements of a taint analysis to an
+ * abstract environment that binds variables to a TaintDomain, e.g.,
+ * PatriciaTreeMapAbstractEnvironment<Register, TaintDomain<...>>.
+ */
+
+/*
+ * dst = f(srcs...), where f propagates the taint of its operands.
+ */
+template <typename Environment, typename Variable>
+void propagate_taint(Environment* env,
+                     const Variable& dst,
+                     std::initializer_list<Variable> srcs) {
+  using Domain = typename std::decay<decltype(env->get(dst))>::type;
+  Domain result = Domain::untainted();
+  for (const auto& src : srcs) {
+    result.propagate(env->get(src));
+  }
+  env->set(dst, std::move(result));
+}
+
+/*
+ * var = sanitize(var), for the given kinds.
+ */
+template <typename Environment, typename Variable, typename Kinds>
+void sanitize_taint(Environment* env, const Variable& var, const Kinds& kinds) {
+  auto taint = env->get(var);
+  taint.sanitize(kinds);
+  env->set(var, std::move(taint));
+}
+
+/*
+ * Returns the labels of the given kinds that flow into a sink through a
+ * variable.
+ */
+template <typename Environment, typename Variable, typename Kinds>
+auto check_sink(const Environment& env,
+                const Variable& var,
+                const Kinds& sink_kinds) {
+  return env.get(var).flows_into(sink_kinds);
+}
+
+} // namespace sparta
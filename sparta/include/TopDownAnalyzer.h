@@ -0,0 +1,221 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <boost/optional.hpp>
+#include <cstddef>
+#include <functional>
+#include <queue>
+#include <type_traits>
+#include <unordered_map>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * A top-down interprocedural analyzer, which complements the summary-based
+ * InterproceduralAnalyzer defined in Analyzer.h. Calling contexts are
+ * propagated from the entry points of the program to the callees, and each
+ * function is analyzed once per context. This is required by analyses that
+ * need the actual calling context of a function in order to be precise, e.g.,
+ * a taint analysis that tracks the flows from sources to sinks.
+ *
+ * A function gets a separate context for each of its call sites, up to a
+ * budget of `max_contexts_per_function` contexts. The calls that exceed the
+ * budget are merged into a single context per function, which is also the
+ * context used for the entry points. Setting the budget to 0 thus yields a
+ * context-insensitive analysis. When a context is updated repeatedly (e.g.,
+ * in the presence of recursion), the widening is applied from the second
+ * update onwards in order to ensure termination.
+ *
+ * Typical usage:
+ *
+ *   struct Analysis {
+ *     using Function = ...; // Must be hashable.
+ *     using CallSite = ...; // Identifies a call instruction, must be hashable.
+ *     using Context = ...;  // An abstract domain.
+ *     using Result = ...;   // E.g., the fixpoint iterator of the function.
+ *
+ *     // Analyzes the function under the given context, and reports the
+ *     // context at the entry of the callee for each call site.
+ *     static Result analyze(
+ *         const Function& function,
+ *         const Context& context,
+ *         const std::function<void(const CallSite&,
+ *                                  const Function& callee,
+ *                                  const Context& callee_context)>& on_call);
+ *   };
+ *
+ *   TopDownInterproceduralAnalyzer<Analysis> analyzer(max_contexts);
+ *   analyzer.run({{main, Analysis::Context::top()}});
+ *   for (const auto& context : analyzer.get_contexts(f)) {
+ *     ... context.call_site ... context.entry_state ... context.result ...
+ *   }
+ */
+template <typename Analysis,
+          typename FunctionHash = std::hash<typename Analysis::Function>,
+          typename CallSiteHash = std::hash<typename Analysis::CallSite>>
+class TopDownInterproceduralAnalyzer final {
+ public:
+  using Function = typename Analysis::Function;
+  using CallSite = typename Analysis::CallSite;
+  using Context = typename Analysis::Context;
+  using Result = typename Analysis::Result;
+
+  /*
+   * The state of the analysis of a function under a given context. The call
+   * site is none for the merged context.
+   */
+  struct ContextState {
+    boost::optional<CallSite> call_site;
+    Context entry_state = Context::bottom();
+    boost::optional<Result> result;
+    size_t num_updates = 0;
+  };
+
+  explicit TopDownInterproceduralAnalyzer(size_t max_contexts_per_function)
+      : m_max_contexts_per_function(max_contexts_per_function) {}
+
+  ~TopDownInterproceduralAnalyzer() {
+    static_assert(std::is_base_of<AbstractDomain<Context>, Context>::value,
+                  "Analysis::Context must inherit from sparta::AbstractDomain");
+  }
+
+  /*
+   * Analyzes the program from the given entry points and their initial
+   * contexts. This discards the results of any previous run.
+   */
+  void run(const std::vector<std::pair<Function, Context>>& entry_points) {
+    m_contexts.clear();
+    std::queue<std::pair<Function, size_t>> worklist;
+    auto propagate = [&](const Function& function,
+                         const boost::optional<CallSite>& call_site,
+                         const Context& context) {
+      size_t index = get_context_index(function, call_site);
+      if (update_entry_state(&m_contexts.at(function).states[index],
+                             context)) {
+        worklist.emplace(function, index);
+      }
+    };
+    for (const auto& entry_point : entry_points) {
+      propagate(entry_point.first, boost::none, entry_point.second);
+    }
+    while (!worklist.empty()) {
+      auto function = worklist.front().first;
+      size_t index = worklist.front().second;
+      worklist.pop();
+      // We copy the entry state, since the analysis of a recursive function
+      // may update it.
+      Context entry_state = m_contexts.at(function).states[index].entry_state;
+      auto result = Analysis::analyze(
+          function,
+          entry_state,
+          [&](const CallSite& call_site,
+              const Function& callee,
+              const Context& callee_context) {
+            if (callee_context.is_bottom()) {
+              // The call site is unreachable.
+              return;
+            }
+            propagate(callee, call_site, callee_context);
+          });
+      m_contexts.at(function).states[index].result = std::move(result);
+    }
+  }
+
+  /*
+   * Returns all the contexts under which the function has been analyzed.
+   */
+  std::vector<ContextState> get_contexts(const Function& function) const {
+    auto it = m_contexts.find(function);
+    if (it == m_contexts.end()) {
+      return {};
+    }
+    return it->second.states;
+  }
+
+  /*
+   * Returns the context created for the given call site of the function, if
+   * any. Passing none returns the merged context.
+   */
+  boost::optional<ContextState> get_context(
+      const Function& function,
+      const boost::optional<CallSite>& call_site) const {
+    auto it = m_contexts.find(function);
+    if (it == m_contexts.end()) {
+      return boost::none;
+    }
+    const auto& contexts = it->second;
+    if (!call_site) {
+      if (!contexts.merged) {
+        return boost::none;
+      }
+      return contexts.states[*contexts.merged];
+    }
+    auto index = contexts.by_call_site.find(*call_site);
+    if (index == contexts.by_call_site.end()) {
+      return boost::none;
+    }
+    return contexts.states[index->second];
+  }
+
+ private:
+  struct FunctionContexts {
+    std::vector<ContextState> states;
+    std::unordered_map<CallSite, size_t, CallSiteHash> by_call_site;
+    boost::optional<size_t> merged;
+  };
+
+  size_t get_context_index(const Function& function,
+                           const boost::optional<CallSite>& call_site) {
+    auto& contexts = m_contexts[function];
+    if (call_site) {
+      auto it = contexts.by_call_site.find(*call_site);
+      if (it != contexts.by_call_site.end()) {
+        return it->second;
+      }
+      if (contexts.by_call_site.size() < m_max_contexts_per_function) {
+        size_t index = contexts.states.size();
+        contexts.states.emplace_back();
+        contexts.states.back().call_site = call_site;
+        contexts.by_call_site.emplace(*call_site, index);
+        return index;
+      }
+    }
+    // The call site exceeds the budget, or the function is an entry point.
+    if (!contexts.merged) {
+      contexts.merged = contexts.states.size();
+      contexts.states.emplace_back();
+    }
+    return *contexts.merged;
+  }
+
+  /*
+   * Returns true if the entry state has changed.
+   */
+  static bool update_entry_state(ContextState* state, const Context& context) {
+    if (context.leq(state->entry_state)) {
+      return false;
+    }
+    if (state->num_updates == 0) {
+      state->entry_state.join_with(context);
+    } else {
+      state->entry_state.widen_with(context);
+    }
+    ++state->num_updates;
+    return true;
+  }
+
+  size_t m_max_contexts_per_function;
+  std::unordered_map<Function, FunctionContexts, FunctionHash> m_contexts;
+};
+
+} // namespace sparta
@@ -0,0 +1,223 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstdint>
+#include <functional>
+#include <ostream>
+#include <sstream>
+#include <string>
+#include <type_traits>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * A lattice operation performed on a Traced domain (see below). The operands
+ * and the result are identified by digests of the abstract values. For the
+ * comparisons (leq and equals), the result is 1 if the comparison holds and 0
+ * otherwise.
+ */
+struct DomainTraceEvent {
+  enum class Op : uint8_t {
+    Join,
+    Meet,
+    Widen,
+    Narrow,
+    Leq,
+    Equals,
+  };
+
+  Op op;
+  uint64_t lhs;
+  uint64_t rhs;
+  uint64_t result;
+};
+
+inline std::ostream& operator<<(std::ostream& o, DomainTraceEvent::Op op) {
+  switch (op) {
+  case DomainTraceEvent::Op::Join:
+    return o << "join";
+  case DomainTraceEvent::Op::Meet:
+    return o << "meet";
+  case DomainTraceEvent::Op::Widen:
+    return o << "widen";
+  case DomainTraceEvent::Op::Narrow:
+    return o << "narrow";
+  case DomainTraceEvent::Op::Leq:
+    return o << "leq";
+  case DomainTraceEvent::Op::Equals:
+    return o << "equals";
+  }
+  return o;
+}
+
+inline std::ostream& operator<<(std::ostream& o, const DomainTraceEvent& e) {
+  return o << e.op << "(" << std::hex << e.lhs << ", " << e.rhs
+           << ") = " << e.result << std::dec;
+}
+
+/*
+ * The log of the lattice operations performed on Traced domains by the
+ * current thread, in the order in which they were performed. The log of a
+ * thread is never shared, hence a concurrent fixpoint iteration produces one
+ * log per worker thread.
+ *
+ * Tracing is disabled by default and can be enabled by defining the macro
+ * SPARTA_TRACE_DOMAINS before including any header of the library. The macro
+ * must be defined consistently across all translation units of a program.
+ * When the macro is not defined, Traced domains behave exactly like the
+ * domains they wrap, no digest is computed and the log stays empty.
+ */
+class DomainTrace final {
+ public:
+  static void record(DomainTraceEvent::Op op,
+                     uint64_t lhs,
+                     uint64_t rhs,
+                     uint64_t result) {
+    events().push_back(DomainTraceEvent{op, lhs, rhs, result});
+  }
+
+  static const std::vector<DomainTraceEvent>& get() { return events(); }
+
+  static void clear() { events().clear(); }
+
+  static constexpr bool enabled() {
+#ifdef SPARTA_TRACE_DOMAINS
+    return true;
+#else
+    return false;
+#endif
+  }
+
+ private:
+  static std::vector<DomainTraceEvent>& events() {
+    thread_local std::vector<DomainTraceEvent> events;
+    return events;
+  }
+};
+
+namespace traced_impl {
+
+/*
+ * The default digest of an abstract value is the hash of its printed form,
+ * which only requires the domain to be printable.
+ */
+template <typename Domain>
+struct PrintedDigest {
+  uint64_t operator()(const Domain& value) const {
+    std::ostringstream o;
+    o << value;
+    return std::hash<std::string>()(o.str());
+  }
+};
+
+} // namespace traced_impl
+
+/*
+ * A wrapper that forwards all the operations to an abstract value of the
+ * domain D, while recording every join, meet, widening, narrowing, leq and
+ * equals in the DomainTrace of the current thread. Substituting Traced<D> for
+ * D in an analysis is the quickest way to find out which operations the
+ * fixpoint iterators perform on the abstract values when the results look
+ * wrong, e.g., a widening that loses too much information:
+ *
+ *   #define SPARTA_TRACE_DOMAINS
+ *   ...
+ *   using Domain = Traced<IntervalDomain<int>>;
+ *
+ * The digest of a value is computed by the Digest functor, which defaults to
+ * hashing the printed form of the value.
+ */
+template <typename D, typename Digest = traced_impl::PrintedDigest<D>>
+class Traced final : public AbstractDomain<Traced<D, Digest>> {
+  static_assert(std::is_base_of<AbstractDomain<D>, D>::value,
+                "Traced must wrap another domain");
+
+ public:
+  using Op = DomainTraceEvent::Op;
+
+  Traced() = default;
+
+  explicit Traced(D value) : m_value(std::move(value)) {}
+
+  static Traced bottom() { return Traced(D::bottom()); }
+
+  static Traced top() { return Traced(D::top()); }
+
+  const D& inner() const { return m_value; }
+
+  D& inner() { return m_value; }
+
+  bool is_bottom() const override { return m_value.is_bottom(); }
+
+  bool is_top() const override { return m_value.is_top(); }
+
+  void set_to_bottom() override { m_value.set_to_bottom(); }
+
+  void set_to_top() override { m_value.set_to_top(); }
+
+  bool leq(const Traced& other) const override {
+    bool result = m_value.leq(other.m_value);
+    trace(Op::Leq, m_value, other.m_value, result);
+    return result;
+  }
+
+  bool equals(const Traced& other) const override {
+    bool result = m_value.equals(other.m_value);
+    trace(Op::Equals, m_value, other.m_value, result);
+    return result;
+  }
+
+  void join_with(const Traced& other) override {
+    apply(Op::Join, other, [](D& x, const D& y) { x.join_with(y); });
+  }
+
+  void widen_with(const Traced& other) override {
+    apply(Op::Widen, other, [](D& x, const D& y) { x.widen_with(y); });
+  }
+
+  void meet_with(const Traced& other) override {
+    apply(Op::Meet, other, [](D& x, const D& y) { x.meet_with(y); });
+  }
+
+  void narrow_with(const Traced& other) override {
+    apply(Op::Narrow, other, [](D& x, const D& y) { x.narrow_with(y); });
+  }
+
+  friend std::ostream& operator<<(std::ostream& o, const Traced& x) {
+    return o << x.m_value;
+  }
+
+ private:
+  template <typename Operation>
+  void apply(Op op, const Traced& other, Operation&& operation) {
+    if (!DomainTrace::enabled()) {
+      operation(m_value, other.m_value);
+      return;
+    }
+    // The operand may be the same object as the result.
+    uint64_t lhs = Digest()(m_value);
+    uint64_t rhs = Digest()(other.m_value);
+    operation(m_value, other.m_value);
+    DomainTrace::record(op, lhs, rhs, Digest()(m_value));
+  }
+
+  static void trace(Op op, const D& lhs, const D& rhs, bool result) {
+    if (DomainTrace::enabled()) {
+      DomainTrace::record(op, Digest()(lhs), Digest()(rhs), result);
+    }
+  }
+
+  D m_value;
+};
+
+} // namespace sparta
@@ -18,6 +18,7 @@
 
 #pragma once
 
+#include <algorithm>
 #include <boost/pending/disjoint_sets.hpp>
 #include <cstddef>
 #include <functional>
@@ -33,6 +34,7 @@
 #include <vector>
 
 #include "Exceptions.h"
+#include "Fingerprint.h"
 
 namespace sparta {
 
@@ -160,6 +162,8 @@ class WeakPartialOrdering final {
   std::vector<WpoIdx> m_toplevel;
   // post DFN of the nodes.
   std::unordered_map<NodeId, uint32_t, NodeHash> m_post_dfn;
+  // Head of the minimal component that contains the node as non-header.
+  std::vector<WpoIdx> m_parents;
   // This is used when generating a WTO from a WPO.
   // See algorithm ConstructWTO^{BU} in Section 7 of the POPL 2020 paper.
   bool m_lifted;
@@ -184,11 +188,16 @@ class WeakPartialOrdering final {
       m_nodes.emplace_back(root, Type::Plain, /*size=*/1);
       m_toplevel.push_back(0);
       m_post_dfn[root] = 1;
+      m_parents.push_back(0);
       return;
     }
     wpo_impl::WpoBuilder<NodeId, NodeHash> builder(
         successors, m_nodes, m_toplevel, m_post_dfn, lift);
     builder.build(root);
+    m_parents.reserve(m_nodes.size());
+    for (WpoIdx idx = 0; idx < m_nodes.size(); ++idx) {
+      m_parents.push_back(builder.get_parent(idx));
+    }
   }
 
   // Total number of nodes in this wpo.
@@ -224,6 +233,14 @@ class WeakPartialOrdering final {
   // Exit of the head node.
   WpoIdx get_exit_of_head(WpoIdx head) const { return head - 1; }
 
+  // Head of the minimal component that contains the node as non-header. The
+  // exit of a component has the same parent as its head. A node that is not
+  // contained in any component is its own parent.
+  WpoIdx get_parent(WpoIdx idx) const { return m_parents[idx]; }
+
+  // Number of nodes in the component of a head or an exit, 1 for a plain node.
+  uint32_t get_size(WpoIdx idx) const { return m_nodes[idx].get_size(); }
+
   // NodeId for the node.
   const NodeId& get_node(WpoIdx idx) const { return m_nodes[idx].get_node(); }
 
@@ -241,6 +258,75 @@ class WeakPartialOrdering final {
     return get_post_dfn(head) < get_post_dfn(pred);
   }
 
+  // Partition of the nodes into waves, i.e., antichains of the WPO. The first
+  // wave only contains the entry, and a node belongs to the wave that follows
+  // the last wave containing one of its predecessors. Hence the nodes of a
+  // wave are independent and can be processed in parallel, as soon as all the
+  // previous waves have been processed. This exposes the schedule of the
+  // concurrent fixpoint iteration to external execution engines. When a
+  // component is not stable after processing its exit, all the nodes of the
+  // component have to be processed again, wave by wave.
+  std::vector<std::vector<WpoIdx>> get_waves() const {
+    std::vector<std::vector<WpoIdx>> waves;
+    if (m_nodes.empty()) {
+      return waves;
+    }
+    std::vector<uint32_t> count(m_nodes.size(), 0);
+    std::vector<WpoIdx> wave{static_cast<WpoIdx>(m_nodes.size() - 1)};
+    while (!wave.empty()) {
+      std::vector<WpoIdx> next;
+      for (auto v : wave) {
+        for (auto w : get_successors(v)) {
+          if (++count[w] == get_num_preds(w)) {
+            next.push_back(w);
+          }
+        }
+      }
+      std::sort(next.begin(), next.end());
+      waves.push_back(std::move(wave));
+      wave = std::move(next);
+    }
+    return waves;
+  }
+
+  // A content hash of the WPO (see Fingerprint.h), which can be stored along
+  // with a cached WPO, or with any artifact computed from it, to validate it
+  // later on. The nodes are identified by their hashes. Two WPOs of the same
+  // graph built with the same order of successors have the same fingerprint,
+  // regardless of the iteration order of the hashtables.
+  uint64_t fingerprint() const {
+    NodeHash hash;
+    Fingerprint fp;
+    fp.add(m_lifted).add(m_nodes.size());
+    for (WpoIdx idx = 0; idx < m_nodes.size(); ++idx) {
+      const auto& node = m_nodes[idx];
+      uint64_t type = node.is_head() ? 1 : (node.is_exit() ? 2 : 0);
+      fp.add(hash(node.get_node()))
+          .add(type)
+          .add(node.get_size())
+          .add(m_parents[idx])
+          .add(node.get_successors().size());
+      for (auto succ : node.get_successors()) {
+        fp.add(succ);
+      }
+      if (node.is_exit()) {
+        Fingerprint outer_preds;
+        for (const auto& p : node.get_num_outer_preds()) {
+          outer_preds.add_unordered(
+              Fingerprint().add(p.first).add(p.second).value());
+        }
+        fp.add(outer_preds.value());
+      }
+    }
+    for (auto idx : m_toplevel) {
+      fp.add(idx);
+    }
+    for (const auto& p : m_post_dfn) {
+      fp.add_unordered(Fingerprint().add(hash(p.first)).add(p.second).value());
+    }
+    return fp.value();
+  }
+
   WeakPartialOrdering(const WeakPartialOrdering& other) = delete;
   WeakPartialOrdering(WeakPartialOrdering&& other) = delete;
   WeakPartialOrdering& operator=(const WeakPartialOrdering& other) = delete;
@@ -291,6 +377,14 @@ class WpoBuilder final {
     }
   }
 
+  WpoIdx get_parent(WpoIdx idx) {
+    if (m_wpo_space[idx].is_exit()) {
+      // index of head == index of exit + 1.
+      ++idx;
+    }
+    return m_parent.at(idx);
+  }
+
  private:
   // Construct auxilary data-structures.
   // Performs DFS iteratively to classify the edges and find lowest common
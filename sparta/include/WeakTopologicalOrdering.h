@@ -256,60 +256,90 @@ class WtoBuilder final {
         m_free_position(0),
         m_num(0) {}
 
-  void build(const NodeId& root) {
-    int32_t partition = -1;
-    visit(root, &partition);
-  }
-
   // We keep the notations used by Bourdoncle in the paper to describe the
-  // algorithm.
-
-  uint32_t visit(const NodeId& vertex, int32_t* partition) {
-    m_stack.push(vertex);
-    uint32_t head = set_dfn(vertex, ++m_num);
-    bool loop = false;
-    for (const NodeId& succ : m_successors(vertex)) {
-      uint32_t succ_dfn = get_dfn(succ);
-      uint32_t min;
-      if (succ_dfn == 0) {
-        min = visit(succ, partition);
-      } else {
-        min = succ_dfn;
-      };
-      if (min <= head) {
-        head = min;
-        loop = true;
+  // algorithm. The recursive procedures `visit` and `component` of the paper
+  // are implemented with an explicit stack of frames, since the recursion
+  // depth would be the length of the longest path in the graph.
+  void build(const NodeId& root) {
+    int32_t root_partition = -1;
+    push_frame(root, kRootPartition);
+    uint32_t result = 0;
+    bool returned = false;
+    while (!m_frames.empty()) {
+      size_t index = m_frames.size() - 1;
+      Frame& frame = m_frames.back();
+      if (returned) {
+        returned = false;
+        if (!frame.in_component && result <= frame.head) {
+          frame.head = result;
+          frame.loop = true;
+        }
       }
-    }
-    if (head == get_dfn(vertex)) {
-      // We encode the special value +oo used in the paper with UINT32_MAX.
-      set_dfn(vertex, std::numeric_limits<uint32_t>::max());
-      NodeId element = m_stack.top();
-      m_stack.pop();
-      if (loop) {
-        // Nodes are required to be comparable using `operator==()`. We don't
-        // assume `operator!=()` to be defined on nodes.
-        while (!(element == vertex)) {
-          set_dfn(element, 0);
-          element = m_stack.top();
-          m_stack.pop();
+      if (frame.next < frame.successors.size()) {
+        NodeId succ = frame.successors[frame.next++];
+        uint32_t succ_dfn = get_dfn(succ);
+        if (frame.in_component) {
+          // The successors of a component head are visited with a partition
+          // that is local to the component.
+          if (succ_dfn == 0) {
+            push_frame(succ, index);
+          }
+        } else if (succ_dfn == 0) {
+          // The successors of a vertex share the partition of the vertex.
+          push_frame(succ, frame.partition_owner);
+        } else if (succ_dfn <= frame.head) {
+          frame.head = succ_dfn;
+          frame.loop = true;
+        }
+        continue;
+      }
+      int32_t& partition = frame.partition_owner == kRootPartition
+                               ? root_partition
+                               : m_frames[frame.partition_owner].partition;
+      if (!frame.in_component && frame.head == get_dfn(frame.vertex)) {
+        // We encode the special value +oo used in the paper with UINT32_MAX.
+        set_dfn(frame.vertex, std::numeric_limits<uint32_t>::max());
+        NodeId element = m_stack.top();
+        m_stack.pop();
+        if (frame.loop) {
+          // Nodes are required to be comparable using `operator==()`. We
+          // don't assume `operator!=()` to be defined on nodes.
+          while (!(element == frame.vertex)) {
+            set_dfn(element, 0);
+            element = m_stack.top();
+            m_stack.pop();
+          }
+          frame.in_component = true;
+          frame.next = 0;
+          frame.partition = partition;
+          continue;
         }
-        push_component(vertex, *partition);
+        m_wto_space->emplace_back(frame.vertex,
+                                  WtoComponent<NodeId>::Kind::Vertex,
+                                  m_free_position, partition);
+        partition = m_free_position++;
+      } else if (frame.in_component) {
+        m_wto_space->emplace_back(frame.vertex,
+                                  WtoComponent<NodeId>::Kind::Scc,
+                                  m_free_position, partition);
+        partition = m_free_position++;
       }
-      auto kind = loop ? WtoComponent<NodeId>::Kind::Scc
-                       : WtoComponent<NodeId>::Kind::Vertex;
-      m_wto_space->emplace_back(vertex, kind, m_free_position, *partition);
-      *partition = m_free_position++;
+      result = frame.head;
+      returned = true;
+      m_frames.pop_back();
     }
-    return head;
   }
 
-  void push_component(const NodeId& vertex, int32_t partition) {
+  // Starts the visit of a vertex, whose enclosing partition is owned by the
+  // given frame.
+  void push_frame(const NodeId& vertex, size_t partition_owner) {
+    m_stack.push(vertex);
+    Frame frame(vertex, partition_owner);
     for (const NodeId& succ : m_successors(vertex)) {
-      if (get_dfn(succ) == 0) {
-        visit(succ, &partition);
-      }
+      frame.successors.push_back(succ);
     }
+    frame.head = set_dfn(vertex, ++m_num);
+    m_frames.push_back(std::move(frame));
   }
 
   uint32_t get_dfn(const NodeId& node) {
@@ -329,6 +359,27 @@ class WtoBuilder final {
     return number;
   }
 
+  static constexpr size_t kRootPartition = std::numeric_limits<size_t>::max();
+
+  struct Frame {
+    Frame(const NodeId& vertex, size_t partition_owner)
+        : vertex(vertex), partition_owner(partition_owner) {}
+
+    NodeId vertex;
+    std::vector<NodeId> successors;
+    // The next successor to visit.
+    size_t next = 0;
+    uint32_t head = 0;
+    bool loop = false;
+    // Whether the vertex is the head of a component whose successors are
+    // being visited.
+    bool in_component = false;
+    // The frame whose partition is updated by this vertex.
+    size_t partition_owner;
+    // The partition local to the component, if the vertex is a head.
+    int32_t partition = -1;
+  };
+
   SuccFn m_successors;
   std::vector<WtoComponent<NodeId>>* m_wto_space;
   // The next available position at the end of the vector of components.
@@ -336,6 +387,7 @@ class WtoBuilder final {
   // These are auxiliary data structures used by Bourdoncle's algorithm.
   std::unordered_map<NodeId, uint32_t, NodeHash> m_dfn;
   std::stack<NodeId> m_stack;
+  std::vector<Frame> m_frames;
   uint32_t m_num;
 };
 
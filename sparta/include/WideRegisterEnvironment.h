@@ -0,0 +1,281 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <ostream>
+#include <type_traits>
+
+#include "AbstractDomain.h"
+#include "DefaultBinding.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+#include "PatriciaTreeSet.h"
+
+namespace sparta {
+
+/*
+ * An abstract environment over a register file in which a wide value (e.g., a
+ * 64-bit long or double in Dex bytecode) occupies a pair of consecutive
+ * registers r and r + 1. The value of a wide pair is bound to its lower
+ * register r, and the upper register r + 1 is bound to Top.
+ *
+ * Writing a register that belongs to a wide pair destroys the pair, i.e., the
+ * partner register no longer holds a valid value and is bound to Top. This
+ * holds for narrow writes (set), wide writes (set_wide), as well as for
+ * explicit invalidations (unset).
+ *
+ * An abstract value is a pair (E, W), where E binds the registers to abstract
+ * values and W is the set of lower registers of the wide pairs. A register that
+ * is bound to Top may hold a value of any width. Hence, the join of two
+ * environments that disagree on whether a register holds a wide pair binds
+ * the register to Top, and the meet of a wide pair with registers that are not
+ * bound to Top is Bottom.
+ */
+template <typename Register, typename Domain>
+class WideRegisterEnvironment final
+    : public AbstractDomain<WideRegisterEnvironment<Register, Domain>> {
+ public:
+  using DefaultBinding = DefaultIsTop;
+
+  using Environment = PatriciaTreeMapAbstractEnvironment<Register, Domain>;
+  using RegisterSet = PatriciaTreeSet<Register>;
+
+  ~WideRegisterEnvironment() {
+    static_assert(std::is_unsigned<Register>::value,
+                  "Register is not an unsigned integral type");
+  }
+
+  /*
+   * The default constructor produces the Top value.
+   */
+  WideRegisterEnvironment() = default;
+
+  explicit WideRegisterEnvironment(AbstractValueKind kind) : m_env(kind) {}
+
+  /*
+   * Returns the value of a narrow register or the value of the wide pair whose
+   * lower register is the given one. The upper register of a wide pair is
+   * bound to Top.
+   */
+  const Domain& get(Register reg) const { return m_env.get(reg); }
+
+  bool is_wide(Register reg) const { return m_wide.contains(reg); }
+
+  bool is_upper_half(Register reg) const {
+    return reg > 0 && m_wide.contains(reg - 1);
+  }
+
+  /*
+   * The lower registers of all the wide pairs.
+   */
+  const RegisterSet& wide_registers() const { return m_wide; }
+
+  /*
+   * The underlying environment, in which the upper registers of wide pairs are
+   * bound to Top.
+   */
+  const Environment& environment() const { return m_env; }
+
+  /*
+   * Binds a narrow value to the register, invalidating the wide pair the
+   * register was part of, if any.
+   */
+  WideRegisterEnvironment& set(Register reg, const Domain& value) {
+    if (is_bottom()) {
+      return *this;
+    }
+    invalidate(reg);
+    m_env.set(reg, value);
+    normalize();
+    return *this;
+  }
+
+  /*
+   * Binds a wide value to the pair (reg, reg + 1), invalidating the wide pairs
+   * that overlap either register.
+   */
+  WideRegisterEnvironment& set_wide(Register reg, const Domain& value) {
+    if (is_bottom()) {
+      return *this;
+    }
+    invalidate(reg);
+    invalidate(reg + 1);
+    m_env.set(reg + 1, Domain::top());
+    m_env.set(reg, value);
+    normalize();
+    if (!is_bottom()) {
+      m_wide.insert(reg);
+    }
+    return *this;
+  }
+
+  /*
+   * Binds the register to Top, invalidating the wide pair the register was
+   * part of, if any.
+   */
+  WideRegisterEnvironment& unset(Register reg) {
+    return set(reg, Domain::top());
+  }
+
+  bool is_bottom() const override { return m_env.is_bottom(); }
+
+  bool is_top() const override { return m_env.is_top() && m_wide.empty(); }
+
+  void set_to_bottom() override {
+    m_env.set_to_bottom();
+    m_wide.clear();
+  }
+
+  void set_to_top() override {
+    m_env.set_to_top();
+    m_wide.clear();
+  }
+
+  bool leq(const WideRegisterEnvironment& other) const override {
+    if (is_bottom()) {
+      return true;
+    }
+    if (other.is_bottom()) {
+      return false;
+    }
+    if (!other.m_wide.is_subset_of(m_wide)) {
+      return false;
+    }
+    for (Register reg : m_wide.get_difference_with(other.m_wide)) {
+      if (!other.is_unknown_pair(reg)) {
+        return false;
+      }
+    }
+    return m_env.leq(other.m_env);
+  }
+
+  bool equals(const WideRegisterEnvironment& other) const override {
+    return m_env.equals(other.m_env) && m_wide.equals(other.m_wide);
+  }
+
+  void join_with(const WideRegisterEnvironment& other) override {
+    join_like_operation(other, [](Environment* x, const Environment& y) {
+      x->join_with(y);
+    });
+  }
+
+  void widen_with(const WideRegisterEnvironment& other) override {
+    join_like_operation(other, [](Environment* x, const Environment& y) {
+      x->widen_with(y);
+    });
+  }
+
+  void meet_with(const WideRegisterEnvironment& other) override {
+    meet_like_operation(other, [](Environment* x, const Environment& y) {
+      x->meet_with(y);
+    });
+  }
+
+  void narrow_with(const WideRegisterEnvironment& other) override {
+    meet_like_operation(other, [](Environment* x, const Environment& y) {
+      x->narrow_with(y);
+    });
+  }
+
+  static WideRegisterEnvironment bottom() {
+    return WideRegisterEnvironment(AbstractValueKind::Bottom);
+  }
+
+  static WideRegisterEnvironment top() {
+    return WideRegisterEnvironment(AbstractValueKind::Top);
+  }
+
+ private:
+  // Destroys the wide pair that contains the register, if any. The register
+  // itself is left for the caller to overwrite.
+  void invalidate(Register reg) {
+    if (m_wide.contains(reg)) {
+      m_wide.remove(reg);
+    }
+    if (is_upper_half(reg)) {
+      m_wide.remove(reg - 1);
+      m_env.set(reg - 1, Domain::top());
+    }
+  }
+
+  // Returns true if the registers of the pair that starts at the given
+  // register may hold values of any width.
+  bool is_unknown_pair(Register reg) const {
+    return m_env.get(reg).is_top() && m_env.get(reg + 1).is_top();
+  }
+
+  void normalize() {
+    if (m_env.is_bottom()) {
+      m_wide.clear();
+    }
+  }
+
+  template <typename Operation>
+  void join_like_operation(const WideRegisterEnvironment& other,
+                           Operation&& operation) {
+    if (other.is_bottom()) {
+      return;
+    }
+    if (is_bottom()) {
+      *this = other;
+      return;
+    }
+    Environment other_env = other.m_env;
+    for (Register reg : m_wide.get_difference_with(other.m_wide)) {
+      m_env.set(reg, Domain::top());
+    }
+    for (Register reg : other.m_wide.get_difference_with(m_wide)) {
+      other_env.set(reg, Domain::top());
+    }
+    m_wide.intersection_with(other.m_wide);
+    operation(&m_env, other_env);
+    normalize();
+  }
+
+  template <typename Operation>
+  void meet_like_operation(const WideRegisterEnvironment& other,
+                           Operation&& operation) {
+    if (is_bottom()) {
+      return;
+    }
+    if (other.is_bottom()) {
+      set_to_bottom();
+      return;
+    }
+    for (Register reg : m_wide.get_difference_with(other.m_wide)) {
+      if (!other.is_unknown_pair(reg)) {
+        set_to_bottom();
+        return;
+      }
+    }
+    for (Register reg : other.m_wide.get_difference_with(m_wide)) {
+      if (!is_unknown_pair(reg)) {
+        set_to_bottom();
+        return;
+      }
+    }
+    m_wide.union_with(other.m_wide);
+    operation(&m_env, other.m_env);
+    normalize();
+  }
+
+  Environment m_env;
+  RegisterSet m_wide;
+};
+
+} // namespace sparta
+
+template <typename Register, typename Domain>
+inline std::ostream& operator<<(
+    std::ostream& o,
+    const typename sparta::WideRegisterEnvironment<Register, Domain>& env) {
+  o << env.environment();
+  if (!env.wide_registers().empty()) {
+    o << " wide: " << env.wide_registers();
+  }
+  return o;
+}
@@ -0,0 +1,110 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <cstdint>
+#include <functional>
+#include <unordered_set>
+#include <utility>
+#include <vector>
+
+#include "MonotonicFixpointIterator.h"
+#include "WeakTopologicalOrdering.h"
+
+namespace sparta {
+
+namespace wp_impl {
+
+template <typename NodeId>
+void collect_heads(const WtoComponent<NodeId>& component,
+                   uint32_t depth,
+                   std::vector<std::pair<uint32_t, NodeId>>* heads) {
+  if (!component.is_scc()) {
+    return;
+  }
+  heads->emplace_back(depth, component.head_node());
+  for (const auto& subcomponent : component) {
+    collect_heads(subcomponent, depth + 1, heads);
+  }
+}
+
+} // namespace wp_impl
+
+/*
+ * Computes a set of widening points for a fixpoint iteration over a graph,
+ * i.e., a set of nodes such that every cycle of the graph that is reachable
+ * from the entry contains at least one of them. The result is a subset of the
+ * heads of the weak topological ordering of the graph, which is minimal in
+ * the sense that no widening point can be removed. The heads are considered
+ * from the outermost to the innermost, so that the innermost heads of nested
+ * loops are kept. For example, the outer loop of
+ *
+ *   while (...) {     // 1
+ *     while (...) {   // 2
+ *       ...
+ *     }
+ *   }
+ *
+ * needs no widening point, since all its iterations go through the inner head
+ * 2. The outer head is kept if some path around the outer loop bypasses the
+ * inner loops.
+ *
+ * See MonotonicFixpointIteratorBase::set_widening_points for the use of the
+ * widening points during a fixpoint iteration.
+ */
+template <typename GraphInterface,
+          typename NodeHash = std::hash<typename GraphInterface::NodeId>>
+std::unordered_set<typename GraphInterface::NodeId, NodeHash>
+compute_widening_points(const typename GraphInterface::Graph& graph) {
+  using NodeId = typename GraphInterface::NodeId;
+  auto successors = successor_nodes<GraphInterface, NodeHash>(graph);
+  WeakTopologicalOrdering<NodeId, NodeHash> wto(GraphInterface::entry(graph),
+                                                successors);
+  std::vector<std::pair<uint32_t, NodeId>> heads;
+  for (const auto& component : wto) {
+    wp_impl::collect_heads(component, 0, &heads);
+  }
+  std::stable_sort(heads.begin(),
+                   heads.end(),
+                   [](const std::pair<uint32_t, NodeId>& x,
+                      const std::pair<uint32_t, NodeId>& y) {
+                     return x.first < y.first;
+                   });
+
+  std::unordered_set<NodeId, NodeHash> points;
+  for (const auto& head : heads) {
+    points.insert(head.second);
+  }
+  for (const auto& entry : heads) {
+    const NodeId& head = entry.second;
+    // The head is redundant if all the cycles that go through it contain
+    // another widening point, i.e., if the head cannot reach itself without
+    // going through another widening point.
+    std::unordered_set<NodeId, NodeHash> visited;
+    std::vector<NodeId> worklist(successors(head));
+    bool on_cycle = false;
+    while (!worklist.empty() && !on_cycle) {
+      NodeId node = worklist.back();
+      worklist.pop_back();
+      if (node == head) {
+        on_cycle = true;
+      } else if (points.count(node) == 0 && visited.insert(node).second) {
+        for (const auto& succ : successors(node)) {
+          worklist.push_back(succ);
+        }
+      }
+    }
+    if (!on_cycle) {
+      points.erase(head);
+    }
+  }
+  return points;
+}
+
+} // namespace sparta
@@ -0,0 +1,167 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <cstddef>
+#include <functional>
+#include <mutex>
+#include <ostream>
+#include <type_traits>
+#include <unordered_set>
+#include <utility>
+#include <vector>
+
+#include "ResultDiff.h"
+
+namespace sparta {
+
+/*
+ * Records where a fixpoint iteration lost precision by extrapolation, in
+ * order to answer questions such as "why is x Top here?". An extrapolation of
+ * the entry state of a head loses precision whenever its result is not
+ * subsumed by the join of the previous and new entry states, which is the
+ * most precise sound update. This is typically the case of the widening, but
+ * also of a custom extrapolation strategy or of the fallback to Top when a
+ * memory ceiling is exceeded. Only these extrapolations are recorded.
+ *
+ *   WideningProvenance<NodeId, Environment> provenance;
+ *   fixpoint.set_widening_provenance(&provenance);
+ *   fixpoint.run(Environment::top());
+ *   for (const auto& head : provenance.widening_heads(x)) {
+ *     ...
+ *   }
+ *
+ * Recording is thread-safe, so the provenance can be used by a parallel
+ * fixpoint iterator. It is meant for debugging, since it keeps a copy of the
+ * states of each recorded extrapolation.
+ */
+template <typename NodeId,
+          typename Domain,
+          typename NodeHash = std::hash<NodeId>>
+class WideningProvenance final {
+ public:
+  struct Event {
+    NodeId head;
+    // The number of times the component of the head had been iterated when
+    // the extrapolation occurred.
+    size_t iteration;
+    // The join of the previous and new entry states of the head.
+    Domain joined;
+    // The entry state of the head computed by the extrapolation.
+    Domain extrapolated;
+  };
+
+  WideningProvenance() = default;
+
+  WideningProvenance(const WideningProvenance&) = delete;
+
+  WideningProvenance& operator=(const WideningProvenance&) = delete;
+
+  /*
+   * Invoked by the fixpoint iterator after each extrapolation of the entry
+   * state of a head.
+   */
+  void record(const NodeId& head,
+              size_t iteration,
+              const Domain& previous_state,
+              const Domain& new_state,
+              const Domain& extrapolated_state) {
+    Domain joined = previous_state.join(new_state);
+    if (extrapolated_state.leq(joined)) {
+      return;
+    }
+    std::lock_guard<std::mutex> guard(m_mutex);
+    m_events.push_back(
+        Event{head, iteration, std::move(joined), extrapolated_state});
+  }
+
+  /*
+   * The extrapolations that lost precision, in the order in which they
+   * occurred.
+   */
+  const std::vector<Event>& events() const { return m_events; }
+
+  std::vector<const Event*> events_at(const NodeId& head) const {
+    std::vector<const Event*> events;
+    for (const auto& event : m_events) {
+      if (event.head == head) {
+        events.push_back(&event);
+      }
+    }
+    return events;
+  }
+
+  /*
+   * For abstract environments, returns the variables whose value lost
+   * precision at the given head, in the order in which they were first
+   * widened. The extrapolations that produced Top or Bottom are ignored (see
+   * diff_environments()).
+   */
+  template <typename Environment = Domain,
+            typename Variable = std::decay_t<
+                decltype(std::declval<const Environment&>()
+                             .bindings()
+                             .begin()
+                             ->first)>>
+  std::vector<Variable> widened_variables(const NodeId& head) const {
+    std::vector<Variable> variables;
+    for (const auto* event : events_at(head)) {
+      for (const auto& change :
+           diff_environments(event->joined, event->extrapolated)) {
+        if (change.second != PrecisionChange::Improved &&
+            std::find(variables.begin(), variables.end(), change.first) ==
+                variables.end()) {
+          variables.push_back(change.first);
+        }
+      }
+    }
+    return variables;
+  }
+
+  /*
+   * For abstract environments, returns the heads at which the value of the
+   * given variable lost precision, in the order in which it was first
+   * widened there.
+   */
+  template <typename Variable>
+  std::vector<NodeId> widening_heads(const Variable& variable) const {
+    std::vector<NodeId> heads;
+    std::unordered_set<NodeId, NodeHash> visited;
+    for (const auto& event : m_events) {
+      auto change = compare_precision(event.joined.get(variable),
+                                      event.extrapolated.get(variable));
+      if ((change == PrecisionChange::Regressed ||
+           change == PrecisionChange::Incomparable) &&
+          visited.insert(event.head).second) {
+        heads.push_back(event.head);
+      }
+    }
+    return heads;
+  }
+
+  void clear() {
+    std::lock_guard<std::mutex> guard(m_mutex);
+    m_events.clear();
+  }
+
+  friend std::ostream& operator<<(std::ostream& o,
+                                  const WideningProvenance& provenance) {
+    for (const auto& event : provenance.m_events) {
+      o << "widened at " << event.head << " (iteration " << event.iteration
+        << "): " << event.joined << " -> " << event.extrapolated << "\n";
+    }
+    return o;
+  }
+
+ private:
+  std::mutex m_mutex;
+  std::vector<Event> m_events;
+};
+
+} // namespace sparta
@@ -0,0 +1,594 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <algorithm>
+#include <boost/optional.hpp>
+#include <cstddef>
+#include <functional>
+#include <limits>
+#include <ostream>
+#include <type_traits>
+#include <unordered_map>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomain.h"
+
+namespace sparta {
+
+/*
+ * The domain of zones, i.e., conjunctions of constraints of the form
+ * x - y <= c and +/-x <= c, where x and y are variables and c is a constant:
+ *
+ *   A. Miné. A New Numerical Abstract Domain Based on Difference-Bound
+ *   Matrices. PADO 2001.
+ *
+ * This is a relational domain that is much cheaper than octagons or
+ * polyhedra, since all the operations are at most cubic in the number of
+ * variables. It can, for example, prove that an index stays below the length
+ * of an array in a loop, whereas a non-relational domain would need to know
+ * the length.
+ *
+ * A zone is represented by a dense difference-bound matrix (DBM) over the
+ * variables that occur in some constraint, plus a special variable that is
+ * always 0 and encodes the unary constraints. The entry (i, j) of the matrix
+ * is the upper bound of v_i - v_j, or none if the difference is unbounded.
+ * The variables that do not occur in the matrix are unconstrained.
+ *
+ * All the operations keep the matrix closed, i.e., every bound is the tightest
+ * one implied by the constraints, which is the canonical form of a zone. The
+ * only exception is the widening, whose result must not be closed in order
+ * to guarantee termination. Closing it explicitly via close() is allowed as
+ * long as the closed zone is not used as the left operand of a subsequent
+ * widening.
+ *
+ * The arithmetic on the bounds is checked: a bound that overflows is dropped,
+ * which is always sound.
+ */
+template <typename Variable,
+          typename Number = int64_t,
+          typename VariableHash = std::hash<Variable>>
+class ZoneDomain final
+    : public AbstractDomain<ZoneDomain<Variable, Number, VariableHash>> {
+  static_assert(std::is_integral<Number>::value &&
+                    std::is_signed<Number>::value,
+                "ZoneDomain requires a signed integral type.");
+
+ public:
+  // The upper bound of a difference, or none if the difference is unbounded.
+  using Bound = boost::optional<Number>;
+
+  /*
+   * The default constructor produces the Top value.
+   */
+  ZoneDomain() = default;
+
+  static ZoneDomain bottom() {
+    ZoneDomain zone;
+    zone.set_to_bottom();
+    return zone;
+  }
+
+  static ZoneDomain top() { return ZoneDomain(); }
+
+  bool is_bottom() const override { return m_is_bottom; }
+
+  bool is_top() const override {
+    if (m_is_bottom) {
+      return false;
+    }
+    size_t n = dimension();
+    for (size_t i = 0; i < n; ++i) {
+      for (size_t j = 0; j < n; ++j) {
+        if (i != j && at(i, j)) {
+          return false;
+        }
+      }
+    }
+    return true;
+  }
+
+  void set_to_bottom() override {
+    m_is_bottom = true;
+    clear();
+  }
+
+  void set_to_top() override {
+    m_is_bottom = false;
+    clear();
+  }
+
+  bool leq(const ZoneDomain& other) const override {
+    if (m_is_bottom) {
+      return true;
+    }
+    if (other.m_is_bottom) {
+      return false;
+    }
+    // Every constraint of the other zone must be implied by this one.
+    auto indices = indices_in(other);
+    size_t n = other.dimension();
+    for (size_t i = 0; i < n; ++i) {
+      for (size_t j = 0; j < n; ++j) {
+        const auto& bound = other.at(i, j);
+        if (i == j || !bound) {
+          continue;
+        }
+        if (!indices[i] || !indices[j]) {
+          return false;
+        }
+        const auto& this_bound = at(*indices[i], *indices[j]);
+        if (!this_bound || *this_bound > *bound) {
+          return false;
+        }
+      }
+    }
+    return true;
+  }
+
+  bool equals(const ZoneDomain& other) const override {
+    return leq(other) && other.leq(*this);
+  }
+
+  /*
+   * The join of two closed zones keeps the weakest bound of each difference
+   * and is closed as well.
+   */
+  void join_with(const ZoneDomain& other) override {
+    join_like_operation(other, [](const Bound& x, const Bound& y) -> Bound {
+      if (!x || !y) {
+        return boost::none;
+      }
+      return std::max(*x, *y);
+    });
+  }
+
+  /*
+   * The standard widening drops the bounds that are not stable.
+   */
+  void widen_with(const ZoneDomain& other) override {
+    join_like_operation(other, [](const Bound& x, const Bound& y) -> Bound {
+      if (!x || !y || *y > *x) {
+        return boost::none;
+      }
+      return x;
+    });
+  }
+
+  void meet_with(const ZoneDomain& other) override {
+    meet_like_operation(other, [](const Bound& x, const Bound& y) -> Bound {
+      if (!x) {
+        return y;
+      }
+      if (!y) {
+        return x;
+      }
+      return std::min(*x, *y);
+    });
+  }
+
+  /*
+   * The standard narrowing only refines the unbounded differences.
+   */
+  void narrow_with(const ZoneDomain& other) override {
+    meet_like_operation(other, [](const Bound& x, const Bound& y) -> Bound {
+      return x ? x : y;
+    });
+  }
+
+  /*
+   * Returns the upper bound of x - y.
+   */
+  Bound get_difference_bound(const Variable& x, const Variable& y) const {
+    if (m_is_bottom) {
+      return boost::none;
+    }
+    auto i = index_of(x);
+    auto j = index_of(y);
+    if (!i || !j) {
+      return boost::none;
+    }
+    return at(*i, *j);
+  }
+
+  Bound get_upper_bound(const Variable& x) const {
+    auto i = index_of(x);
+    if (m_is_bottom || !i) {
+      return boost::none;
+    }
+    return at(*i, 0);
+  }
+
+  Bound get_lower_bound(const Variable& x) const {
+    auto i = index_of(x);
+    if (m_is_bottom || !i) {
+      return boost::none;
+    }
+    return negate(at(0, *i));
+  }
+
+  /*
+   * Returns true if x - y <= c holds in every state of the zone.
+   */
+  bool entails(const Variable& x, const Variable& y, Number c) const {
+    if (m_is_bottom) {
+      return true;
+    }
+    auto bound = get_difference_bound(x, y);
+    return bound && *bound <= c;
+  }
+
+  /*
+   * Adds the constraint x - y <= c.
+   */
+  ZoneDomain& add_constraint(const Variable& x, const Variable& y, Number c) {
+    if (m_is_bottom) {
+      return *this;
+    }
+    if (x == y) {
+      if (c < 0) {
+        set_to_bottom();
+      }
+      return *this;
+    }
+    size_t i = ensure_index(x);
+    size_t j = ensure_index(y);
+    tighten(i, j, c);
+    return *this;
+  }
+
+  /*
+   * Adds the constraint x <= c.
+   */
+  ZoneDomain& add_upper_bound(const Variable& x, Number c) {
+    if (!m_is_bottom) {
+      tighten(ensure_index(x), 0, c);
+    }
+    return *this;
+  }
+
+  /*
+   * Adds the constraint c <= x.
+   */
+  ZoneDomain& add_lower_bound(const Variable& x, Number c) {
+    auto bound = negate(c);
+    if (!m_is_bottom && bound) {
+      tighten(0, ensure_index(x), *bound);
+    }
+    return *this;
+  }
+
+  /*
+   * Removes all the constraints on a variable.
+   */
+  ZoneDomain& forget(const Variable& x) {
+    auto i = index_of(x);
+    if (m_is_bottom || !i) {
+      return *this;
+    }
+    // The projection of a closed zone is obtained by removing the row and the
+    // column of the variable.
+    close();
+    if (m_is_bottom) {
+      return *this;
+    }
+    remove_index(*i);
+    return *this;
+  }
+
+  /*
+   * x := c
+   */
+  ZoneDomain& assign(const Variable& x, Number c) {
+    forget(x);
+    add_upper_bound(x, c);
+    add_lower_bound(x, c);
+    return *this;
+  }
+
+  /*
+   * x := y + c
+   */
+  ZoneDomain& assign(const Variable& x, const Variable& y, Number c) {
+    if (m_is_bottom) {
+      return *this;
+    }
+    if (x == y) {
+      return shift(x, c);
+    }
+    forget(x);
+    add_constraint(x, y, c);
+    auto bound = negate(c);
+    if (bound) {
+      add_constraint(y, x, *bound);
+    }
+    return *this;
+  }
+
+  /*
+   * Computes the closure of the matrix. This is only needed after a widening
+   * (see the comment at the top of the class).
+   */
+  void close() {
+    if (m_is_bottom) {
+      return;
+    }
+    size_t n = dimension();
+    for (size_t k = 0; k < n; ++k) {
+      for (size_t i = 0; i < n; ++i) {
+        if (!at(i, k)) {
+          continue;
+        }
+        for (size_t j = 0; j < n; ++j) {
+          auto bound = add(at(i, k), at(k, j));
+          if (bound && (!at(i, j) || *bound < *at(i, j))) {
+            at(i, j) = bound;
+          }
+        }
+      }
+    }
+    check_consistency();
+  }
+
+  /*
+   * The variables that occur in the matrix.
+   */
+  const std::vector<Variable>& variables() const { return m_variables; }
+
+  friend std::ostream& operator<<(std::ostream& o, const ZoneDomain& zone) {
+    if (zone.is_bottom()) {
+      return o << "_|_";
+    }
+    if (zone.is_top()) {
+      return o << "T";
+    }
+    o << "{";
+    bool first = true;
+    auto separator = [&o, &first]() -> std::ostream& {
+      o << (first ? "" : ", ");
+      first = false;
+      return o;
+    };
+    size_t n = zone.dimension();
+    for (size_t i = 1; i < n; ++i) {
+      const auto& x = zone.m_variables[i - 1];
+      if (zone.at(0, i)) {
+        separator() << -*zone.at(0, i) << " <= " << x;
+      }
+      if (zone.at(i, 0)) {
+        separator() << x << " <= " << *zone.at(i, 0);
+      }
+      for (size_t j = 1; j < n; ++j) {
+        if (i != j && zone.at(i, j)) {
+          separator() << x << " - " << zone.m_variables[j - 1]
+                      << " <= " << *zone.at(i, j);
+        }
+      }
+    }
+    return o << "}";
+  }
+
+ private:
+  static constexpr Number MIN = std::numeric_limits<Number>::min();
+  static constexpr Number MAX = std::numeric_limits<Number>::max();
+
+  static Bound add(const Bound& x, const Bound& y) {
+    if (!x || !y) {
+      return boost::none;
+    }
+    Number a = *x;
+    Number b = *y;
+    if ((b > 0 && a > MAX - b) || (b < 0 && a < MIN - b)) {
+      return boost::none;
+    }
+    return Number(a + b);
+  }
+
+  static Bound negate(const Bound& x) {
+    if (!x || *x == MIN) {
+      return boost::none;
+    }
+    return Number(-*x);
+  }
+
+  // The number of rows and columns of the matrix, including the special
+  // variable 0.
+  size_t dimension() const { return m_variables.size() + 1; }
+
+  Bound& at(size_t i, size_t j) { return m_matrix[i * dimension() + j]; }
+
+  const Bound& at(size_t i, size_t j) const {
+    return m_matrix[i * dimension() + j];
+  }
+
+  boost::optional<size_t> index_of(const Variable& x) const {
+    auto it = m_indices.find(x);
+    if (it == m_indices.end()) {
+      return boost::none;
+    }
+    return it->second;
+  }
+
+  // Maps the indices of the other zone to the ones of this zone.
+  std::vector<boost::optional<size_t>> indices_in(
+      const ZoneDomain& other) const {
+    std::vector<boost::optional<size_t>> indices{size_t(0)};
+    for (const auto& x : other.m_variables) {
+      indices.push_back(index_of(x));
+    }
+    return indices;
+  }
+
+  void clear() {
+    m_variables.clear();
+    m_indices.clear();
+    m_matrix.assign(1, Number(0));
+  }
+
+  size_t ensure_index(const Variable& x) {
+    auto i = index_of(x);
+    if (i) {
+      return *i;
+    }
+    size_t n = dimension();
+    std::vector<Bound> matrix((n + 1) * (n + 1));
+    for (size_t i = 0; i < n; ++i) {
+      std::copy(m_matrix.begin() + i * n,
+                m_matrix.begin() + (i + 1) * n,
+                matrix.begin() + i * (n + 1));
+    }
+    matrix.back() = Number(0);
+    m_matrix = std::move(matrix);
+    m_variables.push_back(x);
+    m_indices.emplace(x, n);
+    return n;
+  }
+
+  void remove_index(size_t index) {
+    size_t n = dimension();
+    std::vector<Bound> matrix;
+    matrix.reserve((n - 1) * (n - 1));
+    for (size_t i = 0; i < n; ++i) {
+      for (size_t j = 0; j < n; ++j) {
+        if (i != index && j != index) {
+          matrix.push_back(at(i, j));
+        }
+      }
+    }
+    m_matrix = std::move(matrix);
+    m_variables.erase(m_variables.begin() + (index - 1));
+    m_indices.clear();
+    for (size_t i = 0; i < m_variables.size(); ++i) {
+      m_indices.emplace(m_variables[i], i + 1);
+    }
+  }
+
+  /*
+   * Adds the constraint v_i - v_j <= c to a closed matrix, and restores the
+   * closure in quadratic time.
+   */
+  void tighten(size_t i, size_t j, Number c) {
+    if (at(i, j) && *at(i, j) <= c) {
+      return;
+    }
+    size_t n = dimension();
+    // The new shortest paths go through the edge (i, j).
+    std::vector<Bound> to_i(n);
+    std::vector<Bound> from_j(n);
+    for (size_t k = 0; k < n; ++k) {
+      to_i[k] = at(k, i);
+      from_j[k] = at(j, k);
+    }
+    for (size_t a = 0; a < n; ++a) {
+      auto prefix = add(to_i[a], Bound(c));
+      if (!prefix) {
+        continue;
+      }
+      for (size_t b = 0; b < n; ++b) {
+        auto bound = add(prefix, from_j[b]);
+        if (bound && (!at(a, b) || *bound < *at(a, b))) {
+          at(a, b) = bound;
+        }
+      }
+    }
+    check_consistency();
+  }
+
+  /*
+   * x := x + c
+   */
+  ZoneDomain& shift(const Variable& x, Number c) {
+    auto index = index_of(x);
+    if (!index) {
+      return *this;
+    }
+    size_t n = dimension();
+    auto minus_c = negate(c);
+    for (size_t k = 0; k < n; ++k) {
+      if (k == *index) {
+        continue;
+      }
+      // (x + c) - v_k = (x - v_k) + c and v_k - (x + c) = (v_k - x) - c.
+      at(*index, k) = add(at(*index, k), Bound(c));
+      at(k, *index) = minus_c ? add(at(k, *index), minus_c) : boost::none;
+    }
+    return *this;
+  }
+
+  // A closed matrix is inconsistent iff it has a negative cycle, i.e., a
+  // negative entry on the diagonal.
+  void check_consistency() {
+    size_t n = dimension();
+    for (size_t i = 0; i < n; ++i) {
+      if (*at(i, i) < 0) {
+        set_to_bottom();
+        return;
+      }
+    }
+  }
+
+  template <typename Operation>
+  void join_like_operation(const ZoneDomain& other, Operation operation) {
+    if (other.m_is_bottom) {
+      return;
+    }
+    if (m_is_bottom) {
+      *this = other;
+      return;
+    }
+    // Only the variables that are constrained in both zones are kept.
+    for (size_t i = m_variables.size(); i > 0; --i) {
+      if (!other.index_of(m_variables[i - 1])) {
+        remove_index(i);
+      }
+    }
+    auto indices = other.indices_in(*this);
+    size_t n = dimension();
+    for (size_t i = 0; i < n; ++i) {
+      for (size_t j = 0; j < n; ++j) {
+        if (i != j) {
+          at(i, j) = operation(at(i, j), other.at(*indices[i], *indices[j]));
+        }
+      }
+    }
+  }
+
+  template <typename Operation>
+  void meet_like_operation(const ZoneDomain& other, Operation operation) {
+    if (m_is_bottom) {
+      return;
+    }
+    if (other.m_is_bottom) {
+      set_to_bottom();
+      return;
+    }
+    for (const auto& x : other.m_variables) {
+      ensure_index(x);
+    }
+    auto indices = indices_in(other);
+    size_t n = other.dimension();
+    for (size_t i = 0; i < n; ++i) {
+      for (size_t j = 0; j < n; ++j) {
+        if (i != j) {
+          auto& bound = at(*indices[i], *indices[j]);
+          bound = operation(bound, other.at(i, j));
+        }
+      }
+    }
+    close();
+  }
+
+  bool m_is_bottom{false};
+  std::vector<Variable> m_variables;
+  std::unordered_map<Variable, size_t, VariableHash> m_indices;
+  // The matrix is stored in row-major order.
+  std::vector<Bound> m_matrix{Bound(Number(0))};
+};
+
+} // namespace sparta
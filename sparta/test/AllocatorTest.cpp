@@ -0,0 +1,144 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <atomic>
+#include <cstddef>
+#include <functional>
+#include <gtest/gtest.h>
+#include <memory_resource>
+#include <string>
+#include <utility>
+
+#include "ConstantAbstractDomain.h"
+#include "HashedAbstractEnvironment.h"
+#include "HashedAbstractPartition.h"
+#include "HashedSetAbstractDomain.h"
+#include "PatriciaTreeAllocator.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+#include "PatriciaTreeSetAbstractDomain.h"
+
+using namespace sparta;
+
+namespace {
+
+/*
+ * Counts the blocks that are currently allocated from the upstream resource.
+ */
+class CountingResource final : public std::pmr::memory_resource {
+ public:
+  size_t num_allocations() const { return m_num_allocations; }
+
+  size_t num_live_blocks() const { return m_num_live_blocks; }
+
+ private:
+  void* do_allocate(size_t bytes, size_t alignment) override {
+    ++m_num_allocations;
+    ++m_num_live_blocks;
+    return std::pmr::new_delete_resource()->allocate(bytes, alignment);
+  }
+
+  void do_deallocate(void* p, size_t bytes, size_t alignment) override {
+    --m_num_live_blocks;
+    std::pmr::new_delete_resource()->deallocate(p, bytes, alignment);
+  }
+
+  bool do_is_equal(const std::pmr::memory_resource& other) const
+      noexcept override {
+    return this == &other;
+  }
+
+  std::atomic<size_t> m_num_allocations{0};
+  std::atomic<size_t> m_num_live_blocks{0};
+};
+
+using Constant = ConstantAbstractDomain<int>;
+
+} // namespace
+
+TEST(AllocatorTest, patriciaTreeNodes) {
+  CountingResource resource;
+  {
+    ScopedPatriciaTreeAllocator scope(&resource);
+    EXPECT_EQ(&resource, PatriciaTreeAllocator::resource());
+
+    PatriciaTreeSetAbstractDomain<uint32_t> s1{1, 2, 3};
+    PatriciaTreeSetAbstractDomain<uint32_t> s2{3, 4};
+    s1.join_with(s2);
+    EXPECT_EQ(4, s1.size());
+
+    PatriciaTreeMapAbstractEnvironment<uint32_t, Constant> env1{
+        {1, Constant(1)}, {2, Constant(2)}};
+    auto env2 = env1;
+    env2.set(2, Constant(3));
+    env1.join_with(env2);
+    EXPECT_EQ(Constant(1), env1.get(1));
+    EXPECT_TRUE(env1.get(2).is_top());
+
+    EXPECT_GT(resource.num_allocations(), 0);
+    EXPECT_GT(resource.num_live_blocks(), 0);
+  }
+  // All the nodes have been returned to the resource.
+  EXPECT_EQ(0, resource.num_live_blocks());
+  EXPECT_EQ(std::pmr::new_delete_resource(), PatriciaTreeAllocator::resource());
+
+  // An arena can be released at once when all the trees are gone.
+  std::pmr::monotonic_buffer_resource arena;
+  {
+    ScopedPatriciaTreeAllocator scope(&arena);
+    PatriciaTreeSetAbstractDomain<uint32_t> s;
+    for (uint32_t i = 0; i < 1000; ++i) {
+      s.add(i);
+    }
+    EXPECT_EQ(1000, s.size());
+  }
+  arena.release();
+}
+
+TEST(AllocatorTest, hashedContainers) {
+  CountingResource resource;
+  auto* previous = std::pmr::set_default_resource(&resource);
+  {
+    using Set = HashedSetAbstractDomain<int,
+                                        std::hash<int>,
+                                        std::equal_to<int>,
+                                        std::pmr::polymorphic_allocator<int>>;
+    Set s1{1, 2};
+    Set s2{2, 3};
+    s1.join_with(s2);
+    EXPECT_EQ(3, s1.size());
+    EXPECT_TRUE(s1.contains(3));
+
+    using Environment = HashedAbstractEnvironment<
+        std::string,
+        Constant,
+        std::hash<std::string>,
+        std::equal_to<std::string>,
+        std::pmr::polymorphic_allocator<
+            std::pair<const std::string, Constant>>>;
+    Environment e1{{"x", Constant(1)}, {"y", Constant(2)}};
+    Environment e2{{"x", Constant(1)}, {"y", Constant(3)}};
+    e1.join_with(e2);
+    EXPECT_EQ(Constant(1), e1.get("x"));
+    EXPECT_TRUE(e1.get("y").is_top());
+
+    using Partition = HashedAbstractPartition<
+        int,
+        Constant,
+        std::hash<int>,
+        std::equal_to<int>,
+        /* MaxLabels */ 0,
+        std::pmr::polymorphic_allocator<std::pair<const int, Constant>>>;
+    Partition p1{{1, Constant(1)}};
+    Partition p2{{2, Constant(2)}};
+    p1.join_with(p2);
+    EXPECT_EQ(2, p1.size());
+
+    EXPECT_GT(resource.num_allocations(), 0);
+  }
+  std::pmr::set_default_resource(previous);
+  EXPECT_EQ(0, resource.num_live_blocks());
+}
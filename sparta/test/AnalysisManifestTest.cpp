@@ -0,0 +1,172 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "AnalysisManifest.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+#include <unordered_set>
+
+#include "IntervalDomain.h"
+#include "MemoryCeiling.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+template <template <typename, typename, typename> class Iterator>
+class Analyzer final
+    : public Iterator<GraphInterface, Interval, std::hash<uint32_t>> {
+ public:
+  using Iterator<GraphInterface, Interval, std::hash<uint32_t>>::Iterator;
+
+  void analyze_node(const uint32_t&, Interval*) const override {}
+
+  Interval analyze_edge(const size_t&, const Interval& state) const override {
+    return state;
+  }
+};
+
+struct DescribedDomain {
+  static void describe(AnalysisManifest* manifest) {
+    manifest->set("name", "intervals").set("bits", 32);
+  }
+};
+
+} // namespace
+
+TEST(AnalysisManifestTest, json) {
+  AnalysisManifest manifest;
+  EXPECT_EQ("{}", manifest.to_json());
+  manifest.set("name", "a \"quoted\" \\ name\n")
+      .set("enabled", true)
+      .set("count", 3)
+      .set("ratio", 0.5);
+  manifest.section("nested").set("depth", -1).set_null("limit");
+  manifest.section("empty");
+  EXPECT_EQ(
+      "{\n"
+      "  \"name\": \"a \\\"quoted\\\" \\\\ name\\n\",\n"
+      "  \"enabled\": true,\n"
+      "  \"count\": 3,\n"
+      "  \"ratio\": 0.5,\n"
+      "  \"nested\": {\n"
+      "    \"depth\": -1,\n"
+      "    \"limit\": null\n"
+      "  },\n"
+      "  \"empty\": {}\n"
+      "}",
+      manifest.to_json());
+
+  // Setting a key again keeps its position.
+  AnalysisManifest copy = manifest;
+  EXPECT_EQ(manifest, copy);
+  copy.set("count", 4);
+  EXPECT_NE(manifest, copy);
+  EXPECT_EQ("4", copy.get("count"));
+  EXPECT_EQ("3", manifest.get("count"));
+  EXPECT_EQ("{\n  \"depth\": -1,\n  \"limit\": null\n}", copy.get("nested"));
+  copy.set("nested", false);
+  EXPECT_EQ("false", copy.get("nested"));
+  EXPECT_FALSE(copy.contains("missing"));
+  EXPECT_EQ("", copy.get("missing"));
+
+  std::ostringstream out;
+  copy.write_json(out);
+  EXPECT_EQ(0, out.str().find("{\n  \"name\""));
+  EXPECT_NE(std::string::npos, out.str().find("\"count\": 4,\n  \"ratio\""));
+}
+
+TEST(AnalysisManifestTest, versions) {
+  AnalysisManifest manifest = make_manifest("constant-propagation");
+  EXPECT_EQ(std::to_string(AnalysisManifest::FORMAT_VERSION),
+            manifest.get("format_version"));
+  EXPECT_EQ("\"constant-propagation\"", manifest.get("analysis"));
+  EXPECT_TRUE(manifest.contains("cplusplus"));
+
+  describe_domain<DescribedDomain>(&manifest.section("domain"));
+  EXPECT_EQ("{\n  \"name\": \"intervals\",\n  \"bits\": 32\n}",
+            manifest.get("domain"));
+  // Domains that don't describe themselves are ignored.
+  describe_domain<Interval>(&manifest.section("other"));
+  EXPECT_EQ("{}", manifest.get("other"));
+}
+
+TEST(AnalysisManifestTest, iterators) {
+  Graph graph;
+  graph.add_edge(0, 1);
+
+  Analyzer<MonotonicFixpointIterator> wpo(graph);
+  AnalysisManifest manifest;
+  wpo.describe(&manifest);
+  EXPECT_EQ("\"wpo\"", manifest.get("iterator"));
+  EXPECT_EQ(
+      "{\n"
+      "  \"extrapolation\": \"default\",\n"
+      "  \"initial_nodes\": false,\n"
+      "  \"head_digest\": false,\n"
+      "  \"dead_bindings\": false,\n"
+      "  \"state_compression\": false\n"
+      "}",
+      manifest.get("strategy"));
+  EXPECT_EQ(
+      "{\n"
+      "  \"memory_ceiling\": null,\n"
+      "  \"parallel_join_threshold\": 0,\n"
+      "  \"parallel_join_threads\": 1,\n"
+      "  \"state_history_depth\": 0\n"
+      "}",
+      manifest.get("budgets"));
+
+  MemoryCeiling ceiling(1000);
+  wpo.set_memory_ceiling(&ceiling);
+  wpo.set_state_history(4);
+  wpo.set_widening_points(std::unordered_set<uint32_t>{1, 2});
+  wpo.describe(&manifest);
+  EXPECT_EQ("1000", manifest.section("budgets").get("memory_ceiling"));
+  EXPECT_EQ("4", manifest.section("budgets").get("state_history_depth"));
+  EXPECT_EQ("\"widening_points\"",
+            manifest.section("strategy").get("extrapolation"));
+  EXPECT_EQ("2", manifest.section("strategy").get("num_widening_points"));
+
+  GrowthRateWideningOptions options;
+  options.patience = 5;
+  wpo.set_growth_rate_widening(options);
+  wpo.describe(&manifest);
+  EXPECT_EQ("\"growth_rate\"",
+            manifest.section("strategy").get("extrapolation"));
+  EXPECT_EQ(
+      "{\n"
+      "  \"max_joins\": 2,\n"
+      "  \"widening_delay\": 2,\n"
+      "  \"patience\": 5,\n"
+      "  \"max_growth_ratio\": 2\n"
+      "}",
+      manifest.section("strategy").get("growth_rate"));
+
+  Analyzer<WTOMonotonicFixpointIterator> wto(graph);
+  AnalysisManifest wto_manifest;
+  wto.describe(&wto_manifest);
+  EXPECT_EQ("\"wto\"", wto_manifest.get("iterator"));
+
+  Analyzer<ParallelMonotonicFixpointIterator> parallel(graph, 3);
+  parallel.set_parallel_join_threshold(16);
+  AnalysisManifest parallel_manifest;
+  parallel.describe(&parallel_manifest);
+  EXPECT_EQ("\"parallel_wpo\"", parallel_manifest.get("iterator"));
+  EXPECT_EQ("3", parallel_manifest.get("num_threads"));
+  EXPECT_EQ("16",
+            parallel_manifest.section("budgets").get(
+                "parallel_join_threshold"));
+  EXPECT_EQ("3",
+            parallel_manifest.section("budgets").get("parallel_join_threads"));
+}
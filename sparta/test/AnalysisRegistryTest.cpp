@@ -0,0 +1,167 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "AnalysisRegistry.h"
+
+#include <gtest/gtest.h>
+#include <string>
+#include <utility>
+#include <vector>
+
+#include "HashedSetAbstractDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Domain = HashedSetAbstractDomain<uint32_t>;
+
+/*
+ * Collects the nodes visited along the paths that lead to a node.
+ */
+template <template <typename, typename, typename> class Iterator>
+class PathAnalyzer final
+    : public Iterator<GraphInterface, Domain, std::hash<uint32_t>> {
+ public:
+  using Iterator<GraphInterface, Domain, std::hash<uint32_t>>::Iterator;
+
+  void analyze_node(const uint32_t& node, Domain* state) const override {
+    state->add(node);
+  }
+
+  Domain analyze_edge(const size_t&, const Domain& state) const override {
+    return state;
+  }
+};
+
+/*
+ * Only keeps the nodes visited along the edges that go forward.
+ */
+class ForwardPathAnalyzer final
+    : public MonotonicFixpointIterator<GraphInterface, Domain> {
+ public:
+  using MonotonicFixpointIterator::MonotonicFixpointIterator;
+
+  void analyze_node(const uint32_t& node, Domain* state) const override {
+    ++m_num_visits;
+    state->add(node);
+  }
+
+  Domain analyze_edge(const size_t& edge, const Domain& state) const override {
+    const auto& graph = this->m_graph;
+    if (GraphInterface::source(graph, edge) <
+        GraphInterface::target(graph, edge)) {
+      return state;
+    }
+    return Domain();
+  }
+
+  size_t num_visits() const { return m_num_visits; }
+
+ private:
+  mutable size_t m_num_visits{0};
+};
+
+using Registry = AnalysisRegistry<GraphInterface, Domain>;
+
+AnalysisRegistration<PathAnalyzer<MonotonicFixpointIterator>> s_sequential(
+    "paths");
+AnalysisRegistration<PathAnalyzer<ParallelMonotonicFixpointIterator>>
+    s_parallel("parallel-paths", /* num_thread */ 4);
+AnalysisRegistration<ForwardPathAnalyzer> s_forward("forward-paths");
+
+Graph make_graph() {
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 1);
+  graph.add_edge(2, 3);
+  return graph;
+}
+
+} // namespace
+
+TEST(AnalysisRegistryTest, globalRegistry) {
+  EXPECT_EQ(
+      std::vector<std::string>({"forward-paths", "parallel-paths", "paths"}),
+      Registry::global().names());
+  EXPECT_TRUE(Registry::global().contains("paths"));
+  EXPECT_FALSE(Registry::global().contains("liveness"));
+
+  Graph graph = make_graph();
+  for (const auto& name : Registry::global().names()) {
+    auto engine = Registry::global().create(name, graph);
+    engine->run(Domain());
+    EXPECT_EQ(Domain({0, 1, 2}), engine->get_entry_state_at(3)) << name;
+    EXPECT_EQ(Domain({0, 1, 2, 3}), engine->get_exit_state_at(3)) << name;
+    EXPECT_EQ(name == "forward-paths" ? Domain({0}) : Domain({0, 1, 2}),
+              engine->get_entry_state_at(1))
+        << name;
+  }
+}
+
+TEST(AnalysisRegistryTest, errors) {
+  Graph graph = make_graph();
+  EXPECT_THROW(Registry::global().create("liveness", graph), invalid_argument);
+  EXPECT_THROW(Registry::global().register_iterator<ForwardPathAnalyzer>(
+                   "forward-paths"),
+               invalid_argument);
+}
+
+TEST(AnalysisRegistryTest, localRegistry) {
+  Registry registry;
+  EXPECT_TRUE(registry.names().empty());
+  registry.register_analysis("paths", [](const Graph& graph) {
+    return std::unique_ptr<Registry::Engine>(
+        new AnalysisEngineAdapter<ForwardPathAnalyzer>(graph));
+  });
+  EXPECT_EQ(std::vector<std::string>({"paths"}), registry.names());
+
+  Graph graph = make_graph();
+  auto engine = registry.create("paths", graph);
+  engine->run(Domain({42}));
+  EXPECT_EQ(Domain({0, 1, 2, 3, 42}), engine->get_exit_state_at(3));
+}
+
+TEST(AnalysisRegistryTest, iteratorAccess) {
+  Graph graph = make_graph();
+  auto engine = Registry::global().create("forward-paths", graph);
+  engine->run(Domain());
+  EXPECT_EQ(nullptr,
+            engine->iterator_as<PathAnalyzer<MonotonicFixpointIterator>>());
+  ForwardPathAnalyzer* analyzer = engine->iterator_as<ForwardPathAnalyzer>();
+  ASSERT_NE(nullptr, analyzer);
+  size_t num_visits = analyzer->num_visits();
+  EXPECT_LE(4, num_visits);
+  const auto& const_engine = *engine;
+  EXPECT_EQ(analyzer, const_engine.iterator_as<ForwardPathAnalyzer>());
+
+  auto released = release_iterator<ForwardPathAnalyzer>(std::move(engine));
+  EXPECT_EQ(analyzer, released.get());
+  EXPECT_EQ(num_visits, released->num_visits());
+  EXPECT_EQ(Domain({0, 1, 2, 3}), released->get_exit_state_at(3));
+
+  EXPECT_THROW(
+      release_iterator<ForwardPathAnalyzer>(Registry::global().create(
+          "paths", graph)),
+      invalid_argument);
+}
+
+TEST(AnalysisRegistryTest, manifest) {
+  Graph graph = make_graph();
+  AnalysisManifest manifest = make_manifest("parallel-paths");
+  Registry::global().create("parallel-paths", graph)->describe(&manifest);
+  EXPECT_EQ("\"parallel-paths\"", manifest.get("analysis"));
+  EXPECT_EQ("\"parallel_wpo\"", manifest.get("iterator"));
+  EXPECT_EQ("4", manifest.get("num_threads"));
+  EXPECT_TRUE(manifest.contains("strategy"));
+  EXPECT_TRUE(manifest.contains("budgets"));
+  // The domain doesn't describe itself.
+  EXPECT_FALSE(manifest.contains("domain"));
+}
@@ -0,0 +1,123 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "ArrayAbstractEnvironment.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+#include "IntervalDomain.h"
+
+using namespace sparta;
+
+using Interval = IntervalDomain<int64_t>;
+
+// Small chunks exercise the boundaries between chunks.
+using Environment = ArrayAbstractEnvironment<Interval, 2>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(ArrayAbstractEnvironment,
+                              AbstractDomainPropertyTest,
+                              Environment);
+
+template <>
+std::vector<Environment>
+AbstractDomainPropertyTest<Environment>::non_extremal_values() {
+  Environment e1({Interval::finite(0, 1), Interval::finite(2, 3)});
+  Environment e2;
+  e2.set(4, Interval::finite(1, 10));
+  Environment e3({Interval::finite(-1, 1), Interval::top(),
+                  Interval::finite(0, 0)});
+  Environment e4;
+  e4.set(0, Interval::finite(0, 0)).set(5, Interval::finite(5, 5));
+  return {e1, e2, e3, e4};
+}
+
+TEST(ArrayAbstractEnvironmentTest, getAndSet) {
+  Environment env;
+  EXPECT_TRUE(env.is_top());
+  EXPECT_EQ(0, env.capacity());
+  EXPECT_TRUE(env.get(100).is_top());
+
+  // Binding an index to Top does not allocate anything.
+  env.set(7, Interval::top());
+  EXPECT_EQ(0, env.capacity());
+
+  env.set(3, Interval::finite(1, 2));
+  EXPECT_FALSE(env.is_top());
+  EXPECT_EQ(4, env.capacity());
+  EXPECT_EQ(Interval::finite(1, 2), env.get(3));
+  EXPECT_TRUE(env.get(0).is_top());
+  EXPECT_TRUE(env.get(4).is_top());
+
+  env.update(3, [](Interval* value) { *value += 1; });
+  EXPECT_EQ(Interval::finite(2, 3), env.get(3));
+
+  env.set(3, Interval::top());
+  EXPECT_TRUE(env.is_top());
+  EXPECT_TRUE(env.equals(Environment::top()));
+
+  env.set(1, Interval::bottom());
+  EXPECT_TRUE(env.is_bottom());
+  EXPECT_TRUE(env.get(1).is_bottom());
+  EXPECT_TRUE(env.get(100).is_bottom());
+  env.set(1, Interval::finite(0, 0));
+  EXPECT_TRUE(env.is_bottom());
+
+  std::ostringstream out;
+  out << Environment({Interval::finite(0, 0), Interval::top(),
+                      Interval::finite(1, 2)});
+  EXPECT_EQ("{0 -> [0, 0], 2 -> [1, 2]}", out.str());
+}
+
+TEST(ArrayAbstractEnvironmentTest, copyOnWrite) {
+  Environment e1({Interval::finite(0, 0), Interval::finite(1, 1),
+                  Interval::finite(2, 2), Interval::finite(3, 3)});
+  Environment e2 = e1;
+  e2.set(0, Interval::finite(10, 10));
+  EXPECT_EQ(Interval::finite(0, 0), e1.get(0));
+  EXPECT_EQ(Interval::finite(10, 10), e2.get(0));
+  EXPECT_EQ(Interval::finite(1, 1), e2.get(1));
+  EXPECT_EQ(Interval::finite(3, 3), e2.get(3));
+  EXPECT_FALSE(e1.equals(e2));
+
+  e2.set(0, Interval::finite(0, 0));
+  EXPECT_TRUE(e1.equals(e2));
+  EXPECT_TRUE(e1.leq(e2));
+  EXPECT_TRUE(e2.leq(e1));
+}
+
+TEST(ArrayAbstractEnvironmentTest, latticeOperations) {
+  Environment e1({Interval::finite(0, 0), Interval::finite(1, 1),
+                  Interval::finite(2, 2)});
+  Environment e2({Interval::finite(5, 5), Interval::finite(1, 1)});
+
+  // The indices that are missing in e2 are bound to Top.
+  Environment join = e1.join(e2);
+  EXPECT_EQ(Interval::finite(0, 5), join.get(0));
+  EXPECT_EQ(Interval::finite(1, 1), join.get(1));
+  EXPECT_TRUE(join.get(2).is_top());
+  EXPECT_TRUE(e1.leq(join));
+  EXPECT_TRUE(e2.leq(join));
+
+  Environment e3;
+  e3.set(0, Interval::finite(0, 10)).set(4, Interval::finite(4, 4));
+  Environment meet = e1.meet(e3);
+  EXPECT_EQ(Interval::finite(0, 0), meet.get(0));
+  EXPECT_EQ(Interval::finite(2, 2), meet.get(2));
+  EXPECT_EQ(Interval::finite(4, 4), meet.get(4));
+  EXPECT_TRUE(meet.leq(e1));
+  EXPECT_TRUE(meet.leq(e3));
+
+  EXPECT_TRUE(e1.meet(e2).is_bottom());
+
+  Environment widened = e1.widening(e1.join(Environment(
+      {Interval::finite(0, 1), Interval::finite(1, 1),
+       Interval::finite(2, 2)})));
+  EXPECT_EQ(Interval::bounded_below(0), widened.get(0));
+  EXPECT_EQ(Interval::finite(2, 2), widened.get(2));
+}
@@ -0,0 +1,192 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "AutomatonProduct.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <utility>
+#include <vector>
+
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+enum class Operation { None, Open, Read, Close };
+
+/*
+ * A control-flow graph where each node performs an operation on a file. Node
+ * 0 is the entry.
+ */
+class Program final : public Graph {
+ public:
+  explicit Program(std::vector<Operation> operations)
+      : m_operations(std::move(operations)) {}
+
+  Operation operation(uint32_t node) const { return m_operations[node]; }
+
+  size_t size() const { return m_operations.size(); }
+
+ private:
+  std::vector<Operation> m_operations;
+};
+
+class ProgramInterface : public GraphInterface {
+ public:
+  using Graph = Program;
+
+  static size_t size_hint(const Graph& graph) { return graph.size(); }
+};
+
+enum class FileState { Closed, Open, Error };
+
+/*
+ * A file must be open when it is read. Any misuse leads to the error state,
+ * which is a sink.
+ */
+class FileAutomaton {
+ public:
+  using State = FileState;
+
+  explicit FileAutomaton(const Program& program) : m_program(program) {}
+
+  State initial() const { return FileState::Closed; }
+
+  std::vector<State> states() const {
+    return {FileState::Closed, FileState::Open, FileState::Error};
+  }
+
+  State transition(const State& state, uint32_t node) const {
+    if (state == FileState::Error) {
+      return FileState::Error;
+    }
+    switch (m_program.operation(node)) {
+    case Operation::None:
+      return state;
+    case Operation::Open:
+      return state == FileState::Closed ? FileState::Open : FileState::Error;
+    case Operation::Read:
+      return state == FileState::Open ? FileState::Open : FileState::Error;
+    case Operation::Close:
+      return state == FileState::Open ? FileState::Closed : FileState::Error;
+    }
+    return FileState::Error;
+  }
+
+  bool is_accepting(const State& state) const {
+    return state == FileState::Error;
+  }
+
+ private:
+  const Program& m_program;
+};
+
+using Analysis = AutomatonProductAnalysis<ProgramInterface, FileAutomaton>;
+
+} // namespace
+
+TEST(AutomatonProductTest, correctUsage) {
+  /*
+   *   0: open
+   *   1: read <-+
+   *   2: nop  --+
+   *   3: close
+   */
+  Program program({Operation::Open, Operation::Read, Operation::None,
+                   Operation::Close});
+  program.add_edge(0, 1);
+  program.add_edge(1, 2);
+  program.add_edge(2, 1);
+  program.add_edge(2, 3);
+  FileAutomaton automaton(program);
+
+  Analysis analysis(program, automaton);
+  analysis.run();
+
+  using ::testing::ElementsAre;
+  EXPECT_THAT(analysis.reachable_states(0), ElementsAre(FileState::Closed));
+  EXPECT_THAT(analysis.reachable_states(1), ElementsAre(FileState::Open));
+  EXPECT_THAT(analysis.reachable_states(2), ElementsAre(FileState::Open));
+  EXPECT_THAT(analysis.reachable_states(3), ElementsAre(FileState::Open));
+  for (uint32_t node = 0; node < 4; ++node) {
+    EXPECT_FALSE(analysis.may_reach_accepting_state(node));
+  }
+}
+
+TEST(AutomatonProductTest, readAfterClose) {
+  /*
+   *   0: open
+   *   1: nop   <----+
+   *   2: close      |
+   *   3: read  -----+
+   *   4: nop
+   *
+   * with the edges 1 -> 2, 1 -> 3, 2 -> 3, 3 -> 1 and 3 -> 4.
+   */
+  Program program({Operation::Open, Operation::None, Operation::Close,
+                   Operation::Read, Operation::None});
+  program.add_edge(0, 1);
+  program.add_edge(1, 2);
+  program.add_edge(1, 3);
+  program.add_edge(2, 3);
+  program.add_edge(3, 1);
+  program.add_edge(3, 4);
+  FileAutomaton automaton(program);
+
+  Analysis analysis(program, automaton);
+  analysis.run();
+
+  using ::testing::ElementsAre;
+  EXPECT_THAT(analysis.reachable_states(0), ElementsAre(FileState::Closed));
+  EXPECT_THAT(analysis.reachable_states(1),
+              ElementsAre(FileState::Open, FileState::Error));
+  EXPECT_THAT(analysis.reachable_states(2),
+              ElementsAre(FileState::Open, FileState::Error));
+  EXPECT_THAT(
+      analysis.reachable_states(3),
+      ElementsAre(FileState::Closed, FileState::Open, FileState::Error));
+  EXPECT_THAT(analysis.reachable_states(4),
+              ElementsAre(FileState::Open, FileState::Error));
+
+  EXPECT_FALSE(analysis.may_reach_accepting_state(0));
+  for (uint32_t node = 1; node < 5; ++node) {
+    EXPECT_TRUE(analysis.may_reach_accepting_state(node));
+  }
+}
+
+TEST(AutomatonProductTest, productInterface) {
+  using Interface = AutomatonProductInterface<ProgramInterface, FileAutomaton>;
+  Program program({Operation::Open, Operation::Close});
+  program.add_edge(0, 1);
+  FileAutomaton automaton(program);
+  Interface::Graph product(program, automaton);
+
+  EXPECT_EQ(6, Interface::size_hint(product));
+  auto entry = Interface::entry(product);
+  EXPECT_EQ(std::make_pair(0u, FileState::Closed), entry);
+
+  auto successors = Interface::successors(product, entry);
+  ASSERT_EQ(1, successors.size());
+  EXPECT_EQ(entry, Interface::source(product, successors[0]));
+  EXPECT_EQ(std::make_pair(1u, FileState::Open),
+            Interface::target(product, successors[0]));
+
+  // Only the closed state leads to the open state through node 0.
+  auto predecessors =
+      Interface::predecessors(product, std::make_pair(1u, FileState::Open));
+  ASSERT_EQ(1, predecessors.size());
+  EXPECT_EQ(entry, Interface::source(product, predecessors[0]));
+  EXPECT_TRUE(
+      Interface::predecessors(product, std::make_pair(1u, FileState::Closed))
+          .empty());
+  EXPECT_EQ(2,
+            Interface::predecessors(product,
+                                    std::make_pair(1u, FileState::Error))
+                .size());
+}
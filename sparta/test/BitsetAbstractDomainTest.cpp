@@ -0,0 +1,71 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "BitsetAbstractDomain.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <sstream>
+#include <vector>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using Domain = BitsetAbstractDomain<100>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(BitsetAbstractDomain,
+                              AbstractDomainPropertyTest,
+                              Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  Domain e1(1);
+  Domain e2({1, 2, 3});
+  Domain e3({2, 3, 99});
+  return {e1, e2, e3, Domain()};
+}
+
+TEST(BitsetAbstractDomainTest, latticeOperations) {
+  Domain e1(1);
+  Domain e2({1, 2, 3});
+  Domain e3({2, 3, 99});
+
+  EXPECT_THAT(e2.elements(), ::testing::ElementsAre(1, 2, 3));
+  EXPECT_EQ(3, e3.size());
+  EXPECT_TRUE(e1.leq(e2));
+  EXPECT_FALSE(e2.leq(e3));
+  EXPECT_THAT(e2.join(e3).elements(), ::testing::ElementsAre(1, 2, 3, 99));
+  EXPECT_THAT(e2.meet(e3).elements(), ::testing::ElementsAre(2, 3));
+  EXPECT_TRUE(e1.meet(e3).empty());
+  EXPECT_FALSE(e1.meet(e3).is_bottom());
+  EXPECT_TRUE(e1.meet(Domain::bottom()).is_bottom());
+
+  Domain d = e2;
+  d.difference_with(e3);
+  EXPECT_EQ(e1, d);
+
+  Domain all;
+  for (size_t i = 0; i < Domain::universe_size(); ++i) {
+    all.add(i);
+  }
+  EXPECT_TRUE(all.is_top());
+  all.remove(42);
+  EXPECT_FALSE(all.is_top());
+  EXPECT_FALSE(all.contains(42));
+  EXPECT_EQ(99, all.size());
+
+  EXPECT_TRUE(Domain::bottom().add(1).is_bottom());
+  EXPECT_THROW(e1.add(100), invalid_argument);
+}
+
+TEST(BitsetAbstractDomainTest, printing) {
+  std::ostringstream out;
+  out << Domain({3, 1}) << " " << Domain() << " " << Domain::top() << " "
+      << Domain::bottom();
+  EXPECT_EQ("{1, 3} {} T _|_", out.str());
+}
@@ -0,0 +1,98 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "BitsetDomain.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+#include <vector>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+INSTANTIATE_TYPED_TEST_CASE_P(BitsetDomain,
+                              AbstractDomainPropertyTest,
+                              BitsetDomain);
+
+template <>
+std::vector<BitsetDomain>
+AbstractDomainPropertyTest<BitsetDomain>::non_extremal_values() {
+  BitsetDomain e1(1);
+  BitsetDomain e2({1, 2, 3});
+  BitsetDomain e3({2, 3, 200});
+  return {e1, e2, e3, BitsetDomain()};
+}
+
+TEST(BitsetDomainTest, latticeOperations) {
+  BitsetDomain e1(1);
+  BitsetDomain e2({1, 2, 3});
+  BitsetDomain e3({2, 3, 200});
+
+  EXPECT_THAT(e3.elements(), ::testing::ElementsAre(2, 3, 200));
+  EXPECT_EQ(3, e3.size());
+  EXPECT_TRUE(e1.leq(e2));
+  EXPECT_FALSE(e3.leq(e2));
+  EXPECT_FALSE(e2.leq(e3));
+  EXPECT_THAT(e2.join(e3).elements(), ::testing::ElementsAre(1, 2, 3, 200));
+  EXPECT_THAT(e2.meet(e3).elements(), ::testing::ElementsAre(2, 3));
+  EXPECT_EQ(0, e1.meet(e3).size());
+  EXPECT_FALSE(e1.meet(e3).is_bottom());
+  EXPECT_TRUE(BitsetDomain::top().contains(1000));
+
+  // The representation doesn't depend on the elements that were removed.
+  BitsetDomain d = e3;
+  d.remove(200);
+  EXPECT_EQ(BitsetDomain({2, 3}), d);
+  d.add(64);
+  d.difference_with(BitsetDomain({64, 100}));
+  EXPECT_EQ(e2.meet(e3), d);
+  EXPECT_TRUE(e3.meet(BitsetDomain(200)).equals(BitsetDomain(200)));
+  EXPECT_TRUE(e3.meet(BitsetDomain(200)).leq(BitsetDomain({200})));
+}
+
+TEST(BitsetDomainTest, genKill) {
+  BitsetDomain x({1, 2, 70});
+  x.apply_gen_kill(BitsetDomain({3, 128}), BitsetDomain({2, 70, 128}));
+  EXPECT_THAT(x.elements(), ::testing::ElementsAre(1, 3, 128));
+
+  BitsetDomain top = BitsetDomain::top();
+  EXPECT_TRUE(top.apply_gen_kill(BitsetDomain(), BitsetDomain(1)).is_top());
+  BitsetDomain bottom = BitsetDomain::bottom();
+  EXPECT_TRUE(
+      bottom.apply_gen_kill(BitsetDomain(1), BitsetDomain()).is_bottom());
+  EXPECT_THROW(x.apply_gen_kill(BitsetDomain::top(), BitsetDomain()),
+               invalid_abstract_value);
+}
+
+TEST(BitsetDomainTest, factNumberer) {
+  FactNumberer<std::string> variables;
+  EXPECT_EQ(0, variables.number("x"));
+  EXPECT_EQ(1, variables.number("y"));
+  EXPECT_EQ(0, variables.number("x"));
+  EXPECT_EQ(1, *variables.find("y"));
+  EXPECT_FALSE(variables.find("z"));
+  EXPECT_EQ(2, variables.size());
+
+  BitsetDomain s = variables.encode({"z", "x"});
+  EXPECT_EQ(BitsetDomain({0, 2}), s);
+  EXPECT_EQ(3, variables.size());
+  EXPECT_THAT(variables.decode(s), ::testing::ElementsAre("x", "z"));
+  EXPECT_THAT(variables.facts(), ::testing::ElementsAre("x", "y", "z"));
+  EXPECT_EQ("z", variables.fact(2));
+  EXPECT_THROW(variables.fact(3), invalid_argument);
+  EXPECT_THROW(variables.decode(BitsetDomain(5)), invalid_argument);
+}
+
+TEST(BitsetDomainTest, printing) {
+  std::ostringstream out;
+  out << BitsetDomain({65, 3}) << " " << BitsetDomain() << " "
+      << BitsetDomain::top() << " " << BitsetDomain::bottom();
+  EXPECT_EQ("[#2]{3, 65} [#0]{} T _|_", out.str());
+}
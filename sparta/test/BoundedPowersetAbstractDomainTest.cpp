@@ -0,0 +1,99 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "BoundedPowersetAbstractDomain.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+#include <vector>
+
+#include "AbstractDomainPropertyTest.h"
+#include "HashedSetAbstractDomain.h"
+#include "PatriciaTreeSetAbstractDomain.h"
+
+using namespace sparta;
+
+using Domain =
+    BoundedPowersetAbstractDomain<HashedSetAbstractDomain<std::string>, 3>;
+
+using PointsTo =
+    BoundedPowersetAbstractDomain<PatriciaTreeSetAbstractDomain<uint32_t>, 2>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(BoundedPowersetAbstractDomain,
+                              AbstractDomainPropertyTest,
+                              Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  Domain e1("a");
+  Domain e2({"a", "b", "c"});
+  Domain e3({"b", "c"});
+  return {e1, e2, e3};
+}
+
+TEST(BoundedPowersetAbstractDomainTest, constructors) {
+  EXPECT_TRUE(Domain().empty());
+  EXPECT_FALSE(Domain().is_bottom());
+  EXPECT_THAT(Domain({"a", "b", "c"}).elements(),
+              ::testing::UnorderedElementsAre("a", "b", "c"));
+  EXPECT_TRUE(Domain({"a", "b", "c", "d"}).is_top());
+  EXPECT_TRUE(
+      Domain(HashedSetAbstractDomain<std::string>({"a", "b", "c", "d"}))
+          .is_top());
+  EXPECT_EQ(3, Domain::max_size());
+}
+
+TEST(BoundedPowersetAbstractDomainTest, insertion) {
+  PointsTo s;
+  s.add(1).add(2);
+  EXPECT_EQ(2, s.size());
+  EXPECT_TRUE(s.contains(2));
+  s.add(2);
+  EXPECT_EQ(2, s.size());
+  s.add(3);
+  EXPECT_TRUE(s.is_top());
+  EXPECT_TRUE(s.contains(4));
+
+  // Removing an element from Top leaves it unchanged.
+  s.remove(3);
+  EXPECT_TRUE(s.is_top());
+
+  PointsTo t;
+  std::vector<uint32_t> elements{1, 2, 3, 4};
+  t.add(elements.begin(), elements.begin() + 2);
+  EXPECT_EQ(PointsTo({1, 2}), t);
+  t.add(elements.begin(), elements.end());
+  EXPECT_TRUE(t.is_top());
+
+  EXPECT_TRUE(PointsTo::bottom().add(1).is_bottom());
+}
+
+TEST(BoundedPowersetAbstractDomainTest, latticeOperations) {
+  PointsTo a({1});
+  PointsTo b({2});
+  PointsTo c({3});
+
+  PointsTo ab = a.join(b);
+  EXPECT_EQ(PointsTo({1, 2}), ab);
+  EXPECT_TRUE(ab.join(c).is_top());
+  EXPECT_TRUE(ab.widening(c).is_top());
+  EXPECT_EQ(a, ab.meet(PointsTo({1, 3})));
+  EXPECT_EQ(a, PointsTo::top().meet(a));
+  EXPECT_TRUE(ab.leq(PointsTo::top()));
+
+  PointsTo d = ab;
+  d.difference_with(b);
+  EXPECT_EQ(a, d);
+}
+
+TEST(BoundedPowersetAbstractDomainTest, printing) {
+  std::ostringstream out;
+  out << PointsTo({1}) << " " << PointsTo::top() << " " << PointsTo::bottom();
+  EXPECT_EQ("[#1]{1} T _|_", out.str());
+}
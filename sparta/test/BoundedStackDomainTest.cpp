@@ -0,0 +1,116 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "BoundedStackDomain.h"
+
+#include <gtest/gtest.h>
+#include <initializer_list>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+#include "ConstantAbstractDomain.h"
+
+using namespace sparta;
+
+using Constant = ConstantAbstractDomain<int>;
+using Stack = BoundedStackDomain<Constant, 2>;
+
+namespace {
+
+Stack make_stack(std::initializer_list<int> values) {
+  auto stack = Stack::empty();
+  for (int value : values) {
+    stack.push(Constant(value));
+  }
+  return stack;
+}
+
+} // namespace
+
+INSTANTIATE_TYPED_TEST_CASE_P(BoundedStackDomain,
+                              AbstractDomainPropertyTest,
+                              Stack);
+
+template <>
+std::vector<Stack> AbstractDomainPropertyTest<Stack>::non_extremal_values() {
+  auto smashed = Stack::top();
+  smashed.push(Constant(1));
+  return {Stack::empty(),
+          make_stack({1}),
+          make_stack({2}),
+          make_stack({1, 2}),
+          make_stack({1, 2, 3}),
+          smashed};
+}
+
+TEST(BoundedStackDomainTest, pushAndPop) {
+  auto stack = Stack::empty();
+  EXPECT_EQ(0, *stack.depth());
+  stack.push(Constant(1));
+  stack.push(Constant(2));
+  EXPECT_EQ(2, *stack.depth());
+  EXPECT_EQ(Constant(2), stack.peek());
+  EXPECT_EQ(Constant(1), stack.peek(1));
+  EXPECT_TRUE(stack.peek(2).is_bottom());
+
+  // Beyond the cap, the deepest elements are smashed.
+  stack.push(Constant(3));
+  EXPECT_FALSE(stack.depth());
+  EXPECT_EQ(Constant(1), stack.smashed());
+  EXPECT_EQ(Constant(1), stack.peek(2));
+  stack.push(Constant(4));
+  EXPECT_TRUE(stack.smashed().is_top());
+
+  {
+    std::ostringstream out;
+    out << stack;
+    EXPECT_EQ("[T*, 3, 4]", out.str());
+  }
+
+  EXPECT_EQ(Constant(4), stack.pop());
+  EXPECT_EQ(Constant(3), stack.pop());
+  // The smashed elements may or may not have been exhausted.
+  EXPECT_TRUE(stack.pop().is_top());
+  EXPECT_FALSE(stack.is_bottom());
+
+  // Underflow.
+  auto empty = Stack::empty();
+  EXPECT_TRUE(empty.pop().is_bottom());
+  EXPECT_TRUE(empty.is_bottom());
+
+  stack = make_stack({1});
+  stack.push(Constant::bottom());
+  EXPECT_TRUE(stack.is_bottom());
+}
+
+TEST(BoundedStackDomainTest, lattice) {
+  // Stacks of different depths are aligned on their tops.
+  auto joined = make_stack({1, 2}).join(make_stack({3}));
+  EXPECT_FALSE(joined.depth());
+  EXPECT_EQ(Constant(1), joined.smashed());
+  EXPECT_TRUE(joined.peek().is_top());
+  EXPECT_TRUE(make_stack({1, 2}).leq(joined));
+  EXPECT_TRUE(make_stack({3}).leq(joined));
+  EXPECT_TRUE(make_stack({1, 1, 5}).leq(joined));
+  EXPECT_FALSE(Stack::empty().leq(joined));
+
+  joined = make_stack({1, 2}).join(make_stack({1, 3}));
+  EXPECT_EQ(2, *joined.depth());
+  EXPECT_EQ(Constant(1), joined.peek(1));
+  EXPECT_TRUE(joined.peek().is_top());
+
+  // The meet recovers the exact depth.
+  auto smashed = Stack::top();
+  smashed.push(Constant(2));
+  auto met = smashed.meet(make_stack({1, 2}));
+  EXPECT_EQ(make_stack({1, 2}), met);
+  EXPECT_TRUE(smashed.meet(Stack::empty()).is_bottom());
+  EXPECT_TRUE(smashed.meet(make_stack({3})).is_bottom());
+
+  auto widened = make_stack({1}).widening(make_stack({1, 1}));
+  EXPECT_TRUE(make_stack({1, 1}).leq(widened));
+}
@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "CallGraphAnalysis.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <map>
+#include <string>
+#include <vector>
+
+#include "HashedSetAbstractDomain.h"
+#include "ReachabilityAnalysis.h"
+
+using namespace sparta;
+
+namespace {
+
+/*
+ * A small object-oriented program:
+ *
+ *   class A { void m() { helper(); } }         // call site 5
+ *   class B extends A { void m() { x.m(); } }  // call site 4, x is null
+ *   class C extends A {}
+ *
+ *   void main() {
+ *     A a = new C(); a.m();                     // call site 1
+ *     helper();                                 // call site 2
+ *   }
+ *   void helper(A a) { a.m(); }                 // call site 3
+ *   void dead() { helper(); }                   // call site 6
+ */
+struct Call {
+  int call_site;
+  std::string method;
+  boost::optional<HashedSetAbstractDomain<std::string>> receiver_types;
+};
+
+struct Analysis {
+  using Function = std::string;
+  using CallSite = int;
+  using Method = std::string;
+  using Type = std::string;
+  using TypeSet = HashedSetAbstractDomain<std::string>;
+
+  static const std::map<std::string, std::vector<Call>>& program() {
+    static const std::map<std::string, std::vector<Call>> program = {
+        {"main", {{1, "m", TypeSet("C")}, {2, "helper", boost::none}}},
+        {"helper", {{3, "m", TypeSet::top()}}},
+        {"A.m", {{5, "helper", boost::none}}},
+        {"B.m", {{4, "m", TypeSet::bottom()}}},
+        {"dead", {{6, "helper", boost::none}}},
+    };
+    return program;
+  }
+
+  static void for_each_call(
+      const Function& function,
+      const std::function<void(const CallSite&,
+                               const Method&,
+                               const boost::optional<TypeSet>&)>& visit) {
+    for (const auto& call : program().at(function)) {
+      visit(call.call_site, call.method, call.receiver_types);
+    }
+  }
+
+  static boost::optional<Function> resolve(const Method& method,
+                                           const Type& receiver_type) {
+    if (method != "m") {
+      return boost::none;
+    }
+    return receiver_type == "B" ? std::string("B.m") : std::string("A.m");
+  }
+
+  static std::vector<Function> implementations(const Method& method) {
+    if (method == "m") {
+      return {"A.m", "B.m"};
+    }
+    return {method};
+  }
+};
+
+using Graph = CallGraphAnalysis<Analysis>::Graph;
+
+} // namespace
+
+TEST(CallGraphAnalysisTest, receiverResolution) {
+  Graph graph = CallGraphAnalysis<Analysis>::run("main");
+
+  EXPECT_EQ("main", graph.entry());
+  EXPECT_THAT(graph.functions(),
+              ::testing::ElementsAre("main", "A.m", "helper", "B.m"));
+  EXPECT_FALSE(graph.contains("dead"));
+  EXPECT_EQ(5, graph.num_edges());
+
+  // The receiver of call site 1 is a C, which inherits A.m.
+  EXPECT_THAT(graph.callees(1), ::testing::ElementsAre("A.m"));
+  EXPECT_THAT(graph.callees(2), ::testing::ElementsAre("helper"));
+  // The receiver types of call site 3 are unknown.
+  EXPECT_THAT(graph.callees(3), ::testing::ElementsAre("A.m", "B.m"));
+  // Call site 4 is unreachable.
+  EXPECT_TRUE(graph.callees(4).empty());
+  EXPECT_THAT(graph.callees(5), ::testing::ElementsAre("helper"));
+  EXPECT_TRUE(graph.callees(6).empty());
+
+  std::vector<std::string> callers;
+  for (auto edge : graph.predecessors("helper")) {
+    callers.push_back(graph.edge(edge).caller);
+  }
+  EXPECT_THAT(callers, ::testing::UnorderedElementsAre("main", "A.m"));
+  EXPECT_TRUE(graph.successors("B.m").empty());
+  EXPECT_TRUE(graph.predecessors("dead").empty());
+}
+
+TEST(CallGraphAnalysisTest, fixpointIteration) {
+  Graph graph = CallGraphAnalysis<Analysis>::run("main");
+  ReachabilityAnalysis<CallGraphInterface<Graph>> reachability(graph);
+  reachability.run();
+  for (const auto& function : graph.functions()) {
+    EXPECT_TRUE(reachability.is_reachable(function)) << function;
+  }
+  EXPECT_FALSE(reachability.is_reachable("dead"));
+}
@@ -0,0 +1,173 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <algorithm>
+#include <atomic>
+#include <chrono>
+#include <gtest/gtest.h>
+#include <mutex>
+#include <set>
+#include <thread>
+#include <unordered_map>
+#include <vector>
+
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+/*
+ * Node 0 initializes a variable to 0 and the branches of the loops increment
+ * it. The analyzer records how many nodes of the loop with head 1 are
+ * analyzed at the same time, and by which threads.
+ */
+class Analyzer final
+    : public ParallelMonotonicFixpointIterator<GraphInterface, Interval> {
+ public:
+  using ParallelMonotonicFixpointIterator::ParallelMonotonicFixpointIterator;
+
+  void analyze_node(const uint32_t& node, Interval* state) const override {
+    bool in_loop = node >= 1 && node <= 6;
+    if (in_loop) {
+      size_t running = ++m_running;
+      size_t max_running = m_max_running;
+      while (running > max_running &&
+             !m_max_running.compare_exchange_weak(max_running, running)) {
+      }
+      std::lock_guard<std::mutex> guard(m_mutex);
+      m_threads.insert(std::this_thread::get_id());
+    }
+    if (node == 0) {
+      *state = Interval::finite(0, 0);
+    } else if (node != 1 && node != 6 && node != 7) {
+      *state += 1;
+    }
+    if (in_loop) {
+      std::this_thread::sleep_for(std::chrono::microseconds(100));
+      --m_running;
+    }
+  }
+
+  Interval analyze_edge(const size_t&, const Interval& state) const override {
+    return state;
+  }
+
+  mutable std::atomic<size_t> m_running{0};
+  mutable std::atomic<size_t> m_max_running{0};
+  mutable std::mutex m_mutex;
+  mutable std::set<std::thread::id> m_threads;
+};
+
+/*
+ *  0 -> 1 -> {2, 3, 4, 5} -> 6 -> 7
+ *  |    ^                    |
+ *  |    +--------------------+
+ *  +--> 8 -> 9 -> 10
+ *       ^    |
+ *       +----+
+ */
+Graph make_graph() {
+  Graph graph;
+  graph.add_edge(0, 1);
+  for (uint32_t node = 2; node <= 5; ++node) {
+    graph.add_edge(1, node);
+    graph.add_edge(node, 6);
+  }
+  graph.add_edge(6, 1);
+  graph.add_edge(6, 7);
+  graph.add_edge(0, 8);
+  graph.add_edge(8, 9);
+  graph.add_edge(9, 8);
+  graph.add_edge(9, 10);
+  return graph;
+}
+
+void expect_same_states(const Analyzer& expected, const Analyzer& actual) {
+  for (uint32_t node = 0; node <= 10; ++node) {
+    EXPECT_EQ(expected.get_entry_state_at(node),
+              actual.get_entry_state_at(node));
+    EXPECT_EQ(expected.get_exit_state_at(node), actual.get_exit_state_at(node));
+  }
+}
+
+} // namespace
+
+TEST(ComponentSchedulingTest, componentConcurrency) {
+  Graph graph = make_graph();
+  Analyzer reference(graph, /*num_thread*/ 4);
+  reference.run(Interval::top());
+  EXPECT_EQ(Interval::bounded_below(1),
+            reference.get_entry_state_at(7));
+
+  for (size_t max_tasks : {1, 2}) {
+    Analyzer analyzer(graph, /*num_thread*/ 4);
+    analyzer.set_component_concurrency(max_tasks);
+    analyzer.run(Interval::top());
+    expect_same_states(reference, analyzer);
+    EXPECT_LE(analyzer.m_max_running, max_tasks);
+  }
+}
+
+TEST(ComponentSchedulingTest, componentPinning) {
+  Graph graph = make_graph();
+  Analyzer reference(graph, /*num_thread*/ 4);
+  reference.run(Interval::top());
+
+  Analyzer analyzer(graph, /*num_thread*/ 4);
+  // The loop with head 1 has 7 nodes in the WPO, the one with head 8 has 3.
+  analyzer.set_component_pinning(5);
+  analyzer.run(Interval::top());
+  expect_same_states(reference, analyzer);
+  EXPECT_EQ(1, analyzer.m_max_running);
+  EXPECT_EQ(1, analyzer.m_threads.size());
+
+  // Running the iterator again uses the same settings.
+  analyzer.m_threads.clear();
+  analyzer.run(Interval::top());
+  expect_same_states(reference, analyzer);
+  EXPECT_EQ(1, analyzer.m_threads.size());
+}
+
+TEST(ComponentSchedulingTest, componentsOfWpo) {
+  Graph graph = make_graph();
+  WeakPartialOrdering<uint32_t> wpo(
+      0,
+      [&graph](const uint32_t& node) {
+        std::vector<uint32_t> successors;
+        for (auto edge : GraphInterface::successors(graph, node)) {
+          successors.push_back(GraphInterface::target(graph, edge));
+        }
+        return successors;
+      },
+      false);
+  std::unordered_map<uint32_t, uint32_t> heads;
+  for (uint32_t idx = 0; idx < wpo.size(); ++idx) {
+    if (wpo.is_head(idx)) {
+      heads[wpo.get_node(idx)] = idx;
+    }
+  }
+  ASSERT_EQ(2, heads.size());
+  EXPECT_EQ(7, wpo.get_size(heads[1]));
+  EXPECT_EQ(3, wpo.get_size(heads[8]));
+  for (uint32_t idx = 0; idx < wpo.size(); ++idx) {
+    uint32_t node = wpo.get_node(idx);
+    if (!wpo.is_plain(idx)) {
+      // Both loops are outermost components.
+      EXPECT_EQ(heads[node], wpo.get_parent(idx)) << node;
+    } else if (node == 0 || node == 7 || node == 10) {
+      EXPECT_EQ(idx, wpo.get_parent(idx)) << node;
+      EXPECT_EQ(1, wpo.get_size(idx));
+    } else {
+      EXPECT_EQ(node <= 6 ? heads[1] : heads[8], wpo.get_parent(idx)) << node;
+    }
+  }
+}
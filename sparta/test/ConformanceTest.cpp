@@ -0,0 +1,241 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <fstream>
+#include <gtest/gtest.h>
+#include <string>
+#include <unordered_map>
+#include <utility>
+#include <vector>
+
+#include "ConstantAbstractDomain.h"
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "S_Expression.h"
+#include "TestGraph.h"
+
+/*
+ * Runs the test vectors shared by the implementations of SPARTA. See
+ * test/vectors/README.md for a description of their format.
+ */
+
+#ifndef SPARTA_TEST_VECTORS_DIR
+#define SPARTA_TEST_VECTORS_DIR "test/vectors"
+#endif
+
+using namespace sparta;
+
+namespace {
+
+using Constant = ConstantAbstractDomain<int32_t>;
+using Interval = IntervalDomain<int32_t>;
+
+std::vector<s_expr> read_vectors(const std::string& name) {
+  std::string path = std::string(SPARTA_TEST_VECTORS_DIR) + "/" + name;
+  std::ifstream file(path);
+  EXPECT_TRUE(file.good()) << "Cannot open " << path;
+  std::vector<s_expr> vectors;
+  s_expr_istream input(file);
+  while (true) {
+    s_expr vector;
+    input >> vector;
+    if (input.eoi()) {
+      break;
+    }
+    if (input.fail()) {
+      ADD_FAILURE() << path << ": " << input.what();
+      break;
+    }
+    vectors.push_back(vector);
+  }
+  EXPECT_FALSE(vectors.empty()) << "No test vectors in " << path;
+  return vectors;
+}
+
+int32_t parse_bound(const s_expr& expr, const std::string& infinity,
+                    int32_t value) {
+  if (expr.is_string() && expr.get_string() == infinity) {
+    return value;
+  }
+  int32_t n;
+  s_patn(&n).must_match(expr, "expected a bound");
+  return n;
+}
+
+template <typename Domain>
+Domain parse_value(const s_expr& expr);
+
+template <>
+Constant parse_value<Constant>(const s_expr& expr) {
+  int32_t n;
+  s_patn({s_patn("constant"), s_patn(&n)})
+      .must_match(expr, "expected a constant");
+  return Constant(n);
+}
+
+template <>
+Interval parse_value<Interval>(const s_expr& expr) {
+  s_expr lb, ub;
+  s_patn({s_patn("interval"), s_patn(lb), s_patn(ub)})
+      .must_match(expr, "expected an interval");
+  int32_t lower = parse_bound(lb, "-inf", Interval::MIN);
+  int32_t upper = parse_bound(ub, "inf", Interval::MAX);
+  if (lower == Interval::MIN) {
+    return upper == Interval::MAX ? Interval::top()
+                                  : Interval::bounded_above(upper);
+  }
+  return upper == Interval::MAX ? Interval::bounded_below(lower)
+                                : Interval::finite(lower, upper);
+}
+
+template <typename Domain>
+Domain evaluate(const s_expr& expr) {
+  std::string symbol;
+  s_expr left, right;
+  if (s_patn("top").match_with(expr)) {
+    return Domain::top();
+  }
+  if (s_patn("bottom").match_with(expr)) {
+    return Domain::bottom();
+  }
+  if (!s_patn({s_patn(&symbol), s_patn(left), s_patn(right)})
+           .match_with(expr)) {
+    return parse_value<Domain>(expr);
+  }
+  if (symbol == "join") {
+    return evaluate<Domain>(left).join(evaluate<Domain>(right));
+  }
+  if (symbol == "meet") {
+    return evaluate<Domain>(left).meet(evaluate<Domain>(right));
+  }
+  if (symbol == "widen") {
+    return evaluate<Domain>(left).widening(evaluate<Domain>(right));
+  }
+  if (symbol == "narrow") {
+    return evaluate<Domain>(left).narrowing(evaluate<Domain>(right));
+  }
+  return parse_value<Domain>(expr);
+}
+
+template <typename Domain>
+void check_domain(const s_expr& vector) {
+  s_expr checks;
+  s_patn({s_patn("domain"), s_patn()}, checks)
+      .must_match(vector, "expected a domain vector");
+  for (size_t i = 0; i < checks.size(); ++i) {
+    s_expr expr, expected;
+    s_patn({s_patn("check"), s_patn(expr), s_patn(expected)})
+        .must_match(checks[i], "expected a check");
+    std::string predicate;
+    s_expr left, right;
+    if (s_patn({s_patn(&predicate), s_patn(left), s_patn(right)})
+            .match_with(expr) &&
+        (predicate == "leq" || predicate == "equals")) {
+      auto x = evaluate<Domain>(left);
+      auto y = evaluate<Domain>(right);
+      bool result = predicate == "leq" ? x.leq(y) : x.equals(y);
+      EXPECT_EQ(expected.str(), result ? "true" : "false") << checks[i].str();
+    } else {
+      auto result = evaluate<Domain>(expr);
+      EXPECT_TRUE(result.equals(evaluate<Domain>(expected)))
+          << checks[i].str() << " evaluates to " << result;
+    }
+  }
+}
+
+/*
+ * Interprets the transformers of a fixpoint vector.
+ */
+class VectorAnalyzer final
+    : public MonotonicFixpointIterator<GraphInterface, Interval> {
+ public:
+  VectorAnalyzer(const Graph& graph,
+                 std::unordered_map<uint32_t, s_expr> transformers)
+      : MonotonicFixpointIterator(graph),
+        m_transformers(std::move(transformers)) {}
+
+  void analyze_node(const uint32_t& node, Interval* state) const override {
+    auto it = m_transformers.find(node);
+    if (it == m_transformers.end()) {
+      return;
+    }
+    int32_t n;
+    if (s_patn({s_patn("const"), s_patn(&n)}).match_with(it->second)) {
+      *state = Interval::finite(n, n);
+    } else {
+      s_patn({s_patn("add"), s_patn(&n)})
+          .must_match(it->second, "expected a transformer");
+      *state += n;
+    }
+  }
+
+  Interval analyze_edge(const size_t&, const Interval& state) const override {
+    return state;
+  }
+
+ private:
+  std::unordered_map<uint32_t, s_expr> m_transformers;
+};
+
+void check_fixpoint(const s_expr& vector) {
+  std::string name;
+  s_expr edges, nodes, entry, exit;
+  s_patn({s_patn("fixpoint"), s_patn(&name),
+          s_patn({s_patn("edges")}, edges), s_patn({s_patn("nodes")}, nodes),
+          s_patn({s_patn("entry")}, entry), s_patn({s_patn("exit")}, exit)})
+      .must_match(vector, "expected a fixpoint vector");
+  Graph graph;
+  for (size_t i = 0; i < edges.size(); ++i) {
+    int32_t src, dst;
+    s_patn({s_patn(&src), s_patn(&dst)})
+        .must_match(edges[i], "expected an edge");
+    graph.add_edge(src, dst);
+  }
+  std::unordered_map<uint32_t, s_expr> transformers;
+  for (size_t i = 0; i < nodes.size(); ++i) {
+    int32_t node;
+    s_expr transformer;
+    s_patn({s_patn(&node), s_patn(transformer)})
+        .must_match(nodes[i], "expected a transformer");
+    transformers.emplace(node, transformer);
+  }
+  VectorAnalyzer analyzer(graph, std::move(transformers));
+  analyzer.run(Interval::top());
+  for (const auto& invariants : {std::make_pair(entry, true),
+                                 std::make_pair(exit, false)}) {
+    for (size_t i = 0; i < invariants.first.size(); ++i) {
+      int32_t node;
+      s_expr expected;
+      s_patn({s_patn(&node), s_patn(expected)})
+          .must_match(invariants.first[i], "expected an invariant");
+      auto state = invariants.second ? analyzer.get_entry_state_at(node)
+                                     : analyzer.get_exit_state_at(node);
+      EXPECT_TRUE(state.equals(evaluate<Interval>(expected)))
+          << name << ": " << invariants.first[i].str() << " but got " << state;
+    }
+  }
+}
+
+} // namespace
+
+TEST(ConformanceTest, constantDomain) {
+  for (const auto& vector : read_vectors("constant.sexp")) {
+    check_domain<Constant>(vector);
+  }
+}
+
+TEST(ConformanceTest, intervalDomain) {
+  for (const auto& vector : read_vectors("interval.sexp")) {
+    check_domain<Interval>(vector);
+  }
+}
+
+TEST(ConformanceTest, fixpoint) {
+  for (const auto& vector : read_vectors("fixpoint.sexp")) {
+    check_fixpoint(vector);
+  }
+}
@@ -0,0 +1,126 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "CongruenceDomain.h"
+
+#include <cstdint>
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using Domain = CongruenceDomain<int32_t>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(CongruenceDomain,
+                              AbstractDomainPropertyTest,
+                              Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  return {Domain::constant(-3),
+          Domain::constant(6),
+          Domain::congruence(2, 0),
+          Domain::congruence(3, 1),
+          Domain::congruence(4, 2),
+          Domain::congruence(6, 4)};
+}
+
+TEST(CongruenceDomainTest, representation) {
+  EXPECT_TRUE(Domain().is_top());
+  EXPECT_EQ(Domain::top(), Domain::congruence(1, 5));
+  auto x = Domain::congruence(4, -3);
+  EXPECT_EQ(4, x.modulus());
+  EXPECT_EQ(1, x.residue());
+  EXPECT_TRUE(x.contains(-7));
+  EXPECT_TRUE(x.contains(9));
+  EXPECT_FALSE(x.contains(3));
+  EXPECT_FALSE(x.get_constant());
+  EXPECT_EQ(5, *Domain::constant(5).get_constant());
+  EXPECT_THROW(Domain::congruence(-2, 0), invalid_argument);
+
+  std::ostringstream out;
+  out << x << " " << Domain::constant(-5) << " " << Domain::top();
+  EXPECT_EQ("4Z+1 -5 T", out.str());
+}
+
+TEST(CongruenceDomainTest, lattice) {
+  EXPECT_EQ(Domain::congruence(4, 2),
+            Domain::constant(2).join(Domain::constant(6)));
+  EXPECT_EQ(Domain::congruence(2, 0),
+            Domain::congruence(4, 2).join(Domain::congruence(6, 0)));
+  EXPECT_TRUE(Domain::constant(2).join(Domain::constant(3)).is_top());
+  EXPECT_TRUE(Domain::constant(2).leq(Domain::congruence(4, 2)));
+  EXPECT_TRUE(Domain::congruence(8, 6).leq(Domain::congruence(4, 2)));
+  EXPECT_FALSE(Domain::congruence(4, 2).leq(Domain::congruence(8, 6)));
+
+  // Chinese remainder theorem: x = 2 (mod 4) and x = 1 (mod 3).
+  EXPECT_EQ(Domain::congruence(12, 10),
+            Domain::congruence(4, 2).meet(Domain::congruence(3, 1)));
+  EXPECT_EQ(Domain::congruence(12, 10),
+            Domain::congruence(6, 4).meet(Domain::congruence(4, 2)));
+  EXPECT_TRUE(
+      Domain::congruence(2, 0).meet(Domain::congruence(4, 1)).is_bottom());
+  EXPECT_EQ(Domain::constant(10),
+            Domain::congruence(4, 2).meet(Domain::constant(10)));
+  EXPECT_TRUE(Domain::congruence(4, 2).meet(Domain::constant(3)).is_bottom());
+
+  // Overflows are approximated.
+  const auto max = Domain::constant(Domain::MAX);
+  const auto min = Domain::constant(Domain::MIN);
+  EXPECT_TRUE(max.join(min).is_top());
+  auto large = Domain::congruence(1 << 20, 0);
+  EXPECT_EQ(large, large.meet(Domain::congruence((1 << 20) - 1, 0)));
+
+  // Only Top is refined by the narrowing.
+  EXPECT_EQ(Domain::congruence(4, 2),
+            Domain::top().narrowing(Domain::congruence(4, 2)));
+  EXPECT_EQ(Domain::congruence(2, 0),
+            Domain::congruence(2, 0).narrowing(Domain::congruence(4, 2)));
+}
+
+TEST(CongruenceDomainTest, arithmetic) {
+  const auto x = Domain::congruence(4, 2);
+  const auto y = Domain::congruence(6, 1);
+  EXPECT_EQ(Domain::congruence(2, 1), x + y);
+  EXPECT_EQ(Domain::congruence(2, 1), x - y);
+  EXPECT_EQ(Domain::congruence(4, 1), x + Domain::constant(3));
+  EXPECT_EQ(Domain::congruence(6, 5), -y);
+  EXPECT_EQ(Domain::congruence(12, 6), x * Domain::constant(3));
+  EXPECT_EQ(Domain::congruence(4, 2), x * y);
+  EXPECT_EQ(Domain::constant(-21), Domain::constant(7) * Domain::constant(-3));
+  EXPECT_TRUE((x + Domain::bottom()).is_bottom());
+  EXPECT_TRUE((Domain::constant(Domain::MAX) + Domain::constant(1)).is_top());
+  EXPECT_TRUE((-Domain::constant(Domain::MIN)).is_top());
+  EXPECT_TRUE(
+      (Domain::constant(Domain::MAX) * Domain::constant(2)).is_top());
+}
+
+TEST(CongruenceDomainTest, operations) {
+  using Ops = ArithmeticDomainOps<Domain>;
+  using CompareOps = CompareDomainOps<Domain>;
+  const auto x = Domain::congruence(4, 2);
+
+  EXPECT_EQ(Domain::constant(3), Ops::literal(3));
+  EXPECT_EQ(Domain::congruence(16, 8), Ops::shl(x, Domain::constant(2)));
+  EXPECT_TRUE(Ops::shl(x, Domain::constant(31)).is_top());
+  EXPECT_EQ(Domain::constant(-4),
+            Ops::div(Domain::constant(-9), Domain::constant(2)));
+  EXPECT_TRUE(Ops::div(x, Domain::constant(0)).is_bottom());
+  EXPECT_TRUE(Ops::div(x, Domain::constant(2)).is_top());
+  EXPECT_EQ(Domain::constant(2),
+            Ops::bit_and(Domain::constant(6), Domain::constant(3)));
+  EXPECT_TRUE(Ops::shr(Domain::constant(6), Domain::constant(32)).is_top());
+
+  EXPECT_EQ(BooleanDomain(false), CompareOps::eq(x, Domain::constant(3)));
+  EXPECT_EQ(BooleanDomain(true), CompareOps::ne(x, Domain::congruence(4, 1)));
+  EXPECT_TRUE(CompareOps::eq(x, Domain::constant(6)).is_top());
+  EXPECT_EQ(BooleanDomain(true),
+            CompareOps::lt(Domain::constant(1), Domain::constant(2)));
+  EXPECT_TRUE(CompareOps::ge(x, Domain::constant(2)).is_top());
+}
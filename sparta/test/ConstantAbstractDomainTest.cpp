@@ -0,0 +1,66 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "ConstantAbstractDomain.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using Domain = ConstantAbstractDomain<int>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(ConstantAbstractDomain,
+                              AbstractDomainPropertyTest,
+                              Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  return {Domain(-1), Domain(0), Domain(1)};
+}
+
+TEST(ConstantAbstractDomainTest, constants) {
+  EXPECT_EQ(3, *Domain(3).get_constant());
+  EXPECT_FALSE(Domain::top().get_constant());
+  EXPECT_FALSE(Domain::bottom().get_constant());
+  EXPECT_TRUE(Domain().is_top());
+
+  std::ostringstream out;
+  out << Domain(3) << " " << Domain::top() << " " << Domain::bottom();
+  EXPECT_EQ("3 T _|_", out.str());
+}
+
+TEST(ConstantAbstractDomainTest, map) {
+  auto increment = [](int x) { return x + 1; };
+  EXPECT_EQ(Domain(4), Domain(3).map(increment));
+  EXPECT_TRUE(Domain::top().map(increment).is_top());
+  EXPECT_TRUE(Domain::bottom().map(increment).is_bottom());
+
+  auto name = Domain(3).map([](int x) { return std::to_string(x); });
+  static_assert(
+      std::is_same<decltype(name), ConstantAbstractDomain<std::string>>::value,
+      "map changes the type of the constants");
+  EXPECT_EQ("3", *name.get_constant());
+}
+
+TEST(ConstantAbstractDomainTest, bind) {
+  auto inverse = [](int x) {
+    return x == 0 ? Domain::bottom() : x == 1 ? Domain(1) : Domain::top();
+  };
+  EXPECT_EQ(Domain(1), Domain(1).bind(inverse));
+  EXPECT_TRUE(Domain(0).bind(inverse).is_bottom());
+  EXPECT_TRUE(Domain(2).bind(inverse).is_top());
+  EXPECT_TRUE(Domain::top().bind(inverse).is_top());
+  EXPECT_TRUE(Domain::bottom().bind(inverse).is_bottom());
+
+  auto parity = Domain(7).bind(
+      [](int x) { return ConstantAbstractDomain<bool>(x % 2 == 0); });
+  EXPECT_EQ(ConstantAbstractDomain<bool>(false), parity);
+}
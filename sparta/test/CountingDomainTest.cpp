@@ -0,0 +1,134 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "CountingDomain.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using Multiset = MultisetAbstractDomain<uint32_t>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(CountingDomain,
+                              AbstractDomainPropertyTest,
+                              CountingDomain);
+
+template <>
+std::vector<CountingDomain>
+AbstractDomainPropertyTest<CountingDomain>::non_extremal_values() {
+  return {CountingDomain::zero(), CountingDomain::one(),
+          CountingDomain::at_most_one(), CountingDomain::at_least_one()};
+}
+
+INSTANTIATE_TYPED_TEST_CASE_P(MultisetAbstractDomain,
+                              AbstractDomainPropertyTest,
+                              Multiset);
+
+template <>
+std::vector<Multiset>
+AbstractDomainPropertyTest<Multiset>::non_extremal_values() {
+  Multiset twice({1});
+  twice.add(1);
+  Multiset maybe({2});
+  maybe.set(3, CountingDomain::at_most_one());
+  return {Multiset(), Multiset({1}), Multiset({1, 2}), twice, maybe};
+}
+
+TEST(CountingDomainTest, transfer) {
+  auto x = CountingDomain::zero();
+  EXPECT_TRUE(x.is_zero());
+  EXPECT_EQ(0, x.lower_bound());
+  EXPECT_EQ(0, *x.upper_bound());
+
+  x.increment();
+  EXPECT_TRUE(x.is_one());
+  x.increment();
+  EXPECT_EQ(CountingDomain::at_least_one(), x);
+  EXPECT_EQ(boost::none, x.upper_bound());
+  EXPECT_TRUE(x.may_be_many());
+  x.increment();
+  EXPECT_EQ(CountingDomain::at_least_one(), x);
+
+  x.decrement();
+  EXPECT_TRUE(x.is_top());
+  EXPECT_TRUE(CountingDomain::one().decrement().is_zero());
+  EXPECT_TRUE(CountingDomain::at_most_one().decrement().is_zero());
+  EXPECT_TRUE(CountingDomain::zero().decrement().is_bottom());
+  EXPECT_TRUE(CountingDomain::bottom().increment().is_bottom());
+  EXPECT_THROW(CountingDomain::bottom().lower_bound(), invalid_abstract_value);
+
+  EXPECT_EQ(CountingDomain::at_most_one(),
+            CountingDomain::zero().join(CountingDomain::one()));
+  EXPECT_TRUE(CountingDomain::zero().meet(CountingDomain::one()).is_bottom());
+  EXPECT_EQ(CountingDomain::one(),
+            CountingDomain::at_most_one().meet(CountingDomain::at_least_one()));
+
+  std::ostringstream out;
+  out << CountingDomain::one() << " " << CountingDomain::at_most_one() << " "
+      << CountingDomain::at_least_one() << " " << CountingDomain::top();
+  EXPECT_EQ("1 [0, 1] [1, +oo] T", out.str());
+}
+
+TEST(CountingDomainTest, add) {
+  auto zero = CountingDomain::zero();
+  auto one = CountingDomain::one();
+  auto at_most_one = CountingDomain::at_most_one();
+  auto at_least_one = CountingDomain::at_least_one();
+
+  EXPECT_EQ(one, CountingDomain(zero).add(one));
+  EXPECT_EQ(at_most_one, CountingDomain(at_most_one).add(zero));
+  EXPECT_EQ(at_least_one, CountingDomain(one).add(one));
+  EXPECT_EQ(at_least_one, CountingDomain(one).add(at_most_one));
+  EXPECT_TRUE(CountingDomain(at_most_one).add(at_most_one).is_top());
+  EXPECT_EQ(at_least_one, CountingDomain::top().add(at_least_one));
+  EXPECT_TRUE(CountingDomain(one).add(CountingDomain::bottom()).is_bottom());
+  EXPECT_TRUE(CountingDomain::bottom().add(zero).is_bottom());
+
+  // Adding one is the same as incrementing.
+  for (const auto& x : {zero, one, at_most_one, at_least_one}) {
+    EXPECT_EQ(CountingDomain(x).increment(), CountingDomain(x).add(one));
+  }
+}
+
+TEST(CountingDomainTest, multiset) {
+  // Each branch of a conditional acquires a resource, and both are released
+  // after the conditional.
+  Multiset then_branch({1});
+  Multiset else_branch({2});
+  Multiset joined = then_branch.join(else_branch);
+  EXPECT_EQ(CountingDomain::at_most_one(), joined.count(1));
+  EXPECT_EQ(CountingDomain::at_most_one(), joined.count(2));
+  EXPECT_TRUE(joined.count(3).is_zero());
+  EXPECT_FALSE(joined.contains(1));
+  EXPECT_TRUE(then_branch.contains(1));
+  EXPECT_TRUE(then_branch.leq(joined));
+  EXPECT_FALSE(joined.leq(then_branch));
+
+  joined.remove(1).remove(2);
+  EXPECT_TRUE(joined.equals(Multiset()));
+  EXPECT_EQ(0, joined.bindings().size());
+
+  // Releasing a resource that is certainly not held is infeasible.
+  EXPECT_TRUE(Multiset({1}).remove(2).is_bottom());
+
+  Multiset twice({1, 1});
+  EXPECT_EQ(CountingDomain::at_least_one(), twice.count(1));
+  EXPECT_TRUE(twice.meet(Multiset()).is_bottom());
+  EXPECT_TRUE(Multiset({1}).meet(Multiset({2})).is_bottom());
+  EXPECT_EQ(Multiset({1}), Multiset({1}).meet(twice.join(Multiset())));
+
+  EXPECT_TRUE(Multiset::top().add(1).is_top());
+  EXPECT_TRUE(Multiset::top().count(1).is_top());
+  EXPECT_TRUE(Multiset::bottom().count(1).is_bottom());
+
+  std::ostringstream out;
+  out << Multiset({2, 2});
+  EXPECT_EQ("{2 -> [1, +oo]}", out.str());
+}
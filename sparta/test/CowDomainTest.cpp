@@ -0,0 +1,98 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "CowDomain.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+#include "IntervalDomain.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+using Environment = PatriciaTreeMapAbstractEnvironment<uint32_t, Interval>;
+using Domain = CowDomain<Environment>;
+
+} // namespace
+
+INSTANTIATE_TYPED_TEST_CASE_P(CowDomain, AbstractDomainPropertyTest, Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::top_values() {
+  return {Domain::top(), Domain(Environment::top())};
+}
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::bottom_values() {
+  return {Domain::bottom(), Domain(Environment::bottom())};
+}
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  return {Domain(Environment({{1, Interval::finite(0, 1)}})),
+          Domain(Environment({{1, Interval::finite(2, 3)},
+                              {2, Interval::finite(0, 0)}}))};
+}
+
+TEST(CowDomainTest, copyOnWrite) {
+  Domain x(Environment({{1, Interval::finite(0, 1)}}));
+  Domain y = x;
+  EXPECT_TRUE(y.reference_equals(x));
+
+  // Reading doesn't clone the value.
+  EXPECT_EQ(Interval::finite(0, 1), y->get(1));
+  EXPECT_TRUE(y.reference_equals(x));
+
+  y.mutate()->set(2, Interval::finite(5, 5));
+  EXPECT_FALSE(y.reference_equals(x));
+  EXPECT_TRUE(x->get(2).is_top());
+  EXPECT_EQ(Interval::finite(5, 5), y->get(2));
+
+  // A value that isn't shared is modified in place.
+  const Environment* before = &y.get();
+  y.update([](Environment* env) { env->set(3, Interval::finite(1, 1)); });
+  EXPECT_EQ(before, &y.get());
+}
+
+TEST(CowDomainTest, operationsShareTheirOperands) {
+  Domain x(Environment({{1, Interval::finite(0, 1)}}));
+  Domain y(Environment({{1, Interval::finite(2, 3)}}));
+
+  Domain bottom = Domain::bottom();
+  bottom.join_with(x);
+  EXPECT_TRUE(bottom.reference_equals(x));
+
+  Domain top = Domain::top();
+  top.meet_with(y);
+  EXPECT_TRUE(top.reference_equals(y));
+
+  Domain z = x;
+  z.join_with(x);
+  EXPECT_TRUE(z.reference_equals(x));
+
+  z.join_with(y);
+  EXPECT_FALSE(z.reference_equals(x));
+  EXPECT_EQ(Interval::finite(0, 3), z->get(1));
+  EXPECT_EQ(Interval::finite(0, 1), x->get(1));
+
+  // The shared extremal values are never modified.
+  Domain b1 = Domain::bottom();
+  b1.mutate()->set_to_top();
+  EXPECT_TRUE(Domain::bottom().is_bottom());
+}
+
+TEST(CowDomainTest, prettyPrinting) {
+  std::ostringstream out;
+  out << CowDomain<Interval>(Interval::finite(0, 1)) << " "
+      << Domain(Environment({{1, Interval::finite(2, 2)}}));
+  EXPECT_EQ("[0, 1] [#1]{1 -> [2, 2]}", out.str());
+}
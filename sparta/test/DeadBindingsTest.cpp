@@ -0,0 +1,111 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <gtest/gtest.h>
+#include <vector>
+
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+using Environment = PatriciaTreeMapAbstractEnvironment<uint32_t, Interval>;
+
+enum Variable : uint32_t { X, Y, I };
+
+/*
+ *   0: x := 1; y := 2; i := 0
+ *   1: loop head
+ *   2: i := i + x
+ *   3: y := i
+ *   4: return y
+ *
+ *  0 -> 1 -> 2 -> 3 -> 4
+ *       ^         |
+ *       +---------+
+ */
+class Analyzer final
+    : public MonotonicFixpointIterator<GraphInterface, Environment> {
+ public:
+  using MonotonicFixpointIterator::MonotonicFixpointIterator;
+
+  void analyze_node(const uint32_t& node, Environment* env) const override {
+    switch (node) {
+    case 0: {
+      env->set(X, Interval::finite(1, 1));
+      env->set(Y, Interval::finite(2, 2));
+      env->set(I, Interval::finite(0, 0));
+      break;
+    }
+    case 2: {
+      env->set(I, env->get(I) + env->get(X));
+      break;
+    }
+    case 3: {
+      env->set(Y, env->get(I));
+      break;
+    }
+    }
+  }
+
+  Environment analyze_edge(const size_t&,
+                           const Environment& env) const override {
+    return env;
+  }
+};
+
+Graph make_graph() {
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 3);
+  graph.add_edge(3, 1);
+  graph.add_edge(3, 4);
+  return graph;
+}
+
+// The variables that are not used anymore after each node.
+std::vector<uint32_t> dead_after(const uint32_t& node) {
+  switch (node) {
+  case 0:
+    return {Y};
+  case 4:
+    return {X, I};
+  default:
+    return {};
+  }
+}
+
+} // namespace
+
+TEST(DeadBindingsTest, deadBindingsAreRemoved) {
+  Graph graph = make_graph();
+  Analyzer analyzer(graph);
+  analyzer.run(Environment::top());
+  EXPECT_EQ(Interval::finite(2, 2), analyzer.get_exit_state_at(0).get(Y));
+  EXPECT_EQ(3, analyzer.get_exit_state_at(4).bindings().size());
+
+  analyzer.set_dead_bindings(dead_after);
+  analyzer.run(Environment::top());
+  auto exit_0 = analyzer.get_exit_state_at(0);
+  EXPECT_TRUE(exit_0.get(Y).is_top());
+  EXPECT_EQ(2, exit_0.bindings().size());
+  // The loop still assigns y, which is live after it.
+  EXPECT_EQ(3, analyzer.get_exit_state_at(3).bindings().size());
+  auto exit_4 = analyzer.get_exit_state_at(4);
+  EXPECT_EQ(1, exit_4.bindings().size());
+  EXPECT_EQ(Interval::bounded_below(1), exit_4.get(Y));
+
+  analyzer.set_dead_bindings(nullptr);
+  analyzer.run(Environment::top());
+  EXPECT_EQ(3, analyzer.get_exit_state_at(4).bindings().size());
+}
@@ -0,0 +1,129 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "DefaultBinding.h"
+
+#include <cstdint>
+#include <gtest/gtest.h>
+
+#include "ArrayAbstractEnvironment.h"
+#include "HashedAbstractEnvironment.h"
+#include "HashedAbstractPartition.h"
+#include "IntervalDomain.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+#include "PatriciaTreeMapAbstractPartition.h"
+#include "SsaAbstractEnvironment.h"
+#include "WideRegisterEnvironment.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+using HashedEnvironment = HashedAbstractEnvironment<uint32_t, Interval>;
+using PatriciaTreeEnvironment =
+    PatriciaTreeMapAbstractEnvironment<uint32_t, Interval>;
+using ArrayEnvironment = ArrayAbstractEnvironment<Interval>;
+using RegisterEnvironment = WideRegisterEnvironment<uint32_t, Interval>;
+using HashedPartition = HashedAbstractPartition<uint32_t, Interval>;
+using PatriciaTreePartition =
+    PatriciaTreeMapAbstractPartition<uint32_t, Interval>;
+using SsaEnvironment = SsaAbstractEnvironment<uint32_t, Interval>;
+
+static_assert(default_is_top<HashedEnvironment>::value, "");
+static_assert(default_is_top<PatriciaTreeEnvironment>::value, "");
+static_assert(default_is_top<ArrayEnvironment>::value, "");
+static_assert(default_is_top<RegisterEnvironment>::value, "");
+static_assert(default_is_bottom<HashedPartition>::value, "");
+static_assert(default_is_bottom<PatriciaTreePartition>::value, "");
+static_assert(default_is_bottom<SsaEnvironment>::value, "");
+
+static_assert(!default_is_bottom<HashedEnvironment>::value, "");
+static_assert(!default_is_top<HashedPartition>::value, "");
+static_assert(!default_is_top<Interval>::value, "");
+static_assert(!default_is_bottom<Interval>::value, "");
+
+static_assert(
+    same_default_binding<HashedEnvironment, PatriciaTreeEnvironment>::value,
+    "");
+static_assert(same_default_binding<HashedPartition, SsaEnvironment>::value,
+              "");
+static_assert(!same_default_binding<HashedEnvironment, HashedPartition>::value,
+              "");
+static_assert(!same_default_binding<Interval, Interval>::value, "");
+
+static_assert(std::is_same<default_binding_of<ArrayEnvironment>::type,
+                           DefaultIsTop>::value,
+              "");
+
+template <typename Map>
+class DefaultBindingTest : public ::testing::Test {};
+
+using Maps = ::testing::Types<HashedEnvironment,
+                              PatriciaTreeEnvironment,
+                              ArrayEnvironment,
+                              RegisterEnvironment,
+                              HashedPartition,
+                              PatriciaTreePartition,
+                              SsaEnvironment>;
+
+TYPED_TEST_CASE(DefaultBindingTest, Maps);
+
+/*
+ * Returns a non-extremal map in which only key 1 is bound.
+ */
+template <typename Map>
+Map make_map() {
+  Map map = default_is_top<Map>::value ? Map::top() : Map();
+  map.set(1, Interval::finite(0, 1));
+  return map;
+}
+
+} // namespace
+
+TYPED_TEST(DefaultBindingTest, missingKeys) {
+  using Map = TypeParam;
+  using Binding = typename default_binding_of<Map>::type;
+  Interval missing = default_binding_value<Binding, Interval>();
+
+  Map map = make_map<Map>();
+  ASSERT_FALSE(map.is_top());
+  ASSERT_FALSE(map.is_bottom());
+  EXPECT_EQ(Interval::finite(0, 1), map.get(1));
+  EXPECT_EQ(missing, map.get(0));
+  EXPECT_EQ(missing, map.get(2));
+}
+
+TYPED_TEST(DefaultBindingTest, bindingToDefaultIsUnbinding) {
+  using Map = TypeParam;
+  using Binding = typename default_binding_of<Map>::type;
+  Interval missing = default_binding_value<Binding, Interval>();
+
+  Map map = make_map<Map>();
+  Map other = map;
+  other.set(2, missing);
+  EXPECT_TRUE(other.equals(map));
+  EXPECT_TRUE(other.leq(map) && map.leq(other));
+
+  // Binding key 1 to the default value leaves no explicit binding.
+  other.set(1, missing);
+  EXPECT_EQ(missing, other.get(1));
+  EXPECT_FALSE(other.equals(map));
+}
+
+TYPED_TEST(DefaultBindingTest, extremalValues) {
+  using Map = TypeParam;
+  EXPECT_TRUE(Map::bottom().get(0).is_bottom());
+  EXPECT_TRUE(Map::top().get(0).is_top());
+}
+
+TEST(DefaultBindingTest, conventionValues) {
+  EXPECT_TRUE((default_binding_value<DefaultIsTop, Interval>().is_top()));
+  EXPECT_TRUE(
+      (default_binding_value<DefaultIsBottom, Interval>().is_bottom()));
+}
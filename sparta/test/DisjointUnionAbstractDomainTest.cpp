@@ -54,3 +54,25 @@ TEST(DisjointUnionAbstractDomainTest, basicOperations) {
   EXPECT_NLEQ(str, zero);
   EXPECT_NE(zero, str);
 }
+
+TEST(DisjointUnionAbstractDomainTest, canonicalize) {
+  auto holds_int = [](IntStringDomain x) {
+    bool holds = false;
+    x.apply<IntDomain>([&holds](IntDomain*) { holds = true; });
+    return holds;
+  };
+
+  IntStringDomain top = StringDomain::top();
+  EXPECT_FALSE(holds_int(top));
+  EXPECT_TRUE(holds_int(canonical(top)));
+  EXPECT_TRUE(canonical(top).is_top());
+
+  IntStringDomain bottom = StringDomain::bottom();
+  bottom.canonicalize();
+  EXPECT_TRUE(holds_int(bottom));
+  EXPECT_TRUE(bottom.is_bottom());
+
+  // The other values are left untouched.
+  EXPECT_FALSE(holds_int(canonical(IntStringDomain(StringDomain("foo")))));
+  EXPECT_EQ(IntDomain(1), canonical(IntDomain(1)));
+}
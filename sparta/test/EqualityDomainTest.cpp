@@ -0,0 +1,119 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "EqualityDomain.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+#include <vector>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using Equalities = EqualityDomain<uint32_t>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(EqualityDomain,
+                              AbstractDomainPropertyTest,
+                              Equalities);
+
+template <>
+std::vector<Equalities>
+AbstractDomainPropertyTest<Equalities>::non_extremal_values() {
+  Equalities e1;
+  e1.add_equality(1, 2);
+  Equalities e2;
+  e2.add_equality(1, 2).add_equality(2, 3);
+  Equalities e3;
+  e3.add_equality(0, 3).add_equality(4, 5);
+  return {e1, e2, e3};
+}
+
+TEST(EqualityDomainTest, unionFind) {
+  Equalities equalities;
+  EXPECT_TRUE(equalities.is_top());
+  EXPECT_TRUE(equalities.are_equal(1, 1));
+  EXPECT_FALSE(equalities.are_equal(1, 2));
+  EXPECT_EQ(1, equalities.representative(1));
+
+  equalities.add_equality(1, 2).add_equality(3, 0).add_equality(2, 3);
+  EXPECT_TRUE(equalities.are_equal(0, 1));
+  EXPECT_FALSE(equalities.are_equal(0, 4));
+  // The representative is the largest variable of the class.
+  for (uint32_t x = 0; x < 4; ++x) {
+    EXPECT_EQ(3, equalities.representative(x));
+  }
+  EXPECT_EQ(4, equalities.representative(4));
+  EXPECT_EQ(PatriciaTreeSet<uint32_t>({0, 1, 2, 3}), equalities.class_of(1));
+  EXPECT_EQ(PatriciaTreeSet<uint32_t>({4}), equalities.class_of(4));
+
+  equalities.add_equality(5, 6);
+  std::vector<std::vector<uint32_t>> classes{{0, 1, 2, 3}, {5, 6}};
+  EXPECT_EQ(classes, equalities.classes());
+  std::ostringstream out;
+  out << equalities;
+  EXPECT_EQ("{0 = 1 = 2 = 3, 5 = 6}", out.str());
+
+  // The representation is canonical.
+  Equalities other;
+  other.add_equality(6, 5).add_equality(0, 1).add_equality(2, 3).add_equality(
+      3, 0);
+  EXPECT_TRUE(equalities.equals(other));
+}
+
+TEST(EqualityDomainTest, copyPropagation) {
+  // x1 = x0; x2 = x1; x0 = x3
+  Equalities equalities;
+  equalities.assign(1, 0).assign(2, 1);
+  EXPECT_TRUE(equalities.are_equal(2, 0));
+  equalities.assign(0, 3);
+  EXPECT_TRUE(equalities.are_equal(1, 2));
+  EXPECT_TRUE(equalities.are_equal(0, 3));
+  EXPECT_FALSE(equalities.are_equal(0, 1));
+  EXPECT_EQ(2, equalities.representative(1));
+
+  // Forgetting the representative of a class elects a new one.
+  equalities.add_equality(1, 4);
+  EXPECT_EQ(4, equalities.representative(2));
+  equalities.forget(4);
+  EXPECT_EQ(2, equalities.representative(1));
+  EXPECT_EQ(4, equalities.representative(4));
+  equalities.forget(1);
+  std::vector<std::vector<uint32_t>> classes{{0, 3}};
+  EXPECT_EQ(classes, equalities.classes());
+  equalities.assign(3, 3);
+  EXPECT_TRUE(equalities.are_equal(0, 3));
+  equalities.forget(0);
+  EXPECT_TRUE(equalities.is_top());
+}
+
+TEST(EqualityDomainTest, latticeOperations) {
+  Equalities e1;
+  e1.add_equality(0, 1).add_equality(1, 2).add_equality(3, 4);
+  Equalities e2;
+  e2.add_equality(0, 1).add_equality(2, 3).add_equality(2, 4);
+
+  // Only the common equalities are kept by the join.
+  Equalities join = e1.join(e2);
+  std::vector<std::vector<uint32_t>> join_classes{{0, 1}, {3, 4}};
+  EXPECT_EQ(join_classes, join.classes());
+  EXPECT_TRUE(e1.leq(join));
+  EXPECT_TRUE(e2.leq(join));
+  EXPECT_FALSE(join.leq(e1));
+
+  Equalities meet = e1.meet(e2);
+  std::vector<std::vector<uint32_t>> meet_classes{{0, 1, 2, 3, 4}};
+  EXPECT_EQ(meet_classes, meet.classes());
+  EXPECT_TRUE(meet.leq(e1));
+  EXPECT_TRUE(meet.leq(e2));
+
+  EXPECT_TRUE(e1.join(Equalities::top()).is_top());
+  EXPECT_EQ(e1, e1.join(Equalities::bottom()));
+  EXPECT_EQ(e1, e1.meet(Equalities::top()));
+  EXPECT_TRUE(e1.meet(Equalities::bottom()).is_bottom());
+  EXPECT_TRUE(Equalities::bottom().are_equal(0, 1));
+}
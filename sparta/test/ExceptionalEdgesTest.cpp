@@ -0,0 +1,225 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "ExceptionalEdges.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <memory>
+#include <string>
+#include <unordered_map>
+#include <vector>
+
+#include "ConstantAbstractDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+
+using namespace sparta;
+
+namespace {
+
+/*
+ * A node is a sequence of assignments of constants to variables. The node may
+ * throw after the first `throw_index` assignments have been executed.
+ */
+struct Block {
+  std::vector<std::pair<uint32_t, int>> assignments;
+  size_t throw_index = 0;
+};
+
+struct Edge {
+  uint32_t source;
+  uint32_t target;
+  bool exceptional;
+};
+
+class Program final {
+ public:
+  using EdgeId = std::shared_ptr<Edge>;
+
+  explicit Program(uint32_t entry) : m_entry(entry) {}
+
+  void add(uint32_t node, const Block& block) {
+    m_blocks[node] = block;
+    m_successors[node];
+    m_predecessors[node];
+  }
+
+  void add_edge(uint32_t src, uint32_t dst, bool exceptional = false) {
+    auto edge = std::make_shared<Edge>(Edge{src, dst, exceptional});
+    m_successors[src].push_back(edge);
+    m_predecessors[dst].push_back(edge);
+  }
+
+  const Block& block_at(uint32_t node) const { return m_blocks.at(node); }
+
+ private:
+  uint32_t m_entry;
+  std::unordered_map<uint32_t, Block> m_blocks;
+  std::unordered_map<uint32_t, std::vector<EdgeId>> m_successors;
+  std::unordered_map<uint32_t, std::vector<EdgeId>> m_predecessors;
+
+  friend class ProgramInterface;
+};
+
+class ProgramInterface {
+ public:
+  using Graph = Program;
+  using NodeId = uint32_t;
+  using EdgeId = Program::EdgeId;
+
+  static NodeId entry(const Graph& graph) { return graph.m_entry; }
+  static std::vector<EdgeId> predecessors(const Graph& graph,
+                                          const NodeId& node) {
+    return graph.m_predecessors.at(node);
+  }
+  static std::vector<EdgeId> successors(const Graph& graph,
+                                        const NodeId& node) {
+    return graph.m_successors.at(node);
+  }
+  static NodeId source(const Graph&, const EdgeId& e) { return e->source; }
+  static NodeId target(const Graph&, const EdgeId& e) { return e->target; }
+  static bool is_exceptional(const Graph&, const EdgeId& e) {
+    return e->exceptional;
+  }
+};
+
+using Constant = ConstantAbstractDomain<int>;
+using Environment = PatriciaTreeMapAbstractEnvironment<uint32_t, Constant>;
+
+class Analyzer final
+    : public ExceptionalEdgesFixpointIterator<MonotonicFixpointIterator,
+                                              ProgramInterface,
+                                              Environment> {
+ public:
+  explicit Analyzer(const Program& program)
+      : ExceptionalEdgesFixpointIterator(program), m_program(program) {}
+
+  void analyze_node(const uint32_t& node,
+                    Environment* current_state) const override {
+    const auto& block = m_program.block_at(node);
+    execute(block, block.assignments.size(), current_state);
+  }
+
+  void analyze_node_until_throw(const uint32_t& node,
+                                Environment* current_state) const override {
+    const auto& block = m_program.block_at(node);
+    execute(block, block.throw_index, current_state);
+  }
+
+  Environment analyze_normal_edge(
+      const EdgeId&, const Environment& exit_state_at_source) const override {
+    return exit_state_at_source;
+  }
+
+  Environment analyze_exceptional_edge(
+      const EdgeId&, const Environment& throw_state_at_source) const override {
+    return throw_state_at_source;
+  }
+
+ private:
+  static void execute(const Block& block,
+                      size_t count,
+                      Environment* current_state) {
+    for (size_t i = 0; i < count; ++i) {
+      const auto& assignment = block.assignments[i];
+      current_state->set(assignment.first, Constant(assignment.second));
+    }
+  }
+
+  const Program& m_program;
+};
+
+constexpr uint32_t x = 1;
+constexpr uint32_t y = 2;
+
+} // namespace
+
+TEST(ExceptionalEdgesTest, adaptor) {
+  Program program(1);
+  program.add(1, Block{});
+  program.add(2, Block{});
+  program.add(3, Block{});
+  program.add_edge(1, 2);
+  program.add_edge(1, 3, /* exceptional */ true);
+  program.add_edge(2, 3, /* exceptional */ true);
+
+  using Adaptor = ExceptionalEdgesAdaptor<ProgramInterface>;
+  auto targets = [&](const std::vector<Program::EdgeId>& edges) {
+    std::vector<uint32_t> result;
+    for (const auto& edge : edges) {
+      result.push_back(edge->target);
+    }
+    return result;
+  };
+  auto sources = [&](const std::vector<Program::EdgeId>& edges) {
+    std::vector<uint32_t> result;
+    for (const auto& edge : edges) {
+      result.push_back(edge->source);
+    }
+    return result;
+  };
+
+  EXPECT_THAT(targets(Adaptor::normal_successors(program, 1)),
+              ::testing::ElementsAre(2));
+  EXPECT_THAT(targets(Adaptor::exceptional_successors(program, 1)),
+              ::testing::ElementsAre(3));
+  EXPECT_TRUE(Adaptor::normal_predecessors(program, 3).empty());
+  EXPECT_THAT(sources(Adaptor::exceptional_predecessors(program, 3)),
+              ::testing::UnorderedElementsAre(1, 2));
+}
+
+TEST(ExceptionalEdgesTest, partialEffectsOnThrowingEdge) {
+  /*
+   * 1: x = 0
+   * 2: try { x = 1; <may throw>; x = 2; y = 3; }
+   * 3: (normal successor of 2)
+   * 4: catch (exceptional successor of 2)
+   * 5: join of 3 and 4
+   */
+  Program program(1);
+  program.add(1, Block{{{x, 0}}, 0});
+  program.add(2, Block{{{x, 1}, {x, 2}, {y, 3}}, 1});
+  program.add(3, Block{});
+  program.add(4, Block{});
+  program.add(5, Block{});
+  program.add_edge(1, 2);
+  program.add_edge(2, 3);
+  program.add_edge(2, 4, /* exceptional */ true);
+  program.add_edge(3, 5);
+  program.add_edge(4, 5);
+
+  Analyzer analyzer(program);
+  analyzer.run(Environment::top());
+
+  auto normal = analyzer.get_entry_state_at(3);
+  EXPECT_EQ(normal.get(x), Constant(2));
+  EXPECT_EQ(normal.get(y), Constant(3));
+
+  auto exceptional = analyzer.get_entry_state_at(4);
+  EXPECT_EQ(exceptional.get(x), Constant(1));
+  EXPECT_TRUE(exceptional.get(y).is_top());
+
+  auto merged = analyzer.get_entry_state_at(5);
+  EXPECT_TRUE(merged.get(x).is_top());
+  EXPECT_TRUE(merged.get(y).is_top());
+}
+
+TEST(ExceptionalEdgesTest, unreachableThrowingNode) {
+  Program program(1);
+  program.add(1, Block{{{x, 0}}, 0});
+  program.add(2, Block{{{x, 1}}, 1});
+  program.add(3, Block{});
+  program.add_edge(1, 3);
+  // Node 2 is not reachable from the entry.
+  program.add_edge(2, 3, /* exceptional */ true);
+
+  Analyzer analyzer(program);
+  analyzer.run(Environment::top());
+
+  EXPECT_EQ(analyzer.get_entry_state_at(3).get(x), Constant(0));
+}
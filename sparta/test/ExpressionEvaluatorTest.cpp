@@ -0,0 +1,191 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "ExpressionEvaluator.h"
+
+#include <cstdint>
+#include <gtest/gtest.h>
+#include <limits>
+#include <memory>
+#include <string>
+
+#include "HashedAbstractEnvironment.h"
+#include "IntervalDomain.h"
+
+using namespace sparta;
+
+namespace {
+
+struct Expr;
+
+using ExprPtr = std::shared_ptr<Expr>;
+
+struct Expr {
+  ExpressionKind kind;
+  int64_t literal;
+  std::string variable;
+  UnaryOperator unary_operator;
+  BinaryOperator binary_operator;
+  ExprPtr left;
+  ExprPtr right;
+};
+
+ExprPtr lit(int64_t value) {
+  return std::make_shared<Expr>(
+      Expr{ExpressionKind::Literal, value, "", UnaryOperator::Neg,
+           BinaryOperator::Add, nullptr, nullptr});
+}
+
+ExprPtr var(const std::string& name) {
+  return std::make_shared<Expr>(
+      Expr{ExpressionKind::Variable, 0, name, UnaryOperator::Neg,
+           BinaryOperator::Add, nullptr, nullptr});
+}
+
+ExprPtr neg(ExprPtr operand) {
+  return std::make_shared<Expr>(
+      Expr{ExpressionKind::Unary, 0, "", UnaryOperator::Neg,
+           BinaryOperator::Add, std::move(operand), nullptr});
+}
+
+ExprPtr binary(BinaryOperator op, ExprPtr left, ExprPtr right) {
+  return std::make_shared<Expr>(Expr{ExpressionKind::Binary, 0, "",
+                                     UnaryOperator::Neg, op, std::move(left),
+                                     std::move(right)});
+}
+
+class ExprInterface {
+ public:
+  using Expression = Expr;
+
+  static ExpressionKind kind(const Expr& e) { return e.kind; }
+  static int64_t literal(const Expr& e) { return e.literal; }
+  static const std::string& variable(const Expr& e) { return e.variable; }
+  static UnaryOperator unary_operator(const Expr& e) {
+    return e.unary_operator;
+  }
+  static const Expr& operand(const Expr& e) { return *e.left; }
+  static BinaryOperator binary_operator(const Expr& e) {
+    return e.binary_operator;
+  }
+  static const Expr& left(const Expr& e) { return *e.left; }
+  static const Expr& right(const Expr& e) { return *e.right; }
+};
+
+using Constant = ConstantAbstractDomain<int32_t>;
+using Environment = HashedAbstractEnvironment<std::string, Constant>;
+
+Constant eval(const ExprPtr& e, const Environment& env) {
+  return evaluate<ExprInterface, Constant>(*e, env);
+}
+
+} // namespace
+
+TEST(ExpressionEvaluatorTest, constants) {
+  Environment env({{"x", Constant(3)}, {"y", Constant(4)}});
+
+  // x + 2 * y
+  auto e = binary(BinaryOperator::Add, var("x"),
+                  binary(BinaryOperator::Mul, lit(2), var("y")));
+  EXPECT_EQ(Constant(11), eval(e, env));
+  EXPECT_EQ(Constant(-3), eval(neg(var("x")), env));
+  EXPECT_EQ(Constant(-1),
+            eval(binary(BinaryOperator::Sub, var("x"), var("y")), env));
+  EXPECT_EQ(Constant(2),
+            eval(binary(BinaryOperator::Div, lit(7), var("x")), env));
+
+  EXPECT_EQ(Constant(1),
+            eval(binary(BinaryOperator::Lt, var("x"), var("y")), env));
+  EXPECT_EQ(Constant(0),
+            eval(binary(BinaryOperator::Eq, var("x"), var("y")), env));
+  EXPECT_EQ(Constant(1),
+            eval(binary(BinaryOperator::Ge, var("y"), lit(4)), env));
+
+  // Unbound variables are Top.
+  EXPECT_TRUE(eval(binary(BinaryOperator::Add, var("x"), var("z")), env)
+                  .is_top());
+  EXPECT_TRUE(eval(binary(BinaryOperator::Ne, var("z"), lit(0)), env)
+                  .is_top());
+}
+
+TEST(ExpressionEvaluatorTest, bottom) {
+  Environment env({{"x", Constant(3)}, {"y", Constant::bottom()}});
+  EXPECT_TRUE(env.is_bottom());
+  EXPECT_TRUE(eval(lit(1), env).is_bottom());
+
+  env = Environment({{"x", Constant(3)}});
+  EXPECT_TRUE(eval(binary(BinaryOperator::Div, var("x"), lit(0)), env)
+                  .is_bottom());
+  // Division by zero has no result, even if the dividend is unknown.
+  EXPECT_TRUE(eval(binary(BinaryOperator::Div, var("z"), lit(0)), env)
+                  .is_bottom());
+  EXPECT_TRUE(
+      eval(binary(BinaryOperator::Add, var("z"),
+                  binary(BinaryOperator::Div, var("x"), lit(0))),
+           env)
+          .is_bottom());
+}
+
+TEST(ExpressionEvaluatorTest, wraparound) {
+  constexpr int32_t min = std::numeric_limits<int32_t>::min();
+  constexpr int32_t max = std::numeric_limits<int32_t>::max();
+  Environment env({{"min", Constant(min)}, {"max", Constant(max)}});
+  EXPECT_EQ(Constant(min),
+            eval(binary(BinaryOperator::Add, var("max"), lit(1)), env));
+  EXPECT_EQ(Constant(max),
+            eval(binary(BinaryOperator::Sub, var("min"), lit(1)), env));
+  EXPECT_EQ(Constant(min), eval(neg(var("min")), env));
+  EXPECT_EQ(Constant(-2),
+            eval(binary(BinaryOperator::Mul, var("max"), lit(2)), env));
+  EXPECT_EQ(Constant(min),
+            eval(binary(BinaryOperator::Div, var("min"), lit(-1)), env));
+}
+
+TEST(ExpressionEvaluatorTest, bitwise) {
+  Environment env({{"x", Constant(12)}, {"y", Constant(10)}});
+  EXPECT_EQ(Constant(8),
+            eval(binary(BinaryOperator::And, var("x"), var("y")), env));
+  EXPECT_EQ(Constant(14),
+            eval(binary(BinaryOperator::Or, var("x"), var("y")), env));
+  EXPECT_EQ(Constant(6),
+            eval(binary(BinaryOperator::Xor, var("x"), var("y")), env));
+  EXPECT_EQ(Constant(48),
+            eval(binary(BinaryOperator::Shl, var("x"), lit(2)), env));
+  EXPECT_EQ(Constant(-2),
+            eval(binary(BinaryOperator::Shr, lit(-8), lit(2)), env));
+  EXPECT_TRUE(
+      eval(binary(BinaryOperator::Shl, var("x"), lit(32)), env).is_top());
+}
+
+TEST(ExpressionEvaluatorTest, intervals) {
+  using Interval = IntervalDomain<int32_t>;
+  using IntervalEnvironment = HashedAbstractEnvironment<std::string, Interval>;
+  IntervalEnvironment env(
+      {{"x", Interval::finite(0, 10)}, {"y", Interval::finite(2, 3)}});
+  auto eval = [&env](const ExprPtr& e) {
+    return evaluate<ExprInterface, Interval>(*e, env);
+  };
+
+  // 2 * x - y
+  EXPECT_EQ(Interval::finite(-3, 18),
+            eval(binary(BinaryOperator::Sub,
+                        binary(BinaryOperator::Mul, lit(2), var("x")),
+                        var("y"))));
+  EXPECT_EQ(Interval::finite(0, 5),
+            eval(binary(BinaryOperator::Div, var("x"), var("y"))));
+  EXPECT_EQ(Interval::finite(-10, 0), eval(neg(var("x"))));
+
+  // Comparisons evaluate to 0 or 1.
+  EXPECT_EQ(Interval::finite(1, 1),
+            eval(binary(BinaryOperator::Lt, var("y"), lit(4))));
+  EXPECT_EQ(Interval::finite(0, 0),
+            eval(binary(BinaryOperator::Gt, var("y"), lit(4))));
+  EXPECT_EQ(Interval::finite(0, 1),
+            eval(binary(BinaryOperator::Le, var("x"), var("y"))));
+  EXPECT_EQ(Interval::finite(1, 1),
+            eval(binary(BinaryOperator::Ne, var("x"), lit(11))));
+}
@@ -0,0 +1,188 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "FallbackDomain.h"
+
+#include <algorithm>
+#include <gtest/gtest.h>
+#include <vector>
+
+#include "AbstractDomainPropertyTest.h"
+#include "HashedSetAbstractDomain.h"
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+using IntSet = HashedSetAbstractDomain<int>;
+using Interval = IntervalDomain<int>;
+
+struct SizePolicy {
+  static Interval coarsen(const IntSet& x) {
+    if (x.is_top()) {
+      return Interval::top();
+    }
+    if (x.is_bottom() || x.size() == 0) {
+      return Interval::bottom();
+    }
+    const auto& elements = x.elements();
+    auto bounds = std::minmax_element(elements.begin(), elements.end());
+    return Interval::finite(*bounds.first, *bounds.second);
+  }
+
+  static bool exceeds_budget(const IntSet& x) {
+    return x.is_value() && x.size() > 4;
+  }
+};
+
+struct TimePolicy {
+  static FallbackDeadline deadline;
+
+  static Interval coarsen(const IntSet& x) { return SizePolicy::coarsen(x); }
+
+  static bool exceeds_budget(const IntSet&) { return deadline.expired(); }
+};
+
+FallbackDeadline TimePolicy::deadline;
+
+using Domain = FallbackDomain<IntSet, Interval, SizePolicy>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(FallbackDomain,
+                              AbstractDomainPropertyTest,
+                              Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  // The order between precise and coarse values is not a lattice order (e.g.,
+  // the meet of a precise value and a coarse value is coarse, hence not below
+  // the precise value), so we only mix them with the extremal values.
+  return {Domain(IntSet{1}), Domain(IntSet{1, 2}), Domain(IntSet{3}),
+          Domain(IntSet{2, 3, 4})};
+}
+
+TEST(FallbackDomainTest, sizeBudget) {
+  Domain x(IntSet{1, 2, 3});
+  EXPECT_TRUE(x.is_precise());
+  EXPECT_EQ(IntSet({1, 2, 3}), x.precise());
+  EXPECT_EQ(Interval::finite(1, 3), x.coarse());
+
+  x.join_with(Domain(IntSet{5}));
+  EXPECT_TRUE(x.is_precise());
+  EXPECT_EQ(IntSet({1, 2, 3, 5}), x.precise());
+
+  x.join_with(Domain(IntSet{8}));
+  EXPECT_TRUE(x.is_coarse());
+  EXPECT_THROW(x.precise(), undefined_operation);
+  EXPECT_EQ(Interval::finite(1, 8), x.coarse());
+
+  Domain y(IntSet{1, 2, 3, 4, 5, 6});
+  EXPECT_TRUE(y.is_coarse());
+  EXPECT_EQ(Interval::finite(1, 6), y.coarse());
+
+  Domain z(IntSet{10});
+  z.apply([](IntSet* s) { s->add({11, 12, 13, 14}); },
+          [](Interval*) { FAIL(); });
+  EXPECT_TRUE(z.is_coarse());
+  z.apply([](IntSet*) { FAIL(); }, [](Interval* i) { *i += 1; });
+  EXPECT_EQ(Interval::finite(11, 15), z.coarse());
+
+  EXPECT_TRUE(Domain::bottom().is_precise());
+  EXPECT_TRUE(Domain::top().is_precise());
+}
+
+TEST(FallbackDomainTest, mixedOperations) {
+  Domain precise(IntSet{2, 3});
+  Domain coarse(Interval::finite(0, 10));
+
+  EXPECT_TRUE(precise.leq(coarse));
+  EXPECT_FALSE(coarse.leq(precise));
+  EXPECT_FALSE(Domain(Interval::finite(2, 3)).leq(precise));
+  EXPECT_TRUE(Domain(Interval::bottom()).leq(precise));
+  EXPECT_TRUE(coarse.leq(Domain::top()));
+  EXPECT_TRUE(Domain(Interval::top()).equals(Domain::top()));
+  EXPECT_TRUE(Domain(Interval::bottom()).equals(Domain::bottom()));
+
+  Domain join = precise.join(Domain(Interval::finite(5, 6)));
+  EXPECT_TRUE(join.is_coarse());
+  EXPECT_EQ(Interval::finite(2, 6), join.coarse());
+
+  Domain meet = precise.meet(coarse);
+  EXPECT_TRUE(meet.is_coarse());
+  EXPECT_EQ(Interval::finite(2, 3), meet.coarse());
+
+  Domain widening = Domain(Interval::finite(0, 1)).widening(precise);
+  EXPECT_EQ(Interval::bounded_below(0), widening.coarse());
+}
+
+TEST(FallbackDomainTest, timeBudget) {
+  using TimedDomain = FallbackDomain<IntSet, Interval, TimePolicy>;
+  TimedDomain x(IntSet{1, 2, 3, 4, 5, 6});
+  EXPECT_TRUE(x.is_precise());
+
+  TimePolicy::deadline.start(std::chrono::hours(1));
+  x.join_with(TimedDomain(IntSet{7}));
+  EXPECT_TRUE(x.is_precise());
+
+  TimePolicy::deadline.start(std::chrono::seconds(0));
+  x.join_with(TimedDomain(IntSet{8}));
+  EXPECT_TRUE(x.is_coarse());
+  EXPECT_EQ(Interval::finite(1, 8), x.coarse());
+
+  TimePolicy::deadline.reset();
+  EXPECT_TRUE(TimedDomain(IntSet{1}).is_precise());
+}
+
+namespace {
+
+/*
+ * Analyzes the values of `x` in the program:
+ *
+ *   0: x = 0
+ *   1: while (*) {
+ *   2:   x = x + 1
+ *      }
+ */
+class FallbackCounterAnalyzer final
+    : public MonotonicFixpointIterator<GraphInterface, Domain> {
+ public:
+  using MonotonicFixpointIterator::MonotonicFixpointIterator;
+
+  void analyze_node(const uint32_t& node, Domain* state) const override {
+    if (node == 0) {
+      *state = Domain(IntSet{0});
+    } else if (node == 2) {
+      state->apply(
+          [](IntSet* s) {
+            IntSet incremented;
+            for (int v : s->elements()) {
+              incremented.add(v + 1);
+            }
+            *s = incremented;
+          },
+          [](Interval* i) { *i += 1; });
+    }
+  }
+
+  Domain analyze_edge(const size_t&, const Domain& state) const override {
+    return state;
+  }
+};
+
+} // namespace
+
+TEST(FallbackDomainTest, fixpoint) {
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 1);
+  FallbackCounterAnalyzer analyzer(graph);
+  analyzer.run(Domain());
+  Domain loop = analyzer.get_entry_state_at(1);
+  EXPECT_TRUE(loop.is_coarse());
+  EXPECT_EQ(Interval::bounded_below(0), loop.coarse());
+}
@@ -0,0 +1,91 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <gtest/gtest.h>
+
+#include "Fingerprint.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+#include "WeakPartialOrdering.h"
+
+using namespace sparta;
+
+namespace {
+
+uint64_t wpo_fingerprint(const Graph& graph) {
+  WeakPartialOrdering<uint32_t> wpo(
+      0, successor_nodes<GraphInterface>(graph), false);
+  return wpo.fingerprint();
+}
+
+} // namespace
+
+TEST(FingerprintTest, sequenceAndMultiset) {
+  EXPECT_EQ(Fingerprint().add(1).add(2).value(),
+            Fingerprint().add(1).add(2).value());
+  EXPECT_NE(Fingerprint().add(1).add(2).value(),
+            Fingerprint().add(2).add(1).value());
+  EXPECT_NE(Fingerprint().add(1).value(), Fingerprint().add(1).add(0).value());
+  EXPECT_EQ(Fingerprint().add_unordered(1).add_unordered(2).value(),
+            Fingerprint().add_unordered(2).add_unordered(1).value());
+  // Unordered values are a multiset, not a set.
+  EXPECT_NE(Fingerprint().add_unordered(1).value(),
+            Fingerprint().add_unordered(1).add_unordered(1).value());
+  EXPECT_NE(Fingerprint().add(1).value(),
+            Fingerprint().add_unordered(1).value());
+}
+
+TEST(FingerprintTest, graph) {
+  Graph graph = make_loop();
+  uint64_t fp = graph_fingerprint<GraphInterface>(graph);
+  EXPECT_EQ(fp, graph_fingerprint<GraphInterface>(make_loop()));
+
+  // The order of the successors doesn't matter.
+  Graph reordered;
+  reordered.add_edge(2, 3);
+  reordered.add_edge(2, 1);
+  reordered.add_edge(1, 2);
+  reordered.add_edge(0, 1);
+  EXPECT_EQ(fp, graph_fingerprint<GraphInterface>(reordered));
+
+  // Unreachable edges don't matter.
+  Graph with_unreachable = make_loop();
+  with_unreachable.add_edge(4, 1);
+  EXPECT_EQ(fp, graph_fingerprint<GraphInterface>(with_unreachable));
+
+  Graph with_exit_edge = make_loop();
+  with_exit_edge.add_edge(3, 1);
+  EXPECT_NE(fp, graph_fingerprint<GraphInterface>(with_exit_edge));
+
+  Graph reversed;
+  reversed.add_edge(0, 1);
+  reversed.add_edge(1, 2);
+  reversed.add_edge(2, 1);
+  reversed.add_edge(1, 3);
+  EXPECT_NE(fp, graph_fingerprint<GraphInterface>(reversed));
+}
+
+TEST(FingerprintTest, weakPartialOrdering) {
+  uint64_t fp = wpo_fingerprint(make_loop());
+  EXPECT_EQ(fp, wpo_fingerprint(make_loop()));
+
+  Graph with_exit_edge = make_loop();
+  with_exit_edge.add_edge(3, 1);
+  EXPECT_NE(fp, wpo_fingerprint(with_exit_edge));
+
+  Graph without_loop;
+  without_loop.add_edge(0, 1);
+  without_loop.add_edge(1, 2);
+  without_loop.add_edge(2, 3);
+  EXPECT_NE(fp, wpo_fingerprint(without_loop));
+
+  // The lifted WPO used to build a WTO is a different ordering.
+  Graph graph = make_loop();
+  WeakPartialOrdering<uint32_t> lifted(
+      0, successor_nodes<GraphInterface>(graph), true);
+  EXPECT_NE(fp, lifted.fingerprint());
+}
@@ -0,0 +1,200 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "FixpointTrace.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+#include <vector>
+
+#include "HashedSetAbstractDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "NodeInfo.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+/*
+ * Collects the nodes along the paths that lead to a node. Each node adds
+ * itself, as well as its successor modulo 5, so that the loops take several
+ * iterations to converge.
+ */
+using Domain = HashedSetAbstractDomain<uint32_t>;
+
+template <template <typename, typename, typename> class Iterator>
+class PathAnalyzer final
+    : public Iterator<GraphInterface, Domain, std::hash<uint32_t>> {
+ public:
+  using Base = Iterator<GraphInterface, Domain, std::hash<uint32_t>>;
+
+  using Base::Base;
+
+  void analyze_node(const uint32_t& node, Domain* state) const override {
+    state->add(node);
+    if (state->contains(node % 5)) {
+      state->add((node + 1) % 5);
+    }
+  }
+
+  Domain analyze_edge(const size_t&, const Domain& state) const override {
+    return state;
+  }
+};
+
+uint64_t digest(const Domain& state) {
+  if (!state.is_value()) {
+    return state.is_top() ? 1 : 0;
+  }
+  uint64_t result = 2;
+  for (auto element : state.elements()) {
+    result += (uint64_t(element) + 1) * 0x9e3779b97f4a7c15ULL;
+  }
+  return result;
+}
+
+Graph make_graph() {
+  //  0 -> 1 -> 2 -> 3 -> 4 -> 9
+  //       ^         |    ^
+  //       +---------+    |
+  //  0 -> 5 -> 6 ---+----+
+  //       ^    |
+  //       +----7
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 3);
+  graph.add_edge(3, 1);
+  graph.add_edge(3, 4);
+  graph.add_edge(4, 9);
+  graph.add_edge(0, 5);
+  graph.add_edge(5, 6);
+  graph.add_edge(6, 7);
+  graph.add_edge(7, 5);
+  graph.add_edge(6, 4);
+  return graph;
+}
+
+} // namespace
+
+TEST(FixpointTraceTest, replayParallelRun) {
+  Graph graph = make_graph();
+  PathAnalyzer<ParallelMonotonicFixpointIterator> parallel(graph, 4);
+  FixpointTrace trace;
+  parallel.set_trace(&trace, digest);
+  parallel.run(Domain());
+  EXPECT_EQ(11, trace.nodes().size());
+  EXPECT_LE(trace.nodes().size(), trace.events().size());
+
+  std::stringstream buffer;
+  trace.write(buffer);
+  FixpointTrace saved = FixpointTrace::read(buffer);
+  EXPECT_EQ(trace.events(), saved.events());
+
+  PathAnalyzer<MonotonicFixpointIterator> sequential(graph);
+  EXPECT_EQ(boost::none, sequential.replay(Domain(), saved, digest));
+  for (uint32_t node : {0, 1, 2, 3, 4, 5, 6, 7, 9}) {
+    EXPECT_EQ(parallel.get_entry_state_at(node),
+              sequential.get_entry_state_at(node));
+    EXPECT_EQ(parallel.get_exit_state_at(node),
+              sequential.get_exit_state_at(node));
+  }
+}
+
+TEST(FixpointTraceTest, divergence) {
+  Graph graph = make_graph();
+  PathAnalyzer<MonotonicFixpointIterator> fp(graph);
+  FixpointTrace trace;
+  fp.set_trace(&trace, digest);
+  fp.run(Domain());
+  const auto& events = trace.events();
+  EXPECT_EQ(boost::none, fp.replay(Domain(), trace, digest));
+  // The digests are only checked when a digest function is provided.
+  EXPECT_EQ(boost::none, fp.replay(Domain(), trace));
+
+  // Replaying the run from a different initial state.
+  Domain init;
+  init.add(42);
+  EXPECT_EQ(0, fp.replay(init, trace, digest));
+  EXPECT_EQ(boost::none, fp.replay(init, trace));
+
+  // Truncating the trace.
+  std::stringstream buffer;
+  trace.write(buffer);
+  std::string bytes = buffer.str();
+  // Each event takes 13 bytes, and the number of events is stored right
+  // before the events.
+  size_t events_offset = bytes.size() - 13 * events.size();
+  bytes[events_offset - 8] = static_cast<char>(events.size() - 1);
+  bytes.resize(bytes.size() - 13);
+  std::istringstream truncated_buffer(bytes);
+  auto truncated = FixpointTrace::read(truncated_buffer);
+  EXPECT_EQ(events.size() - 1, fp.replay(Domain(), truncated, digest));
+
+  // A schedule that violates the WPO.
+  bytes = buffer.str();
+  bytes[events_offset + 1] = static_cast<char>(events[1].wpo_idx);
+  std::istringstream reordered_buffer(bytes);
+  auto reordered = FixpointTrace::read(reordered_buffer);
+  EXPECT_EQ(0, fp.replay(Domain(), reordered, digest));
+}
+
+TEST(FixpointTraceTest, labels) {
+  Graph graph = make_graph();
+  HashedNodeInfo<uint32_t> node_info;
+  node_info.set_location(4, SourceLocation{"foo", 0x10, 3});
+  PathAnalyzer<MonotonicFixpointIterator> fp(graph);
+  FixpointTrace trace;
+  fp.set_trace(&trace, nullptr, &node_info);
+  fp.run(Domain());
+
+  std::stringstream buffer;
+  trace.write(buffer);
+  FixpointTrace saved = FixpointTrace::read(buffer);
+  std::vector<std::string> labels;
+  for (const auto& node : saved.nodes()) {
+    if (node.type != FixpointTrace::Node::Type::Exit) {
+      labels.push_back(node.label);
+    }
+    EXPECT_EQ(node.type == FixpointTrace::Node::Type::Exit,
+              !node.num_outer_preds.empty());
+  }
+  std::sort(labels.begin(), labels.end());
+  EXPECT_EQ(std::vector<std::string>(
+                {"0", "1", "2", "3", "5", "6", "7", "9", "foo+0x10 (line 3)"}),
+            labels);
+  for (const auto& event : saved.events()) {
+    EXPECT_EQ(0, event.digest);
+  }
+}
+
+TEST(FixpointTraceTest, malformedInput) {
+  std::istringstream empty("");
+  EXPECT_THROW(FixpointTrace::read(empty), invalid_argument);
+
+  std::istringstream garbage("SPARTATX\x01\x00\x00\x00");
+  EXPECT_THROW(FixpointTrace::read(garbage), invalid_argument);
+
+  Graph graph = make_graph();
+  PathAnalyzer<MonotonicFixpointIterator> fp(graph);
+  FixpointTrace trace;
+  fp.set_trace(&trace);
+  fp.run(Domain());
+  std::stringstream buffer;
+  trace.write(buffer);
+  std::string bytes = buffer.str();
+  bytes.resize(bytes.size() - 1);
+  std::istringstream truncated(bytes);
+  EXPECT_THROW(FixpointTrace::read(truncated), invalid_argument);
+
+  Graph other_graph;
+  other_graph.add_edge(0, 1);
+  PathAnalyzer<MonotonicFixpointIterator> other_fp(other_graph);
+  EXPECT_THROW(other_fp.replay(Domain(), trace), invalid_argument);
+}
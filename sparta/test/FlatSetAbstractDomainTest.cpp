@@ -0,0 +1,98 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "FlatSetAbstractDomain.h"
+
+#include <deque>
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+#include <tuple>
+#include <vector>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using Domain = FlatSetAbstractDomain<std::string>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(FlatSetAbstractDomain,
+                              AbstractDomainPropertyTest,
+                              Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  Domain e1("a");
+  Domain e2({"a", "b", "c"});
+  Domain e3({"b", "c", "d"});
+  return {e1, e2, e3};
+}
+
+namespace {
+
+// A type without a hash function.
+struct Register {
+  unsigned number;
+  bool wide;
+
+  friend bool operator<(const Register& r1, const Register& r2) {
+    return std::tie(r1.number, r1.wide) < std::tie(r2.number, r2.wide);
+  }
+
+  friend bool operator==(const Register& r1, const Register& r2) {
+    return r1.number == r2.number && r1.wide == r2.wide;
+  }
+
+  friend std::ostream& operator<<(std::ostream& o, const Register& r) {
+    return o << (r.wide ? "w" : "v") << r.number;
+  }
+};
+
+} // namespace
+
+TEST(FlatSetAbstractDomainTest, latticeOperations) {
+  Domain e1("a");
+  Domain e2({"a", "b", "c"});
+  Domain e3({"b", "c", "d"});
+
+  EXPECT_THAT(e2.elements(), ::testing::ElementsAre("a", "b", "c"));
+  EXPECT_TRUE(e1.leq(e2));
+  EXPECT_FALSE(e2.leq(e3));
+  EXPECT_THAT(e2.join(e3).elements(),
+              ::testing::ElementsAre("a", "b", "c", "d"));
+  EXPECT_THAT(e2.meet(e3).elements(), ::testing::ElementsAre("b", "c"));
+  EXPECT_EQ(0, e1.meet(e3).size());
+  EXPECT_FALSE(e1.meet(e3).is_bottom());
+
+  Domain d = e2;
+  d.difference_with(e3);
+  EXPECT_EQ(e1, d);
+
+  d.add({"d", "c"});
+  d.remove("a");
+  EXPECT_THAT(d.elements(), ::testing::ElementsAre("c", "d"));
+  EXPECT_TRUE(d.contains("d"));
+  EXPECT_FALSE(d.contains("a"));
+  EXPECT_TRUE(Domain::top().contains("a"));
+}
+
+TEST(FlatSetAbstractDomainTest, customElementsAndStorage) {
+  using Registers = FlatSetAbstractDomain<Register,
+                                          std::less<Register>,
+                                          std::equal_to<Register>,
+                                          std::deque<Register>>;
+  Registers r1({Register{2, false}, Register{1, true}});
+  Registers r2(Register{1, true});
+  EXPECT_TRUE(r2.leq(r1));
+  r2.add(Register{1, false});
+  EXPECT_EQ(3, r1.join(r2).size());
+
+  std::ostringstream out;
+  out << r1;
+  EXPECT_EQ("[#2]{w1, v2}", out.str());
+}
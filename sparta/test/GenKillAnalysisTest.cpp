@@ -0,0 +1,313 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "GenKillAnalysis.h"
+
+#include <algorithm>
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <string>
+#include <unordered_map>
+#include <utility>
+#include <vector>
+
+#include "BitsetAbstractDomain.h"
+#include "BitsetDomain.h"
+#include "HashedSetAbstractDomain.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+struct Statement {
+  std::vector<std::string> use;
+  std::vector<std::string> def;
+};
+
+/*
+ * A program is a control-flow graph where each node is labeled with a
+ * statement. Node 0 is the entry and the last node is the exit.
+ */
+class Program final : public Graph {
+ public:
+  explicit Program(std::vector<Statement> statements)
+      : m_statements(std::move(statements)) {}
+
+  const std::vector<Statement>& statements() const { return m_statements; }
+
+ private:
+  std::vector<Statement> m_statements;
+};
+
+class ProgramInterface : public GraphInterface {
+ public:
+  using Graph = Program;
+
+  static NodeId exit(const Graph& graph) {
+    return graph.statements().size() - 1;
+  }
+};
+
+using VariableSet = HashedSetAbstractDomain<std::string>;
+using DefinitionSet = HashedSetAbstractDomain<uint32_t>;
+using DefinitionBitset = BitsetAbstractDomain<8>;
+
+class LivenessRelations {
+ public:
+  explicit LivenessRelations(const Program& program) : m_program(program) {}
+
+  const std::vector<std::string>& gen(uint32_t node) const {
+    return m_program.statements()[node].use;
+  }
+
+  const std::vector<std::string>& kill(uint32_t node) const {
+    return m_program.statements()[node].def;
+  }
+
+ private:
+  const Program& m_program;
+};
+
+/*
+ * The variables are numbered, so that the sets of variables can be bitsets.
+ */
+class NumberedLivenessRelations {
+ public:
+  explicit NumberedLivenessRelations(const Program& program) {
+    for (const auto& statement : program.statements()) {
+      std::vector<size_t> use, def;
+      for (const auto& variable : statement.use) {
+        use.push_back(m_variables.number(variable));
+      }
+      for (const auto& variable : statement.def) {
+        def.push_back(m_variables.number(variable));
+      }
+      m_gen.push_back(std::move(use));
+      m_kill.push_back(std::move(def));
+    }
+  }
+
+  const std::vector<size_t>& gen(uint32_t node) const { return m_gen[node]; }
+
+  const std::vector<size_t>& kill(uint32_t node) const { return m_kill[node]; }
+
+  const FactNumberer<std::string>& variables() const { return m_variables; }
+
+ private:
+  FactNumberer<std::string> m_variables;
+  std::vector<std::vector<size_t>> m_gen;
+  std::vector<std::vector<size_t>> m_kill;
+};
+
+/*
+ * A definition is identified by the node of the statement.
+ */
+class ReachingDefinitionsRelations {
+ public:
+  explicit ReachingDefinitionsRelations(const Program& program)
+      : m_program(program) {
+    const auto& statements = program.statements();
+    for (uint32_t node = 0; node < statements.size(); ++node) {
+      for (const auto& variable : statements[node].def) {
+        m_definitions[variable].push_back(node);
+      }
+    }
+  }
+
+  std::vector<uint32_t> gen(uint32_t node) const {
+    if (m_program.statements()[node].def.empty()) {
+      return {};
+    }
+    return {node};
+  }
+
+  std::vector<uint32_t> kill(uint32_t node) const {
+    std::vector<uint32_t> result;
+    for (const auto& variable : m_program.statements()[node].def) {
+      const auto& definitions = m_definitions.at(variable);
+      result.insert(result.end(), definitions.begin(), definitions.end());
+    }
+    return result;
+  }
+
+ private:
+  const Program& m_program;
+  std::unordered_map<std::string, std::vector<uint32_t>> m_definitions;
+};
+
+/*
+ *   0: x = 1
+ *   1: y = x
+ *   2: if (y) goto 4
+ *   3: x = y + 1; goto 1
+ *   4: return x
+ */
+Program make_program() {
+  Program program({{{}, {"x"}},
+                   {{"x"}, {"y"}},
+                   {{"y"}, {}},
+                   {{"y"}, {"x"}},
+                   {{"x"}, {}}});
+  program.add_edge(0, 1);
+  program.add_edge(1, 2);
+  program.add_edge(2, 3);
+  program.add_edge(2, 4);
+  program.add_edge(3, 1);
+  return program;
+}
+
+} // namespace
+
+TEST(GenKillAnalysisTest, liveness) {
+  Program program = make_program();
+  LivenessRelations relations(program);
+  GenKillAnalysis<ProgramInterface,
+                  VariableSet,
+                  LivenessRelations,
+                  AnalysisDirection::Backward>
+      liveness(program, relations);
+  liveness.run(VariableSet());
+
+  EXPECT_EQ(VariableSet(), liveness.get_state_before(0));
+  EXPECT_EQ(VariableSet({"x"}), liveness.get_state_after(0));
+  EXPECT_EQ(VariableSet({"x"}), liveness.get_state_before(1));
+  EXPECT_EQ(VariableSet({"x", "y"}), liveness.get_state_before(2));
+  EXPECT_EQ(VariableSet({"x", "y"}), liveness.get_state_after(2));
+  EXPECT_EQ(VariableSet({"y"}), liveness.get_state_before(3));
+  EXPECT_EQ(VariableSet({"x"}), liveness.get_state_before(4));
+  EXPECT_EQ(VariableSet(), liveness.get_state_after(4));
+  // The states are the ones of the reversed graph.
+  EXPECT_EQ(liveness.get_exit_state_at(2), liveness.get_state_before(2));
+}
+
+TEST(GenKillAnalysisTest, livenessWithNumberedVariables) {
+  Program program = make_program();
+  NumberedLivenessRelations relations(program);
+  GenKillAnalysis<ProgramInterface,
+                  BitsetDomain,
+                  NumberedLivenessRelations,
+                  AnalysisDirection::Backward>
+      liveness(program, relations);
+  liveness.run(BitsetDomain());
+
+  const auto& variables = relations.variables();
+  EXPECT_EQ(BitsetDomain(), liveness.get_state_before(0));
+  EXPECT_THAT(variables.decode(liveness.get_state_before(2)),
+              ::testing::ElementsAre("x", "y"));
+  EXPECT_THAT(variables.decode(liveness.get_state_before(3)),
+              ::testing::ElementsAre("y"));
+  EXPECT_THAT(variables.decode(liveness.get_state_before(4)),
+              ::testing::ElementsAre("x"));
+}
+
+TEST(GenKillAnalysisTest, reachingDefinitions) {
+  Program program = make_program();
+  ReachingDefinitionsRelations relations(program);
+  GenKillAnalysis<ProgramInterface,
+                  DefinitionSet,
+                  ReachingDefinitionsRelations,
+                  AnalysisDirection::Forward>
+      reaching_definitions(program, relations);
+  reaching_definitions.run(DefinitionSet());
+
+  EXPECT_EQ(DefinitionSet(), reaching_definitions.get_state_before(0));
+  EXPECT_EQ(DefinitionSet({0}), reaching_definitions.get_state_after(0));
+  EXPECT_EQ(DefinitionSet({0, 1, 3}), reaching_definitions.get_state_before(1));
+  EXPECT_EQ(DefinitionSet({0, 1, 3}), reaching_definitions.get_state_after(1));
+  EXPECT_EQ(DefinitionSet({1, 3}), reaching_definitions.get_state_after(3));
+  EXPECT_EQ(DefinitionSet({0, 1, 3}), reaching_definitions.get_state_before(4));
+  EXPECT_EQ(reaching_definitions.get_entry_state_at(4),
+            reaching_definitions.get_state_before(4));
+}
+
+TEST(GenKillAnalysisTest, reachingDefinitionsWithBitsets) {
+  Program program = make_program();
+  ReachingDefinitionsRelations relations(program);
+  GenKillAnalysis<ProgramInterface,
+                  DefinitionSet,
+                  ReachingDefinitionsRelations,
+                  AnalysisDirection::Forward>
+      reference(program, relations);
+  reference.run(DefinitionSet());
+  GenKillAnalysis<ProgramInterface,
+                  DefinitionBitset,
+                  ReachingDefinitionsRelations,
+                  AnalysisDirection::Forward>
+      reaching_definitions(program, relations);
+  reaching_definitions.run(DefinitionBitset());
+
+  EXPECT_EQ(DefinitionBitset({0, 1, 3}),
+            reaching_definitions.get_state_before(1));
+  EXPECT_EQ(DefinitionBitset({1, 3}), reaching_definitions.get_state_after(3));
+  for (uint32_t node = 0; node < program.statements().size(); ++node) {
+    auto definitions = reference.get_state_after(node);
+    std::vector<size_t> expected(definitions.elements().begin(),
+                                 definitions.elements().end());
+    std::sort(expected.begin(), expected.end());
+    EXPECT_EQ(expected,
+              reaching_definitions.get_state_after(node).elements())
+        << node;
+  }
+
+  // The precomputed bitsets are reused by subsequent runs.
+  reaching_definitions.run(DefinitionBitset::bottom());
+  EXPECT_TRUE(reaching_definitions.get_state_after(4).is_bottom());
+  reaching_definitions.run(DefinitionBitset({5}));
+  EXPECT_EQ(DefinitionBitset({1, 3, 5}),
+            reaching_definitions.get_state_after(3));
+}
+
+namespace {
+
+/*
+ * Reaching definitions computed by a fixpoint iterator of our own choosing.
+ */
+class ReachingDefinitionsIterator final
+    : public WTOMonotonicFixpointIterator<ProgramInterface, DefinitionBitset> {
+ public:
+  ReachingDefinitionsIterator(const Program& program,
+                              const ReachingDefinitionsRelations& relations)
+      : WTOMonotonicFixpointIterator(program), m_transformer(relations) {}
+
+  void analyze_node(const uint32_t& node,
+                    DefinitionBitset* current_state) const override {
+    m_transformer.apply(node, current_state);
+  }
+
+  DefinitionBitset analyze_edge(
+      const size_t&, const DefinitionBitset& state) const override {
+    return state;
+  }
+
+ private:
+  mutable GenKillTransformer<DefinitionBitset,
+                             ReachingDefinitionsRelations,
+                             uint32_t>
+      m_transformer;
+};
+
+} // namespace
+
+TEST(GenKillAnalysisTest, standaloneTransformer) {
+  Program program = make_program();
+  ReachingDefinitionsRelations relations(program);
+  GenKillAnalysis<ProgramInterface,
+                  DefinitionBitset,
+                  ReachingDefinitionsRelations,
+                  AnalysisDirection::Forward>
+      reference(program, relations);
+  reference.run(DefinitionBitset());
+  ReachingDefinitionsIterator reaching_definitions(program, relations);
+  reaching_definitions.run(DefinitionBitset());
+
+  for (uint32_t node = 0; node < program.statements().size(); ++node) {
+    EXPECT_EQ(reference.get_exit_state_at(node),
+              reaching_definitions.get_exit_state_at(node))
+        << node;
+  }
+}
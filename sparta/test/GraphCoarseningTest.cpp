@@ -0,0 +1,201 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "GraphCoarsening.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <vector>
+
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+/*
+ * Node 0 initializes a variable to 0, every other node adds its identifier to
+ * it and every edge adds 1. The edge leaving the loop bounds the variable.
+ */
+void transfer_node(uint32_t node, Interval* state) {
+  if (node == 0) {
+    *state = Interval::finite(0, 0);
+  } else {
+    *state += node;
+  }
+}
+
+Interval transfer_edge(const SharedEdgeGraph::EdgeId& edge,
+                       const Interval& state) {
+  Interval result = state;
+  result += 1;
+  if (edge->second == 7) {
+    result.meet_with(Interval::bounded_above(1000));
+  }
+  return result;
+}
+
+class ReferenceAnalyzer final
+    : public MonotonicFixpointIterator<SharedEdgeGraphInterface, Interval> {
+ public:
+  using MonotonicFixpointIterator::MonotonicFixpointIterator;
+
+  void analyze_node(const uint32_t& node, Interval* state) const override {
+    transfer_node(node, state);
+  }
+
+  Interval analyze_edge(const SharedEdgeGraph::EdgeId& edge,
+                        const Interval& state) const override {
+    return transfer_edge(edge, state);
+  }
+};
+
+template <template <typename, typename, typename> class FixpointIteratorBase>
+class Analyzer final
+    : public CoarseningFixpointIterator<FixpointIteratorBase,
+                                        SharedEdgeGraphInterface,
+                                        Interval> {
+ public:
+  Analyzer(const SharedEdgeGraph& graph, bool collapse_components)
+      : CoarseningFixpointIterator<FixpointIteratorBase,
+                                   SharedEdgeGraphInterface,
+                                   Interval>(graph, collapse_components) {}
+
+  void analyze_node(const uint32_t& node, Interval* state) const override {
+    transfer_node(node, state);
+  }
+
+  Interval analyze_edge(const SharedEdgeGraph::EdgeId& edge,
+                        const Interval& state) const override {
+    return transfer_edge(edge, state);
+  }
+};
+
+/*
+ *  0 -> 1 -> 2 -> 3 -> 4 -> 6 -> 7 -> 8
+ *       ^         |         ^    |
+ *       |         +--> 5 ---+    |
+ *       +------------------------+
+ *
+ * Node 9 is unreachable.
+ */
+SharedEdgeGraph make_graph() {
+  SharedEdgeGraph graph(0);
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 3);
+  graph.add_edge(3, 4);
+  graph.add_edge(3, 5);
+  graph.add_edge(4, 6);
+  graph.add_edge(5, 6);
+  graph.add_edge(6, 1);
+  graph.add_edge(6, 7);
+  graph.add_edge(7, 8);
+  graph.add_edge(9, 8);
+  return graph;
+}
+
+template <typename Analyzer>
+void expect_same_states(const ReferenceAnalyzer& reference,
+                        const Analyzer& analyzer) {
+  for (uint32_t node = 0; node <= 9; ++node) {
+    EXPECT_EQ(reference.get_entry_state_at(node),
+              analyzer.get_entry_state_at(node))
+        << node;
+    EXPECT_EQ(reference.get_exit_state_at(node),
+              analyzer.get_exit_state_at(node))
+        << node;
+  }
+}
+
+} // namespace
+
+TEST(GraphCoarseningTest, chains) {
+  auto graph = make_graph();
+  CoarsenedGraph<SharedEdgeGraphInterface> coarse(
+      graph, /* collapse_components */ false);
+
+  ASSERT_EQ(6, coarse.size());
+  EXPECT_THAT(coarse.block(0).members, ::testing::ElementsAre(0));
+  EXPECT_THAT(coarse.block(1).members, ::testing::ElementsAre(1, 2, 3));
+  EXPECT_THAT(coarse.block(2).members, ::testing::ElementsAre(4));
+  EXPECT_THAT(coarse.block(3).members, ::testing::ElementsAre(5));
+  EXPECT_THAT(coarse.block(4).members, ::testing::ElementsAre(6));
+  EXPECT_THAT(coarse.block(5).members, ::testing::ElementsAre(7, 8));
+  EXPECT_EQ(2, coarse.block(1).internal_edges.size());
+  EXPECT_EQ(std::make_pair(1u, 2u), *coarse.block_of(3));
+  EXPECT_FALSE(coarse.block_of(9));
+
+  // The back edge goes from the block of 6 to the block of 1.
+  std::vector<uint32_t> preds;
+  for (auto edge : coarse.predecessors(1)) {
+    preds.push_back(coarse.source(edge));
+  }
+  EXPECT_THAT(preds, ::testing::UnorderedElementsAre(0, 4));
+  for (uint32_t block = 0; block < coarse.size(); ++block) {
+    EXPECT_FALSE(coarse.block(block).is_component);
+    for (auto edge : coarse.successors(block)) {
+      EXPECT_EQ(coarse.block(block).members.back(),
+                coarse.original_edge(edge)->first);
+    }
+  }
+}
+
+TEST(GraphCoarseningTest, components) {
+  auto graph = make_graph();
+  CoarsenedGraph<SharedEdgeGraphInterface> coarse(
+      graph, /* collapse_components */ true);
+
+  ASSERT_EQ(3, coarse.size());
+  EXPECT_THAT(coarse.block(0).members, ::testing::ElementsAre(0));
+  EXPECT_TRUE(coarse.block(1).is_component);
+  EXPECT_THAT(coarse.block(1).members,
+              ::testing::ElementsAre(1, 2, 3, 4, 5, 6));
+  EXPECT_EQ(7, coarse.block(1).internal_edges.size());
+  EXPECT_THAT(coarse.block(2).members, ::testing::ElementsAre(7, 8));
+  ASSERT_EQ(1, coarse.successors(1).size());
+  EXPECT_EQ(5, coarse.source_member(coarse.successors(1).front()));
+}
+
+TEST(GraphCoarseningTest, componentWithSeveralEntries) {
+  // The loop {2, 3} can be entered through both of its nodes.
+  SharedEdgeGraph graph(1);
+  graph.add_edge(1, 2);
+  graph.add_edge(1, 3);
+  graph.add_edge(2, 3);
+  graph.add_edge(3, 2);
+  CoarsenedGraph<SharedEdgeGraphInterface> coarse(
+      graph, /* collapse_components */ true);
+
+  EXPECT_EQ(3, coarse.size());
+  for (uint32_t block = 0; block < coarse.size(); ++block) {
+    EXPECT_FALSE(coarse.block(block).is_component);
+  }
+}
+
+TEST(GraphCoarseningTest, fixpoint) {
+  auto graph = make_graph();
+  ReferenceAnalyzer reference(graph);
+  reference.run(Interval::top());
+  EXPECT_EQ(Interval::finite(29, 1007), reference.get_exit_state_at(7));
+
+  Analyzer<MonotonicFixpointIterator> chains(graph, false);
+  chains.run(Interval::top());
+  expect_same_states(reference, chains);
+
+  Analyzer<MonotonicFixpointIterator> components(graph, true);
+  components.run(Interval::top());
+  expect_same_states(reference, components);
+
+  Analyzer<ParallelMonotonicFixpointIterator> parallel(graph, true);
+  parallel.run(Interval::top());
+  expect_same_states(reference, parallel);
+}
@@ -0,0 +1,143 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <algorithm>
+#include <gtest/gtest.h>
+
+#include "GrowthRateWidening.h"
+#include "HashedSetAbstractDomain.h"
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+using Set = HashedSetAbstractDomain<uint32_t>;
+
+size_t size_of(const Set& set) { return set.is_value() ? set.size() : 0; }
+
+/*
+ * Node 0 starts with the set {0} and node 2 adds a fresh element at each
+ * iteration. Since the widening of a set domain is the join, the default
+ * strategy would never converge.
+ */
+template <template <typename, typename, typename> class Iterator>
+class FreshElementAnalyzer final
+    : public Iterator<GraphInterface, Set, std::hash<uint32_t>> {
+ public:
+  using Base = Iterator<GraphInterface, Set, std::hash<uint32_t>>;
+
+  using Base::Base;
+
+  void analyze_node(const uint32_t& node, Set* state) const override {
+    if (node == 0) {
+      *state = Set(0);
+    } else if (node == 2 && state->is_value()) {
+      uint32_t fresh = 0;
+      for (uint32_t e : state->elements()) {
+        fresh = std::max(fresh, e + 1);
+      }
+      state->add(fresh);
+    }
+  }
+
+  Set analyze_edge(const size_t&, const Set& state) const override {
+    return state;
+  }
+};
+
+template <typename Analyzer>
+void check_interval_loop(const Graph& graph) {
+  Analyzer analyzer(graph);
+  analyzer.set_growth_rate_widening(GrowthRateWideningOptions());
+  analyzer.run(Interval::top());
+  // The size of an interval never grows, hence the widening is delayed.
+  EXPECT_EQ(Interval::bounded_below(0), analyzer.get_entry_state_at(1));
+  EXPECT_EQ(Interval::bounded_below(1), analyzer.get_exit_state_at(2));
+  EXPECT_EQ(WideningLevel::DelayedWidening, analyzer.get_widening_level(1));
+  EXPECT_EQ(WideningLevel::Join, analyzer.get_widening_level(2));
+
+  // Clearing the iterator discards the widening levels, and running it again
+  // yields the same result.
+  analyzer.clear_and_shrink();
+  EXPECT_EQ(WideningLevel::Join, analyzer.get_widening_level(1));
+  analyzer.run(Interval::top());
+  EXPECT_EQ(Interval::bounded_below(0), analyzer.get_entry_state_at(1));
+  EXPECT_EQ(Interval::bounded_below(1), analyzer.get_exit_state_at(2));
+  EXPECT_EQ(WideningLevel::DelayedWidening, analyzer.get_widening_level(1));
+
+  // Disabling the strategy restores the default one.
+  analyzer.set_growth_rate_widening(boost::none);
+  analyzer.run(Interval::top());
+  EXPECT_EQ(Interval::bounded_below(0), analyzer.get_entry_state_at(1));
+  EXPECT_EQ(WideningLevel::Join, analyzer.get_widening_level(1));
+}
+
+template <typename Analyzer>
+void check_fresh_element_loop(const Graph& graph) {
+  Analyzer analyzer(graph);
+  analyzer.set_growth_rate_widening(GrowthRateWideningOptions(), size_of);
+  analyzer.run(Set::top());
+  EXPECT_TRUE(analyzer.get_entry_state_at(1).is_top());
+  EXPECT_EQ(Set(0), analyzer.get_exit_state_at(0));
+  EXPECT_EQ(WideningLevel::AggressiveWidening, analyzer.get_widening_level(1));
+}
+
+} // namespace
+
+TEST(GrowthRateWideningTest, intervalLoop) {
+  Graph graph = make_loop();
+  check_interval_loop<CounterAnalyzer<MonotonicFixpointIterator>>(graph);
+  check_interval_loop<CounterAnalyzer<WTOMonotonicFixpointIterator>>(graph);
+  check_interval_loop<CounterAnalyzer<ParallelMonotonicFixpointIterator>>(
+      graph);
+}
+
+TEST(GrowthRateWideningTest, unboundedGrowth) {
+  Graph graph = make_loop();
+  check_fresh_element_loop<FreshElementAnalyzer<MonotonicFixpointIterator>>(
+      graph);
+  check_fresh_element_loop<FreshElementAnalyzer<WTOMonotonicFixpointIterator>>(
+      graph);
+  check_fresh_element_loop<
+      FreshElementAnalyzer<ParallelMonotonicFixpointIterator>>(graph);
+}
+
+TEST(GrowthRateWideningTest, escalation) {
+  GrowthRateWideningOptions options;
+  options.max_joins = 10;
+  GrowthRateWidening<uint32_t, Set> strategy(options, size_of);
+
+  Set state{0};
+  strategy.extrapolate(1, &state, Set{1});
+  EXPECT_EQ(WideningLevel::Join, strategy.level(1));
+  // The size of the state is multiplied by more than 2.
+  strategy.extrapolate(1, &state, Set{2, 3, 4, 5});
+  EXPECT_EQ(Set({0, 1, 2, 3, 4, 5}), state);
+  EXPECT_EQ(WideningLevel::DelayedWidening, strategy.level(1));
+  EXPECT_EQ(WideningLevel::Join, strategy.level(2));
+
+  // The state grows at three consecutive extrapolations.
+  for (uint32_t e = 6; e < 9; ++e) {
+    strategy.extrapolate(1, &state, Set{e});
+  }
+  EXPECT_EQ(WideningLevel::AggressiveWidening, strategy.level(1));
+  strategy.extrapolate(1, &state, Set{0});
+  EXPECT_EQ(9, state.size());
+  strategy.extrapolate(1, &state, Set{9});
+  EXPECT_TRUE(state.is_top());
+
+  strategy.clear();
+  EXPECT_EQ(WideningLevel::Join, strategy.level(1));
+
+  options.widening_delay = 0;
+  EXPECT_THROW((GrowthRateWidening<uint32_t, Set>(options)),
+               invalid_argument);
+}
@@ -162,3 +162,45 @@ TEST(HashedAbstractEnvironmentTest, destructiveOperations) {
   EXPECT_THAT(e2.get("v3").elements(),
               ::testing::UnorderedElementsAre("g", "h"));
 }
+
+TEST(HashedAbstractEnvironmentTest, collectGarbage) {
+  // A variable holds the names of the variables it references.
+  auto references = [](const Domain& value) {
+    return std::vector<std::string>(value.elements().begin(),
+                                    value.elements().end());
+  };
+  Environment e({{"v1", Domain("v2")},
+                 {"v2", Domain({"v1", "v3"})},
+                 {"v4", Domain("v1")}});
+
+  EXPECT_TRUE(e.collect_garbage({"v1"}, references));
+  EXPECT_EQ(2, e.size());
+  EXPECT_TRUE(e.get("v4").is_top());
+  EXPECT_FALSE(e.collect_garbage({"v2"}, references));
+  EXPECT_TRUE(e.collect_garbage({"v3"}, references));
+  EXPECT_TRUE(e.is_top());
+}
+
+TEST(HashedAbstractEnvironmentTest, sortedBindings) {
+  Environment e;
+  for (std::string variable : {"v3", "v1", "v4", "v0", "v2"}) {
+    e.set(variable, Domain(variable + "'"));
+  }
+
+  std::vector<std::string> variables;
+  for (const auto& binding : e.sorted_bindings()) {
+    variables.push_back(binding.first);
+  }
+  EXPECT_THAT(variables, ::testing::ElementsAre("v0", "v1", "v2", "v3", "v4"));
+
+  variables.clear();
+  for (const auto& binding : e.sorted_bindings(std::greater<std::string>())) {
+    variables.push_back(binding.first);
+  }
+  EXPECT_THAT(variables, ::testing::ElementsAre("v4", "v3", "v2", "v1", "v0"));
+
+  std::ostringstream out;
+  out << e.set("v1", Domain::top()).set("v3", Domain::top());
+  EXPECT_EQ("[#3]{v0 -> [#1]{v0'}, v2 -> [#1]{v2'}, v4 -> [#1]{v4'}}",
+            out.str());
+}
@@ -171,3 +171,44 @@ TEST(HashedAbstractPartitionTest, destructiveOperations) {
   EXPECT_TRUE(p6.get("v1").is_top());
   EXPECT_TRUE(p6.is_top());
 }
+
+TEST(HashedAbstractPartitionTest, labelCap) {
+  using CappedPartition = HashedAbstractPartition<std::string,
+                                                  Domain,
+                                                  std::hash<std::string>,
+                                                  std::equal_to<std::string>,
+                                                  /* MaxLabels */ 3>;
+  CappedPartition p1({{"v1", Domain("a")}, {"v2", Domain("b")}});
+  CappedPartition p2({{"v2", Domain("c")}, {"v3", Domain("d")}});
+
+  auto p3 = p1.join(p2);
+  EXPECT_EQ(3, p3.size());
+  EXPECT_EQ(Domain({"b", "c"}), p3.get("v2"));
+
+  // Setting a binding does not enforce the cap, only join-like operations do.
+  p3.set("v4", Domain("e"));
+  EXPECT_EQ(4, p3.size());
+  EXPECT_TRUE(p1.join(p3).is_top());
+  EXPECT_TRUE(p1.widening(p3).is_top());
+  EXPECT_TRUE(p1.leq(p1.join(p3)));
+  EXPECT_TRUE(p3.leq(p1.join(p3)));
+  // Meet-like operations can only decrease the number of labels.
+  EXPECT_EQ(2, p3.meet(p1).size());
+}
+
+TEST(HashedAbstractPartitionTest, fold) {
+  Partition p1({{"v1", Domain({"a", "b"})}, {"v2", Domain({"b", "c"})}});
+  auto count_elements = [](size_t n, const std::string&, const Domain& d) {
+    return n + d.size();
+  };
+  EXPECT_EQ(4, p1.fold(size_t(0), count_elements));
+  EXPECT_EQ(Domain({"a", "b", "c"}), p1.join_of_all_bindings());
+
+  Partition bottom;
+  EXPECT_EQ(0, bottom.fold(size_t(0), count_elements));
+  EXPECT_TRUE(bottom.join_of_all_bindings().is_bottom());
+
+  Partition top = Partition::top();
+  EXPECT_THROW(top.fold(size_t(0), count_elements), undefined_operation);
+  EXPECT_TRUE(top.join_of_all_bindings().is_top());
+}
@@ -0,0 +1,112 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "HasseDiagram.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+
+#include "FiniteAbstractDomain.h"
+#include "HashedSetAbstractDomain.h"
+
+using namespace sparta;
+
+namespace {
+
+enum Elements { BOTTOM, A, B, C, D, E, TOP };
+
+using Lattice = BitVectorLattice<Elements, 7, std::hash<int>>;
+
+/*
+ *              TOP
+ *             /   \
+ *            D     E
+ *           / \   /
+ *          B    C
+ *           \  /
+ *            A
+ *            |
+ *          BOTTOM
+ */
+Lattice lattice(
+    {BOTTOM, A, B, C, D, E, TOP},
+    {{BOTTOM, A}, {A, B}, {A, C}, {B, D}, {C, D}, {C, E}, {D, TOP}, {E, TOP}});
+
+using Domain =
+    FiniteAbstractDomain<Elements, Lattice, Lattice::Encoding, &lattice>;
+
+const char* name(const Domain& x) {
+  static const char* names[] = {"BOTTOM", "A", "B", "C", "D", "E", "TOP"};
+  return names[x.element()];
+}
+
+using StringSet = HashedSetAbstractDomain<std::string>;
+
+} // namespace
+
+TEST(HasseDiagramTest, finiteLattice) {
+  auto diagram = HasseDiagram<Domain>::of_elements<Elements>(
+      {TOP, E, D, C, B, A, BOTTOM});
+  ASSERT_EQ(7, diagram.values().size());
+  std::vector<std::pair<std::string, std::string>> edges;
+  for (const auto& edge : diagram.edges()) {
+    edges.emplace_back(name(diagram.values()[edge.first]),
+                       name(diagram.values()[edge.second]));
+  }
+  // The diagram is the one the lattice was built from.
+  using P = std::pair<std::string, std::string>;
+  EXPECT_THAT(edges,
+              ::testing::UnorderedElementsAre(P("BOTTOM", "A"),
+                                              P("A", "B"),
+                                              P("A", "C"),
+                                              P("B", "D"),
+                                              P("C", "D"),
+                                              P("C", "E"),
+                                              P("D", "TOP"),
+                                              P("E", "TOP")));
+}
+
+TEST(HasseDiagramTest, sampledValues) {
+  StringSet x({"a"});
+  StringSet y({"b"});
+  // The duplicate of x is dropped.
+  HasseDiagram<StringSet> diagram({x, y, x.join(y), StringSet({"a"})}, true);
+  ASSERT_EQ(5, diagram.values().size());
+  EXPECT_TRUE(diagram.values().front().is_bottom());
+  EXPECT_TRUE(diagram.values().back().is_top());
+  // Bottom -> {a}, Bottom -> {b}, {a} -> {a, b}, {b} -> {a, b}, {a, b} -> Top.
+  using E = HasseDiagram<StringSet>::Edge;
+  EXPECT_THAT(diagram.edges(),
+              ::testing::UnorderedElementsAre(
+                  E(0, 1), E(0, 2), E(1, 3), E(2, 3), E(3, 4)));
+}
+
+TEST(HasseDiagramTest, dot) {
+  auto diagram = HasseDiagram<Domain>::of_elements<Elements>({A, B, C, D});
+  std::ostringstream out;
+  diagram.write_dot(
+      out, [](const Domain& x) { return std::string("\"") + name(x) + "\""; });
+  EXPECT_EQ(
+      "digraph {\n"
+      "  rankdir=BT;\n"
+      "  n0 [label=\"\\\"A\\\"\"];\n"
+      "  n1 [label=\"\\\"B\\\"\"];\n"
+      "  n2 [label=\"\\\"C\\\"\"];\n"
+      "  n3 [label=\"\\\"D\\\"\"];\n"
+      "  n0 -> n1;\n"
+      "  n0 -> n2;\n"
+      "  n1 -> n3;\n"
+      "  n2 -> n3;\n"
+      "}\n",
+      out.str());
+
+  std::ostringstream printed;
+  HasseDiagram<StringSet>({StringSet({"a"})}).write_dot(printed);
+  EXPECT_THAT(printed.str(), ::testing::HasSubstr("n0 [label=\"[#1]{a}\"]"));
+}
@@ -0,0 +1,99 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <atomic>
+#include <boost/functional/hash.hpp>
+#include <gtest/gtest.h>
+
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+/*
+ * Node 0 initializes a variable to 0, node 3 negates it and node 4 increments
+ * it. The inner loop has to be iterated again in the second iteration of the
+ * outer loop.
+ */
+template <template <typename, typename, typename> class Iterator>
+class NegatingAnalyzer final
+    : public Iterator<GraphInterface, Interval, std::hash<uint32_t>> {
+ public:
+  using Base = Iterator<GraphInterface, Interval, std::hash<uint32_t>>;
+
+  using Base::Base;
+
+  void analyze_node(const uint32_t& node, Interval* state) const override {
+    ++m_num_visits;
+    if (node == 0) {
+      *state = Interval::finite(0, 0);
+    } else if (node == 3) {
+      *state = -*state;
+    } else if (node == 4) {
+      *state += 1;
+    }
+  }
+
+  Interval analyze_edge(const size_t&, const Interval& state) const override {
+    return state;
+  }
+
+  mutable std::atomic<size_t> m_num_visits{0};
+};
+
+/*
+ * Intervals are represented canonically by their bounds.
+ */
+uint64_t digest(const Interval& interval) {
+  size_t seed = 0;
+  boost::hash_combine(seed, interval.lower_bound());
+  boost::hash_combine(seed, interval.upper_bound());
+  return seed;
+}
+
+template <typename Analyzer>
+void check_digests(const Graph& graph) {
+  Analyzer reference(graph);
+  reference.run(Interval::top());
+
+  Analyzer analyzer(graph);
+  analyzer.set_head_digest(digest);
+  analyzer.run(Interval::top());
+  for (uint32_t node = 0; node <= 5; ++node) {
+    EXPECT_EQ(reference.get_entry_state_at(node),
+              analyzer.get_entry_state_at(node));
+    EXPECT_EQ(reference.get_exit_state_at(node),
+              analyzer.get_exit_state_at(node));
+  }
+  // The digests only replace some stabilization checks.
+  EXPECT_EQ(reference.m_num_visits, analyzer.m_num_visits);
+
+  // A digest with collisions stabilizes the components prematurely.
+  Analyzer unsound(graph);
+  unsound.set_head_digest([](const Interval&) { return 0; });
+  unsound.run(Interval::top());
+  EXPECT_LT(unsound.m_num_visits, reference.m_num_visits);
+
+  analyzer.set_head_digest(nullptr);
+  analyzer.m_num_visits = 0;
+  analyzer.run(Interval::top());
+  EXPECT_EQ(reference.m_num_visits, analyzer.m_num_visits);
+}
+
+} // namespace
+
+TEST(HeadDigestTest, nestedLoops) {
+  Graph graph = make_nested_loops();
+  check_digests<NegatingAnalyzer<MonotonicFixpointIterator>>(graph);
+  check_digests<NegatingAnalyzer<WTOMonotonicFixpointIterator>>(graph);
+  check_digests<NegatingAnalyzer<ParallelMonotonicFixpointIterator>>(graph);
+}
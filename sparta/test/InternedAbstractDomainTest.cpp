@@ -0,0 +1,91 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "ConstantAbstractDomain.h"
+
+#include "InternedAbstractDomain.h"
+
+#include <gtest/gtest.h>
+#include <memory>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+namespace {
+
+using Constant = ConstantAbstractDomain<int>;
+
+struct ConstantHash {
+  size_t operator()(const Constant& c) const {
+    return std::hash<int>()(*c.get_constant());
+  }
+};
+
+using Domain = InternedAbstractDomain<Constant, ConstantHash>;
+
+std::shared_ptr<Domain::Interner> global_interner() {
+  static auto interner = std::make_shared<Domain::Interner>();
+  return interner;
+}
+
+} // namespace
+
+INSTANTIATE_TYPED_TEST_CASE_P(InternedAbstractDomain,
+                              AbstractDomainPropertyTest,
+                              Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::top_values() {
+  return {Domain::top(), Domain(global_interner(), Constant::top())};
+}
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::bottom_values() {
+  return {Domain::bottom(), Domain(global_interner(), Constant::bottom())};
+}
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  return {Domain(global_interner(), Constant(1)),
+          Domain(global_interner(), Constant(2))};
+}
+
+TEST(InternedAbstractDomainTest, sharing) {
+  auto interner = std::make_shared<Domain::Interner>();
+  Domain one(interner, Constant(1));
+  Domain other_one(interner, Constant(1));
+  Domain two(interner, Constant(2));
+  EXPECT_EQ(2, interner->size());
+  EXPECT_EQ(&one.get(), &other_one.get());
+  EXPECT_EQ(one, other_one);
+  EXPECT_NE(one, two);
+
+  // Top and Bottom are never stored in the interner.
+  EXPECT_TRUE(one.join(two).is_top());
+  EXPECT_TRUE(one.meet(two).is_bottom());
+  EXPECT_EQ(2, interner->size());
+
+  Domain three = Domain::bottom();
+  three.join_with(Domain(interner, Constant(3)));
+  EXPECT_EQ(interner, three.interner());
+  EXPECT_EQ(*three.get().get_constant(), 3);
+  EXPECT_EQ(3, interner->size());
+
+  one.update([](Constant* c) { *c = Constant(2); });
+  EXPECT_EQ(&one.get(), &two.get());
+}
+
+TEST(InternedAbstractDomainTest, distinctInterners) {
+  auto interner1 = std::make_shared<Domain::Interner>();
+  auto interner2 = std::make_shared<Domain::Interner>();
+  Domain one1(interner1, Constant(1));
+  Domain one2(interner2, Constant(1));
+  EXPECT_NE(&one1.get(), &one2.get());
+  EXPECT_EQ(one1, one2);
+  EXPECT_TRUE(one1.leq(one2));
+}
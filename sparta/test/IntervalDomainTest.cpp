@@ -5,11 +5,18 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+// All lattice operations check that their results are normalized.
+#define SPARTA_CHECK_INVARIANTS
+
 #include "IntervalDomain.h"
 
 #include <gtest/gtest.h>
 #include <limits>
+#include <set>
 #include <sstream>
+#include <vector>
+
+#include "PatriciaTreeMapAbstractEnvironment.h"
 
 using namespace sparta;
 
@@ -26,6 +33,20 @@ TEST(IntervalDomainTest, top) {
 
 TEST(IntervalDomainTest, bottom) { EXPECT_TRUE(Domain::bottom().is_bottom()); }
 
+TEST(IntervalDomainTest, checkedConstructors) {
+  EXPECT_EQ(Domain::try_finite(-7, 5), Domain::finite(-7, 5));
+  EXPECT_EQ(Domain::try_finite(3, 3), Domain::finite(3, 3));
+  EXPECT_FALSE(Domain::try_finite(5, -7));
+  EXPECT_FALSE(Domain::try_finite(Domain::MIN, 0));
+  EXPECT_FALSE(Domain::try_finite(0, Domain::MAX));
+
+  EXPECT_EQ(Domain::try_bounded_below(0), Domain::bounded_below(0));
+  EXPECT_FALSE(Domain::try_bounded_below(Domain::MIN));
+
+  EXPECT_EQ(Domain::try_bounded_above(0), Domain::bounded_above(0));
+  EXPECT_FALSE(Domain::try_bounded_above(Domain::MAX));
+}
+
 TEST(IntervalDomainTest, addition) {
   const auto a = Domain::finite(-7, 5);
   const auto b = Domain::finite(-3, 5);
@@ -71,6 +92,176 @@ TEST(IntervalDomainTest, saturatedAddition) {
   EXPECT_EQ(neg, Domain::bounded_above(-1));
 }
 
+TEST(IntervalDomainTest, subtraction) {
+  const auto a = Domain::finite(-7, 5);
+  const auto b = Domain::finite(-3, 5);
+  const auto bot = Domain::bottom();
+
+  EXPECT_EQ(a - b, Domain::finite(-12, 8));
+  EXPECT_EQ(a - bot, bot);
+  EXPECT_EQ(bot - b, bot);
+  EXPECT_EQ(Domain::bounded_below(0) - b, Domain::bounded_below(-5));
+  EXPECT_EQ(a - Domain::bounded_below(0), Domain::bounded_above(5));
+  EXPECT_EQ(Domain::high() - Domain::finite(1, 1),
+            Domain::bounded_below(Domain::MAX - 1));
+  EXPECT_EQ(Domain::finite(-2, -2) - Domain::high(), Domain::low());
+
+  auto c = Domain::finite(0, 10);
+  c -= 3;
+  EXPECT_EQ(c, Domain::finite(-3, 7));
+}
+
+TEST(IntervalDomainTest, negation) {
+  EXPECT_EQ(-Domain::finite(-7, 5), Domain::finite(-5, 7));
+  EXPECT_EQ(-Domain::bounded_below(3), Domain::bounded_above(-3));
+  EXPECT_EQ(-Domain::top(), Domain::top());
+  EXPECT_EQ(-Domain::low(), Domain::high());
+  EXPECT_EQ(-Domain::high(), Domain::bounded_above(-Domain::MAX));
+  EXPECT_TRUE((-Domain::bottom()).is_bottom());
+}
+
+TEST(IntervalDomainTest, multiplication) {
+  const auto a = Domain::finite(-7, 5);
+  const auto b = Domain::finite(-3, 2);
+  const auto zero = Domain::finite(0, 0);
+
+  EXPECT_EQ(a * b, Domain::finite(-15, 21));
+  EXPECT_EQ(a * Domain::bottom(), Domain::bottom());
+  EXPECT_EQ(Domain::top() * zero, zero);
+  EXPECT_EQ(Domain::bounded_below(2) * Domain::finite(3, 4),
+            Domain::bounded_below(6));
+  EXPECT_EQ(Domain::bounded_below(2) * Domain::finite(-4, -3),
+            Domain::bounded_above(-6));
+  EXPECT_EQ(Domain::bounded_below(0) * Domain::bounded_above(0),
+            Domain::bounded_above(0));
+  EXPECT_EQ(Domain::bounded_below(-1) * Domain::finite(1, 2),
+            Domain::bounded_below(-2));
+
+  // Products that overflow extend to infinity.
+  const auto big = Domain::finite(1 << 20, 1 << 20);
+  EXPECT_EQ(big * big, Domain::high());
+  EXPECT_EQ(big * -big, Domain::low());
+  EXPECT_EQ(Domain::finite(-1, 1) * big * big, Domain::top());
+
+  auto c = Domain::finite(1, 3);
+  c *= -2;
+  EXPECT_EQ(c, Domain::finite(-6, -2));
+}
+
+TEST(IntervalDomainTest, infiniteOperands) {
+  EXPECT_EQ(Domain::finite(5, 5) + Domain::bounded_above(5),
+            Domain::bounded_above(10));
+  EXPECT_EQ(Domain::finite(-5, -5) + Domain::bounded_below(-5),
+            Domain::bounded_below(-10));
+}
+
+TEST(IntervalDomainTest, division) {
+  const auto a = Domain::finite(-7, 9);
+
+  EXPECT_EQ(a / Domain::finite(2, 3), Domain::finite(-3, 4));
+  EXPECT_EQ(a / Domain::finite(-3, -2), Domain::finite(-4, 3));
+  EXPECT_EQ(a / Domain::finite(-1, 1), Domain::finite(-9, 9));
+  EXPECT_EQ(a / Domain::finite(0, 2), Domain::finite(-7, 9));
+  EXPECT_TRUE((a / Domain::finite(0, 0)).is_bottom());
+  EXPECT_TRUE((a / Domain::bottom()).is_bottom());
+  EXPECT_EQ(a / Domain::bounded_below(1), Domain::finite(-7, 9));
+  EXPECT_EQ(Domain::bounded_below(10) / Domain::finite(2, 5),
+            Domain::bounded_below(2));
+  EXPECT_EQ(Domain::top() / Domain::finite(2, 2), Domain::top());
+
+  // Infinite bounds divided by infinite bounds keep the infinity of their sign.
+  EXPECT_EQ(Domain::bounded_above(5) / Domain::high(),
+            Domain::bounded_above(0));
+  EXPECT_EQ(Domain::bounded_above(5) / Domain::low(), Domain::bounded_below(0));
+  EXPECT_EQ(Domain::high() / Domain::high(), Domain::bounded_below(0));
+  EXPECT_EQ(Domain::low() / Domain::low(), Domain::bounded_below(0));
+  EXPECT_EQ(Domain::top() / Domain::high(), Domain::top());
+  EXPECT_EQ(Domain::top() / Domain::low(), Domain::top());
+
+  auto b = Domain::finite(10, 20);
+  b /= -10;
+  EXPECT_EQ(b, Domain::finite(-2, -1));
+}
+
+TEST(IntervalDomainTest, operations) {
+  using Ops = ArithmeticDomainOps<Domain>;
+  using CompareOps = CompareDomainOps<Domain>;
+
+  EXPECT_EQ(Ops::literal(3), Domain::finite(3, 3));
+  EXPECT_EQ(Ops::literal(Domain::MAX), Domain::high());
+
+  const auto byte = Domain::finite(0, 255);
+  EXPECT_EQ(Ops::bit_and(byte, Domain::finite(0, 15)), Domain::finite(0, 15));
+  EXPECT_EQ(Ops::bit_and(Domain::top(), byte), byte);
+  EXPECT_TRUE(Ops::bit_and(Domain::top(), Domain::top()).is_top());
+  EXPECT_EQ(Ops::bit_or(Domain::finite(4, 5), Domain::finite(1, 9)),
+            Domain::finite(4, 15));
+  EXPECT_EQ(Ops::bit_xor(byte, Domain::finite(3, 3)), byte);
+  EXPECT_EQ(Ops::bit_or(byte, Domain::bounded_below(0)),
+            Domain::bounded_below(0));
+  EXPECT_TRUE(Ops::bit_xor(byte, Domain::finite(-1, 0)).is_top());
+
+  EXPECT_EQ(Ops::shl(Domain::finite(-1, 3), Domain::finite(4, 4)),
+            Domain::finite(-16, 48));
+  EXPECT_TRUE(Ops::shl(byte, Domain::finite(31, 31)).is_top());
+  EXPECT_TRUE(Ops::shl(byte, Domain::finite(0, 1)).is_top());
+  EXPECT_EQ(Ops::shr(Domain::finite(-16, 48), Domain::finite(4, 4)),
+            Domain::finite(-1, 3));
+  EXPECT_EQ(Ops::shr(Domain::bounded_below(16), Domain::finite(4, 4)),
+            Domain::bounded_below(1));
+  EXPECT_EQ(Ops::shr(byte, Domain::finite(0, 7)), byte);
+
+  const auto a = Domain::finite(0, 5);
+  const auto b = Domain::finite(5, 9);
+  const auto c = Domain::finite(6, 9);
+  EXPECT_TRUE(CompareOps::lt(a, b).is_top());
+  EXPECT_EQ(CompareOps::le(a, b), BooleanDomain(true));
+  EXPECT_EQ(CompareOps::lt(a, c), BooleanDomain(true));
+  EXPECT_EQ(CompareOps::ge(a, c), BooleanDomain(false));
+  EXPECT_EQ(CompareOps::gt(c, a), BooleanDomain(true));
+  EXPECT_EQ(CompareOps::eq(a, c), BooleanDomain(false));
+  EXPECT_EQ(CompareOps::ne(a, c), BooleanDomain(true));
+  EXPECT_EQ(CompareOps::eq(Domain::finite(3, 3), Domain::finite(3, 3)),
+            BooleanDomain(true));
+  EXPECT_TRUE(CompareOps::eq(a, b).is_top());
+  EXPECT_TRUE(CompareOps::lt(a, Domain::bottom()).is_bottom());
+
+  // The infinite bounds never prove a comparison.
+  EXPECT_TRUE(CompareOps::lt(Domain::high(), Domain::high()).is_top());
+  EXPECT_TRUE(CompareOps::le(Domain::low(), Domain::low()).is_top());
+  EXPECT_EQ(CompareOps::lt(Domain::high(), a), BooleanDomain(false));
+  EXPECT_EQ(CompareOps::lt(Domain::bounded_above(-1), a), BooleanDomain(true));
+}
+
+TEST(IntervalDomainTest, wideningWithThresholds) {
+  const std::vector<int> thresholds = {-10, 0, 100};
+
+  auto a = Domain::finite(0, 1);
+  a.widen_with_thresholds(Domain::finite(0, 2), thresholds);
+  EXPECT_EQ(a, Domain::finite(0, 100));
+  a.widen_with_thresholds(Domain::finite(0, 50), thresholds);
+  EXPECT_EQ(a, Domain::finite(0, 100));
+  a.widen_with_thresholds(Domain::finite(-1, 101), thresholds);
+  EXPECT_EQ(a, Domain::bounded_below(-10));
+  a.widen_with_thresholds(Domain::finite(-11, 0), thresholds);
+  EXPECT_EQ(a, Domain::top());
+
+  auto b = Domain::bottom();
+  b.widen_with_thresholds(Domain::finite(3, 4), std::set<int>{5});
+  EXPECT_EQ(b, Domain::finite(3, 4));
+  b.widen_with_thresholds(Domain::finite(3, 5), std::set<int>{5});
+  EXPECT_EQ(b, Domain::finite(3, 5));
+  b.widen_with_thresholds(Domain::bottom(), std::set<int>{5});
+  EXPECT_EQ(b, Domain::finite(3, 5));
+
+  // Without thresholds, this is the standard widening.
+  auto c = Domain::finite(0, 1);
+  auto d = c;
+  c.widen_with_thresholds(Domain::finite(-1, 1), std::vector<int>());
+  d.widen_with(Domain::finite(-1, 1));
+  EXPECT_EQ(c, d);
+}
+
 TEST(IntervalDomainTest, ordering) {
   const auto a = Domain::finite(-5, 5);
   const auto b = Domain::finite(0, 10);
@@ -148,4 +339,112 @@ TEST(IntervalDomainTest, lattice) {
   EXPECT_EQ(top.narrowing(b).narrowing(a), Domain::finite(0, 4));
 }
 
+TEST(IntervalDomainTest, refinement) {
+  {
+    // [0, 10] < [5, 20]
+    auto x = Domain::finite(0, 10), y = Domain::finite(5, 20);
+    refine_lt(&x, &y);
+    EXPECT_EQ(Domain::finite(0, 10), x);
+    EXPECT_EQ(Domain::finite(5, 20), y);
+  }
+  {
+    // [0, 10] < [-5, 5]
+    auto x = Domain::finite(0, 10), y = Domain::finite(-5, 5);
+    refine_lt(&x, &y);
+    EXPECT_EQ(Domain::finite(0, 4), x);
+    EXPECT_EQ(Domain::finite(1, 5), y);
+  }
+  {
+    // [0, 10] <= [-5, 5]
+    auto x = Domain::finite(0, 10), y = Domain::finite(-5, 5);
+    refine_le(&x, &y);
+    EXPECT_EQ(Domain::finite(0, 5), x);
+    EXPECT_EQ(Domain::finite(0, 5), y);
+  }
+  {
+    // [5, 10] < [0, 5] is infeasible.
+    auto x = Domain::finite(5, 10), y = Domain::finite(0, 5);
+    refine_lt(&x, &y);
+    EXPECT_TRUE(x.is_bottom());
+    EXPECT_TRUE(y.is_bottom());
+  }
+  {
+    // T < [0, 5]
+    auto x = Domain::top(), y = Domain::finite(0, 5);
+    refine_lt(&x, &y);
+    EXPECT_EQ(Domain::bounded_above(4), x);
+    EXPECT_EQ(Domain::finite(0, 5), y);
+  }
+  {
+    // The infinite bounds are not refined.
+    auto x = Domain::high(), y = Domain::high();
+    refine_lt(&x, &y);
+    EXPECT_EQ(Domain::high(), x);
+    EXPECT_EQ(Domain::high(), y);
+  }
+  {
+    auto x = Domain::finite(0, 10), y = Domain::finite(5, 20);
+    refine_eq(&x, &y);
+    EXPECT_EQ(Domain::finite(5, 10), x);
+    EXPECT_EQ(Domain::finite(5, 10), y);
+  }
+  {
+    auto x = Domain::finite(0, 10), y = Domain::finite(0, 0);
+    refine_ne(&x, &y);
+    EXPECT_EQ(Domain::finite(1, 10), x);
+    EXPECT_EQ(Domain::finite(0, 0), y);
+    y = Domain::finite(10, 10);
+    refine_ne(&y, &x);
+    EXPECT_EQ(Domain::finite(1, 9), x);
+    y = Domain::finite(5, 5);
+    refine_ne(&x, &y);
+    EXPECT_EQ(Domain::finite(1, 9), x);
+    x = Domain::finite(5, 5);
+    refine_ne(&x, &y);
+    EXPECT_TRUE(x.is_bottom());
+    EXPECT_TRUE(y.is_bottom());
+  }
+}
+
+TEST(IntervalDomainTest, environmentRefinement) {
+  using Environment = PatriciaTreeMapAbstractEnvironment<uint32_t, Domain>;
+  Environment env({{1, Domain::finite(0, 10)}, {2, Domain::finite(-5, 5)}});
+
+  Environment lt = env;
+  refine_lt(lt, 1u, 2u);
+  EXPECT_EQ(Domain::finite(0, 4), lt.get(1));
+  EXPECT_EQ(Domain::finite(1, 5), lt.get(2));
+
+  Environment gt = env;
+  refine_gt(gt, 1u, 2u);
+  EXPECT_EQ(env, gt);
+
+  Environment ge = env;
+  refine_ge(ge, 2u, 1u);
+  EXPECT_EQ(Domain::finite(0, 5), ge.get(1));
+  EXPECT_EQ(Domain::finite(0, 5), ge.get(2));
+
+  Environment eq = env;
+  refine_eq(eq, 1u, 3u);
+  EXPECT_EQ(Domain::finite(0, 10), eq.get(3));
+
+  Environment ne = env;
+  refine_ne(ne, 1u, 1u);
+  EXPECT_TRUE(ne.is_bottom());
+  Environment le = env;
+  refine_le(le, 1u, 1u);
+  EXPECT_EQ(env, le);
+
+  Environment unbounded({{1, Domain::finite(6, 10)}});
+  refine_le(unbounded, 1u, 2u);
+  EXPECT_EQ(Domain::bounded_below(6), unbounded.get(2));
+
+  Environment infeasible(
+      {{1, Domain::finite(6, 10)}, {2, Domain::finite(0, 5)}});
+  refine_ge(infeasible, 1u, 2u);
+  EXPECT_FALSE(infeasible.is_bottom());
+  refine_lt(infeasible, 1u, 2u);
+  EXPECT_TRUE(infeasible.is_bottom());
+}
+
 } // namespace
@@ -0,0 +1,208 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "IntervalSetDomain.h"
+
+#include <cstdint>
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+
+using namespace sparta;
+
+using Domain = IntervalSetDomain<int32_t, 3>;
+using Interval = Domain::Interval;
+
+INSTANTIATE_TYPED_TEST_CASE_P(IntervalSetDomain,
+                              AbstractDomainPropertyTest,
+                              Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  return {Domain::finite(0, 10),
+          Domain::constant(4),
+          Domain({Interval::finite(0, 0), Interval::finite(5, 5)}),
+          Domain({Interval::finite(-5, -1), Interval::finite(3, 4),
+                  Interval::bounded_below(10)}),
+          Domain({Interval::bounded_above(-3), Interval::finite(7, 9)})};
+}
+
+TEST(IntervalSetDomainTest, representation) {
+  EXPECT_TRUE(Domain().is_top());
+  EXPECT_TRUE(Domain(Interval::bottom()).is_bottom());
+  EXPECT_TRUE(Domain::bottom().intervals().empty());
+
+  // The intervals are sorted, and adjacent intervals are merged.
+  Domain x({Interval::finite(5, 7), Interval::finite(0, 2),
+            Interval::finite(3, 3)});
+  EXPECT_EQ(
+      std::vector<Interval>({Interval::finite(0, 3), Interval::finite(5, 7)}),
+      x.intervals());
+  EXPECT_EQ(Interval::finite(0, 7), x.hull());
+  EXPECT_TRUE(x.contains(3));
+  EXPECT_FALSE(x.contains(4));
+  EXPECT_TRUE(x.contains(7));
+  EXPECT_FALSE(x.contains(8));
+  EXPECT_FALSE(x.get_constant());
+  EXPECT_EQ(4, *Domain::constant(4).get_constant());
+
+  // Too many intervals are collapsed to their hull.
+  EXPECT_EQ(Domain::finite(0, 6),
+            Domain({Interval::finite(0, 0), Interval::finite(2, 2),
+                    Interval::finite(4, 4), Interval::finite(6, 6)}));
+
+  std::ostringstream out;
+  out << x << " "
+      << Domain({Interval::bounded_above(-1), Interval::bounded_below(9)})
+      << " " << Domain::top() << " " << Domain::bottom();
+  EXPECT_EQ("{[0, 3], [5, 7]} {[-inf, -1], [9, +inf]} T _|_", out.str());
+}
+
+TEST(IntervalSetDomainTest, exclusion) {
+  // The default case of a switch over 1, 2 and 5.
+  auto x = Domain::finite(0, 10);
+  x.exclude(1).exclude(2).exclude(5);
+  EXPECT_EQ(Domain({Interval::finite(0, 0), Interval::finite(3, 4),
+                    Interval::finite(6, 10)}),
+            x);
+  x.exclude(20).exclude(4);
+  EXPECT_EQ(Domain({Interval::finite(0, 0), Interval::finite(3, 3),
+                    Interval::finite(6, 10)}),
+            x);
+  x.exclude(8);
+  EXPECT_EQ(Domain::finite(0, 10), x);
+
+  EXPECT_TRUE(Domain::constant(3).exclude(3).is_bottom());
+  EXPECT_EQ(Domain::top(), Domain::top().exclude(Domain::MIN));
+  EXPECT_EQ(Domain({Interval::bounded_above(-1), Interval::bounded_below(1)}),
+            Domain::top().exclude(0));
+}
+
+TEST(IntervalSetDomainTest, lattice) {
+  const Domain x({Interval::finite(0, 0), Interval::finite(10, 20)});
+  const Domain y({Interval::finite(5, 5), Interval::finite(15, 25)});
+  EXPECT_EQ(Domain({Interval::finite(0, 0), Interval::finite(5, 5),
+                    Interval::finite(10, 25)}),
+            x.join(y));
+  EXPECT_EQ(Domain::finite(15, 20), x.meet(y));
+  EXPECT_TRUE(x.meet(Domain::finite(1, 9)).is_bottom());
+  EXPECT_TRUE(Domain::finite(10, 12).leq(x));
+  EXPECT_FALSE(Domain::finite(0, 12).leq(x));
+  EXPECT_FALSE(x.leq(Domain::finite(10, 20)));
+}
+
+TEST(IntervalSetDomainTest, widening) {
+  // The bounds of the hull are extrapolated.
+  const Domain x({Interval::finite(0, 0), Interval::finite(10, 10)});
+  EXPECT_EQ(Domain({Interval::finite(0, 0), Interval::bounded_below(10)}),
+            x.widening(Domain::constant(11)));
+  EXPECT_EQ(Domain({Interval::bounded_above(0), Interval::finite(10, 10)}),
+            x.widening(Domain::constant(-1)));
+  EXPECT_EQ(x, x.widening(Domain::constant(10)));
+
+  // The intervals inside the hull are not extrapolated.
+  EXPECT_EQ(Domain::finite(0, 10), x.widening(Domain::constant(5)));
+
+  // The narrowing only refines the infinite bounds.
+  const Domain y({Interval::finite(0, 0), Interval::bounded_below(10)});
+  EXPECT_EQ(Domain({Interval::finite(0, 0), Interval::finite(10, 20)}),
+            y.narrowing(Domain::finite(0, 20)));
+  EXPECT_EQ(Domain::finite(0, 10),
+            Domain::finite(0, 10).narrowing(Domain::constant(5)));
+  EXPECT_TRUE(y.narrowing(Domain::finite(1, 9)).is_bottom());
+}
+
+TEST(IntervalSetDomainTest, arithmetic) {
+  const Domain x({Interval::finite(0, 0), Interval::finite(10, 10)});
+  EXPECT_EQ(Domain({Interval::finite(1, 1), Interval::finite(11, 11)}),
+            x + Domain::constant(1));
+  EXPECT_EQ(Domain({Interval::finite(-10, -10), Interval::finite(0, 0)}),
+            -x);
+  EXPECT_EQ(Domain({Interval::finite(0, 0), Interval::finite(20, 20)}),
+            x * Domain::constant(2));
+  EXPECT_EQ(Domain({Interval::finite(-2, 0), Interval::finite(8, 10)}),
+            x - Domain::finite(0, 2));
+  EXPECT_TRUE((x + Domain::bottom()).is_bottom());
+
+  // The result is collapsed if it has too many intervals.
+  EXPECT_EQ(Domain::finite(1, 15),
+            x + Domain({Interval::finite(1, 1), Interval::finite(5, 5)}));
+}
+
+TEST(IntervalSetDomainTest, operations) {
+  using Ops = ArithmeticDomainOps<Domain>;
+  using CompareOps = CompareDomainOps<Domain>;
+  const Domain x({Interval::finite(0, 0), Interval::finite(10, 10)});
+
+  EXPECT_EQ(Domain::constant(3), Ops::literal(3));
+  EXPECT_EQ(Domain::finite(1, 2),
+            Ops::div(Domain({Interval::finite(10, 10),
+                             Interval::finite(20, 20)}),
+                     Domain::constant(10)));
+  EXPECT_EQ(Domain({Interval::finite(0, 0), Interval::finite(40, 40)}),
+            Ops::shl(x, Domain::constant(2)));
+
+  // The equality is decided on the sets.
+  EXPECT_EQ(BooleanDomain(false), CompareOps::eq(x, Domain::constant(5)));
+  EXPECT_EQ(BooleanDomain(true), CompareOps::ne(x, Domain::constant(5)));
+  EXPECT_EQ(BooleanDomain(true),
+            CompareOps::eq(Domain::constant(3), Domain::constant(3)));
+  EXPECT_TRUE(CompareOps::eq(x, Domain::constant(10)).is_top());
+  EXPECT_EQ(BooleanDomain(true), CompareOps::lt(x, Domain::finite(11, 20)));
+  EXPECT_TRUE(CompareOps::lt(x, Domain::constant(5)).is_top());
+  EXPECT_TRUE(CompareOps::le(x, Domain::bottom()).is_bottom());
+}
+
+TEST(IntervalSetDomainTest, refinement) {
+  {
+    auto x = Domain::finite(0, 10);
+    for (int32_t c : {1, 2, 5}) {
+      auto y = Domain::constant(c);
+      refine_ne(&x, &y);
+    }
+    EXPECT_EQ(Domain({Interval::finite(0, 0), Interval::finite(3, 4),
+                      Interval::finite(6, 10)}),
+              x);
+    auto y = Domain::constant(4);
+    refine_lt(&x, &y);
+    EXPECT_EQ(Domain({Interval::finite(0, 0), Interval::finite(3, 3)}), x);
+    EXPECT_EQ(Domain::constant(4), y);
+  }
+  {
+    const Domain x({Interval::finite(0, 0), Interval::finite(10, 10)});
+    auto le_x = x, le_y = Domain::finite(5, 8);
+    refine_le(&le_x, &le_y);
+    EXPECT_EQ(Domain::constant(0), le_x);
+    EXPECT_EQ(Domain::finite(5, 8), le_y);
+    auto eq_x = x, eq_y = Domain::finite(5, 20);
+    refine_eq(&eq_x, &eq_y);
+    EXPECT_EQ(Domain::constant(10), eq_x);
+    EXPECT_EQ(Domain::constant(10), eq_y);
+  }
+  {
+    auto x = Domain::finite(5, 10), y = Domain::finite(0, 4);
+    refine_le(&x, &y);
+    EXPECT_TRUE(x.is_bottom());
+    EXPECT_TRUE(y.is_bottom());
+  }
+}
+
+TEST(IntervalSetDomainTest, environmentRefinement) {
+  using Environment = PatriciaTreeMapAbstractEnvironment<uint32_t, Domain>;
+  Environment env({{1, Domain::finite(0, 10)}, {2, Domain::constant(5)}});
+
+  Environment ne = env;
+  refine_ne(ne, 1u, 2u);
+  EXPECT_EQ(Domain({Interval::finite(0, 4), Interval::finite(6, 10)}),
+            ne.get(1));
+
+  Environment eq = ne;
+  refine_eq(eq, 1u, 2u);
+  EXPECT_TRUE(eq.is_bottom());
+}
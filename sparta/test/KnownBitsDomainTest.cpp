@@ -0,0 +1,141 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "KnownBitsDomain.h"
+
+#include <cstdint>
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using Domain = KnownBitsDomain<int8_t>;
+using UnsignedDomain = KnownBitsDomain<uint8_t>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(KnownBitsDomain,
+                              AbstractDomainPropertyTest,
+                              Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  return {Domain::constant(0),
+          Domain::constant(-1),
+          Domain::constant(5),
+          Domain::from_masks(0x01, 0x00),
+          Domain::from_masks(0x00, 0x01),
+          Domain::from_masks(0xF0, 0x02)};
+}
+
+namespace {
+
+std::string to_string(const Domain& x) {
+  std::ostringstream out;
+  out << x;
+  return out.str();
+}
+
+} // namespace
+
+TEST(KnownBitsDomainTest, representation) {
+  EXPECT_TRUE(Domain().is_top());
+  EXPECT_EQ("00000101", to_string(Domain::constant(5)));
+  EXPECT_EQ("????????", to_string(Domain::top()));
+  EXPECT_EQ("0000??1?", to_string(Domain::from_masks(0xF0, 0x02)));
+  EXPECT_EQ("_|_", to_string(Domain::from_masks(0x01, 0x01)));
+  EXPECT_EQ(-3, *Domain::constant(-3).get_constant());
+
+  auto x = Domain::from_masks(0x0E, 0x80);
+  EXPECT_FALSE(x.get_constant());
+  EXPECT_TRUE(x.contains(-128));
+  EXPECT_TRUE(x.contains(-127));
+  EXPECT_FALSE(x.contains(1));
+  EXPECT_EQ(-128, x.min_value());
+  EXPECT_EQ(-15, x.max_value());
+  EXPECT_EQ(-128, Domain::top().min_value());
+  EXPECT_EQ(127, Domain::top().max_value());
+  EXPECT_EQ(255, UnsignedDomain::top().max_value());
+
+  EXPECT_EQ("00000??0",
+            to_string(Domain::constant(2).join(Domain::constant(4))));
+  auto partial = Domain::from_masks(0x01, 0x04);
+  EXPECT_EQ(Domain::constant(6), partial.meet(Domain::from_masks(0xF8, 0x02)));
+  EXPECT_TRUE(Domain::constant(2).meet(Domain::constant(3)).is_bottom());
+}
+
+TEST(KnownBitsDomainTest, bitwise) {
+  // Masking with 0xF0 clears the low bits.
+  auto x = Domain::from_masks(0x00, 0x11);
+  EXPECT_EQ("???1???1", to_string(x));
+  EXPECT_EQ("???10000", to_string(x & Domain::constant(0xF0)));
+  EXPECT_EQ("1111???1", to_string(x | Domain::constant(0xF0)));
+  EXPECT_EQ("???0???0", to_string(x ^ Domain::constant(0x11)));
+  EXPECT_EQ("???0???0", to_string(~x));
+  EXPECT_EQ(Domain::constant(0x10 ^ 0x07),
+            Domain::constant(0x10) ^ Domain::constant(0x07));
+  EXPECT_TRUE((x & Domain::bottom()).is_bottom());
+
+  EXPECT_EQ("?1???100", to_string(x.shift_left(2)));
+  EXPECT_EQ("????1???", to_string(x.shift_right(1)));
+  EXPECT_EQ("11111?1?", to_string(Domain::from_masks(0x01, 0xA0)
+                                      .shift_right(4)));
+  EXPECT_EQ("000000?0",
+            to_string(Domain::from_masks(0xDF, 0x00).shift_right(4)));
+  // The sign bit is unknown.
+  EXPECT_EQ("?????0?0",
+            to_string(Domain::from_masks(0x5F, 0x00).shift_right(4)));
+  EXPECT_TRUE(x.shift_left(8).is_top());
+  EXPECT_TRUE(x.shift_right(-1).is_top());
+  // Logical shift on unsigned integers.
+  EXPECT_EQ(UnsignedDomain::constant(0x0F),
+            UnsignedDomain::constant(0xF0).shift_right(4));
+}
+
+TEST(KnownBitsDomainTest, arithmetic) {
+  EXPECT_EQ(Domain::constant(7), Domain::constant(3) + Domain::constant(4));
+  EXPECT_EQ(Domain::constant(-128),
+            Domain::constant(127) + Domain::constant(1));
+  EXPECT_EQ(Domain::constant(-1), Domain::constant(3) - Domain::constant(4));
+  EXPECT_EQ(Domain::constant(-3), -Domain::constant(3));
+
+  // Even + even is even, and even + 1 is odd.
+  auto even = Domain::from_masks(0x01, 0x00);
+  EXPECT_EQ(even, even + even);
+  EXPECT_EQ(Domain::from_masks(0x00, 0x01), even + Domain::constant(1));
+  // Adding a multiple of 4 preserves the two low bits.
+  auto multiple_of_four = Domain::from_masks(0x03, 0x00);
+  EXPECT_EQ(Domain::from_masks(0x01, 0x02),
+            multiple_of_four + Domain::constant(2));
+  EXPECT_EQ(Domain::from_masks(0x00, 0x03),
+            multiple_of_four - Domain::constant(1));
+  EXPECT_TRUE((even + Domain::bottom()).is_bottom());
+}
+
+TEST(KnownBitsDomainTest, operations) {
+  using Ops = ArithmeticDomainOps<Domain>;
+  using CompareOps = CompareDomainOps<Domain>;
+  auto even = Domain::from_masks(0x01, 0x00);
+
+  EXPECT_EQ(Domain::constant(12), Ops::mul(Ops::literal(3), Ops::literal(4)));
+  EXPECT_EQ(Domain::from_masks(0x07, 0x00),
+            Ops::mul(even, Domain::from_masks(0x03, 0x00)));
+  EXPECT_EQ(Domain::constant(-3),
+            Ops::div(Domain::constant(-7), Domain::constant(2)));
+  EXPECT_TRUE(Ops::div(even, Domain::constant(0)).is_bottom());
+  EXPECT_EQ(Domain::from_masks(0x07, 0x00), Ops::shl(even, Ops::literal(2)));
+  EXPECT_TRUE(Ops::shr(even, even).is_top());
+
+  EXPECT_EQ(BooleanDomain(false), CompareOps::eq(even, Domain::constant(3)));
+  EXPECT_EQ(BooleanDomain(true), CompareOps::ne(even, Domain::constant(3)));
+  EXPECT_TRUE(CompareOps::eq(even, Domain::constant(4)).is_top());
+  // Non-negative values with the high bits cleared.
+  auto small = Domain::from_masks(0xF0, 0x00);
+  EXPECT_EQ(BooleanDomain(true), CompareOps::lt(small, Domain::constant(16)));
+  EXPECT_EQ(BooleanDomain(false), CompareOps::gt(small, Domain::constant(15)));
+  EXPECT_TRUE(CompareOps::le(small, Domain::constant(8)).is_top());
+}
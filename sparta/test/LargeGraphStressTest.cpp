@@ -0,0 +1,424 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <cstdint>
+#include <gtest/gtest.h>
+#include <utility>
+#include <vector>
+
+#include "ConstantAbstractDomain.h"
+#include "GenKillAnalysis.h"
+#include "MonotonicFixpointIterator.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+#include "PatriciaTreeSetAbstractDomain.h"
+#include "WeakPartialOrdering.h"
+#include "WeakTopologicalOrdering.h"
+
+/*
+ * Runs the fixpoint iterators and the analyses on synthetic graphs of more
+ * than 100k nodes, which exercise the shapes that are prone to deep recursion
+ * or quadratic behavior: long chains, wide switches, deep loop nests and
+ * irreducible regions.
+ */
+
+using namespace sparta;
+
+namespace {
+
+constexpr uint32_t kNodes = 100000;
+constexpr uint32_t kVariables = 64;
+
+struct Statement {
+  enum class Kind { Nop, Const, Copy, Incr };
+
+  Kind kind = Kind::Nop;
+  uint32_t def = 0;
+  uint32_t use = 0;
+  int32_t value = 0;
+};
+
+/*
+ * Unlike the graph of TestGraph.h, the nodes are numbered densely and the
+ * adjacency lists are stored in vectors and returned by reference, so that
+ * the graph interface adds no noticeable cost on graphs of this size.
+ */
+class Program final {
+ public:
+  using Edge = std::pair<uint32_t, uint32_t>;
+
+  uint32_t add_node(Statement statement = Statement()) {
+    m_statements.push_back(statement);
+    m_successors.emplace_back();
+    m_predecessors.emplace_back();
+    return m_statements.size() - 1;
+  }
+
+  void add_edge(uint32_t src, uint32_t dst) {
+    m_edges.emplace_back(src, dst);
+    m_successors[src].push_back(m_edges.size() - 1);
+    m_predecessors[dst].push_back(m_edges.size() - 1);
+  }
+
+  void set_exit(uint32_t exit) { m_exit = exit; }
+
+  size_t size() const { return m_statements.size(); }
+
+  const Statement& statement(uint32_t node) const {
+    return m_statements[node];
+  }
+
+  std::vector<uint32_t> successor_nodes(uint32_t node) const {
+    std::vector<uint32_t> nodes;
+    for (uint32_t edge : m_successors[node]) {
+      nodes.push_back(m_edges[edge].second);
+    }
+    return nodes;
+  }
+
+ private:
+  std::vector<Statement> m_statements;
+  std::vector<Edge> m_edges;
+  std::vector<std::vector<uint32_t>> m_successors;
+  std::vector<std::vector<uint32_t>> m_predecessors;
+  uint32_t m_exit = 0;
+
+  friend class ProgramInterface;
+};
+
+class ProgramInterface {
+ public:
+  using Graph = Program;
+  using NodeId = uint32_t;
+  using EdgeId = uint32_t;
+
+  static NodeId entry(const Graph&) { return 0; }
+  static NodeId exit(const Graph& graph) { return graph.m_exit; }
+  static const std::vector<EdgeId>& predecessors(const Graph& graph,
+                                                 const NodeId& node) {
+    return graph.m_predecessors[node];
+  }
+  static const std::vector<EdgeId>& successors(const Graph& graph,
+                                               const NodeId& node) {
+    return graph.m_successors[node];
+  }
+  static NodeId source(const Graph& graph, const EdgeId& edge) {
+    return graph.m_edges[edge].first;
+  }
+  static NodeId target(const Graph& graph, const EdgeId& edge) {
+    return graph.m_edges[edge].second;
+  }
+  static size_t size_hint(const Graph& graph) { return graph.size(); }
+};
+
+using Constant = ConstantAbstractDomain<int32_t>;
+using Environment = PatriciaTreeMapAbstractEnvironment<uint32_t, Constant>;
+using VariableSet = PatriciaTreeSetAbstractDomain<uint32_t>;
+
+template <template <typename, typename, typename> class Iterator>
+class ConstantPropagation final
+    : public Iterator<ProgramInterface, Environment, std::hash<uint32_t>> {
+ public:
+  explicit ConstantPropagation(const Program& program)
+      : Iterator<ProgramInterface, Environment, std::hash<uint32_t>>(
+            program, program.size()),
+        m_program(program) {}
+
+  void analyze_node(const uint32_t& node,
+                    Environment* env) const override {
+    const auto& statement = m_program.statement(node);
+    switch (statement.kind) {
+    case Statement::Kind::Nop:
+      break;
+    case Statement::Kind::Const:
+      env->set(statement.def, Constant(statement.value));
+      break;
+    case Statement::Kind::Copy:
+      env->set(statement.def, env->get(statement.use));
+      break;
+    case Statement::Kind::Incr: {
+      auto value = env->get(statement.use);
+      env->set(statement.def, value.is_value()
+                                  ? Constant(*value.get_constant() + 1)
+                                  : value);
+      break;
+    }
+    }
+  }
+
+  Environment analyze_edge(const uint32_t&,
+                           const Environment& env) const override {
+    return env;
+  }
+
+ private:
+  const Program& m_program;
+};
+
+class LivenessRelations {
+ public:
+  explicit LivenessRelations(const Program& program) : m_program(program) {}
+
+  std::vector<uint32_t> gen(uint32_t node) const {
+    const auto& statement = m_program.statement(node);
+    if (statement.kind == Statement::Kind::Copy ||
+        statement.kind == Statement::Kind::Incr) {
+      return {statement.use};
+    }
+    return {};
+  }
+
+  std::vector<uint32_t> kill(uint32_t node) const {
+    const auto& statement = m_program.statement(node);
+    if (statement.kind == Statement::Kind::Nop) {
+      return {};
+    }
+    return {statement.def};
+  }
+
+ private:
+  const Program& m_program;
+};
+
+using Liveness = GenKillAnalysis<ProgramInterface,
+                                 VariableSet,
+                                 LivenessRelations,
+                                 AnalysisDirection::Backward>;
+
+Statement constant(uint32_t def, int32_t value) {
+  return Statement{Statement::Kind::Const, def, 0, value};
+}
+
+Statement copy(uint32_t def, uint32_t use) {
+  return Statement{Statement::Kind::Copy, def, use, 0};
+}
+
+Statement incr(uint32_t def, uint32_t use) {
+  return Statement{Statement::Kind::Incr, def, use, 0};
+}
+
+/*
+ * x0 = 0; x1 = x0 + 1; ...; x63 = x62 + 1; x0 = x63 + 1; ...
+ */
+Program make_chain(uint32_t size) {
+  Program program;
+  program.add_node(constant(0, 0));
+  for (uint32_t i = 1; i < size; ++i) {
+    program.add_node(incr(i % kVariables, (i - 1) % kVariables));
+    program.add_edge(i - 1, i);
+  }
+  program.set_exit(size - 1);
+  return program;
+}
+
+/*
+ * x0 = 7; switch (...) { case k: x1 = x0; x2 = k; } x3 = x1;
+ */
+Program make_switch(uint32_t cases) {
+  Program program;
+  uint32_t entry = program.add_node(constant(0, 7));
+  uint32_t head = program.add_node();
+  uint32_t join = program.add_node(copy(3, 1));
+  program.add_edge(entry, head);
+  for (uint32_t k = 0; k < cases; ++k) {
+    uint32_t first = program.add_node(copy(1, 0));
+    uint32_t second = program.add_node(constant(2, k));
+    program.add_edge(head, first);
+    program.add_edge(first, second);
+    program.add_edge(second, join);
+  }
+  program.set_exit(join);
+  return program;
+}
+
+/*
+ * A sequence of loops, each of which can be entered either at its head or in
+ * the middle of its body. Each loop copies x0 around through x2, and x1 is
+ * incremented in the first loop only.
+ *
+ *   before -> head <-> body -> after
+ *        \_____________^
+ */
+Program make_irreducible_regions(uint32_t regions) {
+  Program program;
+  uint32_t before = program.add_node(constant(0, 42));
+  for (uint32_t var : {1, 2}) {
+    uint32_t node = program.add_node(constant(var, var == 1 ? 0 : 42));
+    program.add_edge(before, node);
+    before = node;
+  }
+  for (uint32_t r = 0; r < regions; ++r) {
+    uint32_t head = program.add_node(copy(2, 0));
+    uint32_t body = program.add_node(r == 0 ? incr(1, 1) : copy(0, 2));
+    uint32_t after = program.add_node();
+    program.add_edge(before, head);
+    program.add_edge(before, body);
+    program.add_edge(head, body);
+    program.add_edge(body, head);
+    program.add_edge(body, after);
+    before = after;
+  }
+  program.set_exit(before);
+  return program;
+}
+
+/*
+ * A nest of loops of the given depth, whose heads and latches are chained:
+ *
+ *   head_1 -> head_2 -> ... -> head_d -> latch_d -> ... -> latch_1 -> exit
+ *
+ * with a back edge from latch_i to head_i.
+ */
+Program make_loop_nest(uint32_t depth) {
+  Program program;
+  program.add_node(constant(0, 1));
+  std::vector<uint32_t> heads;
+  for (uint32_t i = 0; i < depth; ++i) {
+    heads.push_back(program.add_node(copy(1 + i % 2, 0)));
+    program.add_edge(heads.size() == 1 ? 0 : heads[i - 1], heads[i]);
+  }
+  uint32_t previous = heads.back();
+  for (uint32_t i = depth; i-- > 0;) {
+    uint32_t latch = program.add_node(copy(0, 1 + i % 2));
+    program.add_edge(previous, latch);
+    program.add_edge(latch, heads[i]);
+    previous = latch;
+  }
+  uint32_t exit = program.add_node();
+  program.add_edge(previous, exit);
+  program.set_exit(exit);
+  return program;
+}
+
+template <typename Analyzer>
+void check_chain(const Program& program, Analyzer* analyzer) {
+  analyzer->run(Environment());
+  for (uint32_t node : {1u, 12345u, kNodes - 1}) {
+    EXPECT_EQ(Constant(node),
+              analyzer->get_exit_state_at(node).get(node % kVariables));
+  }
+}
+
+} // namespace
+
+TEST(LargeGraphStressTest, chain) {
+  Program program = make_chain(kNodes);
+
+  ConstantPropagation<WTOMonotonicFixpointIterator> wto(program);
+  check_chain(program, &wto);
+  ConstantPropagation<MonotonicFixpointIterator> wpo(program);
+  check_chain(program, &wpo);
+
+  LivenessRelations relations(program);
+  Liveness liveness(program, relations);
+  liveness.run(VariableSet());
+  EXPECT_EQ(VariableSet(), liveness.get_state_before(0));
+  EXPECT_EQ(VariableSet({12344 % kVariables}),
+            liveness.get_state_before(12345));
+  EXPECT_EQ(VariableSet(), liveness.get_state_after(kNodes - 1));
+}
+
+TEST(LargeGraphStressTest, wideSwitch) {
+  Program program = make_switch(kNodes / 2);
+
+  ConstantPropagation<MonotonicFixpointIterator> analyzer(program);
+  analyzer.run(Environment());
+  auto env = analyzer.get_exit_state_at(2);
+  EXPECT_EQ(Constant(7), env.get(1));
+  EXPECT_TRUE(env.get(2).is_top());
+  EXPECT_EQ(Constant(7), env.get(3));
+
+  LivenessRelations relations(program);
+  Liveness liveness(program, relations);
+  liveness.run(VariableSet());
+  EXPECT_EQ(VariableSet({0}), liveness.get_state_after(1));
+  EXPECT_EQ(VariableSet({1}), liveness.get_state_before(2));
+}
+
+TEST(LargeGraphStressTest, irreducibleRegions) {
+  Program program = make_irreducible_regions(kNodes / 3);
+
+  ConstantPropagation<WTOMonotonicFixpointIterator> wto(program);
+  wto.run(Environment());
+  ConstantPropagation<MonotonicFixpointIterator> wpo(program);
+  wpo.run(Environment());
+  uint32_t exit = ProgramInterface::exit(program);
+  for (const auto& env :
+       {wto.get_exit_state_at(exit), wpo.get_exit_state_at(exit)}) {
+    EXPECT_EQ(Constant(42), env.get(0));
+    EXPECT_TRUE(env.get(1).is_top());
+  }
+
+  LivenessRelations relations(program);
+  Liveness liveness(program, relations);
+  liveness.run(VariableSet());
+  EXPECT_EQ(VariableSet({0, 1, 2}), liveness.get_state_after(2));
+}
+
+TEST(LargeGraphStressTest, deepLoopNest) {
+  Program program = make_loop_nest(kNodes / 2);
+  uint32_t exit = ProgramInterface::exit(program);
+
+  WeakPartialOrdering<uint32_t> wpo(
+      0,
+      [&program](const uint32_t& node) {
+        return program.successor_nodes(node);
+      },
+      false);
+  // Every head has an exit node in the WPO.
+  EXPECT_EQ(program.size() + kNodes / 2, wpo.size());
+
+  ConstantPropagation<MonotonicFixpointIterator> analyzer(program);
+  analyzer.run(Environment());
+  for (uint32_t var : {0, 1, 2}) {
+    EXPECT_EQ(Constant(1), analyzer.get_exit_state_at(exit).get(var));
+  }
+}
+
+/*
+ * Some algorithms take a time that is quadratic in the depth of a loop nest,
+ * since they revisit the nodes of a component for every enclosing component:
+ * Bourdoncle's algorithm that builds a WTO, as well as the iteration of a
+ * component whose inner components need to be stabilized again, as it happens
+ * for the backward analysis of this nest. WTOs of this depth used to overflow
+ * the stack.
+ */
+TEST(LargeGraphStressTest, loopNestWithQuadraticAlgorithms) {
+  constexpr uint32_t kDepth = 1000;
+  Program program = make_loop_nest(kDepth);
+
+  LivenessRelations relations(program);
+  Liveness liveness(program, relations);
+  liveness.run(VariableSet());
+  EXPECT_EQ(VariableSet({0}), liveness.get_state_before(1));
+  EXPECT_EQ(VariableSet(), liveness.get_state_before(program.size() - 1));
+
+  ConstantPropagation<WTOMonotonicFixpointIterator> analyzer(program);
+  analyzer.run(Environment());
+  EXPECT_EQ(Constant(1),
+            analyzer.get_exit_state_at(ProgramInterface::exit(program)).get(2));
+
+  WeakTopologicalOrdering<uint32_t> wto(0, [&program](const uint32_t& node) {
+    return program.successor_nodes(node);
+  });
+  size_t depth = 0;
+  for (auto it = wto.begin(); it != wto.end();) {
+    if (!it->is_scc()) {
+      ++it;
+      continue;
+    }
+    ++depth;
+    auto end = it->end();
+    it = it->begin();
+    while (it != end && !it->is_scc()) {
+      ++it;
+    }
+    if (it == end) {
+      break;
+    }
+  }
+  EXPECT_EQ(kDepth, depth);
+}
@@ -0,0 +1,63 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "LatticeOrder.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "IntervalDomain.h"
+
+using namespace sparta;
+
+namespace {
+
+using Domain = IntervalDomain<int>;
+using Ordered = LatticeOrder<Domain>;
+
+TEST(LatticeOrderTest, partialCompare) {
+  auto a = Domain::finite(0, 5);
+  auto b = Domain::finite(1, 3);
+  auto c = Domain::finite(4, 10);
+
+  EXPECT_EQ(PartialOrdering::Greater, partial_compare(a, b));
+  EXPECT_EQ(PartialOrdering::Less, partial_compare(b, a));
+  EXPECT_EQ(PartialOrdering::Equivalent, partial_compare(a, a));
+  EXPECT_EQ(PartialOrdering::Unordered, partial_compare(a, c));
+  EXPECT_EQ(PartialOrdering::Less, partial_compare(Domain::bottom(), a));
+  EXPECT_EQ(PartialOrdering::Greater, partial_compare(Domain::top(), a));
+
+  std::ostringstream out;
+  out << partial_compare(a, c);
+  EXPECT_EQ("Unordered", out.str());
+}
+
+TEST(LatticeOrderTest, operators) {
+  Ordered a(Domain::finite(0, 5));
+  Ordered b(Domain::finite(1, 3));
+  Ordered c(Domain::finite(4, 10));
+
+  EXPECT_TRUE(b < a);
+  EXPECT_TRUE(b <= a);
+  EXPECT_TRUE(a > b);
+  EXPECT_TRUE(a >= b);
+  EXPECT_TRUE(a != b);
+
+  EXPECT_TRUE(a <= a);
+  EXPECT_TRUE(a >= a);
+  EXPECT_FALSE(a < a);
+  EXPECT_TRUE(a == Ordered(Domain::finite(0, 5)));
+
+  // Incomparable values.
+  EXPECT_FALSE(a < c);
+  EXPECT_FALSE(a <= c);
+  EXPECT_FALSE(a > c);
+  EXPECT_FALSE(a >= c);
+  EXPECT_EQ(PartialOrdering::Unordered, a.compare(c));
+}
+
+} // namespace
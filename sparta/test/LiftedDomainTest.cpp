@@ -9,6 +9,7 @@
 #include "ConstantAbstractDomain.h"
 
 #include <gtest/gtest.h>
+#include <sstream>
 
 using namespace sparta;
 
@@ -54,4 +55,50 @@ TEST(LiftedDomainTest, meetAndJoin) {
   EXPECT_EQ(bot.meet(lbot), bot);
 }
 
+using TopLifted = TopLiftedDomain<Underlying>;
+
+TEST(TopLiftedDomainTest, ordering) {
+  const auto bot = TopLifted::bottom();
+  const auto top = TopLifted::top();
+  const auto ltop = TopLifted::lifted(Underlying::top());
+  const auto t = TopLifted::lifted(Underlying(true));
+  const auto f = TopLifted::lifted(Underlying(false));
+
+  EXPECT_TRUE(bot.is_bottom());
+  EXPECT_TRUE(top.is_top());
+  EXPECT_FALSE(ltop.is_top());
+  EXPECT_TRUE(TopLifted().is_lifted());
+
+  // Top is greater than everything.
+  EXPECT_TRUE(ltop.leq(top));
+  EXPECT_TRUE(t.leq(top));
+  EXPECT_FALSE(top.leq(ltop));
+
+  // Lifted top is still greater than everything except top.
+  EXPECT_TRUE(t.leq(ltop));
+  EXPECT_TRUE(f.leq(ltop));
+  EXPECT_TRUE(bot.leq(ltop));
+  EXPECT_FALSE(t.leq(f));
+}
+
+TEST(TopLiftedDomainTest, meetAndJoin) {
+  const auto top = TopLifted::top();
+  const auto ltop = TopLifted::lifted(Underlying::top());
+  const auto t = TopLifted::lifted(Underlying(true));
+  const auto f = TopLifted::lifted(Underlying(false));
+
+  EXPECT_EQ(top.join(ltop), top);
+  EXPECT_EQ(top.meet(ltop), ltop);
+  EXPECT_EQ(t.join(f), ltop);
+  EXPECT_TRUE(t.meet(f).is_bottom());
+  EXPECT_EQ(top.widening(t), top);
+  EXPECT_EQ(top.narrowing(t), t);
+  EXPECT_FALSE(top.equals(ltop));
+  EXPECT_FALSE(ltop.equals(top));
+
+  std::ostringstream out;
+  out << top << " " << ltop << " " << t << " " << TopLifted::bottom();
+  EXPECT_EQ("T * 1 _|_", out.str());
+}
+
 } // namespace
@@ -0,0 +1,116 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "MapAbstractDomain.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+#include <vector>
+
+#include "AbstractDomainPropertyTest.h"
+#include "IntervalDomain.h"
+
+using namespace sparta;
+
+using Interval = IntervalDomain<int32_t>;
+using Environment = MapAbstractDomain<uint32_t, Interval, DefaultIsTop>;
+using Partition = MapAbstractDomain<uint32_t, Interval, DefaultIsBottom>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(MapAbstractDomainEnvironment,
+                              AbstractDomainPropertyTest,
+                              Environment);
+
+INSTANTIATE_TYPED_TEST_CASE_P(MapAbstractDomainPartition,
+                              AbstractDomainPropertyTest,
+                              Partition);
+
+template <>
+std::vector<Environment>
+AbstractDomainPropertyTest<Environment>::non_extremal_values() {
+  Environment e1({{1, Interval::finite(0, 1)}, {2, Interval::finite(3, 4)}});
+  Environment e2({{2, Interval::finite(4, 8)}, {3, Interval::finite(-1, 0)}});
+  return {e1, e2};
+}
+
+template <>
+std::vector<Partition>
+AbstractDomainPropertyTest<Partition>::non_extremal_values() {
+  Partition p1({{1, Interval::finite(0, 1)}, {2, Interval::finite(3, 4)}});
+  Partition p2({{2, Interval::finite(4, 8)}, {3, Interval::finite(-1, 0)}});
+  return {p1, p2};
+}
+
+static_assert(default_is_top<Environment>::value, "");
+static_assert(default_is_bottom<Partition>::value, "");
+
+TEST(MapAbstractDomainTest, defaultValues) {
+  Environment env;
+  EXPECT_TRUE(env.is_top());
+  EXPECT_EQ(AbstractValueKind::Top, env.kind());
+  EXPECT_TRUE(env.get(1).is_top());
+  EXPECT_TRUE(Environment::bottom().get(1).is_bottom());
+
+  Partition partition;
+  EXPECT_TRUE(partition.is_bottom());
+  EXPECT_EQ(AbstractValueKind::Bottom, partition.kind());
+  EXPECT_TRUE(partition.get(1).is_bottom());
+  EXPECT_TRUE(Partition::top().get(1).is_top());
+}
+
+TEST(MapAbstractDomainTest, joinAndMeet) {
+  Environment e1({{1, Interval::finite(0, 1)}, {2, Interval::finite(3, 4)}});
+  Environment e2({{2, Interval::finite(5, 6)}, {3, Interval::finite(7, 8)}});
+  // The keys bound on one side only are bound to Top on the other side.
+  EXPECT_EQ(Environment({{2, Interval::finite(3, 6)}}), e1.join(e2));
+  EXPECT_TRUE(e1.meet(e2).is_bottom());
+
+  Partition p1({{1, Interval::finite(0, 1)}, {2, Interval::finite(3, 4)}});
+  Partition p2({{2, Interval::finite(5, 6)}, {3, Interval::finite(7, 8)}});
+  // The labels bound on one side only are bound to Bottom on the other side.
+  EXPECT_EQ(Partition({{1, Interval::finite(0, 1)},
+                       {2, Interval::finite(3, 6)},
+                       {3, Interval::finite(7, 8)}}),
+            p1.join(p2));
+  EXPECT_TRUE(p1.meet(p2).is_bottom());
+}
+
+TEST(MapAbstractDomainTest, collapsingValues) {
+  // Binding a key of an environment to Bottom makes it Bottom.
+  Environment env({{1, Interval::finite(0, 1)}});
+  env.map([](const Interval&) { return Interval::bottom(); });
+  EXPECT_TRUE(env.is_bottom());
+  EXPECT_THROW(env.size(), undefined_operation);
+
+  // Binding a label of a partition to Top doesn't make it Top.
+  Partition partition({{1, Interval::finite(0, 1)}});
+  partition.set(2, Interval::top());
+  EXPECT_FALSE(partition.is_top());
+  EXPECT_EQ(2, partition.size());
+  partition.set_to_top();
+  EXPECT_THROW(partition.size(), undefined_operation);
+}
+
+TEST(MapAbstractDomainTest, setAllOnPartitions) {
+  Partition partition(
+      {{1, Interval::finite(0, 1)}, {2, Interval::finite(2, 2)}});
+  partition.set_all({{2, Interval::bottom()}, {3, Interval::finite(3, 3)}});
+  EXPECT_EQ(
+      Partition({{1, Interval::finite(0, 1)}, {3, Interval::finite(3, 3)}}),
+      partition);
+
+  partition.unset_all({1});
+  EXPECT_EQ(Partition({{3, Interval::finite(3, 3)}}), partition);
+  partition.clear();
+  EXPECT_TRUE(partition.is_bottom());
+}
+
+TEST(MapAbstractDomainTest, prettyPrinting) {
+  std::ostringstream out;
+  out << Environment::bottom() << " " << Partition::top() << " "
+      << Partition({{1, Interval::finite(0, 1)}});
+  EXPECT_EQ("_|_ T [#1]{1 -> [0, 1]}", out.str());
+}
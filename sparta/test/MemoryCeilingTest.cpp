@@ -0,0 +1,155 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "MemoryCeiling.h"
+
+#include <gtest/gtest.h>
+
+#include "HashedSetAbstractDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Domain = HashedSetAbstractDomain<uint32_t>;
+
+/*
+ * The node 2 adds the size of the set to the set, so that the states of a
+ * loop that contains it grow forever unless they are widened to Top.
+ */
+template <template <typename, typename, typename> class Iterator>
+class GrowingAnalyzer final
+    : public Iterator<GraphInterface, Domain, std::hash<uint32_t>> {
+ public:
+  using Base = Iterator<GraphInterface, Domain, std::hash<uint32_t>>;
+
+  using Base::Base;
+
+  void analyze_node(const uint32_t& node, Domain* state) const override {
+    if (node == 2 && state->is_value()) {
+      state->add(state->size());
+    }
+  }
+
+  Domain analyze_edge(const size_t&, const Domain& state) const override {
+    return state;
+  }
+};
+
+size_t size_of(const Domain& state) {
+  return state.is_value() ? state.size() : 0;
+}
+
+template <typename Analyzer>
+void check_divergent_loop(Analyzer* analyzer) {
+  MemoryCeiling ceiling(100);
+  analyzer->set_memory_ceiling(&ceiling, size_of);
+  analyzer->run(Domain({0}));
+  EXPECT_TRUE(ceiling.exceeded());
+  EXPECT_GT(ceiling.peak_usage(), ceiling.limit());
+  EXPECT_TRUE(analyzer->get_entry_state_at(1).is_top());
+  EXPECT_TRUE(analyzer->get_exit_state_at(3).is_top());
+  EXPECT_EQ(Domain({0}), analyzer->get_exit_state_at(0));
+  analyzer->reset();
+  EXPECT_EQ(0, ceiling.usage());
+}
+
+} // namespace
+
+TEST(MemoryCeilingTest, divergentLoop) {
+  //  0 -> 1 -> 3
+  //       ^
+  //       v
+  //       2
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 1);
+  graph.add_edge(1, 3);
+
+  GrowingAnalyzer<MonotonicFixpointIterator> wpo(graph);
+  check_divergent_loop(&wpo);
+  GrowingAnalyzer<WTOMonotonicFixpointIterator> wto(graph);
+  check_divergent_loop(&wto);
+  GrowingAnalyzer<ParallelMonotonicFixpointIterator> parallel(graph);
+  check_divergent_loop(&parallel);
+}
+
+TEST(MemoryCeilingTest, sharedAccounting) {
+  //  0 -> 2 -> 3
+  Graph graph;
+  graph.add_edge(0, 2);
+  graph.add_edge(2, 3);
+
+  MemoryCeiling ceiling(100);
+  GrowingAnalyzer<MonotonicFixpointIterator> first(graph);
+  GrowingAnalyzer<MonotonicFixpointIterator> second(graph);
+  first.set_memory_ceiling(&ceiling, size_of);
+  second.set_memory_ceiling(&ceiling, size_of);
+
+  // Node 0: 1 + 1, node 2: 1 + 2, node 3: 2 + 2.
+  first.run(Domain({5}));
+  EXPECT_EQ(9, ceiling.usage());
+  first.run(Domain({5}));
+  EXPECT_EQ(9, ceiling.usage());
+  second.run(Domain({5, 6}));
+  EXPECT_EQ(24, ceiling.usage());
+  EXPECT_FALSE(ceiling.exceeded());
+  EXPECT_EQ(Domain({1, 5}), first.get_exit_state_at(3));
+
+  first.clear_and_shrink();
+  EXPECT_EQ(15, ceiling.usage());
+  second.set_memory_ceiling(nullptr);
+  EXPECT_EQ(0, ceiling.usage());
+  EXPECT_EQ(24, ceiling.peak_usage());
+
+  ceiling.charge(200);
+  EXPECT_TRUE(ceiling.exceeded());
+  ceiling.release(200);
+  EXPECT_TRUE(ceiling.exceeded());
+  ceiling.reset();
+  EXPECT_FALSE(ceiling.exceeded());
+  EXPECT_EQ(0, ceiling.peak_usage());
+}
+
+namespace {
+
+struct Sized {
+  size_t size_hint() const { return 42; }
+};
+
+} // namespace
+
+TEST(MemoryCeilingTest, sizeHint) {
+  EXPECT_EQ(42, size_hint_of(Sized()));
+  EXPECT_EQ(sizeof(Domain), size_hint_of(Domain()));
+}
+
+TEST(MemoryCeilingTest, parallelAccounting) {
+  //  0 -> i -> 1000 for 1 <= i < 1000
+  Graph graph;
+  for (uint32_t node = 1; node < 1000; ++node) {
+    graph.add_edge(0, node);
+    graph.add_edge(node, 1000);
+  }
+
+  MemoryCeiling sequential_ceiling(100000);
+  GrowingAnalyzer<MonotonicFixpointIterator> sequential(graph);
+  sequential.set_memory_ceiling(&sequential_ceiling, size_of);
+  sequential.run(Domain({7}));
+
+  MemoryCeiling parallel_ceiling(100000);
+  GrowingAnalyzer<ParallelMonotonicFixpointIterator> parallel(graph, 8);
+  parallel.set_memory_ceiling(&parallel_ceiling, size_of);
+  parallel.run(Domain({7}));
+
+  EXPECT_EQ(sequential_ceiling.usage(), parallel_ceiling.usage());
+  EXPECT_EQ(sequential.get_exit_state_at(1000),
+            parallel.get_exit_state_at(1000));
+}
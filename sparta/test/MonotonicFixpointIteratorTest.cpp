@@ -463,6 +463,227 @@ TYPED_TEST(MonotonicFixpointIteratorLivenessTest, program3) {
               ::testing::UnorderedElementsAre("z", "c", "b", "y"));
 }
 
+TYPED_TEST(MonotonicFixpointIteratorLivenessTest, parallelJoin) {
+  using namespace liveness;
+  /*
+   * 0: switch (x) {
+   *      case i: use(vi);  // for 1 <= i <= 300
+   *    }
+   * 301: return
+   *
+   * In the reversed graph, node 0 has 300 predecessors.
+   */
+  constexpr uint32_t num_cases = 300;
+  Program program(0);
+  program.add(0, Statement(/* use: */ {"x"}, /* def: */ {}));
+  program.add(num_cases + 1, Statement(/* use: */ {}, /* def: */ {}));
+  std::vector<std::string> cases;
+  for (uint32_t i = 1; i <= num_cases; ++i) {
+    std::string variable = "v" + std::to_string(i);
+    cases.push_back(variable);
+    program.add(i, Statement(/* use: */ {variable}, /* def: */ {}));
+    program.add_edge(0, i);
+    program.add_edge(i, num_cases + 1);
+  }
+  program.set_exit(num_cases + 1);
+
+  TypeParam fp(program);
+  fp.set_parallel_join(/* threshold */ 16, /* num_thread */ 4);
+  fp.run(LivenessDomain());
+
+  EXPECT_THAT(fp.get_live_out_vars_at(0).elements(),
+              ::testing::UnorderedElementsAreArray(cases));
+  cases.push_back("x");
+  EXPECT_THAT(fp.get_live_in_vars_at(0).elements(),
+              ::testing::UnorderedElementsAreArray(cases));
+}
+
+TYPED_TEST(MonotonicFixpointIteratorLivenessTest, initialNodes) {
+  using namespace liveness;
+  // The variable g is used by an exception handler that may run after any
+  // statement, and hence it is live everywhere.
+  TypeParam fp(this->m_program1);
+  fp.set_initial_nodes([](const uint32_t&) { return true; });
+  fp.run(LivenessDomain({"g"}));
+  for (uint32_t node = 1; node <= 6; ++node) {
+    EXPECT_TRUE(fp.get_live_out_vars_at(node).contains("g"));
+    EXPECT_TRUE(fp.get_live_in_vars_at(node).contains("g"));
+  }
+  EXPECT_THAT(fp.get_live_in_vars_at(2).elements(),
+              ::testing::UnorderedElementsAre("a", "c", "g"));
+
+  // The variable a is used at the exit and after statement 3.
+  fp.set_initial_nodes([](const uint32_t& node) { return node == 3; });
+  fp.run(LivenessDomain({"a"}));
+  EXPECT_THAT(fp.get_live_out_vars_at(3).elements(),
+              ::testing::UnorderedElementsAre("a", "b", "c"));
+  EXPECT_THAT(fp.get_live_in_vars_at(4).elements(),
+              ::testing::UnorderedElementsAre("b", "c"));
+  EXPECT_THAT(fp.get_live_in_vars_at(6).elements(),
+              ::testing::UnorderedElementsAre("a", "c"));
+
+  fp.set_initial_nodes(nullptr);
+  fp.run(LivenessDomain({"a"}));
+  EXPECT_THAT(fp.get_live_out_vars_at(3).elements(),
+              ::testing::UnorderedElementsAre("b", "c"));
+}
+
+TEST(MonotonicFixpointIteratorTest, successorNodes) {
+  using namespace liveness;
+  Program program(1);
+  for (uint32_t node = 1; node <= 3; ++node) {
+    program.add(node, Statement(/* use: */ {}, /* def: */ {}));
+  }
+  program.add_edge(1, 2);
+  program.add_edge(1, 3);
+  program.add_edge(1, 2);
+  program.add_edge(2, 1);
+  program.set_exit(3);
+
+  auto successors = successor_nodes<ProgramInterface>(program);
+  EXPECT_THAT(successors(1), ::testing::UnorderedElementsAre(2, 3));
+  EXPECT_THAT(successors(3), ::testing::IsEmpty());
+
+  WeakPartialOrdering<uint32_t> wpo(ProgramInterface::entry(program),
+                                    successors,
+                                    false);
+  // A head, a plain node and an exit for the loop, and a plain node for 3.
+  EXPECT_EQ(4, wpo.size());
+  EXPECT_TRUE(wpo.is_head(wpo.get_entry()));
+  EXPECT_EQ(1, wpo.get_node(wpo.get_entry()));
+}
+
+namespace liveness {
+
+/*
+ * while (...) {
+ *   0: switch (x) {
+ *        case i: use(vi); vj = ...;  // for 1 <= i <= num_cases, j = i + 1
+ *      }
+ *   num_cases + 1: x = ...;
+ * }
+ * num_cases + 2: return
+ *
+ * In the reversed graph, node 0 is the head of a loop and has num_cases
+ * predecessors.
+ */
+Program make_switch_in_loop(uint32_t num_cases) {
+  Program program(0);
+  program.add(0, Statement(/* use: */ {"x"}, /* def: */ {}));
+  for (uint32_t i = 1; i <= num_cases; ++i) {
+    program.add(i,
+                Statement(/* use: */ {"v" + std::to_string(i)},
+                          /* def: */ {"v" + std::to_string(i + 1)}));
+    program.add_edge(0, i);
+    program.add_edge(i, num_cases + 1);
+  }
+  program.add(num_cases + 1, Statement(/* use: */ {}, /* def: */ {"x"}));
+  program.add(num_cases + 2, Statement(/* use: */ {}, /* def: */ {}));
+  program.add_edge(num_cases + 1, 0);
+  program.add_edge(num_cases + 1, num_cases + 2);
+  program.set_exit(num_cases + 2);
+  return program;
+}
+
+template <typename FixpointEngine>
+void check_parallel_join(const Program& program, uint32_t num_nodes) {
+  FixpointEngine reference(program);
+  reference.run(LivenessDomain());
+  FixpointEngine fp(program);
+  fp.set_parallel_join(/* threshold */ 16, /* num_thread */ 4);
+  fp.run(LivenessDomain());
+  for (uint32_t node = 0; node < num_nodes; ++node) {
+    EXPECT_EQ(reference.get_live_in_vars_at(node),
+              fp.get_live_in_vars_at(node));
+    EXPECT_EQ(reference.get_live_out_vars_at(node),
+              fp.get_live_out_vars_at(node));
+  }
+}
+
+} // namespace liveness
+
+TEST(MonotonicFixpointIteratorParallelJoinTest, sameResults) {
+  using namespace liveness;
+  constexpr uint32_t num_cases = 300;
+  Program program = make_switch_in_loop(num_cases);
+  FixpointEngine<sparta::MonotonicFixpointIterator> sequential(program);
+  sequential.run(LivenessDomain());
+  EXPECT_TRUE(sequential.get_live_out_vars_at(0).contains("v1"));
+  EXPECT_TRUE(sequential.get_live_out_vars_at(num_cases).contains("v1"));
+
+  // The parallel join yields the same results as the sequential one.
+  check_parallel_join<FixpointEngine<sparta::WTOMonotonicFixpointIterator>>(
+      program, num_cases + 3);
+  check_parallel_join<FixpointEngine<sparta::MonotonicFixpointIterator>>(
+      program, num_cases + 3);
+  check_parallel_join<
+      FixpointEngine<sparta::ParallelMonotonicFixpointIterator>>(
+      program, num_cases + 3);
+
+  // The sequential and parallel fixpoint iterators agree when both use the
+  // parallel join.
+  FixpointEngine<sparta::ParallelMonotonicFixpointIterator> parallel(program);
+  parallel.set_parallel_join(/* threshold */ 16, /* num_thread */ 4);
+  parallel.run(LivenessDomain());
+  for (uint32_t node = 0; node < num_cases + 3; ++node) {
+    EXPECT_EQ(sequential.get_live_in_vars_at(node),
+              parallel.get_live_in_vars_at(node));
+    EXPECT_EQ(sequential.get_live_out_vars_at(node),
+              parallel.get_live_out_vars_at(node));
+  }
+}
+
+namespace liveness {
+
+/*
+ * A sequence of num_loops loops of 10 nodes each, where node i uses the
+ * variable v(i mod 10).
+ */
+Program make_loops(uint32_t num_loops) {
+  Program program(0);
+  uint32_t num_nodes = 10 * num_loops;
+  for (uint32_t i = 0; i <= num_nodes; ++i) {
+    program.add(i, Statement(/* use: */ {"v" + std::to_string(i % 10)},
+                             /* def: */ {}));
+    if (i < num_nodes) {
+      program.add_edge(i, i + 1);
+    }
+    if (i % 10 == 9) {
+      program.add_edge(i, i - 9);
+    }
+  }
+  program.set_exit(num_nodes);
+  return program;
+}
+
+/*
+ * The tables of states of the iterators based on a WPO are sized upfront,
+ * hence they are never rehashed during a run.
+ */
+template <typename FixpointEngine>
+void check_presized_tables(const Program& program, size_t num_nodes) {
+  FixpointEngine fp(program);
+  size_t entry_buckets = fp.m_entry_states.bucket_count();
+  size_t exit_buckets = fp.m_exit_states.bucket_count();
+  fp.run(LivenessDomain());
+  EXPECT_EQ(entry_buckets, fp.m_entry_states.bucket_count());
+  EXPECT_EQ(exit_buckets, fp.m_exit_states.bucket_count());
+  EXPECT_EQ(num_nodes, fp.m_entry_states.size());
+  EXPECT_TRUE(fp.get_live_in_vars_at(0).contains("v0"));
+}
+
+} // namespace liveness
+
+TEST(MonotonicFixpointIteratorPresizingTest, largeGraph) {
+  using namespace liveness;
+  Program program = make_loops(/* num_loops */ 1000);
+  check_presized_tables<FixpointEngine<sparta::MonotonicFixpointIterator>>(
+      program, 10001);
+  check_presized_tables<
+      FixpointEngine<sparta::ParallelMonotonicFixpointIterator>>(program,
+                                                                 10001);
+}
+
 namespace numerical {
 
 using namespace sparta;
@@ -860,3 +1081,142 @@ TYPED_TEST(MonotonicFixpointIteratorNumericalTest, program2) {
             IntegerSetAbstractDomain::top());
   EXPECT_EQ(fp.get_exit_state_at(bb3).get(&x), IntegerSetAbstractDomain::top());
 }
+
+TYPED_TEST(MonotonicFixpointIteratorNumericalTest, rerun) {
+  using namespace numerical;
+
+  /*
+   * bb1: x = 1;
+   *      while (...) {
+   * bb2:   y = x + 1;
+   *      }
+   * bb3: return
+   */
+  Program program;
+
+  BasicBlock* bb1 = program.create_block();
+  BasicBlock* bb2 = program.create_block();
+  BasicBlock* bb3 = program.create_block();
+
+  std::string x = "x";
+  std::string y = "y";
+
+  bb1->add(std::make_unique<Assignment>(&x, 1));
+  bb1->add_successor(bb2);
+
+  bb2->add(std::make_unique<Addition>(&y, &x, 1));
+  bb2->add_successor(bb2);
+  bb2->add_successor(bb3);
+
+  program.set_entry(bb1);
+  program.set_exit(bb3);
+
+  TypeParam fp(program);
+  fp.run(AbstractEnvironment::top());
+  auto exit_state = fp.get_exit_state_at(bb3);
+  EXPECT_EQ(exit_state.get(&x), IntegerSetAbstractDomain{1});
+  EXPECT_EQ(exit_state.get(&y), IntegerSetAbstractDomain{2});
+
+  // Running the iterator again over the same graph yields the same result.
+  fp.run(AbstractEnvironment::top());
+  EXPECT_EQ(fp.get_exit_state_at(bb3), exit_state);
+
+  fp.clear_and_shrink();
+  EXPECT_TRUE(fp.get_entry_state_at(bb1).is_bottom());
+  EXPECT_TRUE(fp.get_exit_state_at(bb3).is_bottom());
+
+  fp.run(AbstractEnvironment::top());
+  EXPECT_EQ(fp.get_exit_state_at(bb3), exit_state);
+}
+
+namespace implicit {
+
+using namespace sparta;
+
+/*
+ * A graph over the integers in [0, limit), in which each node n has the edges
+ * n -> n + 1 and n -> 2n. The nodes are only materialized when the fixpoint
+ * iterator queries them.
+ */
+struct Graph {
+  uint32_t limit;
+  mutable std::unordered_set<uint32_t> materialized;
+};
+
+class GraphInterface {
+ public:
+  using Graph = implicit::Graph;
+  using NodeId = uint32_t;
+  using EdgeId = std::pair<uint32_t, uint32_t>;
+
+  static NodeId entry(const Graph&) { return 0; }
+  static std::vector<EdgeId> predecessors(const Graph& graph,
+                                          const NodeId& node) {
+    graph.materialized.insert(node);
+    std::vector<EdgeId> edges;
+    if (node > 0) {
+      edges.emplace_back(node - 1, node);
+    }
+    if (node > 0 && node % 2 == 0) {
+      edges.emplace_back(node / 2, node);
+    }
+    return edges;
+  }
+  static std::vector<EdgeId> successors(const Graph& graph,
+                                        const NodeId& node) {
+    graph.materialized.insert(node);
+    std::vector<EdgeId> edges;
+    if (node + 1 < graph.limit) {
+      edges.emplace_back(node, node + 1);
+    }
+    if (node > 0 && 2 * node < graph.limit) {
+      edges.emplace_back(node, 2 * node);
+    }
+    return edges;
+  }
+  static NodeId source(const Graph&, const EdgeId& edge) { return edge.first; }
+  static NodeId target(const Graph&, const EdgeId& edge) {
+    return edge.second;
+  }
+  // A deliberately wrong estimate.
+  static size_t size_hint(const Graph&) { return 1; }
+};
+
+using Domain = HashedSetAbstractDomain<uint32_t>;
+
+/*
+ * Collects the nodes along the paths that lead to a node.
+ */
+class FixpointEngine final
+    : public MonotonicFixpointIterator<GraphInterface, Domain> {
+ public:
+  using MonotonicFixpointIterator::MonotonicFixpointIterator;
+
+  void analyze_node(const uint32_t& node, Domain* state) const override {
+    state->add(node);
+  }
+
+  Domain analyze_edge(const GraphInterface::EdgeId&,
+                      const Domain& state) const override {
+    return state;
+  }
+};
+
+} // namespace implicit
+
+TEST(MonotonicFixpointIteratorTest, implicitGraph) {
+  using namespace implicit;
+  Graph graph{100, {}};
+  EXPECT_EQ(1, graph_size_hint<GraphInterface>(graph));
+  EXPECT_EQ(1,
+            graph_size_hint<BackwardsFixpointIterationAdaptor<GraphInterface>>(
+                graph));
+  EXPECT_EQ(0,
+            graph_size_hint<liveness::ProgramInterface>(liveness::Program(0)));
+
+  FixpointEngine fp(graph);
+  fp.run(Domain());
+  EXPECT_EQ(100, graph.materialized.size());
+  EXPECT_EQ(Domain({0, 1, 2, 3, 4, 5, 6}), fp.get_exit_state_at(6));
+  EXPECT_EQ(100, fp.get_exit_state_at(99).size());
+}
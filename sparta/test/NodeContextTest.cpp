@@ -0,0 +1,96 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <gtest/gtest.h>
+#include <string>
+
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "NodeInfo.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+/*
+ *   0 -> 1 -> 2 -> 3
+ *        ^    |
+ *        +----+
+ *
+ * The transformer of node 2 fails.
+ */
+template <template <typename, typename, typename> class Iterator>
+class Analyzer final
+    : public Iterator<GraphInterface, Interval, std::hash<uint32_t>> {
+ public:
+  using Iterator<GraphInterface, Interval, std::hash<uint32_t>>::Iterator;
+
+  void analyze_node(const uint32_t& node, Interval*) const override {
+    RUNTIME_CHECK(node != 2,
+                  undefined_operation() << error_msg("Unsupported node"));
+  }
+
+  Interval analyze_edge(const size_t&, const Interval& state) const override {
+    return state;
+  }
+};
+
+Graph make_graph() {
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 1);
+  graph.add_edge(2, 3);
+  return graph;
+}
+
+template <typename Analyzer>
+std::string node_of_error(Analyzer* analyzer) {
+  try {
+    analyzer->run(Interval::top());
+  } catch (const undefined_operation& e) {
+    const std::string* name = boost::get_error_info<node_name>(e);
+    EXPECT_NE(nullptr, boost::get_error_info<error_msg>(e));
+    return name == nullptr ? "" : *name;
+  }
+  ADD_FAILURE() << "The analysis did not fail";
+  return "";
+}
+
+} // namespace
+
+TEST(NodeContextTest, nodeIdentifiers) {
+  Graph graph = make_graph();
+
+  Analyzer<WTOMonotonicFixpointIterator> wto(graph);
+  EXPECT_EQ("2", node_of_error(&wto));
+
+  Analyzer<MonotonicFixpointIterator> wpo(graph);
+  EXPECT_EQ("2", node_of_error(&wpo));
+}
+
+TEST(NodeContextTest, nodeInfo) {
+  Graph graph = make_graph();
+  HashedNodeInfo<uint32_t> node_info;
+  node_info.set_location(2, SourceLocation{"Foo.bar:()V", 0x1c, 12});
+
+  Analyzer<MonotonicFixpointIterator> analyzer(graph);
+  analyzer.set_node_info(&node_info);
+  EXPECT_EQ("Foo.bar:()V+0x1c (line 12)", node_of_error(&analyzer));
+
+  analyzer.set_node_info(nullptr);
+  EXPECT_EQ("2", node_of_error(&analyzer));
+}
+
+TEST(NodeContextTest, describeNode) {
+  EXPECT_EQ("7", describe_node<uint32_t>(7, nullptr));
+  struct Opaque {};
+  EXPECT_EQ("<node>", describe_node<Opaque>(Opaque(), nullptr));
+}
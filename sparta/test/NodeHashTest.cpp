@@ -0,0 +1,128 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <atomic>
+#include <gtest/gtest.h>
+#include <vector>
+
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+
+using namespace sparta;
+
+namespace {
+
+/*
+ * A node identifier without a specialization of std::hash.
+ */
+struct Block {
+  uint32_t id;
+
+  bool operator==(const Block& other) const { return id == other.id; }
+};
+
+std::atomic<size_t> hash_calls{0};
+
+/*
+ * A cheap multiplicative hash that counts its invocations.
+ */
+struct BlockHash {
+  size_t operator()(const Block& block) const {
+    ++hash_calls;
+    return block.id * 0x9e3779b97f4a7c15ULL;
+  }
+};
+
+/*
+ *   0 -> 1 -> 2 -> 3
+ *        ^    |
+ *        +----+
+ */
+struct Graph {
+  std::vector<std::vector<uint32_t>> successors{{1}, {2}, {1, 3}, {}};
+  std::vector<std::vector<uint32_t>> predecessors{{}, {0, 2}, {1}, {2}};
+};
+
+class GraphInterface {
+ public:
+  using Graph = ::Graph;
+  using NodeId = Block;
+  using EdgeId = std::pair<Block, Block>;
+
+  static NodeId entry(const Graph&) { return Block{0}; }
+  static std::vector<EdgeId> predecessors(const Graph& graph,
+                                          const NodeId& node) {
+    std::vector<EdgeId> edges;
+    for (uint32_t pred : graph.predecessors[node.id]) {
+      edges.emplace_back(Block{pred}, node);
+    }
+    return edges;
+  }
+  static std::vector<EdgeId> successors(const Graph& graph,
+                                        const NodeId& node) {
+    std::vector<EdgeId> edges;
+    for (uint32_t succ : graph.successors[node.id]) {
+      edges.emplace_back(node, Block{succ});
+    }
+    return edges;
+  }
+  static NodeId source(const Graph&, const EdgeId& edge) { return edge.first; }
+  static NodeId target(const Graph&, const EdgeId& edge) { return edge.second; }
+};
+
+using Interval = IntervalDomain<int32_t>;
+
+/*
+ * Counts the iterations of the loop, up to 10.
+ */
+template <template <typename, typename, typename> class Iterator>
+class Analyzer final : public Iterator<GraphInterface, Interval, BlockHash> {
+ public:
+  using Iterator<GraphInterface, Interval, BlockHash>::Iterator;
+
+  void analyze_node(const Block& block, Interval* state) const override {
+    if (block.id == 0) {
+      *state = Interval::finite(0, 0);
+    } else if (block.id == 2) {
+      *state = (*state + Interval::finite(1, 1)).meet(Interval::finite(0, 10));
+    }
+  }
+
+  Interval analyze_edge(const GraphInterface::EdgeId&,
+                        const Interval& state) const override {
+    return state;
+  }
+};
+
+template <typename Analyzer>
+void check(Analyzer* analyzer) {
+  hash_calls = 0;
+  analyzer->run(Interval::top());
+  EXPECT_GT(hash_calls, 0);
+  EXPECT_EQ(Interval::finite(1, 10), analyzer->get_exit_state_at(Block{2}));
+  EXPECT_EQ(Interval::finite(1, 10), analyzer->get_exit_state_at(Block{3}));
+}
+
+} // namespace
+
+TEST(NodeHashTest, wto) {
+  Graph graph;
+  Analyzer<WTOMonotonicFixpointIterator> analyzer(graph);
+  check(&analyzer);
+}
+
+TEST(NodeHashTest, wpo) {
+  Graph graph;
+  Analyzer<MonotonicFixpointIterator> analyzer(graph);
+  check(&analyzer);
+}
+
+TEST(NodeHashTest, parallelWpo) {
+  Graph graph;
+  Analyzer<ParallelMonotonicFixpointIterator> analyzer(graph, 2);
+  check(&analyzer);
+}
@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "NodeInfo.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+
+using namespace sparta;
+
+namespace {
+
+struct Opaque {
+  int id;
+  bool operator==(const Opaque& other) const { return id == other.id; }
+};
+
+struct OpaqueHash {
+  size_t operator()(const Opaque& o) const { return o.id; }
+};
+
+} // namespace
+
+TEST(NodeInfoTest, sourceLocation) {
+  std::ostringstream out;
+  out << SourceLocation{"LFoo;.bar:()V", 0x1a, 42};
+  EXPECT_EQ(out.str(), "LFoo;.bar:()V+0x1a (line 42)");
+
+  out.str("");
+  out << SourceLocation{"LFoo;.bar:()V", boost::none, boost::none};
+  EXPECT_EQ(out.str(), "LFoo;.bar:()V");
+
+  out.str("");
+  out << SourceLocation{"", 3, boost::none};
+  EXPECT_EQ(out.str(), "<unknown>+0x3");
+}
+
+TEST(NodeInfoTest, hashedNodeInfo) {
+  HashedNodeInfo<uint32_t> info;
+  info.set_location(1, SourceLocation{"f", 0, 10});
+  EXPECT_EQ(info.describe(1), "f+0x0 (line 10)");
+  EXPECT_EQ(info.location_of(1)->line, 10);
+  EXPECT_FALSE(info.location_of(2));
+  // The identifier is used when the location is unknown.
+  EXPECT_EQ(info.describe(2), "2");
+
+  HashedNodeInfo<Opaque, OpaqueHash> opaque_info;
+  opaque_info.set_location(Opaque{1}, SourceLocation{"g", boost::none, 3});
+  EXPECT_EQ(opaque_info.describe(Opaque{1}), "g (line 3)");
+  EXPECT_EQ(opaque_info.describe(Opaque{2}), "<node>");
+}
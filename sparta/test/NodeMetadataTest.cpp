@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "NodeMetadata.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <string>
+#include <vector>
+
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+/*
+ * Node 0 initializes a counter to 0 and node 2 increments it. Node 1 records
+ * the states it analyzes, and node 3 warns if the counter may exceed 10.
+ */
+template <template <typename, typename, typename> class Iterator>
+class MetadataAnalyzer final
+    : public Iterator<GraphInterface, Interval, std::hash<uint32_t>> {
+ public:
+  using Base = Iterator<GraphInterface, Interval, std::hash<uint32_t>>;
+
+  explicit MetadataAnalyzer(const Graph& graph) : Base(graph) {
+    this->attach_metadata(&m_states);
+    this->attach_metadata(&m_warnings);
+  }
+
+  void analyze_node(const uint32_t& node, Interval* state) const override {
+    if (node == 0) {
+      *state = Interval::finite(0, 0);
+    } else if (node == 1) {
+      m_states.at(node).push_back(*state);
+    } else if (node == 2) {
+      *state += 1;
+    } else if (node == 3 && !state->leq(Interval::finite(0, 10))) {
+      m_warnings.at(node).push_back("counter may exceed 10");
+    }
+  }
+
+  Interval analyze_edge(const size_t&, const Interval& state) const override {
+    return state;
+  }
+
+  const NodeMetadata<uint32_t, std::vector<Interval>>& states() const {
+    return m_states;
+  }
+
+  const NodeMetadata<uint32_t, std::vector<std::string>>& warnings() const {
+    return m_warnings;
+  }
+
+ private:
+  NodeMetadata<uint32_t, std::vector<Interval>> m_states;
+  NodeMetadata<uint32_t, std::vector<std::string>> m_warnings;
+};
+
+/*
+ *  0 -> 1 -> 3     4
+ *      ^ |         |
+ *      | v         v
+ *       2          1
+ */
+Graph make_graph() {
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 1);
+  graph.add_edge(1, 3);
+  graph.add_edge(4, 1);
+  return graph;
+}
+
+template <typename Analyzer>
+void check_metadata(const Graph& graph) {
+  Analyzer analyzer(graph);
+  for (size_t run = 0; run < 2; ++run) {
+    analyzer.run(Interval::top());
+    // Only the metadata attached during the last analysis of a node is kept.
+    ASSERT_TRUE(analyzer.states().contains(1));
+    EXPECT_THAT(*analyzer.states().get(1),
+                ::testing::ElementsAre(Interval::bounded_below(0)));
+    EXPECT_THAT(*analyzer.warnings().get(3),
+                ::testing::ElementsAre("counter may exceed 10"));
+    EXPECT_EQ(1, analyzer.states().size());
+    EXPECT_EQ(1, analyzer.warnings().size());
+    EXPECT_EQ(nullptr, analyzer.warnings().get(4));
+  }
+  analyzer.reset();
+  EXPECT_EQ(0, analyzer.states().size());
+  EXPECT_TRUE(analyzer.warnings().bindings().empty());
+}
+
+} // namespace
+
+TEST(NodeMetadataTest, fixpointIterators) {
+  Graph graph = make_graph();
+  check_metadata<MetadataAnalyzer<MonotonicFixpointIterator>>(graph);
+  check_metadata<MetadataAnalyzer<WTOMonotonicFixpointIterator>>(graph);
+  check_metadata<MetadataAnalyzer<ParallelMonotonicFixpointIterator>>(graph);
+}
+
+TEST(NodeMetadataTest, table) {
+  NodeMetadata<uint32_t, int> metadata;
+  EXPECT_FALSE(metadata.contains(1));
+  metadata.at(1) = 42;
+  ++metadata.at(1);
+  metadata.at(2);
+  EXPECT_EQ(43, *metadata.get(1));
+  EXPECT_EQ(0, *metadata.get(2));
+  EXPECT_EQ(2, metadata.size());
+  metadata.discard(1);
+  EXPECT_FALSE(metadata.contains(1));
+  metadata.clear();
+  EXPECT_EQ(0, metadata.size());
+}
@@ -0,0 +1,174 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "NodeSplitting.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <vector>
+
+#include "ConstantAbstractDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using SplitGraph = NodeSplittingGraph<SharedEdgeGraphInterface>;
+
+/*
+ * The transformer of node n assigns n to the variable 0, and records the
+ * previous value of the variable 0 in the variable n.
+ */
+using Constant = ConstantAbstractDomain<uint32_t>;
+using Environment = PatriciaTreeMapAbstractEnvironment<uint32_t, Constant>;
+
+class Analyzer final
+    : public NodeSplittingFixpointIterator<MonotonicFixpointIterator,
+                                           SharedEdgeGraphInterface,
+                                           Environment> {
+ public:
+  Analyzer(const SharedEdgeGraph& graph, size_t max_size)
+      : NodeSplittingFixpointIterator(graph, max_size) {}
+
+  void analyze_node(const uint32_t& node,
+                    Environment* current_state) const override {
+    current_state->set(node, current_state->get(0));
+    current_state->set(0, Constant(node));
+  }
+
+  Environment analyze_edge(
+      const SharedEdgeGraphInterface::EdgeId&,
+      const Environment& exit_state_at_source) const override {
+    return exit_state_at_source;
+  }
+};
+
+/*
+ * The canonical irreducible graph:
+ *
+ *        1
+ *       / \
+ *      v   v
+ *      2 <-> 3
+ *      |
+ *      v
+ *      4
+ */
+SharedEdgeGraph irreducible_graph() {
+  SharedEdgeGraph graph(1);
+  graph.add_edge(1, 2);
+  graph.add_edge(1, 3);
+  graph.add_edge(2, 3);
+  graph.add_edge(3, 2);
+  graph.add_edge(2, 4);
+  return graph;
+}
+
+} // namespace
+
+TEST(NodeSplittingTest, reducibleGraphIsUnchanged) {
+  SharedEdgeGraph graph(1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 3);
+  graph.add_edge(3, 2);
+  graph.add_edge(3, 4);
+
+  SplitGraph split(graph, /* max_size */ 100);
+  EXPECT_TRUE(split.is_reducible());
+  EXPECT_EQ(split.size(), 4);
+  for (uint32_t node = 1; node <= 4; ++node) {
+    EXPECT_EQ(split.copies_of(node).size(), 1);
+  }
+}
+
+TEST(NodeSplittingTest, splitIrreducibleLoop) {
+  auto graph = irreducible_graph();
+
+  SplitGraph split(graph, /* max_size */ 100);
+  EXPECT_TRUE(split.is_reducible());
+  EXPECT_EQ(split.size(), 5);
+  EXPECT_EQ(split.copies_of(1).size(), 1);
+  EXPECT_EQ(split.copies_of(4).size(), 1);
+  // One of the two entries of the loop has been duplicated.
+  EXPECT_EQ(split.copies_of(2).size() + split.copies_of(3).size(), 3);
+
+  // Every node of the split graph, except the entry, has at least one
+  // predecessor and every copy keeps the successors of the original node.
+  for (uint32_t node = 0; node < split.size(); ++node) {
+    if (node != split.entry()) {
+      EXPECT_FALSE(split.predecessors(node).empty());
+    }
+    std::vector<uint32_t> targets;
+    for (auto edge : split.successors(node)) {
+      targets.push_back(split.original_node(split.target(edge)));
+      EXPECT_EQ(split.original_edge(edge)->first, split.original_node(node));
+      EXPECT_EQ(split.original_edge(edge)->second, targets.back());
+    }
+    std::vector<uint32_t> original_targets;
+    for (const auto& edge : SharedEdgeGraphInterface::successors(
+             graph, split.original_node(node))) {
+      original_targets.push_back(edge->second);
+    }
+    EXPECT_THAT(targets,
+                ::testing::UnorderedElementsAreArray(original_targets));
+  }
+}
+
+TEST(NodeSplittingTest, nestedIrreducibleLoops) {
+  /*
+   * An irreducible loop {3, 4} nested in an irreducible loop {2, 3, 4, 5}.
+   */
+  SharedEdgeGraph graph(1);
+  graph.add_edge(1, 2);
+  graph.add_edge(1, 5);
+  graph.add_edge(2, 3);
+  graph.add_edge(2, 4);
+  graph.add_edge(3, 4);
+  graph.add_edge(4, 3);
+  graph.add_edge(4, 5);
+  graph.add_edge(5, 2);
+  graph.add_edge(5, 6);
+
+  SplitGraph split(graph, /* max_size */ 100);
+  EXPECT_TRUE(split.is_reducible());
+  EXPECT_GT(split.size(), 6);
+}
+
+TEST(NodeSplittingTest, budget) {
+  auto graph = irreducible_graph();
+
+  SplitGraph split(graph, /* max_size */ 4);
+  EXPECT_FALSE(split.is_reducible());
+  EXPECT_EQ(split.size(), 4);
+}
+
+TEST(NodeSplittingTest, fixpoint) {
+  auto graph = irreducible_graph();
+
+  Analyzer split_analyzer(graph, /* max_size */ 100);
+  EXPECT_TRUE(split_analyzer.split_graph().is_reducible());
+  split_analyzer.run(Environment::top());
+
+  Analyzer analyzer(graph, /* max_size */ 0);
+  EXPECT_FALSE(analyzer.split_graph().is_reducible());
+  analyzer.run(Environment::top());
+
+  for (uint32_t node = 1; node <= 4; ++node) {
+    EXPECT_EQ(split_analyzer.get_entry_state_at(node),
+              analyzer.get_entry_state_at(node));
+    EXPECT_EQ(split_analyzer.get_exit_state_at(node),
+              analyzer.get_exit_state_at(node));
+  }
+  EXPECT_EQ(split_analyzer.get_exit_state_at(1).get(0), Constant(1));
+  EXPECT_EQ(split_analyzer.get_exit_state_at(4).get(0), Constant(4));
+  EXPECT_EQ(split_analyzer.get_exit_state_at(4).get(4), Constant(2));
+  EXPECT_TRUE(split_analyzer.get_entry_state_at(2).get(0).is_top());
+  EXPECT_TRUE(split_analyzer.get_entry_state_at(5).is_bottom());
+}
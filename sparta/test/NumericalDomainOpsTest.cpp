@@ -0,0 +1,90 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "NumericalDomainOps.h"
+
+#include <cstdint>
+#include <gtest/gtest.h>
+#include <limits>
+
+#include "IntervalDomain.h"
+
+using namespace sparta;
+
+namespace {
+
+using Constant = ConstantAbstractDomain<int32_t>;
+using Interval = IntervalDomain<int32_t>;
+
+/*
+ * A transformer written once for all the numerical domains: the value of
+ * `(x + 1) * x`, and whether it is less than x.
+ */
+template <typename Domain>
+std::pair<Domain, BooleanDomain> square_plus(const Domain& x) {
+  using Ops = ArithmeticDomainOps<Domain>;
+  using CompareOps = CompareDomainOps<Domain>;
+  auto result = Ops::mul(Ops::add(x, Ops::literal(1)), x);
+  return {result, CompareOps::lt(result, x)};
+}
+
+} // namespace
+
+TEST(NumericalDomainOpsTest, booleans) {
+  EXPECT_EQ(BooleanDomain(false), negate(BooleanDomain(true)));
+  EXPECT_EQ(BooleanDomain(true), negate(BooleanDomain(false)));
+  EXPECT_TRUE(negate(BooleanDomain::top()).is_top());
+  EXPECT_TRUE(negate(BooleanDomain::bottom()).is_bottom());
+}
+
+TEST(NumericalDomainOpsTest, constants) {
+  using Ops = ArithmeticDomainOps<Constant>;
+  using CompareOps = CompareDomainOps<Constant>;
+  const auto top = Constant::top();
+  const auto bottom = Constant::bottom();
+
+  EXPECT_EQ(Constant(-7), Ops::neg(Constant(7)));
+  EXPECT_EQ(Constant(10), Ops::add(Constant(3), Constant(7)));
+  EXPECT_EQ(Constant(-4), Ops::sub(Constant(3), Constant(7)));
+  EXPECT_EQ(Constant(21), Ops::mul(Constant(3), Constant(7)));
+  EXPECT_EQ(Constant(-2), Ops::div(Constant(-7), Constant(3)));
+  EXPECT_TRUE(Ops::div(top, Constant(0)).is_bottom());
+  EXPECT_TRUE(Ops::add(top, Constant(1)).is_top());
+  EXPECT_TRUE(Ops::mul(bottom, top).is_bottom());
+
+  EXPECT_EQ(Constant(2), Ops::bit_and(Constant(6), Constant(3)));
+  EXPECT_EQ(Constant(7), Ops::bit_or(Constant(6), Constant(3)));
+  EXPECT_EQ(Constant(5), Ops::bit_xor(Constant(6), Constant(3)));
+  EXPECT_EQ(Constant(std::numeric_limits<int32_t>::min()),
+            Ops::shl(Constant(1), Constant(31)));
+  EXPECT_EQ(Constant(-1), Ops::shr(Constant(-1), Constant(31)));
+  EXPECT_TRUE(Ops::shl(Constant(1), Constant(32)).is_top());
+  EXPECT_TRUE(Ops::shr(Constant(1), Constant(-1)).is_top());
+
+  EXPECT_EQ(BooleanDomain(true), CompareOps::eq(Constant(3), Constant(3)));
+  EXPECT_EQ(BooleanDomain(false), CompareOps::ne(Constant(3), Constant(3)));
+  EXPECT_EQ(BooleanDomain(true), CompareOps::lt(Constant(3), Constant(4)));
+  EXPECT_EQ(BooleanDomain(false), CompareOps::le(Constant(5), Constant(4)));
+  EXPECT_EQ(BooleanDomain(true), CompareOps::gt(Constant(5), Constant(4)));
+  EXPECT_EQ(BooleanDomain(true), CompareOps::ge(Constant(4), Constant(4)));
+  EXPECT_TRUE(CompareOps::eq(top, Constant(3)).is_top());
+  EXPECT_TRUE(CompareOps::ne(bottom, Constant(3)).is_bottom());
+}
+
+TEST(NumericalDomainOpsTest, genericTransformer) {
+  auto constant = square_plus(Constant(3));
+  EXPECT_EQ(Constant(12), constant.first);
+  EXPECT_EQ(BooleanDomain(false), constant.second);
+
+  auto interval = square_plus(Interval::finite(-1, 2));
+  EXPECT_EQ(Interval::finite(-3, 6), interval.first);
+  EXPECT_TRUE(interval.second.is_top());
+
+  interval = square_plus(Interval::finite(1, 2));
+  EXPECT_EQ(Interval::finite(2, 6), interval.first);
+  EXPECT_EQ(BooleanDomain(false), interval.second);
+}
@@ -0,0 +1,95 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "ParityDomain.h"
+
+#include <cstdint>
+#include <gtest/gtest.h>
+#include <limits>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+INSTANTIATE_TYPED_TEST_CASE_P(ParityDomain,
+                              AbstractDomainPropertyTest,
+                              ParityDomain);
+
+template <>
+std::vector<ParityDomain>
+AbstractDomainPropertyTest<ParityDomain>::non_extremal_values() {
+  return {ParityDomain(Parity::Even), ParityDomain(Parity::Odd)};
+}
+
+namespace {
+
+const auto even = ParityDomain(Parity::Even);
+const auto odd = ParityDomain(Parity::Odd);
+const auto top = ParityDomain::top();
+const auto bottom = ParityDomain::bottom();
+
+} // namespace
+
+TEST(ParityDomainTest, lattice) {
+  EXPECT_TRUE(ParityDomain().is_top());
+  EXPECT_EQ(even, ParityDomain::of(-4));
+  EXPECT_EQ(odd, ParityDomain::of(-3));
+  EXPECT_EQ(odd, ParityDomain::of(std::numeric_limits<int64_t>::max()));
+  EXPECT_EQ(Parity::Even, *even.get_parity());
+  EXPECT_FALSE(top.get_parity());
+  EXPECT_FALSE(bottom.get_parity());
+
+  EXPECT_TRUE(even.join(odd).is_top());
+  EXPECT_TRUE(even.meet(odd).is_bottom());
+  EXPECT_EQ(odd, odd.join(bottom));
+  EXPECT_EQ(odd, odd.meet(top));
+
+  std::ostringstream out;
+  out << even << " " << odd << " " << top << " " << bottom;
+  EXPECT_EQ("even odd T _|_", out.str());
+}
+
+TEST(ParityDomainTest, arithmetic) {
+  EXPECT_EQ(even, even + even);
+  EXPECT_EQ(odd, even + odd);
+  EXPECT_EQ(even, odd - odd);
+  EXPECT_EQ(odd, -odd);
+  EXPECT_TRUE((top + odd).is_top());
+  EXPECT_TRUE((bottom - top).is_bottom());
+
+  EXPECT_EQ(even, even * top);
+  EXPECT_EQ(odd, odd * odd);
+  EXPECT_TRUE((odd * top).is_top());
+  EXPECT_TRUE((even * bottom).is_bottom());
+}
+
+TEST(ParityDomainTest, operations) {
+  using Ops = ArithmeticDomainOps<ParityDomain>;
+  using CompareOps = CompareDomainOps<ParityDomain>;
+
+  EXPECT_EQ(odd, Ops::literal(7));
+  EXPECT_EQ(odd, Ops::add(Ops::literal(2), odd));
+  EXPECT_TRUE(Ops::div(even, even).is_top());
+  EXPECT_TRUE(Ops::div(bottom, even).is_bottom());
+
+  EXPECT_EQ(even, Ops::bit_and(even, top));
+  EXPECT_EQ(odd, Ops::bit_and(odd, odd));
+  EXPECT_TRUE(Ops::bit_and(odd, top).is_top());
+  EXPECT_EQ(odd, Ops::bit_or(top, odd));
+  EXPECT_EQ(even, Ops::bit_or(even, even));
+  EXPECT_TRUE(Ops::bit_or(even, top).is_top());
+  EXPECT_EQ(odd, Ops::bit_xor(even, odd));
+  EXPECT_TRUE(Ops::shl(even, odd).is_top());
+  EXPECT_TRUE(Ops::shr(odd, bottom).is_bottom());
+
+  EXPECT_EQ(BooleanDomain(false), CompareOps::eq(even, odd));
+  EXPECT_EQ(BooleanDomain(true), CompareOps::ne(odd, even));
+  EXPECT_TRUE(CompareOps::eq(odd, odd).is_top());
+  EXPECT_TRUE(CompareOps::lt(even, odd).is_top());
+  EXPECT_TRUE(CompareOps::ge(even, bottom).is_bottom());
+}
@@ -270,6 +270,63 @@ TEST_F(PatriciaTreeMapAbstractEnvironmentTest, map) {
   EXPECT_TRUE(e1.is_top());
 }
 
+TEST_F(PatriciaTreeMapAbstractEnvironmentTest, setAll) {
+  Environment e1({{1, Domain({"a", "b"})}, {2, Domain("c")}, {3, Domain("d")}});
+  e1.set_all({{2, Domain("e")}, {3, Domain::top()}, {4, Domain("f")}});
+  EXPECT_EQ(Environment(
+                {{1, Domain({"a", "b"})}, {2, Domain("e")}, {4, Domain("f")}}),
+            e1);
+
+  e1.set_all({{4, Domain("g")}, {4, Domain::top()}, {5, Domain("h")}});
+  EXPECT_EQ(Environment(
+                {{1, Domain({"a", "b"})}, {2, Domain("e")}, {5, Domain("h")}}),
+            e1);
+
+  e1.unset_all({1, 5, 6});
+  EXPECT_EQ(Environment({{2, Domain("e")}}), e1);
+  e1.unset_all(std::vector<uint32_t>{2});
+  EXPECT_TRUE(e1.is_top());
+
+  e1.set_all({{1, Domain("a")}, {2, Domain::bottom()}});
+  EXPECT_TRUE(e1.is_bottom());
+  e1.set_all({{1, Domain("a")}});
+  EXPECT_TRUE(e1.is_bottom());
+
+  for (size_t k = 0; k < 10; ++k) {
+    Environment env = this->generate_random_environment();
+    Environment updates = this->generate_random_environment();
+    std::vector<std::pair<uint32_t, Domain>> bindings;
+    std::vector<uint32_t> variables;
+    Environment expected = env;
+    if (updates.is_value()) {
+      for (const auto& binding : updates.bindings()) {
+        bindings.push_back(binding);
+        variables.push_back(binding.first);
+        expected.set(binding.first, binding.second);
+      }
+    }
+    // Overwrite some of the existing bindings.
+    if (env.is_value()) {
+      for (const auto& binding : env.bindings()) {
+        if (binding.first % 2 == 0) {
+          bindings.emplace_back(binding.first, Domain("x"));
+          variables.push_back(binding.first);
+          expected.set(binding.first, Domain("x"));
+        }
+      }
+    }
+    Environment actual = env;
+    actual.set_all(bindings);
+    EXPECT_EQ(expected, actual);
+
+    for (uint32_t variable : variables) {
+      expected.set(variable, Domain::top());
+    }
+    actual.unset_all(variables);
+    EXPECT_EQ(expected, actual);
+  }
+}
+
 TEST_F(PatriciaTreeMapAbstractEnvironmentTest, prettyPrinting) {
   using StringEnvironment =
       PatriciaTreeMapAbstractEnvironment<std::string*, Domain>;
@@ -280,3 +337,36 @@ TEST_F(PatriciaTreeMapAbstractEnvironmentTest, prettyPrinting) {
   out << e.bindings();
   EXPECT_EQ("{a -> [#1]{A}}", out.str());
 }
+
+TEST_F(PatriciaTreeMapAbstractEnvironmentTest, collectGarbage) {
+  // A variable holds the set of variables it references, e.g., 1 -> {"2"}
+  // means that variable 1 points to variable 2.
+  auto references = [](const Domain& value) {
+    std::vector<uint32_t> result;
+    for (const auto& element : value.elements()) {
+      result.push_back(std::stoul(element));
+    }
+    return result;
+  };
+  Environment e({{1, Domain("2")},
+                 {2, Domain({"3", "4"})},
+                 {3, Domain("2")},
+                 {5, Domain("6")},
+                 {6, Domain("1")}});
+
+  EXPECT_FALSE(e.collect_garbage({1, 5}, references));
+  EXPECT_EQ(5, e.size());
+
+  EXPECT_TRUE(e.collect_garbage({1}, references));
+  EXPECT_EQ(3, e.size());
+  EXPECT_THAT(e.get(2).elements(), ::testing::UnorderedElementsAre("3", "4"));
+  EXPECT_TRUE(e.get(5).is_top());
+  EXPECT_TRUE(e.get(6).is_top());
+
+  EXPECT_TRUE(e.collect_garbage({}, references));
+  EXPECT_TRUE(e.is_top());
+
+  Environment bottom = Environment::bottom();
+  EXPECT_FALSE(bottom.collect_garbage({}, references));
+  EXPECT_TRUE(bottom.is_bottom());
+}
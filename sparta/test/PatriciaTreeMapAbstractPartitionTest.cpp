@@ -258,3 +258,43 @@ TEST(PatriciaTreeMapAbstractPartitionTest, map) {
   EXPECT_TRUE(any_changes);
   EXPECT_TRUE(p1.is_bottom());
 }
+
+TEST(PatriciaTreeMapAbstractPartitionTest, fold) {
+  Partition p1({{1, Domain({"a", "b"})}, {2, Domain({"b", "c"})}});
+  auto count_elements = [](size_t n, uint32_t, const Domain& d) {
+    return n + d.size();
+  };
+  EXPECT_EQ(4, p1.fold(size_t(0), count_elements));
+  EXPECT_EQ(Domain({"a", "b", "c"}), p1.join_of_all_bindings());
+
+  Partition bottom;
+  EXPECT_EQ(0, bottom.fold(size_t(0), count_elements));
+  EXPECT_TRUE(bottom.join_of_all_bindings().is_bottom());
+
+  Partition top = Partition::top();
+  EXPECT_THROW(top.fold(size_t(0), count_elements), undefined_operation);
+  EXPECT_TRUE(top.join_of_all_bindings().is_top());
+}
+
+TEST(PatriciaTreeMapAbstractPartitionTest, labelCap) {
+  using CappedPartition =
+      PatriciaTreeMapAbstractPartition<uint32_t, Domain, /* MaxLabels */ 3>;
+  CappedPartition p1({{1, Domain("a")}, {2, Domain("b")}});
+  CappedPartition p2({{2, Domain("c")}, {3, Domain("d")}});
+
+  auto p3 = p1.join(p2);
+  EXPECT_EQ(3, p3.size());
+  EXPECT_EQ(Domain({"b", "c"}), p3.get(2));
+
+  // Setting a binding does not enforce the cap, only join-like operations do.
+  p3.set(4, Domain("e"));
+  EXPECT_EQ(4, p3.size());
+  EXPECT_TRUE(p1.join(p3).is_top());
+  EXPECT_TRUE(p1.widening(p3).is_top());
+  EXPECT_TRUE(p3.leq(p1.join(p3)));
+  EXPECT_EQ(2, p3.meet(p1).size());
+
+  std::ostringstream out;
+  out << p1.join(p3);
+  EXPECT_EQ("T", out.str());
+}
@@ -12,6 +12,7 @@
 #include <gmock/gmock.h>
 #include <gtest/gtest.h>
 #include <initializer_list>
+#include <string>
 #include <unordered_map>
 
 using namespace sparta;
@@ -209,3 +210,106 @@ TEST(PatriciaTreeMapTest, difference) {
                                      create_pt_map({{2, 1}, {4, 1}, {6, 1}})),
             create_pt_map({{1, 3}, {3, 3}, {5, 3}}));
 }
+
+namespace {
+
+struct HashedValue : ptmap_impl::SimpleValue<uint32_t> {
+  static size_t hash(const uint32_t& x) { return std::hash<uint32_t>()(x); }
+};
+
+using hashed_pt_map = PatriciaTreeMap<uint32_t, uint32_t, HashedValue>;
+
+} // namespace
+
+TEST(PatriciaTreeMapTest, contentHashing) {
+  // Two maps with the same bindings that do not share any structure.
+  hashed_pt_map m1;
+  hashed_pt_map m2;
+  for (uint32_t k = 0; k < 100; ++k) {
+    m1.insert_or_assign(k, k + 1);
+    m2.insert_or_assign(99 - k, 100 - k);
+  }
+  EXPECT_TRUE(m1.equals(m2));
+  EXPECT_FALSE(m1.reference_equals(m2));
+
+  auto sum = [](const uint32_t& x, const uint32_t& y) { return x + y; };
+  // The maps are recognized as equal, hence the combining function is never
+  // applied and the result is physically equal to the first operand.
+  auto u = m1.get_union_with(sum, m2);
+  EXPECT_TRUE(u.reference_equals(m1));
+  auto i = m1.get_intersection_with(sum, m2);
+  EXPECT_TRUE(i.reference_equals(m1));
+  EXPECT_TRUE(m1.get_difference_with(sum, m2).empty());
+
+  // Subtrees that hold the same bindings are skipped. Like physical equality,
+  // this assumes that the combining function is idempotent.
+  auto max = [](const uint32_t& x, const uint32_t& y) {
+    return std::max(x, y);
+  };
+  m2.insert_or_assign(0, 2);
+  m2.insert_or_assign(200, 1);
+  u = m1.get_union_with(max, m2);
+  EXPECT_EQ(2, u.at(0));
+  EXPECT_EQ(1, u.at(200));
+  for (uint32_t k = 1; k < 100; ++k) {
+    EXPECT_EQ(k + 1, u.at(k));
+  }
+  EXPECT_FALSE(m1.equals(m2));
+
+  // The content hash does not depend on the order of insertions.
+  hashed_pt_map m3;
+  m3.insert_or_assign(200, 1);
+  for (uint32_t k = 0; k < 100; ++k) {
+    m3.insert_or_assign(k, k == 0 ? 2 : k + 1);
+  }
+  EXPECT_TRUE(m3.equals(m2));
+  EXPECT_TRUE(m3.get_union_with(max, m2).reference_equals(m3));
+}
+
+TEST(PatriciaTreeMapTest, combineWithKey) {
+  pt_map m1;
+  pt_map m2;
+  for (uint32_t k = 0; k < 10; ++k) {
+    m1.insert_or_assign(k, k + 1);
+  }
+  for (uint32_t k = 5; k < 15; ++k) {
+    m2.insert_or_assign(k, 2 * k);
+  }
+  // Key 7 is a distinguished key whose bindings are summed, while the others
+  // are joined.
+  auto combine = [](uint32_t key, const uint32_t& x, const uint32_t& y) {
+    return key == 7 ? x + y : std::max(x, y);
+  };
+
+  auto u = m1;
+  u.union_with_key(combine, m2);
+  EXPECT_EQ(15, u.size());
+  EXPECT_EQ(1, u.at(0));
+  EXPECT_EQ(10, u.at(5));
+  EXPECT_EQ(8 + 14, u.at(7));
+  EXPECT_EQ(28, u.at(14));
+
+  auto i = m1;
+  i.intersection_with_key(combine, m2);
+  EXPECT_EQ(5, i.size());
+  EXPECT_EQ(0, i.at(4));
+  EXPECT_EQ(8 + 14, i.at(7));
+  EXPECT_EQ(18, i.at(9));
+
+  // The keys are decoded before being passed to the combining function.
+  PatriciaTreeMap<std::string*, uint32_t> m3;
+  PatriciaTreeMap<std::string*, uint32_t> m4;
+  std::string a = "a";
+  std::string b = "b";
+  m3.insert_or_assign(&a, 1);
+  m3.insert_or_assign(&b, 1);
+  m4.insert_or_assign(&a, 2);
+  m4.insert_or_assign(&b, 2);
+  m3.union_with_key(
+      [](std::string* key, const uint32_t& x, const uint32_t& y) {
+        return *key == "a" ? x + y : x * y;
+      },
+      m4);
+  EXPECT_EQ(3, m3.at(&a));
+  EXPECT_EQ(2, m3.at(&b));
+}
@@ -0,0 +1,95 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+// The instrumentation must be enabled before including any Patricia tree
+// header.
+#define SPARTA_PATRICIA_TREE_STATS
+
+#include "PatriciaTreeStats.h"
+
+#include <cstdint>
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "PatriciaTreeMap.h"
+#include "PatriciaTreeSet.h"
+
+using namespace sparta;
+
+TEST(PatriciaTreeStatsTest, setOperations) {
+  PatriciaTreeStats::reset();
+  PatriciaTreeSet<uint32_t> s1{1, 2, 3};
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::Updates), 3);
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::LeafAllocations), 3);
+  // Inserting 3 creates a branch for {1, 3} and copies the root.
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::BranchAllocations), 3);
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::PathCopies), 1);
+
+  PatriciaTreeSet<uint32_t> s2 = s1;
+  s2.union_with(s1);
+  EXPECT_TRUE(s2.reference_equals(s1));
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::MergeCalls), 1);
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::MergeReferenceHits), 1);
+
+  s2.insert(4);
+  EXPECT_TRUE(s1.is_subset_of(s2));
+  EXPECT_GT(PatriciaTreeStats::get(PatriciaTreeStats::SubsetCalls), 1);
+  // The subtree holding {1, 3} is shared by both sets.
+  EXPECT_GT(PatriciaTreeStats::get(PatriciaTreeStats::SubsetReferenceHits), 0);
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::PathCopies), 2);
+
+  PatriciaTreeStats::reset();
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::Updates), 0);
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::PathCopies), 0);
+}
+
+TEST(PatriciaTreeStatsTest, mapOperations) {
+  PatriciaTreeStats::reset();
+  PatriciaTreeMap<uint32_t, uint32_t> m1;
+  m1.insert_or_assign(1, 10);
+  m1.insert_or_assign(2, 20);
+  auto m2 = m1;
+  m2.update([](const uint32_t& x) { return x + 1; }, 1);
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::Updates), 3);
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::PathCopies), 1);
+
+  EXPECT_FALSE(m1.equals(m2));
+  EXPECT_GT(PatriciaTreeStats::get(PatriciaTreeStats::EqualsCalls), 0);
+  m2.intersection_with([](const uint32_t& x, const uint32_t&) { return x; },
+                       m1);
+  EXPECT_GT(PatriciaTreeStats::get(PatriciaTreeStats::IntersectCalls), 0);
+  EXPECT_GT(PatriciaTreeStats::get(PatriciaTreeStats::IntersectReferenceHits),
+            0);
+
+  std::ostringstream out;
+  PatriciaTreeStats::report(out);
+  EXPECT_THAT(out.str(), ::testing::HasSubstr("updates: 3, path copies: 1"));
+}
+
+TEST(PatriciaTreeStatsTest, contentHits) {
+  struct HashedValue : ptmap_impl::SimpleValue<uint32_t> {
+    static size_t hash(const uint32_t& x) { return x; }
+  };
+  using Map = PatriciaTreeMap<uint32_t, uint32_t, HashedValue>;
+
+  Map m1;
+  Map m2;
+  for (uint32_t k = 0; k < 8; ++k) {
+    m1.insert_or_assign(k, k + 1);
+    m2.insert_or_assign(7 - k, 8 - k);
+  }
+  m2.insert_or_assign(100, 1);
+  PatriciaTreeStats::reset();
+  m1.union_with([](const uint32_t& x, const uint32_t&) { return x; }, m2);
+  EXPECT_EQ(PatriciaTreeStats::get(PatriciaTreeStats::MergeReferenceHits), 0);
+  EXPECT_GT(PatriciaTreeStats::get(PatriciaTreeStats::ContentHits), 0);
+
+  std::ostringstream out;
+  PatriciaTreeStats::report(out);
+  EXPECT_THAT(out.str(), ::testing::HasSubstr("content hits: "));
+}
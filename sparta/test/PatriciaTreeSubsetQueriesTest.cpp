@@ -0,0 +1,80 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "PatriciaTreeSet.h"
+
+#include <cstdint>
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <random>
+#include <vector>
+
+using namespace sparta;
+
+using Set = PatriciaTreeSet<uint32_t>;
+
+TEST(PatriciaTreeSubsetQueriesTest, candidates) {
+  Set base;
+  for (uint32_t i = 0; i < 1000; i += 3) {
+    base.insert(i);
+  }
+  std::vector<Set> candidates;
+  candidates.push_back(base);
+  candidates.push_back(Set(base).insert(1));
+  candidates.push_back(Set(base).remove(999));
+  candidates.push_back(Set(base).insert(1000).insert(2000));
+  candidates.push_back(Set());
+  candidates.push_back(Set{0, 3, 6});
+
+  Set query = Set(base).remove(0).remove(3);
+  EXPECT_THAT(query.is_subset_of_each(candidates),
+              ::testing::ElementsAre(true, true, false, true, false, false));
+  EXPECT_THAT(Set().is_subset_of_each(candidates),
+              ::testing::ElementsAre(true, true, true, true, true, true));
+  EXPECT_TRUE(query.is_subset_of_each({}).empty());
+}
+
+TEST(PatriciaTreeSubsetQueriesTest, matrix) {
+  std::vector<Set> sets = {Set{1, 2, 3}, Set{1, 2}, Set{2, 3, 4}, Set(),
+                           Set{1, 2, 3}};
+  auto matrix = Set::subset_matrix(sets);
+  ASSERT_EQ(sets.size(), matrix.size());
+  using ::testing::ElementsAre;
+  EXPECT_THAT(matrix[0], ElementsAre(true, false, false, false, true));
+  EXPECT_THAT(matrix[1], ElementsAre(true, true, false, false, true));
+  EXPECT_THAT(matrix[2], ElementsAre(false, false, true, false, false));
+  EXPECT_THAT(matrix[3], ElementsAre(true, true, true, true, true));
+  EXPECT_THAT(matrix[4], ElementsAre(true, false, false, false, true));
+}
+
+TEST(PatriciaTreeSubsetQueriesTest, agreesWithPairwiseTests) {
+  std::mt19937 generator(7);
+  std::uniform_int_distribution<uint32_t> element(0, 200);
+  std::vector<Set> sets;
+  Set current;
+  for (size_t i = 0; i < 60; ++i) {
+    // Derive most sets from the previous one so that they share subtrees.
+    if (i % 10 == 0) {
+      current.clear();
+    }
+    if (i % 3 == 0) {
+      current.remove(element(generator));
+    } else {
+      current.insert(element(generator));
+    }
+    sets.push_back(current);
+  }
+  auto matrix = Set::subset_matrix(sets);
+  for (size_t i = 0; i < sets.size(); ++i) {
+    auto row = sets[i].is_subset_of_each(sets);
+    for (size_t j = 0; j < sets.size(); ++j) {
+      bool expected = sets[i].is_subset_of(sets[j]);
+      EXPECT_EQ(expected, matrix[i][j]) << i << " " << j;
+      EXPECT_EQ(expected, row[j]) << i << " " << j;
+    }
+  }
+}
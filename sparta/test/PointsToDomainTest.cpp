@@ -0,0 +1,86 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "PointsToDomain.h"
+
+#include <gtest/gtest.h>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using PointsTo = PointsToDomain<uint32_t, uint32_t>;
+using Sites = PointsTo::Sites;
+using PointsToSet = PointsTo::PointsToSet;
+
+INSTANTIATE_TYPED_TEST_CASE_P(PointsToDomain,
+                              AbstractDomainPropertyTest,
+                              PointsTo);
+
+template <>
+std::vector<PointsTo>
+AbstractDomainPropertyTest<PointsTo>::non_extremal_values() {
+  PointsTo g1;
+  g1.strong_update(1, Sites{10});
+  PointsTo g2;
+  g2.strong_update(1, Sites{10, 11}).strong_update(2, Sites{12});
+  PointsTo g3;
+  g3.strong_update(2, Sites{10}).strong_update(3, Sites{11});
+  return {PointsTo(), g1, g2, g3};
+}
+
+TEST(PointsToDomainTest, updates) {
+  PointsTo graph;
+  EXPECT_TRUE(graph.points_to(1).elements().empty());
+
+  // x1 = new 10; x2 = x1; x1 = new 11
+  graph.strong_update(1, Sites{10}).assign(2, 1).strong_update(1, Sites{11});
+  EXPECT_EQ(PointsToSet({11}), graph.points_to(1));
+  EXPECT_EQ(PointsToSet({10}), graph.points_to(2));
+  EXPECT_FALSE(graph.may_alias(1, 2));
+
+  // A field of a summary object is only updated weakly.
+  graph.weak_update(3, Sites{11}).weak_update(3, Sites{10});
+  EXPECT_EQ(PointsToSet({10, 11}), graph.points_to(3));
+  EXPECT_TRUE(graph.may_alias(1, 3));
+  EXPECT_TRUE(graph.may_alias(2, 3));
+  EXPECT_FALSE(graph.may_alias(1, 4));
+
+  graph.forget(3);
+  EXPECT_TRUE(graph.points_to(3).elements().empty());
+  EXPECT_EQ(2, graph.edges().size());
+
+  // Updates have no effect on the extremal values.
+  EXPECT_TRUE(PointsTo::top().strong_update(1, Sites{10}).is_top());
+  EXPECT_TRUE(PointsTo::bottom().weak_update(1, Sites{10}).is_bottom());
+  EXPECT_TRUE(PointsTo::top().points_to(1).is_top());
+  EXPECT_TRUE(PointsTo::bottom().points_to(1).is_bottom());
+  EXPECT_TRUE(PointsTo::top().may_alias(1, 2));
+  EXPECT_FALSE(PointsTo::bottom().may_alias(1, 2));
+}
+
+TEST(PointsToDomainTest, latticeOperations) {
+  PointsTo g1;
+  g1.strong_update(1, Sites{10}).strong_update(2, Sites{11});
+  PointsTo g2;
+  g2.strong_update(1, Sites{12}).strong_update(3, Sites{11});
+
+  PointsTo join = g1.join(g2);
+  EXPECT_EQ(PointsToSet({10, 12}), join.points_to(1));
+  EXPECT_EQ(PointsToSet({11}), join.points_to(2));
+  EXPECT_EQ(PointsToSet({11}), join.points_to(3));
+  EXPECT_TRUE(g1.leq(join));
+  EXPECT_TRUE(g2.leq(join));
+  EXPECT_FALSE(join.leq(g1));
+
+  PointsTo meet = join.meet(g1);
+  EXPECT_EQ(g1, meet);
+  EXPECT_TRUE(g1.meet(g2).edges().empty());
+  EXPECT_EQ(PointsTo(), g1.meet(g2));
+  EXPECT_TRUE(PointsTo().leq(g1));
+  EXPECT_FALSE(PointsTo().is_bottom());
+}
@@ -0,0 +1,80 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "PredicateDomain.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using Domain = PredicateDomain<uint32_t>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(PredicateDomain,
+                              AbstractDomainPropertyTest,
+                              Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  return {Domain({{1, true}}), Domain({{1, false}, {2, true}})};
+}
+
+namespace {
+
+constexpr uint32_t kFileIsOpen = 1;
+constexpr uint32_t kXIsPositive = 2;
+
+} // namespace
+
+TEST(PredicateDomainTest, assume) {
+  Domain state;
+  EXPECT_TRUE(state.get(kFileIsOpen).is_top());
+
+  // if (file.isOpen()) { ... } else { ... }
+  Domain then_branch = Domain(state).assume(kFileIsOpen, true);
+  Domain else_branch = Domain(state).assume(kFileIsOpen, false);
+  EXPECT_EQ(BooleanDomain(true), then_branch.get(kFileIsOpen));
+  EXPECT_EQ(BooleanDomain(false), else_branch.get(kFileIsOpen));
+  EXPECT_TRUE(then_branch.join(else_branch).is_top());
+
+  // An infeasible branch.
+  EXPECT_TRUE(Domain(then_branch).assume(kFileIsOpen, false).is_bottom());
+  EXPECT_EQ(then_branch, Domain(then_branch).assume(kFileIsOpen, true));
+  EXPECT_TRUE(Domain::bottom().assume(kFileIsOpen, true).is_bottom());
+  EXPECT_TRUE(Domain::bottom().get(kFileIsOpen).is_bottom());
+}
+
+TEST(PredicateDomainTest, setAndForget) {
+  Domain state({{kFileIsOpen, true}, {kXIsPositive, false}});
+  state.set(kFileIsOpen, false);
+  EXPECT_EQ(BooleanDomain(false), state.get(kFileIsOpen));
+  state.forget(kXIsPositive);
+  EXPECT_TRUE(state.get(kXIsPositive).is_top());
+  EXPECT_EQ(Domain({{kFileIsOpen, false}}), state);
+}
+
+TEST(PredicateDomainTest, assertions) {
+  Domain state({{kFileIsOpen, true}});
+  EXPECT_EQ(BooleanDomain(true), state.assert_that(kFileIsOpen, true));
+  EXPECT_EQ(BooleanDomain(false), state.assert_that(kFileIsOpen, false));
+  EXPECT_TRUE(state.is_bottom());
+
+  // The assertion may fail, but it holds afterwards.
+  Domain unknown;
+  EXPECT_TRUE(unknown.assert_that(kXIsPositive, true).is_top());
+  EXPECT_EQ(BooleanDomain(true), unknown.assert_that(kXIsPositive, true));
+
+  EXPECT_TRUE(Domain::bottom().assert_that(kXIsPositive, true).is_bottom());
+}
+
+TEST(PredicateDomainTest, prettyPrinting) {
+  std::ostringstream out;
+  out << Domain::bottom() << " " << Domain({{kFileIsOpen, true}});
+  EXPECT_EQ("_|_ [#1]{1 -> 1}", out.str());
+}
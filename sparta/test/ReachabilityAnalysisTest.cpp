@@ -0,0 +1,104 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "ReachabilityAnalysis.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+#include <unordered_set>
+#include <utility>
+#include <vector>
+
+#include "AbstractDomainPropertyTest.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+INSTANTIATE_TYPED_TEST_CASE_P(ReachabilityDomain,
+                              AbstractDomainPropertyTest,
+                              ReachabilityDomain);
+
+template <>
+std::vector<ReachabilityDomain>
+AbstractDomainPropertyTest<ReachabilityDomain>::non_extremal_values() {
+  // The lattice only has two elements.
+  return {};
+}
+
+namespace {
+
+/*
+ * A reachability analysis where some edges are known to be infeasible.
+ */
+class PrunedReachability final : public ReachabilityAnalysis<GraphInterface> {
+ public:
+  PrunedReachability(const Graph& graph,
+                     std::unordered_set<size_t> infeasible)
+      : ReachabilityAnalysis<GraphInterface>(graph),
+        m_infeasible(std::move(infeasible)) {}
+
+  bool is_feasible(const size_t& edge) const override {
+    return m_infeasible.count(edge) == 0;
+  }
+
+ private:
+  std::unordered_set<size_t> m_infeasible;
+};
+
+} // namespace
+
+TEST(ReachabilityAnalysisTest, domain) {
+  auto top = ReachabilityDomain::top();
+  auto bottom = ReachabilityDomain::bottom();
+  EXPECT_TRUE(ReachabilityDomain().is_top());
+  EXPECT_TRUE(bottom.leq(top));
+  EXPECT_FALSE(top.leq(bottom));
+  EXPECT_EQ(top, top.join(bottom));
+  EXPECT_EQ(bottom, top.meet(bottom));
+
+  std::ostringstream out;
+  out << top << " " << bottom;
+  EXPECT_EQ("T _|_", out.str());
+}
+
+TEST(ReachabilityAnalysisTest, reachableNodesAndEdges) {
+  //  0 -> 1 -> 2 -> 4
+  //  |    ^    |
+  //  v    +----+
+  //  3
+  //
+  // Node 5 is disconnected from the entry.
+  Graph graph;
+  auto e01 = graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  auto e21 = graph.add_edge(2, 1);
+  auto e24 = graph.add_edge(2, 4);
+  auto e03 = graph.add_edge(0, 3);
+  auto e53 = graph.add_edge(5, 3);
+
+  ReachabilityAnalysis<GraphInterface> reachability(graph);
+  reachability.run();
+  for (uint32_t node : {0, 1, 2, 3, 4}) {
+    EXPECT_TRUE(reachability.is_reachable(node)) << node;
+  }
+  EXPECT_FALSE(reachability.is_reachable(5));
+  EXPECT_TRUE(reachability.is_reachable_edge(e21));
+  EXPECT_FALSE(reachability.is_reachable_edge(e53));
+
+  // The branch to node 1 is never taken, hence only the entry and node 3
+  // are reachable.
+  PrunedReachability pruned(graph, {e01, e24});
+  pruned.run();
+  EXPECT_TRUE(pruned.is_reachable(0));
+  EXPECT_TRUE(pruned.is_reachable(3));
+  for (uint32_t node : {1, 2, 4, 5}) {
+    EXPECT_FALSE(pruned.is_reachable(node)) << node;
+  }
+  EXPECT_FALSE(pruned.is_reachable_edge(e01));
+  EXPECT_TRUE(pruned.is_reachable_edge(e03));
+  EXPECT_FALSE(pruned.is_reachable_edge(e21));
+}
@@ -0,0 +1,116 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "RegisterFileDomain.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+#include "IntervalDomain.h"
+
+using namespace sparta;
+
+using Interval = IntervalDomain<int64_t>;
+
+using Environment = RegisterFileDomain<Interval, 4>;
+
+static_assert(default_is_top<Environment>::value, "");
+
+INSTANTIATE_TYPED_TEST_CASE_P(RegisterFileDomain,
+                              AbstractDomainPropertyTest,
+                              Environment);
+
+template <>
+std::vector<Environment>
+AbstractDomainPropertyTest<Environment>::non_extremal_values() {
+  Environment e1({Interval::finite(0, 1), Interval::finite(2, 3)});
+  Environment e2;
+  e2.set(3, Interval::finite(1, 10));
+  Environment e3({Interval::finite(-1, 1), Interval::top(),
+                  Interval::finite(0, 0)});
+  Environment e4;
+  e4.set(0, Interval::finite(0, 0)).set(3, Interval::finite(5, 5));
+  return {e1, e2, e3, e4};
+}
+
+TEST(RegisterFileDomainTest, getAndSet) {
+  Environment env;
+  EXPECT_TRUE(env.is_top());
+  EXPECT_EQ(4, Environment::size());
+  EXPECT_TRUE(env.get(3).is_top());
+
+  env.set(2, Interval::finite(1, 2));
+  EXPECT_FALSE(env.is_top());
+  EXPECT_EQ(Interval::finite(1, 2), env.get(2));
+  EXPECT_TRUE(env.get(0).is_top());
+
+  env.update(2, [](Interval* value) { *value += 1; });
+  EXPECT_EQ(Interval::finite(2, 3), env.get(2));
+
+  env.set(2, Interval::top());
+  EXPECT_TRUE(env.is_top());
+  EXPECT_TRUE(env.equals(Environment::top()));
+
+  EXPECT_THROW(env.get(4), invalid_argument);
+  EXPECT_THROW(env.set(4, Interval::top()), invalid_argument);
+  EXPECT_THROW(Environment({Interval::top(), Interval::top(), Interval::top(),
+                            Interval::top(), Interval::top()}),
+               invalid_argument);
+
+  env.update(1, [](Interval* value) { value->set_to_bottom(); });
+  EXPECT_TRUE(env.is_bottom());
+  EXPECT_TRUE(env.get(0).is_bottom());
+  env.set(1, Interval::finite(0, 0));
+  EXPECT_TRUE(env.is_bottom());
+
+  std::ostringstream out;
+  out << Environment({Interval::finite(0, 0), Interval::top(),
+                      Interval::finite(1, 2)});
+  EXPECT_EQ("{0 -> [0, 0], 2 -> [1, 2]}", out.str());
+}
+
+TEST(RegisterFileDomainTest, latticeOperations) {
+  Environment e1({Interval::finite(0, 0), Interval::finite(1, 1),
+                  Interval::finite(2, 2)});
+  Environment e2({Interval::finite(5, 5), Interval::finite(1, 1)});
+
+  Environment join = e1.join(e2);
+  EXPECT_EQ(Interval::finite(0, 5), join.get(0));
+  EXPECT_EQ(Interval::finite(1, 1), join.get(1));
+  EXPECT_TRUE(join.get(2).is_top());
+  EXPECT_TRUE(e1.leq(join));
+  EXPECT_TRUE(e2.leq(join));
+
+  Environment e3;
+  e3.set(0, Interval::finite(0, 10)).set(3, Interval::finite(4, 4));
+  Environment meet = e1.meet(e3);
+  EXPECT_EQ(Interval::finite(0, 0), meet.get(0));
+  EXPECT_EQ(Interval::finite(2, 2), meet.get(2));
+  EXPECT_EQ(Interval::finite(4, 4), meet.get(3));
+  EXPECT_TRUE(meet.leq(e1));
+  EXPECT_TRUE(meet.leq(e3));
+
+  EXPECT_TRUE(e1.meet(e2).is_bottom());
+
+  Environment widened = e1.widening(e1.join(Environment(
+      {Interval::finite(0, 1), Interval::finite(1, 1),
+       Interval::finite(2, 2)})));
+  EXPECT_EQ(Interval::bounded_below(0), widened.get(0));
+  EXPECT_EQ(Interval::finite(2, 2), widened.get(2));
+}
+
+TEST(RegisterFileDomainTest, branchRefinement) {
+  // The refinements of IntervalDomain.h apply to any environment.
+  Environment env({Interval::finite(0, 10), Interval::finite(-5, 5),
+                   Interval::finite(5, 5)});
+  refine_lt(env, 0, 1);
+  EXPECT_EQ(Interval::finite(0, 4), env.get(0));
+  EXPECT_EQ(Interval::finite(1, 5), env.get(1));
+  refine_ge(env, 0, 2);
+  EXPECT_TRUE(env.is_bottom());
+}
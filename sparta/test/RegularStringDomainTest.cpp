@@ -0,0 +1,116 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "RegularStringDomain.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using Strings = RegularStringDomain<8>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(RegularStringDomain,
+                              AbstractDomainPropertyTest,
+                              Strings);
+
+template <>
+std::vector<Strings>
+AbstractDomainPropertyTest<Strings>::non_extremal_values() {
+  Strings s1("foo");
+  Strings s2("bar");
+  s2.join_with(Strings("baz"));
+  Strings s3("a");
+  s3.concatenate(Strings::top());
+  return {s1, s2, s3};
+}
+
+TEST(RegularStringDomainTest, constants) {
+  Strings s("ab");
+  EXPECT_TRUE(s.accepts("ab"));
+  EXPECT_FALSE(s.accepts("a"));
+  EXPECT_FALSE(s.accepts("abc"));
+  EXPECT_EQ(3, s.num_states());
+
+  Strings empty("");
+  EXPECT_TRUE(empty.accepts(""));
+  EXPECT_FALSE(empty.accepts("a"));
+  EXPECT_EQ(1, empty.num_states());
+
+  EXPECT_TRUE(Strings::top().accepts("anything"));
+  EXPECT_FALSE(Strings::bottom().accepts(""));
+}
+
+TEST(RegularStringDomainTest, joinAndMeet) {
+  Strings s1("abc");
+  s1.join_with(Strings("abd"));
+  Strings s2("abd");
+  s2.join_with(Strings("abc"));
+  // The automata are canonical.
+  EXPECT_EQ(s1, s2);
+  EXPECT_EQ(4, s1.num_states());
+  EXPECT_TRUE(s1.accepts("abc"));
+  EXPECT_TRUE(s1.accepts("abd"));
+  EXPECT_FALSE(s1.accepts("ab"));
+  EXPECT_TRUE(Strings("abc").leq(s1));
+  EXPECT_FALSE(s1.leq(Strings("abc")));
+
+  Strings s3("abd");
+  s3.join_with(Strings("x"));
+  EXPECT_EQ(Strings("abd"), s1.meet(s3));
+  EXPECT_TRUE(s1.meet(Strings("x")).is_bottom());
+
+  // The language of all strings is Top.
+  Strings any_prefix = Strings("").concatenate(Strings::top());
+  EXPECT_TRUE(any_prefix.is_top());
+}
+
+TEST(RegularStringDomainTest, concatenation) {
+  // ("a" | "bc") . T . "z"
+  Strings s("a");
+  s.join_with(Strings("bc"));
+  s.concatenate(Strings::top()).concatenate(Strings("z"));
+  EXPECT_TRUE(s.accepts("az"));
+  EXPECT_TRUE(s.accepts("bcxyz"));
+  EXPECT_TRUE(s.accepts("azzz"));
+  EXPECT_FALSE(s.accepts("bz"));
+  EXPECT_FALSE(s.accepts("a"));
+  EXPECT_FALSE(s.is_top());
+
+  Strings t("a");
+  t.concatenate(Strings::bottom());
+  EXPECT_TRUE(t.is_bottom());
+}
+
+TEST(RegularStringDomainTest, widening) {
+  // A loop appending "," and "x" at each iteration.
+  Strings s("x");
+  for (int i = 0; i < 10 && !s.is_top(); ++i) {
+    Strings next = s;
+    next.concatenate(Strings(",x"));
+    s.widen_with(next);
+  }
+  EXPECT_TRUE(s.is_top());
+
+  // The widening is the join while the automaton stays small.
+  Strings small("x");
+  small.widen_with(Strings("x,x"));
+  Strings expected("x");
+  expected.join_with(Strings("x,x"));
+  EXPECT_EQ(expected, small);
+}
+
+TEST(RegularStringDomainTest, printing) {
+  std::ostringstream out;
+  Strings s("a");
+  s.join_with(Strings("b\n"));
+  out << s << " " << Strings::top() << " " << Strings::bottom();
+  EXPECT_EQ("{0: a->1 b->2, 1*:, 2: \\x0a->1} T _|_", out.str());
+}
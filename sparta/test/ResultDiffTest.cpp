@@ -0,0 +1,153 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "ResultDiff.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+#include <utility>
+#include <vector>
+
+#include "HashedAbstractEnvironment.h"
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+/*
+ * Two versions of the transformers of the same analysis. The new version is
+ * more precise at node 1, incomparable at node 2 and less precise at node 3.
+ */
+class Analyzer final
+    : public MonotonicFixpointIterator<GraphInterface, Interval> {
+ public:
+  Analyzer(const Graph& graph, bool new_version)
+      : MonotonicFixpointIterator(graph), m_new_version(new_version) {}
+
+  void analyze_node(const uint32_t& node, Interval* state) const override {
+    switch (node) {
+    case 0: {
+      *state = Interval::finite(1, 1);
+      break;
+    }
+    case 1: {
+      *state +=
+          m_new_version ? Interval::finite(0, 5) : Interval::finite(0, 10);
+      break;
+    }
+    case 2: {
+      if (m_new_version) {
+        *state -= 3;
+      }
+      break;
+    }
+    case 3: {
+      *state = m_new_version ? Interval::top() : Interval::finite(0, 0);
+      break;
+    }
+    }
+  }
+
+  Interval analyze_edge(const size_t&, const Interval& state) const override {
+    return state;
+  }
+
+ private:
+  bool m_new_version;
+};
+
+} // namespace
+
+TEST(ResultDiffTest, comparePrecision) {
+  EXPECT_EQ(PrecisionChange::Unchanged,
+            compare_precision(Interval::finite(0, 1), Interval::finite(0, 1)));
+  EXPECT_EQ(PrecisionChange::Improved,
+            compare_precision(Interval::finite(0, 2), Interval::finite(0, 1)));
+  EXPECT_EQ(PrecisionChange::Regressed,
+            compare_precision(Interval::finite(0, 1), Interval::top()));
+  EXPECT_EQ(PrecisionChange::Incomparable,
+            compare_precision(Interval::finite(0, 1), Interval::finite(2, 3)));
+}
+
+TEST(ResultDiffTest, environments) {
+  using Environment = HashedAbstractEnvironment<std::string, Interval>;
+  Environment before{{"x", Interval::finite(0, 1)},
+                     {"y", Interval::finite(0, 10)},
+                     {"z", Interval::finite(5, 5)}};
+  Environment after{{"x", Interval::finite(0, 1)},
+                    {"y", Interval::finite(0, 5)},
+                    {"w", Interval::finite(0, 0)}};
+  EXPECT_THAT(diff_environments(before, after),
+              ::testing::UnorderedElementsAre(
+                  std::make_pair("y", PrecisionChange::Improved),
+                  std::make_pair("z", PrecisionChange::Regressed),
+                  std::make_pair("w", PrecisionChange::Improved)));
+  EXPECT_TRUE(diff_environments(before, before).empty());
+  EXPECT_TRUE(diff_environments(before, Environment::top()).empty());
+  EXPECT_TRUE(diff_environments(Environment::bottom(), after).empty());
+}
+
+TEST(ResultDiffTest, fixpoints) {
+  //  0 -> 1 -> 2 -> 3
+  //  |         ^
+  //  +---------+
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(0, 2);
+  graph.add_edge(2, 3);
+
+  Analyzer before(graph, /* new_version */ false);
+  Analyzer after(graph, /* new_version */ true);
+  before.run(Interval::top());
+  after.run(Interval::top());
+
+  std::vector<uint32_t> nodes{0, 1, 2, 3};
+  auto diff = diff_results(before, after, nodes);
+  EXPECT_TRUE(diff.has_regressions());
+  // The entry and exit of node 0 and the entry of node 1 are unchanged.
+  EXPECT_EQ(3, diff.count(PrecisionChange::Unchanged));
+  EXPECT_EQ(2, diff.count(PrecisionChange::Improved));
+  EXPECT_EQ(1, diff.count(PrecisionChange::Regressed));
+  EXPECT_EQ(2, diff.count(PrecisionChange::Incomparable));
+
+  const auto& changes = diff.changes();
+  ASSERT_EQ(5, changes.size());
+  EXPECT_EQ(1, changes[0].node);
+  EXPECT_FALSE(changes[0].at_entry);
+  EXPECT_EQ(PrecisionChange::Improved, changes[0].change);
+  EXPECT_EQ(Interval::finite(1, 11), changes[0].before);
+  EXPECT_EQ(Interval::finite(1, 6), changes[0].after);
+  EXPECT_EQ(2, changes[1].node);
+  EXPECT_TRUE(changes[1].at_entry);
+  EXPECT_EQ(PrecisionChange::Improved, changes[1].change);
+  EXPECT_EQ(2, changes[2].node);
+  EXPECT_FALSE(changes[2].at_entry);
+  EXPECT_EQ(PrecisionChange::Incomparable, changes[2].change);
+  EXPECT_EQ(Interval::finite(-2, 3), changes[2].after);
+  EXPECT_EQ(3, changes[3].node);
+  EXPECT_TRUE(changes[3].at_entry);
+  EXPECT_EQ(PrecisionChange::Incomparable, changes[3].change);
+  EXPECT_EQ(3, changes[4].node);
+  EXPECT_FALSE(changes[4].at_entry);
+  EXPECT_EQ(PrecisionChange::Regressed, changes[4].change);
+
+  std::ostringstream out;
+  out << diff;
+  EXPECT_THAT(out.str(),
+              ::testing::HasSubstr("regressed at exit of 3: [0, 0] -> "));
+
+  // The new version is at least as precise as itself.
+  EXPECT_FALSE(diff_results(after, after, nodes).has_regressions());
+}
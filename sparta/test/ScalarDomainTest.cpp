@@ -0,0 +1,180 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "ScalarDomain.h"
+
+#include <cstdint>
+#include <gtest/gtest.h>
+
+#include "AbstractDomainPropertyTest.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+
+using namespace sparta;
+
+using Domain = ScalarDomain<int32_t>;
+using Interval = Domain::Interval;
+using Congruence = Domain::Congruence;
+
+INSTANTIATE_TYPED_TEST_CASE_P(ScalarDomain,
+                              AbstractDomainPropertyTest,
+                              Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  return {Domain::constant(-3),
+          Domain::constant(6),
+          Domain::finite(0, 10),
+          Domain::finite(0, 20, 4, 2),
+          Domain(Interval::bounded_below(1), Congruence::congruence(3, 1)),
+          Domain(Congruence::congruence(2, 0))};
+}
+
+TEST(ScalarDomainTest, reduction) {
+  EXPECT_TRUE(Domain().is_top());
+  EXPECT_EQ(Interval::finite(2, 10), Domain::finite(1, 10, 4, 2).interval());
+  EXPECT_EQ(Interval::finite(2, 6), Domain::finite(1, 9, 4, 2).interval());
+  EXPECT_EQ(Interval::finite(-7, -3),
+            Domain::finite(-7, -1, 4, 1).interval());
+  EXPECT_EQ(Interval::bounded_below(2),
+            Domain(Interval::bounded_below(1), Congruence::congruence(4, 2))
+                .interval());
+  EXPECT_TRUE(Domain::finite(3, 5, 4, 2).is_bottom());
+
+  // An interval with a single value is a constant, and conversely.
+  auto x = Domain::finite(5, 7, 4, 2);
+  EXPECT_EQ(Congruence::constant(6), x.congruence());
+  EXPECT_EQ(6, *x.get_constant());
+  EXPECT_EQ(Domain::constant(3), Domain(Interval::finite(3, 3)));
+  EXPECT_EQ(Interval::finite(3, 3), Domain::constant(3).interval());
+  EXPECT_TRUE(
+      Domain(Interval::finite(0, 10), Congruence::constant(20)).is_bottom());
+  EXPECT_FALSE(Domain::finite(0, 10, 4, 2).get_constant());
+
+  // The infinite bounds are never moved.
+  const int32_t max = Interval::MAX;
+  EXPECT_EQ(Interval::bounded_below(max - 2),
+            Domain(Interval::bounded_below(max - 2),
+                   Congruence::congruence(4, 0))
+                .interval());
+  EXPECT_TRUE(Domain::finite(max - 2, max - 1, 4, 0).is_bottom());
+  EXPECT_EQ(Interval::high(),
+            Domain(Interval::high(), Congruence::congruence(4, 0)).interval());
+}
+
+TEST(ScalarDomainTest, lattice) {
+  // The meet is reduced.
+  auto x = Domain(Interval::finite(1, 10))
+               .meet(Domain(Congruence::congruence(3, 0)));
+  EXPECT_EQ(Domain::finite(3, 9, 3, 0), x);
+  EXPECT_EQ(Interval::finite(3, 9), x.interval());
+  EXPECT_TRUE(Domain::finite(0, 20, 4, 2)
+                  .meet(Domain::finite(0, 20, 4, 0))
+                  .is_bottom());
+  EXPECT_EQ(Domain::constant(6),
+            Domain::finite(0, 20, 4, 2).meet(Domain::finite(5, 15, 3, 0)));
+
+  auto y = Domain::constant(2).join(Domain::constant(7));
+  EXPECT_EQ(Interval::finite(2, 7), y.interval());
+  EXPECT_EQ(Congruence::congruence(5, 2), y.congruence());
+
+  // An index that starts at 0 and is incremented by 4 in a loop.
+  auto i = Domain::constant(0);
+  auto next = i.widening(i + Domain::constant(4));
+  EXPECT_EQ(Domain(Interval::bounded_below(0), Congruence::congruence(4, 0)),
+            next);
+  EXPECT_EQ(next, next.widening(next + Domain::constant(4)));
+}
+
+TEST(ScalarDomainTest, arithmetic) {
+  const auto x = Domain::finite(0, 8, 4, 0);
+  EXPECT_EQ(Domain::finite(2, 10, 4, 2), x + Domain::constant(2));
+  EXPECT_EQ(Domain::finite(-8, 0, 4, 0), -x);
+  EXPECT_EQ(Domain::finite(0, 24, 12, 0), x * Domain::constant(3));
+  EXPECT_EQ(Domain::finite(-8, 8, 4, 0), x - x);
+  EXPECT_TRUE((x + Domain::bottom()).is_bottom());
+
+  // The operands are reduced before the interval arithmetic.
+  EXPECT_EQ(Interval::finite(0, 8),
+            (Domain::finite(0, 7, 4, 0) + Domain::finite(0, 7, 4, 0))
+                .interval());
+}
+
+TEST(ScalarDomainTest, operations) {
+  using Ops = ArithmeticDomainOps<Domain>;
+  using CompareOps = CompareDomainOps<Domain>;
+  const auto x = Domain::finite(0, 8, 4, 0);
+
+  EXPECT_EQ(Domain::constant(3), Ops::literal(3));
+  EXPECT_EQ(Domain::constant(4),
+            Ops::div(Domain::constant(9), Domain::constant(2)));
+  EXPECT_EQ(Domain::finite(0, 32, 16, 0), Ops::shl(x, Domain::constant(2)));
+  EXPECT_EQ(Domain::finite(0, 4), Ops::shr(x, Domain::constant(1)));
+  EXPECT_EQ(Domain::constant(2),
+            Ops::bit_and(Domain::constant(6), Domain::constant(3)));
+  EXPECT_TRUE(Ops::div(x, Domain::constant(0)).is_bottom());
+
+  // Either component may decide a comparison.
+  EXPECT_EQ(BooleanDomain(false),
+            CompareOps::eq(x, Domain::finite(0, 8, 4, 1)));
+  EXPECT_EQ(BooleanDomain(true),
+            CompareOps::lt(Domain::finite(0, 4), Domain::finite(10, 20)));
+  EXPECT_EQ(BooleanDomain(true), CompareOps::ne(x, Domain::constant(3)));
+  EXPECT_TRUE(CompareOps::lt(x, Domain::constant(2)).is_top());
+  EXPECT_TRUE(CompareOps::eq(x, Domain::bottom()).is_bottom());
+}
+
+TEST(ScalarDomainTest, refinement) {
+  {
+    // [2, 18] and 4Z+2 < 10
+    auto x = Domain::finite(0, 20, 4, 2), y = Domain::constant(10);
+    refine_lt(&x, &y);
+    EXPECT_EQ(Domain::finite(2, 6, 4, 2), x);
+    EXPECT_EQ(Domain::constant(10), y);
+  }
+  {
+    // [2, 6] and 4Z+2 != 6
+    auto x = Domain::finite(0, 8, 4, 2), y = Domain::constant(6);
+    refine_ne(&x, &y);
+    EXPECT_EQ(Domain::constant(2), x);
+  }
+  {
+    auto x = Domain::finite(0, 20, 4, 2), y = Domain::finite(5, 15, 3, 0);
+    refine_eq(&x, &y);
+    EXPECT_EQ(Domain::constant(6), x);
+    EXPECT_EQ(Domain::constant(6), y);
+  }
+  {
+    // [5, 10] and 4Z+2 <= [0, 6] and 4Z+1 is infeasible.
+    auto x = Domain::finite(5, 10, 4, 2), y = Domain::finite(0, 6, 4, 1);
+    refine_le(&x, &y);
+    EXPECT_TRUE(x.is_bottom());
+    EXPECT_TRUE(y.is_bottom());
+  }
+}
+
+TEST(ScalarDomainTest, environmentRefinement) {
+  using Environment = PatriciaTreeMapAbstractEnvironment<uint32_t, Domain>;
+  Environment env(
+      {{1, Domain::finite(0, 20, 4, 2)}, {2, Domain::constant(10)}});
+
+  Environment lt = env;
+  refine_lt(lt, 1u, 2u);
+  EXPECT_EQ(Domain::finite(2, 6, 4, 2), lt.get(1));
+
+  Environment ge = env;
+  refine_ge(ge, 1u, 2u);
+  EXPECT_EQ(Domain::finite(10, 18, 4, 2), ge.get(1));
+
+  Environment eq = env;
+  refine_eq(eq, 1u, 2u);
+  EXPECT_EQ(Domain::constant(10), eq.get(1));
+
+  Environment infeasible = env;
+  infeasible.set(2, Domain::constant(12));
+  refine_eq(infeasible, 1u, 2u);
+  EXPECT_TRUE(infeasible.is_bottom());
+}
@@ -178,6 +178,14 @@ TYPED_TEST(UInt32SetTest, basicOperations) {
   TypeParam d43 = t4.get_difference_with(t3);
   EXPECT_THAT(d43,
               ::testing::UnorderedElementsAre(0, 1, 5, 101, 8137, 1234567));
+
+  // Removes consecutive elements.
+  std::vector<uint32_t> elements5 = {0, 1, 2};
+  TypeParam d45 = t4;
+  d45.difference_with(TypeParam(elements5.begin(), elements5.end()));
+  EXPECT_THAT(d45,
+              ::testing::UnorderedElementsAre(
+                  5, 101, 4096, 8137, 1234567, bigint));
 }
 
 TYPED_TEST(UInt32SetTest, robustness) {
@@ -5,6 +5,9 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+// All set operations check that the underlying vectors remain sorted.
+#define SPARTA_CHECK_INVARIANTS
+
 #include <gmock/gmock.h>
 
 #include "SmallSortedSetAbstractDomain.h"
@@ -29,6 +32,15 @@ TEST_F(SmallSortedSetAbstractDomainTest, constructor) {
   EXPECT_TRUE((Domain{1, 2, 3, 4, 5}).is_top());
 }
 
+TEST_F(SmallSortedSetAbstractDomainTest, checkedConstructor) {
+  auto set = Set::try_from_sorted({1, 2, 4});
+  ASSERT_TRUE(set);
+  EXPECT_EQ(*set, Set({1, 2, 4}));
+  EXPECT_TRUE(Set::try_from_sorted({}));
+  EXPECT_FALSE(Set::try_from_sorted({2, 1}));
+  EXPECT_FALSE(Set::try_from_sorted({1, 1, 2}));
+}
+
 TEST_F(SmallSortedSetAbstractDomainTest, leq) {
   EXPECT_TRUE(Domain::bottom().leq(Domain::bottom()));
   EXPECT_TRUE(Domain::bottom().leq(Domain()));
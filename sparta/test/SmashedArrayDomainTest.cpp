@@ -0,0 +1,115 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "SmashedArrayDomain.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+#include <vector>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using Interval = IntervalDomain<int32_t>;
+using Array = SmashedArrayDomain<Interval>;
+using Length = Array::Length;
+
+INSTANTIATE_TYPED_TEST_CASE_P(SmashedArrayDomain,
+                              AbstractDomainPropertyTest,
+                              Array);
+
+template <>
+std::vector<Array> AbstractDomainPropertyTest<Array>::non_extremal_values() {
+  return {Array::empty(),
+          Array(Interval::finite(0, 1), Length::finite(1, 3)),
+          Array(Interval::finite(-1, 5), Length::finite(0, 10)),
+          Array(Interval::top(), Length::bounded_below(2))};
+}
+
+TEST(SmashedArrayDomainTest, normalization) {
+  EXPECT_TRUE(Array().is_top());
+  EXPECT_TRUE(Array(Interval::top(), Length::top()).is_top());
+  EXPECT_EQ(Length::finite(0, 4),
+            Array(Interval::top(), Length::finite(-2, 4)).length());
+  EXPECT_TRUE(Array(Interval::top(), Length::finite(-2, -1)).is_bottom());
+
+  // The smashed value of an empty array is Bottom.
+  EXPECT_EQ(Array::empty(),
+            Array(Interval::finite(0, 1), Length::finite(0, 0)));
+  EXPECT_EQ(Array::empty(), Array(Interval::bottom(), Length::finite(0, 3)));
+  EXPECT_TRUE(Array(Interval::bottom(), Length::finite(1, 3)).is_bottom());
+  EXPECT_TRUE(Array::empty().read_any().is_bottom());
+}
+
+TEST(SmashedArrayDomainTest, readsAndWrites) {
+  // int a[n] = {0}, with 1 <= n <= 5.
+  Array a(Interval::finite(0, 0), Length::finite(1, 5));
+  a.write_any(Interval::finite(3, 3));
+  EXPECT_EQ(Interval::finite(0, 3), a.read_any());
+  EXPECT_EQ(Length::finite(1, 5), a.length());
+
+  // A write to an array that may be empty proves that it isn't.
+  Array b(Interval::finite(0, 0), Length::finite(0, 5));
+  b.write_any(Interval::finite(7, 7));
+  EXPECT_EQ(Length::finite(1, 5), b.length());
+  EXPECT_EQ(Interval::finite(0, 7), b.read_any());
+
+  // The update of an array of one element is strong.
+  Array c(Interval::finite(0, 0), Length::finite(0, 1));
+  c.write_any(Interval::finite(7, 7));
+  EXPECT_EQ(Interval::finite(7, 7), c.read_any());
+
+  Array empty = Array::empty();
+  empty.write_any(Interval::finite(1, 1));
+  EXPECT_TRUE(empty.is_bottom());
+
+  Array d = a;
+  d.write_any(Interval::bottom());
+  EXPECT_TRUE(d.is_bottom());
+}
+
+TEST(SmashedArrayDomainTest, append) {
+  Array a = Array::empty();
+  a.append(Interval::finite(1, 1));
+  EXPECT_EQ(Array(Interval::finite(1, 1), Length::finite(1, 1)), a);
+  a.append(Interval::finite(4, 4));
+  EXPECT_EQ(Array(Interval::finite(1, 4), Length::finite(2, 2)), a);
+
+  // Appending in a loop converges with the widening.
+  Array loop = Array::empty();
+  for (int i = 0; i < 3; ++i) {
+    Array next = loop;
+    next.append(Interval::finite(0, 0));
+    loop.widen_with(next);
+  }
+  EXPECT_EQ(Array(Interval::finite(0, 0), Length::bounded_below(0)), loop);
+  Array next = loop;
+  next.append(Interval::finite(0, 0));
+  EXPECT_TRUE(next.leq(loop));
+}
+
+TEST(SmashedArrayDomainTest, latticeOperations) {
+  Array a(Interval::finite(0, 1), Length::finite(1, 3));
+  Array b(Interval::finite(5, 6), Length::finite(2, 4));
+  EXPECT_EQ(Array(Interval::finite(0, 6), Length::finite(1, 4)), a.join(b));
+  // Only the empty array has all its elements in both [0, 1] and [5, 6].
+  EXPECT_TRUE(a.meet(b).is_bottom());
+  EXPECT_EQ(Array::empty(),
+            Array(Interval::finite(0, 1), Length::finite(0, 3))
+                .meet(Array(Interval::finite(5, 6), Length::finite(0, 4))));
+  EXPECT_TRUE(Array::empty().leq(
+      Array(Interval::finite(0, 1), Length::finite(0, 3))));
+  EXPECT_FALSE(Array::empty().leq(a));
+}
+
+TEST(SmashedArrayDomainTest, printing) {
+  std::ostringstream out;
+  out << Array(Interval::finite(0, 1), Length::finite(1, 3)) << " "
+      << Array::empty() << " " << Array::top() << " " << Array::bottom();
+  EXPECT_EQ("{[0, 1]}^[1, 3] {_|_}^[0, 0] T _|_", out.str());
+}
@@ -0,0 +1,129 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "SsaAbstractEnvironment.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+#include "IntervalDomain.h"
+
+using namespace sparta;
+
+using Interval = IntervalDomain<int>;
+
+using Environment = SsaAbstractEnvironment<uint32_t, Interval>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(SsaAbstractEnvironment,
+                              AbstractDomainPropertyTest,
+                              Environment);
+
+template <>
+std::vector<Environment>
+AbstractDomainPropertyTest<Environment>::non_extremal_values() {
+  // In SSA form, a variable that is not bound by a phi node has the same value
+  // in all environments.
+  Environment e1({{1, Interval::finite(0, 1)}, {2, Interval::finite(2, 4)}});
+  Environment e2({{1, Interval::finite(0, 1)}, {3, Interval::finite(-5, 5)}});
+  Environment e3({{1, Interval::finite(0, 1)}});
+  e3.set_phi(4, Interval::finite(1, 1));
+  Environment e4({{1, Interval::finite(0, 1)}});
+  e4.set_phi(4, Interval::finite(3, 3));
+  return {Environment(), e1, e2, e3, e4};
+}
+
+TEST(SsaAbstractEnvironmentTest, basicOperations) {
+  Environment env;
+  EXPECT_FALSE(env.is_bottom());
+  EXPECT_FALSE(env.is_top());
+  EXPECT_EQ(0, env.size());
+  EXPECT_TRUE(env.get(1).is_bottom());
+
+  env.set(1, Interval::finite(0, 1));
+  EXPECT_EQ(Interval::finite(0, 1), env.get(1));
+  EXPECT_EQ(1, env.size());
+  EXPECT_TRUE(env.phis().empty());
+
+  env.set_phi(2, Interval::finite(2, 3));
+  EXPECT_EQ(Interval::finite(2, 3), env.get(2));
+  EXPECT_EQ(2, env.size());
+  EXPECT_TRUE(env.phis().contains(2));
+  env.clear_phis();
+  EXPECT_TRUE(env.phis().empty());
+  EXPECT_EQ(2, env.size());
+
+  Environment bottom = Environment::bottom();
+  bottom.set(1, Interval::finite(0, 1));
+  EXPECT_TRUE(bottom.is_bottom());
+
+  Environment top = Environment::top();
+  top.set(1, Interval::finite(0, 1));
+  EXPECT_TRUE(top.is_top());
+  EXPECT_TRUE(top.get(1).is_top());
+
+  std::ostringstream out;
+  out << Environment({{1, Interval::finite(0, 1)}});
+  EXPECT_EQ("[#1]{1 -> [0, 1]}", out.str());
+}
+
+TEST(SsaAbstractEnvironmentTest, phiAwareJoin) {
+  // x1 = [0, 10]
+  // if (...) { x2 = 1 } else { x3 = 2 }
+  // x4 = phi(x2, x3)
+  Environment dominator({{1, Interval::finite(0, 10)}});
+  Environment then_branch = dominator;
+  then_branch.set(2, Interval::finite(1, 1));
+  Environment else_branch = dominator;
+  else_branch.set(3, Interval::finite(2, 2));
+
+  // Edges to the merge point.
+  then_branch.set_phi(4, then_branch.get(2));
+  else_branch.set_phi(4, else_branch.get(3));
+
+  Environment merge = Environment::bottom();
+  merge.join_with(then_branch);
+  merge.join_with(else_branch);
+  EXPECT_EQ(Interval::finite(0, 10), merge.get(1));
+  EXPECT_EQ(Interval::finite(1, 1), merge.get(2));
+  EXPECT_EQ(Interval::finite(2, 2), merge.get(3));
+  EXPECT_EQ(Interval::finite(1, 2), merge.get(4));
+  EXPECT_TRUE(merge.phis().contains(4));
+  EXPECT_EQ(1, merge.phis().size());
+
+  // The join is an upper bound of both incoming environments.
+  EXPECT_TRUE(then_branch.leq(merge));
+  EXPECT_TRUE(else_branch.leq(merge));
+
+  // The phi markers are not part of the abstract value.
+  Environment cleared = merge;
+  cleared.clear_phis();
+  EXPECT_TRUE(cleared.equals(merge));
+}
+
+TEST(SsaAbstractEnvironmentTest, loop) {
+  // x1 = 0
+  // loop: x2 = phi(x1, x3); x3 = x2 + 1; goto loop
+  Environment preheader({{1, Interval::finite(0, 0)}});
+  preheader.set_phi(2, preheader.get(1));
+
+  Environment head = preheader;
+  for (size_t i = 0; i < 3; ++i) {
+    Environment body = head;
+    body.clear_phis();
+    body.set(3, body.get(2) + Interval::finite(1, 1));
+    // Back edge.
+    body.set_phi(2, body.get(3));
+    Environment new_head = preheader;
+    new_head.join_with(body);
+    if (new_head.leq(head)) {
+      break;
+    }
+    head.widen_with(new_head);
+  }
+  EXPECT_EQ(Interval::bounded_below(0), head.get(2));
+}
@@ -0,0 +1,187 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <atomic>
+#include <cstring>
+#include <gtest/gtest.h>
+#include <string>
+
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+/*
+ * Node 0 initializes a variable to 0, node 3 negates it and node 4 increments
+ * it.
+ */
+template <template <typename, typename, typename> class Iterator>
+class NegatingAnalyzer final
+    : public Iterator<GraphInterface, Interval, std::hash<uint32_t>> {
+ public:
+  using Base = Iterator<GraphInterface, Interval, std::hash<uint32_t>>;
+
+  using Base::Base;
+
+  void analyze_node(const uint32_t& node, Interval* state) const override {
+    if (node == 0) {
+      *state = Interval::finite(0, 0);
+    } else if (node == 3) {
+      *state = -*state;
+    } else if (node == 4) {
+      *state += 1;
+    }
+  }
+
+  Interval analyze_edge(const size_t&, const Interval& state) const override {
+    return state;
+  }
+};
+
+/*
+ * Intervals are serialized as the raw bytes of their bounds.
+ */
+struct Codec {
+  std::atomic<size_t> num_compressed{0};
+  std::atomic<size_t> num_decompressed{0};
+
+  std::string compress(const Interval& interval) {
+    ++num_compressed;
+    int32_t bounds[2];
+    if (interval.is_bottom()) {
+      return std::string();
+    }
+    bounds[0] = interval.lower_bound();
+    bounds[1] = interval.upper_bound();
+    return std::string(reinterpret_cast<const char*>(bounds), sizeof(bounds));
+  }
+
+  Interval decompress(const std::string& bytes) {
+    ++num_decompressed;
+    if (bytes.empty()) {
+      return Interval::bottom();
+    }
+    int32_t bounds[2];
+    std::memcpy(bounds, bytes.data(), sizeof(bounds));
+    if (bounds[0] == Interval::MIN) {
+      return bounds[1] == Interval::MAX ? Interval::top()
+                                        : Interval::bounded_above(bounds[1]);
+    }
+    return bounds[1] == Interval::MAX ? Interval::bounded_below(bounds[0])
+                                      : Interval::finite(bounds[0], bounds[1]);
+  }
+};
+
+template <typename Analyzer>
+void check_compression(const Graph& graph) {
+  Analyzer reference(graph);
+  reference.run(Interval::top());
+
+  Codec codec;
+  Analyzer analyzer(graph);
+  analyzer.set_state_compression(
+      [&codec](const Interval& x) { return codec.compress(x); },
+      [&codec](const std::string& x) { return codec.decompress(x); });
+  analyzer.run(Interval::top());
+  // The states of the nodes of the outer loop are compressed once, after
+  // the loop has stabilized, except for node 4, whose exit state is needed to
+  // compute the state of node 5.
+  EXPECT_EQ(6, codec.num_compressed);
+  EXPECT_EQ(0, codec.num_decompressed);
+  for (uint32_t node = 1; node <= 3; ++node) {
+    EXPECT_TRUE(analyzer.m_entry_states.at(node).is_bottom());
+    EXPECT_TRUE(analyzer.m_exit_states.at(node).is_bottom());
+  }
+  EXPECT_FALSE(analyzer.m_exit_states.at(4).is_bottom());
+  for (uint32_t node = 0; node <= 5; ++node) {
+    EXPECT_EQ(reference.get_entry_state_at(node),
+              analyzer.get_entry_state_at(node));
+    EXPECT_EQ(reference.get_exit_state_at(node),
+              analyzer.get_exit_state_at(node));
+  }
+  // Only the compressed states are decompressed.
+  EXPECT_EQ(6, codec.num_decompressed);
+
+  // The compressed states are discarded by the next run.
+  analyzer.set_state_compression(nullptr, nullptr);
+  analyzer.run(Interval::top());
+  EXPECT_EQ(reference.get_exit_state_at(4), analyzer.get_exit_state_at(4));
+  EXPECT_EQ(6, codec.num_compressed);
+  EXPECT_EQ(6, codec.num_decompressed);
+}
+
+/*
+ *  0 -> 1 -> 2 -> 3 -> 4 -> 5
+ *       ^    |    ^    |
+ *       +----+    +----+
+ */
+Graph make_consecutive_loops() {
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 1);
+  graph.add_edge(2, 3);
+  graph.add_edge(3, 4);
+  graph.add_edge(4, 3);
+  graph.add_edge(4, 5);
+  return graph;
+}
+
+template <typename Analyzer>
+void check_no_decompression_during_run(const Graph& graph) {
+  Analyzer reference(graph);
+  reference.run(Interval::top());
+
+  Codec codec;
+  Analyzer analyzer(graph);
+  analyzer.set_state_compression(
+      [&codec](const Interval& x) { return codec.compress(x); },
+      [&codec](const std::string& x) { return codec.decompress(x); });
+  analyzer.run(Interval::top());
+  // Only nodes 1 and 3 are compressed. The second loop is iterated several
+  // times, and each iteration reads the exit state of node 2, which is left
+  // uncompressed.
+  EXPECT_EQ(4, codec.num_compressed);
+  EXPECT_EQ(0, codec.num_decompressed);
+  for (uint32_t node = 0; node <= 5; ++node) {
+    EXPECT_EQ(reference.get_exit_state_at(node),
+              analyzer.get_exit_state_at(node));
+  }
+  EXPECT_EQ(2, codec.num_decompressed);
+}
+
+} // namespace
+
+TEST(StateCompressionTest, nestedLoops) {
+  Graph graph = make_nested_loops();
+  check_compression<NegatingAnalyzer<MonotonicFixpointIterator>>(graph);
+  check_compression<NegatingAnalyzer<WTOMonotonicFixpointIterator>>(graph);
+  check_compression<NegatingAnalyzer<ParallelMonotonicFixpointIterator>>(graph);
+}
+
+TEST(StateCompressionTest, consecutiveLoops) {
+  Graph graph = make_consecutive_loops();
+  check_no_decompression_during_run<
+      NegatingAnalyzer<MonotonicFixpointIterator>>(graph);
+  check_no_decompression_during_run<
+      NegatingAnalyzer<WTOMonotonicFixpointIterator>>(graph);
+  check_no_decompression_during_run<
+      NegatingAnalyzer<ParallelMonotonicFixpointIterator>>(graph);
+}
+
+TEST(StateCompressionTest, missingFunction) {
+  Graph graph = make_nested_loops();
+  NegatingAnalyzer<MonotonicFixpointIterator> analyzer(graph);
+  EXPECT_THROW(analyzer.set_state_compression(
+                   [](const Interval&) { return std::string(); }, nullptr),
+               invalid_argument);
+}
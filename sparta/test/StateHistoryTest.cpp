@@ -0,0 +1,102 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include <gtest/gtest.h>
+#include <type_traits>
+#include <utility>
+
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+template <typename Analyzer>
+void check_history(const Graph& graph) {
+  Analyzer analyzer(graph);
+  analyzer.set_state_history(8);
+  analyzer.run(Interval::top());
+
+  uint32_t n = analyzer.get_num_extrapolations(1);
+  ASSERT_GE(n, 2);
+  EXPECT_EQ(Interval::finite(0, 0),
+            *analyzer.get_entry_state_at_iteration(1, 0));
+  EXPECT_EQ(analyzer.get_entry_state_at(1),
+            *analyzer.get_entry_state_at_iteration(1, n));
+  for (uint32_t k = 0; k < n; ++k) {
+    auto before = analyzer.get_entry_state_at_iteration(1, k);
+    auto after = analyzer.get_entry_state_at_iteration(1, k + 1);
+    ASSERT_TRUE(before && after);
+    EXPECT_TRUE(before->leq(*after));
+  }
+  EXPECT_FALSE(analyzer.get_entry_state_at_iteration(1, n + 1));
+  // Only the heads of components are recorded.
+  EXPECT_FALSE(analyzer.get_entry_state_at_iteration(2, 0));
+  EXPECT_EQ(0, analyzer.get_num_extrapolations(2));
+
+  // The ring buffer only retains the last extrapolations.
+  analyzer.set_state_history(1);
+  analyzer.run(Interval::top());
+  EXPECT_EQ(n, analyzer.get_num_extrapolations(1));
+  EXPECT_FALSE(analyzer.get_entry_state_at_iteration(1, 0));
+  EXPECT_FALSE(analyzer.get_entry_state_at_iteration(1, n - 1));
+  EXPECT_EQ(analyzer.get_entry_state_at(1),
+            *analyzer.get_entry_state_at_iteration(1, n));
+
+  // The history of the previous run is discarded.
+  analyzer.set_state_history(0);
+  analyzer.run(Interval::top());
+  EXPECT_FALSE(analyzer.get_entry_state_at_iteration(1, n));
+  EXPECT_EQ(0, analyzer.get_num_extrapolations(1));
+
+  // Clearing the iterator discards the history as well.
+  analyzer.set_state_history(8);
+  analyzer.run(Interval::top());
+  ASSERT_EQ(n, analyzer.get_num_extrapolations(1));
+  analyzer.reset();
+  EXPECT_EQ(0, analyzer.get_num_extrapolations(1));
+  analyzer.run(Interval::top());
+  ASSERT_EQ(n, analyzer.get_num_extrapolations(1));
+  analyzer.clear_and_shrink();
+  EXPECT_FALSE(analyzer.get_entry_state_at_iteration(1, 0));
+  EXPECT_EQ(0, analyzer.get_num_extrapolations(1));
+}
+
+// The state history is only allocated while it is recorded, so that it
+// doesn't prevent the fixpoint iterator from being moved.
+static_assert(std::is_move_constructible<
+                  CounterAnalyzer<WTOMonotonicFixpointIterator>>::value,
+              "The WTO fixpoint iterator must be move-constructible");
+
+} // namespace
+
+TEST(StateHistoryTest, loop) {
+  Graph graph = make_loop();
+  check_history<CounterAnalyzer<MonotonicFixpointIterator>>(graph);
+  check_history<CounterAnalyzer<WTOMonotonicFixpointIterator>>(graph);
+  check_history<CounterAnalyzer<ParallelMonotonicFixpointIterator>>(graph);
+}
+
+TEST(StateHistoryTest, move) {
+  Graph graph = make_loop();
+  CounterAnalyzer<WTOMonotonicFixpointIterator> analyzer(graph);
+  analyzer.set_state_history(8);
+  analyzer.run(Interval::top());
+  uint32_t n = analyzer.get_num_extrapolations(1);
+  ASSERT_GE(n, 2);
+
+  CounterAnalyzer<WTOMonotonicFixpointIterator> moved(std::move(analyzer));
+  EXPECT_EQ(n, moved.get_num_extrapolations(1));
+  EXPECT_EQ(moved.get_entry_state_at(1),
+            *moved.get_entry_state_at_iteration(1, n));
+  moved.run(Interval::top());
+  EXPECT_EQ(n, moved.get_num_extrapolations(1));
+}
@@ -0,0 +1,172 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "StatementFixpointIterator.h"
+
+#include <gtest/gtest.h>
+#include <unordered_map>
+#include <vector>
+
+#include "IntervalDomain.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+/*
+ * A statement either assigns a constant to the variable or increments it.
+ */
+struct Statement {
+  bool is_assignment;
+  int32_t value;
+};
+
+/*
+ * A graph where each node is labeled with a list of statements.
+ */
+class Program final : public Graph {
+ public:
+  void set_statements(uint32_t node, std::vector<Statement> statements) {
+    m_statements[node] = std::move(statements);
+  }
+
+  const std::vector<Statement>& statements(uint32_t node) const {
+    static const std::vector<Statement> none;
+    auto it = m_statements.find(node);
+    return it == m_statements.end() ? none : it->second;
+  }
+
+ private:
+  std::unordered_map<uint32_t, std::vector<Statement>> m_statements;
+};
+
+template <template <typename, typename, typename> class Iterator>
+class Analyzer final
+    : public StatementFixpointIterator<Iterator, GraphInterface, Interval> {
+ public:
+  using Base = StatementFixpointIterator<Iterator, GraphInterface, Interval>;
+
+  explicit Analyzer(const Program& program)
+      : Base(program), m_program(program) {}
+
+  void analyze_statements(const uint32_t& node,
+                          Interval* state,
+                          StatementSink<Interval>* sink) const override {
+    size_t i = 0;
+    for (const auto& statement : m_program.statements(node)) {
+      if (statement.is_assignment) {
+        *state = Interval::finite(statement.value, statement.value);
+      } else {
+        *state += statement.value;
+      }
+      sink->emit(i++, *state);
+    }
+  }
+
+  Interval analyze_edge(const size_t&, const Interval& state) const override {
+    return state;
+  }
+
+ private:
+  const Program& m_program;
+};
+
+/*
+ *  0 -> 1 -> 2 -> 3
+ *       ^    |
+ *       +----+
+ */
+Program make_labeled_loop() {
+  Program program;
+  program.add_edge(0, 1);
+  program.add_edge(1, 2);
+  program.add_edge(2, 1);
+  program.add_edge(2, 3);
+  program.set_statements(0, {{true, 0}, {false, 1}});
+  program.set_statements(2, {{false, 2}, {false, 3}});
+  program.set_statements(3, {{true, 7}});
+  return program;
+}
+
+template <typename Analyzer>
+void check_statement_states(const Program& program) {
+  Analyzer analyzer(program);
+  analyzer.run(Interval::top());
+  // The states are not captured by default.
+  EXPECT_EQ(nullptr, analyzer.get_statement_states(0));
+  EXPECT_EQ(Interval::finite(1, 1), analyzer.get_exit_state_at(0));
+
+  analyzer.set_statement_capture(true);
+  analyzer.run(Interval::top());
+  ASSERT_NE(nullptr, analyzer.get_statement_states(0));
+  EXPECT_EQ(std::vector<Interval>({Interval::finite(0, 0),
+                                   Interval::finite(1, 1)}),
+            *analyzer.get_statement_states(0));
+  // A node without statements is analyzed, but emits no state.
+  ASSERT_NE(nullptr, analyzer.get_statement_states(1));
+  EXPECT_TRUE(analyzer.get_statement_states(1)->empty());
+  EXPECT_EQ(Interval::bounded_below(3),
+            analyzer.get_state_after_statement(2, 0));
+  EXPECT_EQ(Interval::bounded_below(6),
+            analyzer.get_state_after_statement(2, 1));
+  // The last state of a node is its exit state.
+  for (uint32_t node : {0, 2, 3}) {
+    EXPECT_EQ(analyzer.get_exit_state_at(node),
+              analyzer.get_statement_states(node)->back());
+  }
+  EXPECT_TRUE(analyzer.get_state_after_statement(2, 2).is_bottom());
+  EXPECT_TRUE(analyzer.get_state_after_statement(4, 0).is_bottom());
+
+  analyzer.set_statement_capture(false);
+  EXPECT_EQ(nullptr, analyzer.get_statement_states(0));
+  analyzer.run(Interval::top());
+  EXPECT_EQ(nullptr, analyzer.get_statement_states(0));
+}
+
+} // namespace
+
+TEST(StatementFixpointIteratorTest, loop) {
+  Program program = make_labeled_loop();
+  check_statement_states<Analyzer<MonotonicFixpointIterator>>(program);
+  check_statement_states<Analyzer<WTOMonotonicFixpointIterator>>(program);
+  check_statement_states<Analyzer<ParallelMonotonicFixpointIterator>>(program);
+}
+
+TEST(StatementFixpointIteratorTest, sparseStatements) {
+  class SparseAnalyzer final
+      : public StatementFixpointIterator<MonotonicFixpointIterator,
+                                         GraphInterface,
+                                         Interval> {
+   public:
+    using StatementFixpointIterator::StatementFixpointIterator;
+
+    void analyze_statements(const uint32_t&,
+                            Interval* state,
+                            StatementSink<Interval>* sink) const override {
+      *state = Interval::finite(5, 5);
+      sink->emit(2, *state);
+    }
+
+    Interval analyze_edge(const size_t&,
+                          const Interval& state) const override {
+      return state;
+    }
+  };
+
+  Graph graph;
+  graph.add_edge(0, 1);
+  SparseAnalyzer analyzer(graph);
+  analyzer.set_statement_capture(true);
+  analyzer.run(Interval::top());
+  EXPECT_EQ(std::vector<Interval>({Interval::bottom(),
+                                   Interval::bottom(),
+                                   Interval::finite(5, 5)}),
+            *analyzer.get_statement_states(1));
+}
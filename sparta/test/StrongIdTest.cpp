@@ -0,0 +1,118 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "StrongId.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <sstream>
+#include <unordered_set>
+#include <vector>
+
+#include "ConstantAbstractDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+#include "PatriciaTreeSet.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using BlockId = StrongId<struct BlockIdTag>;
+using RegisterId = StrongId<struct RegisterIdTag, uint16_t>;
+
+/*
+ * The graph of TestGraph.h, where the nodes are identified by BlockIds.
+ */
+class BlockGraphInterface {
+ public:
+  using Graph = ::Graph;
+  using NodeId = BlockId;
+  using EdgeId = size_t;
+
+  static NodeId entry(const Graph& graph) {
+    return BlockId(GraphInterface::entry(graph));
+  }
+  static std::vector<EdgeId> predecessors(const Graph& graph,
+                                          const NodeId& node) {
+    return GraphInterface::predecessors(graph, node.value());
+  }
+  static std::vector<EdgeId> successors(const Graph& graph,
+                                        const NodeId& node) {
+    return GraphInterface::successors(graph, node.value());
+  }
+  static NodeId source(const Graph& graph, const EdgeId& e) {
+    return BlockId(GraphInterface::source(graph, e));
+  }
+  static NodeId target(const Graph& graph, const EdgeId& e) {
+    return BlockId(GraphInterface::target(graph, e));
+  }
+};
+
+using Constant = ConstantAbstractDomain<uint32_t>;
+using Environment = PatriciaTreeMapAbstractEnvironment<RegisterId, Constant>;
+
+/*
+ * Each block assigns its identifier to the register 0.
+ */
+class Analyzer final
+    : public MonotonicFixpointIterator<BlockGraphInterface, Environment> {
+ public:
+  using MonotonicFixpointIterator::MonotonicFixpointIterator;
+
+  void analyze_node(const BlockId& block,
+                    Environment* current_state) const override {
+    current_state->set(RegisterId(0), Constant(block.value()));
+  }
+
+  Environment analyze_edge(const size_t&,
+                           const Environment& state) const override {
+    return state;
+  }
+};
+
+} // namespace
+
+TEST(StrongIdTest, patriciaTrees) {
+  PatriciaTreeSet<BlockId> blocks{BlockId(3), BlockId(1)};
+  EXPECT_TRUE(blocks.contains(BlockId(1)));
+  EXPECT_FALSE(blocks.contains(BlockId(2)));
+  EXPECT_THAT(std::vector<BlockId>(blocks.begin(), blocks.end()),
+              ::testing::UnorderedElementsAre(BlockId(1), BlockId(3)));
+
+  Environment env{{RegisterId(1), Constant(10)}};
+  env.set(RegisterId(2), Constant(20));
+  EXPECT_EQ(env.get(RegisterId(1)), Constant(10));
+  EXPECT_TRUE(env.get(RegisterId(3)).is_top());
+  std::vector<RegisterId> registers;
+  for (const auto& binding : env.bindings()) {
+    registers.push_back(binding.first);
+  }
+  EXPECT_THAT(registers,
+              ::testing::UnorderedElementsAre(RegisterId(1), RegisterId(2)));
+
+  std::ostringstream out;
+  out << BlockId(42);
+  EXPECT_EQ(out.str(), "42");
+}
+
+TEST(StrongIdTest, fixpointIterator) {
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 1);
+  graph.add_edge(1, 2);
+
+  Analyzer analyzer(graph);
+  analyzer.run(Environment::top());
+  EXPECT_EQ(analyzer.get_exit_state_at(BlockId(2)).get(RegisterId(0)),
+            Constant(2));
+  EXPECT_TRUE(
+      analyzer.get_entry_state_at(BlockId(1)).get(RegisterId(0)).is_top());
+  std::unordered_set<BlockId> visited{BlockId(0), BlockId(1), BlockId(2)};
+  EXPECT_EQ(visited.size(), 3);
+}
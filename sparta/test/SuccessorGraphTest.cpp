@@ -0,0 +1,138 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "SuccessorGraph.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <memory>
+#include <vector>
+
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+
+using namespace sparta;
+using namespace testing;
+
+namespace {
+
+/*
+ * A toy IR, in which a basic block either falls through to its successor,
+ * jumps to another block, or branches to one of two blocks depending on
+ * whether the counter is below a bound. Each block may increment the counter.
+ */
+struct BasicBlock {
+  int32_t increment = 0;
+  boost::optional<int32_t> bound;
+  std::vector<BasicBlock*> targets;
+};
+
+struct Function {
+  std::vector<std::unique_ptr<BasicBlock>> blocks;
+
+  BasicBlock* add_block() {
+    blocks.push_back(std::make_unique<BasicBlock>());
+    return blocks.back().get();
+  }
+};
+
+struct BasicBlockTraits {
+  using Graph = Function;
+  using Block = BasicBlock*;
+
+  static Block entry(const Graph& function) {
+    return function.blocks.front().get();
+  }
+
+  static const std::vector<BasicBlock*>& successors(const Graph&,
+                                                    const Block& block) {
+    return block->targets;
+  }
+};
+
+using CFG = SuccessorGraph<BasicBlockTraits>;
+using Interval = IntervalDomain<int32_t>;
+
+class CounterAnalyzer final
+    : public MonotonicFixpointIterator<SuccessorGraphInterface<CFG>,
+                                       Interval> {
+ public:
+  explicit CounterAnalyzer(const CFG& cfg)
+      : MonotonicFixpointIterator(cfg), m_cfg(cfg) {}
+
+  void analyze_node(BasicBlock* const& block,
+                    Interval* counter) const override {
+    *counter = *counter + Interval::finite(block->increment, block->increment);
+  }
+
+  Interval analyze_edge(const uint32_t& edge,
+                        const Interval& counter) const override {
+    BasicBlock* source = m_cfg.source(edge);
+    if (!source->bound) {
+      return counter;
+    }
+    // The first successor is taken if the counter is below the bound.
+    int32_t bound = *source->bound;
+    return m_cfg.successor_index(edge) == 0
+               ? counter.meet(Interval::bounded_above(bound - 1))
+               : counter.meet(Interval::bounded_below(bound));
+  }
+
+ private:
+  const CFG& m_cfg;
+};
+
+} // namespace
+
+TEST(SuccessorGraphTest, basicBlocks) {
+  // entry: goto head
+  // head: if (i < 10) goto body else goto exit
+  // body: i++; goto head
+  // exit: return
+  // dead: goto exit
+  Function function;
+  BasicBlock* entry = function.add_block();
+  BasicBlock* head = function.add_block();
+  BasicBlock* body = function.add_block();
+  BasicBlock* exit = function.add_block();
+  BasicBlock* dead = function.add_block();
+  entry->targets = {head};
+  head->bound = 10;
+  head->targets = {body, exit};
+  body->increment = 1;
+  body->targets = {head};
+  dead->targets = {exit};
+
+  CFG cfg(function);
+  EXPECT_EQ(entry, cfg.entry());
+  EXPECT_THAT(cfg.blocks(), ElementsAre(entry, head, body, exit));
+  EXPECT_FALSE(cfg.contains(dead));
+  EXPECT_EQ(2, cfg.predecessors(head).size());
+  EXPECT_EQ(1, cfg.predecessors(exit).size());
+  EXPECT_TRUE(cfg.successors(dead).empty());
+  EXPECT_EQ(body, cfg.target(cfg.successors(head)[0]));
+  EXPECT_EQ(1, cfg.successor_index(cfg.successors(head)[1]));
+
+  CounterAnalyzer analyzer(cfg);
+  analyzer.run(Interval::finite(0, 0));
+  EXPECT_EQ(Interval::finite(0, 10), analyzer.get_entry_state_at(head));
+  EXPECT_EQ(Interval::finite(1, 10), analyzer.get_exit_state_at(body));
+  EXPECT_TRUE(
+      analyzer.get_entry_state_at(exit).leq(Interval::bounded_below(10)));
+}
+
+TEST(SuccessorGraphTest, adjacencyLists) {
+  using Graph = SuccessorGraph<AdjacencyListBlocks>;
+  // The two cases of the switch in node 1 branch to node 2.
+  Graph graph({{1}, {2, 2, 3}, {1}, {}, {3}});
+  EXPECT_EQ(4, graph.size());
+  EXPECT_EQ(3, graph.successors(1).size());
+  EXPECT_THAT(graph.predecessors(2), ElementsAre(1, 2));
+  EXPECT_EQ(1, graph.source(graph.predecessors(2)[1]));
+  EXPECT_EQ(4, SuccessorGraphInterface<Graph>::size_hint(graph));
+  EXPECT_FALSE(graph.contains(4));
+}
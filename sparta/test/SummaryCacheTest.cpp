@@ -0,0 +1,148 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "SummaryCache.h"
+
+#include <gtest/gtest.h>
+#include <string>
+#include <thread>
+#include <vector>
+
+using namespace sparta;
+
+using Cache = SummaryCache<int, std::string>;
+
+TEST(SummaryCacheTest, leastRecentlyUsedEviction) {
+  Cache cache(2);
+  cache.put(1, "f1");
+  cache.put(2, "f2");
+  EXPECT_EQ("f1", *cache.get(1));
+
+  // The summary of 2 is the least recently used one.
+  cache.put(3, "f3");
+  EXPECT_EQ(2, cache.size());
+  EXPECT_EQ(nullptr, cache.get(2));
+  EXPECT_EQ("f1", *cache.get(1));
+  EXPECT_EQ("f3", *cache.get(3));
+
+  auto stats = cache.stats();
+  EXPECT_EQ(3, stats.hits);
+  EXPECT_EQ(1, stats.misses);
+  EXPECT_EQ(1, stats.evictions);
+}
+
+TEST(SummaryCacheTest, recomputation) {
+  Cache cache(1);
+  size_t computations = 0;
+  auto compute = [&computations](const int& f) {
+    ++computations;
+    return "f" + std::to_string(f);
+  };
+  EXPECT_EQ("f1", *cache.get_or_compute(1, compute));
+  EXPECT_EQ("f1", *cache.get_or_compute(1, compute));
+  EXPECT_EQ(1, computations);
+
+  EXPECT_EQ("f2", *cache.get_or_compute(2, compute));
+  EXPECT_EQ("f1", *cache.get_or_compute(1, compute));
+  EXPECT_EQ(3, computations);
+}
+
+TEST(SummaryCacheTest, weakReferences) {
+  Cache cache(1);
+  auto f1 = cache.put(1, "f1");
+  cache.put(2, "f2");
+  EXPECT_EQ(1, cache.stats().evictions);
+
+  // The evicted summary of 1 is still referenced, so it is restored.
+  EXPECT_EQ(f1, cache.get(1));
+  EXPECT_EQ(1, cache.stats().recoveries);
+
+  // The evicted summary of 2 is no longer referenced.
+  EXPECT_EQ(nullptr, cache.get(2));
+  EXPECT_EQ(1, cache.stats().misses);
+}
+
+TEST(SummaryCacheTest, pinning) {
+  Cache cache(1);
+  cache.pin(1);
+  cache.pin(1);
+  cache.pin(2);
+  cache.put(1, "f1");
+  cache.put(2, "f2");
+  cache.put(3, "f3");
+  // Pinned summaries may exceed the capacity.
+  EXPECT_EQ(2, cache.weight());
+  EXPECT_EQ("f1", *cache.get(1));
+  EXPECT_EQ("f2", *cache.get(2));
+  EXPECT_EQ(nullptr, cache.get(3));
+
+  cache.unpin(2);
+  EXPECT_EQ(1, cache.size());
+  EXPECT_EQ(nullptr, cache.get(2));
+
+  cache.unpin(1);
+  EXPECT_TRUE(cache.is_pinned(1));
+  cache.put(4, "f4");
+  EXPECT_EQ("f1", *cache.get(1));
+  cache.unpin(1);
+  EXPECT_FALSE(cache.is_pinned(1));
+  EXPECT_EQ(1, cache.size());
+  EXPECT_THROW(cache.unpin(1), invalid_argument);
+}
+
+TEST(SummaryCacheTest, pinScope) {
+  Cache cache(1);
+  cache.put(1, "f1");
+  {
+    Cache::PinScope scope(&cache, {1, 2});
+    cache.put(2, "f2");
+    cache.put(3, "f3");
+    EXPECT_EQ("f1", *cache.get(1));
+    EXPECT_EQ("f2", *cache.get(2));
+    EXPECT_EQ(nullptr, cache.get(3));
+
+    cache.clear();
+    EXPECT_EQ(2, cache.size());
+  }
+  EXPECT_FALSE(cache.is_pinned(1));
+  EXPECT_EQ(1, cache.size());
+}
+
+TEST(SummaryCacheTest, weigher) {
+  Cache cache(10, [](const std::string& s) { return s.size(); });
+  cache.put(1, "aaaa");
+  cache.put(2, "bbbb");
+  EXPECT_EQ(8, cache.weight());
+  cache.put(3, "cccc");
+  EXPECT_EQ(8, cache.weight());
+  EXPECT_EQ(nullptr, cache.get(1));
+
+  // Replacing a summary updates the weight.
+  cache.put(3, "c");
+  EXPECT_EQ(5, cache.weight());
+  cache.erase(2);
+  EXPECT_EQ(1, cache.weight());
+}
+
+TEST(SummaryCacheTest, concurrentAccesses) {
+  Cache cache(8);
+  std::vector<std::thread> threads;
+  for (int t = 0; t < 4; ++t) {
+    threads.emplace_back([&cache, t]() {
+      for (int i = 0; i < 1000; ++i) {
+        int f = (i * 7 + t) % 32;
+        auto summary = cache.get_or_compute(
+            f, [](const int& f) { return std::to_string(f); });
+        EXPECT_EQ(std::to_string(f), *summary);
+      }
+    });
+  }
+  for (auto& thread : threads) {
+    thread.join();
+  }
+  EXPECT_LE(cache.weight(), cache.capacity());
+}
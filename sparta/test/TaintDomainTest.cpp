@@ -0,0 +1,104 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "TaintDomain.h"
+
+#include <cstdint>
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+#include <vector>
+
+#include "AbstractDomainPropertyTest.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+
+using namespace sparta;
+
+namespace {
+
+enum class Kind { UserInput, Secret };
+
+struct KindHash {
+  size_t operator()(Kind kind) const { return static_cast<size_t>(kind); }
+};
+
+std::ostream& operator<<(std::ostream& o, Kind kind) {
+  return o << (kind == Kind::UserInput ? "UserInput" : "Secret");
+}
+
+// The taints are introduced at call sites, which are identified by strings.
+using Taint = TaintDomain<Kind, std::string, KindHash>;
+using Environment = PatriciaTreeMapAbstractEnvironment<uint32_t, Taint>;
+
+} // namespace
+
+INSTANTIATE_TYPED_TEST_CASE_P(TaintDomain, AbstractDomainPropertyTest, Taint);
+
+template <>
+std::vector<Taint> AbstractDomainPropertyTest<Taint>::non_extremal_values() {
+  Taint input = Taint::source(Kind::UserInput, "read");
+  Taint secret = Taint::source(Kind::Secret, "password");
+  return {Taint::untainted(), input, secret, input.join(secret)};
+}
+
+TEST(TaintDomainTest, combinators) {
+  Taint input = Taint::source(Kind::UserInput, "read");
+  Taint secret = Taint::source(Kind::Secret, "password");
+  EXPECT_FALSE(Taint::untainted().is_tainted());
+  EXPECT_TRUE(input.is_tainted());
+  EXPECT_TRUE(Taint::top().is_tainted());
+  EXPECT_FALSE(Taint::bottom().is_tainted());
+
+  Taint x = input;
+  x.propagate(secret);
+  EXPECT_EQ(input.join(secret), x);
+  EXPECT_EQ(Taint::Kinds({Kind::UserInput, Kind::Secret}), x.kinds());
+
+  EXPECT_EQ(secret, x.flows_into({Kind::Secret}));
+  EXPECT_FALSE(input.flows_into({Kind::Secret}).is_tainted());
+  EXPECT_TRUE(Taint::top().flows_into({Kind::Secret}).is_top());
+
+  x.sanitize({Kind::UserInput});
+  EXPECT_EQ(secret, x);
+  x.sanitize({Kind::Secret});
+  EXPECT_FALSE(x.is_tainted());
+  EXPECT_TRUE(Taint::top().sanitize({Kind::Secret}).is_top());
+
+  std::ostringstream out;
+  out << input;
+  EXPECT_EQ("[#1]{UserInput@read}", out.str());
+}
+
+TEST(TaintDomainTest, environment) {
+  /*
+   *   v0 = read()            // source of user input
+   *   v1 = password()        // source of a secret
+   *   v2 = concat(v0, v1)
+   *   v3 = escape(v2)        // sanitizes user input
+   *   sql(v3)                // sink for user input
+   *   log(v3)                // sink for secrets
+   */
+  Environment env;
+  env.set(0, Taint::source(Kind::UserInput, "read"));
+  env.set(1, Taint::source(Kind::Secret, "password"));
+  propagate_taint(&env, 2u, {0u, 1u});
+  propagate_taint(&env, 3u, {2u});
+  EXPECT_EQ(Taint::Kinds({Kind::UserInput, Kind::Secret}),
+            check_sink(env, 3u, Taint::Kinds({Kind::UserInput, Kind::Secret}))
+                .kinds());
+  sanitize_taint(&env, 3u, Taint::Kinds({Kind::UserInput}));
+  EXPECT_FALSE(
+      check_sink(env, 3u, Taint::Kinds({Kind::UserInput})).is_tainted());
+  EXPECT_EQ(Taint::source(Kind::Secret, "password"),
+            check_sink(env, 3u, Taint::Kinds({Kind::Secret})));
+  // The sanitizer only applies to v3.
+  EXPECT_TRUE(
+      check_sink(env, 2u, Taint::Kinds({Kind::UserInput})).is_tainted());
+
+  // An unknown variable may carry any taint.
+  EXPECT_TRUE(check_sink(env, 7u, Taint::Kinds({Kind::Secret})).is_top());
+}
@@ -0,0 +1,187 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <memory>
+#include <unordered_map>
+#include <utility>
+#include <vector>
+
+#include "IntervalDomain.h"
+
+/*
+ * A minimal control-flow graph for testing the fixpoint iterators. The nodes
+ * are integers and node 0 is the entry. The edges are numbered in the order
+ * in which they are added.
+ *
+ * Tests that label the nodes, e.g., with statements, can derive from Graph
+ * and GraphInterface, redefining GraphInterface::Graph accordingly.
+ */
+class Graph {
+ public:
+  using Edge = std::pair<uint32_t, uint32_t>;
+
+  // Returns the identifier of the new edge.
+  size_t add_edge(uint32_t src, uint32_t dst) {
+    m_edges.emplace_back(src, dst);
+    m_successors[src].push_back(m_edges.size() - 1);
+    m_predecessors[dst].push_back(m_edges.size() - 1);
+    return m_edges.size() - 1;
+  }
+
+ private:
+  std::vector<Edge> m_edges;
+  std::unordered_map<uint32_t, std::vector<size_t>> m_successors;
+  std::unordered_map<uint32_t, std::vector<size_t>> m_predecessors;
+
+  friend class GraphInterface;
+};
+
+class GraphInterface {
+ public:
+  using Graph = ::Graph;
+  using NodeId = uint32_t;
+  using EdgeId = size_t;
+
+  static NodeId entry(const Graph&) { return 0; }
+  static std::vector<EdgeId> predecessors(const Graph& graph,
+                                          const NodeId& node) {
+    auto it = graph.m_predecessors.find(node);
+    return it == graph.m_predecessors.end() ? std::vector<EdgeId>()
+                                            : it->second;
+  }
+  static std::vector<EdgeId> successors(const Graph& graph,
+                                        const NodeId& node) {
+    auto it = graph.m_successors.find(node);
+    return it == graph.m_successors.end() ? std::vector<EdgeId>() : it->second;
+  }
+  static NodeId source(const Graph& graph, const EdgeId& edge) {
+    return graph.m_edges[edge].first;
+  }
+  static NodeId target(const Graph& graph, const EdgeId& edge) {
+    return graph.m_edges[edge].second;
+  }
+};
+
+/*
+ *  0 -> 1 -> 2 -> 3 -> 4 -> 5
+ *       ^    ^    |    |
+ *       |    +----+    |
+ *       +--------------+
+ */
+inline Graph make_nested_loops() {
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 3);
+  graph.add_edge(3, 2);
+  graph.add_edge(3, 4);
+  graph.add_edge(4, 1);
+  graph.add_edge(4, 5);
+  return graph;
+}
+
+/*
+ *  0 -> 1 -> 2 -> 3
+ *       ^    |
+ *       +----+
+ */
+inline Graph make_loop() {
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 1);
+  graph.add_edge(2, 3);
+  return graph;
+}
+
+/*
+ * An interval analysis of a counter that node 0 initializes to 0 and that a
+ * given node, node 2 by default, increments. The iterator can be any of the
+ * fixpoint iterators over a graph interface, a domain and a node hash.
+ */
+template <template <typename, typename, typename> class Iterator>
+class CounterAnalyzer final
+    : public Iterator<GraphInterface,
+                      sparta::IntervalDomain<int32_t>,
+                      std::hash<uint32_t>> {
+ public:
+  using Interval = sparta::IntervalDomain<int32_t>;
+  using Base = Iterator<GraphInterface, Interval, std::hash<uint32_t>>;
+
+  explicit CounterAnalyzer(const Graph& graph, uint32_t increment = 2)
+      : Base(graph), m_increment(increment) {}
+
+  void analyze_node(const uint32_t& node, Interval* state) const override {
+    if (node == 0) {
+      *state = Interval::finite(0, 0);
+    } else if (node == m_increment) {
+      *state += 1;
+    }
+  }
+
+  Interval analyze_edge(const size_t&, const Interval& state) const override {
+    return state;
+  }
+
+ private:
+  uint32_t m_increment;
+};
+
+/*
+ * A graph over integers for testing the graph transformations, with a given
+ * entry node. An edge is identified by a shared pointer to its endpoints, and
+ * every node that is an endpoint of an edge is known to the graph.
+ */
+class SharedEdgeGraph final {
+ public:
+  using Edge = std::pair<uint32_t, uint32_t>;
+  using EdgeId = std::shared_ptr<Edge>;
+
+  explicit SharedEdgeGraph(uint32_t entry) : m_entry(entry) {
+    m_successors[entry];
+    m_predecessors[entry];
+  }
+
+  void add_edge(uint32_t src, uint32_t dst) {
+    auto edge = std::make_shared<Edge>(src, dst);
+    m_successors[src].push_back(edge);
+    m_successors[dst];
+    m_predecessors[dst].push_back(edge);
+    m_predecessors[src];
+  }
+
+ private:
+  uint32_t m_entry;
+  std::unordered_map<uint32_t, std::vector<EdgeId>> m_successors;
+  std::unordered_map<uint32_t, std::vector<EdgeId>> m_predecessors;
+
+  friend class SharedEdgeGraphInterface;
+};
+
+class SharedEdgeGraphInterface {
+ public:
+  using Graph = SharedEdgeGraph;
+  using NodeId = uint32_t;
+  using EdgeId = Graph::EdgeId;
+
+  static NodeId entry(const Graph& graph) { return graph.m_entry; }
+  static std::vector<EdgeId> predecessors(const Graph& graph,
+                                          const NodeId& node) {
+    return graph.m_predecessors.at(node);
+  }
+  static std::vector<EdgeId> successors(const Graph& graph,
+                                        const NodeId& node) {
+    return graph.m_successors.at(node);
+  }
+  static NodeId source(const Graph&, const EdgeId& e) { return e->first; }
+  static NodeId target(const Graph&, const EdgeId& e) { return e->second; }
+};
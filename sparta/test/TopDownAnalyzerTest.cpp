@@ -0,0 +1,144 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "TopDownAnalyzer.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <string>
+#include <unordered_map>
+#include <vector>
+
+#include "ConstantAbstractDomain.h"
+
+using namespace sparta;
+
+namespace {
+
+/*
+ * A function of the toy language takes a single integer parameter and is a
+ * sequence of calls `callee(parameter + offset)`.
+ */
+struct Call {
+  std::string callee;
+  int offset;
+};
+
+using Program = std::unordered_map<std::string, std::vector<Call>>;
+
+const Program* program = nullptr;
+
+using Constant = ConstantAbstractDomain<int>;
+
+struct Analysis {
+  using Function = std::string;
+  // A call site is identified by the caller and the index of the call.
+  using CallSite = std::string;
+  using Context = Constant;
+  // The value of the argument at each call.
+  using Result = std::vector<Constant>;
+
+  static Result analyze(
+      const Function& function,
+      const Context& parameter,
+      const std::function<void(const CallSite&,
+                               const Function&,
+                               const Context&)>& on_call) {
+    Result result;
+    const auto& calls = program->at(function);
+    for (size_t i = 0; i < calls.size(); ++i) {
+      Constant argument = parameter;
+      if (auto value = parameter.get_constant()) {
+        argument = Constant(*value + calls[i].offset);
+      }
+      result.push_back(argument);
+      on_call(function + "#" + std::to_string(i), calls[i].callee, argument);
+    }
+    return result;
+  }
+};
+
+using Analyzer = TopDownInterproceduralAnalyzer<Analysis>;
+
+} // namespace
+
+TEST(TopDownAnalyzerTest, callSiteSplitting) {
+  /*
+   * main(x) { f(x + 1); f(x + 2); g(x + 3); }
+   * f(x) { g(x); }
+   * g(x) { }
+   */
+  Program p{{"main", {{"f", 1}, {"f", 2}, {"g", 3}}},
+            {"f", {{"g", 0}}},
+            {"g", {}}};
+  program = &p;
+
+  Analyzer analyzer(/* max_contexts_per_function */ 2);
+  analyzer.run({{"main", Constant(0)}});
+
+  auto f_contexts = analyzer.get_contexts("f");
+  ASSERT_EQ(f_contexts.size(), 2);
+  EXPECT_EQ(analyzer.get_context("f", std::string("main#0"))->entry_state,
+            Constant(1));
+  EXPECT_EQ(analyzer.get_context("f", std::string("main#1"))->entry_state,
+            Constant(2));
+  EXPECT_FALSE(analyzer.get_context("f", boost::none));
+
+  // Both contexts of f share the same call site to g.
+  auto g_contexts = analyzer.get_contexts("g");
+  ASSERT_EQ(g_contexts.size(), 2);
+  EXPECT_TRUE(
+      analyzer.get_context("g", std::string("f#0"))->entry_state.is_top());
+  EXPECT_EQ(analyzer.get_context("g", std::string("main#2"))->entry_state,
+            Constant(3));
+
+  auto main_context = analyzer.get_context("main", boost::none);
+  ASSERT_TRUE(main_context);
+  EXPECT_THAT(*main_context->result,
+              ::testing::ElementsAre(Constant(1), Constant(2), Constant(3)));
+}
+
+TEST(TopDownAnalyzerTest, budget) {
+  Program p{{"main", {{"f", 1}, {"f", 2}, {"f", 1}}}, {"f", {}}};
+  program = &p;
+
+  Analyzer split(/* max_contexts_per_function */ 1);
+  split.run({{"main", Constant(0)}});
+  EXPECT_EQ(split.get_contexts("f").size(), 2);
+  EXPECT_EQ(split.get_context("f", std::string("main#0"))->entry_state,
+            Constant(1));
+  EXPECT_FALSE(split.get_context("f", std::string("main#1")));
+  // The last two calls are merged.
+  EXPECT_TRUE(split.get_context("f", boost::none)->entry_state.is_top());
+
+  Analyzer insensitive(/* max_contexts_per_function */ 0);
+  insensitive.run({{"main", Constant(0)}});
+  ASSERT_EQ(insensitive.get_contexts("f").size(), 1);
+  EXPECT_TRUE(insensitive.get_contexts("f")[0].entry_state.is_top());
+  EXPECT_FALSE(insensitive.get_contexts("f")[0].call_site);
+}
+
+TEST(TopDownAnalyzerTest, recursion) {
+  /*
+   * main(x) { f(x); }
+   * f(x) { f(x + 1); h(x); }
+   */
+  Program p{{"main", {{"f", 0}}}, {"f", {{"f", 1}, {"h", 0}}}, {"h", {}}};
+  program = &p;
+
+  Analyzer analyzer(/* max_contexts_per_function */ 10);
+  analyzer.run({{"main", Constant(0)}});
+
+  EXPECT_EQ(analyzer.get_context("f", std::string("main#0"))->entry_state,
+            Constant(0));
+  EXPECT_TRUE(
+      analyzer.get_context("f", std::string("f#0"))->entry_state.is_top());
+  // The context of h at the unique call site joins the contexts of f.
+  EXPECT_TRUE(
+      analyzer.get_context("h", std::string("f#1"))->entry_state.is_top());
+  EXPECT_FALSE(analyzer.get_contexts("unreachable").size());
+}
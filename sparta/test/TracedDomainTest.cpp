@@ -0,0 +1,138 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+// Tracing must be enabled before including any header of the library.
+#define SPARTA_TRACE_DOMAINS
+
+#include "TracedDomain.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+#include <thread>
+#include <vector>
+
+#include "AbstractDomainPropertyTest.h"
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+using Interval = IntervalDomain<int32_t>;
+using Domain = Traced<Interval>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(Traced, AbstractDomainPropertyTest, Domain);
+
+template <>
+std::vector<Domain> AbstractDomainPropertyTest<Domain>::non_extremal_values() {
+  return {Domain(Interval::finite(0, 1)), Domain(Interval::finite(1, 5)),
+          Domain(Interval::bounded_below(3))};
+}
+
+namespace {
+
+struct Digest {
+  uint64_t operator()(const Interval& x) const {
+    return x.is_bottom() ? 0 : x.upper_bound();
+  }
+};
+
+using Op = DomainTraceEvent::Op;
+
+/*
+ * Node 0 initializes a variable to 0 and node 2 increments it.
+ */
+class TracedCounterAnalyzer final
+    : public MonotonicFixpointIterator<GraphInterface, Domain> {
+ public:
+  using MonotonicFixpointIterator::MonotonicFixpointIterator;
+
+  void analyze_node(const uint32_t& node, Domain* state) const override {
+    if (node == 0) {
+      state->inner() = Interval::finite(0, 0);
+    } else if (node == 2) {
+      state->inner() += 1;
+    }
+  }
+
+  Domain analyze_edge(const size_t&, const Domain& state) const override {
+    return state;
+  }
+};
+
+} // namespace
+
+TEST(TracedDomainTest, operations) {
+  using D = Traced<Interval, Digest>;
+  DomainTrace::clear();
+  D x(Interval::finite(0, 1));
+  D y(Interval::finite(2, 5));
+  EXPECT_EQ(Interval::finite(0, 5), x.join(y).inner());
+  EXPECT_TRUE(x.leq(x.join(y)));
+  EXPECT_FALSE(x.equals(y));
+  EXPECT_TRUE(x.meet(y).is_bottom());
+  x.widen_with(y);
+  EXPECT_TRUE(DomainTrace::enabled());
+
+  const auto& log = DomainTrace::get();
+  ASSERT_EQ(6, log.size());
+  EXPECT_EQ(Op::Join, log[0].op);
+  EXPECT_EQ(1, log[0].lhs);
+  EXPECT_EQ(5, log[0].rhs);
+  EXPECT_EQ(5, log[0].result);
+  EXPECT_EQ(Op::Join, log[1].op);
+  EXPECT_EQ(Op::Leq, log[2].op);
+  EXPECT_EQ(1, log[2].result);
+  EXPECT_EQ(Op::Equals, log[3].op);
+  EXPECT_EQ(0, log[3].result);
+  EXPECT_EQ(Op::Meet, log[4].op);
+  EXPECT_EQ(0, log[4].result);
+  EXPECT_EQ(Op::Widen, log[5].op);
+  EXPECT_EQ(Interval::MAX, log[5].result);
+
+  std::ostringstream out;
+  out << log[0] << " " << x;
+  EXPECT_EQ("join(1, 5) = 5 [0, +inf]", out.str());
+
+  // The log is local to each thread.
+  std::thread([] {
+    D z;
+    z.join_with(D::bottom());
+    EXPECT_EQ(1, DomainTrace::get().size());
+  }).join();
+  EXPECT_EQ(6, DomainTrace::get().size());
+  DomainTrace::clear();
+  EXPECT_TRUE(DomainTrace::get().empty());
+}
+
+TEST(TracedDomainTest, fixpointIteration) {
+  /*
+   *  0 -> 1 -> 2 -> 3
+   *       ^    |
+   *       +----+
+   */
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 1);
+  graph.add_edge(2, 3);
+
+  DomainTrace::clear();
+  TracedCounterAnalyzer analyzer(graph);
+  analyzer.run(Domain::top());
+  EXPECT_EQ(Interval::bounded_below(1), analyzer.get_entry_state_at(3).inner());
+
+  // The entry state of the loop head is widened until the loop stabilizes.
+  size_t num_widenings = 0;
+  for (const auto& event : DomainTrace::get()) {
+    if (event.op == Op::Widen) {
+      ++num_widenings;
+    }
+  }
+  EXPECT_GE(num_widenings, 1);
+  EXPECT_FALSE(DomainTrace::get().empty());
+}
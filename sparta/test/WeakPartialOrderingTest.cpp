@@ -7,6 +7,7 @@
 
 #include "WeakPartialOrdering.h"
 
+#include <algorithm>
 #include <gtest/gtest.h>
 #include <set>
 #include <sstream>
@@ -1333,3 +1334,44 @@ TEST(WeakPartialOrderingTest, handleNestedLoopsWithBranch) {
     EXPECT_EQ(wto.str(), "(1 (2 3) 4 5)");
   }
 }
+
+TEST(WeakPartialOrderingTest, waves) {
+  SimpleGraph2 g;
+  g.add_edge("1", "2");
+  g.add_edge("1", "3");
+  g.add_edge("2", "4");
+  g.add_edge("3", "4");
+  g.add_edge("3", "5");
+  g.add_edge("5", "6");
+  g.add_edge("6", "5");
+  g.add_edge("4", "7");
+  g.add_edge("6", "7");
+
+  WeakPartialOrdering<std::string> wpo(
+      "1", [&g](const std::string& n) { return g.successors(n); }, false);
+
+  auto waves = wpo.get_waves();
+  std::vector<std::vector<std::string>> nodes;
+  std::unordered_map<WpoIdx, size_t> wave_of;
+  size_t num_nodes = 0;
+  for (size_t i = 0; i < waves.size(); ++i) {
+    std::vector<std::string> wave_nodes;
+    for (auto v : waves[i]) {
+      wave_of[v] = i;
+      wave_nodes.push_back((wpo.is_exit(v) ? "exit " : "") + wpo.get_node(v));
+      ++num_nodes;
+    }
+    std::sort(wave_nodes.begin(), wave_nodes.end());
+    nodes.push_back(wave_nodes);
+  }
+  EXPECT_EQ(wpo.size(), num_nodes);
+  for (WpoIdx v = 0; v < wpo.size(); ++v) {
+    for (auto w : wpo.get_successors(v)) {
+      EXPECT_LT(wave_of.at(v), wave_of.at(w));
+    }
+  }
+
+  std::vector<std::vector<std::string>> expected = {
+      {"1"}, {"2", "3"}, {"4", "5"}, {"6"}, {"exit 5"}, {"7"}};
+  EXPECT_EQ(expected, nodes);
+}
@@ -0,0 +1,117 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "WideRegisterEnvironment.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "AbstractDomainPropertyTest.h"
+#include "IntervalDomain.h"
+
+using namespace sparta;
+
+using Interval = IntervalDomain<int64_t>;
+
+using Environment = WideRegisterEnvironment<uint32_t, Interval>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(WideRegisterEnvironment,
+                              AbstractDomainPropertyTest,
+                              Environment);
+
+template <>
+std::vector<Environment>
+AbstractDomainPropertyTest<Environment>::non_extremal_values() {
+  Environment e1;
+  e1.set(0, Interval::finite(0, 1)).set(1, Interval::finite(2, 3));
+  Environment e2;
+  e2.set_wide(0, Interval::finite(1, 1L << 40));
+  Environment e3;
+  e3.set_wide(1, Interval::finite(-1, 1)).set(4, Interval::finite(0, 0));
+  Environment e4;
+  e4.set_wide(0, Interval::finite(0, 0)).set_wide(2, Interval::finite(5, 5));
+  return {e1, e2, e3, e4};
+}
+
+TEST(WideRegisterEnvironmentTest, pairing) {
+  Environment env;
+  EXPECT_TRUE(env.is_top());
+
+  env.set_wide(2, Interval::finite(0, 100));
+  EXPECT_FALSE(env.is_top());
+  EXPECT_TRUE(env.is_wide(2));
+  EXPECT_TRUE(env.is_upper_half(3));
+  EXPECT_FALSE(env.is_upper_half(2));
+  EXPECT_EQ(Interval::finite(0, 100), env.get(2));
+  EXPECT_TRUE(env.get(3).is_top());
+
+  // Overwriting the upper half destroys the pair.
+  env.set(3, Interval::finite(7, 7));
+  EXPECT_FALSE(env.is_wide(2));
+  EXPECT_FALSE(env.is_upper_half(3));
+  EXPECT_TRUE(env.get(2).is_top());
+  EXPECT_EQ(Interval::finite(7, 7), env.get(3));
+
+  // Overwriting the lower half destroys the pair.
+  env.set_wide(4, Interval::finite(1, 2));
+  env.set(4, Interval::finite(3, 3));
+  EXPECT_FALSE(env.is_wide(4));
+  EXPECT_EQ(Interval::finite(3, 3), env.get(4));
+  EXPECT_TRUE(env.get(5).is_top());
+
+  // Overlapping wide writes.
+  env.set_wide(6, Interval::finite(1, 1));
+  env.set_wide(8, Interval::finite(2, 2));
+  env.set_wide(7, Interval::finite(3, 3));
+  EXPECT_EQ(Environment::RegisterSet({7}), env.wide_registers());
+  EXPECT_TRUE(env.get(6).is_top());
+  EXPECT_EQ(Interval::finite(3, 3), env.get(7));
+  EXPECT_TRUE(env.get(8).is_top());
+  EXPECT_TRUE(env.get(9).is_top());
+
+  env.unset(8);
+  EXPECT_TRUE(env.wide_registers().empty());
+  EXPECT_TRUE(env.get(7).is_top());
+  EXPECT_EQ(Interval::finite(7, 7), env.get(3));
+
+  env.set(0, Interval::bottom());
+  EXPECT_TRUE(env.is_bottom());
+  env.set_wide(0, Interval::finite(0, 0));
+  EXPECT_TRUE(env.is_bottom());
+  EXPECT_TRUE(env.wide_registers().empty());
+}
+
+TEST(WideRegisterEnvironmentTest, join) {
+  Environment e1;
+  e1.set_wide(0, Interval::finite(0, 0));
+  e1.set_wide(2, Interval::finite(1, 1));
+  Environment e2;
+  e2.set_wide(0, Interval::finite(5, 5));
+  e2.set(2, Interval::finite(1, 1));
+  e2.set(3, Interval::finite(1, 1));
+
+  Environment join = e1.join(e2);
+  EXPECT_TRUE(e1.leq(join));
+  EXPECT_TRUE(e2.leq(join));
+  EXPECT_TRUE(join.is_wide(0));
+  EXPECT_FALSE(join.is_wide(2));
+  EXPECT_EQ(Interval::finite(0, 5), join.get(0));
+  // The values of registers 2 and 3 do not have the same width on both sides.
+  EXPECT_TRUE(join.get(2).is_top());
+  EXPECT_TRUE(join.get(3).is_top());
+
+  Environment meet = e1.meet(e2);
+  EXPECT_TRUE(meet.is_bottom());
+}
+
+TEST(WideRegisterEnvironmentTest, print) {
+  Environment env;
+  env.set_wide(0, Interval::finite(0, 1));
+  std::ostringstream out;
+  out << env;
+  EXPECT_EQ("[#1]{0 -> [0, 1]} wide: {0}", out.str());
+}
@@ -0,0 +1,90 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "WideningPoints.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+
+#include "IntervalDomain.h"
+#include "MonotonicFixpointIterator.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+template <typename Analyzer>
+void check_counter(const Graph& graph) {
+  // The counter is incremented in the inner loop.
+  Analyzer analyzer(graph, /* increment */ 3);
+  analyzer.set_widening_points(
+      compute_widening_points<GraphInterface>(graph));
+  analyzer.run(Interval::top());
+  EXPECT_EQ(Interval::bounded_below(0), analyzer.get_entry_state_at(1));
+  EXPECT_EQ(Interval::bounded_below(0), analyzer.get_entry_state_at(2));
+  EXPECT_EQ(Interval::bounded_below(1), analyzer.get_exit_state_at(3));
+  EXPECT_EQ(Interval::bounded_below(1), analyzer.get_entry_state_at(5));
+}
+
+} // namespace
+
+TEST(WideningPointsTest, nestedLoops) {
+  Graph graph = make_nested_loops();
+  // All the iterations of the outer loop go through the inner head.
+  EXPECT_THAT(compute_widening_points<GraphInterface>(graph),
+              ::testing::UnorderedElementsAre(2));
+
+  // A path around the outer loop that bypasses the inner loop.
+  graph.add_edge(1, 6);
+  graph.add_edge(6, 1);
+  EXPECT_THAT(compute_widening_points<GraphInterface>(graph),
+              ::testing::UnorderedElementsAre(1, 2));
+}
+
+TEST(WideningPointsTest, siblingLoops) {
+  //  0 -> 1 -> 2 -> 3 -> 4
+  //       ^   ( )  ( )   |
+  //       +--------------+
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 2);
+  graph.add_edge(2, 3);
+  graph.add_edge(3, 3);
+  graph.add_edge(3, 4);
+  graph.add_edge(4, 1);
+  EXPECT_THAT(compute_widening_points<GraphInterface>(graph),
+              ::testing::UnorderedElementsAre(2, 3));
+
+  // An acyclic graph has no widening points.
+  Graph acyclic;
+  acyclic.add_edge(0, 1);
+  acyclic.add_edge(0, 2);
+  acyclic.add_edge(1, 2);
+  EXPECT_TRUE(compute_widening_points<GraphInterface>(acyclic).empty());
+}
+
+TEST(WideningPointsTest, fixpoint) {
+  Graph graph = make_nested_loops();
+  check_counter<CounterAnalyzer<MonotonicFixpointIterator>>(graph);
+  check_counter<CounterAnalyzer<WTOMonotonicFixpointIterator>>(graph);
+  check_counter<CounterAnalyzer<ParallelMonotonicFixpointIterator>>(graph);
+
+  CounterAnalyzer<MonotonicFixpointIterator> analyzer(graph,
+                                                      /* increment */ 3);
+  analyzer.set_widening_points(
+      compute_widening_points<GraphInterface>(graph));
+  EXPECT_TRUE(analyzer.is_widening_point(2));
+  EXPECT_FALSE(analyzer.is_widening_point(1));
+  analyzer.set_widening_points(boost::none);
+  EXPECT_FALSE(analyzer.is_widening_point(2));
+  analyzer.run(Interval::top());
+  EXPECT_EQ(Interval::bounded_below(0), analyzer.get_entry_state_at(1));
+}
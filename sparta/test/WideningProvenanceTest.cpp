@@ -0,0 +1,151 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "WideningProvenance.h"
+
+#include <gmock/gmock.h>
+#include <gtest/gtest.h>
+#include <sstream>
+
+#include "IntervalDomain.h"
+#include "MemoryCeiling.h"
+#include "MonotonicFixpointIterator.h"
+#include "PatriciaTreeMapAbstractEnvironment.h"
+#include "TestGraph.h"
+
+using namespace sparta;
+
+namespace {
+
+using Interval = IntervalDomain<int32_t>;
+
+using Environment = PatriciaTreeMapAbstractEnvironment<uint32_t, Interval>;
+
+using Provenance = WideningProvenance<uint32_t, Environment>;
+
+constexpr uint32_t x = 0;
+constexpr uint32_t y = 1;
+
+/*
+ * Node 0 initializes x and y to 0, node 2 increments x and node 4 resets y.
+ */
+template <template <typename, typename, typename> class Iterator>
+class EnvironmentAnalyzer final
+    : public Iterator<GraphInterface, Environment, std::hash<uint32_t>> {
+ public:
+  using Base = Iterator<GraphInterface, Environment, std::hash<uint32_t>>;
+
+  using Base::Base;
+
+  void analyze_node(const uint32_t& node, Environment* env) const override {
+    if (node == 0) {
+      env->set(x, Interval::finite(0, 0)).set(y, Interval::finite(0, 0));
+    } else if (node == 2) {
+      env->update(x, [](const Interval& value) {
+        return value + Interval::finite(1, 1);
+      });
+    } else if (node == 4) {
+      env->set(y, Interval::finite(0, 0));
+    }
+  }
+
+  Environment analyze_edge(const size_t&,
+                           const Environment& env) const override {
+    return env;
+  }
+};
+
+} // namespace
+
+TEST(WideningProvenanceTest, widenedVariables) {
+  Graph graph = make_loop();
+  Provenance provenance;
+  EnvironmentAnalyzer<MonotonicFixpointIterator> analyzer(graph);
+  analyzer.set_widening_provenance(&provenance);
+  analyzer.run(Environment::top());
+  EXPECT_EQ(Interval::bounded_below(0), analyzer.get_entry_state_at(1).get(x));
+
+  // The first extrapolation is a join, which loses no precision.
+  ASSERT_EQ(1, provenance.events().size());
+  const auto& event = provenance.events().front();
+  EXPECT_EQ(1, event.head);
+  EXPECT_EQ(1, event.iteration);
+  EXPECT_EQ(Interval::finite(0, 2), event.joined.get(x));
+  EXPECT_EQ(Interval::bounded_below(0), event.extrapolated.get(x));
+  EXPECT_EQ(1, provenance.events_at(1).size());
+  EXPECT_TRUE(provenance.events_at(2).empty());
+
+  EXPECT_THAT(provenance.widened_variables(1), ::testing::ElementsAre(x));
+  EXPECT_THAT(provenance.widening_heads(x), ::testing::ElementsAre(1));
+  EXPECT_TRUE(provenance.widening_heads(y).empty());
+
+  // The provenance is left untouched once the recording is disabled.
+  analyzer.set_widening_provenance(nullptr);
+  analyzer.run(Environment::top());
+  EXPECT_EQ(1, provenance.events().size());
+  provenance.clear();
+  EXPECT_TRUE(provenance.events().empty());
+}
+
+TEST(WideningProvenanceTest, parallelIteration) {
+  //  0 -> 1 -> 2 -> 3 -> 4 -> 5
+  //       ^    ^    |    |
+  //       |    +----+    |
+  //       +--------------+
+  Graph graph;
+  graph.add_edge(0, 1);
+  graph.add_edge(1, 2);
+  graph.add_edge(2, 3);
+  graph.add_edge(3, 2);
+  graph.add_edge(3, 4);
+  graph.add_edge(4, 1);
+  graph.add_edge(4, 5);
+
+  Provenance provenance;
+  EnvironmentAnalyzer<ParallelMonotonicFixpointIterator> analyzer(graph);
+  analyzer.set_widening_provenance(&provenance);
+  analyzer.run(Environment::top());
+  EXPECT_THAT(provenance.widening_heads(x), ::testing::ElementsAre(2));
+  EXPECT_TRUE(provenance.widening_heads(y).empty());
+}
+
+TEST(WideningProvenanceTest, memoryCeiling) {
+  Graph graph = make_loop();
+  MemoryCeiling ceiling(0);
+  Provenance provenance;
+  EnvironmentAnalyzer<MonotonicFixpointIterator> analyzer(graph);
+  analyzer.set_memory_ceiling(&ceiling);
+  analyzer.set_widening_provenance(&provenance);
+  analyzer.run(Environment::top());
+
+  // Falling back to Top loses the precision of all the variables, even
+  // though the extrapolation would have been a join.
+  ASSERT_EQ(1, provenance.events().size());
+  EXPECT_EQ(0, provenance.events().front().iteration);
+  EXPECT_TRUE(provenance.events().front().extrapolated.is_top());
+  EXPECT_THAT(provenance.widening_heads(x), ::testing::ElementsAre(1));
+  EXPECT_THAT(provenance.widening_heads(y), ::testing::ElementsAre(1));
+}
+
+TEST(WideningProvenanceTest, record) {
+  WideningProvenance<uint32_t, Interval> provenance;
+  // A join and a narrowing lose no precision.
+  provenance.record(
+      3, 0, Interval::finite(0, 0), Interval::finite(1, 1),
+      Interval::finite(0, 1));
+  provenance.record(
+      3, 1, Interval::finite(0, 1), Interval::finite(0, 0),
+      Interval::finite(0, 1));
+  provenance.record(
+      3, 2, Interval::finite(0, 1), Interval::finite(0, 2),
+      Interval::bounded_below(0));
+  ASSERT_EQ(1, provenance.events().size());
+
+  std::ostringstream out;
+  out << provenance;
+  EXPECT_EQ("widened at 3 (iteration 2): [0, 2] -> [0, +inf]\n", out.str());
+}
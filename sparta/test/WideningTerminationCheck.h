@@ -0,0 +1,66 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#pragma once
+
+#include <boost/optional.hpp>
+#include <cstddef>
+#include <functional>
+#include <random>
+#include <vector>
+
+#include "AbstractDomain.h"
+
+/*
+ * A randomized check that the widening of an abstract domain enforces
+ * termination, complementing the lattice properties checked by
+ * AbstractDomainPropertyTest.h. A widening that is not sound in that respect
+ * causes the fixpoint iteration to hang on loops, which is hard to diagnose
+ * in a production analysis.
+ *
+ * The widening must guarantee that, for any ascending chain y0 <= y1 <= ...,
+ * the sequence x0 = y0, x{i+1} = xi W y{i+1} is eventually stationary. We
+ * build ascending chains by repeatedly joining values produced by the given
+ * generator, and we report a chain whenever the widened sequence strictly
+ * increases more than `max_height` times.
+ */
+struct WideningTerminationOptions {
+  // Number of random ascending chains to try.
+  size_t num_trials = 100;
+  // Length of each ascending chain.
+  size_t max_steps = 1000;
+  // Maximal number of strict increases of the widened sequence.
+  size_t max_height = 64;
+  unsigned seed = 0;
+};
+
+/*
+ * Returns the widened sequence of the first chain that exceeds the height
+ * bound, or none if the widening stabilized on all the chains that were tried.
+ */
+template <typename Domain>
+boost::optional<std::vector<Domain>> find_infinite_ascending_chain(
+    const std::function<Domain(std::mt19937&)>& generate,
+    const WideningTerminationOptions& options = WideningTerminationOptions()) {
+  std::mt19937 generator(options.seed);
+  for (size_t trial = 0; trial < options.num_trials; ++trial) {
+    Domain y = generate(generator);
+    std::vector<Domain> widened = {y};
+    for (size_t step = 0; step < options.max_steps; ++step) {
+      y.join_with(generate(generator));
+      Domain x = widened.back().widening(y);
+      if (x.equals(widened.back())) {
+        continue;
+      }
+      widened.push_back(std::move(x));
+      if (widened.size() > options.max_height + 1) {
+        return widened;
+      }
+    }
+  }
+  return boost::none;
+}
@@ -0,0 +1,100 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "WideningTerminationCheck.h"
+
+#include <algorithm>
+#include <gtest/gtest.h>
+
+#include "IntervalDomain.h"
+
+using namespace sparta;
+
+namespace {
+
+/*
+ * The natural numbers ordered by magnitude, completed with Top. The widening
+ * is the join, which does not enforce termination.
+ */
+class NaturalDomain final : public AbstractDomain<NaturalDomain> {
+ public:
+  explicit NaturalDomain(unsigned n = 0) : m_value(n) {}
+
+  bool is_bottom() const override { return !m_top && m_value == 0; }
+
+  bool is_top() const override { return m_top; }
+
+  bool leq(const NaturalDomain& other) const override {
+    return other.m_top || (!m_top && m_value <= other.m_value);
+  }
+
+  bool equals(const NaturalDomain& other) const override {
+    return m_top == other.m_top && (m_top || m_value == other.m_value);
+  }
+
+  void set_to_bottom() override {
+    m_top = false;
+    m_value = 0;
+  }
+
+  void set_to_top() override { m_top = true; }
+
+  void join_with(const NaturalDomain& other) override {
+    m_top = m_top || other.m_top;
+    m_value = std::max(m_value, other.m_value);
+  }
+
+  void widen_with(const NaturalDomain& other) override { join_with(other); }
+
+  void meet_with(const NaturalDomain& other) override {
+    if (m_top) {
+      *this = other;
+    } else if (!other.m_top) {
+      m_value = std::min(m_value, other.m_value);
+    }
+  }
+
+  void narrow_with(const NaturalDomain& other) override { meet_with(other); }
+
+  static NaturalDomain bottom() { return NaturalDomain(); }
+
+  static NaturalDomain top() {
+    NaturalDomain result;
+    result.set_to_top();
+    return result;
+  }
+
+ private:
+  bool m_top{false};
+  unsigned m_value;
+};
+
+} // namespace
+
+TEST(WideningTerminationCheckTest, intervals) {
+  using Domain = IntervalDomain<int>;
+  auto chain = find_infinite_ascending_chain<Domain>([](std::mt19937& gen) {
+    std::uniform_int_distribution<int> dist(-1000, 1000);
+    int a = dist(gen), b = dist(gen);
+    return Domain::finite(std::min(a, b), std::max(a, b));
+  });
+  EXPECT_FALSE(chain);
+}
+
+TEST(WideningTerminationCheckTest, nonTerminatingWidening) {
+  unsigned n = 0;
+  WideningTerminationOptions options;
+  options.num_trials = 1;
+  auto chain = find_infinite_ascending_chain<NaturalDomain>(
+      [&n](std::mt19937&) { return NaturalDomain(++n); }, options);
+  ASSERT_TRUE(chain);
+  EXPECT_EQ(options.max_height + 2, chain->size());
+  for (size_t i = 1; i < chain->size(); ++i) {
+    EXPECT_TRUE((*chain)[i - 1].leq((*chain)[i]));
+    EXPECT_FALSE((*chain)[i].leq((*chain)[i - 1]));
+  }
+}
@@ -0,0 +1,130 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+#include "ZoneDomain.h"
+
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+
+#include "AbstractDomainPropertyTest.h"
+
+using namespace sparta;
+
+using Zone = ZoneDomain<std::string>;
+
+INSTANTIATE_TYPED_TEST_CASE_P(ZoneDomain, AbstractDomainPropertyTest, Zone);
+
+template <>
+std::vector<Zone> AbstractDomainPropertyTest<Zone>::non_extremal_values() {
+  Zone z1;
+  z1.add_constraint("x", "y", 1).add_upper_bound("y", 5);
+  Zone z2;
+  z2.assign("x", 0).add_constraint("z", "x", -2);
+  Zone z3;
+  z3.add_lower_bound("y", -3).add_constraint("y", "z", 4);
+  return {z1, z2, z3};
+}
+
+TEST(ZoneDomainTest, closure) {
+  Zone zone;
+  zone.add_constraint("x", "y", 1).add_constraint("y", "z", 2);
+  EXPECT_EQ(3, *zone.get_difference_bound("x", "z"));
+  EXPECT_TRUE(zone.entails("x", "z", 3));
+  EXPECT_FALSE(zone.entails("x", "z", 2));
+  EXPECT_FALSE(zone.get_difference_bound("z", "x"));
+
+  zone.add_upper_bound("z", 10);
+  EXPECT_EQ(13, *zone.get_upper_bound("x"));
+  EXPECT_FALSE(zone.get_lower_bound("x"));
+  zone.add_lower_bound("x", 4);
+  EXPECT_EQ(3, *zone.get_lower_bound("y"));
+  EXPECT_EQ(1, *zone.get_lower_bound("z"));
+
+  // z - x <= -4 contradicts x - z <= 3.
+  Zone inconsistent = zone;
+  inconsistent.add_constraint("z", "x", -4);
+  EXPECT_TRUE(inconsistent.is_bottom());
+  zone.add_constraint("z", "x", -3);
+  EXPECT_FALSE(zone.is_bottom());
+  EXPECT_EQ(-3, *zone.get_difference_bound("z", "x"));
+
+  EXPECT_TRUE(Zone().add_constraint("x", "x", -1).is_bottom());
+  EXPECT_TRUE(Zone().add_constraint("x", "x", 0).is_top());
+}
+
+TEST(ZoneDomainTest, assignment) {
+  Zone zone;
+  zone.assign("i", 0).assign("n", "i", 5);
+  EXPECT_EQ(5, *zone.get_upper_bound("n"));
+  EXPECT_EQ(5, *zone.get_lower_bound("n"));
+  EXPECT_EQ(-5, *zone.get_difference_bound("i", "n"));
+
+  // i := i + 1 preserves the relation with n.
+  zone.assign("i", "i", 1);
+  EXPECT_EQ(-4, *zone.get_difference_bound("i", "n"));
+  EXPECT_EQ(1, *zone.get_upper_bound("i"));
+
+  zone.forget("n");
+  EXPECT_FALSE(zone.get_difference_bound("i", "n"));
+  EXPECT_EQ(1, *zone.get_lower_bound("i"));
+  EXPECT_EQ(std::vector<std::string>{"i"}, zone.variables());
+}
+
+TEST(ZoneDomainTest, latticeOperations) {
+  Zone z1;
+  z1.assign("x", 0).assign("y", "x", 1);
+  Zone z2;
+  z2.assign("x", 5).assign("y", "x", 1).assign("z", 3);
+
+  Zone join = z1.join(z2);
+  EXPECT_EQ(1, *join.get_difference_bound("y", "x"));
+  EXPECT_EQ(-1, *join.get_difference_bound("x", "y"));
+  EXPECT_EQ(0, *join.get_lower_bound("x"));
+  EXPECT_EQ(5, *join.get_upper_bound("x"));
+  EXPECT_FALSE(join.get_upper_bound("z"));
+  EXPECT_TRUE(z1.leq(join));
+  EXPECT_TRUE(z2.leq(join));
+  EXPECT_FALSE(join.leq(z1));
+
+  EXPECT_TRUE(z1.meet(z2).is_bottom());
+  Zone bounded;
+  bounded.add_upper_bound("x", 2).add_upper_bound("z", 7);
+  Zone meet = join.meet(bounded);
+  EXPECT_EQ(3, *meet.get_upper_bound("y"));
+  EXPECT_EQ(7, *meet.get_upper_bound("z"));
+
+  std::ostringstream out;
+  out << z1;
+  EXPECT_EQ("{0 <= x, x <= 0, x - y <= -1, 1 <= y, y <= 1, y - x <= 1}",
+            out.str());
+}
+
+TEST(ZoneDomainTest, wideningAndNarrowing) {
+  // i := 0; while (i < n) { i := i + 1; }
+  Zone entry;
+  entry.assign("i", 0).add_lower_bound("n", 0);
+  Zone head = entry;
+  for (size_t iteration = 0; iteration < 3; ++iteration) {
+    Zone body = head;
+    body.add_constraint("i", "n", -1).assign("i", "i", 1);
+    Zone next = entry.join(body);
+    if (next.leq(head)) {
+      break;
+    }
+    head.widen_with(next);
+  }
+  EXPECT_EQ(0, *head.get_lower_bound("i"));
+  EXPECT_FALSE(head.get_upper_bound("i"));
+  // The relation i <= n survives the widening.
+  EXPECT_EQ(0, *head.get_difference_bound("i", "n"));
+
+  Zone bounded;
+  bounded.add_upper_bound("i", 100);
+  EXPECT_EQ(100, *head.narrowing(bounded).get_upper_bound("i"));
+  EXPECT_EQ(0, *head.narrowing(bounded).get_lower_bound("i"));
+}